@@ -4,6 +4,7 @@ use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use rpg_core::config::RpgConfig;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser)]
 #[command(name = "rpg-encoder", about = "Repository Planning Graph encoder")]
@@ -20,7 +21,9 @@ struct Cli {
 enum Commands {
     /// Build a full RPG from the codebase
     Build {
-        /// Primary language (auto-detected if not specified)
+        /// Languages to index, comma-separated, the first being the primary one
+        /// (auto-detected if not specified). Markdown and HTML are never
+        /// detected; list them to index code fences and inline scripts.
         #[arg(short, long)]
         lang: Option<String>,
 
@@ -68,6 +71,22 @@ enum Commands {
         /// Glob pattern to filter entities by file path (e.g., "src/**/*.rs")
         #[arg(long)]
         file_pattern: Option<String>,
+
+        /// Comma-separated visibility filter (public, protected, internal, private)
+        #[arg(long)]
+        visibility: Option<String>,
+
+        /// Only async functions and methods
+        #[arg(long = "async")]
+        is_async: bool,
+
+        /// Only entities marked deprecated
+        #[arg(long)]
+        deprecated: bool,
+
+        /// Only entities with a decorator/attribute/annotation containing this text
+        #[arg(long)]
+        decorator: Option<String>,
     },
 
     /// Fetch detailed info about a specific entity
@@ -90,6 +109,17 @@ enum Commands {
         depth: usize,
     },
 
+    /// Run a structured graph query, e.g.
+    /// `find controller where area = API and not <-[invokes]- (kind = test)`
+    Query {
+        /// Query text
+        query: String,
+
+        /// Output format: toon, json
+        #[arg(short, long, default_value = "toon")]
+        format: String,
+    },
+
     /// Show RPG statistics
     Info,
 
@@ -100,6 +130,17 @@ enum Commands {
         format: String,
     },
 
+    /// List recorded graph revisions, or show the graph or one entity across commits
+    History {
+        /// Show how an entity's features and hierarchy placement evolved
+        #[arg(long)]
+        entity: Option<String>,
+
+        /// Show the graph as recorded at a commit (SHA, branch, or tag)
+        #[arg(long)]
+        as_of: Option<String>,
+    },
+
     /// Show what would change without updating (dry-run)
     Diff {
         /// Base commit to diff from (defaults to RPG's base_commit)
@@ -122,15 +163,37 @@ enum Commands {
         include_modules: bool,
     },
 
-    /// Validate graph integrity (check for orphans, dangling edges, etc.)
-    Validate,
+    /// Validate graph integrity (dangling edges, dependency symmetry, file index,
+    /// hierarchy membership, indexes and metadata counts)
+    Validate {
+        /// Fix repairable findings and save the graph
+        #[arg(long)]
+        repair: bool,
+    },
 
-    /// Install or uninstall the git pre-commit hook for auto-sync
+    /// Install or uninstall the git pre-commit hook for auto-sync, or the semantic merge driver
     Hook {
-        /// Action: "install" or "uninstall"
+        /// Action: "install", "uninstall", "install-merge-driver", or "uninstall-merge-driver"
         action: String,
     },
 
+    /// Semantic three-way merge of graph files (invoked by git as `%O %A %B`)
+    MergeDriver {
+        /// Common ancestor version (%O)
+        base: PathBuf,
+        /// Our version (%A); overwritten with the merge result
+        ours: PathBuf,
+        /// Their version (%B)
+        theirs: PathBuf,
+        /// Where to write the JSON conflict report (defaults to .rpg/merge_conflicts.json)
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Path of the merged file in the repository (%P); selects shard-level
+        /// merging for files under .rpg/graph/
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
     /// Autonomous LLM-driven semantic lifting (fire-and-forget)
     #[cfg(feature = "lift")]
     Lift {
@@ -171,7 +234,7 @@ fn get_project_root(cli: &Cli) -> Result<PathBuf> {
     }
 }
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
@@ -181,6 +244,9 @@ fn main() -> Result<()> {
 
     let cli = Cli::parse();
     let project_root = get_project_root(&cli)?;
+    for e in rpg_parser::languages::user::install_user_languages(&project_root) {
+        eprintln!("warning: ignoring language definition: {}", e);
+    }
 
     match cli.command {
         Commands::Build {
@@ -197,6 +263,10 @@ fn main() -> Result<()> {
             scope,
             line_range,
             file_pattern,
+            visibility,
+            is_async,
+            deprecated,
+            decorator,
         } => cmd_search(
             &project_root,
             &query,
@@ -204,6 +274,17 @@ fn main() -> Result<()> {
             scope.as_deref(),
             line_range.as_deref(),
             file_pattern.as_deref(),
+            &rpg_nav::search::MetadataFilter {
+                visibility: visibility.map(|v| {
+                    v.split(',')
+                        .filter_map(|s| rpg_core::graph::Visibility::from_name(s.trim()))
+                        .collect()
+                }),
+                is_async: is_async.then_some(true),
+                is_deprecated: deprecated.then_some(true),
+                decorator,
+                ..Default::default()
+            },
         ),
         Commands::Fetch { entity_id } => cmd_fetch(&project_root, &entity_id),
         Commands::Explore {
@@ -211,16 +292,42 @@ fn main() -> Result<()> {
             direction,
             depth,
         } => cmd_explore(&project_root, &entity_id, &direction, depth),
+        Commands::Query { query, format } => cmd_query(&project_root, &query, &format),
         Commands::Info => cmd_info(&project_root),
         Commands::Export { format } => cmd_export(&project_root, &format),
+        Commands::History { entity, as_of } => {
+            cmd_history(&project_root, entity.as_deref(), as_of.as_deref())
+        }
         Commands::Diff { since } => cmd_diff(&project_root, since),
         Commands::ReconstructPlan {
             max_batch_size,
             format,
             include_modules,
         } => cmd_reconstruct_plan(&project_root, max_batch_size, &format, include_modules),
-        Commands::Validate => cmd_validate(&project_root),
+        Commands::Validate { repair } => cmd_validate(&project_root, repair),
         Commands::Hook { action } => cmd_hook(&project_root, &action),
+        Commands::MergeDriver {
+            base,
+            ours,
+            theirs,
+            report,
+            path,
+        } => {
+            let clean = cmd_merge_driver(
+                &project_root,
+                &base,
+                &ours,
+                &theirs,
+                path.as_deref(),
+                report,
+            )?;
+            // A non-zero exit tells git the merge is conflicted
+            return Ok(if clean {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            });
+        }
         #[cfg(feature = "lift")]
         Commands::Lift {
             provider,
@@ -242,7 +349,8 @@ fn main() -> Result<()> {
             eprintln!("MCP server not yet implemented. Use rpg-mcp binary instead.");
             Ok(())
         }
-    }
+    }?;
+    Ok(ExitCode::SUCCESS)
 }
 
 /// Collect source files matching language and glob filters.
@@ -353,7 +461,7 @@ fn cmd_build(
     // Check if RPG already exists
     if rpg_core::storage::rpg_exists(project_root) && !force {
         anyhow::bail!(
-            ".rpg/graph.json already exists. Use --force to rebuild, or `rpg-encoder update` for incremental changes."
+            "RPG graph already exists in .rpg/. Use --force to rebuild, or `rpg-encoder update` for incremental changes."
         );
     }
 
@@ -400,10 +508,14 @@ fn cmd_build(
 
     // Detect languages (multi-language support)
     let languages: Vec<Language> = if let Some(l) = lang {
-        let lang = Language::from_name(&l)
-            .or_else(|| Language::from_extension(&l))
-            .ok_or_else(|| anyhow::anyhow!("unsupported language: {}", l))?;
-        vec![lang]
+        l.split(',')
+            .map(str::trim)
+            .map(|l| {
+                Language::from_name(l)
+                    .or_else(|| Language::from_extension(l))
+                    .ok_or_else(|| anyhow::anyhow!("unsupported language: {}", l))
+            })
+            .collect::<Result<_>>()?
     } else {
         let detected = Language::detect_all(project_root);
         if detected.is_empty() {
@@ -428,7 +540,7 @@ fn cmd_build(
     graph.metadata.languages = languages.iter().map(|l| l.name().to_string()).collect();
 
     // Load TOML paradigm definitions + compile tree-sitter queries
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().map_err(|errs| {
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs(project_root).map_err(|errs| {
         anyhow::anyhow!(
             "paradigm definition errors: {}",
             errs.iter()
@@ -486,7 +598,6 @@ fn cmd_build(
     // Hierarchy node enrichment
     graph.assign_hierarchy_ids();
     graph.aggregate_hierarchy_features();
-    graph.materialize_containment_edges();
 
     // Artifact Grounding
     eprintln!("  Artifact grounding...");
@@ -502,7 +613,11 @@ fn cmd_build(
         Some(&paradigm_ctx),
     );
     rpg_encoder::grounding::ground_hierarchy(&mut graph);
+    rpg_encoder::grounding::collect_external_packages(&mut graph, project_root);
     rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    rpg_encoder::test_links::compute_test_edges(&mut graph);
+    // Resolution replaces the edge list, so containment edges come after it
+    graph.materialize_containment_edges();
 
     // Set git commit if available
     if let Ok(sha) = rpg_encoder::evolution::get_head_sha(project_root) {
@@ -530,6 +645,7 @@ fn cmd_build(
     // Refresh metadata and save
     graph.refresh_metadata();
     rpg_core::storage::save_with_config(project_root, &graph, &config.storage)?;
+    rpg_nav::search_index::SearchIndex::open(project_root, &graph)?;
 
     // Handle gitignore
     let _ = rpg_core::storage::ensure_gitignore(project_root);
@@ -551,7 +667,18 @@ fn cmd_build(
     eprintln!("  Dependency edges: {}", graph.metadata.dependency_edges);
     eprintln!("  Containment edges: {}", graph.metadata.containment_edges);
     eprintln!("  Total edges: {}", graph.metadata.total_edges);
-    eprintln!("  Saved to: .rpg/graph.json");
+    let saved_to = match config.storage.format {
+        rpg_core::config::StorageFormat::Json => rpg_core::storage::rpg_file(project_root),
+        rpg_core::config::StorageFormat::Binary => rpg_core::storage::rpg_binary_file(project_root),
+        rpg_core::config::StorageFormat::Sharded => rpg_core::storage::rpg_shard_dir(project_root),
+    };
+    eprintln!(
+        "  Saved to: {}",
+        saved_to
+            .strip_prefix(project_root)
+            .unwrap_or(&saved_to)
+            .display()
+    );
 
     if let Some(stats) = merge_stats {
         eprintln!("\n  Auto-preserved from previous graph:");
@@ -592,7 +719,7 @@ fn cmd_update(project_root: &Path, since: Option<String>) -> Result<()> {
                 .into_iter()
                 .collect()
         };
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().map_err(|errs| {
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs(project_root).map_err(|errs| {
         anyhow::anyhow!(
             "paradigm definition errors: {}",
             errs.iter()
@@ -620,6 +747,7 @@ fn cmd_update(project_root: &Path, since: Option<String>) -> Result<()> {
     )?;
 
     rpg_core::storage::save_with_config(project_root, &graph, &config.storage)?;
+    rpg_nav::search_index::SearchIndex::open(project_root, &graph)?;
 
     if summary.entities_added == 0
         && summary.entities_modified == 0
@@ -633,6 +761,12 @@ fn cmd_update(project_root: &Path, since: Option<String>) -> Result<()> {
         eprintln!("  Entities removed: {}", summary.entities_removed);
         eprintln!("  Edges added: {}", summary.edges_added);
         eprintln!("  Edges removed: {}", summary.edges_removed);
+        let feature_changes = summary.delta.feature_changes().len();
+        let hierarchy_moves = summary.delta.hierarchy_moves().len();
+        if feature_changes > 0 || hierarchy_moves > 0 {
+            eprintln!("  Feature changes: {}", feature_changes);
+            eprintln!("  Hierarchy moves: {}", hierarchy_moves);
+        }
     }
 
     Ok(())
@@ -645,6 +779,7 @@ fn cmd_search(
     scope: Option<&str>,
     line_range: Option<&str>,
     file_pattern: Option<&str>,
+    metadata_filter: &rpg_nav::search::MetadataFilter,
 ) -> Result<()> {
     let graph = rpg_core::storage::load(project_root)?;
     let config = RpgConfig::load(project_root)?;
@@ -668,20 +803,29 @@ fn cmd_search(
         }
     });
 
-    let results = rpg_nav::search::search_with_params(
-        &graph,
-        &rpg_nav::search::SearchParams {
-            query,
-            mode: search_mode,
-            scope,
-            limit,
-            line_nums,
-            file_pattern,
-            entity_type_filter: None,
-            embedding_scores: None,
-            diff_context: None,
-        },
-    );
+    let index = match rpg_nav::search_index::SearchIndex::open(project_root, &graph) {
+        Ok((index, _)) => Some(index),
+        Err(e) => {
+            eprintln!("warning: search index unavailable, scanning graph: {e}");
+            None
+        }
+    };
+
+    let params = rpg_nav::search::SearchParams {
+        query,
+        mode: search_mode,
+        scope,
+        limit,
+        line_nums,
+        file_pattern,
+        entity_type_filter: None,
+        metadata_filter: Some(metadata_filter),
+        embedding_scores: None,
+        diff_context: None,
+        index: index.as_ref(),
+    };
+    let results = rpg_nav::search::search_with_params(&graph, &params);
+    let results = rpg_nav::search::with_external_packages(&graph, &params, results);
 
     if results.is_empty() {
         eprintln!("No results found for: {}", query);
@@ -719,6 +863,9 @@ fn cmd_fetch(project_root: &Path, entity_id: &str) -> Result<()> {
                 result.entity.line_start,
                 result.entity.line_end
             );
+            if let Some(cell) = result.entity.metadata.cell {
+                println!("Cell: {} (line {})", cell.index, cell.line);
+            }
             println!("Hierarchy: {}", result.entity.hierarchy_path);
 
             if !result.entity.semantic_features.is_empty() {
@@ -795,17 +942,28 @@ fn cmd_explore(project_root: &Path, entity_id: &str, direction: &str, depth: usi
 const PRECOMMIT_HOOK: &str = r#"#!/bin/sh
 # RPG-Encoder: auto-update semantic graph before commit
 # Installed by: rpg-encoder hook install
-if [ -f ".rpg/graph.json" ]; then
+if [ -f ".rpg/graph.json" ] || [ -f ".rpg/graph.rpgb" ] || [ -d ".rpg/graph" ]; then
     if command -v rpg-encoder >/dev/null 2>&1; then
         rpg-encoder update 2>&1 | while IFS= read -r line; do echo "  [rpg] $line"; done
-        git add .rpg/graph.json 2>/dev/null
     elif command -v npx >/dev/null 2>&1; then
         npx -y rpg-encoder update 2>&1 | while IFS= read -r line; do echo "  [rpg] $line"; done
-        git add .rpg/graph.json 2>/dev/null
     fi
+    for f in .rpg/graph.json .rpg/graph.rpgb; do
+        if [ -f "$f" ]; then git add "$f" 2>/dev/null; fi
+    done
+    if [ -d ".rpg/graph" ]; then git add -A .rpg/graph 2>/dev/null; fi
 fi
 "#;
 
+const MERGE_DRIVER_NAME: &str = "RPG-Encoder semantic graph merge";
+const MERGE_DRIVER_COMMAND: &str = "rpg-encoder merge-driver %O %A %B --path %P";
+const MERGE_ATTRIBUTES: &str =
+    "# RPG-Encoder: semantic merge for the graph (rpg-encoder hook install-merge-driver)
+.rpg/graph.json merge=rpg
+.rpg/graph.rpgb merge=rpg
+.rpg/graph/** merge=rpg
+";
+
 fn cmd_hook(project_root: &Path, action: &str) -> Result<()> {
     let git_dir = project_root.join(".git");
     if !git_dir.exists() {
@@ -815,6 +973,44 @@ fn cmd_hook(project_root: &Path, action: &str) -> Result<()> {
     let hook_path = hooks_dir.join("pre-commit");
 
     match action {
+        "install-merge-driver" => {
+            let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
+            let mut config = repo.config().context("failed to open git config")?;
+            config.set_str("merge.rpg.name", MERGE_DRIVER_NAME)?;
+            config.set_str("merge.rpg.driver", MERGE_DRIVER_COMMAND)?;
+
+            let attributes_path = project_root.join(".gitattributes");
+            let existing = std::fs::read_to_string(&attributes_path).unwrap_or_default();
+            if !existing.contains(MERGE_ATTRIBUTES) {
+                let mut content = existing;
+                if !content.is_empty() && !content.ends_with('\n') {
+                    content.push('\n');
+                }
+                content.push_str(MERGE_ATTRIBUTES);
+                std::fs::write(&attributes_path, content)?;
+            }
+            eprintln!("Merge driver installed (git config merge.rpg.*, .gitattributes).");
+            eprintln!(
+                "Commit .gitattributes; each clone runs this command once to register the driver."
+            );
+        }
+        "uninstall-merge-driver" => {
+            let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
+            let mut config = repo.config().context("failed to open git config")?;
+            let _ = config.remove("merge.rpg.name");
+            let _ = config.remove("merge.rpg.driver");
+
+            let attributes_path = project_root.join(".gitattributes");
+            if let Ok(content) = std::fs::read_to_string(&attributes_path) {
+                let cleaned = content.replace(MERGE_ATTRIBUTES, "");
+                if cleaned.trim().is_empty() {
+                    std::fs::remove_file(&attributes_path)?;
+                } else {
+                    std::fs::write(&attributes_path, cleaned)?;
+                }
+            }
+            eprintln!("Merge driver uninstalled.");
+        }
         "install" => {
             std::fs::create_dir_all(&hooks_dir)?;
             if hook_path.exists() {
@@ -860,8 +1056,122 @@ fn cmd_hook(project_root: &Path, action: &str) -> Result<()> {
             }
             eprintln!("Pre-commit hook uninstalled.");
         }
-        _ => anyhow::bail!("Unknown action: {}. Use 'install' or 'uninstall'.", action),
+        _ => anyhow::bail!(
+            "Unknown action: {}. Use 'install', 'uninstall', 'install-merge-driver', or 'uninstall-merge-driver'.",
+            action
+        ),
+    }
+    Ok(())
+}
+
+/// Returns whether the merge was clean.
+fn cmd_merge_driver(
+    project_root: &Path,
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+    path: Option<&Path>,
+    report_path: Option<PathBuf>,
+) -> Result<bool> {
+    let shard_dir = Path::new(".rpg").join("graph");
+    let report = match path.and_then(|p| p.strip_prefix(&shard_dir).ok()) {
+        Some(rel) => merge_shard_files(rel, base, ours, theirs)?,
+        None => merge_graph_files(base, ours, theirs)?,
+    };
+
+    eprintln!("[rpg] Semantic graph merge:");
+    eprintln!("  Entities added: {}", report.entities_added);
+    eprintln!("  Entities removed: {}", report.entities_removed);
+    eprintln!("  Entities merged: {}", report.entities_merged);
+    eprintln!(
+        "  Features taken from theirs: {}",
+        report.features_from_theirs
+    );
+    eprintln!("  Edges added: {}", report.edges_added);
+    eprintln!("  Edges removed: {}", report.edges_removed);
+
+    if report.is_clean() {
+        return Ok(true);
+    }
+
+    let report_path = report_path
+        .unwrap_or_else(|| rpg_core::storage::rpg_dir(project_root).join("merge_conflicts.json"));
+    if let Some(parent) = report_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_path, serde_json::to_string_pretty(&report)?)?;
+
+    eprintln!("  Conflicts: {}", report.conflicts.len());
+    for conflict in &report.conflicts {
+        eprintln!(
+            "    {} ({:?}, kept {:?})",
+            conflict.entity_id, conflict.kind, conflict.kept
+        );
+    }
+    eprintln!("  Report written to {}", report_path.display());
+    eprintln!("  The merged graph keeps one side per conflict; re-lift those entities and commit.");
+    Ok(false)
+}
+
+/// Merge whole graph files (`graph.json` or `graph.rpgb`), writing the result to `ours`.
+fn merge_graph_files(
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+) -> Result<rpg_encoder::merge::MergeReport> {
+    let (ours_graph, ours_storage) = rpg_core::storage::read_graph_file(ours)
+        .with_context(|| format!("failed to load our graph {}", ours.display()))?;
+    let (theirs_graph, _) = rpg_core::storage::read_graph_file(theirs)
+        .with_context(|| format!("failed to load their graph {}", theirs.display()))?;
+    // Git passes an empty ancestor when both branches added the graph independently
+    let base_graph = if std::fs::metadata(base).is_ok_and(|m| m.len() > 0) {
+        rpg_core::storage::read_graph_file(base)
+            .with_context(|| format!("failed to load base graph {}", base.display()))?
+            .0
+    } else {
+        rpg_core::graph::RPGraph::new(&ours_graph.metadata.language)
+    };
+
+    let (merged, report) =
+        rpg_encoder::merge::merge_graphs(&base_graph, &ours_graph, &theirs_graph);
+    rpg_core::storage::write_graph_file(ours, &merged, &ours_storage)?;
+    Ok(report)
+}
+
+/// Merge one file of the sharded layout (`rel` is relative to `.rpg/graph/`),
+/// writing the result to `ours`.
+fn merge_shard_files(
+    rel: &Path,
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+) -> Result<rpg_encoder::merge::MergeReport> {
+    let read = |side: &str, path: &Path| {
+        rpg_core::sharded::read_shard(rel, path)
+            .with_context(|| format!("failed to load {} shard {}", side, path.display()))
+    };
+    let (base_graph, ours_graph, theirs_graph) = (
+        read("base", base)?,
+        read("our", ours)?,
+        read("their", theirs)?,
+    );
+
+    let (merged, report) =
+        rpg_encoder::merge::merge_shards(&base_graph, &ours_graph, &theirs_graph);
+    rpg_core::sharded::write_shard(rel, ours, &merged)?;
+    Ok(report)
+}
+
+fn cmd_query(project_root: &Path, query: &str, format: &str) -> Result<()> {
+    let graph = rpg_core::storage::load(project_root)?;
+    let result = rpg_nav::query::run(&graph, query).map_err(|e| anyhow::anyhow!(e))?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&result)?),
+        "toon" => println!("{}", rpg_nav::toon::format_query_result(&result)),
+        other => anyhow::bail!("Unknown format: {}. Use 'toon' or 'json'.", other),
     }
+
     Ok(())
 }
 
@@ -884,6 +1194,94 @@ fn cmd_export(project_root: &Path, format: &str) -> Result<()> {
     Ok(())
 }
 
+fn short_sha(sha: &str) -> &str {
+    &sha[..8.min(sha.len())]
+}
+
+fn cmd_history(project_root: &Path, entity: Option<&str>, as_of: Option<&str>) -> Result<()> {
+    use rpg_core::history;
+
+    if let Some(rev) = as_of {
+        let Some((revision, graph)) = resolve_history_revision(project_root, rev)? else {
+            anyhow::bail!(
+                "No recorded revision at or before '{}'. Run `rpg-encoder history` to list them.",
+                rev
+            );
+        };
+        eprintln!(
+            "Graph as recorded at {} ({}):\n",
+            short_sha(&revision.commit),
+            revision.recorded_at.format("%Y-%m-%d %H:%M")
+        );
+        print_graph_summary(&graph);
+        return Ok(());
+    }
+
+    if let Some(entity_id) = entity {
+        let timeline = history::entity_timeline(project_root, entity_id)?;
+        if timeline.iter().all(|s| s.entity.is_none()) {
+            anyhow::bail!("Entity '{}' not found in any recorded revision", entity_id);
+        }
+        for snapshot in &timeline {
+            let date = snapshot.recorded_at.format("%Y-%m-%d");
+            let Some(e) = &snapshot.entity else {
+                println!("{}  {}  (absent)", short_sha(&snapshot.commit), date);
+                continue;
+            };
+            let path = if e.hierarchy_path.is_empty() {
+                "(unplaced)"
+            } else {
+                &e.hierarchy_path
+            };
+            println!("{}  {}  {}", short_sha(&snapshot.commit), date, path);
+            if !e.semantic_features.is_empty() {
+                println!("    {}", e.semantic_features.join(", "));
+            }
+        }
+        return Ok(());
+    }
+
+    let revisions = history::revisions(project_root)?;
+    if revisions.is_empty() {
+        eprintln!("No revisions recorded yet. History is recorded on build/update in a git repo.");
+        return Ok(());
+    }
+    for revision in revisions.iter().rev() {
+        println!(
+            "{}  {}  {} entities, {} edges",
+            short_sha(&revision.commit),
+            revision.recorded_at.format("%Y-%m-%d %H:%M"),
+            revision.entities,
+            revision.edges
+        );
+    }
+    Ok(())
+}
+
+/// Find the newest recorded revision at or before `rev`. When `rev` resolves in the
+/// git repo, a commit that was never recorded maps to its newest recorded ancestor;
+/// otherwise `rev` is matched as a recorded SHA prefix.
+fn resolve_history_revision(
+    project_root: &Path,
+    rev: &str,
+) -> Result<Option<(rpg_core::history::Revision, rpg_core::graph::RPGraph)>> {
+    use rpg_core::history;
+
+    let resolved = git2::Repository::open(project_root).ok().and_then(|repo| {
+        let target = repo.revparse_single(rev).ok()?.peel_to_commit().ok()?.id();
+        Some((repo, target))
+    });
+    let Some((repo, target)) = resolved else {
+        return history::as_of_where(project_root, |r| r.commit.starts_with(rev));
+    };
+
+    history::as_of_where(project_root, |revision| {
+        git2::Oid::from_str(&revision.commit).is_ok_and(|oid| {
+            oid == target || repo.graph_descendant_of(target, oid).unwrap_or(false)
+        })
+    })
+}
+
 fn cmd_diff(project_root: &Path, since: Option<String>) -> Result<()> {
     use rpg_encoder::evolution::FileChange;
 
@@ -1005,59 +1403,41 @@ fn cmd_reconstruct_plan(
     Ok(())
 }
 
-fn cmd_validate(project_root: &Path) -> Result<()> {
+fn cmd_validate(project_root: &Path, repair: bool) -> Result<()> {
+    use rpg_core::validate::{self, Severity};
+
     if !rpg_core::storage::rpg_exists(project_root) {
         anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
     }
 
-    let graph = rpg_core::storage::load(project_root)?;
-    let mut issues = 0;
-
-    // 1. Dangling edge targets (edge references entity ID not in entities or hierarchy)
-    for edge in &graph.edges {
-        let source_exists = graph.entities.contains_key(&edge.source)
-            || graph.find_hierarchy_node_by_id(&edge.source).is_some();
-        let target_exists = graph.entities.contains_key(&edge.target)
-            || graph.find_hierarchy_node_by_id(&edge.target).is_some();
-
-        if !source_exists {
-            println!("WARN: dangling edge source: {}", edge.source);
-            issues += 1;
-        }
-        if !target_exists {
-            println!("WARN: dangling edge target: {}", edge.target);
-            issues += 1;
-        }
-    }
-
-    // 2. Orphan entity references in hierarchy
-    for (area_name, node) in &graph.hierarchy {
-        check_hierarchy_orphans(node, area_name, &graph, &mut issues);
-    }
-
-    // 3. Entity IDs not matching file:name format
-    for (id, entity) in &graph.entities {
-        if entity.kind != rpg_core::graph::EntityKind::Module && !id.contains(':') {
-            println!("WARN: entity ID missing file:name format: {}", id);
-            issues += 1;
-        }
-    }
-
-    // 4. File index consistency
-    for (file, ids) in &graph.file_index {
-        for id in ids {
-            if !graph.entities.contains_key(id) {
-                println!(
-                    "WARN: file_index references missing entity: {} in {}",
-                    id,
-                    file.display()
-                );
-                issues += 1;
+    let mut graph = rpg_core::storage::load(project_root)?;
+    let findings = if repair {
+        let report = validate::repair(&mut graph);
+        if !report.fixed.is_empty() {
+            let config = RpgConfig::load(project_root)?;
+            rpg_core::storage::save_with_config(project_root, &graph, &config.storage)?;
+            for finding in &report.fixed {
+                println!("FIXED: {}", finding);
             }
+            eprintln!(
+                "Repaired {} issue(s) and saved the graph.",
+                report.fixed.len()
+            );
         }
+        report.remaining
+    } else {
+        validate::validate(&graph).findings
+    };
+
+    for finding in &findings {
+        let label = match finding.severity() {
+            Severity::Error => "ERROR",
+            Severity::Warning => "WARN",
+        };
+        println!("{}: {}", label, finding);
     }
 
-    if issues == 0 {
+    if findings.is_empty() {
         eprintln!("Graph is valid. No integrity issues found.");
         eprintln!(
             "  {} entities, {} edges, {} files",
@@ -1066,31 +1446,17 @@ fn cmd_validate(project_root: &Path) -> Result<()> {
             graph.file_index.len()
         );
     } else {
-        eprintln!("\nFound {} integrity issue(s).", issues);
-    }
-
-    Ok(())
-}
-
-fn check_hierarchy_orphans(
-    node: &rpg_core::graph::HierarchyNode,
-    path: &str,
-    graph: &rpg_core::graph::RPGraph,
-    issues: &mut usize,
-) {
-    for entity_id in &node.entities {
-        if !graph.entities.contains_key(entity_id) {
-            println!(
-                "WARN: hierarchy node '{}' references missing entity: {}",
-                path, entity_id
+        let repairable = findings.iter().filter(|f| f.is_repairable()).count();
+        eprintln!("\nFound {} integrity issue(s).", findings.len());
+        if !repair && repairable > 0 {
+            eprintln!(
+                "  {} can be fixed with `rpg-encoder validate --repair`.",
+                repairable
             );
-            *issues += 1;
         }
     }
-    for (child_name, child) in &node.children {
-        let child_path = format!("{}/{}", path, child_name);
-        check_hierarchy_orphans(child, &child_path, graph, issues);
-    }
+
+    Ok(())
 }
 
 #[cfg(feature = "lift")]
@@ -1197,7 +1563,19 @@ fn cmd_info(project_root: &Path) -> Result<()> {
     }
 
     let graph = rpg_core::storage::load(project_root)?;
+    print_graph_summary(&graph);
+
+    let user_defs = rpg_parser::user_defs::UserDefsReport::collect(project_root);
+    if !user_defs.is_empty() {
+        println!();
+        println!("Custom definitions:");
+        print!("{}", user_defs);
+    }
+
+    Ok(())
+}
 
+fn print_graph_summary(graph: &rpg_core::graph::RPGraph) {
     println!("RPG v{}", graph.version);
     println!("Language: {}", graph.metadata.language);
     println!("Created: {}", graph.created_at);
@@ -1208,6 +1586,9 @@ fn cmd_info(project_root: &Path) -> Result<()> {
     println!();
     println!("Entities: {}", graph.metadata.total_entities);
     println!("Files: {}", graph.metadata.total_files);
+    if !graph.externals.is_empty() {
+        println!("External packages: {}", graph.externals.len());
+    }
     let (lifted, total) = graph.lifting_coverage();
     println!("Lifted: {}/{}", lifted, total);
     println!(
@@ -1235,6 +1616,4 @@ fn cmd_info(project_root: &Path) -> Result<()> {
             }
         }
     }
-
-    Ok(())
 }
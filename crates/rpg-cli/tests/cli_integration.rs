@@ -18,6 +18,8 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         hierarchy_path: "Core/test".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -69,6 +71,35 @@ fn test_search_on_saved_graph() {
     assert_eq!(results[0].entity_name, "main");
 }
 
+#[test]
+fn test_query_on_saved_graph() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity("main.rs:main", "main", "main.rs"));
+    graph.insert_entity(make_entity("lib.rs:run", "run", "lib.rs"));
+    graph.edges.push(DependencyEdge {
+        source: "main.rs:main".to_string(),
+        target: "lib.rs:run".to_string(),
+        kind: EdgeKind::Invokes,
+        confidence: None,
+    });
+    graph.refresh_metadata();
+    rpg_core::storage::save(tmpdir.path(), &graph).unwrap();
+
+    let loaded = rpg_core::storage::load(tmpdir.path()).unwrap();
+    let result = rpg_nav::query::run(
+        &loaded,
+        "find function where area = core and -[invokes]-> ()",
+    )
+    .unwrap();
+    assert_eq!(result.total, 1);
+    assert_eq!(result.matches[0].entity_id, "main.rs:main");
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["matches"][0]["name"], "main");
+    assert!(rpg_nav::query::run(&loaded, "find nothing").is_err());
+}
+
 #[test]
 fn test_info_displays_metadata() {
     let mut graph = RPGraph::new("rust");
@@ -0,0 +1,130 @@
+//! End-to-end test of the merge driver: install it, then let git merge two
+//! branches' sharded graphs through it.
+
+use rpg_core::config::{StorageConfig, StorageFormat};
+use rpg_core::graph::*;
+use rpg_core::storage;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn make_entity(id: &str, name: &str, file: &str) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        hierarchy_path: "Core/auth".to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
+    }
+}
+
+fn sharded() -> StorageConfig {
+    StorageConfig {
+        format: StorageFormat::Sharded,
+        ..Default::default()
+    }
+}
+
+/// Run git in `root`, with the built `rpg-encoder` first on PATH so git can
+/// invoke the merge driver.
+fn git(root: &Path, args: &[&str]) -> std::process::Output {
+    let bin_dir = Path::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let path = std::env::join_paths(std::iter::once(bin_dir).chain(std::env::split_paths(
+        &std::env::var_os("PATH").unwrap_or_default(),
+    )))
+    .unwrap();
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .env("PATH", path)
+        .env("GIT_AUTHOR_NAME", "test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+/// Save `graph` sharded at `commit` and commit everything.
+fn commit_graph(root: &Path, graph: &mut RPGraph, commit: &str) {
+    graph.base_commit = Some(commit.to_string());
+    graph.refresh_metadata();
+    storage::save_with_config(root, graph, &sharded()).unwrap();
+    git(root, &["add", "-A"]);
+    git(root, &["commit", "-q", "-m", commit]);
+}
+
+fn lift(graph: &mut RPGraph, id: &str, feature: &str) {
+    let entity = graph.entities.get_mut(id).unwrap();
+    entity.semantic_features = vec![feature.to_string()];
+    entity.feature_source = Some("llm".to_string());
+}
+
+#[test]
+fn test_merge_driver_merges_sharded_graphs() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    git(root, &["init", "-q", "-b", "main"]);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .args(["--project", root.to_str().unwrap()])
+        .args(["hook", "install-merge-driver"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let attributes = std::fs::read_to_string(root.join(".gitattributes")).unwrap();
+    assert!(attributes.contains(".rpg/graph/** merge=rpg"));
+
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity("src/auth.rs:login", "login", "src/auth.rs"));
+    graph.insert_entity(make_entity("src/auth.rs:logout", "logout", "src/auth.rs"));
+    commit_graph(root, &mut graph, "base");
+
+    // Both branches lift a different entity of the same shard and move base_commit
+    git(root, &["checkout", "-q", "-b", "feature"]);
+    let mut theirs = graph.clone();
+    lift(&mut theirs, "src/auth.rs:logout", "end user session");
+    commit_graph(root, &mut theirs, "theirs");
+
+    git(root, &["checkout", "-q", "main"]);
+    let mut ours = graph.clone();
+    lift(&mut ours, "src/auth.rs:login", "authenticate user");
+    commit_graph(root, &mut ours, "ours");
+
+    let merge = git(root, &["merge", "--no-edit", "feature"]);
+    let log = String::from_utf8_lossy(&merge.stderr);
+    assert!(
+        log.contains("Semantic graph merge"),
+        "driver did not run: {log}"
+    );
+
+    let merged = storage::load(root).unwrap();
+    assert_eq!(
+        merged.entities["src/auth.rs:login"].semantic_features,
+        ["authenticate user"]
+    );
+    assert_eq!(
+        merged.entities["src/auth.rs:logout"].semantic_features,
+        ["end user session"]
+    );
+    assert_eq!(merged.base_commit.as_deref(), Some("ours"));
+    assert_eq!(merged.metadata.lifted_entities, 2);
+}
@@ -17,6 +17,8 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>) -> Entity
         hierarchy_path: format!("Area/category/{}", name),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -39,6 +41,7 @@ fn build_graph(entity_count: usize) -> RPGraph {
             source: entity_ids[i].clone(),
             target: entity_ids[i + 1].clone(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
     }
 
@@ -71,6 +74,27 @@ fn bench_serialization_500(c: &mut Criterion) {
     });
 }
 
+fn bench_binary_deserialization_500(c: &mut Criterion) {
+    let graph = build_graph(500);
+    let bytes = rpg_core::binary::encode(&graph, false).unwrap();
+
+    c.bench_function("deserialize_binary_500_entities", |b| {
+        b.iter(|| rpg_core::binary::decode(black_box(&bytes)).unwrap())
+    });
+}
+
+fn bench_binary_entity_lookup(c: &mut Criterion) {
+    let graph = build_graph(500);
+    let middle_id = graph.entities.keys().nth(250).unwrap().clone();
+    let lazy =
+        rpg_core::binary::BinaryGraph::from_bytes(rpg_core::binary::encode(&graph, false).unwrap())
+            .unwrap();
+
+    c.bench_function("binary_lazy_entity_lookup_500", |b| {
+        b.iter(|| lazy.get_entity(black_box(&middle_id)).unwrap())
+    });
+}
+
 fn bench_edge_lookup(c: &mut Criterion) {
     let graph = build_graph(500);
     let entity_ids: Vec<&String> = graph.entities.keys().collect();
@@ -107,6 +131,8 @@ criterion_group!(
     bench_serialization_100,
     bench_deserialization_100,
     bench_serialization_500,
+    bench_binary_deserialization_500,
+    bench_binary_entity_lookup,
     bench_edge_lookup,
     bench_rebuild_edge_index,
     bench_rebuild_hierarchy_index,
@@ -0,0 +1,443 @@
+//! Compact binary graph format (`.rpg/graph.rpgb`), readable through a memory map.
+//!
+//! Layout (integers are little-endian):
+//!
+//! ```text
+//! magic "RPGB" | format version u16 | reserved u16 | section count u32
+//! section table: [kind u32 | flags u32 | offset u64 | length u64] × count
+//! section payloads: MessagePack, each optionally zstd-compressed
+//! ```
+//!
+//! Entities are encoded as individual records behind a sorted directory, so
+//! [`BinaryGraph`] can read the header or a single entity without decoding the
+//! rest of the file. The `edge_index` and `hierarchy_node_index` performance
+//! indexes are persisted as well, so a full load skips rebuilding them.
+//!
+//! Payloads use MessagePack with named fields (not positional arrays) because
+//! the graph types rely on `skip_serializing_if` and `serde(default)` for
+//! backward compatibility, which positional encodings cannot represent.
+
+use crate::graph::{DependencyEdge, Entity, GraphHeader, HierarchyNode, RPGraph};
+use crate::schema;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Magic bytes at the start of every binary graph file.
+pub const BINARY_MAGIC: [u8; 4] = *b"RPGB";
+
+/// Current binary container version. Bump when the section layout changes.
+/// The graph schema version is stored separately in the header section.
+const FORMAT_VERSION: u16 = 1;
+
+const PREAMBLE_LEN: usize = 12;
+const SECTION_ENTRY_LEN: usize = 24;
+
+/// Section payload is zstd-compressed.
+const FLAG_ZSTD: u32 = 1;
+
+/// Section kinds in the binary container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+enum SectionKind {
+    Header = 1,
+    EntityDirectory = 2,
+    Entities = 3,
+    Edges = 4,
+    Hierarchy = 5,
+    FileIndex = 6,
+    EdgeIndex = 7,
+    HierarchyIndex = 8,
+    /// Optional: absent in graphs without external packages.
+    Externals = 9,
+}
+
+/// Directory entry locating one entity record inside the entities section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EntityRecord {
+    id: String,
+    offset: u64,
+    len: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Section {
+    kind: u32,
+    flags: u32,
+    offset: usize,
+    len: usize,
+}
+
+/// Whether the given bytes start with the binary graph magic.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.len() >= BINARY_MAGIC.len() && bytes[..BINARY_MAGIC.len()] == BINARY_MAGIC
+}
+
+/// Whether a binary graph's sections are zstd-compressed.
+pub fn is_compressed(bytes: &[u8]) -> bool {
+    if !is_binary(bytes) || bytes.len() < PREAMBLE_LEN {
+        return false;
+    }
+    let count = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize;
+    (0..count).any(|i| {
+        let at = PREAMBLE_LEN + i * SECTION_ENTRY_LEN + 4;
+        bytes
+            .get(at..at + 4)
+            .is_some_and(|f| u32::from_le_bytes([f[0], f[1], f[2], f[3]]) & FLAG_ZSTD != 0)
+    })
+}
+
+fn encode_value<T: Serialize + ?Sized>(value: &T, what: &str) -> Result<Vec<u8>> {
+    rmp_serde::to_vec_named(value).with_context(|| format!("failed to encode {}", what))
+}
+
+fn decode_value<T: for<'de> Deserialize<'de>>(bytes: &[u8], what: &str) -> Result<T> {
+    rmp_serde::from_slice(bytes).with_context(|| format!("failed to decode {}", what))
+}
+
+/// Encode a graph into the binary container.
+///
+/// With `compress`, each section is zstd-compressed individually, which keeps
+/// the header cheap to read while shrinking the large sections.
+pub fn encode(graph: &RPGraph, compress: bool) -> Result<Vec<u8>> {
+    let header = graph.header();
+
+    let mut entity_blob = Vec::new();
+    let mut directory = Vec::with_capacity(graph.entities.len());
+    for (id, entity) in &graph.entities {
+        let record = encode_value(entity, "entity")?;
+        directory.push(EntityRecord {
+            id: id.clone(),
+            offset: entity_blob.len() as u64,
+            len: record.len() as u64,
+        });
+        entity_blob.extend_from_slice(&record);
+    }
+
+    // Sorted for deterministic output, matching `schema::to_json`
+    let mut edges = graph.edges.clone();
+    edges.sort();
+
+    // Persisted indexes must match the sorted edge order written above
+    let edge_index = build_edge_index(&edges);
+    let mut hierarchy = RPGraph::new(&graph.metadata.language);
+    hierarchy.hierarchy = graph.hierarchy.clone();
+    hierarchy.rebuild_hierarchy_index();
+
+    let mut sections: Vec<(SectionKind, Vec<u8>)> = vec![
+        (SectionKind::Header, encode_value(&header, "graph header")?),
+        (
+            SectionKind::EntityDirectory,
+            encode_value(&directory, "entity directory")?,
+        ),
+        (SectionKind::Entities, entity_blob),
+        (SectionKind::Edges, encode_value(&edges, "edges")?),
+        (
+            SectionKind::Hierarchy,
+            encode_value(&graph.hierarchy, "hierarchy")?,
+        ),
+        (
+            SectionKind::FileIndex,
+            encode_value(&graph.file_index, "file index")?,
+        ),
+        (
+            SectionKind::EdgeIndex,
+            encode_value(&edge_index, "edge index")?,
+        ),
+        (
+            SectionKind::HierarchyIndex,
+            encode_value(&hierarchy.hierarchy_node_index, "hierarchy index")?,
+        ),
+    ];
+    if !graph.externals.is_empty() {
+        sections.push((
+            SectionKind::Externals,
+            encode_value(&graph.externals, "externals")?,
+        ));
+    }
+
+    let mut payloads = Vec::with_capacity(sections.len());
+    for (kind, bytes) in sections {
+        if compress {
+            let compressed = zstd::encode_all(bytes.as_slice(), 3)
+                .with_context(|| format!("failed to compress {:?} section", kind))?;
+            payloads.push((kind, FLAG_ZSTD, compressed));
+        } else {
+            payloads.push((kind, 0, bytes));
+        }
+    }
+
+    let table_len = payloads.len() * SECTION_ENTRY_LEN;
+    let mut out = Vec::with_capacity(
+        PREAMBLE_LEN + table_len + payloads.iter().map(|(_, _, b)| b.len()).sum::<usize>(),
+    );
+    out.extend_from_slice(&BINARY_MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&(payloads.len() as u32).to_le_bytes());
+
+    let mut offset = (PREAMBLE_LEN + table_len) as u64;
+    for (kind, flags, bytes) in &payloads {
+        out.extend_from_slice(&(*kind as u32).to_le_bytes());
+        out.extend_from_slice(&flags.to_le_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+        out.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        offset += bytes.len() as u64;
+    }
+    for (_, _, bytes) in payloads {
+        out.extend_from_slice(&bytes);
+    }
+
+    Ok(out)
+}
+
+/// Decode a complete graph from binary container bytes.
+pub fn decode(bytes: &[u8]) -> Result<RPGraph> {
+    BinaryGraph::from_bytes(bytes.to_vec())?.load_graph()
+}
+
+fn build_edge_index(edges: &[DependencyEdge]) -> HashMap<String, Vec<usize>> {
+    let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, edge) in edges.iter().enumerate() {
+        index.entry(edge.source.clone()).or_default().push(i);
+        index.entry(edge.target.clone()).or_default().push(i);
+    }
+    index
+}
+
+enum Backing {
+    Mapped(memmap2::Mmap),
+    Owned(Vec<u8>),
+}
+
+impl std::ops::Deref for Backing {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(map) => map,
+            Self::Owned(bytes) => bytes,
+        }
+    }
+}
+
+/// A lazily decoded binary graph file.
+///
+/// Opening only parses the section table; sections are decoded on demand.
+/// Use [`BinaryGraph::header`] for cheap metadata access,
+/// [`BinaryGraph::get_entity`] for single-entity lookups, or
+/// [`BinaryGraph::load_graph`] to materialize the full [`RPGraph`].
+pub struct BinaryGraph {
+    data: Backing,
+    path: Option<PathBuf>,
+    sections: Vec<Section>,
+    directory: OnceLock<Vec<EntityRecord>>,
+    /// Decompressed entities section (only populated when it is compressed).
+    entities: OnceLock<Vec<u8>>,
+}
+
+impl BinaryGraph {
+    /// Memory-map a binary graph file.
+    ///
+    /// Writers replace graph files by atomic rename rather than rewriting them
+    /// in place, so an open mapping keeps seeing the old, complete file.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        // SAFETY: the mapping is read-only and graph files are only ever
+        // replaced via rename, never truncated or modified in place.
+        let map = unsafe { memmap2::Mmap::map(&file) }
+            .with_context(|| format!("failed to mmap {}", path.display()))?;
+        let mut graph = Self::parse(Backing::Mapped(map))?;
+        graph.path = Some(path.to_path_buf());
+        Ok(graph)
+    }
+
+    /// Wrap binary container bytes already held in memory.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Self::parse(Backing::Owned(bytes))
+    }
+
+    fn parse(data: Backing) -> Result<Self> {
+        if !is_binary(&data) || data.len() < PREAMBLE_LEN {
+            anyhow::bail!("not a binary RPG graph (missing RPGB magic)");
+        }
+        let format = u16::from_le_bytes([data[4], data[5]]);
+        if format != FORMAT_VERSION {
+            anyhow::bail!(
+                "unsupported binary graph format version {} (expected {})",
+                format,
+                FORMAT_VERSION
+            );
+        }
+        let count = u32::from_le_bytes(data[8..12].try_into()?) as usize;
+        let table_end = PREAMBLE_LEN + count * SECTION_ENTRY_LEN;
+        if data.len() < table_end {
+            anyhow::bail!("binary graph is truncated (section table)");
+        }
+
+        let mut sections = Vec::with_capacity(count);
+        for i in 0..count {
+            let at = PREAMBLE_LEN + i * SECTION_ENTRY_LEN;
+            let entry = &data[at..at + SECTION_ENTRY_LEN];
+            let section = Section {
+                kind: u32::from_le_bytes(entry[0..4].try_into()?),
+                flags: u32::from_le_bytes(entry[4..8].try_into()?),
+                offset: usize::try_from(u64::from_le_bytes(entry[8..16].try_into()?))?,
+                len: usize::try_from(u64::from_le_bytes(entry[16..24].try_into()?))?,
+            };
+            if section.offset.saturating_add(section.len) > data.len() {
+                anyhow::bail!("binary graph is truncated (section {})", section.kind);
+            }
+            sections.push(section);
+        }
+
+        Ok(Self {
+            data,
+            path: None,
+            sections,
+            directory: OnceLock::new(),
+            entities: OnceLock::new(),
+        })
+    }
+
+    fn section(&self, kind: SectionKind) -> Result<Section> {
+        self.sections
+            .iter()
+            .find(|s| s.kind == kind as u32)
+            .copied()
+            .with_context(|| format!("binary graph has no {:?} section", kind))
+    }
+
+    fn has_section(&self, kind: SectionKind) -> bool {
+        self.sections.iter().any(|s| s.kind == kind as u32)
+    }
+
+    fn raw_section(&self, section: Section) -> &[u8] {
+        &self.data[section.offset..section.offset + section.len]
+    }
+
+    /// Section payload, decompressed into an owned buffer when needed.
+    fn section_bytes(&self, kind: SectionKind) -> Result<std::borrow::Cow<'_, [u8]>> {
+        let section = self.section(kind)?;
+        let raw = self.raw_section(section);
+        if section.flags & FLAG_ZSTD != 0 {
+            let mut decoder = zstd::Decoder::new(raw).context("failed to init zstd decoder")?;
+            let mut out = Vec::new();
+            decoder
+                .read_to_end(&mut out)
+                .with_context(|| format!("failed to decompress {:?} section", kind))?;
+            Ok(std::borrow::Cow::Owned(out))
+        } else {
+            Ok(std::borrow::Cow::Borrowed(raw))
+        }
+    }
+
+    fn decode_section<T: for<'de> Deserialize<'de>>(&self, kind: SectionKind) -> Result<T> {
+        let bytes = self.section_bytes(kind)?;
+        decode_value(&bytes, &format!("{:?} section", kind))
+    }
+
+    /// Decode only the header: schema version, timestamps, commit and metadata.
+    pub fn header(&self) -> Result<GraphHeader> {
+        self.decode_section(SectionKind::Header)
+    }
+
+    fn directory(&self) -> Result<&[EntityRecord]> {
+        if let Some(dir) = self.directory.get() {
+            return Ok(dir);
+        }
+        let dir: Vec<EntityRecord> = self.decode_section(SectionKind::EntityDirectory)?;
+        Ok(self.directory.get_or_init(|| dir))
+    }
+
+    fn entities_blob(&self) -> Result<&[u8]> {
+        let section = self.section(SectionKind::Entities)?;
+        if section.flags & FLAG_ZSTD == 0 {
+            return Ok(self.raw_section(section));
+        }
+        if let Some(blob) = self.entities.get() {
+            return Ok(blob);
+        }
+        let blob = self.section_bytes(SectionKind::Entities)?.into_owned();
+        Ok(self.entities.get_or_init(|| blob))
+    }
+
+    /// All entity IDs, in sorted order, without decoding any entity.
+    pub fn entity_ids(&self) -> Result<Vec<String>> {
+        Ok(self.directory()?.iter().map(|r| r.id.clone()).collect())
+    }
+
+    /// Decode a single entity by ID.
+    pub fn get_entity(&self, id: &str) -> Result<Option<Entity>> {
+        let dir = self.directory()?;
+        let Ok(pos) = dir.binary_search_by(|r| r.id.as_str().cmp(id)) else {
+            return Ok(None);
+        };
+        let blob = self.entities_blob()?;
+        Self::decode_record(blob, &dir[pos]).map(Some)
+    }
+
+    fn decode_record(blob: &[u8], record: &EntityRecord) -> Result<Entity> {
+        let start = usize::try_from(record.offset)?;
+        let end = start.saturating_add(usize::try_from(record.len)?);
+        let bytes = blob
+            .get(start..end)
+            .with_context(|| format!("entity record out of bounds: {}", record.id))?;
+        decode_value(bytes, &format!("entity {}", record.id))
+    }
+
+    /// Decode every section into a full graph, applying schema migrations.
+    pub fn load_graph(&self) -> Result<RPGraph> {
+        let header = self.header()?;
+        let dir = self.directory()?;
+        let blob = self.entities_blob()?;
+        let mut entities = BTreeMap::new();
+        for record in dir {
+            entities.insert(record.id.clone(), Self::decode_record(blob, record)?);
+        }
+        let hierarchy: BTreeMap<String, HierarchyNode> =
+            self.decode_section(SectionKind::Hierarchy)?;
+        let file_index: BTreeMap<PathBuf, Vec<String>> =
+            self.decode_section(SectionKind::FileIndex)?;
+        let externals = if self.has_section(SectionKind::Externals) {
+            self.decode_section(SectionKind::Externals)?
+        } else {
+            BTreeMap::new()
+        };
+
+        let mut graph = RPGraph {
+            version: header.version,
+            created_at: header.created_at,
+            updated_at: header.updated_at,
+            base_commit: header.base_commit,
+            metadata: header.metadata,
+            hierarchy,
+            entities,
+            edges: self.decode_section(SectionKind::Edges)?,
+            file_index,
+            externals,
+            edge_index: self.decode_section(SectionKind::EdgeIndex)?,
+            hierarchy_node_index: self.decode_section(SectionKind::HierarchyIndex)?,
+        };
+
+        schema::validate_version(&graph)?;
+        let schema_version = graph.version.clone();
+        schema::migrate(&mut graph)?;
+        if graph.version != schema_version {
+            // Migrations may rewrite IDs, so the persisted indexes are stale
+            graph.rebuild_edge_index();
+            graph.rebuild_hierarchy_index();
+        }
+
+        Ok(graph)
+    }
+
+    /// Path of the mapped file, if this graph was opened from disk.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
@@ -16,12 +16,44 @@ pub struct RpgConfig {
 }
 
 /// Storage configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct StorageConfig {
-    /// Compress graph.json with zstd before writing.
-    /// Decompression on load is automatic (detected by magic bytes).
+    /// On-disk graph format. Loading detects the format from the files present,
+    /// so switching formats only requires a save.
+    pub format: StorageFormat,
+    /// Compress the graph with zstd before writing (whole file for JSON,
+    /// per section for binary). Decompression on load is automatic.
+    /// Ignored by the sharded format, whose point is line-mergeable text.
     pub compress: bool,
+    /// Number of commit revisions kept in `.rpg/history/` for time-travel queries.
+    /// A revision is recorded whenever a graph with a new `base_commit` is saved.
+    /// 0 disables history.
+    pub history_limit: usize,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            format: StorageFormat::default(),
+            compress: false,
+            history_limit: 20,
+        }
+    }
+}
+
+/// On-disk graph format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageFormat {
+    /// `.rpg/graph.json` — human-readable and diff-friendly.
+    #[default]
+    Json,
+    /// `.rpg/graph.rpgb` — compact MessagePack; single entities can be read through a memory map.
+    Binary,
+    /// `.rpg/graph/` — one JSON shard per source file plus a stable manifest,
+    /// so concurrent branches merge cleanly in git.
+    Sharded,
 }
 
 /// Encoding pipeline configuration.
@@ -57,6 +89,37 @@ pub struct EncodingConfig {
 pub struct NavigationConfig {
     /// Maximum number of search results returned.
     pub search_result_limit: usize,
+    /// Embedding backend for hybrid semantic search.
+    pub embedding_backend: EmbeddingBackendKind,
+}
+
+/// Which embedding backend semantic search uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingBackendKind {
+    /// fastembed when compiled in and its model loads, else the offline backend.
+    #[default]
+    Auto,
+    /// BGE-small-en-v1.5 via fastembed (downloads the model on first use).
+    Fastembed,
+    /// Offline hashed word and character n-gram vectors; no model, no network.
+    Hashed,
+    /// No embeddings: lexical search only.
+    Off,
+}
+
+impl std::str::FromStr for EmbeddingBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "fastembed" => Ok(Self::Fastembed),
+            "hashed" => Ok(Self::Hashed),
+            "off" => Ok(Self::Off),
+            other => Err(format!("unknown embedding backend: {other}")),
+        }
+    }
 }
 
 impl Default for EncodingConfig {
@@ -78,6 +141,7 @@ impl Default for NavigationConfig {
     fn default() -> Self {
         Self {
             search_result_limit: 10,
+            embedding_backend: EmbeddingBackendKind::default(),
         }
     }
 }
@@ -127,6 +191,10 @@ impl RpgConfig {
             "RPG_SEARCH_LIMIT",
             &mut config.navigation.search_result_limit,
         );
+        env_override(
+            "RPG_EMBEDDING_BACKEND",
+            &mut config.navigation.embedding_backend,
+        );
 
         // Validate drift thresholds
         if config.encoding.drift_ignore_threshold >= config.encoding.drift_auto_threshold {
@@ -176,6 +244,34 @@ search_result_limit = 20
         assert_eq!(config.encoding.drift_threshold, 0.5);
     }
 
+    #[test]
+    fn test_storage_format_from_toml() {
+        let config: RpgConfig = toml::from_str("[storage]\nformat = \"binary\"\n").unwrap();
+        assert_eq!(config.storage.format, StorageFormat::Binary);
+        assert!(!config.storage.compress);
+        assert_eq!(
+            RpgConfig::default().storage.format,
+            StorageFormat::Json,
+            "JSON stays the default format"
+        );
+    }
+
+    #[test]
+    fn test_embedding_backend_from_toml() {
+        let config: RpgConfig =
+            toml::from_str("[navigation]\nembedding_backend = \"hashed\"\n").unwrap();
+        assert_eq!(
+            config.navigation.embedding_backend,
+            EmbeddingBackendKind::Hashed
+        );
+        assert_eq!(
+            RpgConfig::default().navigation.embedding_backend,
+            EmbeddingBackendKind::Auto
+        );
+        assert_eq!("OFF".parse(), Ok(EmbeddingBackendKind::Off));
+        assert!("bert".parse::<EmbeddingBackendKind>().is_err());
+    }
+
     #[test]
     fn test_config_load_nonexistent() {
         let config = RpgConfig::load(Path::new("/nonexistent/path")).unwrap();
@@ -0,0 +1,461 @@
+//! Structural differences between two graph states.
+//!
+//! A [`GraphDelta`] records whole before/after values for every entity, file index
+//! entry, hierarchy, external package set and header that changed, so applying it to the old state yields
+//! the new state exactly (up to edge order, which is a set), and [`GraphDelta::invert`]
+//! gives the delta that undoes it. Incremental updates, feature merges and the MCP
+//! submit tools each produce one, so consumers can audit, stream or revert exactly
+//! what a mutation changed.
+
+use crate::graph::{
+    DependencyEdge, EdgeKind, Entity, ExternalNode, GraphHeader, HierarchyNode, RPGraph,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// An entity present in both states with a different value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityChange {
+    pub before: Entity,
+    pub after: Entity,
+}
+
+/// A `file_index` entry that changed (`None` means the file is not indexed).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileIndexChange {
+    pub file: PathBuf,
+    pub before: Option<Vec<String>>,
+    pub after: Option<Vec<String>>,
+}
+
+/// The hierarchy tree before and after, recorded only when it changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HierarchyChange {
+    pub before: BTreeMap<String, HierarchyNode>,
+    pub after: BTreeMap<String, HierarchyNode>,
+}
+
+/// The external packages before and after, recorded only when they changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalsChange {
+    pub before: BTreeMap<String, ExternalNode>,
+    pub after: BTreeMap<String, ExternalNode>,
+}
+
+/// Graph-level fields before and after, recorded only when they changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeaderChange {
+    pub before: GraphHeader,
+    pub after: GraphHeader,
+}
+
+/// An entity whose semantic features changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeatureChange<'a> {
+    pub entity_id: &'a str,
+    pub before: &'a [String],
+    pub after: &'a [String],
+}
+
+/// An entity that moved to a different hierarchy path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HierarchyMove<'a> {
+    pub entity_id: &'a str,
+    pub from: &'a str,
+    pub to: &'a str,
+}
+
+/// The difference between two graph states.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphDelta {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<Entity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<Entity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modified: Vec<EntityChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edges_added: Vec<DependencyEdge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edges_removed: Vec<DependencyEdge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_index: Vec<FileIndexChange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hierarchy: Option<HierarchyChange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub externals: Option<ExternalsChange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<HeaderChange>,
+}
+
+impl GraphDelta {
+    /// Compute the delta that turns `old` into `new`.
+    pub fn between(old: &RPGraph, new: &RPGraph) -> Self {
+        let mut delta = Self::default();
+
+        for (id, before) in &old.entities {
+            match new.entities.get(id) {
+                None => delta.removed.push(before.clone()),
+                Some(after) if after != before => delta.modified.push(EntityChange {
+                    before: before.clone(),
+                    after: after.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (id, after) in &new.entities {
+            if !old.entities.contains_key(id) {
+                delta.added.push(after.clone());
+            }
+        }
+
+        let old_edges: BTreeSet<&DependencyEdge> = old.edges.iter().collect();
+        let new_edges: BTreeSet<&DependencyEdge> = new.edges.iter().collect();
+        delta.edges_added = new_edges
+            .difference(&old_edges)
+            .map(|e| (*e).clone())
+            .collect();
+        delta.edges_removed = old_edges
+            .difference(&new_edges)
+            .map(|e| (*e).clone())
+            .collect();
+
+        let files: BTreeSet<&PathBuf> =
+            old.file_index.keys().chain(new.file_index.keys()).collect();
+        for file in files {
+            let before = old.file_index.get(file);
+            let after = new.file_index.get(file);
+            if before != after {
+                delta.file_index.push(FileIndexChange {
+                    file: file.clone(),
+                    before: before.cloned(),
+                    after: after.cloned(),
+                });
+            }
+        }
+
+        if old.hierarchy != new.hierarchy {
+            delta.hierarchy = Some(HierarchyChange {
+                before: old.hierarchy.clone(),
+                after: new.hierarchy.clone(),
+            });
+        }
+
+        if old.externals != new.externals {
+            delta.externals = Some(ExternalsChange {
+                before: old.externals.clone(),
+                after: new.externals.clone(),
+            });
+        }
+
+        let (before, after) = (old.header(), new.header());
+        if before != after {
+            delta.header = Some(HeaderChange { before, after });
+        }
+
+        delta
+    }
+
+    /// Whether the delta changes nothing.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the delta changes anything besides graph-level fields
+    /// (timestamps and metadata counts change on every save).
+    pub fn has_content_changes(&self) -> bool {
+        !(self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.edges_added.is_empty()
+            && self.edges_removed.is_empty()
+            && self.file_index.is_empty()
+            && self.hierarchy.is_none()
+            && self.externals.is_none())
+    }
+
+    /// The delta that undoes this one: applying it to the new state yields the old state.
+    #[must_use]
+    pub fn invert(&self) -> Self {
+        Self {
+            added: self.removed.clone(),
+            removed: self.added.clone(),
+            modified: self
+                .modified
+                .iter()
+                .map(|c| EntityChange {
+                    before: c.after.clone(),
+                    after: c.before.clone(),
+                })
+                .collect(),
+            edges_added: self.edges_removed.clone(),
+            edges_removed: self.edges_added.clone(),
+            file_index: self
+                .file_index
+                .iter()
+                .map(|c| FileIndexChange {
+                    file: c.file.clone(),
+                    before: c.after.clone(),
+                    after: c.before.clone(),
+                })
+                .collect(),
+            hierarchy: self.hierarchy.as_ref().map(|c| HierarchyChange {
+                before: c.after.clone(),
+                after: c.before.clone(),
+            }),
+            externals: self.externals.as_ref().map(|c| ExternalsChange {
+                before: c.after.clone(),
+                after: c.before.clone(),
+            }),
+            header: self.header.as_ref().map(|c| HeaderChange {
+                before: c.after.clone(),
+                after: c.before.clone(),
+            }),
+        }
+    }
+
+    /// Entity IDs whose state in `graph` no longer matches this delta's new state,
+    /// plus `<edge ...>`, `<file_index ...>`, `"<hierarchy>"` and `"<externals>"`
+    /// markers for the other parts that differ. Empty means the delta is still the
+    /// latest change to everything it touched, so its inverse can be applied
+    /// without clobbering later work.
+    pub fn conflicts_with(&self, graph: &RPGraph) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for entity in &self.added {
+            if graph.entities.get(&entity.id) != Some(entity) {
+                conflicts.push(entity.id.clone());
+            }
+        }
+        for change in &self.modified {
+            if graph.entities.get(&change.after.id) != Some(&change.after) {
+                conflicts.push(change.after.id.clone());
+            }
+        }
+        for entity in &self.removed {
+            if graph.entities.contains_key(&entity.id) {
+                conflicts.push(entity.id.clone());
+            }
+        }
+        let edges: BTreeSet<&DependencyEdge> = graph.edges.iter().collect();
+        let edge_conflicts = self
+            .edges_added
+            .iter()
+            .filter(|e| !edges.contains(e))
+            .chain(self.edges_removed.iter().filter(|e| edges.contains(e)));
+        for edge in edge_conflicts {
+            conflicts.push(format!(
+                "<edge {} -{:?}-> {}>",
+                edge.source, edge.kind, edge.target
+            ));
+        }
+        for change in &self.file_index {
+            if graph.file_index.get(&change.file) != change.after.as_ref() {
+                conflicts.push(format!("<file_index {}>", change.file.display()));
+            }
+        }
+        if let Some(change) = &self.hierarchy
+            && graph.hierarchy != change.after
+        {
+            conflicts.push("<hierarchy>".to_string());
+        }
+        if let Some(change) = &self.externals
+            && graph.externals != change.after
+        {
+            conflicts.push("<externals>".to_string());
+        }
+        conflicts
+    }
+
+    /// Modified entities whose `semantic_features` changed.
+    pub fn feature_changes(&self) -> Vec<FeatureChange<'_>> {
+        self.modified
+            .iter()
+            .filter(|c| c.before.semantic_features != c.after.semantic_features)
+            .map(|c| FeatureChange {
+                entity_id: &c.after.id,
+                before: &c.before.semantic_features,
+                after: &c.after.semantic_features,
+            })
+            .collect()
+    }
+
+    /// Modified entities whose `hierarchy_path` changed.
+    pub fn hierarchy_moves(&self) -> Vec<HierarchyMove<'_>> {
+        self.modified
+            .iter()
+            .filter(|c| c.before.hierarchy_path != c.after.hierarchy_path)
+            .map(|c| HierarchyMove {
+                entity_id: &c.after.id,
+                from: &c.before.hierarchy_path,
+                to: &c.after.hierarchy_path,
+            })
+            .collect()
+    }
+
+    /// One-line human-readable summary, e.g. for tool output.
+    pub fn summary(&self) -> String {
+        format!(
+            "+{} -{} ~{} entities ({} feature changes, {} hierarchy moves), +{} -{} edges{}",
+            self.added.len(),
+            self.removed.len(),
+            self.modified.len(),
+            self.feature_changes().len(),
+            self.hierarchy_moves().len(),
+            self.edges_added.len(),
+            self.edges_removed.len(),
+            if self.hierarchy.is_some() {
+                ", hierarchy changed"
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// Apply the delta to `graph`, turning the delta's old state into its new state.
+    /// Performance indexes are rebuilt afterwards.
+    pub fn apply(&self, graph: &mut RPGraph) {
+        for entity in &self.removed {
+            graph.entities.remove(&entity.id);
+        }
+        for entity in &self.added {
+            graph.entities.insert(entity.id.clone(), entity.clone());
+        }
+        for change in &self.modified {
+            graph
+                .entities
+                .insert(change.after.id.clone(), change.after.clone());
+        }
+
+        if !self.edges_removed.is_empty() {
+            let removed: BTreeSet<&DependencyEdge> = self.edges_removed.iter().collect();
+            graph.edges.retain(|e| !removed.contains(e));
+        }
+        let existing: BTreeSet<DependencyEdge> = graph.edges.iter().cloned().collect();
+        graph.edges.extend(
+            self.edges_added
+                .iter()
+                .filter(|e| !existing.contains(*e))
+                .cloned(),
+        );
+
+        for change in &self.file_index {
+            match &change.after {
+                Some(ids) => {
+                    graph.file_index.insert(change.file.clone(), ids.clone());
+                }
+                None => {
+                    graph.file_index.remove(&change.file);
+                }
+            }
+        }
+
+        if let Some(change) = &self.hierarchy {
+            graph.hierarchy.clone_from(&change.after);
+        }
+        if let Some(change) = &self.externals {
+            graph.externals.clone_from(&change.after);
+        }
+        if let Some(change) = &self.header {
+            graph.set_header(change.after.clone());
+        }
+
+        graph.rebuild_edge_index();
+        graph.rebuild_hierarchy_index();
+    }
+}
+
+/// The prior state of the parts of a graph a mutation is about to touch, so the
+/// resulting [`GraphDelta`] can be built without cloning the whole graph.
+///
+/// Call [`DeltaRecorder::entity`] before changing, adding or removing an entity,
+/// and [`DeltaRecorder::hierarchy`] before changing the hierarchy tree or the
+/// containment edges derived from it. [`DeltaRecorder::finish`] then compares only
+/// the recorded parts, plus the graph-level fields, with the graph's current state.
+/// Changes to other edges, the file index or external packages are not seen.
+#[derive(Debug, Clone)]
+pub struct DeltaRecorder {
+    entities: BTreeMap<String, Option<Entity>>,
+    hierarchy: Option<(BTreeMap<String, HierarchyNode>, Vec<DependencyEdge>)>,
+    header: GraphHeader,
+}
+
+impl DeltaRecorder {
+    /// Start recording a mutation of `graph`.
+    pub fn new(graph: &RPGraph) -> Self {
+        Self {
+            entities: BTreeMap::new(),
+            hierarchy: None,
+            header: graph.header(),
+        }
+    }
+
+    /// Record the current state of entity `id` (absent if not in the graph).
+    /// Only the first call per ID records anything.
+    pub fn entity(&mut self, graph: &RPGraph, id: &str) {
+        if !self.entities.contains_key(id) {
+            self.entities
+                .insert(id.to_string(), graph.entities.get(id).cloned());
+        }
+    }
+
+    /// Record the current hierarchy tree and containment edges.
+    /// Only the first call records anything.
+    pub fn hierarchy(&mut self, graph: &RPGraph) {
+        if self.hierarchy.is_none() {
+            self.hierarchy = Some((graph.hierarchy.clone(), containment_edges(graph)));
+        }
+    }
+
+    /// The delta from the recorded state to the current state of `graph`.
+    pub fn finish(self, graph: &RPGraph) -> GraphDelta {
+        let mut delta = GraphDelta::default();
+
+        for (id, before) in self.entities {
+            match (before, graph.entities.get(&id)) {
+                (None, Some(after)) => delta.added.push(after.clone()),
+                (Some(before), None) => delta.removed.push(before),
+                (Some(before), Some(after)) if *after != before => {
+                    delta.modified.push(EntityChange {
+                        before,
+                        after: after.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((hierarchy, edges)) = self.hierarchy {
+            let old_edges: BTreeSet<DependencyEdge> = edges.into_iter().collect();
+            let new_edges: BTreeSet<DependencyEdge> =
+                containment_edges(graph).into_iter().collect();
+            delta.edges_added = new_edges.difference(&old_edges).cloned().collect();
+            delta.edges_removed = old_edges.difference(&new_edges).cloned().collect();
+            if hierarchy != graph.hierarchy {
+                delta.hierarchy = Some(HierarchyChange {
+                    before: hierarchy,
+                    after: graph.hierarchy.clone(),
+                });
+            }
+        }
+
+        let after = graph.header();
+        if self.header != after {
+            delta.header = Some(HeaderChange {
+                before: self.header,
+                after,
+            });
+        }
+
+        delta
+    }
+}
+
+fn containment_edges(graph: &RPGraph) -> Vec<DependencyEdge> {
+    graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Contains)
+        .cloned()
+        .collect()
+}
@@ -10,6 +10,24 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
+/// Strip the overload discriminator (`#2`, `#1.3`) from an entity ID.
+///
+/// Overloads of the same symbol share a base ID (`file:Class::name`); one of them
+/// uses it verbatim and the others append `#<arity>`.
+pub fn base_entity_id(id: &str) -> &str {
+    match id.rsplit_once('#') {
+        Some((base, disc))
+            if !disc.is_empty()
+                && disc
+                    .split('.')
+                    .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit())) =>
+        {
+            base
+        }
+        _ => id,
+    }
+}
+
 /// The complete Repository Planning Graph: G = (V, E) where V = V_H ∪ V_L.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RPGraph {
@@ -26,6 +44,9 @@ pub struct RPGraph {
     pub edges: Vec<DependencyEdge>,
     /// Reverse index: file path → entity IDs in that file.
     pub file_index: BTreeMap<PathBuf, Vec<String>>,
+    /// Third-party packages declared in the project's manifests, keyed by ID.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub externals: BTreeMap<String, ExternalNode>,
     /// Performance index: entity ID → edge indices in `edges` vec.
     /// Rebuilt on load and after edge mutations via `rebuild_edge_index()`.
     #[serde(skip)]
@@ -36,8 +57,18 @@ pub struct RPGraph {
     pub hierarchy_node_index: HashMap<String, Vec<String>>,
 }
 
+/// Graph-level fields of an [`RPGraph`]: everything except nodes, edges and indexes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphHeader {
+    pub version: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub base_commit: Option<String>,
+    pub metadata: GraphMetadata,
+}
+
 /// Aggregate statistics and metadata for the graph.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GraphMetadata {
     pub language: String,
     /// All languages indexed in this graph (ordered by file count).
@@ -55,6 +86,9 @@ pub struct GraphMetadata {
     /// Number of DataFlow edges in the graph.
     #[serde(default)]
     pub data_flow_edges: usize,
+    /// Number of Tests edges in the graph.
+    #[serde(default)]
+    pub test_edges: usize,
     /// Whether the hierarchy is LLM-generated (true) or file-path structural (false).
     #[serde(default)]
     pub semantic_hierarchy: bool,
@@ -68,7 +102,7 @@ pub struct GraphMetadata {
 
 /// A code entity (V_L node): function, class, or method.
 /// Each node v = (f, m) with semantic features f and structural metadata m.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Entity {
     pub id: String,
     pub kind: EntityKind,
@@ -87,10 +121,16 @@ pub struct Entity {
     /// Typed function/method signature extracted from AST.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub signature: Option<Signature>,
+    /// Visibility, modifiers, docstring and decorators extracted from AST.
+    #[serde(default, skip_serializing_if = "EntityMetadata::is_empty")]
+    pub metadata: EntityMetadata,
+    /// Structural complexity signals computed from the AST.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signals: Option<StructuralSignals>,
 }
 
 /// Resolved dependency relationships for an entity (forward and reverse).
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct EntityDeps {
     pub imports: Vec<String>,
     pub invokes: Vec<String>,
@@ -107,6 +147,20 @@ pub struct EntityDeps {
     pub dispatches: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub data_flows_to: Vec<String>,
+    /// Module each entry of `imports` comes from, used to resolve references
+    /// to the imported file rather than to any same-named entity.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub import_sources: Vec<ImportSource>,
+    /// Receivers of the method calls in `invokes`, for methods whose every
+    /// call site is made on a value, type or module path (`repo.save()`,
+    /// `Repo::open()`, `std::fs::metadata()`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub receivers: Vec<CallReceiver>,
+    /// Argument counts of the method calls in `invokes`, for methods whose
+    /// every call site passes a known number of arguments; used to tell
+    /// overloads apart.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub call_arities: Vec<CallArity>,
     pub imported_by: Vec<String>,
     pub invoked_by: Vec<String>,
     pub inherited_by: Vec<String>,
@@ -136,6 +190,9 @@ impl EntityDeps {
         self.writes_state.clear();
         self.dispatches.clear();
         self.data_flows_to.clear();
+        self.import_sources.clear();
+        self.receivers.clear();
+        self.call_arities.clear();
     }
 
     /// Clear all reverse dependency vectors.
@@ -166,19 +223,42 @@ impl EntityDeps {
         ]
     }
 
+    /// Iterate all reverse dep vectors with their edge kinds.
+    pub fn reverse_deps(&self) -> [(EdgeKind, &Vec<String>); 9] {
+        [
+            (EdgeKind::Imports, &self.imported_by),
+            (EdgeKind::Invokes, &self.invoked_by),
+            (EdgeKind::Inherits, &self.inherited_by),
+            (EdgeKind::Composes, &self.composed_by),
+            (EdgeKind::Renders, &self.rendered_by),
+            (EdgeKind::ReadsState, &self.state_read_by),
+            (EdgeKind::WritesState, &self.state_written_by),
+            (EdgeKind::Dispatches, &self.dispatched_by),
+            (EdgeKind::DataFlow, &self.data_flows_from),
+        ]
+    }
+
+    /// The reverse dep vector for the given edge kind (`None` for `Contains` and
+    /// `Tests`, which are derived from other edges rather than stored in deps).
+    pub fn reverse_deps_mut(&mut self, kind: EdgeKind) -> Option<&mut Vec<String>> {
+        match kind {
+            EdgeKind::Imports => Some(&mut self.imported_by),
+            EdgeKind::Invokes => Some(&mut self.invoked_by),
+            EdgeKind::Inherits => Some(&mut self.inherited_by),
+            EdgeKind::Composes => Some(&mut self.composed_by),
+            EdgeKind::Renders => Some(&mut self.rendered_by),
+            EdgeKind::ReadsState => Some(&mut self.state_read_by),
+            EdgeKind::WritesState => Some(&mut self.state_written_by),
+            EdgeKind::Dispatches => Some(&mut self.dispatched_by),
+            EdgeKind::DataFlow => Some(&mut self.data_flows_from),
+            EdgeKind::Tests | EdgeKind::Contains => None,
+        }
+    }
+
     /// Push a source ID to the correct reverse dep vector for the given edge kind.
     pub fn push_reverse(&mut self, kind: EdgeKind, source_id: String) {
-        let vec = match kind {
-            EdgeKind::Imports => &mut self.imported_by,
-            EdgeKind::Invokes => &mut self.invoked_by,
-            EdgeKind::Inherits => &mut self.inherited_by,
-            EdgeKind::Composes => &mut self.composed_by,
-            EdgeKind::Renders => &mut self.rendered_by,
-            EdgeKind::ReadsState => &mut self.state_read_by,
-            EdgeKind::WritesState => &mut self.state_written_by,
-            EdgeKind::Dispatches => &mut self.dispatched_by,
-            EdgeKind::DataFlow => &mut self.data_flows_from,
-            EdgeKind::Contains => return,
+        let Some(vec) = self.reverse_deps_mut(kind) else {
+            return;
         };
         if !vec.contains(&source_id) {
             vec.push(source_id);
@@ -196,6 +276,14 @@ impl EntityDeps {
             || !self.dispatches.is_empty()
     }
 
+    /// The module a symbol was imported from, if the entity imports it.
+    pub fn import_source(&self, symbol: &str) -> Option<&str> {
+        self.import_sources
+            .iter()
+            .find(|s| s.symbol == symbol)
+            .map(|s| s.module.as_str())
+    }
+
     /// Whether any forward dep vector (excluding imports) contains the given symbol.
     pub fn references_symbol(&self, sym: &str) -> bool {
         self.invokes.iter().any(|s| s == sym)
@@ -207,9 +295,41 @@ impl EntityDeps {
     }
 }
 
+/// An imported symbol and the module it is imported from, as written in the
+/// import statement (e.g. `save` from `app.models`, `Entity` from `crate::graph`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSource {
+    pub symbol: String,
+    pub module: String,
+}
+
+/// A method called on a value, type or module path (e.g. `find` on `Repo`
+/// for `repo.find()` where `repo: Repo`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallReceiver {
+    pub method: String,
+    /// Static type of the receiver without generics, references or path
+    /// (`Repo` for `&mut repo::Repo<T>`); `None` when it could not be inferred.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receiver: Option<String>,
+    /// Module path the call is qualified by instead of a value (`std::fs` for
+    /// `std::fs::metadata()`), as written at the call site.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+}
+
+/// The argument counts a method is called with (e.g. `put` with `[1, 2]` for
+/// `put(k)` and `put(k, v)`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallArity {
+    pub method: String,
+    /// Distinct argument counts, ascending.
+    pub args: Vec<usize>,
+}
+
 /// A node in the semantic hierarchy tree (V_H node).
 /// Unified with Entity as a proper graph node: has id, semantic_features.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HierarchyNode {
     /// Unique ID: "h:Area/Category/Subcategory"
     pub id: String,
@@ -319,6 +439,62 @@ impl HierarchyNode {
         all.dedup();
         self.semantic_features = all;
     }
+
+    /// Ground this node and all descendants at the LCA directories of their files.
+    pub fn ground(&mut self, entities: &BTreeMap<String, Entity>) {
+        for child in self.children.values_mut() {
+            child.ground(entities);
+        }
+        let paths = self.collect_file_paths(entities);
+        if !paths.is_empty() {
+            self.grounded_paths = crate::lca::compute_lca(&paths);
+        }
+    }
+}
+
+/// A third-party package declared in a manifest (`Cargo.toml`, `package.json`,
+/// `requirements.txt`, `pyproject.toml`, `go.mod`). Entities importing it are
+/// linked to it by dependency edges, so it can be the origin of an impact query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalNode {
+    /// `ext:<name>`, see [`ExternalNode::id_for`].
+    pub id: String,
+    /// Package name as declared in the manifest.
+    pub name: String,
+    pub ecosystem: PackageEcosystem,
+    /// Version requirement as declared, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Manifests declaring the package, relative to the project root.
+    pub manifests: Vec<PathBuf>,
+}
+
+impl ExternalNode {
+    /// The node ID of a package.
+    pub fn id_for(name: &str) -> String {
+        format!("ext:{}", name)
+    }
+}
+
+/// The package registry an [`ExternalNode`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageEcosystem {
+    Cargo,
+    Npm,
+    PyPI,
+    Go,
+}
+
+impl PackageEcosystem {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cargo => "cargo",
+            Self::Npm => "npm",
+            Self::PyPI => "pypi",
+            Self::Go => "go",
+        }
+    }
 }
 
 /// An edge in the unified edge set E = E_dep ∪ E_feature.
@@ -327,6 +503,25 @@ pub struct DependencyEdge {
     pub source: String,
     pub target: String,
     pub kind: EdgeKind,
+    /// How reliably the target was resolved. `None` for containment edges and
+    /// edges not produced by name resolution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<EdgeConfidence>,
+}
+
+/// Confidence of a resolved dependency edge, from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeConfidence {
+    /// The only entity with that name in the project, with no import or scope
+    /// evidence (typically a method called on an object of unknown type).
+    Low,
+    /// One of several same-file candidates, or a symbol imported from a module
+    /// that could not be located.
+    Medium,
+    /// Resolved through the caller's class hierarchy, an import naming the
+    /// target's file, or a unique definition in the caller's file.
+    High,
 }
 
 /// The kind of relationship between two nodes in the graph.
@@ -351,6 +546,9 @@ pub enum EdgeKind {
     Dispatches,
     /// E_dep: data flow between entities (parameter passing and return values).
     DataFlow,
+    /// E_dep: test → code it calls, renders or dispatches to, or state it reads
+    /// or writes; code reached through further calls is not linked.
+    Tests,
     /// E_feature: hierarchy containment (parent → child).
     Contains,
 }
@@ -363,6 +561,135 @@ pub struct Signature {
     pub return_type: Option<String>,
 }
 
+/// Declared visibility of an entity, normalized across languages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Exported / `pub` / `public`.
+    Public,
+    /// Visible to subclasses (`protected`).
+    Protected,
+    /// Visible to the enclosing package, module or assembly
+    /// (`internal`, `pub(crate)`, Java package-private).
+    Internal,
+    /// Visible only to the declaring scope (`private`, `_name`, C `static`).
+    Private,
+}
+
+impl Visibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Protected => "protected",
+            Self::Internal => "internal",
+            Self::Private => "private",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "public" | "pub" | "exported" => Some(Self::Public),
+            "protected" => Some(Self::Protected),
+            "internal" | "package" | "crate" => Some(Self::Internal),
+            "private" => Some(Self::Private),
+            _ => None,
+        }
+    }
+}
+
+/// Structural metadata extracted from declaration modifiers, doc comments
+/// and decorators/attributes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct EntityMetadata {
+    /// `None` when the language has no notion of visibility for this entity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_static: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_abstract: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_deprecated: bool,
+    /// Doc comment or docstring, with comment markers stripped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docstring: Option<String>,
+    /// Decorators, attributes or annotations as written, without the leading
+    /// sigil (e.g. `staticmethod`, `derive(Debug)`, `Override`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub decorators: Vec<String>,
+    /// Notebook cell the entity starts in, for entities of `.ipynb` files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell: Option<NotebookCell>,
+}
+
+/// Position of an entity's first line inside a Jupyter notebook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotebookCell {
+    /// 1-indexed position of the cell among all cells of the notebook.
+    pub index: usize,
+    /// 1-indexed line within the cell.
+    pub line: usize,
+}
+
+impl EntityMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Short modifier keywords for display (e.g. `public async static`).
+    pub fn modifiers(&self) -> Vec<&'static str> {
+        let mut out = Vec::new();
+        if let Some(v) = self.visibility {
+            out.push(v.as_str());
+        }
+        for (flag, word) in [
+            (self.is_abstract, "abstract"),
+            (self.is_static, "static"),
+            (self.is_async, "async"),
+            (self.is_deprecated, "deprecated"),
+        ] {
+            if flag {
+                out.push(word);
+            }
+        }
+        out
+    }
+}
+
+/// Structural complexity signals for a function-like entity.
+///
+/// The first five counts are also produced by the regex fallback in
+/// `rpg_parser::signals::analyze`; the remaining fields need a syntax tree
+/// and stay zero on that path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuralSignals {
+    /// Total line count.
+    pub line_count: usize,
+    /// Branch points: if, else, match, switch, case, when, elif, elsif.
+    pub branch_count: usize,
+    /// Loop constructs: for, while, loop, each, do { }.
+    pub loop_count: usize,
+    /// Return statements not on the last line.
+    pub early_return_count: usize,
+    /// Function/method calls: identifier followed by `(`.
+    pub call_count: usize,
+    /// McCabe cyclomatic complexity: 1 + decision points.
+    #[serde(default)]
+    pub cyclomatic_complexity: usize,
+    /// Cognitive complexity: control flow weighted by nesting.
+    #[serde(default)]
+    pub cognitive_complexity: usize,
+    /// Deepest nesting of control-flow structures.
+    #[serde(default)]
+    pub max_nesting_depth: usize,
+    /// Declared parameters, excluding the receiver (`self`, `this`).
+    #[serde(default)]
+    pub parameter_count: usize,
+}
+
 /// A single parameter in a function signature.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Param {
@@ -390,6 +717,32 @@ pub enum EntityKind {
     Middleware,
     Route,
     Test,
+    /// A field or property of a class, struct or record.
+    Field,
+    /// A named constant: `const`, `static final`, `#define`, or an ALL_CAPS
+    /// module-level assignment in languages without constants.
+    Constant,
+    /// A variant or member of an enum.
+    EnumVariant,
+    /// A mutable variable declared at module level.
+    Global,
+}
+
+impl EntityKind {
+    /// Whether this kind names data (fields, constants, enum variants,
+    /// globals) rather than code.
+    pub fn is_data(self) -> bool {
+        matches!(
+            self,
+            Self::Field | Self::Constant | Self::EnumVariant | Self::Global
+        )
+    }
+
+    /// Whether entities of this kind receive semantic features from lifting.
+    /// Modules get theirs by aggregation; data entities are never lifted.
+    pub fn is_liftable(self) -> bool {
+        self != Self::Module && !self.is_data()
+    }
 }
 
 impl RPGraph {
@@ -412,6 +765,7 @@ impl RPGraph {
                 containment_edges: 0,
                 lifted_entities: 0,
                 data_flow_edges: 0,
+                test_edges: 0,
                 semantic_hierarchy: false,
                 repo_summary: None,
                 paradigms: Vec::new(),
@@ -420,39 +774,77 @@ impl RPGraph {
             entities: BTreeMap::new(),
             edges: Vec::new(),
             file_index: BTreeMap::new(),
+            externals: BTreeMap::new(),
             edge_index: HashMap::new(),
             hierarchy_node_index: HashMap::new(),
         }
     }
 
+    /// Graph-level fields (version, timestamps, base commit, metadata).
+    pub fn header(&self) -> GraphHeader {
+        GraphHeader {
+            version: self.version.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            base_commit: self.base_commit.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Replace the graph-level fields.
+    pub fn set_header(&mut self, header: GraphHeader) {
+        self.version = header.version;
+        self.created_at = header.created_at;
+        self.updated_at = header.updated_at;
+        self.base_commit = header.base_commit;
+        self.metadata = header.metadata;
+    }
+
     /// Recompute metadata from current state and rebuild performance indexes.
     pub fn refresh_metadata(&mut self) {
-        self.metadata.total_entities = self.entities.len();
-        self.metadata.total_files = self.file_index.len();
-        self.metadata.functional_areas = self.hierarchy.len();
-        self.metadata.total_edges = self.edges.len();
-        self.metadata.dependency_edges = self
+        self.recount_metadata();
+        self.updated_at = Utc::now();
+        self.rebuild_edge_index();
+    }
+
+    /// Recompute the metadata counts from current state, leaving timestamps untouched.
+    pub fn recount_metadata(&mut self) {
+        self.metadata = self.recounted_metadata();
+    }
+
+    /// The metadata with every count recomputed from current state.
+    pub fn recounted_metadata(&self) -> GraphMetadata {
+        let mut metadata = self.metadata.clone();
+        metadata.total_entities = self.entities.len();
+        metadata.total_files = self.file_index.len();
+        metadata.functional_areas = self.hierarchy.len();
+        metadata.total_edges = self.edges.len();
+        metadata.dependency_edges = self
             .edges
             .iter()
             .filter(|e| e.kind != EdgeKind::Contains)
             .count();
-        self.metadata.containment_edges = self
+        metadata.containment_edges = self
             .edges
             .iter()
             .filter(|e| e.kind == EdgeKind::Contains)
             .count();
-        self.metadata.lifted_entities = self
+        metadata.lifted_entities = self
             .entities
             .values()
-            .filter(|e| !e.semantic_features.is_empty())
+            .filter(|e| e.kind.is_liftable() && !e.semantic_features.is_empty())
             .count();
-        self.metadata.data_flow_edges = self
+        metadata.data_flow_edges = self
             .edges
             .iter()
             .filter(|e| e.kind == EdgeKind::DataFlow)
             .count();
-        self.updated_at = Utc::now();
-        self.rebuild_edge_index();
+        metadata.test_edges = self
+            .edges
+            .iter()
+            .filter(|e| e.kind == EdgeKind::Tests)
+            .count();
+        metadata
     }
 
     /// Rebuild the edge index from the current edge list.
@@ -499,26 +891,21 @@ impl RPGraph {
         }
     }
 
-    /// Return (lifted, total) entity counts.
+    /// Return (lifted, total) counts of liftable entities.
     pub fn lifting_coverage(&self) -> (usize, usize) {
-        let non_module = self
-            .entities
-            .values()
-            .filter(|e| e.kind != EntityKind::Module);
-        let total = non_module.clone().count();
-        let lifted = non_module
-            .filter(|e| !e.semantic_features.is_empty())
-            .count();
+        let liftable = self.entities.values().filter(|e| e.kind.is_liftable());
+        let total = liftable.clone().count();
+        let lifted = liftable.filter(|e| !e.semantic_features.is_empty()).count();
         (lifted, total)
     }
 
-    /// Return unlifted non-module entities grouped by file path.
+    /// Return unlifted liftable entities grouped by file path.
     /// Each entry is (file_display_string, Vec<entity_id>), sorted by count descending.
     pub fn unlifted_by_file(&self) -> Vec<(String, Vec<String>)> {
         let mut by_file: std::collections::HashMap<String, Vec<String>> =
             std::collections::HashMap::new();
         for (id, entity) in &self.entities {
-            if entity.kind != EntityKind::Module && entity.semantic_features.is_empty() {
+            if entity.kind.is_liftable() && entity.semantic_features.is_empty() {
                 by_file
                     .entry(entity.file.to_string_lossy().to_string())
                     .or_default()
@@ -526,7 +913,7 @@ impl RPGraph {
             }
         }
         let mut result: Vec<(String, Vec<String>)> = by_file.into_iter().collect();
-        result.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+        result.sort_by_key(|b| std::cmp::Reverse(b.1.len()));
         result
     }
 
@@ -534,15 +921,16 @@ impl RPGraph {
     pub fn area_coverage(&self) -> Vec<(String, usize, usize)> {
         let mut result = Vec::new();
         for (area_name, node) in &self.hierarchy {
-            let ids = node.all_entity_ids();
-            let total = ids.len();
-            let lifted = ids
+            let entities: Vec<&Entity> = node
+                .all_entity_ids()
                 .iter()
-                .filter(|id| {
-                    self.entities
-                        .get(*id)
-                        .is_some_and(|e| !e.semantic_features.is_empty())
-                })
+                .filter_map(|id| self.entities.get(id))
+                .filter(|e| e.kind.is_liftable())
+                .collect();
+            let total = entities.len();
+            let lifted = entities
+                .iter()
+                .filter(|e| !e.semantic_features.is_empty())
                 .count();
             result.push((area_name.clone(), lifted, total));
         }
@@ -717,7 +1105,7 @@ impl RPGraph {
         None
     }
 
-    /// Get display info for any node (V_L entity or V_H hierarchy node).
+    /// Get display info for any node (V_L entity, V_H hierarchy node or external package).
     /// Returns (name, description) for unified display.
     pub fn get_node_display_info(&self, id: &str) -> Option<(String, String)> {
         if let Some(entity) = self.entities.get(id) {
@@ -748,6 +1136,13 @@ impl RPGraph {
             };
             return Some((node.name.clone(), desc));
         }
+        if let Some(ext) = self.externals.get(id) {
+            let desc = match &ext.version {
+                Some(version) => format!("external {} package {}", ext.ecosystem.as_str(), version),
+                None => format!("external {} package", ext.ecosystem.as_str()),
+            };
+            return Some((ext.name.clone(), desc));
+        }
         None
     }
 
@@ -784,6 +1179,31 @@ impl RPGraph {
         }
     }
 
+    /// Rebuild V_H from the entities' `hierarchy_path`s, then ground it, assign
+    /// node IDs and aggregate features. Containment edges are left untouched.
+    pub fn rebuild_hierarchy_from_paths(&mut self) {
+        self.hierarchy.clear();
+        let assignments: Vec<(String, String)> = self
+            .entities
+            .iter()
+            .filter(|(_, e)| !e.hierarchy_path.is_empty())
+            .map(|(id, e)| (id.clone(), e.hierarchy_path.clone()))
+            .collect();
+        for (entity_id, path) in &assignments {
+            self.insert_into_hierarchy(path, entity_id);
+        }
+        let Self {
+            entities,
+            hierarchy,
+            ..
+        } = self;
+        for area in hierarchy.values_mut() {
+            area.ground(entities);
+        }
+        self.assign_hierarchy_ids();
+        self.aggregate_hierarchy_features();
+    }
+
     /// Create Module entities for each file in the graph (paper §3.1: "files, classes, and functions").
     /// Must be called after all entities have been inserted.
     pub fn create_module_entities(&mut self) {
@@ -823,6 +1243,8 @@ impl RPGraph {
                 hierarchy_path: String::new(),
                 deps: EntityDeps::default(),
                 signature: None,
+                metadata: EntityMetadata::default(),
+                signals: None,
             };
             self.entities.insert(module_id.clone(), entity);
             self.file_index.entry(file).or_default().push(module_id);
@@ -884,6 +1306,7 @@ impl RPGraph {
                     source: node.id.clone(),
                     target: child.id.clone(),
                     kind: EdgeKind::Contains,
+                    confidence: None,
                 });
             }
             Self::collect_containment_edges(child, edges);
@@ -894,6 +1317,7 @@ impl RPGraph {
                     source: node.id.clone(),
                     target: eid.clone(),
                     kind: EdgeKind::Contains,
+                    confidence: None,
                 });
             }
         }
@@ -0,0 +1,250 @@
+//! Bounded revision history (`.rpg/history/`) keyed by commit SHA.
+//!
+//! ```text
+//! .rpg/history/
+//!   index.json             recorded revisions, oldest first
+//!   head.rpgb              full snapshot of the newest revision (compressed binary)
+//!   <seq>.delta.zst        reverse delta: next-newer revision → this revision
+//! ```
+//!
+//! A revision is recorded whenever a graph with a new `base_commit` is saved. Later
+//! saves at the same commit (lifting, routing) cost only a read of the index and
+//! are not recorded, so each revision holds the graph as first saved at its commit
+//! and lifting done since then shows up in the next one. Older states are
+//! materialized by applying reverse [`GraphDelta`]s to the head snapshot, so the
+//! store costs one full graph plus what actually changed between commits. Only the
+//! newest `history_limit` revisions (see [`crate::config::StorageConfig`]) are kept.
+//!
+//! History is local, derived state and is git-ignored.
+
+use crate::binary;
+use crate::delta::GraphDelta;
+use crate::graph::{Entity, RPGraph};
+use crate::storage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+
+const HISTORY_DIR: &str = "history";
+const INDEX_FILE: &str = "index.json";
+const HEAD_FILE: &str = "head.rpgb";
+
+/// One recorded graph revision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    /// Monotonic sequence number; names the revision's delta file.
+    pub seq: u64,
+    /// The graph's `base_commit` when the revision was recorded.
+    pub commit: String,
+    pub recorded_at: DateTime<Utc>,
+    pub entities: usize,
+    pub edges: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    revisions: Vec<Revision>,
+}
+
+/// One entity's state at a recorded revision (`None` if it did not exist).
+#[derive(Debug, Clone, Serialize)]
+pub struct EntitySnapshot {
+    pub commit: String,
+    pub recorded_at: DateTime<Utc>,
+    pub entity: Option<Entity>,
+}
+
+/// Get the path to the history directory for a given project root.
+pub fn history_dir(project_root: &Path) -> PathBuf {
+    storage::rpg_dir(project_root).join(HISTORY_DIR)
+}
+
+fn delta_file(dir: &Path, seq: u64) -> PathBuf {
+    dir.join(format!("{}.delta.zst", seq))
+}
+
+fn read_index(dir: &Path) -> Result<HistoryIndex> {
+    let path = dir.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(HistoryIndex::default());
+    }
+    let json =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn write_index(dir: &Path, index: &HistoryIndex) -> Result<()> {
+    let json = serde_json::to_string_pretty(index).context("failed to serialize history index")?;
+    storage::write_atomic(&dir.join(INDEX_FILE), json.as_bytes())
+}
+
+fn read_head(dir: &Path) -> Result<RPGraph> {
+    let path = dir.join(HEAD_FILE);
+    let bytes = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    binary::decode(&bytes).with_context(|| format!("failed to decode {}", path.display()))
+}
+
+fn write_head(dir: &Path, graph: &RPGraph) -> Result<()> {
+    storage::write_atomic(&dir.join(HEAD_FILE), &binary::encode(graph, true)?)
+}
+
+fn read_delta(dir: &Path, seq: u64) -> Result<GraphDelta> {
+    let path = delta_file(dir, seq);
+    let raw = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut json = String::new();
+    zstd::Decoder::new(&raw[..])
+        .context("failed to init zstd decoder")?
+        .read_to_string(&mut json)
+        .with_context(|| format!("failed to decompress {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn write_delta(dir: &Path, seq: u64, delta: &GraphDelta) -> Result<()> {
+    let json = serde_json::to_vec(delta).context("failed to serialize history delta")?;
+    let compressed = zstd::encode_all(&json[..], 3).context("failed to compress history delta")?;
+    storage::write_atomic(&delta_file(dir, seq), &compressed)
+}
+
+/// Record `graph` as the newest revision. Called by [`storage::save_with_config`]
+/// while it holds the exclusive graph lock.
+///
+/// Graphs without a `base_commit`, or at the commit of the newest revision, are not
+/// recorded. A `limit` of 0 disables history.
+pub(crate) fn record(project_root: &Path, graph: &RPGraph, limit: usize) -> Result<()> {
+    let Some(commit) = graph.base_commit.clone() else {
+        return Ok(());
+    };
+    if limit == 0 {
+        return Ok(());
+    }
+
+    let dir = history_dir(project_root);
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let mut index = match read_index(&dir) {
+        Ok(index) => index,
+        Err(e) => {
+            tracing::warn!("discarding unreadable revision history: {:#}", e);
+            fs::remove_dir_all(&dir).ok();
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+            HistoryIndex::default()
+        }
+    };
+    if index
+        .revisions
+        .last()
+        .is_some_and(|head| head.commit == commit)
+    {
+        return Ok(());
+    }
+
+    let revision = Revision {
+        seq: index.revisions.last().map_or(0, |r| r.seq + 1),
+        commit,
+        recorded_at: Utc::now(),
+        entities: graph.entities.len(),
+        edges: graph.edges.len(),
+    };
+
+    if let Some(head) = index.revisions.last() {
+        let old_head = read_head(&dir)?;
+        write_delta(&dir, head.seq, &GraphDelta::between(graph, &old_head))?;
+    }
+    index.revisions.push(revision);
+    write_head(&dir, graph)?;
+
+    let excess = index.revisions.len().saturating_sub(limit);
+    for pruned in index.revisions.drain(..excess) {
+        storage::remove_if_exists(&delta_file(&dir, pruned.seq))?;
+    }
+    write_index(&dir, &index)
+}
+
+/// List recorded revisions, oldest first.
+pub fn revisions(project_root: &Path) -> Result<Vec<Revision>> {
+    Ok(read_index(&history_dir(project_root))?.revisions)
+}
+
+/// Visit recorded graph states from newest to oldest until `visit` returns false.
+fn walk(project_root: &Path, mut visit: impl FnMut(&Revision, &RPGraph) -> bool) -> Result<()> {
+    let dir = history_dir(project_root);
+    let _lock = storage::acquire_lock(project_root, false)?;
+    let index = read_index(&dir)?;
+    let Some((head, older)) = index.revisions.split_last() else {
+        return Ok(());
+    };
+
+    let mut graph = read_head(&dir)?;
+    if !visit(head, &graph) {
+        return Ok(());
+    }
+    for revision in older.iter().rev() {
+        read_delta(&dir, revision.seq)?.apply(&mut graph);
+        if !visit(revision, &graph) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Materialize the newest recorded revision matching `predicate`.
+///
+/// Callers with access to git can pass an ancestry check here to find the graph
+/// in effect at a commit that was never recorded itself.
+pub fn as_of_where(
+    project_root: &Path,
+    mut predicate: impl FnMut(&Revision) -> bool,
+) -> Result<Option<(Revision, RPGraph)>> {
+    let mut found = None;
+    walk(project_root, |revision, graph| {
+        if predicate(revision) {
+            found = Some((revision.clone(), graph.clone()));
+            false
+        } else {
+            true
+        }
+    })?;
+    Ok(found)
+}
+
+/// Materialize the graph as recorded at `commit` (a full SHA or unique prefix).
+/// Returns `None` if no revision matches.
+pub fn as_of(project_root: &Path, commit: &str) -> Result<Option<RPGraph>> {
+    let mut matches: Vec<String> = revisions(project_root)?
+        .into_iter()
+        .filter(|r| r.commit.starts_with(commit))
+        .map(|r| r.commit)
+        .collect();
+    matches.sort();
+    matches.dedup();
+    if matches.len() > 1 {
+        anyhow::bail!(
+            "commit prefix '{}' is ambiguous ({} recorded revisions match)",
+            commit,
+            matches.len()
+        );
+    }
+    let Some(full) = matches.pop() else {
+        return Ok(None);
+    };
+    Ok(as_of_where(project_root, |r| r.commit == full)?.map(|(_, graph)| graph))
+}
+
+/// An entity's state at every recorded revision, oldest first: its features,
+/// hierarchy placement and position as they evolved across commits.
+pub fn entity_timeline(project_root: &Path, entity_id: &str) -> Result<Vec<EntitySnapshot>> {
+    let mut timeline = Vec::new();
+    walk(project_root, |revision, graph| {
+        timeline.push(EntitySnapshot {
+            commit: revision.commit.clone(),
+            recorded_at: revision.recorded_at,
+            entity: graph.entities.get(entity_id).cloned(),
+        });
+        true
+    })?;
+    timeline.reverse();
+    Ok(timeline)
+}
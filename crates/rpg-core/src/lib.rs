@@ -1,10 +1,18 @@
 //! Core types and storage for the Repository Planning Graph (RPG).
 //!
 //! Provides the graph data model ([`graph::RPGraph`]), entity types, dependency edges,
-//! hierarchy nodes, JSON persistence, and LCA-based directory grounding.
+//! hierarchy nodes, JSON, memory-mapped binary and sharded persistence, graph deltas
+//! and per-commit revision history, integrity validation and repair, LCA-based
+//! directory grounding, and the script view of Jupyter notebooks.
 
+pub mod binary;
 pub mod config;
+pub mod delta;
 pub mod graph;
+pub mod history;
 pub mod lca;
+pub mod notebook;
 pub mod schema;
+pub mod sharded;
 pub mod storage;
+pub mod validate;
@@ -0,0 +1,179 @@
+//! Script view of Jupyter notebooks (`.ipynb`).
+//!
+//! The code cells of a Python notebook are joined into a script in the
+//! `# %% [n]` cell format. Entities of a notebook are extracted from that
+//! script, so their line numbers refer to it; [`read_source`] renders it
+//! wherever entity source is sliced by line.
+
+use crate::graph::NotebookCell;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Deserialize)]
+struct NotebookJson {
+    #[serde(default)]
+    cells: Vec<CellJson>,
+    #[serde(default)]
+    metadata: NotebookMetadata,
+}
+
+#[derive(Deserialize, Default)]
+struct NotebookMetadata {
+    kernelspec: Option<KernelSpec>,
+    language_info: Option<LanguageInfo>,
+}
+
+#[derive(Deserialize)]
+struct KernelSpec {
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LanguageInfo {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CellJson {
+    cell_type: String,
+    #[serde(default)]
+    source: CellSource,
+}
+
+/// nbformat stores cell sources as a list of lines or as a single string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CellSource {
+    Lines(Vec<String>),
+    Text(String),
+}
+
+impl Default for CellSource {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl CellSource {
+    fn text(&self) -> String {
+        match self {
+            Self::Lines(lines) => lines.concat(),
+            Self::Text(text) => text.clone(),
+        }
+    }
+}
+
+/// Cell magics whose body is still Python.
+const PYTHON_CELL_MAGICS: &[&str] = &["time", "timeit", "capture", "prun", "debug"];
+
+/// Script lines holding one code cell.
+#[derive(Debug, Clone, Copy)]
+struct CellSpan {
+    /// 1-indexed position of the cell in the notebook.
+    index: usize,
+    /// Script line of the cell's first line.
+    first_line: usize,
+    line_count: usize,
+}
+
+/// The Python code cells of a notebook, joined into a script.
+#[derive(Debug, Clone)]
+pub struct Script {
+    pub source: String,
+    cells: Vec<CellSpan>,
+}
+
+impl Script {
+    /// Build the script of a notebook. Returns `None` for malformed JSON and
+    /// notebooks whose kernel is not Python.
+    pub fn parse(notebook: &str) -> Option<Self> {
+        let nb: NotebookJson = serde_json::from_str(notebook).ok()?;
+        let kernel = nb
+            .metadata
+            .kernelspec
+            .and_then(|k| k.language)
+            .or_else(|| nb.metadata.language_info.and_then(|l| l.name));
+        if kernel.is_some_and(|k| !k.eq_ignore_ascii_case("python")) {
+            return None;
+        }
+
+        let mut source = String::new();
+        let mut cells = Vec::new();
+        let mut line = 1;
+        for (i, cell) in nb.cells.iter().enumerate() {
+            if cell.cell_type != "code" {
+                continue;
+            }
+            let text = cell.source.text();
+            if text.trim().is_empty() || !is_python_cell(&text) {
+                continue;
+            }
+            source.push_str(&format!("# %% [{}]\n", i + 1));
+            line += 1;
+            let first_line = line;
+            for code in text.lines() {
+                // IPython magics and shell escapes are not Python
+                if code.trim_start().starts_with(['%', '!']) {
+                    source.push_str("# ");
+                }
+                source.push_str(code);
+                source.push('\n');
+                line += 1;
+            }
+            cells.push(CellSpan {
+                index: i + 1,
+                first_line,
+                line_count: line - first_line,
+            });
+            source.push('\n');
+            line += 1;
+        }
+        Some(Self { source, cells })
+    }
+
+    /// Whether the notebook has no Python code.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The cell holding a script line, with the line's position in the cell.
+    pub fn cell_at(&self, line: usize) -> Option<NotebookCell> {
+        self.cells
+            .iter()
+            .find(|c| line >= c.first_line && line < c.first_line + c.line_count)
+            .map(|c| NotebookCell {
+                index: c.index,
+                line: line - c.first_line + 1,
+            })
+    }
+}
+
+/// Whether a code cell holds Python, i.e. does not start with a cell magic
+/// switching to another language (`%%bash`, `%%html`, ...).
+fn is_python_cell(text: &str) -> bool {
+    let first = text.trim_start().lines().next().unwrap_or_default();
+    match first.strip_prefix("%%") {
+        Some(magic) => {
+            let name = magic.split_whitespace().next().unwrap_or_default();
+            PYTHON_CELL_MAGICS.contains(&name)
+        }
+        None => true,
+    }
+}
+
+/// Whether a file is a notebook.
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "ipynb")
+}
+
+/// Read a source file the way entity line numbers see it: notebooks are
+/// rendered as their script, other files are returned as-is.
+pub fn read_source(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    if is_notebook(path)
+        && let Some(script) = Script::parse(&content)
+    {
+        return Some(script.source);
+    }
+    Some(content)
+}
@@ -8,7 +8,7 @@ use crate::graph::RPGraph;
 use anyhow::{Context, Result};
 use semver::Version;
 
-const CURRENT_VERSION: &str = "2.2.0";
+const CURRENT_VERSION: &str = "2.3.0";
 
 /// Validate an RPGraph's schema version using semver compatibility.
 ///
@@ -44,6 +44,10 @@ pub fn migrate(graph: &mut RPGraph) -> Result<()> {
         if found < Version::new(2, 2, 0) {
             migrate_normalize_entity_ids(graph);
         }
+        // v2.3.0: same-named entities (overloads, property accessors) get discriminated IDs
+        if found < Version::new(2, 3, 0) {
+            migrate_dedupe_colliding_ids(graph);
+        }
         graph.version = CURRENT_VERSION.to_string();
     }
 
@@ -116,6 +120,20 @@ fn migrate_normalize_entity_ids(graph: &mut RPGraph) {
     }
 }
 
+/// Collapse repeated IDs left behind by pre-2.3.0 ID collisions.
+///
+/// Older parsers gave every overload of a symbol the same ID, so `entities`
+/// kept only the last definition while `file_index` listed the ID once per
+/// overload. The surviving entity keeps the plain ID, which the current scheme
+/// assigns to the first definition; the remaining overloads are picked up as
+/// new `#<arity>` entities on the next update.
+fn migrate_dedupe_colliding_ids(graph: &mut RPGraph) {
+    for ids in graph.file_index.values_mut() {
+        let mut seen = std::collections::HashSet::new();
+        ids.retain(|id| seen.insert(id.clone()));
+    }
+}
+
 /// Serialize an RPGraph to a pretty-printed JSON string.
 ///
 /// Edges are sorted by (source, target, kind) for deterministic output,
@@ -208,10 +226,35 @@ mod tests {
         assert!(validate_version(&graph).is_err());
     }
 
+    #[test]
+    fn test_migrate_dedupes_colliding_ids() {
+        use std::path::PathBuf;
+
+        let mut graph = graph_with_version("2.2.0");
+        let id = "src/Foo.java:Foo::Foo".to_string();
+        graph.file_index.insert(
+            PathBuf::from("src/Foo.java"),
+            vec![
+                "src/Foo.java:Foo".to_string(),
+                id.clone(),
+                id.clone(),
+                id.clone(),
+            ],
+        );
+
+        migrate(&mut graph).unwrap();
+
+        assert_eq!(
+            graph.file_index[&PathBuf::from("src/Foo.java")],
+            vec!["src/Foo.java:Foo".to_string(), id]
+        );
+        assert_eq!(graph.version, CURRENT_VERSION);
+    }
+
     #[test]
     fn test_migrate_normalizes_backslash_ids() {
         use crate::graph::{
-            DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, HierarchyNode,
+            DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, EntityMetadata, HierarchyNode,
         };
         use std::path::PathBuf;
 
@@ -234,6 +277,8 @@ mod tests {
                 hierarchy_path: String::new(),
                 deps: EntityDeps::default(),
                 signature: None,
+                metadata: EntityMetadata::default(),
+                signals: None,
             },
         );
 
@@ -247,6 +292,7 @@ mod tests {
             source: old_id.clone(),
             target: "other:target".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         // hierarchy references the old ID
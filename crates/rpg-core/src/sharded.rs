@@ -0,0 +1,460 @@
+//! Sharded graph layout (`.rpg/graph/`), one JSON shard per source file.
+//!
+//! ```text
+//! .rpg/graph/
+//!   manifest.json        schema version and layout format only
+//!   metadata.json        base commit, languages, paradigms, repo summary
+//!   edges.json           edges whose source is not a code entity
+//!   externals.json       third-party packages (only when there are any)
+//!   files/<path>.json    entities of one source file + the edges they originate
+//! ```
+//!
+//! Two branches that touch different source files change different shards, so
+//! git merges the graph line-by-line instead of conflicting on one huge file.
+//! Nothing that changes on every save is stored: metadata counts are recomputed
+//! on load, timestamps come from the shards' modification times, and the
+//! hierarchy (with its containment edges) is rebuilt from the entities'
+//! `hierarchy_path`s. Shards whose content is unchanged are not rewritten.
+
+use crate::graph::{DependencyEdge, EdgeKind, Entity, GraphMetadata, RPGraph};
+use crate::schema;
+use crate::storage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const METADATA_FILE: &str = "metadata.json";
+const EDGES_FILE: &str = "edges.json";
+const EXTERNALS_FILE: &str = "externals.json";
+const FILES_DIR: &str = "files";
+const SHARD_EXT: &str = "json";
+
+/// Value of the manifest's `format` field.
+const FORMAT: &str = "sharded";
+
+/// `manifest.json`: only fields that stay the same from save to save.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: String,
+    format: String,
+}
+
+/// `metadata.json`: the graph-level fields that cannot be derived on load.
+#[derive(Debug, Serialize, Deserialize)]
+struct Metadata {
+    base_commit: Option<String>,
+    language: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    languages: Vec<String>,
+    #[serde(default)]
+    semantic_hierarchy: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    repo_summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    paradigms: Vec<String>,
+}
+
+/// One source file's entities (in `file_index` order) and outgoing edges.
+#[derive(Debug, Serialize, Deserialize)]
+struct FileShard {
+    file: PathBuf,
+    #[serde(default)]
+    entities: Vec<Entity>,
+    #[serde(default)]
+    edges: Vec<DependencyEdge>,
+}
+
+fn to_pretty<T: Serialize>(value: &T, what: &str) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(value)
+        .with_context(|| format!("failed to serialize {}", what))?;
+    json.push('\n');
+    Ok(json)
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Oldest and newest modification times seen while loading, which stand in
+/// for the graph's `created_at` and `updated_at`.
+#[derive(Default)]
+struct Timestamps(Option<(DateTime<Utc>, DateTime<Utc>)>);
+
+impl Timestamps {
+    fn observe(&mut self, path: &Path) {
+        let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) else {
+            return;
+        };
+        let time = DateTime::<Utc>::from(modified);
+        self.0 = Some(match self.0 {
+            Some((oldest, newest)) => (oldest.min(time), newest.max(time)),
+            None => (time, time),
+        });
+    }
+}
+
+/// Relative shard path for a source file: `files/<source path>.json`.
+/// Non-normal components (`..`, roots, drive prefixes) are flattened to `_`
+/// so shards always stay inside the shard directory.
+fn shard_path(file: &Path) -> PathBuf {
+    let mut rel = PathBuf::from(FILES_DIR);
+    for component in file.components() {
+        match component {
+            Component::Normal(part) => rel.push(part),
+            Component::CurDir => {}
+            _ => rel.push("_"),
+        }
+    }
+    let mut name = rel.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(SHARD_EXT);
+    rel.set_file_name(name);
+    rel
+}
+
+/// Render every file of the sharded layout, keyed by path relative to the shard dir.
+fn render(graph: &RPGraph) -> Result<BTreeMap<PathBuf, String>> {
+    let mut shards: BTreeMap<&Path, FileShard> = BTreeMap::new();
+    let mut entity_file: HashMap<&str, &Path> = HashMap::new();
+
+    for (file, ids) in &graph.file_index {
+        let shard = shards.entry(file.as_path()).or_insert_with(|| FileShard {
+            file: file.clone(),
+            entities: Vec::new(),
+            edges: Vec::new(),
+        });
+        for id in ids {
+            if let Some(entity) = graph.entities.get(id)
+                && entity_file.insert(id.as_str(), file.as_path()).is_none()
+            {
+                shard.entities.push(entity.clone());
+            }
+        }
+    }
+    // Entities missing from file_index are stored with their own file
+    for (id, entity) in &graph.entities {
+        if entity_file.contains_key(id.as_str()) {
+            continue;
+        }
+        entity_file.insert(id.as_str(), entity.file.as_path());
+        shards
+            .entry(entity.file.as_path())
+            .or_insert_with(|| FileShard {
+                file: entity.file.clone(),
+                entities: Vec::new(),
+                edges: Vec::new(),
+            })
+            .entities
+            .push(entity.clone());
+    }
+
+    let mut loose_edges = Vec::new();
+    for edge in graph.edges.iter().filter(|e| e.kind != EdgeKind::Contains) {
+        match entity_file.get(edge.source.as_str()) {
+            Some(file) => shards
+                .get_mut(file)
+                .expect("every indexed entity has a shard")
+                .edges
+                .push(edge.clone()),
+            None => loose_edges.push(edge.clone()),
+        }
+    }
+    loose_edges.sort();
+
+    let mut out = BTreeMap::new();
+    out.insert(
+        PathBuf::from(MANIFEST_FILE),
+        to_pretty(
+            &Manifest {
+                version: graph.version.clone(),
+                format: FORMAT.to_string(),
+            },
+            "shard manifest",
+        )?,
+    );
+    let metadata = &graph.metadata;
+    out.insert(
+        PathBuf::from(METADATA_FILE),
+        to_pretty(
+            &Metadata {
+                base_commit: graph.base_commit.clone(),
+                language: metadata.language.clone(),
+                languages: metadata.languages.clone(),
+                semantic_hierarchy: metadata.semantic_hierarchy,
+                repo_summary: metadata.repo_summary.clone(),
+                paradigms: metadata.paradigms.clone(),
+            },
+            "graph metadata",
+        )?,
+    );
+    out.insert(PathBuf::from(EDGES_FILE), to_pretty(&loose_edges, "edges")?);
+    if !graph.externals.is_empty() {
+        out.insert(
+            PathBuf::from(EXTERNALS_FILE),
+            to_pretty(&graph.externals, "externals")?,
+        );
+    }
+
+    for (file, mut shard) in shards {
+        shard.edges.sort();
+        let rendered = to_pretty(&shard, &format!("shard for {}", file.display()))?;
+        out.insert(shard_path(file), rendered);
+    }
+
+    Ok(out)
+}
+
+/// Write the graph as shards under `dir`, rewriting only shards whose content
+/// changed and removing shards for files that no longer exist in the graph.
+pub fn save(dir: &Path, graph: &RPGraph) -> Result<()> {
+    let rendered = render(graph)?;
+
+    for (rel, content) in &rendered {
+        let path = dir.join(rel);
+        if fs::read_to_string(&path).is_ok_and(|existing| existing == *content) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        storage::write_atomic(&path, content.as_bytes())?;
+    }
+
+    let keep: BTreeSet<PathBuf> = rendered.into_keys().map(|rel| dir.join(rel)).collect();
+    prune_stale_shards(&dir.join(FILES_DIR), &keep)?;
+    let externals = dir.join(EXTERNALS_FILE);
+    if !keep.contains(&externals) && externals.exists() {
+        fs::remove_file(&externals)
+            .with_context(|| format!("failed to remove {}", externals.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Read one file of the sharded layout as a partial graph, so the merge driver
+/// can merge a single shard. `rel` is the file's path relative to the shard
+/// directory; an empty file (git's ancestor for an add/add merge) reads as an
+/// empty graph.
+pub fn read_shard(rel: &Path, path: &Path) -> Result<RPGraph> {
+    let mut graph = RPGraph::new("");
+    if fs::metadata(path)
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .len()
+        == 0
+    {
+        return Ok(graph);
+    }
+    if rel == Path::new(MANIFEST_FILE) {
+        let manifest: Manifest = read_json(path)?;
+        graph.version = manifest.version;
+    } else if rel == Path::new(METADATA_FILE) {
+        let metadata: Metadata = read_json(path)?;
+        graph.base_commit = metadata.base_commit;
+        graph.metadata.language = metadata.language;
+        graph.metadata.languages = metadata.languages;
+        graph.metadata.semantic_hierarchy = metadata.semantic_hierarchy;
+        graph.metadata.repo_summary = metadata.repo_summary;
+        graph.metadata.paradigms = metadata.paradigms;
+    } else if rel == Path::new(EDGES_FILE) {
+        graph.edges = read_json(path)?;
+    } else if rel == Path::new(EXTERNALS_FILE) {
+        graph.externals = read_json(path)?;
+    } else if rel.starts_with(FILES_DIR) {
+        let shard: FileShard = read_json(path)?;
+        let ids = graph.file_index.entry(shard.file).or_default();
+        for entity in shard.entities {
+            ids.push(entity.id.clone());
+            graph.entities.insert(entity.id.clone(), entity);
+        }
+        graph.edges = shard.edges;
+    } else {
+        anyhow::bail!("{} is not part of the sharded layout", rel.display());
+    }
+    Ok(graph)
+}
+
+/// Write the file `rel` of the sharded layout, rendered from `graph`, to `path`.
+/// The counterpart of [`read_shard`] for the merge driver.
+pub fn write_shard(rel: &Path, path: &Path, graph: &RPGraph) -> Result<()> {
+    let content = match render(graph)?.remove(rel) {
+        Some(content) => content,
+        None if rel == Path::new(EXTERNALS_FILE) => to_pretty(&graph.externals, "externals")?,
+        // Every entity of the file was deleted; loads skip the empty shard and
+        // the next save prunes it
+        None if rel.starts_with(FILES_DIR) => {
+            let file = rel
+                .strip_prefix(FILES_DIR)
+                .unwrap_or(rel)
+                .with_extension("");
+            to_pretty(
+                &FileShard {
+                    file,
+                    entities: Vec::new(),
+                    edges: Vec::new(),
+                },
+                "empty shard",
+            )?
+        }
+        None => anyhow::bail!("{} is not part of the sharded layout", rel.display()),
+    };
+    storage::write_atomic(path, content.as_bytes())
+}
+
+/// Remove shard files not in `keep`, then any directories left empty.
+fn prune_stale_shards(dir: &Path, keep: &BTreeSet<PathBuf>) -> Result<()> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            prune_stale_shards(&path, keep)?;
+            if fs::read_dir(&path)?.next().is_none() {
+                fs::remove_dir(&path)
+                    .with_context(|| format!("failed to remove {}", path.display()))?;
+            }
+        } else if !keep.contains(&path) {
+            fs::remove_file(&path)
+                .with_context(|| format!("failed to remove stale shard {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn collect_shards(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_shards(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == SHARD_EXT) {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Load a sharded graph from `dir`, applying schema migrations.
+///
+/// Counts, timestamps, the hierarchy and containment edges are derived here
+/// rather than read, so independently merged shards always load consistently.
+pub fn load(dir: &Path) -> Result<RPGraph> {
+    let mut timestamps = Timestamps::default();
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest: Manifest = read_json(&manifest_path)?;
+    if manifest.format != FORMAT {
+        anyhow::bail!(
+            "{} has unknown format {:?} (expected {:?})",
+            manifest_path.display(),
+            manifest.format,
+            FORMAT
+        );
+    }
+    timestamps.observe(&manifest_path);
+    let metadata_path = dir.join(METADATA_FILE);
+    let metadata: Metadata = read_json(&metadata_path)?;
+    timestamps.observe(&metadata_path);
+    let mut edges: Vec<DependencyEdge> = if dir.join(EDGES_FILE).exists() {
+        timestamps.observe(&dir.join(EDGES_FILE));
+        read_json(&dir.join(EDGES_FILE))?
+    } else {
+        Vec::new()
+    };
+
+    let externals = if dir.join(EXTERNALS_FILE).exists() {
+        timestamps.observe(&dir.join(EXTERNALS_FILE));
+        read_json(&dir.join(EXTERNALS_FILE))?
+    } else {
+        BTreeMap::new()
+    };
+
+    let mut shard_paths = Vec::new();
+    collect_shards(&dir.join(FILES_DIR), &mut shard_paths)?;
+    shard_paths.sort();
+
+    let mut entities = BTreeMap::new();
+    let mut file_index: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+    for path in shard_paths {
+        let shard: FileShard = read_json(&path)?;
+        timestamps.observe(&path);
+        // A merge can leave a shard whose entities were all deleted
+        if shard.entities.is_empty() && shard.edges.is_empty() {
+            continue;
+        }
+        let ids = file_index.entry(shard.file).or_default();
+        for entity in shard.entities {
+            ids.push(entity.id.clone());
+            entities.insert(entity.id.clone(), entity);
+        }
+        edges.extend(shard.edges);
+    }
+    edges.sort();
+    edges.dedup();
+
+    let now = Utc::now();
+    let (created_at, updated_at) = timestamps.0.unwrap_or((now, now));
+    let mut graph = RPGraph {
+        version: manifest.version,
+        created_at,
+        updated_at,
+        base_commit: metadata.base_commit,
+        metadata: GraphMetadata {
+            language: metadata.language,
+            languages: metadata.languages,
+            total_files: 0,
+            total_entities: 0,
+            functional_areas: 0,
+            total_edges: 0,
+            dependency_edges: 0,
+            containment_edges: 0,
+            lifted_entities: 0,
+            data_flow_edges: 0,
+            test_edges: 0,
+            semantic_hierarchy: metadata.semantic_hierarchy,
+            repo_summary: metadata.repo_summary,
+            paradigms: metadata.paradigms,
+        },
+        hierarchy: BTreeMap::new(),
+        entities,
+        edges,
+        file_index,
+        externals,
+        edge_index: HashMap::new(),
+        hierarchy_node_index: HashMap::new(),
+    };
+
+    schema::validate_version(&graph)?;
+    schema::migrate(&mut graph)?;
+    graph.rebuild_hierarchy_from_paths();
+    graph.materialize_containment_edges();
+    graph.recount_metadata();
+    graph.rebuild_edge_index();
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_path_mirrors_source_path() {
+        assert_eq!(
+            shard_path(Path::new("src/auth/login.rs")),
+            PathBuf::from("files/src/auth/login.rs.json")
+        );
+        assert_eq!(
+            shard_path(Path::new("../outside.py")),
+            PathBuf::from("files/_/outside.py.json")
+        );
+    }
+}
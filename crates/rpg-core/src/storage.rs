@@ -1,8 +1,21 @@
 //! Read/write RPG graph files from disk.
+//!
+//! Writes are crash-safe: every file is written to a temporary sibling, synced, and
+//! renamed into place, so the JSON and binary formats are replaced in one step. The
+//! sharded format rewrites many files, so a crash can leave a mix of old and new
+//! shards; `.rpg/graph.saving` marks a sharded save in progress, and [`load`] treats
+//! a leftover marker as an unreadable graph. Writers hold an exclusive advisory lock
+//! on `.rpg/graph.lock` and readers a shared one, so the CLI, MCP server and
+//! pre-commit hook never observe each other's partial writes. If the primary graph
+//! is unreadable (e.g. truncated by an older version or an interrupted sharded
+//! save), [`load`] falls back to `graph.backup.json`.
 
-use crate::config::StorageConfig;
+use crate::binary::{self, BinaryGraph};
+use crate::config::{StorageConfig, StorageFormat};
 use crate::graph::RPGraph;
+use crate::history;
 use crate::schema;
+use crate::sharded;
 use anyhow::{Context, Result};
 use std::fs;
 use std::io::Read as _;
@@ -10,7 +23,14 @@ use std::path::{Path, PathBuf};
 
 const RPG_DIR: &str = ".rpg";
 const RPG_FILE: &str = "graph.json";
+const RPG_BINARY_FILE: &str = "graph.rpgb";
+const RPG_SHARD_DIR: &str = "graph";
 const RPG_BACKUP_FILE: &str = "graph.backup.json";
+const RPG_LOCK_FILE: &str = "graph.lock";
+const RPG_SAVING_MARKER: &str = "graph.saving";
+
+/// Entries of `.rpg/.gitignore`: local config and machine-specific state.
+const RPG_GITIGNORE: &str = "config.toml\nmodels/\nembeddings.bin\nembeddings.hnsw\nembeddings.meta.json\nsearch_index.json\npending_routing.json\nmerge_conflicts.json\ngraph.lock\ngraph.saving\nhistory/\n*.tmp\n";
 
 /// Get the path to the RPG directory for a given project root.
 pub fn rpg_dir(project_root: &Path) -> PathBuf {
@@ -22,9 +42,33 @@ pub fn rpg_file(project_root: &Path) -> PathBuf {
     rpg_dir(project_root).join(RPG_FILE)
 }
 
-/// Check if an RPG exists for the given project root.
+/// Get the path to the binary RPG graph file for a given project root.
+pub fn rpg_binary_file(project_root: &Path) -> PathBuf {
+    rpg_dir(project_root).join(RPG_BINARY_FILE)
+}
+
+/// Get the path to the sharded graph directory for a given project root.
+pub fn rpg_shard_dir(project_root: &Path) -> PathBuf {
+    rpg_dir(project_root).join(RPG_SHARD_DIR)
+}
+
+/// Check if an RPG exists for the given project root (in any format).
 pub fn rpg_exists(project_root: &Path) -> bool {
-    rpg_file(project_root).exists()
+    stored_format(project_root).is_some()
+}
+
+/// The format of the graph currently on disk, if any.
+/// Precedence when several are present: binary, sharded, JSON.
+pub fn stored_format(project_root: &Path) -> Option<StorageFormat> {
+    if rpg_binary_file(project_root).exists() {
+        Some(StorageFormat::Binary)
+    } else if rpg_shard_dir(project_root).is_dir() {
+        Some(StorageFormat::Sharded)
+    } else if rpg_file(project_root).exists() {
+        Some(StorageFormat::Json)
+    } else {
+        None
+    }
 }
 
 /// Get the path to the RPG backup file for a given project root.
@@ -32,23 +76,88 @@ pub fn rpg_backup_file(project_root: &Path) -> PathBuf {
     rpg_dir(project_root).join(RPG_BACKUP_FILE)
 }
 
+/// Get the path to the advisory lock file for a given project root.
+pub fn rpg_lock_file(project_root: &Path) -> PathBuf {
+    rpg_dir(project_root).join(RPG_LOCK_FILE)
+}
+
+/// Get the path to the marker of an in-progress sharded save.
+pub fn rpg_saving_marker(project_root: &Path) -> PathBuf {
+    rpg_dir(project_root).join(RPG_SAVING_MARKER)
+}
+
+/// Advisory lock on `.rpg/graph.lock`, released when dropped.
+pub struct StorageLock {
+    _file: fs::File,
+}
+
+/// Block until the graph lock is held: exclusive for writers, shared for readers.
+/// Returns `None` when the `.rpg` directory does not exist (nothing to protect).
+pub fn acquire_lock(project_root: &Path, exclusive: bool) -> Result<Option<StorageLock>> {
+    if !rpg_dir(project_root).is_dir() {
+        return Ok(None);
+    }
+    let path = rpg_lock_file(project_root);
+    let file = match fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+    {
+        Ok(file) => file,
+        // Read-only checkouts can still be read without a lock
+        Err(_) if !exclusive => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to open {}", path.display())),
+    };
+    if exclusive {
+        file.lock()
+    } else {
+        file.lock_shared()
+    }
+    .with_context(|| format!("failed to lock {}", path.display()))?;
+    Ok(Some(StorageLock { _file: file }))
+}
+
+/// Write `bytes` to `path` atomically: write a temporary sibling, sync it, then
+/// rename it over the target. A crash leaves either the old or the new file.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    use std::io::Write as _;
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("failed to write {}", path.display()));
+    }
+    Ok(())
+}
+
 /// Create a backup of the current graph before destructive operations.
 /// Returns the backup path if created, or None if no graph exists.
+///
+/// The backup is always plain JSON so it stays readable and restorable by hand.
+/// An unreadable graph is never backed up, so a good backup is not overwritten
+/// by a corrupt one.
 pub fn create_backup(project_root: &Path) -> Result<Option<PathBuf>> {
     if !rpg_exists(project_root) {
         return Ok(None);
     }
+    let graph = {
+        let _lock = acquire_lock(project_root, false)?;
+        load_primary(project_root).context("refusing to back up an unreadable graph")?
+    };
 
-    let source = rpg_file(project_root);
     let dest = rpg_backup_file(project_root);
-
-    fs::copy(&source, &dest).with_context(|| {
-        format!(
-            "failed to backup {} to {}",
-            source.display(),
-            dest.display()
-        )
-    })?;
+    let _lock = acquire_lock(project_root, true)?;
+    write_atomic(&dest, schema::to_json(&graph)?.as_bytes())?;
 
     Ok(Some(dest))
 }
@@ -56,14 +165,84 @@ pub fn create_backup(project_root: &Path) -> Result<Option<PathBuf>> {
 /// Zstd magic bytes: 0x28 0xB5 0x2F 0xFD.
 const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
 
-/// Load an RPG from disk.
-/// Automatically detects zstd-compressed graph files by magic bytes.
+/// Load an RPG from disk in whichever format is stored (see [`stored_format`]).
+/// `graph.json` is checked for zstd compression by magic bytes.
+///
+/// If the stored graph exists but cannot be read, the backup from
+/// [`create_backup`] is loaded instead (with a warning) when one exists.
 pub fn load(project_root: &Path) -> Result<RPGraph> {
-    let path = rpg_file(project_root);
+    let _lock = acquire_lock(project_root, false)?;
+    let err = match load_primary(project_root) {
+        Ok(graph) => return Ok(graph),
+        Err(err) => err,
+    };
+
+    let backup = rpg_backup_file(project_root);
+    if rpg_exists(project_root) && backup.exists() {
+        match read_graph_file(&backup) {
+            Ok((graph, _)) => {
+                tracing::warn!(
+                    "stored graph is unreadable ({:#}); recovered from {}",
+                    err,
+                    backup.display()
+                );
+                return Ok(graph);
+            }
+            Err(backup_err) => {
+                return Err(err.context(format!(
+                    "backup {} is unreadable too: {:#}",
+                    backup.display(),
+                    backup_err
+                )));
+            }
+        }
+    }
+    Err(err)
+}
+
+/// Load the stored graph without lock or backup fallback.
+fn load_primary(project_root: &Path) -> Result<RPGraph> {
+    match stored_format(project_root) {
+        Some(StorageFormat::Binary) => return open_binary(project_root)?.load_graph(),
+        Some(StorageFormat::Sharded) => {
+            // Loads hold the shared lock, so a marker means a save was interrupted
+            if rpg_saving_marker(project_root).exists() {
+                anyhow::bail!(
+                    "an earlier save of {} did not finish; its shards may be inconsistent",
+                    rpg_shard_dir(project_root).display()
+                );
+            }
+            return sharded::load(&rpg_shard_dir(project_root));
+        }
+        Some(StorageFormat::Json) | None => {}
+    }
+
+    read_graph_file(&rpg_file(project_root)).map(|(graph, _)| graph)
+}
+
+/// Read a standalone graph file (JSON, zstd JSON, or binary, detected by magic
+/// bytes). Also returns the storage settings that reproduce its encoding, so
+/// callers such as the merge driver can write a result back in kind.
+pub fn read_graph_file(path: &Path) -> Result<(RPGraph, StorageConfig)> {
     let raw =
-        fs::read(&path).with_context(|| format!("failed to read RPG from {}", path.display()))?;
+        fs::read(path).with_context(|| format!("failed to read RPG from {}", path.display()))?;
+
+    if binary::is_binary(&raw) {
+        let graph =
+            binary::decode(&raw).with_context(|| format!("failed to decode {}", path.display()))?;
+        let compressed = binary::is_compressed(&raw);
+        return Ok((
+            graph,
+            StorageConfig {
+                format: StorageFormat::Binary,
+                compress: compressed,
+                ..Default::default()
+            },
+        ));
+    }
 
-    let json = if raw.len() >= 4 && raw[..4] == ZSTD_MAGIC {
+    let compressed = raw.len() >= 4 && raw[..4] == ZSTD_MAGIC;
+    let json = if compressed {
         // Decompress zstd
         let mut decoder = zstd::Decoder::new(&raw[..]).context("failed to init zstd decoder")?;
         let mut decompressed = String::new();
@@ -81,18 +260,66 @@ pub fn load(project_root: &Path) -> Result<RPGraph> {
     graph.rebuild_edge_index();
     graph.rebuild_hierarchy_index();
 
-    Ok(graph)
+    Ok((
+        graph,
+        StorageConfig {
+            format: StorageFormat::Json,
+            compress: compressed,
+            ..Default::default()
+        },
+    ))
+}
+
+/// Write a graph to a standalone file in the JSON or binary format.
+/// The sharded format is a directory layout and cannot be written this way.
+pub fn write_graph_file(
+    path: &Path,
+    graph: &RPGraph,
+    storage_config: &StorageConfig,
+) -> Result<()> {
+    match storage_config.format {
+        StorageFormat::Json => {
+            let json = schema::to_json(graph)?;
+            if storage_config.compress {
+                let compressed = zstd::encode_all(json.as_bytes(), 3)
+                    .context("failed to compress graph.json with zstd")?;
+                write_atomic(path, &compressed)?;
+            } else {
+                write_atomic(path, json.as_bytes())?;
+            }
+        }
+        // Also required for mmap safety: readers may hold a live mapping of the
+        // old file, which must never be truncated underneath them
+        StorageFormat::Binary => {
+            write_atomic(path, &binary::encode(graph, storage_config.compress)?)?;
+        }
+        StorageFormat::Sharded => {
+            anyhow::bail!("the sharded format is a directory; use save_with_config")
+        }
+    }
+    Ok(())
+}
+
+/// Memory-map the binary graph for lazy access (header or single entities)
+/// without decoding the whole graph.
+pub fn open_binary(project_root: &Path) -> Result<BinaryGraph> {
+    BinaryGraph::open(&rpg_binary_file(project_root))
 }
 
 /// Save an RPG to disk. Also creates `.rpg/.gitignore` and
 /// `.rpg/README.md` on first save.
 ///
-/// Pass `storage_config` to enable compression. When `None`, saves uncompressed.
+/// Always writes uncompressed JSON; use [`save_with_config`] to honor the
+/// `[storage]` settings from `.rpg/config.toml`.
 pub fn save(project_root: &Path, graph: &RPGraph) -> Result<()> {
     save_with_config(project_root, graph, &StorageConfig::default())
 }
 
 /// Save with explicit storage configuration.
+///
+/// Writing one format removes the other formats' graph files so that loads
+/// never pick up a stale copy. A graph saved at a new `base_commit` is also recorded
+/// in the revision history (see [`crate::history`]).
 pub fn save_with_config(
     project_root: &Path,
     graph: &RPGraph,
@@ -101,26 +328,29 @@ pub fn save_with_config(
     let dir = rpg_dir(project_root);
     fs::create_dir_all(&dir)
         .with_context(|| format!("failed to create RPG directory {}", dir.display()))?;
+    let _lock = acquire_lock(project_root, true)?;
 
-    let json = schema::to_json(graph)?;
+    match storage_config.format {
+        StorageFormat::Json => write_graph_file(&rpg_file(project_root), graph, storage_config)?,
+        StorageFormat::Binary => {
+            write_graph_file(&rpg_binary_file(project_root), graph, storage_config)?;
+        }
+        StorageFormat::Sharded => {
+            let marker = rpg_saving_marker(project_root);
+            write_atomic(&marker, b"")?;
+            sharded::save(&rpg_shard_dir(project_root), graph)?;
+            remove_if_exists(&marker)?;
+        }
+    }
+    remove_other_formats(project_root, storage_config.format)?;
 
-    if storage_config.compress {
-        let compressed = zstd::encode_all(json.as_bytes(), 3)
-            .context("failed to compress graph.json with zstd")?;
-        fs::write(rpg_file(project_root), compressed)
-            .with_context(|| "failed to write graph.json")?;
-    } else {
-        fs::write(rpg_file(project_root), json).with_context(|| "failed to write graph.json")?;
+    // History is a convenience; a failure to record it must not fail the save
+    if let Err(e) = history::record(project_root, graph, storage_config.history_limit) {
+        tracing::warn!("failed to record graph revision history: {:#}", e);
     }
 
     // Create .rpg/.gitignore (keeps config local)
-    let inner_gitignore = dir.join(".gitignore");
-    if !inner_gitignore.exists() {
-        let _ = fs::write(
-            &inner_gitignore,
-            "config.toml\nmodels/\nembeddings.bin\nembeddings.meta.json\npending_routing.json\n",
-        );
-    }
+    let _ = write_gitignore(&dir);
 
     // Create README on first save so people discovering .rpg/ know what it is
     let readme = dir.join("README.md");
@@ -131,20 +361,72 @@ pub fn save_with_config(
     Ok(())
 }
 
+/// Rewrite the stored graph in the given storage configuration
+/// (e.g. JSON → binary or back). Returns the converted graph.
+pub fn convert(project_root: &Path, storage_config: &StorageConfig) -> Result<RPGraph> {
+    let graph = load(project_root)?;
+    save_with_config(project_root, &graph, storage_config)?;
+    Ok(graph)
+}
+
+fn remove_other_formats(project_root: &Path, keep: StorageFormat) -> Result<()> {
+    if keep != StorageFormat::Json {
+        remove_if_exists(&rpg_file(project_root))?;
+    }
+    if keep != StorageFormat::Binary {
+        remove_if_exists(&rpg_binary_file(project_root))?;
+    }
+    let shard_dir = rpg_shard_dir(project_root);
+    if keep != StorageFormat::Sharded && shard_dir.is_dir() {
+        fs::remove_dir_all(&shard_dir)
+            .with_context(|| format!("failed to remove {}", shard_dir.display()))?;
+    }
+    if keep != StorageFormat::Sharded {
+        remove_if_exists(&rpg_saving_marker(project_root))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("failed to remove {}", path.display())),
+    }
+}
+
 /// Ensure the .rpg directory has its internal .gitignore.
 /// The graph itself is intentionally committed — only local config is ignored.
+/// Returns whether the file already existed.
 pub fn ensure_gitignore(project_root: &Path) -> Result<bool> {
     let dir = rpg_dir(project_root);
     fs::create_dir_all(&dir)?;
-    let inner_gitignore = dir.join(".gitignore");
-    if inner_gitignore.exists() {
-        return Ok(true);
-    }
-    fs::write(
-        &inner_gitignore,
-        "config.toml\nmodels/\nembeddings.bin\nembeddings.meta.json\npending_routing.json\n",
-    )?;
-    Ok(false)
+    write_gitignore(&dir)
+}
+
+/// Create `.rpg/.gitignore`, or append entries missing from an older one.
+fn write_gitignore(dir: &Path) -> Result<bool> {
+    let path = dir.join(".gitignore");
+    let Ok(existing) = fs::read_to_string(&path) else {
+        fs::write(&path, RPG_GITIGNORE)?;
+        return Ok(false);
+    };
+    let missing: Vec<&str> = RPG_GITIGNORE
+        .lines()
+        .filter(|entry| !existing.lines().any(|line| line.trim() == *entry))
+        .collect();
+    if !missing.is_empty() {
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        for entry in missing {
+            content.push_str(entry);
+            content.push('\n');
+        }
+        fs::write(&path, content)?;
+    }
+    Ok(true)
 }
 
 /// Get the path to the pending routing state file.
@@ -0,0 +1,941 @@
+//! Graph integrity checks with optional auto-repair.
+//!
+//! [`validate`] checks every structural invariant the encoder maintains:
+//!
+//! - edges point at existing entities or hierarchy nodes and are not duplicated
+//! - reverse deps (`invoked_by`, ...) and `data_flows_to` mirror the edge list
+//! - `file_index` lists every entity exactly once, under its own file
+//! - hierarchy membership agrees with each entity's `hierarchy_path`
+//! - hierarchy node IDs, containment edges and the performance indexes are current
+//! - metadata counts match what [`RPGraph::recount_metadata`] would compute
+//!
+//! [`repair`] fixes everything derivable from the authoritative state: the edge
+//! list for dependency mirrors, entity `file`/`hierarchy_path` for the file index
+//! and hierarchy, and the hierarchy for containment edges.
+
+use crate::graph::{DependencyEdge, EdgeKind, Entity, EntityKind, HierarchyNode, RPGraph};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+
+/// How serious a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Derived state is out of date; queries still work but may be imprecise.
+    Warning,
+    /// The graph references something that does not exist or disagrees with itself.
+    Error,
+}
+
+/// A single integrity violation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding {
+    /// An edge endpoint is neither an entity, a hierarchy node nor an external package.
+    DanglingEdge {
+        edge: DependencyEdge,
+        missing: String,
+    },
+    /// The same edge appears more than once.
+    DuplicateEdge { edge: DependencyEdge, count: usize },
+    /// A dependency edge whose source declares no dependency of that kind.
+    UndeclaredEdge { edge: DependencyEdge },
+    /// An edge has no matching entry in the target's reverse deps.
+    MissingReverseDep {
+        entity: String,
+        edge_kind: EdgeKind,
+        source: String,
+    },
+    /// A reverse dep entry has no matching edge.
+    StaleReverseDep {
+        entity: String,
+        edge_kind: EdgeKind,
+        source: String,
+    },
+    /// A data flow edge has no matching entry in the source's `data_flows_to`.
+    MissingDataFlowTarget { entity: String, target: String },
+    /// A `data_flows_to` entry has no matching data flow edge.
+    StaleDataFlowTarget { entity: String, target: String },
+    /// A `file_index` entry names an entity that does not exist.
+    FileIndexMissingEntity { file: PathBuf, entity: String },
+    /// A `file_index` entry lists an entity under a file other than its own.
+    FileIndexWrongFile {
+        file: PathBuf,
+        entity: String,
+        actual: PathBuf,
+    },
+    /// A `file_index` entry lists the same entity twice.
+    FileIndexDuplicate { file: PathBuf, entity: String },
+    /// An entity is not listed under its file in `file_index`.
+    UnindexedEntity { entity: String, file: PathBuf },
+    /// A hierarchy node names an entity that does not exist.
+    HierarchyMissingEntity { node: String, entity: String },
+    /// An entity sits in a hierarchy node other than its `hierarchy_path`.
+    HierarchyPathMismatch {
+        entity: String,
+        node: String,
+        hierarchy_path: String,
+    },
+    /// An entity has a `hierarchy_path` but no hierarchy node lists it.
+    UnplacedEntity {
+        entity: String,
+        hierarchy_path: String,
+    },
+    /// An entity is listed by more than one hierarchy node (or twice by one).
+    DuplicateHierarchyMembership { entity: String, nodes: Vec<String> },
+    /// A hierarchy node's ID does not match its position in the tree.
+    HierarchyIdMismatch {
+        node: String,
+        id: String,
+        expected: String,
+    },
+    /// A containment edge implied by the hierarchy is missing.
+    MissingContainmentEdge { source: String, target: String },
+    /// A containment edge no longer matches the hierarchy.
+    StaleContainmentEdge { source: String, target: String },
+    /// The entity ID key differs from the entity's own `id`.
+    EntityIdMismatch { key: String, id: String },
+    /// A non-module entity ID lacks the `file:name` form.
+    MalformedEntityId { entity: String },
+    /// `edge_index` disagrees with the edge list for this many node IDs.
+    StaleEdgeIndex { stale_keys: usize },
+    /// `hierarchy_node_index` disagrees with the hierarchy for this many node IDs.
+    StaleHierarchyIndex { stale_keys: usize },
+    /// A metadata count differs from the graph's actual contents.
+    MetadataMismatch {
+        field: &'static str,
+        stored: usize,
+        actual: usize,
+    },
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::DuplicateEdge { .. }
+            | Self::UndeclaredEdge { .. }
+            | Self::MalformedEntityId { .. }
+            | Self::StaleEdgeIndex { .. }
+            | Self::StaleHierarchyIndex { .. }
+            | Self::MetadataMismatch { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Whether [`repair`] can fix this finding.
+    pub fn is_repairable(&self) -> bool {
+        !matches!(
+            self,
+            Self::UndeclaredEdge { .. }
+                | Self::EntityIdMismatch { .. }
+                | Self::MalformedEntityId { .. }
+        )
+    }
+}
+
+/// Field name of the reverse dep vector for an edge kind, for display.
+fn reverse_field(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Imports => "imported_by",
+        EdgeKind::Invokes => "invoked_by",
+        EdgeKind::Inherits => "inherited_by",
+        EdgeKind::Composes => "composed_by",
+        EdgeKind::Renders => "rendered_by",
+        EdgeKind::ReadsState => "state_read_by",
+        EdgeKind::WritesState => "state_written_by",
+        EdgeKind::Dispatches => "dispatched_by",
+        EdgeKind::DataFlow => "data_flows_from",
+        EdgeKind::Tests => "tested_by",
+        EdgeKind::Contains => "contained_by",
+    }
+}
+
+fn edge_display(edge: &DependencyEdge) -> String {
+    format!("{} -[{:?}]-> {}", edge.source, edge.kind, edge.target)
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DanglingEdge { edge, missing } => {
+                write!(
+                    f,
+                    "dangling edge {}: '{}' does not exist",
+                    edge_display(edge),
+                    missing
+                )
+            }
+            Self::DuplicateEdge { edge, count } => {
+                write!(f, "edge {} appears {} times", edge_display(edge), count)
+            }
+            Self::UndeclaredEdge { edge } => write!(
+                f,
+                "edge {} has no matching forward dependency on its source",
+                edge_display(edge)
+            ),
+            Self::MissingReverseDep {
+                entity,
+                edge_kind,
+                source,
+            } => write!(
+                f,
+                "{}.{} is missing '{}'",
+                entity,
+                reverse_field(*edge_kind),
+                source
+            ),
+            Self::StaleReverseDep {
+                entity,
+                edge_kind,
+                source,
+            } => write!(
+                f,
+                "{}.{} lists '{}' but no such edge exists",
+                entity,
+                reverse_field(*edge_kind),
+                source
+            ),
+            Self::MissingDataFlowTarget { entity, target } => {
+                write!(f, "{}.data_flows_to is missing '{}'", entity, target)
+            }
+            Self::StaleDataFlowTarget { entity, target } => write!(
+                f,
+                "{}.data_flows_to lists '{}' but no such edge exists",
+                entity, target
+            ),
+            Self::FileIndexMissingEntity { file, entity } => write!(
+                f,
+                "file_index references missing entity: {} in {}",
+                entity,
+                file.display()
+            ),
+            Self::FileIndexWrongFile {
+                file,
+                entity,
+                actual,
+            } => write!(
+                f,
+                "file_index lists {} under {} but it is defined in {}",
+                entity,
+                file.display(),
+                actual.display()
+            ),
+            Self::FileIndexDuplicate { file, entity } => write!(
+                f,
+                "file_index lists {} more than once under {}",
+                entity,
+                file.display()
+            ),
+            Self::UnindexedEntity { entity, file } => {
+                write!(
+                    f,
+                    "{} is missing from file_index[{}]",
+                    entity,
+                    file.display()
+                )
+            }
+            Self::HierarchyMissingEntity { node, entity } => write!(
+                f,
+                "hierarchy node '{}' references missing entity: {}",
+                node, entity
+            ),
+            Self::HierarchyPathMismatch {
+                entity,
+                node,
+                hierarchy_path,
+            } => write!(
+                f,
+                "{} is in hierarchy node '{}' but its hierarchy_path is '{}'",
+                entity, node, hierarchy_path
+            ),
+            Self::UnplacedEntity {
+                entity,
+                hierarchy_path,
+            } => write!(
+                f,
+                "{} has hierarchy_path '{}' but no hierarchy node lists it",
+                entity, hierarchy_path
+            ),
+            Self::DuplicateHierarchyMembership { entity, nodes } => write!(
+                f,
+                "{} is listed by multiple hierarchy nodes: {}",
+                entity,
+                nodes.join(", ")
+            ),
+            Self::HierarchyIdMismatch { node, id, expected } => write!(
+                f,
+                "hierarchy node '{}' has ID '{}', expected '{}'",
+                node, id, expected
+            ),
+            Self::MissingContainmentEdge { source, target } => {
+                write!(f, "missing containment edge {} -> {}", source, target)
+            }
+            Self::StaleContainmentEdge { source, target } => write!(
+                f,
+                "containment edge {} -> {} does not match the hierarchy",
+                source, target
+            ),
+            Self::EntityIdMismatch { key, id } => {
+                write!(f, "entity stored under '{}' has ID '{}'", key, id)
+            }
+            Self::MalformedEntityId { entity } => {
+                write!(f, "entity ID missing file:name format: {}", entity)
+            }
+            Self::StaleEdgeIndex { stale_keys } => {
+                write!(f, "edge_index is stale for {} node(s)", stale_keys)
+            }
+            Self::StaleHierarchyIndex { stale_keys } => {
+                write!(
+                    f,
+                    "hierarchy_node_index is stale for {} node(s)",
+                    stale_keys
+                )
+            }
+            Self::MetadataMismatch {
+                field,
+                stored,
+                actual,
+            } => write!(
+                f,
+                "metadata.{} is {} but the graph has {}",
+                field, stored, actual
+            ),
+        }
+    }
+}
+
+/// The result of [`validate`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// Whether no invariant is violated.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity() == severity)
+            .count()
+    }
+
+    pub fn repairable(&self) -> usize {
+        self.findings.iter().filter(|f| f.is_repairable()).count()
+    }
+}
+
+/// The result of [`repair`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RepairReport {
+    /// Findings present before the repair and gone after it.
+    pub fixed: Vec<Finding>,
+    /// Findings still present after the repair.
+    pub remaining: Vec<Finding>,
+}
+
+/// Expected dependency mirrors derived from the (non-dangling) edge list.
+#[derive(Default)]
+struct Mirrors {
+    /// (target entity, kind) → sources, for reverse dep vectors.
+    reverse: HashMap<(String, EdgeKind), BTreeSet<String>>,
+    /// source entity → targets, for `data_flows_to`.
+    data_flows_to: HashMap<String, BTreeSet<String>>,
+}
+
+fn node_exists(graph: &RPGraph, id: &str) -> bool {
+    graph.entities.contains_key(id)
+        || graph.find_hierarchy_node_by_id(id).is_some()
+        || graph.externals.contains_key(id)
+}
+
+fn mirrors(graph: &RPGraph) -> Mirrors {
+    let mut mirrors = Mirrors::default();
+    for edge in &graph.edges {
+        if edge.kind == EdgeKind::Contains
+            || !graph.entities.contains_key(&edge.source)
+            || !graph.entities.contains_key(&edge.target)
+        {
+            continue;
+        }
+        mirrors
+            .reverse
+            .entry((edge.target.clone(), edge.kind))
+            .or_default()
+            .insert(edge.source.clone());
+        if edge.kind == EdgeKind::DataFlow {
+            mirrors
+                .data_flows_to
+                .entry(edge.source.clone())
+                .or_default()
+                .insert(edge.target.clone());
+        }
+    }
+    mirrors
+}
+
+/// A hierarchy node flattened with its key path (`Area/category/subcategory`).
+struct NodeInfo<'a> {
+    path: String,
+    keys: Vec<String>,
+    expected_id: String,
+    node: &'a HierarchyNode,
+}
+
+fn flatten_hierarchy(graph: &RPGraph) -> Vec<NodeInfo<'_>> {
+    fn walk<'a>(
+        node: &'a HierarchyNode,
+        keys: Vec<String>,
+        id_path: String,
+        out: &mut Vec<NodeInfo<'a>>,
+    ) {
+        // Pre-order, like `RPGraph::rebuild_hierarchy_index`
+        out.push(NodeInfo {
+            path: keys.join("/"),
+            keys: keys.clone(),
+            expected_id: format!("h:{}", id_path),
+            node,
+        });
+        for (key, child) in &node.children {
+            let mut child_keys = keys.clone();
+            child_keys.push(key.clone());
+            walk(
+                child,
+                child_keys,
+                format!("{}/{}", id_path, child.name),
+                out,
+            );
+        }
+    }
+    let mut out = Vec::new();
+    for (key, area) in &graph.hierarchy {
+        walk(area, vec![key.clone()], area.name.clone(), &mut out);
+    }
+    out
+}
+
+/// Containment edges implied by the hierarchy, as [`RPGraph::materialize_containment_edges`]
+/// would produce them.
+fn expected_containment(nodes: &[NodeInfo<'_>]) -> BTreeSet<(String, String)> {
+    let mut expected = BTreeSet::new();
+    for info in nodes {
+        if info.node.id.is_empty() {
+            continue;
+        }
+        for child in info.node.children.values() {
+            if !child.id.is_empty() {
+                expected.insert((info.node.id.clone(), child.id.clone()));
+            }
+        }
+        for entity in &info.node.entities {
+            expected.insert((info.node.id.clone(), entity.clone()));
+        }
+    }
+    expected
+}
+
+/// Check every graph invariant. Findings are sorted by kind, then by subject.
+pub fn validate(graph: &RPGraph) -> ValidationReport {
+    let mut findings = Vec::new();
+    check_edges(graph, &mut findings);
+    check_dependency_mirrors(graph, &mut findings);
+    check_file_index(graph, &mut findings);
+    check_hierarchy(graph, &mut findings);
+    check_entity_ids(graph, &mut findings);
+    check_indexes(graph, &mut findings);
+    check_metadata(graph, &mut findings);
+    findings.sort();
+    ValidationReport { findings }
+}
+
+fn check_edges(graph: &RPGraph, findings: &mut Vec<Finding>) {
+    let mut counts: BTreeMap<&DependencyEdge, usize> = BTreeMap::new();
+    for edge in &graph.edges {
+        *counts.entry(edge).or_default() += 1;
+    }
+    for (edge, count) in counts {
+        if count > 1 {
+            findings.push(Finding::DuplicateEdge {
+                edge: edge.clone(),
+                count,
+            });
+        }
+        let mut dangling = false;
+        for endpoint in [&edge.source, &edge.target] {
+            if !node_exists(graph, endpoint) {
+                dangling = true;
+                findings.push(Finding::DanglingEdge {
+                    edge: edge.clone(),
+                    missing: endpoint.clone(),
+                });
+            }
+        }
+        if dangling
+            || matches!(
+                edge.kind,
+                EdgeKind::Contains | EdgeKind::DataFlow | EdgeKind::Tests
+            )
+        {
+            continue;
+        }
+        // Imports of external packages are declared by the source's import sources
+        let external_import =
+            edge.kind == EdgeKind::Imports && graph.externals.contains_key(&edge.target);
+        if let Some(source) = graph.entities.get(&edge.source)
+            && if external_import {
+                source.deps.import_sources.is_empty()
+            } else {
+                source
+                    .deps
+                    .forward_deps()
+                    .iter()
+                    .any(|(kind, deps)| *kind == edge.kind && deps.is_empty())
+            }
+        {
+            findings.push(Finding::UndeclaredEdge { edge: edge.clone() });
+        }
+    }
+}
+
+fn check_dependency_mirrors(graph: &RPGraph, findings: &mut Vec<Finding>) {
+    let mirrors = mirrors(graph);
+    let empty = BTreeSet::new();
+    for (id, entity) in &graph.entities {
+        for (kind, stored) in entity.deps.reverse_deps() {
+            let expected = mirrors.reverse.get(&(id.clone(), kind)).unwrap_or(&empty);
+            for source in expected {
+                if !stored.contains(source) {
+                    findings.push(Finding::MissingReverseDep {
+                        entity: id.clone(),
+                        edge_kind: kind,
+                        source: source.clone(),
+                    });
+                }
+            }
+            for source in stored.iter().collect::<BTreeSet<_>>() {
+                if !expected.contains(source) {
+                    findings.push(Finding::StaleReverseDep {
+                        entity: id.clone(),
+                        edge_kind: kind,
+                        source: source.clone(),
+                    });
+                }
+            }
+        }
+
+        let expected = mirrors.data_flows_to.get(id).unwrap_or(&empty);
+        let stored = &entity.deps.data_flows_to;
+        for target in expected {
+            if !stored.contains(target) {
+                findings.push(Finding::MissingDataFlowTarget {
+                    entity: id.clone(),
+                    target: target.clone(),
+                });
+            }
+        }
+        for target in stored.iter().collect::<BTreeSet<_>>() {
+            if !expected.contains(target) {
+                findings.push(Finding::StaleDataFlowTarget {
+                    entity: id.clone(),
+                    target: target.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn check_file_index(graph: &RPGraph, findings: &mut Vec<Finding>) {
+    for (file, ids) in &graph.file_index {
+        let mut seen = BTreeSet::new();
+        for id in ids {
+            if !seen.insert(id) {
+                findings.push(Finding::FileIndexDuplicate {
+                    file: file.clone(),
+                    entity: id.clone(),
+                });
+                continue;
+            }
+            match graph.entities.get(id) {
+                None => findings.push(Finding::FileIndexMissingEntity {
+                    file: file.clone(),
+                    entity: id.clone(),
+                }),
+                Some(entity) if entity.file != *file => {
+                    findings.push(Finding::FileIndexWrongFile {
+                        file: file.clone(),
+                        entity: id.clone(),
+                        actual: entity.file.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    for (id, entity) in &graph.entities {
+        if !graph
+            .file_index
+            .get(&entity.file)
+            .is_some_and(|ids| ids.contains(id))
+        {
+            findings.push(Finding::UnindexedEntity {
+                entity: id.clone(),
+                file: entity.file.clone(),
+            });
+        }
+    }
+}
+
+fn check_hierarchy(graph: &RPGraph, findings: &mut Vec<Finding>) {
+    let nodes = flatten_hierarchy(graph);
+    let mut memberships: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+    for info in &nodes {
+        if info.node.id != info.expected_id {
+            findings.push(Finding::HierarchyIdMismatch {
+                node: info.path.clone(),
+                id: info.node.id.clone(),
+                expected: info.expected_id.clone(),
+            });
+        }
+        for id in &info.node.entities {
+            if graph.entities.contains_key(id) {
+                memberships.entry(id).or_default().push(&info.path);
+            } else {
+                findings.push(Finding::HierarchyMissingEntity {
+                    node: info.path.clone(),
+                    entity: id.clone(),
+                });
+            }
+        }
+    }
+
+    for (id, entity) in &graph.entities {
+        let nodes = memberships.get(id.as_str()).map_or(&[][..], Vec::as_slice);
+        if nodes.len() > 1 {
+            findings.push(Finding::DuplicateHierarchyMembership {
+                entity: id.clone(),
+                nodes: nodes.iter().map(|n| (*n).to_string()).collect(),
+            });
+        }
+        match nodes.first() {
+            None if !entity.hierarchy_path.is_empty() => {
+                findings.push(Finding::UnplacedEntity {
+                    entity: id.clone(),
+                    hierarchy_path: entity.hierarchy_path.clone(),
+                });
+            }
+            Some(_) if !nodes.contains(&entity.hierarchy_path.as_str()) => {
+                findings.push(Finding::HierarchyPathMismatch {
+                    entity: id.clone(),
+                    node: nodes[0].to_string(),
+                    hierarchy_path: entity.hierarchy_path.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let expected = expected_containment(&nodes);
+    let actual: BTreeSet<(String, String)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Contains)
+        .map(|e| (e.source.clone(), e.target.clone()))
+        .collect();
+    for (source, target) in expected.difference(&actual) {
+        findings.push(Finding::MissingContainmentEdge {
+            source: source.clone(),
+            target: target.clone(),
+        });
+    }
+    for (source, target) in actual.difference(&expected) {
+        if node_exists(graph, source) && node_exists(graph, target) {
+            findings.push(Finding::StaleContainmentEdge {
+                source: source.clone(),
+                target: target.clone(),
+            });
+        }
+    }
+}
+
+fn check_entity_ids(graph: &RPGraph, findings: &mut Vec<Finding>) {
+    for (key, entity) in &graph.entities {
+        if entity.id != *key {
+            findings.push(Finding::EntityIdMismatch {
+                key: key.clone(),
+                id: entity.id.clone(),
+            });
+        }
+        if entity.kind != EntityKind::Module && !key.contains(':') {
+            findings.push(Finding::MalformedEntityId {
+                entity: key.clone(),
+            });
+        }
+    }
+}
+
+fn check_indexes(graph: &RPGraph, findings: &mut Vec<Finding>) {
+    let mut edge_index: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, edge) in graph.edges.iter().enumerate() {
+        edge_index.entry(&edge.source).or_default().push(i);
+        edge_index.entry(&edge.target).or_default().push(i);
+    }
+    let stale_keys = edge_index
+        .iter()
+        .filter(|(id, expected)| graph.edge_index.get(**id) != Some(*expected))
+        .count()
+        + graph
+            .edge_index
+            .keys()
+            .filter(|id| !edge_index.contains_key(id.as_str()))
+            .count();
+    if stale_keys > 0 {
+        findings.push(Finding::StaleEdgeIndex { stale_keys });
+    }
+
+    let nodes = flatten_hierarchy(graph);
+    let mut hierarchy_index: HashMap<&str, &[String]> = HashMap::new();
+    for info in nodes.iter().filter(|info| !info.node.id.is_empty()) {
+        hierarchy_index.insert(&info.node.id, &info.keys);
+    }
+    let stale_keys = hierarchy_index
+        .iter()
+        .filter(|(id, keys)| {
+            graph
+                .hierarchy_node_index
+                .get(**id)
+                .is_none_or(|stored| stored.as_slice() != **keys)
+        })
+        .count()
+        + graph
+            .hierarchy_node_index
+            .keys()
+            .filter(|id| !hierarchy_index.contains_key(id.as_str()))
+            .count();
+    if stale_keys > 0 {
+        findings.push(Finding::StaleHierarchyIndex { stale_keys });
+    }
+}
+
+fn check_metadata(graph: &RPGraph, findings: &mut Vec<Finding>) {
+    let stored = &graph.metadata;
+    let actual = graph.recounted_metadata();
+    for (field, stored, actual) in [
+        (
+            "total_entities",
+            stored.total_entities,
+            actual.total_entities,
+        ),
+        ("total_files", stored.total_files, actual.total_files),
+        (
+            "functional_areas",
+            stored.functional_areas,
+            actual.functional_areas,
+        ),
+        ("total_edges", stored.total_edges, actual.total_edges),
+        (
+            "dependency_edges",
+            stored.dependency_edges,
+            actual.dependency_edges,
+        ),
+        (
+            "containment_edges",
+            stored.containment_edges,
+            actual.containment_edges,
+        ),
+        (
+            "lifted_entities",
+            stored.lifted_entities,
+            actual.lifted_entities,
+        ),
+        (
+            "data_flow_edges",
+            stored.data_flow_edges,
+            actual.data_flow_edges,
+        ),
+        ("test_edges", stored.test_edges, actual.test_edges),
+    ] {
+        if stored != actual {
+            findings.push(Finding::MetadataMismatch {
+                field,
+                stored,
+                actual,
+            });
+        }
+    }
+}
+
+/// Fix every repairable finding in place, then re-validate.
+///
+/// Edges are authoritative for dependency mirrors, entity `file` and
+/// `hierarchy_path` for the file index and hierarchy, and the hierarchy for
+/// containment edges. Timestamps are left untouched.
+pub fn repair(graph: &mut RPGraph) -> RepairReport {
+    let before = validate(graph).findings;
+
+    if repair_hierarchy(graph, &before)
+        || before
+            .iter()
+            .any(|f| matches!(f, Finding::HierarchyIdMismatch { .. }))
+    {
+        graph.assign_hierarchy_ids();
+    } else {
+        graph.rebuild_hierarchy_index();
+    }
+    repair_edges(graph);
+    repair_file_index(graph);
+    repair_dependency_mirrors(graph);
+    graph.recount_metadata();
+    graph.rebuild_edge_index();
+
+    let remaining = validate(graph).findings;
+    let fixed = before
+        .into_iter()
+        .filter(|f| !remaining.contains(f))
+        .collect();
+    RepairReport { fixed, remaining }
+}
+
+/// Re-place misplaced entities at their `hierarchy_path` and drop references to
+/// missing entities. Returns whether the hierarchy changed.
+fn repair_hierarchy(graph: &mut RPGraph, findings: &[Finding]) -> bool {
+    fn retain_existing(node: &mut HierarchyNode, entities: &BTreeMap<String, Entity>) {
+        node.entities.retain(|id| entities.contains_key(id));
+        for child in node.children.values_mut() {
+            retain_existing(child, entities);
+        }
+    }
+
+    let mut changed = false;
+    if findings
+        .iter()
+        .any(|f| matches!(f, Finding::HierarchyMissingEntity { .. }))
+    {
+        let RPGraph {
+            hierarchy,
+            entities,
+            ..
+        } = graph;
+        for area in hierarchy.values_mut() {
+            retain_existing(area, entities);
+        }
+        hierarchy.retain(|_, area| !area.prune_empty());
+        changed = true;
+    }
+
+    // An entity placed in the tree without a path of its own adopts its node's path
+    for finding in findings {
+        if let Finding::HierarchyPathMismatch {
+            entity,
+            node,
+            hierarchy_path,
+        } = finding
+            && hierarchy_path.is_empty()
+            && let Some(entity) = graph.entities.get_mut(entity)
+        {
+            entity.hierarchy_path.clone_from(node);
+        }
+    }
+
+    let misplaced: BTreeSet<&str> = findings
+        .iter()
+        .filter_map(|f| match f {
+            Finding::HierarchyPathMismatch { entity, .. }
+            | Finding::UnplacedEntity { entity, .. }
+            | Finding::DuplicateHierarchyMembership { entity, .. } => Some(entity.as_str()),
+            _ => None,
+        })
+        .collect();
+    for id in misplaced {
+        let Some(path) = graph.entities.get(id).map(|e| e.hierarchy_path.clone()) else {
+            continue;
+        };
+        graph.remove_entity_from_hierarchy(id);
+        if !path.is_empty() {
+            graph.insert_into_hierarchy(&path, id);
+        }
+        changed = true;
+    }
+    changed
+}
+
+/// Drop dangling and duplicate edges, then re-materialize containment edges if
+/// they no longer match the hierarchy.
+fn repair_edges(graph: &mut RPGraph) {
+    let mut seen = BTreeSet::new();
+    let edges = std::mem::take(&mut graph.edges);
+    let kept: Vec<DependencyEdge> = edges
+        .into_iter()
+        .filter(|e| {
+            node_exists(graph, &e.source) && node_exists(graph, &e.target) && seen.insert(e.clone())
+        })
+        .collect();
+    graph.edges = kept;
+
+    let expected = expected_containment(&flatten_hierarchy(graph));
+    let actual: BTreeSet<(String, String)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Contains)
+        .map(|e| (e.source.clone(), e.target.clone()))
+        .collect();
+    if expected != actual {
+        graph.materialize_containment_edges();
+    }
+}
+
+/// Keep each entity listed once, under its own file, preserving existing order.
+fn repair_file_index(graph: &mut RPGraph) {
+    let RPGraph {
+        file_index,
+        entities,
+        ..
+    } = graph;
+    file_index.retain(|file, ids| {
+        let was_empty = ids.is_empty();
+        let mut seen = BTreeSet::new();
+        ids.retain(|id| {
+            entities.get(id).is_some_and(|e| e.file == *file) && seen.insert(id.clone())
+        });
+        was_empty || !ids.is_empty()
+    });
+    for (id, entity) in entities.iter() {
+        let ids = file_index.entry(entity.file.clone()).or_default();
+        if !ids.contains(id) {
+            ids.push(id.clone());
+        }
+    }
+}
+
+/// Make reverse deps and `data_flows_to` mirror the edge list exactly, keeping
+/// the order of entries that are still valid.
+fn repair_dependency_mirrors(graph: &mut RPGraph) {
+    fn sync(stored: &mut Vec<String>, expected: &BTreeSet<String>) {
+        let mut seen = BTreeSet::new();
+        stored.retain(|id| expected.contains(id) && seen.insert(id.clone()));
+        for id in expected {
+            if !stored.contains(id) {
+                stored.push(id.clone());
+            }
+        }
+    }
+
+    let mirrors = mirrors(graph);
+    let empty = BTreeSet::new();
+    for (id, entity) in &mut graph.entities {
+        let kinds = entity.deps.reverse_deps().map(|(kind, _)| kind);
+        for kind in kinds {
+            let expected = mirrors.reverse.get(&(id.clone(), kind)).unwrap_or(&empty);
+            if let Some(stored) = entity.deps.reverse_deps_mut(kind) {
+                sync(stored, expected);
+            }
+        }
+        let expected = mirrors.data_flows_to.get(id).unwrap_or(&empty);
+        sync(&mut entity.deps.data_flows_to, expected);
+    }
+}
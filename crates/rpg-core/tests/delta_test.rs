@@ -0,0 +1,216 @@
+use rpg_core::delta::{DeltaRecorder, GraphDelta};
+use rpg_core::graph::*;
+use std::path::PathBuf;
+
+fn make_entity(id: &str, file: &str, path: &str, features: &[&str]) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: id.rsplit(':').next().unwrap().to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: features.iter().map(|f| (*f).to_string()).collect(),
+        feature_source: None,
+        hierarchy_path: path.to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
+    }
+}
+
+fn with_hierarchy(mut graph: RPGraph) -> RPGraph {
+    let placements: Vec<(String, String)> = graph
+        .entities
+        .values()
+        .map(|e| (e.hierarchy_path.clone(), e.id.clone()))
+        .collect();
+    graph.hierarchy.clear();
+    for (path, id) in placements {
+        graph.insert_into_hierarchy(&path, &id);
+    }
+    graph.assign_hierarchy_ids();
+    graph.refresh_metadata();
+    graph
+}
+
+/// Old: two entities. New: one lifted, one moved, one removed, one added.
+fn old_and_new() -> (RPGraph, RPGraph) {
+    let mut old = RPGraph::new("rust");
+    old.insert_entity(make_entity("a.rs:parse", "a.rs", "Core/parsing/ast", &[]));
+    old.insert_entity(make_entity("a.rs:lex", "a.rs", "Core/parsing/ast", &[]));
+    old.insert_entity(make_entity("b.rs:old", "b.rs", "Core/legacy/misc", &[]));
+    old.edges.push(DependencyEdge {
+        source: "a.rs:parse".to_string(),
+        target: "a.rs:lex".to_string(),
+        kind: EdgeKind::Invokes,
+        confidence: None,
+    });
+    let old = with_hierarchy(old);
+
+    let mut new = old.clone();
+    new.entities
+        .get_mut("a.rs:parse")
+        .unwrap()
+        .semantic_features = vec!["parse source text".to_string()];
+    new.entities.get_mut("a.rs:lex").unwrap().hierarchy_path = "Frontend/lexing/tokens".to_string();
+    new.remove_entity("b.rs:old");
+    new.insert_entity(make_entity("c.rs:emit", "c.rs", "Core/codegen/emit", &[]));
+    new.edges.push(DependencyEdge {
+        source: "c.rs:emit".to_string(),
+        target: "a.rs:parse".to_string(),
+        kind: EdgeKind::Invokes,
+        confidence: None,
+    });
+    (old, with_hierarchy(new))
+}
+
+fn assert_same_state(actual: &RPGraph, expected: &RPGraph) {
+    assert_eq!(actual.entities, expected.entities);
+    assert_eq!(actual.hierarchy, expected.hierarchy);
+    assert_eq!(actual.file_index, expected.file_index);
+    assert_eq!(actual.header(), expected.header());
+    let mut actual_edges = actual.edges.clone();
+    let mut expected_edges = expected.edges.clone();
+    actual_edges.sort();
+    expected_edges.sort();
+    assert_eq!(actual_edges, expected_edges);
+}
+
+#[test]
+fn test_delta_apply_and_invert_roundtrip() {
+    let (old, new) = old_and_new();
+    let delta = GraphDelta::between(&old, &new);
+
+    let mut forward = old.clone();
+    delta.apply(&mut forward);
+    assert_same_state(&forward, &new);
+
+    let mut back = new.clone();
+    delta.invert().apply(&mut back);
+    assert_same_state(&back, &old);
+
+    assert_eq!(delta.invert().invert(), delta);
+    assert!(GraphDelta::between(&new, &new).is_empty());
+}
+
+#[test]
+fn test_delta_reports_feature_changes_and_hierarchy_moves() {
+    let (old, new) = old_and_new();
+    let delta = GraphDelta::between(&old, &new);
+
+    assert_eq!(delta.added.len(), 1);
+    assert_eq!(delta.removed.len(), 1);
+    assert_eq!(delta.edges_added.len(), 1);
+    assert!(delta.hierarchy.is_some());
+    assert!(delta.has_content_changes());
+
+    let features = delta.feature_changes();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0].entity_id, "a.rs:parse");
+    assert!(features[0].before.is_empty());
+    assert_eq!(features[0].after, ["parse source text"]);
+
+    let moves = delta.hierarchy_moves();
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].entity_id, "a.rs:lex");
+    assert_eq!(moves[0].from, "Core/parsing/ast");
+    assert_eq!(moves[0].to, "Frontend/lexing/tokens");
+
+    assert_eq!(
+        delta.summary(),
+        "+1 -1 ~2 entities (1 feature changes, 1 hierarchy moves), +1 -0 edges, hierarchy changed"
+    );
+}
+
+#[test]
+fn test_delta_serializes_only_what_changed() {
+    let (old, _) = old_and_new();
+    let mut touched = old.clone();
+    touched.refresh_metadata();
+    let delta = GraphDelta::between(&old, &touched);
+    assert!(!delta.has_content_changes());
+
+    let json = serde_json::to_value(&delta).unwrap();
+    let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["header"]);
+    let roundtrip: GraphDelta = serde_json::from_value(json).unwrap();
+    assert_eq!(roundtrip, delta);
+}
+
+#[test]
+fn test_delta_conflicts_with_later_changes() {
+    let (old, new) = old_and_new();
+    let delta = GraphDelta::between(&old, &new);
+    assert!(delta.conflicts_with(&new).is_empty());
+
+    let mut later = new.clone();
+    later
+        .entities
+        .get_mut("a.rs:parse")
+        .unwrap()
+        .semantic_features
+        .push("validate syntax".to_string());
+    later.insert_entity(make_entity("b.rs:old", "b.rs", "Core/legacy/misc", &[]));
+    assert_eq!(
+        delta.conflicts_with(&later),
+        ["a.rs:parse", "b.rs:old", "<file_index b.rs>"]
+    );
+}
+
+#[test]
+fn test_delta_conflicts_with_later_edge_and_file_index_changes() {
+    let (old, new) = old_and_new();
+    let delta = GraphDelta::between(&old, &new);
+
+    // Entities untouched, but the added edge is gone and c.rs was re-indexed
+    let mut later = new.clone();
+    later.edges.retain(|e| e.source != "c.rs:emit");
+    later
+        .file_index
+        .get_mut(&PathBuf::from("c.rs"))
+        .unwrap()
+        .push("c.rs:helper".to_string());
+    assert_eq!(
+        delta.conflicts_with(&later),
+        [
+            "<edge c.rs:emit -Invokes-> a.rs:parse>",
+            "<file_index c.rs>"
+        ]
+    );
+}
+
+#[test]
+fn test_delta_recorder_matches_full_diff() {
+    let (mut graph, _) = old_and_new();
+    graph.materialize_containment_edges();
+    let old = graph.clone();
+
+    let mut recorder = DeltaRecorder::new(&graph);
+    recorder.entity(&graph, "a.rs:parse");
+    recorder.entity(&graph, "a.rs:lex");
+    recorder.hierarchy(&graph);
+    graph
+        .entities
+        .get_mut("a.rs:parse")
+        .unwrap()
+        .semantic_features = vec!["parse source text".to_string()];
+    graph.remove_entity_from_hierarchy("a.rs:lex");
+    graph.entities.get_mut("a.rs:lex").unwrap().hierarchy_path =
+        "Frontend/lexing/tokens".to_string();
+    graph.insert_into_hierarchy("Frontend/lexing/tokens", "a.rs:lex");
+    graph.assign_hierarchy_ids();
+    graph.materialize_containment_edges();
+    graph.refresh_metadata();
+
+    let delta = recorder.finish(&graph);
+    assert_eq!(delta, GraphDelta::between(&old, &graph));
+    assert!(!delta.edges_added.is_empty());
+    assert!(delta.hierarchy.is_some());
+
+    let mut back = graph.clone();
+    delta.invert().apply(&mut back);
+    assert_same_state(&back, &old);
+}
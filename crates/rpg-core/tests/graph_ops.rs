@@ -15,6 +15,8 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -61,6 +63,7 @@ fn test_remove_entity_cleans_edges() {
         source: "f.rs:a".to_string(),
         target: "f.rs:b".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
 
     graph.remove_entity("f.rs:a");
@@ -118,6 +121,7 @@ fn test_refresh_metadata() {
         source: "a.py:f1".to_string(),
         target: "b.py:f2".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.insert_into_hierarchy("Area/cat/sub", "a.py:f1");
 
@@ -147,11 +151,13 @@ fn test_edges_for() {
         source: "f.rs:a".to_string(),
         target: "f.rs:b".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "f.rs:c".to_string(),
         target: "f.rs:a".to_string(),
         kind: EdgeKind::Imports,
+        confidence: None,
     });
 
     let edges = graph.edges_for("f.rs:a");
@@ -168,16 +174,19 @@ fn test_edges_for_indexed_vs_fallback_equivalence() {
         source: "f.rs:a".to_string(),
         target: "f.rs:b".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "g.rs:c".to_string(),
         target: "f.rs:a".to_string(),
         kind: EdgeKind::Imports,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "f.rs:a".to_string(),
         target: "g.rs:c".to_string(),
         kind: EdgeKind::Composes,
+        confidence: None,
     });
 
     // Fallback path (no index built)
@@ -335,6 +344,7 @@ fn test_contains_edge_kind_serde() {
         source: "h:Area".to_string(),
         target: "h:Area/cat".to_string(),
         kind: EdgeKind::Contains,
+        confidence: None,
     };
     let json = serde_json::to_string(&edge).unwrap();
     assert!(json.contains("\"contains\""));
@@ -350,11 +360,13 @@ fn test_refresh_metadata_edge_counts() {
         source: "a.rs:f1".to_string(),
         target: "b.rs:f2".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "h:Area".to_string(),
         target: "a.rs:f1".to_string(),
         kind: EdgeKind::Contains,
+        confidence: None,
     });
 
     graph.refresh_metadata();
@@ -390,11 +402,13 @@ fn test_refresh_metadata_data_flow_edges() {
         source: "a.rs:f1".to_string(),
         target: "b.rs:f2".to_string(),
         kind: EdgeKind::DataFlow,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "b.rs:f2".to_string(),
         target: "a.rs:f1".to_string(),
         kind: EdgeKind::DataFlow,
+        confidence: None,
     });
     graph.refresh_metadata();
     assert_eq!(graph.metadata.data_flow_edges, 2);
@@ -420,3 +434,49 @@ fn test_clear_reverse_includes_data_flows_from() {
     deps.clear_reverse();
     assert!(deps.data_flows_from.is_empty());
 }
+
+#[test]
+fn test_base_entity_id_strips_discriminator() {
+    assert_eq!(
+        base_entity_id("src/Foo.java:Foo::put#2"),
+        "src/Foo.java:Foo::put"
+    );
+    assert_eq!(
+        base_entity_id("src/Foo.java:Foo::Foo#1.2"),
+        "src/Foo.java:Foo::Foo"
+    );
+    assert_eq!(
+        base_entity_id("src/Foo.java:Foo::put"),
+        "src/Foo.java:Foo::put"
+    );
+    // A `#` that is not followed by digits is part of the name, not a discriminator
+    assert_eq!(base_entity_id("src/lib.fs:op#x"), "src/lib.fs:op#x");
+}
+
+#[test]
+fn test_lifting_coverage_ignores_modules_and_data() {
+    let mut graph = RPGraph::new("python");
+    let mut lifted = make_entity("a.py:run", "run", "a.py");
+    lifted.semantic_features = vec!["run the job".to_string()];
+    let mut unlifted = make_entity("a.py:stop", "stop", "a.py");
+    unlifted.semantic_features.clear();
+    graph.insert_entity(lifted);
+    graph.insert_entity(unlifted);
+    for (name, kind) in [
+        ("a.py", EntityKind::Module),
+        ("TIMEOUT", EntityKind::Constant),
+        ("count", EntityKind::Global),
+    ] {
+        let mut entity = make_entity(&format!("a.py:{name}"), name, "a.py");
+        entity.kind = kind;
+        entity.semantic_features.clear();
+        graph.insert_entity(entity);
+    }
+
+    assert_eq!(graph.lifting_coverage(), (1, 2));
+    assert_eq!(
+        graph.unlifted_by_file(),
+        vec![("a.py".to_string(), vec!["a.py:stop".to_string()])]
+    );
+    assert_eq!(graph.recounted_metadata().lifted_entities, 1);
+}
@@ -0,0 +1,263 @@
+use rpg_core::config::StorageConfig;
+use rpg_core::delta::GraphDelta;
+use rpg_core::graph::*;
+use rpg_core::history;
+use rpg_core::storage;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn make_entity(id: &str, name: &str, file: &str, path: &str) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        hierarchy_path: path.to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
+    }
+}
+
+fn commit_graph(commit: &str) -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    graph.base_commit = Some(commit.to_string());
+    graph.insert_entity(make_entity(
+        "a.rs:parse",
+        "parse",
+        "a.rs",
+        "Core/parsing/ast",
+    ));
+    graph.insert_entity(make_entity("a.rs:lex", "lex", "a.rs", "Core/parsing/ast"));
+    graph.edges.push(DependencyEdge {
+        source: "a.rs:parse".to_string(),
+        target: "a.rs:lex".to_string(),
+        kind: EdgeKind::Invokes,
+        confidence: None,
+    });
+    rebuild(&mut graph);
+    graph
+}
+
+fn rebuild(graph: &mut RPGraph) {
+    graph.hierarchy.clear();
+    let placements: Vec<(String, String)> = graph
+        .entities
+        .values()
+        .map(|e| (e.hierarchy_path.clone(), e.id.clone()))
+        .collect();
+    for (path, id) in placements {
+        graph.insert_into_hierarchy(&path, &id);
+    }
+    graph.assign_hierarchy_ids();
+    graph.refresh_metadata();
+}
+
+fn assert_same_state(actual: &RPGraph, expected: &RPGraph) {
+    assert_eq!(actual.base_commit, expected.base_commit);
+    assert_eq!(actual.entities, expected.entities);
+    assert_eq!(actual.hierarchy, expected.hierarchy);
+    assert_eq!(actual.file_index, expected.file_index);
+    let mut actual_edges = actual.edges.clone();
+    let mut expected_edges = expected.edges.clone();
+    actual_edges.sort();
+    expected_edges.sort();
+    assert_eq!(actual_edges, expected_edges);
+}
+
+/// Three commits: add an entity, lift features, then move an entity to another area.
+fn three_revisions() -> Vec<RPGraph> {
+    let v1 = commit_graph("1111111aaaa");
+
+    let mut v2 = v1.clone();
+    v2.base_commit = Some("2222222bbbb".to_string());
+    v2.insert_entity(make_entity(
+        "b.rs:emit",
+        "emit",
+        "b.rs",
+        "Core/codegen/emit",
+    ));
+    let parse = v2.entities.get_mut("a.rs:parse").unwrap();
+    parse.semantic_features = vec!["parse source text".to_string()];
+    parse.feature_source = Some("llm".to_string());
+    rebuild(&mut v2);
+
+    let mut v3 = v2.clone();
+    v3.base_commit = Some("3333333cccc".to_string());
+    v3.entities.get_mut("a.rs:parse").unwrap().hierarchy_path = "Frontend/syntax/parse".to_string();
+    v3.remove_entity("a.rs:lex");
+    rebuild(&mut v3);
+
+    vec![v1, v2, v3]
+}
+
+#[test]
+fn test_delta_apply_reproduces_new_graph() {
+    let revisions = three_revisions();
+    let (old, new) = (&revisions[0], &revisions[2]);
+
+    let delta = GraphDelta::between(old, new);
+    assert!(!delta.is_empty());
+    let mut applied = old.clone();
+    delta.apply(&mut applied);
+    assert_same_state(&applied, new);
+
+    assert!(GraphDelta::between(new, new).is_empty());
+}
+
+#[test]
+fn test_history_materializes_each_commit() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let revisions = three_revisions();
+    for graph in &revisions {
+        storage::save_with_config(root, graph, &StorageConfig::default()).unwrap();
+    }
+
+    let recorded = history::revisions(root).unwrap();
+    let commits: Vec<&str> = recorded.iter().map(|r| r.commit.as_str()).collect();
+    assert_eq!(commits, ["1111111aaaa", "2222222bbbb", "3333333cccc"]);
+
+    for graph in &revisions {
+        let commit = graph.base_commit.as_deref().unwrap();
+        let past = history::as_of(root, &commit[..7]).unwrap().unwrap();
+        assert_same_state(&past, graph);
+    }
+    assert!(history::as_of(root, "deadbeef").unwrap().is_none());
+}
+
+#[test]
+fn test_history_same_commit_is_recorded_once() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let revisions = three_revisions();
+    storage::save_with_config(root, &revisions[0], &StorageConfig::default()).unwrap();
+    storage::save_with_config(root, &revisions[1], &StorageConfig::default()).unwrap();
+    let head = root.join(".rpg/history/head.rpgb");
+    let head_written = std::fs::metadata(&head).unwrap().modified().unwrap();
+
+    // Re-save the same commit with more lifting done: the revision is left alone
+    let mut relifted = revisions[1].clone();
+    relifted
+        .entities
+        .get_mut("a.rs:lex")
+        .unwrap()
+        .semantic_features = vec!["tokenize input".to_string()];
+    storage::save_with_config(root, &relifted, &StorageConfig::default()).unwrap();
+
+    assert_eq!(history::revisions(root).unwrap().len(), 2);
+    assert_eq!(
+        std::fs::metadata(&head).unwrap().modified().unwrap(),
+        head_written
+    );
+    assert_same_state(
+        &history::as_of(root, "2222222").unwrap().unwrap(),
+        &revisions[1],
+    );
+
+    // The lifting shows up in the next commit's revision
+    let mut next = relifted.clone();
+    next.base_commit = Some("4444444dddd".to_string());
+    storage::save_with_config(root, &next, &StorageConfig::default()).unwrap();
+    assert_same_state(&history::as_of(root, "4444444").unwrap().unwrap(), &next);
+    assert_same_state(
+        &history::as_of(root, "2222222").unwrap().unwrap(),
+        &revisions[1],
+    );
+}
+
+#[test]
+fn test_history_is_bounded_and_optional() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let config = StorageConfig {
+        history_limit: 2,
+        ..Default::default()
+    };
+    for graph in three_revisions() {
+        storage::save_with_config(root, &graph, &config).unwrap();
+    }
+    let commits: Vec<String> = history::revisions(root)
+        .unwrap()
+        .into_iter()
+        .map(|r| r.commit)
+        .collect();
+    assert_eq!(commits, ["2222222bbbb", "3333333cccc"]);
+    assert!(history::as_of(root, "1111111").unwrap().is_none());
+    let deltas = std::fs::read_dir(history::history_dir(root))
+        .unwrap()
+        .filter(|e| {
+            e.as_ref()
+                .unwrap()
+                .path()
+                .to_string_lossy()
+                .ends_with(".delta.zst")
+        })
+        .count();
+    assert_eq!(deltas, 1, "pruned revisions drop their deltas");
+
+    // Disabled history and uncommitted graphs record nothing
+    let tmp = TempDir::new().unwrap();
+    let disabled = StorageConfig {
+        history_limit: 0,
+        ..Default::default()
+    };
+    storage::save_with_config(tmp.path(), &commit_graph("abc"), &disabled).unwrap();
+    storage::save_with_config(tmp.path(), &RPGraph::new("rust"), &StorageConfig::default())
+        .unwrap();
+    assert!(history::revisions(tmp.path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_entity_timeline_tracks_features_and_placement() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    for graph in three_revisions() {
+        storage::save_with_config(root, &graph, &StorageConfig::default()).unwrap();
+    }
+
+    let timeline = history::entity_timeline(root, "a.rs:parse").unwrap();
+    let placements: Vec<&str> = timeline
+        .iter()
+        .map(|s| s.entity.as_ref().unwrap().hierarchy_path.as_str())
+        .collect();
+    assert_eq!(
+        placements,
+        [
+            "Core/parsing/ast",
+            "Core/parsing/ast",
+            "Frontend/syntax/parse"
+        ]
+    );
+    assert!(
+        timeline[0]
+            .entity
+            .as_ref()
+            .unwrap()
+            .semantic_features
+            .is_empty()
+    );
+    assert_eq!(
+        timeline[1].entity.as_ref().unwrap().semantic_features,
+        ["parse source text"]
+    );
+
+    let lex = history::entity_timeline(root, "a.rs:lex").unwrap();
+    assert!(lex[1].entity.is_some());
+    assert!(lex[2].entity.is_none(), "removed entities show as absent");
+}
+
+#[test]
+fn test_history_is_gitignored() {
+    let tmp = TempDir::new().unwrap();
+    storage::save_with_config(tmp.path(), &commit_graph("abc"), &StorageConfig::default()).unwrap();
+    let gitignore =
+        std::fs::read_to_string(storage::rpg_dir(tmp.path()).join(".gitignore")).unwrap();
+    assert!(gitignore.lines().any(|l| l == "history/"));
+}
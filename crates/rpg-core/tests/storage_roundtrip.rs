@@ -1,4 +1,5 @@
 use rpg_core::graph::*;
+use rpg_core::schema;
 use rpg_core::storage;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -17,6 +18,8 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         hierarchy_path: "Area/cat/sub".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -31,6 +34,7 @@ fn test_save_and_load_roundtrip() {
         source: "f.rs:main".to_string(),
         target: "f.rs:helper".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.insert_into_hierarchy("Core/parsing/ast", "f.rs:main");
     graph.refresh_metadata();
@@ -59,6 +63,7 @@ fn test_composes_edge_roundtrip() {
         source: "f.rs:main".to_string(),
         target: "f.rs:helper".to_string(),
         kind: EdgeKind::Composes,
+        confidence: None,
     });
     graph.refresh_metadata();
 
@@ -147,6 +152,38 @@ fn test_signature_none_backward_compat() {
     assert!(e.signature.is_none());
 }
 
+#[test]
+fn test_entity_metadata_roundtrip_and_default() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+
+    let mut graph = RPGraph::new("rust");
+    let mut entity = make_entity("f.rs:run", "run", "f.rs");
+    entity.metadata = EntityMetadata {
+        visibility: Some(Visibility::Internal),
+        is_static: true,
+        is_deprecated: true,
+        docstring: Some("Runs the job.\n\nBlocks until done.".to_string()),
+        decorators: vec!["deprecated".to_string()],
+        ..Default::default()
+    };
+    graph.insert_entity(entity);
+    graph.insert_entity(make_entity("f.rs:plain", "plain", "f.rs"));
+    graph.refresh_metadata();
+
+    storage::save(root, &graph).unwrap();
+    let json = std::fs::read_to_string(storage::rpg_file(root)).unwrap();
+    // Empty entity metadata is omitted: only the graph's block and `run`'s remain
+    assert_eq!(json.matches("\"metadata\": {").count(), 2);
+
+    let loaded = storage::load(root).unwrap();
+    assert_eq!(
+        loaded.entities["f.rs:run"].metadata,
+        graph.entities["f.rs:run"].metadata
+    );
+    assert!(loaded.entities["f.rs:plain"].metadata.is_empty());
+}
+
 #[test]
 fn test_data_flow_deps_roundtrip() {
     let tmp = TempDir::new().unwrap();
@@ -161,6 +198,7 @@ fn test_data_flow_deps_roundtrip() {
         source: "f.rs:caller".to_string(),
         target: "f.rs:callee".to_string(),
         kind: EdgeKind::DataFlow,
+        confidence: None,
     });
     graph.refresh_metadata();
 
@@ -172,3 +210,404 @@ fn test_data_flow_deps_roundtrip() {
     assert_eq!(e.deps.data_flows_from, vec!["f.rs:source".to_string()]);
     assert_eq!(loaded.edges[0].kind, EdgeKind::DataFlow);
 }
+
+fn binary_config(compress: bool) -> rpg_core::config::StorageConfig {
+    rpg_core::config::StorageConfig {
+        format: rpg_core::config::StorageFormat::Binary,
+        compress,
+        ..Default::default()
+    }
+}
+
+fn make_binary_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    let mut main = make_entity("f.rs:main", "main", "f.rs");
+    main.signature = Some(Signature {
+        parameters: vec![Param {
+            name: "args".to_string(),
+            type_annotation: Some("Vec<String>".to_string()),
+        }],
+        return_type: None,
+    });
+    main.metadata = EntityMetadata {
+        visibility: Some(Visibility::Public),
+        is_async: true,
+        docstring: Some("Entry point.".to_string()),
+        decorators: vec!["tokio::main".to_string()],
+        ..Default::default()
+    };
+    graph.insert_entity(main);
+    graph.insert_entity(make_entity("f.rs:helper", "helper", "f.rs"));
+    graph.edges.push(DependencyEdge {
+        source: "f.rs:main".to_string(),
+        target: "f.rs:helper".to_string(),
+        kind: EdgeKind::Invokes,
+        confidence: None,
+    });
+    graph.insert_into_hierarchy("Core/parsing/ast", "f.rs:main");
+    graph.assign_hierarchy_ids();
+    graph.refresh_metadata();
+    // Pass through JSON once so the schema version is current and loads compare equal
+    let mut graph = schema::from_json(&schema::to_json(&graph).unwrap()).unwrap();
+    graph.rebuild_edge_index();
+    graph.rebuild_hierarchy_index();
+    graph
+}
+
+#[test]
+fn test_binary_save_and_load_roundtrip() {
+    for compress in [false, true] {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        let graph = make_binary_graph();
+
+        storage::save_with_config(root, &graph, &binary_config(compress)).unwrap();
+        assert!(storage::rpg_binary_file(root).exists());
+        assert!(!storage::rpg_file(root).exists());
+        assert!(storage::rpg_exists(root));
+
+        let loaded = storage::load(root).unwrap();
+        assert_eq!(
+            schema::to_json(&loaded).unwrap(),
+            schema::to_json(&graph).unwrap(),
+            "binary round-trip must be lossless (compress={})",
+            compress
+        );
+        // Persisted indexes are usable without a rebuild
+        assert_eq!(loaded.edges_for("f.rs:helper").len(), 1);
+        assert_eq!(loaded.hierarchy_node_index, graph.hierarchy_node_index);
+    }
+}
+
+/// `make_binary_graph` with an external package imported by `main`.
+fn with_external_package(mut graph: RPGraph) -> RPGraph {
+    graph.externals.insert(
+        "ext:serde".to_string(),
+        ExternalNode {
+            id: "ext:serde".to_string(),
+            name: "serde".to_string(),
+            ecosystem: PackageEcosystem::Cargo,
+            version: Some("1.0".to_string()),
+            manifests: vec![PathBuf::from("Cargo.toml")],
+        },
+    );
+    graph.edges.push(DependencyEdge {
+        source: "f.rs:main".to_string(),
+        target: "ext:serde".to_string(),
+        kind: EdgeKind::Imports,
+        confidence: Some(EdgeConfidence::High),
+    });
+    graph.refresh_metadata();
+    graph
+}
+
+#[test]
+fn test_external_packages_roundtrip_in_every_format() {
+    let graph = with_external_package(make_binary_graph());
+    for config in [
+        binary_config(true),
+        sharded_config(),
+        rpg_core::config::StorageConfig::default(),
+    ] {
+        let tmp = TempDir::new().unwrap();
+        storage::save_with_config(tmp.path(), &graph, &config).unwrap();
+        let loaded = storage::load(tmp.path()).unwrap();
+        assert_eq!(loaded.externals, graph.externals, "{:?}", config.format);
+        assert_eq!(loaded.edges_for("ext:serde").len(), 1);
+    }
+
+    // Dropping the last package removes the sharded externals file
+    let tmp = TempDir::new().unwrap();
+    storage::save_with_config(tmp.path(), &graph, &sharded_config()).unwrap();
+    let externals_file = storage::rpg_shard_dir(tmp.path()).join("externals.json");
+    assert!(externals_file.exists());
+    storage::save_with_config(tmp.path(), &make_binary_graph(), &sharded_config()).unwrap();
+    assert!(!externals_file.exists());
+}
+
+#[test]
+fn test_binary_lazy_entity_lookup() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    storage::save_with_config(root, &make_binary_graph(), &binary_config(true)).unwrap();
+
+    let lazy = storage::open_binary(root).unwrap();
+    let header = lazy.header().unwrap();
+    assert_eq!(header.metadata.total_entities, 2);
+    assert_eq!(lazy.entity_ids().unwrap(), vec!["f.rs:helper", "f.rs:main"]);
+
+    let main = lazy.get_entity("f.rs:main").unwrap().unwrap();
+    assert_eq!(main.name, "main");
+    assert!(main.signature.is_some());
+    assert!(lazy.get_entity("f.rs:missing").unwrap().is_none());
+}
+
+#[test]
+fn test_storage_format_conversion() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let graph = make_binary_graph();
+    storage::save_with_config(
+        root,
+        &graph,
+        &rpg_core::config::StorageConfig {
+            compress: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // zstd JSON → binary
+    storage::convert(root, &binary_config(false)).unwrap();
+    assert!(!storage::rpg_file(root).exists());
+    assert_eq!(
+        storage::stored_format(root),
+        Some(rpg_core::config::StorageFormat::Binary)
+    );
+
+    // binary → plain JSON
+    storage::convert(root, &rpg_core::config::StorageConfig::default()).unwrap();
+    assert!(!storage::rpg_binary_file(root).exists());
+    let json = std::fs::read_to_string(storage::rpg_file(root)).unwrap();
+    assert_eq!(json, schema::to_json(&graph).unwrap());
+}
+
+#[test]
+fn test_binary_rejects_bad_magic() {
+    assert!(rpg_core::binary::decode(b"{\"version\": \"2.3.0\"}").is_err());
+    assert!(!rpg_core::binary::is_binary(b"RPG"));
+}
+
+fn sharded_config() -> rpg_core::config::StorageConfig {
+    rpg_core::config::StorageConfig {
+        format: rpg_core::config::StorageFormat::Sharded,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_sharded_save_and_load_roundtrip() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let mut graph = make_binary_graph();
+    graph.insert_entity(make_entity("src/lib.rs:run", "run", "src/lib.rs"));
+    graph.base_commit = Some("abc123".to_string());
+    graph.rebuild_hierarchy_from_paths();
+    graph.materialize_containment_edges();
+    graph.refresh_metadata();
+
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+    let shard_dir = storage::rpg_shard_dir(root);
+    assert!(shard_dir.join("manifest.json").exists());
+    assert!(shard_dir.join("files/f.rs.json").exists());
+    assert!(shard_dir.join("files/src/lib.rs.json").exists());
+    assert!(!shard_dir.join("hierarchy.json").exists());
+    assert!(!storage::rpg_file(root).exists());
+
+    // Timestamps come from the shards' modification times
+    let mut loaded = storage::load(root).unwrap();
+    assert!(loaded.created_at <= loaded.updated_at);
+    loaded.created_at = graph.created_at;
+    loaded.updated_at = graph.updated_at;
+    assert_eq!(
+        schema::to_json(&loaded).unwrap(),
+        schema::to_json(&graph).unwrap()
+    );
+}
+
+#[test]
+fn test_sharded_manifest_is_stable_across_saves() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let mut graph = make_binary_graph();
+    graph.base_commit = Some("abc123".to_string());
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+    let manifest_path = storage::rpg_shard_dir(root).join("manifest.json");
+    let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+
+    graph.base_commit = Some("def456".to_string());
+    graph.insert_entity(make_entity("src/new.rs:go", "go", "src/new.rs"));
+    graph.refresh_metadata();
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&manifest_path).unwrap(), manifest);
+    assert!(!manifest.contains("updated_at"));
+    assert!(!manifest.contains("total_entities"));
+    let loaded = storage::load(root).unwrap();
+    assert_eq!(loaded.base_commit.as_deref(), Some("def456"));
+    assert_eq!(loaded.metadata.total_entities, 3);
+    assert!(loaded.hierarchy.contains_key("Area"));
+}
+
+#[test]
+fn test_sharded_save_only_touches_changed_shards() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let mut graph = make_binary_graph();
+    graph.insert_entity(make_entity("src/lib.rs:run", "run", "src/lib.rs"));
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+
+    let shard_dir = storage::rpg_shard_dir(root);
+    let untouched = std::fs::read_to_string(shard_dir.join("files/f.rs.json")).unwrap();
+
+    graph.remove_entity("src/lib.rs:run");
+    graph.insert_entity(make_entity("src/new.rs:go", "go", "src/new.rs"));
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(shard_dir.join("files/f.rs.json")).unwrap(),
+        untouched
+    );
+    assert!(!shard_dir.join("files/src/lib.rs.json").exists());
+    assert!(shard_dir.join("files/src/new.rs.json").exists());
+}
+
+#[test]
+fn test_sharded_load_merges_independent_shards() {
+    // Simulates a git merge: a shard added on another branch shows up on load,
+    // and metadata counts are recomputed rather than trusted from the manifest.
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let graph = make_binary_graph();
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+
+    let other = TempDir::new().unwrap();
+    let mut branch = make_binary_graph();
+    branch.insert_entity(make_entity("src/other.rs:x", "x", "src/other.rs"));
+    storage::save_with_config(other.path(), &branch, &sharded_config()).unwrap();
+    let shard = "files/src/other.rs.json";
+    let dest = storage::rpg_shard_dir(root).join(shard);
+    std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+    std::fs::copy(storage::rpg_shard_dir(other.path()).join(shard), &dest).unwrap();
+
+    let loaded = storage::load(root).unwrap();
+    assert!(loaded.entities.contains_key("src/other.rs:x"));
+    assert_eq!(loaded.metadata.total_entities, 3);
+    assert_eq!(loaded.metadata.total_files, 2);
+}
+
+#[test]
+fn test_sharded_interrupted_save_is_not_loaded() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let graph = make_binary_graph();
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+    assert!(!storage::rpg_saving_marker(root).exists());
+    assert!(storage::create_backup(root).unwrap().is_some());
+
+    // A crash mid-save leaves the marker behind with a mix of old and new shards
+    std::fs::write(storage::rpg_saving_marker(root), b"").unwrap();
+    let loaded = storage::load(root).unwrap();
+    assert_eq!(loaded.entities.len(), graph.entities.len());
+
+    std::fs::remove_file(storage::rpg_backup_file(root)).unwrap();
+    let err = storage::load(root).unwrap_err();
+    assert!(format!("{err:#}").contains("did not finish"));
+
+    // The next complete save clears the marker
+    storage::save_with_config(root, &graph, &sharded_config()).unwrap();
+    assert!(storage::load(root).is_ok());
+}
+
+#[test]
+fn test_graph_file_roundtrip_preserves_encoding() {
+    let tmp = TempDir::new().unwrap();
+    let graph = make_binary_graph();
+    for config in [
+        rpg_core::config::StorageConfig::default(),
+        rpg_core::config::StorageConfig {
+            compress: true,
+            ..Default::default()
+        },
+        binary_config(true),
+    ] {
+        let path = tmp.path().join("graph.out");
+        storage::write_graph_file(&path, &graph, &config).unwrap();
+        let (loaded, detected) = storage::read_graph_file(&path).unwrap();
+        assert_eq!(detected.format, config.format);
+        assert_eq!(detected.compress, config.compress);
+        assert_eq!(loaded.entities.len(), graph.entities.len());
+    }
+    assert!(storage::write_graph_file(&tmp.path().join("x"), &graph, &sharded_config()).is_err());
+}
+
+#[test]
+fn test_save_writes_json_regardless_of_config() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    std::fs::create_dir_all(storage::rpg_dir(root)).unwrap();
+    std::fs::write(
+        storage::rpg_dir(root).join("config.toml"),
+        "[storage]\nformat = \"binary\"\n",
+    )
+    .unwrap();
+
+    storage::save(root, &make_binary_graph()).unwrap();
+    assert!(storage::rpg_file(root).exists());
+    assert!(!storage::rpg_binary_file(root).exists());
+}
+
+#[test]
+fn test_save_is_atomic_and_leaves_no_temp_files() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    storage::save(root, &make_binary_graph()).unwrap();
+    storage::save_with_config(root, &make_binary_graph(), &binary_config(false)).unwrap();
+
+    let leftovers: Vec<_> = std::fs::read_dir(storage::rpg_dir(root))
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "temp files left: {:?}", leftovers);
+    let gitignore = std::fs::read_to_string(storage::rpg_dir(root).join(".gitignore")).unwrap();
+    assert!(gitignore.contains("graph.lock"));
+}
+
+#[test]
+fn test_load_falls_back_to_backup_when_corrupt() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    storage::save(root, &make_binary_graph()).unwrap();
+    assert!(storage::create_backup(root).unwrap().is_some());
+
+    // Simulate a crash mid-write by an older version: truncated graph.json
+    let json = std::fs::read(storage::rpg_file(root)).unwrap();
+    std::fs::write(storage::rpg_file(root), &json[..json.len() / 2]).unwrap();
+
+    let loaded = storage::load(root).unwrap();
+    assert_eq!(loaded.entities.len(), 2);
+
+    // A corrupt graph must never overwrite the good backup
+    assert!(storage::create_backup(root).is_err());
+    assert!(storage::load(root).is_ok());
+
+    std::fs::remove_file(storage::rpg_backup_file(root)).unwrap();
+    assert!(storage::load(root).is_err());
+}
+
+#[test]
+fn test_concurrent_saves_never_expose_partial_graphs() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path().to_path_buf();
+    storage::save(&root, &make_binary_graph()).unwrap();
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let root = root.clone();
+            std::thread::spawn(move || {
+                for i in 0..10 {
+                    let mut graph = make_binary_graph();
+                    let id = format!("t{}.rs:f{}", t, i);
+                    graph.insert_entity(make_entity(&id, "f", &format!("t{}.rs", t)));
+                    storage::save(&root, &graph).unwrap();
+                    let loaded = storage::load(&root).unwrap();
+                    assert_eq!(loaded.entities.len(), 3);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use rpg_core::graph::*;
+use rpg_core::validate::{self, Finding, Severity};
+use std::path::PathBuf;
+
+fn make_entity(id: &str, name: &str, file: &str, path: &str) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        hierarchy_path: path.to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
+    }
+}
+
+fn edge(source: &str, target: &str, kind: EdgeKind) -> DependencyEdge {
+    DependencyEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind,
+        confidence: None,
+    }
+}
+
+/// A consistent graph: `parse` invokes `lex`, and `lex` flows data back to `parse`.
+fn consistent_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    let mut parse = make_entity("a.rs:parse", "parse", "a.rs", "Core/parsing/ast");
+    parse.deps.invokes = vec!["lex".to_string()];
+    parse.deps.data_flows_from = vec!["a.rs:lex".to_string()];
+    let mut lex = make_entity("a.rs:lex", "lex", "a.rs", "Core/parsing/ast");
+    lex.deps.invoked_by = vec!["a.rs:parse".to_string()];
+    lex.deps.data_flows_to = vec!["a.rs:parse".to_string()];
+    graph.insert_entity(parse);
+    graph.insert_entity(lex);
+    graph.insert_entity(make_entity("b.rs:emit", "emit", "b.rs", "Core/codegen"));
+    graph.edges = vec![
+        edge("a.rs:parse", "a.rs:lex", EdgeKind::Invokes),
+        edge("a.rs:lex", "a.rs:parse", EdgeKind::DataFlow),
+    ];
+    for (path, id) in [
+        ("Core/parsing/ast", "a.rs:parse"),
+        ("Core/parsing/ast", "a.rs:lex"),
+        ("Core/codegen", "b.rs:emit"),
+    ] {
+        graph.insert_into_hierarchy(path, id);
+    }
+    graph.assign_hierarchy_ids();
+    graph.materialize_containment_edges();
+    graph.refresh_metadata();
+    graph
+}
+
+#[test]
+fn test_consistent_graph_is_clean() {
+    let report = validate::validate(&consistent_graph());
+    assert!(
+        report.is_clean(),
+        "unexpected findings: {:?}",
+        report.findings
+    );
+}
+
+#[test]
+fn test_detects_each_class_of_violation() {
+    let mut graph = consistent_graph();
+    graph
+        .edges
+        .push(edge("a.rs:parse", "gone.rs:missing", EdgeKind::Invokes));
+    graph
+        .edges
+        .push(edge("a.rs:parse", "a.rs:lex", EdgeKind::Invokes));
+    graph
+        .entities
+        .get_mut("a.rs:lex")
+        .unwrap()
+        .deps
+        .invoked_by
+        .clear();
+    graph
+        .entities
+        .get_mut("b.rs:emit")
+        .unwrap()
+        .deps
+        .imported_by
+        .push("a.rs:parse".to_string());
+    graph
+        .file_index
+        .get_mut(&PathBuf::from("b.rs"))
+        .unwrap()
+        .push("a.rs:lex".to_string());
+    graph.entities.get_mut("b.rs:emit").unwrap().hierarchy_path = "Core/output".to_string();
+    graph.metadata.lifted_entities = 7;
+
+    let report = validate::validate(&graph);
+    let has = |pred: &dyn Fn(&Finding) -> bool| report.findings.iter().any(pred);
+    assert!(has(
+        &|f| matches!(f, Finding::DanglingEdge { missing, .. } if missing == "gone.rs:missing")
+    ));
+    assert!(has(&|f| matches!(
+        f,
+        Finding::DuplicateEdge { count: 2, .. }
+    )));
+    assert!(has(&|f| matches!(
+        f,
+        Finding::MissingReverseDep { entity, edge_kind: EdgeKind::Invokes, source }
+            if entity == "a.rs:lex" && source == "a.rs:parse"
+    )));
+    assert!(has(&|f| matches!(
+        f,
+        Finding::StaleReverseDep { entity, edge_kind: EdgeKind::Imports, .. } if entity == "b.rs:emit"
+    )));
+    assert!(has(&|f| matches!(
+        f,
+        Finding::FileIndexWrongFile { entity, actual, .. }
+            if entity == "a.rs:lex" && actual == &PathBuf::from("a.rs")
+    )));
+    assert!(has(&|f| matches!(
+        f,
+        Finding::HierarchyPathMismatch { entity, node, hierarchy_path }
+            if entity == "b.rs:emit" && node == "Core/codegen" && hierarchy_path == "Core/output"
+    )));
+    assert!(has(&|f| matches!(
+        f,
+        Finding::MetadataMismatch {
+            field: "lifted_entities",
+            stored: 7,
+            actual: 0
+        }
+    )));
+    assert!(report.count(Severity::Error) > 0);
+    assert_eq!(report.repairable(), report.findings.len());
+    assert!(report.findings.iter().all(|f| !f.to_string().is_empty()));
+}
+
+#[test]
+fn test_repair_restores_a_clean_graph() {
+    let mut graph = consistent_graph();
+    let clean = graph.clone();
+
+    graph
+        .edges
+        .push(edge("a.rs:parse", "gone.rs:missing", EdgeKind::Invokes));
+    graph
+        .entities
+        .get_mut("a.rs:lex")
+        .unwrap()
+        .deps
+        .invoked_by
+        .clear();
+    graph
+        .entities
+        .get_mut("a.rs:parse")
+        .unwrap()
+        .deps
+        .data_flows_from
+        .clear();
+    graph
+        .file_index
+        .get_mut(&PathBuf::from("a.rs"))
+        .unwrap()
+        .retain(|id| id != "a.rs:lex");
+    graph.entities.get_mut("b.rs:emit").unwrap().hierarchy_path = "Core/output".to_string();
+    graph.insert_into_hierarchy("Core/parsing/ast", "gone.rs:ghost");
+    graph.metadata.total_edges = 0;
+    graph.edge_index.clear();
+
+    let report = validate::repair(&mut graph);
+    assert!(report.remaining.is_empty(), "left: {:?}", report.remaining);
+    assert!(!report.fixed.is_empty());
+    assert!(validate::validate(&graph).is_clean());
+
+    // The misplaced entity moved to its own path; everything else is back as it was
+    assert!(
+        graph
+            .find_hierarchy_node_by_id("h:Core/output")
+            .is_some_and(|n| n.entities == ["b.rs:emit"])
+    );
+    assert!(graph.find_hierarchy_node_by_id("h:Core/codegen").is_none());
+    for id in ["a.rs:parse", "a.rs:lex"] {
+        assert_eq!(graph.entities[id], clean.entities[id]);
+    }
+    assert_eq!(graph.file_index, clean.file_index);
+    assert_eq!(graph.edges_for("a.rs:lex").len(), 3);
+}
+
+#[test]
+fn test_unrepairable_findings_remain() {
+    let mut graph = consistent_graph();
+    graph.insert_entity(make_entity("orphan", "orphan", "c.rs", ""));
+    graph
+        .edges
+        .push(edge("b.rs:emit", "a.rs:lex", EdgeKind::Composes));
+    graph
+        .entities
+        .get_mut("a.rs:lex")
+        .unwrap()
+        .deps
+        .composed_by
+        .push("b.rs:emit".to_string());
+    graph.refresh_metadata();
+
+    let report = validate::repair(&mut graph);
+    assert!(report.fixed.is_empty());
+    assert_eq!(report.remaining.len(), 2);
+    assert!(report.remaining.iter().all(|f| !f.is_repairable()));
+    assert!(
+        report
+            .remaining
+            .iter()
+            .any(|f| matches!(f, Finding::MalformedEntityId { entity } if entity == "orphan"))
+    );
+    assert!(
+        report
+            .remaining
+            .iter()
+            .any(|f| matches!(f, Finding::UndeclaredEdge { .. })
+                && f.severity() == Severity::Warning)
+    );
+}
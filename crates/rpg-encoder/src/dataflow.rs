@@ -53,6 +53,7 @@ pub fn compute_data_flow_edges(graph: &mut RPGraph) {
                     source: caller_id.clone(),
                     target: callee_id.clone(),
                     kind: EdgeKind::DataFlow,
+                    confidence: None,
                 });
                 new_forward.push((caller_id.clone(), callee_id.clone()));
                 new_reverse.push((callee_id.clone(), caller_id.clone()));
@@ -64,6 +65,7 @@ pub fn compute_data_flow_edges(graph: &mut RPGraph) {
                     source: callee_id.clone(),
                     target: caller_id.clone(),
                     kind: EdgeKind::DataFlow,
+                    confidence: None,
                 });
                 new_forward.push((callee_id.clone(), caller_id.clone()));
                 new_reverse.push((caller_id.clone(), callee_id.clone()));
@@ -99,7 +101,7 @@ pub fn compute_data_flow_edges(graph: &mut RPGraph) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{Entity, EntityDeps, EntityKind, Param, Signature};
+    use rpg_core::graph::{Entity, EntityDeps, EntityKind, EntityMetadata, Param, Signature};
     use std::path::PathBuf;
 
     fn make_entity(name: &str, sig: Option<Signature>) -> Entity {
@@ -116,6 +118,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: sig,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -147,6 +151,7 @@ mod tests {
             source: "src/lib.rs:caller".to_string(),
             target: "src/lib.rs:callee".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         compute_data_flow_edges(&mut graph);
@@ -204,6 +209,7 @@ mod tests {
             source: "src/lib.rs:a".to_string(),
             target: "src/lib.rs:b".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         compute_data_flow_edges(&mut graph);
@@ -239,6 +245,7 @@ mod tests {
             source: "src/lib.rs:a".to_string(),
             target: "src/lib.rs:b".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         compute_data_flow_edges(&mut graph);
@@ -277,6 +284,7 @@ mod tests {
             source: "src/lib.rs:a".to_string(),
             target: "src/lib.rs:b".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         // First computation
@@ -322,6 +330,7 @@ mod tests {
             source: "src/lib.rs:a".to_string(),
             target: "src/lib.rs:b".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         // First computation creates DataFlow edge
@@ -4,6 +4,7 @@
 
 use crate::grounding;
 use anyhow::{Context, Result};
+use rpg_core::delta::{EntityChange, GraphDelta};
 use rpg_core::graph::RPGraph;
 use rpg_parser::entities::RawEntity;
 use rpg_parser::languages::Language;
@@ -41,6 +42,8 @@ pub struct UpdateSummary {
     /// Entity IDs that were structurally modified but not re-lifted (features may be stale).
     /// These should be re-lifted interactively via MCP.
     pub modified_entity_ids: Vec<String>,
+    /// Exact changes made to the graph (empty when nothing changed).
+    pub delta: GraphDelta,
 }
 
 /// Statistics from merging an old graph's semantic data into a new graph.
@@ -56,6 +59,8 @@ pub struct MergeStats {
     pub orphaned: usize,
     /// Entities in the new graph that did not exist in the old graph.
     pub new_entities: usize,
+    /// Entity changes made to the new graph by the merge.
+    pub delta: GraphDelta,
 }
 
 /// Merge semantic features, hierarchy paths, and Module features from an old graph
@@ -80,6 +85,8 @@ pub fn merge_features(new_graph: &mut RPGraph, old_graph: &RPGraph) -> MergeStat
 
     for (id, new_entity) in &mut new_graph.entities {
         if let Some(old_entity) = old_graph.entities.get(id) {
+            let before = new_entity.clone();
+
             // Restore semantic features
             if new_entity.semantic_features.is_empty() && !old_entity.semantic_features.is_empty() {
                 new_entity.semantic_features = old_entity.semantic_features.clone();
@@ -103,6 +110,13 @@ pub fn merge_features(new_graph: &mut RPGraph, old_graph: &RPGraph) -> MergeStat
             if new_entity.signature.is_none() && old_entity.signature.is_some() {
                 new_entity.signature = old_entity.signature.clone();
             }
+
+            if *new_entity != before {
+                stats.delta.modified.push(EntityChange {
+                    before,
+                    after: new_entity.clone(),
+                });
+            }
         }
     }
 
@@ -434,6 +448,7 @@ pub fn apply_modifications(
                 language,
                 &mut new_raw,
             );
+            rpg_parser::entities::disambiguate_ids(&mut new_raw);
         }
 
         let new_ids: std::collections::HashSet<String> = new_raw.iter().map(|e| e.id()).collect();
@@ -464,6 +479,8 @@ pub fn apply_modifications(
                 // Refresh structural fields that paradigm reclassification may change
                 entity.kind = raw.kind;
                 entity.parent_class = raw.parent_class.clone();
+                entity.metadata = raw.metadata.clone();
+                entity.signals = raw.signals;
                 modified_count += 1;
                 // Track entities with existing features that need re-lifting
                 if !entity.semantic_features.is_empty() {
@@ -566,6 +583,7 @@ pub fn apply_additions(
                 language,
                 &mut raw_entities,
             );
+            rpg_parser::entities::disambiguate_ids(&mut raw_entities);
         }
 
         // Compute structural hierarchy path from file path
@@ -636,18 +654,17 @@ pub fn apply_renames(graph: &mut RPGraph, renames: &[(PathBuf, PathBuf)]) -> (us
             for old_id in &old_ids {
                 if let Some(mut entity) = graph.entities.remove(old_id) {
                     entity.file = to.clone();
-                    // Recompute ID from updated file path
-                    let new_id = match &entity.parent_class {
-                        Some(class) => {
-                            format!(
-                                "{}:{}::{}",
-                                rpg_core::graph::normalize_path(to),
-                                class,
-                                entity.name
-                            )
-                        }
-                        None => format!("{}:{}", rpg_core::graph::normalize_path(to), entity.name),
-                    };
+                    // Recompute ID from updated file path, keeping any overload discriminator
+                    let symbol = old_id
+                        .strip_prefix(&format!("{}:", rpg_core::graph::normalize_path(from)))
+                        .map_or_else(
+                            || match &entity.parent_class {
+                                Some(class) => format!("{}::{}", class, entity.name),
+                                None => entity.name.clone(),
+                            },
+                            str::to_string,
+                        );
+                    let new_id = format!("{}:{}", rpg_core::graph::normalize_path(to), symbol);
                     entity.id = new_id.clone();
                     graph.entities.insert(new_id.clone(), entity);
                     id_map.push((old_id.clone(), new_id));
@@ -960,6 +977,7 @@ pub fn run_update(
     }
 
     let mut summary = UpdateSummary::default();
+    let before = graph.clone();
     let old_edge_count = graph.edges.len();
     let old_hierarchy_count: usize = graph.hierarchy.values().map(count_hierarchy_nodes).sum();
 
@@ -1014,8 +1032,10 @@ pub fn run_update(
         Some(&changed_file_list),
         grounding_ctx.as_ref(),
     );
+    grounding::collect_external_packages(graph, project_root);
     grounding::resolve_dependencies(graph);
     crate::dataflow::compute_data_flow_edges(graph);
+    crate::test_links::compute_test_edges(graph);
 
     // Step 6: Re-ground hierarchy
     grounding::ground_hierarchy(graph);
@@ -1039,6 +1059,7 @@ pub fn run_update(
     summary.edges_removed = old_edge_count.saturating_sub(new_edge_count);
     summary.hierarchy_nodes_added = new_hierarchy_count.saturating_sub(old_hierarchy_count);
     summary.hierarchy_nodes_removed = old_hierarchy_count.saturating_sub(new_hierarchy_count);
+    summary.delta = GraphDelta::between(&before, graph);
 
     Ok(summary)
 }
@@ -1,13 +1,19 @@
 //! Artifact Grounding — anchor hierarchy to directories and resolve dependency edges.
 
-use rpg_core::graph::{DependencyEdge, EdgeKind, HierarchyNode, RPGraph};
+use rpg_core::graph::{
+    CallArity, CallReceiver, DependencyEdge, EdgeConfidence, EdgeKind, Entity, EntityKind,
+    ExternalNode, HierarchyNode, ImportSource, PackageEcosystem, RPGraph, base_entity_id,
+};
 use rpg_core::lca;
 use rpg_parser::deps;
 use rpg_parser::languages::Language;
+use rpg_parser::packages;
 use rpg_parser::paradigms::defs::ParadigmDef;
 use rpg_parser::paradigms::query_engine::QueryCache;
-use std::collections::{BTreeMap, HashMap};
-use std::path::Path;
+use rpg_parser::receivers::{self, Receiver, ReceiverCall};
+use rpg_parser::variables::{self, StateAccess};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Paradigm context for TOML-driven dependency extraction.
 /// When provided to `populate_entity_deps`, the TOML dep pipeline
@@ -73,6 +79,14 @@ pub fn populate_entity_deps(
         }
     }
 
+    // Names of data entities, which accessed names must match to become deps
+    let data_names: HashSet<String> = graph
+        .entities
+        .values()
+        .filter(|e| e.kind.is_data())
+        .map(|e| e.name.clone())
+        .collect();
+
     for rel_path in &file_list {
         let file_lang = rel_path
             .extension()
@@ -118,6 +132,9 @@ pub fn populate_entity_deps(
                     parent_class: e.parent_class.clone(),
                     source_text: String::new(),
                     signature: None,
+                    metadata: e.metadata.clone(),
+                    signals: e.signals,
+                    discriminator: None,
                 })
                 .collect();
 
@@ -137,12 +154,12 @@ pub fn populate_entity_deps(
             None => continue,
         };
 
-        // Extract import symbols
-        let import_symbols: Vec<String> = raw_deps
+        // Extract import symbols with the module each comes from
+        let import_symbols: Vec<ImportSource> = raw_deps
             .imports
             .iter()
             .flat_map(|imp| {
-                if imp.symbols.is_empty() {
+                let symbols = if imp.symbols.is_empty() {
                     // import module → use the last segment as symbol
                     vec![
                         imp.module
@@ -154,7 +171,11 @@ pub fn populate_entity_deps(
                     ]
                 } else {
                     imp.symbols.clone()
-                }
+                };
+                symbols.into_iter().map(|symbol| ImportSource {
+                    symbol,
+                    module: imp.module.clone(),
+                })
             })
             .collect();
 
@@ -163,7 +184,9 @@ pub fn populate_entity_deps(
         for (edge_kind, call_deps) in raw_deps.call_dep_vectors() {
             for call in call_deps {
                 for id in &entity_ids {
-                    if let Some(entity) = graph.entities.get_mut(id) {
+                    if let Some(entity) = graph.entities.get_mut(id)
+                        && !entity.kind.is_data()
+                    {
                         let matches = entity.name == call.caller_entity
                             || call.caller_entity.ends_with(&format!(".{}", entity.name))
                             || call.caller_entity.ends_with(&format!("::{}", entity.name));
@@ -175,10 +198,39 @@ pub fn populate_entity_deps(
             }
         }
 
+        // Receiver types of method calls, where the language declares them,
+        // and the module paths calls are made through
+        if receivers::records_calls(language) {
+            let mut calls = receivers::receiver_calls(&source, language);
+            if !receivers::supports(language) {
+                // Without declarations only an import tells a module from a value
+                for call in &mut calls {
+                    let imported = call
+                        .module
+                        .as_deref()
+                        .and_then(path_root)
+                        .is_some_and(|root| {
+                            import_symbols.iter().any(|import| import.symbol == root)
+                        });
+                    if !imported {
+                        call.module = None;
+                    }
+                }
+            }
+            assign_receivers(graph, &entity_ids, &calls);
+        }
+
+        // Fields, constants and globals read or written by functions
+        if variables::supports(language) {
+            let accesses = variables::state_accesses(&source, language);
+            assign_state_accesses(graph, &entity_ids, &accesses, &data_names);
+        }
+
         // Map inherits: match child_class to entity
         for inherit in &raw_deps.inherits {
             for id in &entity_ids {
                 if let Some(entity) = graph.entities.get_mut(id)
+                    && !entity.kind.is_data()
                     && entity.name == inherit.child_class
                     && !entity.deps.inherits.contains(&inherit.parent_class)
                 {
@@ -203,19 +255,39 @@ pub fn populate_entity_deps(
         // If the entity invokes or inherits a symbol that matches an import, assign it.
         // Fall back to broadcast if the entity has no call-site info.
         for id in &entity_ids {
-            if let Some(entity) = graph.entities.get_mut(id) {
-                if entity.deps.has_callsite_info() {
-                    for sym in &import_symbols {
-                        if entity.deps.references_symbol(sym) && !entity.deps.imports.contains(sym)
-                        {
-                            entity.deps.imports.push(sym.clone());
+            if let Some(entity) = graph.entities.get_mut(id)
+                && !entity.kind.is_data()
+            {
+                // The module knows every import, so packages used only through
+                // module attributes (`requests.get`) are still linked to the file
+                if entity.kind == EntityKind::Module {
+                    for import in &import_symbols {
+                        if entity.deps.import_source(&import.symbol).is_none() {
+                            entity.deps.import_sources.push(import.clone());
                         }
                     }
-                } else if broadcast_imports {
-                    for sym in &import_symbols {
-                        if !entity.deps.imports.contains(sym) {
-                            entity.deps.imports.push(sym.clone());
+                }
+                let callsite_info = entity.deps.has_callsite_info();
+                if !callsite_info && !broadcast_imports {
+                    continue;
+                }
+                for import in &import_symbols {
+                    if callsite_info && !entity.deps.references_symbol(&import.symbol) {
+                        // A module that calls are made through (`os.remove()`)
+                        // is known by its source only
+                        let qualifies = entity.deps.receivers.iter().any(|r| {
+                            r.module.as_deref().and_then(path_root) == Some(import.symbol.as_str())
+                        });
+                        if qualifies && entity.deps.import_source(&import.symbol).is_none() {
+                            entity.deps.import_sources.push(import.clone());
                         }
+                        continue;
+                    }
+                    if !entity.deps.imports.contains(&import.symbol) {
+                        entity.deps.imports.push(import.symbol.clone());
+                    }
+                    if entity.deps.import_source(&import.symbol).is_none() {
+                        entity.deps.import_sources.push(import.clone());
                     }
                 }
             }
@@ -223,6 +295,167 @@ pub fn populate_entity_deps(
     }
 }
 
+/// Record the receivers and argument counts of each entity's method calls
+/// (see [`EntityDeps::receivers`] and [`EntityDeps::call_arities`]). A call
+/// site belongs to the innermost entity spanning its line; a method gets
+/// receivers only if none of its call sites in the entity is a bare call, and
+/// argument counts only if every call site's count is known.
+///
+/// [`EntityDeps::receivers`]: rpg_core::graph::EntityDeps::receivers
+/// [`EntityDeps::call_arities`]: rpg_core::graph::EntityDeps::call_arities
+fn assign_receivers(graph: &mut RPGraph, entity_ids: &[String], calls: &[ReceiverCall]) {
+    // Receiver types and module paths per entity and method; `None` once a
+    // bare call is seen
+    type Receivers<'a> = Option<BTreeSet<(Option<&'a str>, Option<&'a str>)>>;
+    let mut by_entity: HashMap<&str, BTreeMap<&str, Receivers<'_>>> = HashMap::new();
+    // Argument counts per entity and method; `None` once a count is unknown
+    let mut arities: HashMap<&str, BTreeMap<&str, Option<BTreeSet<usize>>>> = HashMap::new();
+    for call in calls {
+        let Some(owner) = innermost_code(graph, entity_ids, call.line) else {
+            continue;
+        };
+        let counts = arities
+            .entry(owner)
+            .or_default()
+            .entry(call.method.as_str())
+            .or_insert_with(|| Some(BTreeSet::new()));
+        match (call.args, counts) {
+            (Some(args), Some(counts)) => {
+                counts.insert(args);
+            }
+            (None, counts) => *counts = None,
+            (_, None) => {}
+        }
+        let types = by_entity
+            .entry(owner)
+            .or_default()
+            .entry(call.method.as_str())
+            .or_insert_with(|| Some(BTreeSet::new()));
+        match (&call.receiver, &call.module, types) {
+            (Receiver::None, None, types) => *types = None,
+            (_, _, None) => {}
+            (Receiver::None, Some(module), Some(types)) => {
+                types.insert((None, Some(module)));
+            }
+            (Receiver::Unknown, _, Some(types)) => {
+                types.insert((None, None));
+            }
+            (Receiver::Typed(ty), _, Some(types)) => {
+                types.insert((Some(ty), None));
+            }
+        }
+    }
+
+    for (id, methods) in by_entity {
+        let Some(entity) = graph.entities.get_mut(id) else {
+            continue;
+        };
+        for (method, types) in methods {
+            let Some(types) = types else {
+                continue;
+            };
+            if !entity.deps.invokes.iter().any(|m| m == method) {
+                continue;
+            }
+            for (ty, module) in types {
+                entity.deps.receivers.push(CallReceiver {
+                    method: method.to_string(),
+                    receiver: ty.map(str::to_string),
+                    module: module.map(str::to_string),
+                });
+            }
+        }
+    }
+
+    for (id, methods) in arities {
+        let Some(entity) = graph.entities.get_mut(id) else {
+            continue;
+        };
+        for (method, counts) in methods {
+            if let Some(counts) = counts
+                && entity.deps.invokes.iter().any(|m| m == method)
+            {
+                entity.deps.call_arities.push(CallArity {
+                    method: method.to_string(),
+                    args: counts.into_iter().collect(),
+                });
+            }
+        }
+    }
+}
+
+/// Record the data entities each entity reads and writes (`reads_state` /
+/// `writes_state`). An access belongs to the innermost code entity spanning
+/// its line, and counts only if a field, constant or global of that name
+/// exists somewhere in the graph.
+fn assign_state_accesses(
+    graph: &mut RPGraph,
+    entity_ids: &[String],
+    accesses: &[StateAccess],
+    data_names: &HashSet<String>,
+) {
+    let owned: Vec<(String, &StateAccess)> = accesses
+        .iter()
+        .filter(|access| data_names.contains(&access.name))
+        .filter_map(|access| {
+            Some((
+                innermost_code(graph, entity_ids, access.line)?.to_string(),
+                access,
+            ))
+        })
+        .collect();
+    for (owner, access) in owned {
+        if let Some(entity) = graph.entities.get_mut(&owner) {
+            let kind = if access.write {
+                EdgeKind::WritesState
+            } else {
+                EdgeKind::ReadsState
+            };
+            push_forward_dep(&mut entity.deps, kind, &access.name);
+        }
+    }
+}
+
+/// The innermost code (non-data) entity among `entity_ids` spanning `line`.
+fn innermost_code<'a>(graph: &RPGraph, entity_ids: &'a [String], line: usize) -> Option<&'a str> {
+    entity_ids
+        .iter()
+        .filter_map(|id| Some((id.as_str(), graph.entities.get(id)?)))
+        .filter(|(_, e)| !e.kind.is_data() && e.line_start <= line && line <= e.line_end)
+        .min_by_key(|(_, e)| e.line_end - e.line_start)
+        .map(|(id, _)| id)
+}
+
+/// Collect the third-party packages declared in manifests (see
+/// [`packages::declared_packages`]) into `graph.externals`, replacing any from
+/// a previous build. Manifests are read from the project root and from every
+/// directory holding indexed files, so nested crates and packages count too.
+/// Must run before `resolve_dependencies` for imports to link to packages.
+pub fn collect_external_packages(graph: &mut RPGraph, project_root: &Path) {
+    let dirs: BTreeSet<PathBuf> = graph
+        .file_index
+        .keys()
+        .flat_map(|file| file.ancestors().skip(1))
+        .map(Path::to_path_buf)
+        .collect();
+
+    let mut externals: BTreeMap<String, ExternalNode> = BTreeMap::new();
+    for dir in dirs {
+        for node in packages::declared_packages(project_root, &project_root.join(dir)) {
+            match externals.get_mut(&node.id) {
+                Some(existing) if existing.ecosystem == node.ecosystem => {
+                    existing.manifests.extend(node.manifests);
+                }
+                Some(_) => {}
+                None => {
+                    externals.insert(node.id.clone(), node);
+                }
+            }
+        }
+    }
+    graph.externals = externals;
+}
+
 /// Push a callee to the correct forward dep vector for the given edge kind.
 fn push_forward_dep(deps: &mut rpg_core::graph::EntityDeps, kind: EdgeKind, callee: &str) {
     let vec = match kind {
@@ -233,7 +466,11 @@ fn push_forward_dep(deps: &mut rpg_core::graph::EntityDeps, kind: EdgeKind, call
         EdgeKind::Dispatches => &mut deps.dispatches,
         EdgeKind::DataFlow => &mut deps.data_flows_to,
         // These edge kinds are not call-like and are handled separately
-        EdgeKind::Imports | EdgeKind::Inherits | EdgeKind::Composes | EdgeKind::Contains => return,
+        EdgeKind::Imports
+        | EdgeKind::Inherits
+        | EdgeKind::Composes
+        | EdgeKind::Tests
+        | EdgeKind::Contains => return,
     };
     if !vec.contains(&callee.to_string()) {
         vec.push(callee.to_string());
@@ -241,59 +478,70 @@ fn push_forward_dep(deps: &mut rpg_core::graph::EntityDeps, kind: EdgeKind, call
 }
 
 /// Resolve raw dependency references into proper entity-to-entity edges.
+///
+/// A name is resolved from the referencing entity's point of view, in order:
+///
+/// 1. members of its own class and, through `inherits`, its base classes;
+/// 2. the module the name was imported from (see [`EntityDeps::import_sources`]),
+///    matched against file paths — a name imported from a module outside the
+///    project (`requests`, `std::fs`) is left unresolved;
+/// 3. definitions in its own file, preferring top-level ones;
+/// 4. the only symbol with that name anywhere in the project, other than
+///    data members of types (fields, class constants, enum variants).
+///
+/// Calls made on a receiver (see [`EntityDeps::receivers`]) of known type skip
+/// these steps: they resolve to the method of that type or of one of its base
+/// classes, or to nothing when the type is not declared in the project. Calls
+/// on receivers of unknown type skip step 4, which would only guess.
+///
+/// Imports of a package in `graph.externals` link the importing entity to the
+/// package node instead, with an `Imports` edge plus one edge per kind of use
+/// of the imported symbol (e.g. `Invokes` for a called function).
+///
+/// Calls, inheritance and the other code edges resolve only to code
+/// entities. `reads_state` / `writes_state` resolve to fields, constants,
+/// enum variants and globals, or to code (framework stores) when no data
+/// entity has the name; imports prefer code over data of the same name.
+///
+/// Ambiguous names are left unresolved rather than guessed. Every edge records
+/// the [`EdgeConfidence`] of the step that produced it. Overloads (entities
+/// sharing a base ID, see [`base_entity_id`]) are resolved as a set, narrowed
+/// to those whose parameter count matches an argument count the method is
+/// called with ([`EntityDeps::call_arities`]); without a match, or where the
+/// counts are unknown, an edge is created to every overload.
+///
+/// [`EntityDeps::import_sources`]: rpg_core::graph::EntityDeps::import_sources
+/// [`EntityDeps::receivers`]: rpg_core::graph::EntityDeps::receivers
+/// [`EntityDeps::call_arities`]: rpg_core::graph::EntityDeps::call_arities
 pub fn resolve_dependencies(graph: &mut RPGraph) {
-    // Build a qualified name index: "file_display:name" → id
-    let qualified_index: HashMap<String, String> = graph
-        .entities
-        .iter()
-        .map(|(id, entity)| {
-            let key = format!(
-                "{}:{}",
-                rpg_core::graph::normalize_path(&entity.file),
-                entity.name
-            );
-            (key, id.clone())
-        })
-        .collect();
-
-    // Build a simple name-to-id index for fallback matching
-    let name_to_ids: HashMap<String, Vec<String>> = {
-        let mut map: HashMap<String, Vec<String>> = HashMap::new();
-        for (id, entity) in &graph.entities {
-            map.entry(entity.name.clone()).or_default().push(id.clone());
-        }
-        map
-    };
-
     let mut edges = Vec::new();
-
-    // Collect edges from entity deps
-    let entity_pairs: Vec<(String, rpg_core::graph::EntityDeps, String)> = graph
-        .entities
-        .iter()
-        .map(|(id, e)| {
-            (
-                id.clone(),
-                e.deps.clone(),
-                rpg_core::graph::normalize_path(&e.file),
-            )
-        })
-        .collect();
-
-    for (source_id, deps, source_file) in &entity_pairs {
-        // Resolve all forward dep kinds generically
-        for (edge_kind, dep_names) in deps.forward_deps() {
-            for target_name in dep_names {
-                resolve_dep(
-                    source_id,
-                    target_name,
-                    source_file,
-                    edge_kind,
-                    &qualified_index,
-                    &name_to_ids,
-                    &mut edges,
-                );
+    {
+        let index = ResolveIndex::new(&graph.entities, &graph.externals);
+        for (source_id, source) in &graph.entities {
+            // Resolve all forward dep kinds generically
+            for (edge_kind, dep_names) in source.deps.forward_deps() {
+                for target_name in dep_names {
+                    let resolved = if edge_kind == EdgeKind::Invokes {
+                        index.resolve_call(source_id, source, target_name)
+                    } else {
+                        index
+                            .resolve(source_id, source, target_name, edge_kind, true)
+                            .into_iter()
+                            .collect()
+                    };
+                    for (targets, confidence) in resolved {
+                        for target in targets {
+                            edges.push(DependencyEdge {
+                                source: source_id.clone(),
+                                target: target.to_string(),
+                                kind: edge_kind,
+                                confidence: Some(confidence),
+                            });
+                        }
+                    }
+                }
             }
+            edges.extend(external_edges(&graph.externals, source_id, source));
         }
     }
 
@@ -315,47 +563,489 @@ pub fn resolve_dependencies(graph: &mut RPGraph) {
     graph.edges = edges;
 }
 
-/// Resolve a single dependency using qualified lookup first, then import-aware fallback.
-///
-/// The fallback only creates a cross-file edge if the target name is unambiguous
-/// (exactly one entity with that name across the entire graph). This avoids false
-/// edges for common names like `new`, `parse`, `build`, `run`.
-fn resolve_dep(
+/// Edges from `source` to the external packages it imports.
+fn external_edges(
+    externals: &BTreeMap<String, ExternalNode>,
     source_id: &str,
-    target_name: &str,
-    source_file: &str,
-    kind: EdgeKind,
-    qualified_index: &HashMap<String, String>,
-    name_to_ids: &HashMap<String, Vec<String>>,
-    edges: &mut Vec<DependencyEdge>,
-) {
-    // Try qualified lookup first: same file
-    let qualified_key = format!("{}:{}", source_file, target_name);
-    if let Some(target_id) = qualified_index.get(&qualified_key)
-        && target_id != source_id
-    {
-        edges.push(DependencyEdge {
-            source: source_id.to_string(),
-            target: target_id.clone(),
-            kind,
-        });
-        return;
+    source: &Entity,
+) -> BTreeSet<DependencyEdge> {
+    let mut edges = BTreeSet::new();
+    if externals.is_empty() || source.deps.import_sources.is_empty() {
+        return edges;
     }
+    for import in &source.deps.import_sources {
+        let Some(package) = external_package(externals, source, &import.module) else {
+            continue;
+        };
+        let kinds = source
+            .deps
+            .forward_deps()
+            .into_iter()
+            .filter(|(kind, names)| *kind != EdgeKind::Imports && names.contains(&import.symbol))
+            .map(|(kind, _)| kind);
+        for kind in std::iter::once(EdgeKind::Imports).chain(kinds) {
+            edges.insert(DependencyEdge {
+                source: source_id.to_string(),
+                target: package.id.clone(),
+                kind,
+                confidence: Some(EdgeConfidence::High),
+            });
+        }
+    }
+    edges
+}
+
+/// The declared package that `module`, as referenced from `source`, belongs
+/// to: one of the source's ecosystem whose manifest covers its file.
+fn external_package<'a>(
+    externals: &'a BTreeMap<String, ExternalNode>,
+    source: &Entity,
+    module: &str,
+) -> Option<&'a ExternalNode> {
+    let ecosystem = source_ecosystem(source)?;
+    externals.values().find(|ext| {
+        ext.ecosystem == ecosystem
+            && ext
+                .manifests
+                .iter()
+                .any(|m| source.file.starts_with(m.parent().unwrap_or(Path::new(""))))
+            && packages::provides_module(ext, module)
+    })
+}
+
+/// The package ecosystem of the language `source` is written in.
+fn source_ecosystem(source: &Entity) -> Option<PackageEcosystem> {
+    source
+        .file
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Language::from_extension)
+        .and_then(packages::ecosystem)
+}
+
+/// Maximum depth of base classes searched for inherited members.
+const MAX_INHERITANCE_DEPTH: usize = 8;
+
+/// Lookup tables shared by every name resolution.
+struct ResolveIndex<'a> {
+    entities: &'a BTreeMap<String, Entity>,
+    externals: &'a BTreeMap<String, ExternalNode>,
+    by_name: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> ResolveIndex<'a> {
+    fn new(
+        entities: &'a BTreeMap<String, Entity>,
+        externals: &'a BTreeMap<String, ExternalNode>,
+    ) -> Self {
+        let mut by_name: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (id, entity) in entities {
+            by_name.entry(&entity.name).or_default().push(id);
+        }
+        Self {
+            entities,
+            externals,
+            by_name,
+        }
+    }
+
+    /// Entities other than `source_id` named `name` that an edge of `kind`
+    /// may target (see [`resolve_dependencies`]).
+    fn candidates(&self, source_id: &str, name: &str, kind: EdgeKind) -> Vec<&'a str> {
+        let named = self
+            .by_name
+            .get(name)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|id| *id != source_id)
+            // Tests are run by their framework, not referenced by name, but may
+            // share a base class
+            .filter(|id| kind == EdgeKind::Inherits || self.entities[*id].kind != EntityKind::Test);
+        let (data, code): (Vec<&str>, Vec<&str>) =
+            named.partition(|id| self.entities[*id].kind.is_data());
+        match kind {
+            EdgeKind::ReadsState | EdgeKind::WritesState if !data.is_empty() => data,
+            EdgeKind::ReadsState | EdgeKind::WritesState => code,
+            EdgeKind::Imports if code.is_empty() => data,
+            _ => code,
+        }
+    }
+
+    /// Resolve `name` as referenced by `source` through an edge of `kind`.
+    /// Returns the target IDs (one overload set) and the confidence of the match.
+    fn resolve(
+        &self,
+        source_id: &str,
+        source: &Entity,
+        name: &str,
+        kind: EdgeKind,
+        use_class_scope: bool,
+    ) -> Option<(Vec<&'a str>, EdgeConfidence)> {
+        let candidates = self.candidates(source_id, name, kind);
+        if candidates.is_empty() {
+            return None;
+        }
+
+        // 1. Own class and its base classes
+        if use_class_scope
+            && let Some(class) = &source.parent_class
+            && let Some(targets) = self.class_member(&source.file, class, &candidates)
+        {
+            return Some((targets, EdgeConfidence::High));
+        }
+
+        // 2. The module the name was imported from
+        let mut imported_locally = false;
+        if let Some(module) = source.deps.import_source(name) {
+            let segments = module_segments(module);
+            if segments.is_empty() {
+                // `super`, `.`: somewhere in the project, but not a file we can name
+                imported_locally = true;
+            } else {
+                let best = candidates
+                    .iter()
+                    .map(|id| (*id, module_match_len(&segments, &self.entities[*id].file)))
+                    .filter(|(_, len)| *len > 0)
+                    .collect::<Vec<_>>();
+                let longest = best.iter().map(|(_, len)| *len).max()?;
+                let targets: Vec<&str> = best
+                    .into_iter()
+                    .filter(|(_, len)| *len == longest)
+                    .map(|(id, _)| id)
+                    .collect();
+                return single_symbol(targets).map(|t| (t, EdgeConfidence::High));
+            }
+        }
 
-    // Fallback: name-based lookup — only if unambiguous (exactly one match outside this file)
-    if let Some(target_ids) = name_to_ids.get(target_name) {
-        let cross_file_targets: Vec<&String> = target_ids
+        // Data members of a type are reached through a value or type that the
+        // name alone does not identify (`Aggregate::None` for a bare `None`),
+        // so from here on they are not guessed
+        let candidates: Vec<&str> = candidates
+            .into_iter()
+            .filter(|id| {
+                let e = &self.entities[*id];
+                !(e.kind.is_data() && e.parent_class.is_some())
+            })
+            .collect();
+
+        // 3. The source's own file
+        let same_file: Vec<&str> = candidates
             .iter()
-            .filter(|id| *id != source_id && !id.starts_with(&format!("{}:", source_file)))
+            .copied()
+            .filter(|id| self.entities[*id].file == source.file)
             .collect();
+        if !same_file.is_empty() {
+            if let Some(targets) = single_symbol(same_file.clone()) {
+                return Some((targets, EdgeConfidence::High));
+            }
+            let top_level: Vec<&str> = same_file
+                .into_iter()
+                .filter(|id| self.entities[*id].parent_class.is_none())
+                .collect();
+            return single_symbol(top_level).map(|t| (t, EdgeConfidence::Medium));
+        }
 
-        // Only create edge if there's exactly one candidate — refuse to guess among multiples
-        if cross_file_targets.len() == 1 {
-            edges.push(DependencyEdge {
-                source: source_id.to_string(),
-                target: cross_file_targets[0].clone(),
-                kind,
-            });
+        // 4. Project-wide, only if the name denotes a single symbol
+        let confidence = if imported_locally {
+            EdgeConfidence::Medium
+        } else {
+            EdgeConfidence::Low
+        };
+        single_symbol(candidates).map(|t| (t, confidence))
+    }
+
+    /// Resolve a called method. Calls on receivers of known type resolve to
+    /// that type's method (one overload set per receiver type), calls on
+    /// values of unknown type through [`Self::resolve`] short of a
+    /// project-wide guess, calls through a module path as by
+    /// [`Self::resolve_qualified`], and bare calls through [`Self::resolve`].
+    fn resolve_call(
+        &self,
+        source_id: &str,
+        source: &Entity,
+        method: &str,
+    ) -> Vec<(Vec<&'a str>, EdgeConfidence)> {
+        let arities = source
+            .deps
+            .call_arities
+            .iter()
+            .find(|a| a.method == method)
+            .map(|a| a.args.as_slice());
+        let receivers: Vec<&CallReceiver> = source
+            .deps
+            .receivers
+            .iter()
+            .filter(|r| r.method == method)
+            .collect();
+        if receivers.is_empty() {
+            return self
+                .resolve(source_id, source, method, EdgeKind::Invokes, true)
+                .map(|(targets, confidence)| (self.matching_arity(targets, arities), confidence))
+                .into_iter()
+                .collect();
+        }
+        let mut resolved: Vec<(Vec<&str>, EdgeConfidence)> = Vec::new();
+        for receiver in receivers {
+            let found = match (&receiver.receiver, &receiver.module) {
+                (Some(ty), _) => self
+                    .type_member(source_id, source, ty, method)
+                    .map(|targets| (targets, EdgeConfidence::High)),
+                (None, Some(path)) => self.resolve_qualified(source_id, source, method, path),
+                (None, None) => self
+                    .resolve(source_id, source, method, EdgeKind::Invokes, true)
+                    .filter(|(_, confidence)| *confidence != EdgeConfidence::Low),
+            };
+            if let Some((targets, confidence)) = found {
+                let targets = self.matching_arity(targets, arities);
+                if !resolved.iter().any(|(t, _)| *t == targets) {
+                    resolved.push((targets, confidence));
+                }
+            }
+        }
+        resolved
+    }
+
+    /// The overloads among `targets` whose parameter count matches one of the
+    /// argument counts `arities` of the calls, or all of them if none does or
+    /// the counts are unknown.
+    fn matching_arity(&self, targets: Vec<&'a str>, arities: Option<&[usize]>) -> Vec<&'a str> {
+        let Some(arities) = arities else {
+            return targets;
+        };
+        if targets.len() < 2 {
+            return targets;
+        }
+        let matching: Vec<&str> = targets
+            .iter()
+            .copied()
+            .filter(|id| {
+                let params = self.entities[*id]
+                    .signature
+                    .as_ref()
+                    .map_or(0, |s| s.parameters.len());
+                arities.contains(&params)
+            })
+            .collect();
+        if matching.is_empty() {
+            targets
+        } else {
+            matching
+        }
+    }
+
+    /// Resolve `method` called through the module path `path` (`utils::parse()`,
+    /// `os.remove()`). Paths into the standard library or a declared package
+    /// are left unresolved. Otherwise the call resolves within the files the
+    /// path names, read through the import of its first segment if there is
+    /// one; a path whose import names no project file is left unresolved, and
+    /// one neither imported nor naming a file resolves as a bare call.
+    fn resolve_qualified(
+        &self,
+        source_id: &str,
+        source: &Entity,
+        method: &str,
+        path: &str,
+    ) -> Option<(Vec<&'a str>, EdgeConfidence)> {
+        let import =
+            path_root(path).and_then(|root| Some((root, source.deps.import_source(root)?)));
+        let module = import.map_or(path, |(_, module)| module);
+        if self.is_external_module(source, module) {
+            return None;
+        }
+        let segments = module_segments(path);
+        let variants: Vec<Vec<&str>> = match import {
+            // `from app import utils` (`utils.save()` in `app/utils.py`) or
+            // `from app.models import Repo` (`Repo.create()` in `app/models.py`)
+            Some((root, module)) => {
+                let base = module_segments(module);
+                let rest = segments.iter().skip_while(|s| **s != root).skip(1);
+                let mut with_root = base.clone();
+                with_root.push(root);
+                with_root.extend(rest.clone());
+                let mut without_root = base;
+                without_root.extend(rest);
+                vec![with_root, without_root]
+            }
+            None => vec![segments],
+        };
+        let best: Vec<(&str, usize)> = self
+            .candidates(source_id, method, EdgeKind::Invokes)
+            .into_iter()
+            .map(|id| {
+                let file = &self.entities[id].file;
+                let len = variants.iter().map(|v| module_match_len(v, file)).max();
+                (id, len.unwrap_or(0))
+            })
+            .filter(|(_, len)| *len > 0)
+            .collect();
+        let Some(longest) = best.iter().map(|(_, len)| *len).max() else {
+            return match import {
+                Some(_) => None,
+                None => self.resolve(source_id, source, method, EdgeKind::Invokes, true),
+            };
+        };
+        let targets: Vec<&str> = best
+            .into_iter()
+            .filter(|(_, len)| *len == longest)
+            .map(|(id, _)| id)
+            .collect();
+        single_symbol(targets).map(|t| (t, EdgeConfidence::High))
+    }
+
+    /// Whether `module`, as referenced from `source`, lies outside the
+    /// project: in the standard library or a declared package.
+    fn is_external_module(&self, source: &Entity, module: &str) -> bool {
+        source_ecosystem(source).is_some_and(|e| packages::is_standard_library(e, module))
+            || external_package(self.externals, source, module).is_some()
+    }
+
+    /// The method `method` of the type named `ty`, as seen from `source`: a
+    /// member of the type's declaration or of its base classes, else the only
+    /// method declared for that type anywhere (Rust `impl` blocks and Go
+    /// methods may live apart from the type).
+    fn type_member(
+        &self,
+        source_id: &str,
+        source: &Entity,
+        ty: &str,
+        method: &str,
+    ) -> Option<Vec<&'a str>> {
+        let candidates = self.candidates(source_id, method, EdgeKind::Invokes);
+        if let Some(class) = self.type_declaration(source, ty)
+            && let Some(targets) = self.class_member(&class.file, &class.name, &candidates)
+        {
+            return Some(targets);
+        }
+        let members: Vec<&str> = candidates
+            .into_iter()
+            .filter(|id| {
+                let parent = self.entities[*id].parent_class.as_deref();
+                parent.and_then(receivers::type_name).as_deref() == Some(ty)
+            })
+            .collect();
+        single_symbol(members)
+    }
+
+    /// The declaration of the type named `ty` referenced by `source`: the
+    /// only one in the project, else the one in its file or in the module it
+    /// was imported from.
+    fn type_declaration(&self, source: &Entity, ty: &str) -> Option<&'a Entity> {
+        let declarations: Vec<&'a Entity> = self
+            .by_name
+            .get(ty)?
+            .iter()
+            .map(|id| &self.entities[*id])
+            .filter(|e| {
+                !e.kind.is_data() && !matches!(e.kind, EntityKind::Function | EntityKind::Method)
+            })
+            .collect();
+        if let [declaration] = declarations[..] {
+            return Some(declaration);
+        }
+        if let Some(declaration) = declarations.iter().find(|e| e.file == source.file) {
+            return Some(declaration);
+        }
+        let segments = module_segments(source.deps.import_source(ty)?);
+        let mut matches = declarations
+            .into_iter()
+            .filter(|e| module_match_len(&segments, &e.file) > 0);
+        let declaration = matches.next()?;
+        matches.next().is_none().then_some(declaration)
+    }
+
+    /// Members named like `candidates` of `class` (declared in `file`) or of
+    /// its base classes, nearest class first.
+    fn class_member(
+        &self,
+        file: &Path,
+        class: &str,
+        candidates: &[&'a str],
+    ) -> Option<Vec<&'a str>> {
+        let mut frontier = vec![(file.to_path_buf(), class.to_string())];
+        let mut visited = Vec::new();
+        for _ in 0..MAX_INHERITANCE_DEPTH {
+            let mut next = Vec::new();
+            for (file, class) in frontier {
+                if visited.contains(&(file.clone(), class.clone())) {
+                    continue;
+                }
+                let members: Vec<&str> = candidates
+                    .iter()
+                    .copied()
+                    .filter(|id| {
+                        let e = &self.entities[*id];
+                        e.file == file && e.parent_class.as_deref() == Some(class.as_str())
+                    })
+                    .collect();
+                if let Some(targets) = single_symbol(members) {
+                    return Some(targets);
+                }
+                // Locate the bases through the class entity's own scope and imports
+                let class_id = format!("{}:{}", rpg_core::graph::normalize_path(&file), class);
+                if let Some(class_entity) = self.entities.get(&class_id) {
+                    for base in &class_entity.deps.inherits {
+                        let base = base.rsplit(['.', ':']).next().unwrap_or(base);
+                        if let Some((ids, _)) =
+                            self.resolve(&class_id, class_entity, base, EdgeKind::Inherits, false)
+                        {
+                            let base_entity = &self.entities[ids[0]];
+                            next.push((base_entity.file.clone(), base_entity.name.clone()));
+                        }
+                    }
+                }
+                visited.push((file, class));
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
         }
+        None
     }
 }
+
+/// The targets if they all belong to one overload set, `None` if empty or
+/// ambiguous between distinct symbols.
+fn single_symbol(targets: Vec<&str>) -> Option<Vec<&str>> {
+    let (first, rest) = targets.split_first()?;
+    rest.iter()
+        .all(|id| base_entity_id(id) == base_entity_id(first))
+        .then_some(targets)
+}
+
+/// The first name of a module path (`std` in `std::fs`, `os` in `os.path`).
+fn path_root(path: &str) -> Option<&str> {
+    path.split(['.', ':']).find(|s| !s.is_empty())
+}
+
+/// Path segments of an import's module, without relative markers:
+/// `crate::graph` → `[graph]`, `./utils/helpers` → `[utils, helpers]`,
+/// `app.models` → `[app, models]`, `super` → `[]`.
+fn module_segments(module: &str) -> Vec<&str> {
+    module
+        .trim_matches(|c| c == '"' || c == '\'' || c == '<' || c == '>')
+        .split(['/', '\\', '.', ':'])
+        .filter(|s| !s.is_empty() && !matches!(*s, "crate" | "self" | "super" | "@" | "~"))
+        .collect()
+}
+
+/// How many trailing module segments name `file`: its path without extension
+/// (`app/models.py` for `app.models`) or its directory, for package imports
+/// (`com/acme/Store.java` for `com.acme`) and index files (`utils/index.ts`
+/// for `./utils`). 0 if neither does.
+fn module_match_len(segments: &[&str], file: &Path) -> usize {
+    let path = rpg_core::graph::normalize_path(&file.with_extension(""));
+    let parts: Vec<&str> = path.split('/').collect();
+    let dir = &parts[..parts.len() - 1];
+    [parts.as_slice(), dir]
+        .into_iter()
+        .map(|path| {
+            (1..=segments.len().min(path.len()))
+                .rev()
+                .find(|&n| segments[segments.len() - n..] == path[path.len() - n..])
+                .unwrap_or(0)
+        })
+        .max()
+        .unwrap_or(0)
+}
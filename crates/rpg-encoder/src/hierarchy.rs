@@ -183,7 +183,7 @@ fn balance_clusters(clusters: Vec<FileCluster>, target_size: usize) -> Vec<FileC
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{Entity, EntityDeps};
+    use rpg_core::graph::{Entity, EntityDeps, EntityMetadata};
     use std::path::PathBuf;
 
     fn make_module(file: &str, features: Vec<&str>) -> Entity {
@@ -200,6 +200,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
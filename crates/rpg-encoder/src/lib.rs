@@ -13,5 +13,7 @@ pub mod evolution;
 pub mod grounding;
 pub mod hierarchy;
 pub mod lift;
+pub mod merge;
 pub mod reconstruction;
 pub mod semantic_lifting;
+pub mod test_links;
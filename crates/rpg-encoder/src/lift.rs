@@ -137,7 +137,8 @@ fn normalize_field(s: &str) -> String {
             result.push(ch.to_ascii_lowercase());
         }
     }
-    result
+    // Trailing underscores (`type_`, `class_`) leave an edge space
+    result.trim().to_string()
 }
 
 /// An auto-lift rule tagged with its source paradigm's languages.
@@ -207,7 +208,9 @@ impl AutoLiftEngine {
         &self,
         raw: &RawEntity,
     ) -> Option<(Vec<String>, LiftConfidence)> {
-        let (features, has_structural_gates) = self.try_lift_internal(raw)?;
+        let Some((features, has_structural_gates)) = self.try_lift_internal(raw) else {
+            return Self::lift_from_docstring(raw);
+        };
 
         if !has_structural_gates {
             // Rules without structural fields → binary match → Accept
@@ -218,7 +221,7 @@ impl AutoLiftEngine {
         // - Accept: simple (0 branches, 0 loops, ≤2 calls)
         // - Review: moderate (exactly 1 branch, or 3+ calls, but no loops)
         // - Reject: complex (2+ branches OR any loop) — needs full LLM lifting
-        let signals = rpg_parser::signals::analyze(&raw.source_text);
+        let signals = raw.structural_signals();
         let confidence = if signals.branch_count > 1 || signals.loop_count > 0 {
             LiftConfidence::Reject
         } else if signals.branch_count == 0 && signals.loop_count == 0 && signals.call_count <= 2 {
@@ -230,6 +233,19 @@ impl AutoLiftEngine {
         Some((features, confidence))
     }
 
+    /// Fallback when no rule matches: derive a feature from the docstring summary.
+    ///
+    /// Only structurally simple entities qualify (at most one branch, no loops) —
+    /// a one-line summary under-describes anything larger. Always `Review`.
+    fn lift_from_docstring(raw: &RawEntity) -> Option<(Vec<String>, LiftConfidence)> {
+        let feature = docstring_feature(raw.metadata.docstring.as_deref()?)?;
+        let signals = raw.structural_signals();
+        if signals.branch_count > 1 || signals.loop_count > 0 {
+            return None;
+        }
+        Some((vec![feature], LiftConfidence::Review))
+    }
+
     /// Try to match an entity against auto-lift rules. First match wins.
     /// Language-specific rules are skipped when the entity's file extension
     /// doesn't belong to the rule's source language.
@@ -308,6 +324,77 @@ impl AutoLiftEngine {
     }
 }
 
+/// Turn a docstring summary into a verb-object feature.
+///
+/// Takes the first sentence, drops markup, lowercases it and de-inflects the
+/// leading verb ("Parses the config file." → "parse the config file").
+/// Returns `None` when the summary doesn't start with a verb-like word or is
+/// too long to be an atomic feature.
+fn docstring_feature(docstring: &str) -> Option<String> {
+    const MAX_WORDS: usize = 12;
+    const NON_VERBS: &[&str] = &["this", "the", "a", "an", "it", "todo", "note", "deprecated"];
+
+    let paragraph: Vec<&str> = docstring
+        .lines()
+        .map(str::trim)
+        .take_while(|l| !l.is_empty() && !l.starts_with('@'))
+        .collect();
+    let mut text = paragraph.join(" ");
+    // Strip XML doc tags (`<summary>`, `<see cref=".."/>`)
+    while let (Some(open), Some(close)) = (text.find('<'), text.find('>')) {
+        if close < open {
+            break;
+        }
+        text.replace_range(open..=close, "");
+    }
+    let sentence = text
+        .split(". ")
+        .next()?
+        .trim()
+        .trim_end_matches(['.', ':', ';']);
+
+    let words: Vec<String> = sentence.split_whitespace().map(str::to_lowercase).collect();
+    let first = words.first()?;
+    if words.len() < 2
+        || words.len() > MAX_WORDS
+        || !first.chars().all(|c| c.is_ascii_alphabetic())
+        || NON_VERBS.contains(&first.as_str())
+    {
+        return None;
+    }
+
+    let verb = deinflect_verb(first);
+    Some(
+        std::iter::once(verb.as_str())
+            .chain(words[1..].iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Third-person singular → base form ("parses" → "parse", "applies" → "apply").
+fn deinflect_verb(word: &str) -> String {
+    if word.len() <= 3 {
+        return word.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{stem}y");
+    }
+    for suffix in ["sses", "shes", "ches", "xes", "zes", "oes"] {
+        if word.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+    if word.ends_with('s')
+        && !word.ends_with("ss")
+        && !word.ends_with("us")
+        && !word.ends_with("is")
+    {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
 /// Try to auto-lift a trivial entity using the default core rules.
 ///
 /// This is a backward-compatible wrapper around `AutoLiftEngine`. For MCP
@@ -459,7 +546,7 @@ pub fn build_token_aware_batches(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::EntityKind;
+    use rpg_core::graph::{EntityKind, EntityMetadata};
 
     fn make_raw(name: &str, parent: Option<&str>, source: &str) -> RawEntity {
         make_raw_file(name, parent, source, "src/lib.rs")
@@ -475,6 +562,9 @@ mod tests {
             parent_class: parent.map(|s| s.to_string()),
             source_text: source.to_string(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
+            discriminator: None,
         }
     }
 
@@ -764,6 +854,57 @@ mod tests {
         assert_eq!(features, vec!["create default appconfig"]);
     }
 
+    #[test]
+    fn test_docstring_feature() {
+        use super::docstring_feature;
+        assert_eq!(
+            docstring_feature("Parses the config file.\n\nMore detail.").as_deref(),
+            Some("parse the config file")
+        );
+        assert_eq!(
+            docstring_feature("Applies pending migrations. Idempotent.").as_deref(),
+            Some("apply pending migrations")
+        );
+        assert_eq!(
+            docstring_feature("<summary>Fetches a user by id.</summary>").as_deref(),
+            Some("fetch a user by id")
+        );
+        assert_eq!(
+            docstring_feature("Does nothing\n@param x ignored").as_deref(),
+            Some("do nothing")
+        );
+        assert!(docstring_feature("The main entry point.").is_none());
+        assert!(docstring_feature("Helper").is_none());
+        assert!(
+            docstring_feature(
+                "Walks every node in the tree and collects each identifier that it finds along the way"
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_engine_docstring_fallback() {
+        let engine = make_engine();
+        let mut raw = make_raw(
+            "reconcile",
+            None,
+            "fn reconcile(&mut self) { self.sync(); }",
+        );
+        assert!(engine.try_lift_with_confidence(&raw).is_none());
+
+        raw.metadata.docstring = Some("Reconciles local state with the server.".to_string());
+        let (features, confidence) = engine.try_lift_with_confidence(&raw).unwrap();
+        assert_eq!(features, vec!["reconcile local state with the server"]);
+        assert_eq!(confidence, LiftConfidence::Review);
+        // Rule-only matching ignores docstrings
+        assert!(engine.try_lift(&raw).is_none());
+
+        // Loops make a one-line summary too thin
+        raw.source_text = "fn reconcile(&mut self) { for x in xs { self.sync(x); } }".to_string();
+        assert!(engine.try_lift_with_confidence(&raw).is_none());
+    }
+
     // --- normalize_field tests ---
 
     #[test]
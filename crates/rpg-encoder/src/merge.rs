@@ -0,0 +1,467 @@
+//! Semantic three-way merge of RPG graphs, used by the `merge-driver` git integration.
+//!
+//! Instead of merging serialized text, the base (`%O`), ours (`%A`) and theirs (`%B`)
+//! graphs are merged entity by entity:
+//!
+//! - structure (kind, lines, signature, dependencies) takes whichever side changed it;
+//! - semantic features take whichever side changed them, and when both did, LLM-lifted
+//!   features win over auto-generated ones;
+//! - edges are merged as sets (an edge survives unless one side removed it), and so
+//!   are external packages; Tests edges are recomputed from the merged result;
+//! - the hierarchy is rebuilt from the merged entities' `hierarchy_path`s.
+//!
+//! [`merge_shards`] applies the same rules to one file of the sharded layout.
+//!
+//! Only genuinely ambiguous cases are reported as [`MergeConflict`]s: both sides lifted
+//! the same entity differently with equal confidence, or one side deleted an entity
+//! that the other changed. Ours is kept for those so the result is always loadable.
+
+use crate::test_links;
+use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, ExternalNode, RPGraph};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Which side of the merge a value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeSide {
+    Ours,
+    Theirs,
+}
+
+/// The kind of a merge conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// Both sides lifted the entity to different features with equal confidence.
+    Features,
+    /// One side deleted the entity while the other modified it.
+    ModifyDelete,
+}
+
+/// One side's semantic view of a conflicting entity.
+#[derive(Debug, Clone, Serialize)]
+pub struct SideFeatures {
+    pub features: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature_source: Option<String>,
+    pub hierarchy_path: String,
+}
+
+impl SideFeatures {
+    fn of(entity: &Entity) -> Self {
+        Self {
+            features: entity.semantic_features.clone(),
+            feature_source: entity.feature_source.clone(),
+            hierarchy_path: entity.hierarchy_path.clone(),
+        }
+    }
+}
+
+/// A merge conflict that could not be resolved automatically.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeConflict {
+    pub entity_id: String,
+    pub kind: ConflictKind,
+    /// Ours, or `None` if ours deleted the entity.
+    pub ours: Option<SideFeatures>,
+    /// Theirs, or `None` if theirs deleted the entity.
+    pub theirs: Option<SideFeatures>,
+    /// The side kept in the merged graph.
+    pub kept: MergeSide,
+}
+
+/// Summary of a three-way graph merge.
+#[derive(Debug, Default, Serialize)]
+pub struct MergeReport {
+    /// Entities added relative to the base (by either side).
+    pub entities_added: usize,
+    /// Entities removed relative to the base (by either side).
+    pub entities_removed: usize,
+    /// Entities changed on both sides whose changes were combined automatically.
+    pub entities_merged: usize,
+    /// Entities whose features were taken from theirs.
+    pub features_from_theirs: usize,
+    pub edges_added: usize,
+    pub edges_removed: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeReport {
+    /// Whether the merge completed without conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Confidence of an entity's features: LLM-lifted beats legacy (unattributed)
+/// beats module synthesis beats auto-generated beats nothing.
+fn feature_rank(entity: &Entity) -> u8 {
+    if entity.semantic_features.is_empty() {
+        return 0;
+    }
+    match entity.feature_source.as_deref() {
+        Some("llm") => 4,
+        None => 3,
+        Some("synthesized") => 2,
+        Some(_) => 1,
+    }
+}
+
+fn same_features(a: &Entity, b: &Entity) -> bool {
+    a.semantic_features == b.semantic_features && a.feature_source == b.feature_source
+}
+
+/// Structural part of an entity: everything except semantic data and deps.
+fn structure(entity: &Entity) -> serde_json::Value {
+    let mut value = serde_json::to_value(entity).unwrap_or_default();
+    if let Some(map) = value.as_object_mut() {
+        for key in [
+            "semantic_features",
+            "feature_source",
+            "hierarchy_path",
+            "deps",
+        ] {
+            map.remove(key);
+        }
+    }
+    value
+}
+
+fn same_entity(a: &Entity, b: &Entity) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Three-way merge of two JSON values holding lists: items survive unless one
+/// side removed them, new items from either side are kept (ours first).
+fn merge_lists(
+    base: &[serde_json::Value],
+    ours: &[serde_json::Value],
+    theirs: &[serde_json::Value],
+) -> Vec<serde_json::Value> {
+    let mut out: Vec<serde_json::Value> = Vec::new();
+    for item in ours.iter().chain(theirs) {
+        if out.contains(item) {
+            continue;
+        }
+        let in_base = base.contains(item);
+        let keep = if in_base {
+            ours.contains(item) && theirs.contains(item)
+        } else {
+            true
+        };
+        if keep {
+            out.push(item.clone());
+        }
+    }
+    out
+}
+
+/// Merge the per-kind dependency lists of an entity list-by-list.
+fn merge_deps(base: Option<&Entity>, ours: &Entity, theirs: &Entity) -> EntityDeps {
+    let to_map = |e: &Entity| {
+        serde_json::to_value(&e.deps)
+            .ok()
+            .and_then(|v| v.as_object().cloned())
+            .unwrap_or_default()
+    };
+    let base_map = base.map(to_map).unwrap_or_default();
+    let ours_map = to_map(ours);
+    let theirs_map = to_map(theirs);
+
+    let keys: BTreeSet<&String> = ours_map.keys().chain(theirs_map.keys()).collect();
+    let list = |map: &serde_json::Map<String, serde_json::Value>, key: &str| {
+        map.get(key)
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    let mut merged = serde_json::Map::new();
+    for key in keys {
+        let items = merge_lists(
+            &list(&base_map, key),
+            &list(&ours_map, key),
+            &list(&theirs_map, key),
+        );
+        merged.insert(key.clone(), serde_json::Value::Array(items));
+    }
+
+    serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_else(|_| ours.deps.clone())
+}
+
+/// Merge an entity present on both sides. Returns the merged entity, whether
+/// theirs supplied the features, and a conflict if both lifted it differently.
+fn merge_entity(
+    id: &str,
+    base: Option<&Entity>,
+    ours: &Entity,
+    theirs: &Entity,
+) -> (Entity, bool, Option<MergeConflict>) {
+    let ours_struct_changed = base.is_none_or(|b| structure(b) != structure(ours));
+    let theirs_struct_changed = base.is_none_or(|b| structure(b) != structure(theirs));
+    // Structure follows whichever side changed it (ours when both did)
+    let structural = if !ours_struct_changed && theirs_struct_changed {
+        theirs
+    } else {
+        ours
+    };
+    let mut merged = structural.clone();
+    merged.deps = merge_deps(base, ours, theirs);
+
+    let ours_feat_changed = base.is_none_or(|b| !same_features(b, ours));
+    let theirs_feat_changed = base.is_none_or(|b| !same_features(b, theirs));
+    let mut conflict = None;
+    let features_side = if same_features(ours, theirs) || !theirs_feat_changed {
+        MergeSide::Ours
+    } else if !ours_feat_changed {
+        MergeSide::Theirs
+    } else {
+        let (ours_rank, theirs_rank) = (feature_rank(ours), feature_rank(theirs));
+        if theirs_rank > ours_rank {
+            MergeSide::Theirs
+        } else {
+            // Two differing auto-generated lifts are not worth a conflict
+            if ours_rank == theirs_rank && ours_rank >= 3 {
+                conflict = Some(MergeConflict {
+                    entity_id: id.to_string(),
+                    kind: ConflictKind::Features,
+                    ours: Some(SideFeatures::of(ours)),
+                    theirs: Some(SideFeatures::of(theirs)),
+                    kept: MergeSide::Ours,
+                });
+            }
+            MergeSide::Ours
+        }
+    };
+    let feature_source = match features_side {
+        MergeSide::Ours => ours,
+        MergeSide::Theirs => theirs,
+    };
+    merged
+        .semantic_features
+        .clone_from(&feature_source.semantic_features);
+    merged
+        .feature_source
+        .clone_from(&feature_source.feature_source);
+
+    // Hierarchy path: whichever side moved it, else follow the winning features
+    let ours_path_changed = base.is_none_or(|b| b.hierarchy_path != ours.hierarchy_path);
+    let theirs_path_changed = base.is_none_or(|b| b.hierarchy_path != theirs.hierarchy_path);
+    merged.hierarchy_path = match (ours_path_changed, theirs_path_changed) {
+        (false, true) => theirs.hierarchy_path.clone(),
+        (true, true) => feature_source.hierarchy_path.clone(),
+        _ => ours.hierarchy_path.clone(),
+    };
+
+    (merged, features_side == MergeSide::Theirs, conflict)
+}
+
+fn merge_edges(
+    base: &[DependencyEdge],
+    ours: &[DependencyEdge],
+    theirs: &[DependencyEdge],
+) -> BTreeSet<DependencyEdge> {
+    let base: BTreeSet<&DependencyEdge> = base.iter().collect();
+    let ours_set: BTreeSet<&DependencyEdge> = ours.iter().collect();
+    let theirs_set: BTreeSet<&DependencyEdge> = theirs.iter().collect();
+    ours.iter()
+        .chain(theirs)
+        .filter(|e| !base.contains(e) || (ours_set.contains(e) && theirs_set.contains(e)))
+        .cloned()
+        .collect()
+}
+
+/// Merge `ours` and `theirs`, both descended from `base`, into a new graph.
+///
+/// The result has its hierarchy rebuilt, grounded and re-aggregated, containment
+/// edges re-materialized, and metadata refreshed.
+pub fn merge_graphs(base: &RPGraph, ours: &RPGraph, theirs: &RPGraph) -> (RPGraph, MergeReport) {
+    // Containment edges are re-materialized from the rebuilt hierarchy below, and
+    // Tests edges are recomputed since a set merge can miss links made on both sides.
+    let derived = |e: &DependencyEdge| matches!(e.kind, EdgeKind::Contains | EdgeKind::Tests);
+    let (mut graph, report) = merge_parts(base, ours, theirs, derived);
+    test_links::compute_test_edges(&mut graph);
+
+    // Rebuild V_H from the merged hierarchy paths and re-derive everything on top of it
+    graph.rebuild_hierarchy_from_paths();
+    graph.materialize_containment_edges();
+    graph.refresh_metadata();
+
+    (graph, report)
+}
+
+/// Merge three versions of a single shard of the sharded layout, each read with
+/// [`rpg_core::sharded::read_shard`].
+///
+/// Nothing is re-derived: a shard holds only part of the graph, and the hierarchy,
+/// containment edges and counts are rebuilt when the merged layout is loaded.
+pub fn merge_shards(base: &RPGraph, ours: &RPGraph, theirs: &RPGraph) -> (RPGraph, MergeReport) {
+    merge_parts(base, ours, theirs, |_| false)
+}
+
+/// Merge entities, edges, externals, file index and graph-level metadata.
+/// Edges matching `derived` are dropped so the caller can recompute them.
+fn merge_parts(
+    base: &RPGraph,
+    ours: &RPGraph,
+    theirs: &RPGraph,
+    derived: impl Fn(&DependencyEdge) -> bool,
+) -> (RPGraph, MergeReport) {
+    let mut report = MergeReport::default();
+    let mut entities: BTreeMap<String, Entity> = BTreeMap::new();
+
+    let ids: BTreeSet<&String> = base
+        .entities
+        .keys()
+        .chain(ours.entities.keys())
+        .chain(theirs.entities.keys())
+        .collect();
+
+    for id in ids {
+        let b = base.entities.get(id);
+        let o = ours.entities.get(id);
+        let t = theirs.entities.get(id);
+        match (b, o, t) {
+            (_, Some(o), Some(t)) => {
+                if same_entity(o, t) {
+                    entities.insert(id.clone(), o.clone());
+                    if b.is_none() {
+                        report.entities_added += 1;
+                    }
+                    continue;
+                }
+                let (merged, from_theirs, conflict) = merge_entity(id, b, o, t);
+                if b.is_none() {
+                    report.entities_added += 1;
+                } else if b.is_some_and(|b| !same_entity(b, o) && !same_entity(b, t)) {
+                    report.entities_merged += 1;
+                }
+                if from_theirs {
+                    report.features_from_theirs += 1;
+                }
+                report.conflicts.extend(conflict);
+                entities.insert(id.clone(), merged);
+            }
+            (None, Some(e), None) | (None, None, Some(e)) => {
+                report.entities_added += 1;
+                entities.insert(id.clone(), e.clone());
+            }
+            (Some(b), Some(o), None) => {
+                if same_entity(b, o) {
+                    report.entities_removed += 1;
+                } else {
+                    report.conflicts.push(MergeConflict {
+                        entity_id: id.clone(),
+                        kind: ConflictKind::ModifyDelete,
+                        ours: Some(SideFeatures::of(o)),
+                        theirs: None,
+                        kept: MergeSide::Ours,
+                    });
+                    entities.insert(id.clone(), o.clone());
+                }
+            }
+            (Some(b), None, Some(t)) => {
+                if same_entity(b, t) {
+                    report.entities_removed += 1;
+                } else {
+                    report.conflicts.push(MergeConflict {
+                        entity_id: id.clone(),
+                        kind: ConflictKind::ModifyDelete,
+                        ours: None,
+                        theirs: Some(SideFeatures::of(t)),
+                        kept: MergeSide::Theirs,
+                    });
+                    entities.insert(id.clone(), t.clone());
+                }
+            }
+            (Some(_), None, None) => report.entities_removed += 1,
+            (None, None, None) => {}
+        }
+    }
+
+    // Edges: set merge, then drop dependency edges touching entities that are gone
+    let known: HashSet<&String> = base
+        .entities
+        .keys()
+        .chain(ours.entities.keys())
+        .chain(theirs.entities.keys())
+        .collect();
+    let dangling = |id: &String| known.contains(id) && !entities.contains_key(id);
+
+    // External packages survive unless one side removed them, ours winning on changes
+    let mut externals: BTreeMap<String, ExternalNode> = BTreeMap::new();
+    for (id, ext) in theirs.externals.iter().chain(&ours.externals) {
+        if !base.externals.contains_key(id)
+            || (ours.externals.contains_key(id) && theirs.externals.contains_key(id))
+        {
+            externals.insert(id.clone(), ext.clone());
+        }
+    }
+    let dropped = |id: &String| {
+        (base.externals.contains_key(id) || ours.externals.contains_key(id))
+            && !externals.contains_key(id)
+    };
+
+    let edges: Vec<DependencyEdge> = merge_edges(&base.edges, &ours.edges, &theirs.edges)
+        .into_iter()
+        .filter(|e| !derived(e) && !dangling(&e.source) && !dangling(&e.target))
+        .filter(|e| !dropped(&e.target))
+        .collect();
+    let ours_deps: BTreeSet<&DependencyEdge> = ours.edges.iter().filter(|e| !derived(e)).collect();
+    report.edges_added = edges.iter().filter(|e| !ours_deps.contains(e)).count();
+    let merged_set: BTreeSet<&DependencyEdge> = edges.iter().collect();
+    report.edges_removed = ours_deps
+        .iter()
+        .filter(|e| !merged_set.contains(*e))
+        .count();
+
+    let mut graph = ours.clone();
+    graph.created_at = ours.created_at.min(theirs.created_at);
+    graph.metadata.semantic_hierarchy =
+        ours.metadata.semantic_hierarchy || theirs.metadata.semantic_hierarchy;
+    if ours.metadata.repo_summary == base.metadata.repo_summary {
+        graph
+            .metadata
+            .repo_summary
+            .clone_from(&theirs.metadata.repo_summary);
+    }
+    for lang in &theirs.metadata.languages {
+        if !graph.metadata.languages.contains(lang) {
+            graph.metadata.languages.push(lang.clone());
+        }
+    }
+    for paradigm in &theirs.metadata.paradigms {
+        if !graph.metadata.paradigms.contains(paradigm) {
+            graph.metadata.paradigms.push(paradigm.clone());
+        }
+    }
+
+    // File index: ours' order first, then theirs', then anything left over
+    let mut file_index: BTreeMap<std::path::PathBuf, Vec<String>> = BTreeMap::new();
+    let mut placed: HashSet<&str> = HashSet::new();
+    for (file, ids) in ours.file_index.iter().chain(&theirs.file_index) {
+        for id in ids {
+            if let Some(entity) = entities.get(id)
+                && entity.file == *file
+                && placed.insert(id.as_str())
+            {
+                file_index.entry(file.clone()).or_default().push(id.clone());
+            }
+        }
+    }
+    for (id, entity) in &entities {
+        if placed.insert(id.as_str()) {
+            file_index
+                .entry(entity.file.clone())
+                .or_default()
+                .push(id.clone());
+        }
+    }
+
+    graph.entities = entities;
+    graph.edges = edges;
+    graph.file_index = file_index;
+    graph.externals = externals;
+
+    (graph, report)
+}
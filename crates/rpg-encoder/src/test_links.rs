@@ -0,0 +1,225 @@
+//! Tests edge computation — link each test to the code it exercises.
+//!
+//! A test is linked to what it calls, renders or dispatches to directly, and to
+//! the state it reads or writes. Code those callees reach in turn is found when
+//! tests are selected (`rpg_nav::test_selection`), so the number of edges grows
+//! with the tests' own call sites rather than with the depth of the call graph.
+//! Other tests are never linked, so a suite that calls a shared helper does not
+//! pick up its sibling tests.
+
+use rpg_core::graph::{DependencyEdge, EdgeKind, EntityKind, RPGraph};
+use std::collections::BTreeSet;
+
+/// Compute Tests edges from every Test entity to the code it uses directly.
+///
+/// This is idempotent: it clears all existing Tests edges first, then recomputes
+/// from the current dependency edges. Call it after dependency resolution.
+///
+/// Tests edges live only in `graph.edges`; they are not mirrored into entity
+/// dep vectors.
+pub fn compute_test_edges(graph: &mut RPGraph) {
+    graph.edges.retain(|e| e.kind != EdgeKind::Tests);
+
+    let kind_of = |id: &str| graph.entities.get(id).map(|e| e.kind);
+    let links: BTreeSet<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|e| {
+            matches!(
+                e.kind,
+                EdgeKind::Invokes
+                    | EdgeKind::Renders
+                    | EdgeKind::Dispatches
+                    | EdgeKind::ReadsState
+                    | EdgeKind::WritesState
+            )
+        })
+        .filter(|e| kind_of(&e.source) == Some(EntityKind::Test))
+        .filter(|e| kind_of(&e.target).is_some_and(|k| k != EntityKind::Test))
+        .map(|e| (e.source.as_str(), e.target.as_str()))
+        .collect();
+
+    let new_edges: Vec<DependencyEdge> = links
+        .into_iter()
+        .map(|(test, target)| DependencyEdge {
+            source: test.to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Tests,
+            confidence: None,
+        })
+        .collect();
+
+    graph.edges.extend(new_edges);
+    graph.refresh_metadata();
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpg_core::graph::{Entity, EntityDeps, EntityMetadata};
+    use std::path::PathBuf;
+
+    fn make_entity(id: &str, kind: EntityKind) -> Entity {
+        let (file, name) = id.split_once(':').unwrap();
+        Entity {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind,
+            file: PathBuf::from(file),
+            line_start: 1,
+            line_end: 10,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
+        }
+    }
+
+    fn make_graph(entities: &[(&str, EntityKind)], edges: &[(&str, &str, EdgeKind)]) -> RPGraph {
+        let mut graph = RPGraph::new("rust");
+        for (id, kind) in entities {
+            graph
+                .entities
+                .insert((*id).to_string(), make_entity(id, *kind));
+        }
+        for (source, target, kind) in edges {
+            graph.edges.push(DependencyEdge {
+                source: (*source).to_string(),
+                target: (*target).to_string(),
+                kind: *kind,
+                confidence: None,
+            });
+        }
+        graph
+    }
+
+    fn tested_by(graph: &RPGraph, test: &str) -> Vec<String> {
+        let mut targets: Vec<String> = graph
+            .edges
+            .iter()
+            .filter(|e| e.kind == EdgeKind::Tests && e.source == test)
+            .map(|e| e.target.clone())
+            .collect();
+        targets.sort();
+        targets
+    }
+
+    #[test]
+    fn test_links_only_direct_uses() {
+        let mut graph = make_graph(
+            &[
+                ("tests/api.rs:login_works", EntityKind::Test),
+                ("src/auth.rs:login", EntityKind::Function),
+                ("src/auth.rs:hash", EntityKind::Function),
+                ("src/auth.rs:SESSIONS", EntityKind::Global),
+                ("src/auth.rs:unused", EntityKind::Function),
+            ],
+            &[
+                (
+                    "tests/api.rs:login_works",
+                    "src/auth.rs:login",
+                    EdgeKind::Invokes,
+                ),
+                (
+                    "tests/api.rs:login_works",
+                    "src/auth.rs:SESSIONS",
+                    EdgeKind::ReadsState,
+                ),
+                (
+                    "tests/api.rs:login_works",
+                    "src/auth.rs:hash",
+                    EdgeKind::DataFlow,
+                ),
+                ("src/auth.rs:login", "src/auth.rs:hash", EdgeKind::Invokes),
+            ],
+        );
+
+        compute_test_edges(&mut graph);
+
+        assert_eq!(
+            tested_by(&graph, "tests/api.rs:login_works"),
+            vec!["src/auth.rs:SESSIONS", "src/auth.rs:login"]
+        );
+        assert_eq!(graph.metadata.test_edges, 2);
+    }
+
+    #[test]
+    fn test_links_skip_other_tests() {
+        let mut graph = make_graph(
+            &[
+                ("tests/a.py:test_one", EntityKind::Test),
+                ("tests/a.py:test_two", EntityKind::Test),
+                ("tests/a.py:make_user", EntityKind::Function),
+            ],
+            &[
+                (
+                    "tests/a.py:test_one",
+                    "tests/a.py:test_two",
+                    EdgeKind::Invokes,
+                ),
+                (
+                    "tests/a.py:test_two",
+                    "tests/a.py:make_user",
+                    EdgeKind::Invokes,
+                ),
+            ],
+        );
+
+        compute_test_edges(&mut graph);
+
+        assert!(tested_by(&graph, "tests/a.py:test_one").is_empty());
+        assert_eq!(
+            tested_by(&graph, "tests/a.py:test_two"),
+            vec!["tests/a.py:make_user"]
+        );
+    }
+
+    /// A test calling into a chain of `depth` functions.
+    fn chain_graph(depth: usize) -> RPGraph {
+        let ids: Vec<String> = (0..depth).map(|i| format!("src/chain.rs:f{i}")).collect();
+        let mut entities: Vec<(&str, EntityKind)> = vec![("tests/chain.rs:deep", EntityKind::Test)];
+        entities.extend(ids.iter().map(|id| (id.as_str(), EntityKind::Function)));
+        let mut edges = vec![("tests/chain.rs:deep", ids[0].as_str(), EdgeKind::Invokes)];
+        edges.extend(
+            ids.windows(2)
+                .map(|w| (w[0].as_str(), w[1].as_str(), EdgeKind::Invokes)),
+        );
+        make_graph(&entities, &edges)
+    }
+
+    #[test]
+    fn test_links_do_not_grow_with_call_depth() {
+        for depth in [1, 4, 32] {
+            let mut graph = chain_graph(depth);
+
+            compute_test_edges(&mut graph);
+
+            assert_eq!(graph.metadata.test_edges, 1, "call chain of depth {depth}");
+        }
+    }
+
+    #[test]
+    fn test_links_are_recomputed_idempotently() {
+        let mut graph = make_graph(
+            &[
+                ("tests/x.rs:t", EntityKind::Test),
+                ("src/x.rs:f", EntityKind::Function),
+            ],
+            &[("tests/x.rs:t", "src/x.rs:f", EdgeKind::Invokes)],
+        );
+
+        compute_test_edges(&mut graph);
+        compute_test_edges(&mut graph);
+
+        assert_eq!(tested_by(&graph, "tests/x.rs:t"), vec!["src/x.rs:f"]);
+        assert_eq!(graph.metadata.test_edges, 1);
+    }
+}
@@ -18,6 +18,8 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -55,6 +57,7 @@ fn test_apply_deletions_cleans_edges() {
         source: "a.rs:foo".to_string(),
         target: "b.rs:bar".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
 
     apply_deletions(&mut graph, &[PathBuf::from("a.rs")]);
@@ -185,6 +188,8 @@ fn make_lifted_entity(id: &str, name: &str, file: &str, features: &[&str]) -> En
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -202,6 +207,8 @@ fn make_module_entity(id: &str, name: &str, file: &str, features: &[&str]) -> En
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -242,6 +249,14 @@ fn test_merge_features_restores_hierarchy_path() {
         new_graph.entities["a.rs:foo"].hierarchy_path,
         "Auth/sessions/validate"
     );
+
+    // The delta records both restorations on the one entity
+    assert_eq!(stats.delta.modified.len(), 1);
+    assert_eq!(stats.delta.feature_changes()[0].after, ["test"]);
+    assert_eq!(
+        stats.delta.hierarchy_moves()[0].to,
+        "Auth/sessions/validate"
+    );
 }
 
 #[test]
@@ -16,6 +16,8 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -346,3 +348,954 @@ fn test_resolve_reads_state_to_selector() {
         "selector should have state_read_by reverse edge"
     );
 }
+
+#[test]
+fn test_resolve_invokes_reaches_every_overload() {
+    let mut graph = RPGraph::new("java");
+    let mut caller = make_entity("App.java:App::main", "main", "App.java");
+    caller.deps.invokes.push("put".to_string());
+    graph.insert_entity(caller);
+    graph.insert_entity(make_entity("Store.java:Store::put", "put", "Store.java"));
+    graph.insert_entity(make_entity("Store.java:Store::put#2", "put", "Store.java"));
+
+    resolve_dependencies(&mut graph);
+
+    let mut targets: Vec<&str> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == "App.java:App::main" && e.kind == EdgeKind::Invokes)
+        .map(|e| e.target.as_str())
+        .collect();
+    targets.sort_unstable();
+    assert_eq!(
+        targets,
+        vec!["Store.java:Store::put", "Store.java:Store::put#2"]
+    );
+
+    let overload = graph.get_entity("Store.java:Store::put#2").unwrap();
+    assert!(
+        overload
+            .deps
+            .invoked_by
+            .contains(&"App.java:App::main".to_string())
+    );
+}
+
+#[test]
+fn test_resolve_same_file_overload_call() {
+    let mut graph = RPGraph::new("java");
+    // Constructor chaining: Foo(int) delegates to Foo()
+    let mut chained = make_entity("Foo.java:Foo::Foo#1", "Foo", "Foo.java");
+    chained.deps.invokes.push("Foo".to_string());
+    graph.insert_entity(chained);
+    graph.insert_entity(make_entity("Foo.java:Foo::Foo", "Foo", "Foo.java"));
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(graph.edges.len(), 1);
+    assert_eq!(graph.edges[0].source, "Foo.java:Foo::Foo#1");
+    assert_eq!(graph.edges[0].target, "Foo.java:Foo::Foo");
+}
+
+#[test]
+fn test_resolve_overloaded_call_by_argument_count() {
+    let dir = tempfile::tempdir().unwrap();
+    let java = ground_files(
+        dir.path(),
+        "java",
+        &[
+            (
+                "Store.java",
+                "class Store {\n    void put(String k) {}\n    void put(String k, int v) {}\n    \
+                 void reset() { put(\"x\", 0); }\n}\n",
+            ),
+            (
+                "App.java",
+                "class App {\n    void one(Store s) { s.put(\"a\"); }\n    \
+                 void both(Store s) { s.put(\"a\"); s.put(\"a\", 1); }\n}\n",
+            ),
+        ],
+    );
+    let targets = |graph: &RPGraph, source: &str| -> Vec<String> {
+        invoke_targets(graph, source)
+            .into_iter()
+            .map(|(t, _)| t.to_string())
+            .collect()
+    };
+    assert_eq!(
+        targets(&java, "App.java:App::one"),
+        vec!["Store.java:Store::put"]
+    );
+    assert_eq!(
+        targets(&java, "App.java:App::both"),
+        vec!["Store.java:Store::put", "Store.java:Store::put#2"]
+    );
+    assert_eq!(
+        targets(&java, "Store.java:Store::reset"),
+        vec!["Store.java:Store::put#2"]
+    );
+
+    let cpp = ground_files(
+        dir.path(),
+        "cpp",
+        &[(
+            "store.cpp",
+            "void put(int k) {}\nvoid put(int k, int v) {}\nvoid run() { put(1, 2); }\n",
+        )],
+    );
+    assert_eq!(targets(&cpp, "store.cpp:run"), vec!["store.cpp:put#2"]);
+}
+
+fn import(entity: &mut Entity, symbol: &str, module: &str) {
+    entity.deps.imports.push(symbol.to_string());
+    entity.deps.import_sources.push(ImportSource {
+        symbol: symbol.to_string(),
+        module: module.to_string(),
+    });
+}
+
+fn method(id: &str, name: &str, file: &str, class: &str) -> Entity {
+    let mut entity = make_entity(id, name, file);
+    entity.kind = EntityKind::Method;
+    entity.parent_class = Some(class.to_string());
+    entity
+}
+
+fn invoke_targets<'a>(graph: &'a RPGraph, source: &str) -> Vec<(&'a str, Option<EdgeConfidence>)> {
+    let mut targets: Vec<_> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == EdgeKind::Invokes)
+        .map(|e| (e.target.as_str(), e.confidence))
+        .collect();
+    targets.sort();
+    targets
+}
+
+#[test]
+fn test_resolve_follows_import_module() {
+    let mut graph = RPGraph::new("python");
+    let mut caller = make_entity("app/views.py:index", "index", "app/views.py");
+    caller.deps.invokes.push("save".to_string());
+    import(&mut caller, "save", "app.models");
+    graph.insert_entity(caller);
+    graph.insert_entity(make_entity("app/models.py:save", "save", "app/models.py"));
+    graph.insert_entity(make_entity(
+        "tools/backup.py:save",
+        "save",
+        "tools/backup.py",
+    ));
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "app/views.py:index"),
+        vec![("app/models.py:save", Some(EdgeConfidence::High))]
+    );
+}
+
+#[test]
+fn test_resolve_import_matches_package_and_crate_paths() {
+    let mut graph = RPGraph::new("java");
+    let mut caller = make_entity("src/App.java:App::main", "main", "src/App.java");
+    caller.deps.inherits.push("Store".to_string());
+    import(&mut caller, "Store", "com.acme.data");
+    graph.insert_entity(caller);
+    let mut store = make_entity(
+        "src/com/acme/data/Store.java:Store",
+        "Store",
+        "src/com/acme/data/Store.java",
+    );
+    store.kind = EntityKind::Class;
+    graph.insert_entity(store);
+    let mut other = make_entity(
+        "src/legacy/Store.java:Store",
+        "Store",
+        "src/legacy/Store.java",
+    );
+    other.kind = EntityKind::Class;
+    graph.insert_entity(other);
+
+    let mut user = make_entity(
+        "crates/cli/src/main.rs:run",
+        "run",
+        "crates/cli/src/main.rs",
+    );
+    user.deps.invokes.push("load".to_string());
+    import(&mut user, "load", "rpg_core::storage");
+    graph.insert_entity(user);
+    graph.insert_entity(make_entity(
+        "crates/rpg-core/src/storage.rs:load",
+        "load",
+        "crates/rpg-core/src/storage.rs",
+    ));
+    graph.insert_entity(make_entity(
+        "crates/rpg-core/src/config.rs:load",
+        "load",
+        "crates/rpg-core/src/config.rs",
+    ));
+
+    resolve_dependencies(&mut graph);
+
+    let store = graph
+        .edges
+        .iter()
+        .find(|e| e.source == "src/App.java:App::main")
+        .unwrap();
+    assert_eq!(store.target, "src/com/acme/data/Store.java:Store");
+    assert_eq!(
+        invoke_targets(&graph, "crates/cli/src/main.rs:run"),
+        vec![(
+            "crates/rpg-core/src/storage.rs:load",
+            Some(EdgeConfidence::High)
+        )]
+    );
+}
+
+#[test]
+fn test_resolve_leaves_external_imports_unresolved() {
+    let mut graph = RPGraph::new("python");
+    let mut caller = make_entity("client.py:fetch", "fetch", "client.py");
+    caller.deps.invokes.push("get".to_string());
+    import(&mut caller, "get", "requests");
+    graph.insert_entity(caller);
+    // The only `get` in the project is not the one imported
+    graph.insert_entity(make_entity("cache.py:get", "get", "cache.py"));
+
+    resolve_dependencies(&mut graph);
+
+    assert!(graph.edges.is_empty(), "edges: {:?}", graph.edges);
+}
+
+#[test]
+fn test_resolve_leaves_ambiguous_names_unresolved() {
+    let mut graph = RPGraph::new("python");
+    let mut caller = make_entity("main.py:run", "run", "main.py");
+    caller.deps.invokes.push("save".to_string());
+    graph.insert_entity(caller);
+    graph.insert_entity(make_entity("a.py:save", "save", "a.py"));
+    graph.insert_entity(make_entity("b.py:save", "save", "b.py"));
+
+    resolve_dependencies(&mut graph);
+
+    assert!(graph.edges.is_empty(), "edges: {:?}", graph.edges);
+}
+
+#[test]
+fn test_resolve_prefers_callers_class() {
+    let mut graph = RPGraph::new("python");
+    let mut caller = method("m.py:Order::submit", "submit", "m.py", "Order");
+    caller.deps.invokes.push("save".to_string());
+    graph.insert_entity(caller);
+    graph.insert_entity(method("m.py:Order::save", "save", "m.py", "Order"));
+    graph.insert_entity(method("m.py:User::save", "save", "m.py", "User"));
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "m.py:Order::submit"),
+        vec![("m.py:Order::save", Some(EdgeConfidence::High))]
+    );
+}
+
+#[test]
+fn test_resolve_inherited_member_through_imported_base() {
+    let mut graph = RPGraph::new("python");
+    let mut class = make_entity("shop/order.py:Order", "Order", "shop/order.py");
+    class.kind = EntityKind::Class;
+    class.deps.inherits.push("Model".to_string());
+    import(&mut class, "Model", "db.base");
+    graph.insert_entity(class);
+    let mut caller = method(
+        "shop/order.py:Order::submit",
+        "submit",
+        "shop/order.py",
+        "Order",
+    );
+    caller.deps.invokes.push("save".to_string());
+    graph.insert_entity(caller);
+
+    let mut base = make_entity("db/base.py:Model", "Model", "db/base.py");
+    base.kind = EntityKind::Class;
+    graph.insert_entity(base);
+    graph.insert_entity(method(
+        "db/base.py:Model::save",
+        "save",
+        "db/base.py",
+        "Model",
+    ));
+    graph.insert_entity(method(
+        "cli/io.py:Writer::save",
+        "save",
+        "cli/io.py",
+        "Writer",
+    ));
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "shop/order.py:Order::submit"),
+        vec![("db/base.py:Model::save", Some(EdgeConfidence::High))]
+    );
+}
+
+#[test]
+fn test_resolve_same_file_prefers_top_level() {
+    let mut graph = RPGraph::new("python");
+    let mut caller = make_entity("util.py:main", "main", "util.py");
+    caller.deps.invokes.push("parse".to_string());
+    graph.insert_entity(caller);
+    graph.insert_entity(make_entity("util.py:parse", "parse", "util.py"));
+    graph.insert_entity(method(
+        "util.py:Reader::parse",
+        "parse",
+        "util.py",
+        "Reader",
+    ));
+    graph.insert_entity(make_entity("other.py:parse", "parse", "other.py"));
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "util.py:main"),
+        vec![("util.py:parse", Some(EdgeConfidence::Medium))]
+    );
+}
+
+#[test]
+fn test_resolve_name_only_match_is_low_confidence() {
+    let mut graph = RPGraph::new("rust");
+    let mut caller = make_entity("a.rs:caller", "caller", "a.rs");
+    caller.deps.invokes.push("callee".to_string());
+    graph.insert_entity(caller);
+    graph.insert_entity(make_entity("b.rs:callee", "callee", "b.rs"));
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(graph.edges[0].confidence, Some(EdgeConfidence::Low));
+}
+
+fn package(name: &str, ecosystem: PackageEcosystem, manifest: &str) -> ExternalNode {
+    ExternalNode {
+        id: ExternalNode::id_for(name),
+        name: name.to_string(),
+        ecosystem,
+        version: None,
+        manifests: vec![PathBuf::from(manifest)],
+    }
+}
+
+fn external_edges(graph: &RPGraph) -> Vec<(&str, &str, EdgeKind)> {
+    let mut edges: Vec<_> = graph
+        .edges
+        .iter()
+        .filter(|e| graph.externals.contains_key(&e.target))
+        .map(|e| (e.source.as_str(), e.target.as_str(), e.kind))
+        .collect();
+    edges.sort();
+    edges
+}
+
+#[test]
+fn test_resolve_links_imports_to_external_packages() {
+    let mut graph = RPGraph::new("python");
+    for ext in [
+        package("requests", PackageEcosystem::PyPI, "requirements.txt"),
+        package("PyYAML", PackageEcosystem::PyPI, "requirements.txt"),
+        package("lodash", PackageEcosystem::Npm, "package.json"),
+    ] {
+        graph.externals.insert(ext.id.clone(), ext);
+    }
+    let mut fetch = make_entity("client.py:fetch", "fetch", "client.py");
+    fetch.deps.invokes.push("get".to_string());
+    import(&mut fetch, "get", "requests");
+    graph.insert_entity(fetch);
+    let mut load = make_entity("config.py:load", "load", "config.py");
+    load.deps.invokes.push("safe_load".to_string());
+    import(&mut load, "safe_load", "yaml");
+    // `get` from a local module is not the package's
+    import(&mut load, "get", ".cache");
+    graph.insert_entity(load);
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        external_edges(&graph),
+        vec![
+            ("client.py:fetch", "ext:requests", EdgeKind::Imports),
+            ("client.py:fetch", "ext:requests", EdgeKind::Invokes),
+            ("config.py:load", "ext:PyYAML", EdgeKind::Imports),
+            ("config.py:load", "ext:PyYAML", EdgeKind::Invokes),
+        ]
+    );
+    assert!(
+        graph
+            .edges
+            .iter()
+            .filter(|e| e.target.starts_with("ext:"))
+            .all(|e| e.confidence == Some(EdgeConfidence::High))
+    );
+    graph.refresh_metadata();
+    let report = rpg_core::validate::validate(&graph);
+    assert!(report.is_clean(), "findings: {:?}", report.findings);
+}
+
+#[test]
+fn test_resolve_external_packages_only_within_their_manifest() {
+    let mut graph = RPGraph::new("typescript");
+    let ext = package("react", PackageEcosystem::Npm, "web/package.json");
+    graph.externals.insert(ext.id.clone(), ext);
+    for (id, file) in [
+        ("web/src/App.tsx:App", "web/src/App.tsx"),
+        ("server/index.ts:main", "server/index.ts"),
+        ("tools/gen.py:main", "tools/gen.py"),
+    ] {
+        let mut entity = make_entity(id, "main", file);
+        entity.deps.invokes.push("useState".to_string());
+        import(&mut entity, "useState", "react");
+        graph.insert_entity(entity);
+    }
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        external_edges(&graph),
+        vec![
+            ("web/src/App.tsx:App", "ext:react", EdgeKind::Imports),
+            ("web/src/App.tsx:App", "ext:react", EdgeKind::Invokes),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_external_packages_from_nested_manifests() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("web/src")).unwrap();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace.dependencies]\nserde = \"1\"\nlocal = { path = \"local\" }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("web/package.json"),
+        r#"{"dependencies": {"react": "^18.2.0"}}"#,
+    )
+    .unwrap();
+    // Not an ancestor of any indexed file
+    std::fs::create_dir_all(root.join("docs")).unwrap();
+    std::fs::write(root.join("docs/requirements.txt"), "mkdocs\n").unwrap();
+
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity("src/lib.rs:run", "run", "src/lib.rs"));
+    graph.insert_entity(make_entity("web/src/App.tsx:App", "App", "web/src/App.tsx"));
+
+    rpg_encoder::grounding::collect_external_packages(&mut graph, root);
+
+    let ids: Vec<&str> = graph.externals.keys().map(String::as_str).collect();
+    assert_eq!(ids, vec!["ext:react", "ext:serde"]);
+    let react = &graph.externals["ext:react"];
+    assert_eq!(react.ecosystem, PackageEcosystem::Npm);
+    assert_eq!(react.version.as_deref(), Some("^18.2.0"));
+    assert_eq!(react.manifests, vec![PathBuf::from("web/package.json")]);
+}
+
+fn receiver(entity: &mut Entity, method: &str, receiver: &str) {
+    entity.deps.invokes.push(method.to_string());
+    entity.deps.receivers.push(CallReceiver {
+        method: method.to_string(),
+        receiver: Some(receiver.to_string()),
+        module: None,
+    });
+}
+
+#[test]
+fn test_resolve_call_through_receiver_type() {
+    let mut graph = RPGraph::new("java");
+    let mut caller = method(
+        "app/Service.java:Service::run",
+        "run",
+        "app/Service.java",
+        "Service",
+    );
+    receiver(&mut caller, "save", "Cache");
+    graph.insert_entity(caller);
+    for class in ["Repo", "Cache"] {
+        let file = format!("app/{}.java", class);
+        let mut entity = make_entity(&format!("{}:{}", file, class), class, &file);
+        entity.kind = EntityKind::Class;
+        graph.insert_entity(entity);
+        graph.insert_entity(method(
+            &format!("{}:{}::save", file, class),
+            "save",
+            &file,
+            class,
+        ));
+    }
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "app/Service.java:Service::run"),
+        vec![("app/Cache.java:Cache::save", Some(EdgeConfidence::High))]
+    );
+}
+
+#[test]
+fn test_resolve_call_on_receiver_reaches_inherited_and_impl_methods() {
+    let mut graph = RPGraph::new("rust");
+    let mut caller = make_entity("src/main.rs:main", "main", "src/main.rs");
+    receiver(&mut caller, "save", "FileStore");
+    receiver(&mut caller, "open", "Db");
+    graph.insert_entity(caller);
+
+    let mut store = make_entity("src/store.rs:FileStore", "FileStore", "src/store.rs");
+    store.kind = EntityKind::Class;
+    store.deps.inherits.push("Store".to_string());
+    graph.insert_entity(store);
+    let mut base = make_entity("src/store.rs:Store", "Store", "src/store.rs");
+    base.kind = EntityKind::Class;
+    graph.insert_entity(base);
+    graph.insert_entity(method(
+        "src/store.rs:Store::save",
+        "save",
+        "src/store.rs",
+        "Store",
+    ));
+    graph.insert_entity(method(
+        "src/cache.rs:Cache::save",
+        "save",
+        "src/cache.rs",
+        "Cache",
+    ));
+    // Generic impl block in another file than the type
+    graph.insert_entity(method(
+        "src/db/ops.rs:Db<T>::open",
+        "open",
+        "src/db/ops.rs",
+        "Db<T>",
+    ));
+    graph.insert_entity(method("src/fs.rs:Dir::open", "open", "src/fs.rs", "Dir"));
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "src/main.rs:main"),
+        vec![
+            ("src/db/ops.rs:Db<T>::open", Some(EdgeConfidence::High)),
+            ("src/store.rs:Store::save", Some(EdgeConfidence::High)),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_call_on_external_receiver_type_is_dropped() {
+    let mut graph = RPGraph::new("java");
+    let mut caller = make_entity("app/Main.java:main", "main", "app/Main.java");
+    receiver(&mut caller, "add", "List");
+    caller.deps.invokes.push("format".to_string());
+    graph.insert_entity(caller);
+    graph.insert_entity(method(
+        "app/Names.java:Names::add",
+        "add",
+        "app/Names.java",
+        "Names",
+    ));
+    graph.insert_entity(make_entity(
+        "app/Text.java:format",
+        "format",
+        "app/Text.java",
+    ));
+
+    resolve_dependencies(&mut graph);
+
+    // `list.add()` no longer links to the only project `add`; untyped calls
+    // still resolve by name
+    assert_eq!(
+        invoke_targets(&graph, "app/Main.java:main"),
+        vec![("app/Text.java:format", Some(EdgeConfidence::Low))]
+    );
+}
+
+#[test]
+fn test_populate_records_receiver_types_of_java_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("app")).unwrap();
+    let files = [
+        ("app/Repo.java", "class Repo {\n    void save() {}\n}\n"),
+        ("app/Cache.java", "class Cache {\n    void save() {}\n}\n"),
+        (
+            "app/Names.java",
+            "class Names {\n    void add(String s) {}\n}\n",
+        ),
+        (
+            "app/Service.java",
+            "class Service {\n    private Repo repo;\n\n    void run(Cache cache, List<String> names) {\n        \
+             repo.save();\n        cache.save();\n        names.add(\"x\");\n    }\n\n    \
+             void flush(Object o) {\n        o.save();\n        lookup().save();\n    }\n}\n",
+        ),
+    ];
+    let mut graph = RPGraph::new("java");
+    for (file, source) in files {
+        std::fs::write(root.join(file), source).unwrap();
+        for raw in rpg_parser::entities::extract_entities(
+            std::path::Path::new(file),
+            source,
+            rpg_parser::languages::Language::JAVA,
+        ) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, root, false, None, None);
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "app/Service.java:Service::run"),
+        vec![
+            ("app/Cache.java:Cache::save", Some(EdgeConfidence::High)),
+            ("app/Repo.java:Repo::save", Some(EdgeConfidence::High)),
+        ]
+    );
+    // `Object` is not a project type, and the value of unknown type falls
+    // back to name-based resolution, which stays ambiguous between the two
+    // `save` methods
+    let receivers: Vec<Option<&str>> = graph.entities["app/Service.java:Service::flush"]
+        .deps
+        .receivers
+        .iter()
+        .map(|r| r.receiver.as_deref())
+        .collect();
+    assert_eq!(receivers, vec![None, Some("Object")]);
+    assert!(invoke_targets(&graph, "app/Service.java:Service::flush").is_empty());
+}
+
+#[test]
+fn test_resolve_call_through_std_or_package_path_is_dropped() {
+    let mut graph = RPGraph::new("rust");
+    let ext = package("serde_json", PackageEcosystem::Cargo, "Cargo.toml");
+    graph.externals.insert(ext.id.clone(), ext);
+    let mut caller = make_entity("src/main.rs:main", "main", "src/main.rs");
+    for (method, module) in [
+        ("metadata", "std::fs"),
+        ("to_string", "serde_json"),
+        ("parse", "utils"),
+    ] {
+        caller.deps.invokes.push(method.to_string());
+        caller.deps.receivers.push(CallReceiver {
+            method: method.to_string(),
+            receiver: None,
+            module: Some(module.to_string()),
+        });
+    }
+    graph.insert_entity(caller);
+    for name in ["metadata", "to_string", "parse"] {
+        let file = format!("src/{}.rs", name);
+        graph.insert_entity(make_entity(&format!("{}:{}", file, name), name, &file));
+    }
+
+    resolve_dependencies(&mut graph);
+
+    // Only the project module path resolves by name
+    assert_eq!(
+        invoke_targets(&graph, "src/main.rs:main"),
+        vec![("src/parse.rs:parse", Some(EdgeConfidence::Low))]
+    );
+}
+
+#[test]
+fn test_populate_records_module_paths_of_rust_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    let files = [
+        (
+            "src/main.rs",
+            "fn check() -> bool {\n    std::fs::metadata(\"x\").is_ok()\n}\n",
+        ),
+        ("src/metadata.rs", "pub fn metadata() {}\n"),
+    ];
+    let mut graph = RPGraph::new("rust");
+    for (file, source) in files {
+        std::fs::write(root.join(file), source).unwrap();
+        for raw in rpg_parser::entities::extract_entities(
+            std::path::Path::new(file),
+            source,
+            rpg_parser::languages::Language::RUST,
+        ) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, root, false, None, None);
+    resolve_dependencies(&mut graph);
+
+    let receivers = &graph.entities["src/main.rs:check"].deps.receivers;
+    assert!(
+        receivers
+            .iter()
+            .any(|r| r.method == "metadata" && r.module.as_deref() == Some("std::fs")),
+        "receivers: {receivers:?}"
+    );
+    // `std::fs::metadata` is not the project's only `metadata`
+    assert!(invoke_targets(&graph, "src/main.rs:check").is_empty());
+}
+
+/// Parse `files` into a graph under `root` and ground their dependencies.
+fn ground_files(root: &std::path::Path, language: &str, files: &[(&str, &str)]) -> RPGraph {
+    let mut graph = RPGraph::new(language);
+    for (file, source) in files {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+        let extension = file.rsplit('.').next().unwrap();
+        let language = rpg_parser::languages::Language::from_extension(extension).unwrap();
+        for raw in
+            rpg_parser::entities::extract_entities(std::path::Path::new(file), source, language)
+        {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, root, false, None, None);
+    resolve_dependencies(&mut graph);
+    graph
+}
+
+#[test]
+fn test_resolve_std_calls_next_to_same_named_project_functions() {
+    let dir = tempfile::tempdir().unwrap();
+    let go = ground_files(
+        dir.path(),
+        "go",
+        &[
+            (
+                "main.go",
+                "package main\n\nimport \"strings\"\n\nfunc run() []string {\n    \
+                 return strings.Split(\"a,b\", \",\")\n}\n",
+            ),
+            (
+                "util/split.go",
+                "package util\n\nfunc Split(s string) []string {\n    return nil\n}\n",
+            ),
+        ],
+    );
+    assert!(
+        invoke_targets(&go, "main.go:run").is_empty(),
+        "{:?}",
+        go.edges
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let python = ground_files(
+        dir.path(),
+        "python",
+        &[
+            (
+                "main.py",
+                "import os\nimport requests\n\ndef clean():\n    os.remove(\"x\")\n    \
+                 requests.get(\"u\")\n",
+            ),
+            (
+                "helpers.py",
+                "def remove(path):\n    pass\n\ndef get(url):\n    pass\n",
+            ),
+        ],
+    );
+    // `requests` is not declared in a manifest here, but still imported from
+    // no project file
+    assert!(
+        invoke_targets(&python, "main.py:clean").is_empty(),
+        "{:?}",
+        python.edges
+    );
+}
+
+#[test]
+fn test_resolve_qualified_call_within_imported_module() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = ground_files(
+        dir.path(),
+        "python",
+        &[
+            (
+                "app/views.py",
+                "from app import utils\n\ndef index():\n    utils.save()\n",
+            ),
+            ("app/utils.py", "def save():\n    pass\n"),
+            ("app/models.py", "def save():\n    pass\n"),
+        ],
+    );
+    assert_eq!(
+        invoke_targets(&graph, "app/views.py:index"),
+        vec![("app/utils.py:save", Some(EdgeConfidence::High))]
+    );
+}
+
+fn state_edges(graph: &RPGraph, source: &str) -> Vec<(String, EdgeKind)> {
+    let mut edges: Vec<_> = graph
+        .edges
+        .iter()
+        .filter(|e| {
+            e.source == source && matches!(e.kind, EdgeKind::ReadsState | EdgeKind::WritesState)
+        })
+        .map(|e| (e.target.clone(), e.kind))
+        .collect();
+    edges.sort();
+    edges
+}
+
+#[test]
+fn test_resolve_state_prefers_data_and_calls_prefer_code() {
+    let mut graph = RPGraph::new("java");
+    let mut caller = method(
+        "app/Service.java:Service::run",
+        "run",
+        "app/Service.java",
+        "Service",
+    );
+    caller.deps.reads_state.push("count".to_string());
+    caller.deps.invokes.push("count".to_string());
+    caller.deps.writes_state.push("cartStore".to_string());
+    graph.insert_entity(caller);
+    let mut field = method(
+        "app/Service.java:Service::count#0",
+        "count",
+        "app/Service.java",
+        "Service",
+    );
+    field.kind = EntityKind::Field;
+    graph.insert_entity(field);
+    graph.insert_entity(method(
+        "app/Service.java:Service::count",
+        "count",
+        "app/Service.java",
+        "Service",
+    ));
+    // Framework stores are code entities; state edges still reach them
+    let mut store = make_entity("app/cart.ts:cartStore", "cartStore", "app/cart.ts");
+    store.kind = EntityKind::Store;
+    graph.insert_entity(store);
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        invoke_targets(&graph, "app/Service.java:Service::run"),
+        vec![(
+            "app/Service.java:Service::count",
+            Some(EdgeConfidence::High)
+        )]
+    );
+    assert_eq!(
+        state_edges(&graph, "app/Service.java:Service::run"),
+        vec![
+            (
+                "app/Service.java:Service::count#0".to_string(),
+                EdgeKind::ReadsState
+            ),
+            ("app/cart.ts:cartStore".to_string(), EdgeKind::WritesState),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_state_leaves_bare_names_of_members_unresolved() {
+    let mut graph = RPGraph::new("rust");
+    let mut caller = make_entity("src/query.rs:run", "run", "src/query.rs");
+    caller.deps.reads_state.push("None".to_string());
+    caller.deps.reads_state.push("LIMIT".to_string());
+    graph.insert_entity(caller);
+    // `enum Aggregate { None }` in the same file: a bare `None` is `Option::None`
+    let mut variant = method(
+        "src/query.rs:Aggregate::None",
+        "None",
+        "src/query.rs",
+        "Aggregate",
+    );
+    variant.kind = EntityKind::EnumVariant;
+    graph.insert_entity(variant);
+    let mut limit = make_entity("src/query.rs:LIMIT", "LIMIT", "src/query.rs");
+    limit.kind = EntityKind::Constant;
+    graph.insert_entity(limit);
+
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        state_edges(&graph, "src/query.rs:run"),
+        vec![("src/query.rs:LIMIT".to_string(), EdgeKind::ReadsState)]
+    );
+}
+
+#[test]
+fn test_populate_records_reads_and_writes_of_python_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("app")).unwrap();
+    let files = [
+        ("app/config.py", "TIMEOUT = 30\nretries = 0\n"),
+        (
+            "app/client.py",
+            "from app.config import TIMEOUT\nfrom app import config\n\n\
+             class Client:\n    def __init__(self):\n        self.calls = 0\n\n    \
+             def send(self):\n        self.calls += 1\n        config.retries = 0\n        \
+             return TIMEOUT\n",
+        ),
+    ];
+    let mut graph = RPGraph::new("python");
+    for (file, source) in files {
+        std::fs::write(root.join(file), source).unwrap();
+        for raw in rpg_parser::entities::extract_entities(
+            std::path::Path::new(file),
+            source,
+            rpg_parser::languages::Language::PYTHON,
+        ) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    assert_eq!(
+        graph.entities["app/config.py:TIMEOUT"].kind,
+        EntityKind::Constant
+    );
+    assert_eq!(
+        graph.entities["app/config.py:retries"].kind,
+        EntityKind::Global
+    );
+    assert_eq!(
+        graph.entities["app/client.py:Client::calls"].kind,
+        EntityKind::Field
+    );
+
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, root, false, None, None);
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        state_edges(&graph, "app/client.py:Client::__init__"),
+        vec![(
+            "app/client.py:Client::calls".to_string(),
+            EdgeKind::WritesState
+        )]
+    );
+    assert_eq!(
+        state_edges(&graph, "app/client.py:Client::send"),
+        vec![
+            (
+                "app/client.py:Client::calls".to_string(),
+                EdgeKind::ReadsState
+            ),
+            (
+                "app/client.py:Client::calls".to_string(),
+                EdgeKind::WritesState
+            ),
+            ("app/config.py:TIMEOUT".to_string(), EdgeKind::ReadsState),
+            ("app/config.py:retries".to_string(), EdgeKind::WritesState),
+        ]
+    );
+    let timeout = &graph.entities["app/config.py:TIMEOUT"];
+    assert_eq!(
+        timeout.deps.state_read_by,
+        vec!["app/client.py:Client::send"]
+    );
+}
@@ -4,7 +4,7 @@
 //! apply incremental updates → verify graph integrity.
 //! Uses the Python fixture project from tests/fixtures/python_project.
 
-use rpg_core::graph::{EntityKind, RPGraph};
+use rpg_core::graph::{EntityKind, EntityMetadata, RPGraph};
 use rpg_encoder::evolution::{
     apply_additions, apply_deletions, apply_modifications, apply_renames, check_drift_and_reroute,
     compute_drift, find_best_hierarchy_path, reroute_entity, route_new_entity, semantic_similarity,
@@ -417,6 +417,7 @@ fn test_rename_rekeys_entity_ids() {
         source: test_edge_source.clone(),
         target: test_edge_target.clone(),
         kind: rpg_core::graph::EdgeKind::Invokes,
+        confidence: None,
     });
 
     // Rename login.py → authentication.py
@@ -965,6 +966,8 @@ fn build_semantic_hierarchy_graph() -> RPGraph {
             hierarchy_path: hier_path.to_string(),
             deps: rpg_core::graph::EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         };
         graph.insert_entity(entity);
         graph.insert_into_hierarchy(hier_path, id);
@@ -1056,6 +1059,8 @@ fn test_reroute_entity_moves_in_hierarchy() {
         hierarchy_path: "DataProcessing/loading".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1115,6 +1120,8 @@ fn test_check_drift_and_reroute_below_threshold() {
         hierarchy_path: "Authentication/login".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1157,6 +1164,8 @@ fn test_check_drift_and_reroute_above_threshold() {
         hierarchy_path: "Authentication/login".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1204,6 +1213,8 @@ fn test_route_new_entity_places_in_semantic_hierarchy() {
         hierarchy_path: "src/auth/verify".to_string(), // file-path-based
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1248,6 +1259,8 @@ fn test_route_new_entity_skips_without_semantic_hierarchy() {
         hierarchy_path: "src/main".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1324,6 +1337,8 @@ fn test_check_drift_and_reroute_restores_on_no_match() {
         hierarchy_path: "Authentication".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -262,6 +262,17 @@ fn test_fixture_metadata_consistency() {
     );
 }
 
+#[test]
+fn test_fixture_passes_full_validation() {
+    let graph = build_fixture_graph();
+    let report = rpg_core::validate::validate(&graph);
+    assert!(
+        report.is_clean(),
+        "fixture graph violates invariants: {:?}",
+        report.findings
+    );
+}
+
 #[test]
 fn test_fixture_storage_roundtrip() {
     let graph = build_fixture_graph();
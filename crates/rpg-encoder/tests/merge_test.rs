@@ -0,0 +1,169 @@
+use rpg_core::graph::*;
+use rpg_encoder::merge::{ConflictKind, MergeSide, merge_graphs};
+use std::path::PathBuf;
+
+fn make_entity(id: &str, name: &str, file: &str) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        hierarchy_path: "Core/util".to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
+    }
+}
+
+fn base_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity("a.rs:foo", "foo", "a.rs"));
+    graph.insert_entity(make_entity("a.rs:bar", "bar", "a.rs"));
+    graph.insert_entity(make_entity("b.rs:baz", "baz", "b.rs"));
+    graph.edges.push(DependencyEdge {
+        source: "a.rs:foo".to_string(),
+        target: "a.rs:bar".to_string(),
+        kind: EdgeKind::Invokes,
+        confidence: None,
+    });
+    graph.refresh_metadata();
+    graph
+}
+
+fn lift(graph: &mut RPGraph, id: &str, features: &[&str], source: &str) {
+    let entity = graph.entities.get_mut(id).unwrap();
+    entity.semantic_features = features.iter().map(|f| (*f).to_string()).collect();
+    entity.feature_source = Some(source.to_string());
+}
+
+#[test]
+fn test_merge_combines_independent_changes() {
+    let base = base_graph();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+
+    lift(&mut ours, "a.rs:foo", &["parse input"], "llm");
+    ours.insert_entity(make_entity("c.rs:new_ours", "new_ours", "c.rs"));
+    lift(&mut theirs, "b.rs:baz", &["validate token"], "llm");
+    theirs.remove_entity("a.rs:bar");
+
+    let (merged, report) = merge_graphs(&base, &ours, &theirs);
+
+    assert!(report.is_clean());
+    assert_eq!(
+        merged.entities["a.rs:foo"].semantic_features,
+        ["parse input"]
+    );
+    assert_eq!(
+        merged.entities["b.rs:baz"].semantic_features,
+        ["validate token"]
+    );
+    assert!(merged.entities.contains_key("c.rs:new_ours"));
+    assert!(!merged.entities.contains_key("a.rs:bar"));
+    assert!(
+        !merged
+            .edges
+            .iter()
+            .any(|e| e.kind == EdgeKind::Invokes && e.target == "a.rs:bar"),
+        "edges to deleted entities are dropped"
+    );
+    assert_eq!(report.entities_added, 1);
+    assert_eq!(report.entities_removed, 1);
+    assert_eq!(report.features_from_theirs, 1);
+    assert_eq!(merged.metadata.total_entities, 3);
+    assert_eq!(merged.file_index[&PathBuf::from("a.rs")], ["a.rs:foo"]);
+}
+
+#[test]
+fn test_merge_prefers_llm_over_auto_features() {
+    let base = base_graph();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    lift(&mut ours, "a.rs:foo", &["auto summary"], "auto");
+    lift(&mut theirs, "a.rs:foo", &["load configuration"], "llm");
+    theirs.entities.get_mut("a.rs:foo").unwrap().hierarchy_path = "Config/load".to_string();
+
+    let (merged, report) = merge_graphs(&base, &ours, &theirs);
+
+    assert!(report.is_clean());
+    let foo = &merged.entities["a.rs:foo"];
+    assert_eq!(foo.semantic_features, ["load configuration"]);
+    assert_eq!(foo.feature_source.as_deref(), Some("llm"));
+    assert_eq!(foo.hierarchy_path, "Config/load");
+    assert!(merged.hierarchy.contains_key("Config"));
+}
+
+#[test]
+fn test_merge_reports_conflicting_llm_lifts() {
+    let base = base_graph();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    lift(&mut ours, "a.rs:foo", &["parse input"], "llm");
+    lift(&mut theirs, "a.rs:foo", &["tokenize source"], "llm");
+
+    let (merged, report) = merge_graphs(&base, &ours, &theirs);
+
+    assert_eq!(report.conflicts.len(), 1);
+    let conflict = &report.conflicts[0];
+    assert_eq!(conflict.entity_id, "a.rs:foo");
+    assert_eq!(conflict.kind, ConflictKind::Features);
+    assert_eq!(conflict.kept, MergeSide::Ours);
+    assert_eq!(
+        conflict.theirs.as_ref().unwrap().features,
+        ["tokenize source"]
+    );
+    assert_eq!(
+        merged.entities["a.rs:foo"].semantic_features,
+        ["parse input"]
+    );
+}
+
+#[test]
+fn test_merge_reports_modify_delete() {
+    let base = base_graph();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    ours.remove_entity("b.rs:baz");
+    lift(&mut theirs, "b.rs:baz", &["validate token"], "llm");
+
+    let (merged, report) = merge_graphs(&base, &ours, &theirs);
+
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].kind, ConflictKind::ModifyDelete);
+    assert_eq!(report.conflicts[0].kept, MergeSide::Theirs);
+    assert!(merged.entities.contains_key("b.rs:baz"));
+}
+
+#[test]
+fn test_merge_unions_dependency_lists() {
+    let base = base_graph();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    ours.entities
+        .get_mut("a.rs:bar")
+        .unwrap()
+        .deps
+        .invoked_by
+        .push("a.rs:foo".to_string());
+    theirs
+        .entities
+        .get_mut("a.rs:bar")
+        .unwrap()
+        .deps
+        .invoked_by
+        .push("b.rs:baz".to_string());
+
+    let (merged, report) = merge_graphs(&base, &ours, &theirs);
+
+    assert!(report.is_clean());
+    assert_eq!(
+        merged.entities["a.rs:bar"].deps.invoked_by,
+        ["a.rs:foo", "b.rs:baz"]
+    );
+}
@@ -1,4 +1,6 @@
-use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, RPGraph};
+use rpg_core::graph::{
+    DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, EntityMetadata, RPGraph,
+};
 use std::path::PathBuf;
 
 fn entity(id: &str, name: &str, hierarchy_path: &str, kind: EntityKind) -> Entity {
@@ -15,6 +17,8 @@ fn entity(id: &str, name: &str, hierarchy_path: &str, kind: EntityKind) -> Entit
         hierarchy_path: hierarchy_path.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -40,11 +44,13 @@ fn test_topological_execution_order_dependency_first() {
         source: "a".into(),
         target: "b".into(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     g.edges.push(DependencyEdge {
         source: "b".into(),
         target: "c".into(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
 
     let order = rpg_encoder::reconstruction::build_topological_execution_order(&g, false);
@@ -65,11 +71,13 @@ fn test_topological_execution_order_cycle_fallback_is_deterministic() {
         source: "a".into(),
         target: "b".into(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     g.edges.push(DependencyEdge {
         source: "b".into(),
         target: "a".into(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
 
     let order = rpg_encoder::reconstruction::build_topological_execution_order(&g, false);
@@ -104,16 +112,19 @@ fn test_schedule_reconstruction_batches_by_area_and_size() {
         source: "a".into(),
         target: "b".into(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     g.edges.push(DependencyEdge {
         source: "b".into(),
         target: "c".into(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     g.edges.push(DependencyEdge {
         source: "d".into(),
         target: "c".into(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
 
     let plan = rpg_encoder::reconstruction::schedule_reconstruction(
@@ -148,6 +148,8 @@ fn make_test_entity(id: &str, name: &str, file: &str) -> Entity {
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
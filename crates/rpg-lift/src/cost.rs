@@ -96,7 +96,7 @@ pub fn estimate_cost(
     let raw_entities =
         rpg_encoder::lift::collect_raw_entities(graph, &scope, project_root).unwrap_or_default();
 
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs_or_builtin(project_root);
     let active_paradigms: Vec<String> = graph.metadata.paradigms.clone();
     let engine = rpg_encoder::lift::AutoLiftEngine::new(&paradigm_defs, &active_paradigms);
 
@@ -108,6 +108,7 @@ pub fn estimate_cost(
             Some((_, rpg_encoder::lift::LiftConfidence::Accept)) => {
                 auto_lifted += 1;
             }
+            _ if raw.kind.is_data() => {}
             _ => {
                 llm_needed.push(raw);
             }
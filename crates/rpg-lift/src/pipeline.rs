@@ -97,7 +97,7 @@ pub fn run_pipeline(
     );
 
     // Phase 2: Auto-lift trivial entities
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs_or_builtin(config.project_root);
     let active_paradigms: Vec<String> = graph.metadata.paradigms.clone();
     let engine = AutoLiftEngine::new(&paradigm_defs, &active_paradigms);
 
@@ -126,6 +126,8 @@ pub fn run_pipeline(
                 auto_lifted += 1;
                 // Don't add to needs_llm — accept the auto-lift for autonomous mode
             }
+            // Data entities are only ever auto-lifted
+            _ if raw.kind.is_data() => {}
             _ => {
                 needs_llm.push(raw);
             }
@@ -33,7 +33,7 @@ pub(crate) fn format_dep_context(deps: &rpg_core::graph::EntityDeps) -> String {
 /// Parse a comma-separated entity type filter string into EntityKind values.
 ///
 /// Accepts entity names: function, class, method, page, layout, component,
-/// hook, store, file, module, directory.
+/// hook, store, file, module, directory, field, constant, enum_variant, global.
 /// "file" is an alias for Module (file-level entity nodes, V_L).
 /// "directory" is mapped to Module for paper-schema compatibility.
 pub(crate) fn parse_entity_type_filter(filter: &str) -> Vec<rpg_core::graph::EntityKind> {
@@ -55,11 +55,47 @@ pub(crate) fn parse_entity_type_filter(filter: &str) -> Vec<rpg_core::graph::Ent
             "middleware" => Some(rpg_core::graph::EntityKind::Middleware),
             "route" => Some(rpg_core::graph::EntityKind::Route),
             "test" => Some(rpg_core::graph::EntityKind::Test),
+            "field" => Some(rpg_core::graph::EntityKind::Field),
+            "constant" => Some(rpg_core::graph::EntityKind::Constant),
+            "enum_variant" => Some(rpg_core::graph::EntityKind::EnumVariant),
+            "global" => Some(rpg_core::graph::EntityKind::Global),
             _ => None,
         })
         .collect()
 }
 
+/// Build a metadata filter from `search_node` params; `None` when no constraint is set.
+pub(crate) fn build_metadata_filter(
+    params: &crate::params::SearchNodeParams,
+) -> Option<rpg_nav::search::MetadataFilter> {
+    let visibility = params.visibility.as_deref().map(|v| {
+        v.split(',')
+            .filter_map(|s| rpg_core::graph::Visibility::from_name(s.trim()))
+            .collect::<Vec<_>>()
+    });
+    let filter = rpg_nav::search::MetadataFilter {
+        visibility: visibility.filter(|v| !v.is_empty()),
+        is_async: params.is_async,
+        is_static: params.is_static,
+        is_abstract: params.is_abstract,
+        is_deprecated: params.is_deprecated,
+        decorator: params.decorator.clone().filter(|d| !d.trim().is_empty()),
+        has_docstring: params.has_docstring,
+    };
+    let any_set = filter.visibility.is_some()
+        || filter.decorator.is_some()
+        || [
+            filter.is_async,
+            filter.is_static,
+            filter.is_abstract,
+            filter.is_deprecated,
+            filter.has_docstring,
+        ]
+        .iter()
+        .any(Option::is_some);
+    any_set.then_some(filter)
+}
+
 /// Validate strict paper-style hierarchy path format: `Area/category/subcategory`.
 ///
 /// Rules:
@@ -113,6 +149,26 @@ pub(crate) fn hierarchy_path_exists(
     true
 }
 
+/// Record every entity `apply_hierarchy` will move for `assignments`: each assigned
+/// entity and, for a Module, every entity of its file.
+pub(crate) fn record_assignment_targets(
+    recorder: &mut rpg_core::delta::DeltaRecorder,
+    graph: &rpg_core::graph::RPGraph,
+    assignments: &std::collections::HashMap<String, String>,
+) {
+    for id in assignments.keys() {
+        recorder.entity(graph, id);
+        if let Some(entity) = graph.entities.get(id)
+            && entity.kind == rpg_core::graph::EntityKind::Module
+            && let Some(siblings) = graph.file_index.get(&entity.file)
+        {
+            for sibling in siblings {
+                recorder.entity(graph, sibling);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
@@ -58,7 +58,7 @@ impl RpgServer {
 
         // Inject paradigm-specific discovery hints
         let discovery_hints =
-            Self::collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.discovery);
+            self.collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.discovery);
         if !discovery_hints.is_empty() {
             output.push_str("\n\n## Framework-Specific Discovery Guidelines\n\n");
             output.push_str(&discovery_hints);
@@ -142,7 +142,7 @@ impl RpgServer {
 
         // Inject paradigm-specific hierarchy hints
         let hierarchy_hints =
-            Self::collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.hierarchy);
+            self.collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.hierarchy);
         if !hierarchy_hints.is_empty() {
             output.push_str("\n\n## Framework-Specific Hierarchy Patterns\n\n");
             output.push_str(&hierarchy_hints);
@@ -11,7 +11,6 @@ mod types;
 
 use anyhow::Result;
 use rmcp::ServiceExt;
-use rpg_core::storage;
 use std::path::PathBuf;
 
 use server::RpgServer;
@@ -47,7 +46,7 @@ async fn main() -> Result<()> {
                 // Detect paradigms for framework-aware classification
                 let detected_langs = RpgServer::resolve_languages(&graph.metadata);
                 let paradigm_defs =
-                    rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+                    rpg_parser::paradigms::defs::load_defs_or_builtin(&server.project_root);
                 let qcache_result =
                     rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs);
                 let active_defs = rpg_parser::paradigms::detect_paradigms_toml(
@@ -72,7 +71,7 @@ async fn main() -> Result<()> {
                 ) {
                     Ok(s) => {
                         graph.metadata.paradigms = paradigm_names;
-                        let _ = storage::save(&server.project_root, graph);
+                        let _ = server.save_graph(graph);
                         eprintln!(
                             "  Auto-update complete: +{} -{} ~{}",
                             s.entities_added, s.entities_removed, s.entities_modified
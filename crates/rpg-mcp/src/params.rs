@@ -18,6 +18,20 @@ pub(crate) struct SearchNodeParams {
     pub(crate) file_pattern: Option<String>,
     /// Comma-separated entity type filter (e.g., "function,class,method"). Valid: function, class, method, file, module.
     pub(crate) entity_type_filter: Option<String>,
+    /// Comma-separated visibility filter (e.g., "public,protected"). Valid: public, protected, internal, private.
+    pub(crate) visibility: Option<String>,
+    /// Only async (true) or only non-async (false) entities
+    pub(crate) is_async: Option<bool>,
+    /// Only static/associated (true) or only instance-level (false) entities
+    pub(crate) is_static: Option<bool>,
+    /// Only abstract (true) or only concrete (false) entities
+    pub(crate) is_abstract: Option<bool>,
+    /// Only deprecated (true) or only non-deprecated (false) entities
+    pub(crate) is_deprecated: Option<bool>,
+    /// Keep entities with a decorator/attribute/annotation containing this text (e.g., "route", "test")
+    pub(crate) decorator: Option<String>,
+    /// Only entities with (true) or without (false) a docstring
+    pub(crate) has_docstring: Option<bool>,
     /// Git commit to diff from for proximity-based ranking (e.g., "HEAD~10", "abc123"). Boosts entities in changed files and their dependencies.
     pub(crate) since_commit: Option<String>,
 }
@@ -46,9 +60,9 @@ pub(crate) struct ExploreRpgParams {
     pub(crate) direction: Option<String>,
     /// Maximum traversal depth (default: 2). Use -1 for unlimited depth.
     pub(crate) depth: Option<i64>,
-    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', or 'dispatches'
+    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', 'dispatches', 'data_flow', or 'tests'
     pub(crate) edge_filter: Option<String>,
-    /// Comma-separated entity type filter (e.g., "function,class,method"). Valid: function, class, method, file, module, page, layout, component, hook, store.
+    /// Comma-separated entity type filter (e.g., "function,class,method"). Valid: function, class, method, file, module, page, layout, component, hook, store, field, constant, enum_variant, global.
     pub(crate) entity_type_filter: Option<String>,
     /// Output format: "tree" (default, indented tree) or "compact" (pipe-delimited rows with entity_ids)
     pub(crate) format: Option<String>,
@@ -59,7 +73,9 @@ pub(crate) struct ExploreRpgParams {
 /// Parameters for the `build_rpg` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct BuildRpgParams {
-    /// Primary language override (auto-detected if not specified)
+    /// Languages to index, comma-separated, primary first (auto-detected if not
+    /// specified). Markdown and HTML are never detected; list them to index
+    /// code fences and inline scripts.
     pub(crate) language: Option<String>,
     /// Glob pattern to include files (e.g., "src/**/*.rs")
     pub(crate) include: Option<String>,
@@ -156,7 +172,7 @@ pub(crate) struct ImpactRadiusParams {
     pub(crate) direction: Option<String>,
     /// Maximum traversal depth (default: 3). Use -1 for unlimited.
     pub(crate) max_depth: Option<i64>,
-    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'renders', 'reads_state', 'writes_state', 'dispatches'
+    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'renders', 'reads_state', 'writes_state', 'dispatches', 'data_flow', 'tests'
     pub(crate) edge_filter: Option<String>,
     /// Maximum number of reachable entities to return (default: 100). Prevents overwhelming output on highly-connected nodes.
     pub(crate) max_results: Option<usize>,
@@ -185,6 +201,17 @@ pub(crate) struct PlanChangeParams {
     pub(crate) max_entities: Option<usize>,
 }
 
+/// Parameters for the `tests_for` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct TestsForParams {
+    /// Changed entity IDs (e.g., ['src/auth.rs:validate_token']). A changed class includes its methods.
+    pub(crate) entity_ids: Option<Vec<String>>,
+    /// Changed files, relative to the project root; every entity in them counts as changed
+    pub(crate) files: Option<Vec<String>>,
+    /// Git commit to diff from (e.g., "HEAD~1", "main"); every entity in a changed file counts as changed
+    pub(crate) since_commit: Option<String>,
+}
+
 /// Parameters for the `find_paths` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct FindPathsParams {
@@ -196,7 +223,7 @@ pub(crate) struct FindPathsParams {
     pub(crate) max_hops: Option<i64>,
     /// Maximum number of paths to return (default: 3)
     pub(crate) max_paths: Option<usize>,
-    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', or 'dispatches'
+    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', 'dispatches', 'data_flow', or 'tests'
     pub(crate) edge_filter: Option<String>,
 }
 
@@ -211,6 +238,15 @@ pub(crate) struct SliceBetweenParams {
     pub(crate) include_metadata: Option<bool>,
 }
 
+/// Parameters for the `query_rpg` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct QueryRpgParams {
+    /// The query, e.g. `find controller where area = "API" and -[invokes *1..3]-> (area = "Persistence") and not <-[invokes]- (kind = test)`
+    pub(crate) query: String,
+    /// Output format: 'toon' (default) or 'json'
+    pub(crate) format: Option<String>,
+}
+
 /// Parameters for the `analyze_health` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct AnalyzeHealthParams {
@@ -218,6 +254,10 @@ pub(crate) struct AnalyzeHealthParams {
     pub(crate) instability_threshold: Option<f64>,
     /// Minimum total degree for god object detection (default: 10).
     pub(crate) god_object_threshold: Option<usize>,
+    /// Cyclomatic complexity above which a function is flagged (default: 10).
+    pub(crate) cyclomatic_threshold: Option<usize>,
+    /// Cognitive complexity above which a function is flagged (default: 15).
+    pub(crate) cognitive_threshold: Option<usize>,
     /// Run Rabin-Karp token-based clone detection (reads source files from disk, slower). Default: false.
     pub(crate) include_duplication: Option<bool>,
     /// Run Jaccard feature-based semantic clone detection (in-memory, fast).
@@ -253,3 +293,12 @@ pub(crate) struct DetectCyclesParams {
     /// Ignore .rpgignore rules and include all files (default: false)
     pub(crate) ignore_rpgignore: Option<bool>,
 }
+
+/// Parameters for the `validate_rpg` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ValidateRpgParams {
+    /// Fix repairable findings (dangling/duplicate edges, reverse deps, file index,
+    /// hierarchy membership, containment edges, indexes, metadata) and save the graph.
+    /// Default: false (report only).
+    pub(crate) repair: Option<bool>,
+}
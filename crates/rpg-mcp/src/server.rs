@@ -8,7 +8,9 @@ use std::path::PathBuf;
 use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
 
-use crate::types::{HierarchySession, LiftingSession, PendingRouting, load_pending_routing};
+use crate::types::{
+    HierarchySession, LiftingSession, PendingRouting, SubmissionJournal, load_pending_routing,
+};
 
 /// Cached protocol prompt versions (SHA256 hashes) for deduplication.
 #[derive(Clone)]
@@ -43,10 +45,13 @@ pub(crate) struct RpgServer {
     pub(crate) lifting_session: Arc<RwLock<Option<LiftingSession>>>,
     pub(crate) hierarchy_session: Arc<RwLock<Option<HierarchySession>>>,
     pub(crate) pending_routing: Arc<RwLock<Vec<PendingRouting>>>,
-    #[cfg(feature = "embeddings")]
+    /// Recent submit-tool mutations, for `undo_last_submission`.
+    pub(crate) journal: Arc<RwLock<SubmissionJournal>>,
+    /// Inverted index backing `search_node`, loaded from `.rpg/` on first search.
+    pub(crate) search_index: Arc<RwLock<Option<rpg_nav::search_index::SearchIndex>>>,
     pub(crate) embedding_index: Arc<RwLock<Option<rpg_nav::embeddings::EmbeddingIndex>>>,
-    /// Set to true after first failed init to avoid retrying every search.
-    #[cfg(feature = "embeddings")]
+    /// Set to true after first failed init (or with embeddings turned off)
+    /// to avoid retrying every search.
     pub(crate) embedding_init_failed: Arc<std::sync::atomic::AtomicBool>,
     pub(crate) tool_router: rmcp::handler::server::router::tool::ToolRouter<Self>,
     /// Protocol prompt versions for deduplication.
@@ -67,6 +72,9 @@ impl RpgServer {
     pub(crate) fn new(project_root: PathBuf) -> Self {
         let graph = storage::load(&project_root).ok();
         let config = RpgConfig::load(&project_root).unwrap_or_default();
+        for e in rpg_parser::languages::user::install_user_languages(&project_root) {
+            eprintln!("warning: ignoring language definition: {}", e);
+        }
         // Restore pending routing from disk if present
         let pending = load_pending_routing(&project_root)
             .map(|s| s.entries)
@@ -78,9 +86,9 @@ impl RpgServer {
             lifting_session: Arc::new(RwLock::new(None)),
             hierarchy_session: Arc::new(RwLock::new(None)),
             pending_routing: Arc::new(RwLock::new(pending)),
-            #[cfg(feature = "embeddings")]
+            journal: Arc::new(RwLock::new(SubmissionJournal::default())),
+            search_index: Arc::new(RwLock::new(None)),
             embedding_index: Arc::new(RwLock::new(None)),
-            #[cfg(feature = "embeddings")]
             embedding_init_failed: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             tool_router: Self::create_tool_router(),
             prompt_versions: PromptVersions::new(),
@@ -135,17 +143,18 @@ impl RpgServer {
 
     /// Collect paradigm-specific prompt hints for detected frameworks.
     ///
-    /// Uses a cached copy of the builtin paradigm defs (parsed once via OnceLock),
+    /// Uses a cached copy of the project's paradigm defs (parsed once via OnceLock),
     /// filters by the paradigm names stored in `graph.metadata.paradigms`, and
     /// concatenates the requested hint type from each active paradigm.
     pub(crate) fn collect_paradigm_hints(
+        &self,
         paradigm_names: &[String],
         hint_selector: fn(&rpg_parser::paradigms::defs::PromptHints) -> &Option<String>,
     ) -> String {
         static PARADIGM_DEFS: OnceLock<Vec<rpg_parser::paradigms::defs::ParadigmDef>> =
             OnceLock::new();
         let defs = PARADIGM_DEFS
-            .get_or_init(|| rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default());
+            .get_or_init(|| rpg_parser::paradigms::defs::load_defs_or_builtin(&self.project_root));
         let mut hints = String::new();
         for name in paradigm_names {
             if let Some(def) = defs.iter().find(|d| &d.name == name)
@@ -362,9 +371,52 @@ impl RpgServer {
         RpgConfig::load(&self.project_root).unwrap_or_default()
     }
 
-    /// Lazy-initialize the embedding index on first semantic search.
+    /// Save `graph` in the format configured under `[storage]`.
+    pub(crate) fn save_graph(&self, graph: &RPGraph) -> anyhow::Result<()> {
+        storage::save_with_config(
+            &self.project_root,
+            graph,
+            &self.get_config_blocking().storage,
+        )
+    }
+
+    /// Make sure the search index matches `graph`, loading it from disk on
+    /// first use. Covers graph changes made outside the submit tools.
+    pub(crate) async fn ensure_search_index(&self, graph: &RPGraph) {
+        let current = self
+            .search_index
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|idx| idx.is_current(graph));
+        if !current {
+            self.sync_search_index(graph).await;
+        }
+    }
+
+    /// Incrementally sync the search index with a changed graph and persist it.
+    /// Failures are logged and never fail the calling tool.
+    pub(crate) async fn sync_search_index(&self, graph: &RPGraph) {
+        let mut guard = self.search_index.write().await;
+        let result = if let Some(ref mut idx) = *guard {
+            let stats = idx.sync(graph);
+            if stats.is_noop() {
+                Ok(())
+            } else {
+                idx.save(&self.project_root)
+            }
+        } else {
+            rpg_nav::search_index::SearchIndex::open(&self.project_root, graph)
+                .map(|(idx, _)| *guard = Some(idx))
+        };
+        if let Err(e) = result {
+            eprintln!("rpg: search index sync failed: {e}");
+        }
+    }
+
+    /// Lazy-initialize the embedding index on first semantic search, with the
+    /// backend chosen by `[navigation] embedding_backend`.
     /// If init fails, logs a warning and sets a flag to avoid retrying.
-    #[cfg(feature = "embeddings")]
     pub(crate) async fn try_init_embeddings(&self, graph: &RPGraph) {
         // Skip if already initialized or previously failed
         if self.embedding_index.read().await.is_some() {
@@ -377,8 +429,28 @@ impl RpgServer {
             return;
         }
 
+        let kind = self.config.read().await.navigation.embedding_backend;
+        let backend = match rpg_nav::embeddings::init_backend(&self.project_root, kind) {
+            Ok(Some(backend)) => backend,
+            Ok(None) => {
+                self.embedding_init_failed
+                    .store(true, std::sync::atomic::Ordering::Relaxed);
+                return;
+            }
+            Err(e) => {
+                eprintln!("rpg: embedding init failed: {e} — using lexical search");
+                self.embedding_init_failed
+                    .store(true, std::sync::atomic::Ordering::Relaxed);
+                return;
+            }
+        };
+
         let updated_at = graph.updated_at.to_rfc3339();
-        match rpg_nav::embeddings::EmbeddingIndex::load_or_init(&self.project_root, &updated_at) {
+        match rpg_nav::embeddings::EmbeddingIndex::load_or_init(
+            &self.project_root,
+            &updated_at,
+            backend,
+        ) {
             Ok(mut idx) => {
                 // Incremental sync: only re-embed entities whose features changed
                 if let Err(e) = idx.sync(graph) {
@@ -396,7 +468,6 @@ impl RpgServer {
 
     /// Update embeddings for entities that just received new features.
     /// Also updates fingerprints so that the next `sync()` won't re-embed these.
-    #[cfg(feature = "embeddings")]
     pub(crate) async fn update_embeddings(
         &self,
         entity_features: &std::collections::HashMap<String, Vec<String>>,
@@ -416,6 +487,29 @@ impl RpgServer {
             }
         }
     }
+
+    /// Journal a submit tool's changes for undo and return the summary line
+    /// reported in the tool output.
+    pub(crate) async fn journal_submission(
+        &self,
+        tool: &'static str,
+        delta: rpg_core::delta::GraphDelta,
+        pending_before: Option<Vec<PendingRouting>>,
+    ) -> String {
+        let summary = delta.summary();
+        match self
+            .journal
+            .write()
+            .await
+            .record(tool, delta, pending_before)
+        {
+            Some(id) => format!(
+                "{} (submission #{}, undo_last_submission reverts)",
+                summary, id
+            ),
+            None => summary,
+        }
+    }
 }
 
 #[tool_handler]
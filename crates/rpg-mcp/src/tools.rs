@@ -4,6 +4,7 @@
 //! `impl` block, so this file cannot be split further without upstream changes.
 
 use rmcp::{handler::server::wrapper::Parameters, tool, tool_router};
+use rpg_core::delta::DeltaRecorder;
 use rpg_core::graph::{RPGraph, normalize_path};
 use rpg_core::storage;
 
@@ -44,21 +45,20 @@ impl RpgServer {
             .as_deref()
             .map(parse_entity_type_filter)
             .filter(|v| !v.is_empty());
+        let metadata_filter = build_metadata_filter(&params);
 
         // Attempt hybrid embedding search for features/auto modes
         let use_embeddings = matches!(
             search_mode,
             rpg_nav::search::SearchMode::Features | rpg_nav::search::SearchMode::Auto
         );
-        #[allow(unused_mut)]
         let mut search_mode_label = "lexical";
 
-        #[cfg(feature = "embeddings")]
         let embedding_scores = if use_embeddings {
             self.try_init_embeddings(graph).await;
             let mut emb_guard = self.embedding_index.write().await;
             if let Some(ref mut idx) = *emb_guard {
-                match idx.score_all(&params.query) {
+                match idx.score_nearest(&params.query, rpg_nav::embeddings::SEMANTIC_CANDIDATES) {
                     Ok(scores) if !scores.is_empty() => {
                         search_mode_label = "hybrid";
                         Some(scores)
@@ -71,11 +71,6 @@ impl RpgServer {
         } else {
             None
         };
-        #[cfg(not(feature = "embeddings"))]
-        let embedding_scores: Option<std::collections::HashMap<String, f64>> = {
-            let _ = use_embeddings;
-            None
-        };
 
         // Compute diff-aware search context if since_commit is provided
         let mut diff_warning = String::new();
@@ -119,20 +114,23 @@ impl RpgServer {
             None
         };
 
-        let results = rpg_nav::search::search_with_params(
-            graph,
-            &rpg_nav::search::SearchParams {
-                query: &params.query,
-                mode: search_mode,
-                scope: params.scope.as_deref(),
-                limit: config.navigation.search_result_limit,
-                line_nums,
-                file_pattern: params.file_pattern.as_deref(),
-                entity_type_filter,
-                embedding_scores: embedding_scores.as_ref(),
-                diff_context: diff_context.as_ref(),
-            },
-        );
+        self.ensure_search_index(graph).await;
+        let search_index = self.search_index.read().await;
+        let search_params = rpg_nav::search::SearchParams {
+            query: &params.query,
+            mode: search_mode,
+            scope: params.scope.as_deref(),
+            limit: config.navigation.search_result_limit,
+            line_nums,
+            file_pattern: params.file_pattern.as_deref(),
+            entity_type_filter,
+            metadata_filter: metadata_filter.as_ref(),
+            embedding_scores: embedding_scores.as_ref(),
+            diff_context: diff_context.as_ref(),
+            index: search_index.as_ref(),
+        };
+        let results = rpg_nav::search::search_with_params(graph, &search_params);
+        let results = rpg_nav::search::with_external_packages(graph, &search_params, results);
 
         if results.is_empty() {
             return Ok(format!(
@@ -279,14 +277,19 @@ impl RpgServer {
         let notice = self.staleness_notice().await;
         let guard = self.graph.read().await;
         let graph = guard.as_ref().unwrap();
-        #[cfg(feature = "embeddings")]
         let emb_status = {
             let emb_guard = self.embedding_index.read().await;
             if let Some(ref idx) = *emb_guard {
                 format!(
-                    "\nembedding_index: {} entities indexed (BGE-small-en-v1.5)",
-                    idx.entity_count()
+                    "\nembedding_index: {} entities, {} feature vectors in HNSW graph ({})",
+                    idx.entity_count(),
+                    idx.vector_count(),
+                    idx.model_id()
                 )
+            } else if self.config.read().await.navigation.embedding_backend
+                == rpg_core::config::EmbeddingBackendKind::Off
+            {
+                "\nembedding_index: off (lexical-only search)".to_string()
             } else if self
                 .embedding_init_failed
                 .load(std::sync::atomic::Ordering::Relaxed)
@@ -297,8 +300,19 @@ impl RpgServer {
                     .to_string()
             }
         };
-        #[cfg(not(feature = "embeddings"))]
-        let emb_status = "\nembedding_index: disabled (compiled without embeddings feature)";
+        let search_status = match *self.search_index.read().await {
+            Some(ref idx) => format!(
+                "\nsearch_index: {} entities, {} terms{}",
+                idx.entity_count(),
+                idx.term_count(),
+                if idx.is_current(graph) {
+                    ""
+                } else {
+                    " (stale, syncs on next search)"
+                }
+            ),
+            None => "\nsearch_index: not loaded (loads on first search)".to_string(),
+        };
         let area_invocations = rpg_nav::dataflow::compute_area_invocations(graph);
         let area_text = rpg_nav::dataflow::format_area_invocations(&area_invocations);
         let area_section = if area_text.is_empty() {
@@ -306,11 +320,22 @@ impl RpgServer {
         } else {
             format!("\n{}", area_text)
         };
+        let user_defs = rpg_parser::user_defs::UserDefsReport::collect(&self.project_root);
+        let user_defs_section = if user_defs.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "\ncustom_definitions:\n{}",
+                user_defs.to_string().trim_end()
+            )
+        };
         Ok(format!(
-            "{}{}{}{}",
+            "{}{}{}{}{}{}",
             notice,
             rpg_nav::toon::format_rpg_info(graph),
+            search_status,
             emb_status,
+            user_defs_section,
             area_section,
         ))
     }
@@ -360,14 +385,22 @@ impl RpgServer {
         use rpg_parser::languages::Language;
 
         let project_root = &self.project_root;
+        // Pick up edits to runtime language definitions
+        for e in rpg_parser::languages::user::install_user_languages(project_root) {
+            eprintln!("warning: ignoring language definition: {}", e);
+        }
 
         // Detect languages (multi-language support)
         let languages: Vec<Language> = if let Some(ref l) = params.language {
-            // User specified a single language override
-            let lang = Language::from_name(l)
-                .or_else(|| Language::from_extension(l))
-                .ok_or_else(|| format!("unsupported language: {}", l))?;
-            vec![lang]
+            // User specified the languages, primary first
+            l.split(',')
+                .map(str::trim)
+                .map(|l| {
+                    Language::from_name(l)
+                        .or_else(|| Language::from_extension(l))
+                        .ok_or_else(|| format!("unsupported language: {}", l))
+                })
+                .collect::<Result<_, _>>()?
         } else {
             let detected = Language::detect_all(project_root);
             if detected.is_empty() {
@@ -402,15 +435,16 @@ impl RpgServer {
         graph.metadata.languages = languages.iter().map(|l| l.name().to_string()).collect();
 
         // Load TOML paradigm definitions + compile tree-sitter queries
-        let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().map_err(|errs| {
-            format!(
-                "paradigm definition errors: {}",
-                errs.iter()
-                    .map(|e| e.to_string())
-                    .collect::<Vec<_>>()
-                    .join("; ")
-            )
-        })?;
+        let paradigm_defs =
+            rpg_parser::paradigms::defs::load_defs(project_root).map_err(|errs| {
+                format!(
+                    "paradigm definition errors: {}",
+                    errs.iter()
+                        .map(|e| e.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                )
+            })?;
         let qcache = rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs)
             .map_err(|errs| format!("query compile errors: {}", errs.join("; ")))?;
 
@@ -489,6 +523,7 @@ impl RpgServer {
                 file_lang,
                 &mut raw_entities,
             );
+            rpg_parser::entities::disambiguate_ids(&mut raw_entities);
 
             for raw in raw_entities {
                 graph.insert_entity(raw.into_entity());
@@ -504,7 +539,6 @@ impl RpgServer {
         // Hierarchy enrichment
         graph.assign_hierarchy_ids();
         graph.aggregate_hierarchy_features();
-        graph.materialize_containment_edges();
 
         // Artifact grounding + dependency resolution
         let cfg = self.get_config_blocking();
@@ -520,7 +554,10 @@ impl RpgServer {
             Some(&paradigm_ctx),
         );
         rpg_encoder::grounding::ground_hierarchy(&mut graph);
+        rpg_encoder::grounding::collect_external_packages(&mut graph, project_root);
         rpg_encoder::grounding::resolve_dependencies(&mut graph);
+        // Resolution replaces the edge list, so containment edges come after it
+        graph.materialize_containment_edges();
 
         // Set git commit
         if let Ok(sha) = rpg_encoder::evolution::get_head_sha(project_root) {
@@ -547,22 +584,31 @@ impl RpgServer {
 
         // Compute DataFlow edges after merge_features (which may restore signatures)
         rpg_encoder::dataflow::compute_data_flow_edges(&mut graph);
+        rpg_encoder::test_links::compute_test_edges(&mut graph);
 
         // Refresh metadata and save
         graph.refresh_metadata();
-        storage::save(project_root, &graph).map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.save_graph(&graph)
+            .map_err(|e| format!("Failed to save RPG: {}", e))?;
         let _ = storage::ensure_gitignore(project_root);
 
         // Update in-memory state
         let meta = graph.metadata.clone();
         *self.graph.write().await = Some(graph);
 
-        // Clear sessions — graph structure changed
+        // Clear sessions and journal — graph structure changed
         *self.lifting_session.write().await = None;
         *self.hierarchy_session.write().await = None;
+        self.journal.write().await.clear();
+
+        {
+            let graph_guard = self.graph.read().await;
+            if let Some(ref graph) = *graph_guard {
+                self.sync_search_index(graph).await;
+            }
+        }
 
         // Sync embedding index incrementally (fingerprints detect what changed)
-        #[cfg(feature = "embeddings")]
         {
             let graph_guard = self.graph.read().await;
             if let Some(ref graph) = *graph_guard {
@@ -575,7 +621,6 @@ impl RpgServer {
                 }
             }
         }
-        #[cfg(feature = "embeddings")]
         self.embedding_init_failed
             .store(false, std::sync::atomic::Ordering::Relaxed);
         // Clear stale pending routing (graph was fully replaced)
@@ -730,7 +775,7 @@ impl RpgServer {
 
                 // Auto-lift trivial entities (getters, setters, constructors, etc.)
                 let paradigm_defs =
-                    rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+                    rpg_parser::paradigms::defs::load_defs_or_builtin(&self.project_root);
                 let engine = rpg_encoder::lift::AutoLiftEngine::new(
                     &paradigm_defs,
                     &graph.metadata.paradigms,
@@ -766,6 +811,8 @@ impl RpgServer {
                             }
                             review_candidates.push((eid, features));
                         }
+                        // Data entities are only ever auto-lifted
+                        _ if raw.kind.is_data() => {}
                         Some((_, rpg_encoder::lift::LiftConfidence::Reject)) | None => {
                             needs_llm.push(raw);
                         }
@@ -775,7 +822,7 @@ impl RpgServer {
                 // Save if we auto-lifted anything
                 if auto_lifted > 0 {
                     graph.refresh_metadata();
-                    if let Err(e) = rpg_core::storage::save(&self.project_root, graph) {
+                    if let Err(e) = self.save_graph(graph) {
                         eprintln!("Warning: failed to persist auto-lifted features: {e}");
                     }
                 }
@@ -877,7 +924,7 @@ impl RpgServer {
 
             // Inject paradigm-specific lifting hints
             let lifting_hints =
-                Self::collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.lifting);
+                self.collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.lifting);
             if !lifting_hints.is_empty() {
                 output.push_str("\n## Framework-Specific Guidelines\n\n");
                 output.push_str(&lifting_hints);
@@ -943,6 +990,8 @@ impl RpgServer {
         let mut guard = self.graph.write().await;
         let graph = guard.as_mut().ok_or("No RPG loaded")?;
 
+        let mut recorder = DeltaRecorder::new(graph);
+        let pending_before = self.pending_routing.read().await.clone();
         let config = self.get_config_blocking();
         let drift_ignore = config.encoding.drift_ignore_threshold;
         let drift_auto = config.encoding.drift_auto_threshold;
@@ -1017,6 +1066,7 @@ impl RpgServer {
                         newly_lifted_ids.push(eid.clone());
                     }
 
+                    recorder.entity(graph, eid);
                     if let Some(entity) = graph.entities.get_mut(eid) {
                         entity.semantic_features = feats.clone();
                         entity.feature_source = Some("llm".to_string());
@@ -1113,18 +1163,21 @@ impl RpgServer {
 
         graph.refresh_metadata();
 
-        storage::save(&self.project_root, graph)
+        self.save_graph(graph)
             .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.sync_search_index(graph).await;
+        let changes = self
+            .journal_submission(
+                "submit_lift_results",
+                recorder.finish(graph),
+                Some(pending_before),
+            )
+            .await;
 
         // Update embedding index for newly-lifted entities (non-blocking on failure)
-        #[cfg(feature = "embeddings")]
-        {
-            let graph_ts = graph.updated_at.to_rfc3339();
-            drop(guard); // Release graph write lock before async embedding update
-            self.update_embeddings(&resolved_features, &graph_ts).await;
-        }
-        #[cfg(not(feature = "embeddings"))]
-        drop(guard);
+        let graph_ts = graph.updated_at.to_rfc3339();
+        drop(guard); // Release graph write lock before async embedding update
+        self.update_embeddings(&resolved_features, &graph_ts).await;
 
         let guard = self.graph.read().await;
         let graph = guard.as_ref().unwrap();
@@ -1144,6 +1197,7 @@ impl RpgServer {
             total,
             coverage_pct,
         );
+        result.push_str(&format!("\nchanges: {}", changes));
         if unmatched > 0 {
             result.push_str("\nNote: Unmatched keys must match headers from get_entities_for_lifting (e.g., \"src/main.rs:MyStruct::method\" for methods).");
         }
@@ -1207,9 +1261,7 @@ impl RpgServer {
             let unlifted: Vec<&str> = graph
                 .entities
                 .iter()
-                .filter(|(_, e)| {
-                    e.semantic_features.is_empty() && e.kind != rpg_core::graph::EntityKind::Module
-                })
+                .filter(|(_, e)| e.semantic_features.is_empty() && e.kind.is_liftable())
                 .map(|(id, _)| id.as_str())
                 .take(10)
                 .collect();
@@ -1345,10 +1397,10 @@ impl RpgServer {
                     } else {
                         cat_node.semantic_features.join(", ")
                     };
-                    result.push_str(&format!("  - {}/{}: {}\n", area_name, cat_name, cat_feats,));
+                    result.push_str(&format!("  - {}/{}: {}\n", area_name, cat_name, cat_feats));
                     for sub_name in cat_node.children.keys() {
                         result
-                            .push_str(&format!("    - {}/{}/{}\n", area_name, cat_name, sub_name,));
+                            .push_str(&format!("    - {}/{}/{}\n", area_name, cat_name, sub_name));
                     }
                 }
             }
@@ -1451,6 +1503,8 @@ impl RpgServer {
             ));
         }
 
+        let mut recorder = DeltaRecorder::new(graph);
+        let pending_before = pending.clone();
         for (entity_id, action) in &decisions {
             // Validate entity exists
             if !graph.entities.contains_key(entity_id) {
@@ -1469,6 +1523,8 @@ impl RpgServer {
                     .map(|e| e.hierarchy_path.clone())
                     .unwrap_or_default();
 
+                recorder.entity(graph, entity_id);
+                recorder.hierarchy(graph);
                 graph.remove_entity_from_hierarchy(entity_id);
 
                 if let Some(entity) = graph.entities.get_mut(entity_id) {
@@ -1498,8 +1554,9 @@ impl RpgServer {
         }
         graph.refresh_metadata();
 
-        storage::save(&self.project_root, graph)
+        self.save_graph(graph)
             .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.sync_search_index(graph).await;
 
         // Update or clear persisted pending state
         if pending.is_empty() {
@@ -1515,7 +1572,17 @@ impl RpgServer {
         }
 
         let remaining = pending.len();
-        let mut result = format!("Routed {} entities, kept {} in place.\n", routed, kept);
+        let changes = self
+            .journal_submission(
+                "submit_routing_decisions",
+                recorder.finish(graph),
+                Some(pending_before),
+            )
+            .await;
+        let mut result = format!(
+            "Routed {} entities, kept {} in place.\nchanges: {}\n",
+            routed, kept, changes
+        );
         for report in &reports {
             result.push_str(report);
             result.push('\n');
@@ -1547,7 +1614,7 @@ impl RpgServer {
 
         // Detect paradigms BEFORE running update so entities get classified
         let detected_langs = Self::resolve_languages(&g.metadata);
-        let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs()
+        let paradigm_defs = rpg_parser::paradigms::defs::load_defs(&self.project_root)
             .map_err(|e| format!("Failed to load paradigm defs: {:?}", e))?;
         let qcache = rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs)
             .map_err(|errs| format!("query compile errors: {}", errs.join("; ")))?;
@@ -1571,14 +1638,16 @@ impl RpgServer {
         )
         .map_err(|e| format!("Update failed: {}", e))?;
 
-        storage::save(&self.project_root, g).map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.save_graph(g)
+            .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.sync_search_index(g).await;
 
-        // Clear sessions — entity list changed
+        // Clear sessions and journal — entity list changed
         *self.lifting_session.write().await = None;
         *self.hierarchy_session.write().await = None;
+        self.journal.write().await.clear();
 
         // Sync embedding index incrementally — entities changed
-        #[cfg(feature = "embeddings")]
         {
             let mut emb_guard = self.embedding_index.write().await;
             if let Some(ref mut idx) = *emb_guard
@@ -1588,7 +1657,6 @@ impl RpgServer {
                 *emb_guard = None;
             }
         }
-        #[cfg(feature = "embeddings")]
         self.embedding_init_failed
             .store(false, std::sync::atomic::Ordering::Relaxed);
         // Reconcile pending routing against the updated graph:
@@ -1648,12 +1716,14 @@ impl RpgServer {
                  entities_modified: {}\n\
                  entities_removed: {}\n\
                  edges_added: {}\n\
-                 edges_removed: {}",
+                 edges_removed: {}\n\
+                 changes: {}",
                 summary.entities_added,
                 summary.entities_modified,
                 summary.entities_removed,
                 summary.edges_added,
                 summary.edges_removed,
+                summary.delta.summary(),
             );
 
             let needs_relift = summary.modified_entity_ids.len();
@@ -1691,8 +1761,13 @@ impl RpgServer {
             Ok(g) => {
                 let entities = g.metadata.total_entities;
                 *self.graph.write().await = Some(g);
+                {
+                    let graph_guard = self.graph.read().await;
+                    if let Some(ref graph) = *graph_guard {
+                        self.sync_search_index(graph).await;
+                    }
+                }
                 // Sync embedding index incrementally
-                #[cfg(feature = "embeddings")]
                 {
                     let graph_guard = self.graph.read().await;
                     if let Some(ref graph) = *graph_guard {
@@ -1705,13 +1780,13 @@ impl RpgServer {
                         }
                     }
                 }
-                #[cfg(feature = "embeddings")]
                 self.embedding_init_failed
                     .store(false, std::sync::atomic::Ordering::Relaxed);
 
-                // Clear sessions — graph reloaded
+                // Clear sessions and journal — graph reloaded
                 *self.lifting_session.write().await = None;
                 *self.hierarchy_session.write().await = None;
+                self.journal.write().await.clear();
 
                 // Reload pending routing from disk (may have changed externally)
                 let pending = load_pending_routing(&self.project_root)
@@ -1780,8 +1855,10 @@ impl RpgServer {
             clear_pending_routing(&self.project_root);
         }
 
-        // Clear lifting session cache
+        // Clear lifting session cache; finalizing rewrites hierarchy and module features,
+        // so earlier submissions can no longer be undone
         *self.lifting_session.write().await = None;
+        self.journal.write().await.clear();
 
         let mut steps: Vec<String> = Vec::new();
 
@@ -1805,8 +1882,9 @@ impl RpgServer {
         graph.refresh_metadata();
 
         // Save
-        storage::save(&self.project_root, graph)
+        self.save_graph(graph)
             .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.sync_search_index(graph).await;
 
         let (final_lifted, final_total) = graph.lifting_coverage();
         let coverage_pct = if final_total > 0 {
@@ -1934,7 +2012,7 @@ impl RpgServer {
 
             // Inject paradigm-specific synthesis hints
             let synthesis_hints =
-                Self::collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.synthesis);
+                self.collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.synthesis);
             if !synthesis_hints.is_empty() {
                 output.push_str("## Framework-Specific Synthesis Guidelines\n\n");
                 output.push_str(&synthesis_hints);
@@ -1986,6 +2064,8 @@ impl RpgServer {
         let mut guard = self.graph.write().await;
         let graph = guard.as_mut().ok_or("No RPG loaded")?;
 
+        let mut recorder = DeltaRecorder::new(graph);
+        recorder.hierarchy(graph);
         let mut updated = 0usize;
         let mut unmatched = Vec::new();
 
@@ -2015,6 +2095,7 @@ impl RpgServer {
             });
 
             if let Some(module_id) = module_id {
+                recorder.entity(graph, &module_id);
                 if let Some(module) = graph.entities.get_mut(&module_id) {
                     // Parse comma-separated features from the summary, or use as single feature
                     let features: Vec<String> = summary
@@ -2038,8 +2119,9 @@ impl RpgServer {
         graph.aggregate_hierarchy_features();
         graph.refresh_metadata();
 
-        storage::save(&self.project_root, graph)
+        self.save_graph(graph)
             .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.sync_search_index(graph).await;
 
         let total_modules = graph
             .entities
@@ -2050,11 +2132,13 @@ impl RpgServer {
             .count();
 
         let mut result = format!(
-            "Applied {} file syntheses ({} matched, {} unmatched).\ntotal_synthesized_modules: {}\n",
+            "Applied {} file syntheses ({} matched, {} unmatched).\ntotal_synthesized_modules: {}\nchanges: {}\n",
             syntheses.len(),
             updated,
             unmatched.len(),
             total_modules,
+            self.journal_submission("submit_file_syntheses", recorder.finish(graph), None)
+                .await,
         );
 
         if !unmatched.is_empty() {
@@ -2236,7 +2320,7 @@ impl RpgServer {
 
         // Inject paradigm-specific discovery hints
         let discovery_hints =
-            Self::collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.discovery);
+            self.collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.discovery);
         if !discovery_hints.is_empty() {
             output.push_str("\n\n## Framework-Specific Discovery Guidelines\n\n");
             output.push_str(&discovery_hints);
@@ -2250,7 +2334,7 @@ impl RpgServer {
 
         // Inject paradigm-specific hierarchy hints
         let hierarchy_hints =
-            Self::collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.hierarchy);
+            self.collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.hierarchy);
         if !hierarchy_hints.is_empty() {
             output.push_str("\n\n## Framework-Specific Hierarchy Patterns\n\n");
             output.push_str(&hierarchy_hints);
@@ -2275,18 +2359,17 @@ impl RpgServer {
         let graph = guard.as_ref().unwrap();
 
         // Attempt hybrid embedding search
-        #[cfg(feature = "embeddings")]
         let embedding_scores = {
             self.try_init_embeddings(graph).await;
             let mut emb_guard = self.embedding_index.write().await;
             if let Some(ref mut idx) = *emb_guard {
-                idx.score_all(&params.query).ok().filter(|s| !s.is_empty())
+                idx.score_nearest(&params.query, rpg_nav::embeddings::SEMANTIC_CANDIDATES)
+                    .ok()
+                    .filter(|s| !s.is_empty())
             } else {
                 None
             }
         };
-        #[cfg(not(feature = "embeddings"))]
-        let embedding_scores: Option<std::collections::HashMap<String, f64>> = None;
 
         let request = rpg_nav::context::ContextPackRequest {
             query: &params.query,
@@ -2304,7 +2387,7 @@ impl RpgServer {
         );
 
         if result.primary_entities.is_empty() {
-            return Ok(format!("{}No entities found for: {}", notice, params.query,));
+            return Ok(format!("{}No entities found for: {}", notice, params.query));
         }
 
         Ok(format!(
@@ -2390,6 +2473,7 @@ impl RpgServer {
             "writes_state" => Some(rpg_core::graph::EdgeKind::WritesState),
             "dispatches" => Some(rpg_core::graph::EdgeKind::Dispatches),
             "data_flow" => Some(rpg_core::graph::EdgeKind::DataFlow),
+            "tests" => Some(rpg_core::graph::EdgeKind::Tests),
             _ => None,
         });
 
@@ -2497,6 +2581,76 @@ impl RpgServer {
         Ok(output)
     }
 
+    #[tool(
+        description = "Run a structured graph query. Syntax: `find <kinds|*> [where <expr>] [count | group by <field>] [limit N]`. Predicates: `field op value` over id, name, kind, file, area, path, feature, decorator, visibility, parent, lifted, lines, fan_in, fan_out (ops = != ~ !~ < <= > >=; `*` globs). Edge patterns: `-[invokes|imports *1..3]-> (expr)` outgoing, `<-[invokes]- (expr)` incoming, `-[...]- (expr)` either; combine with and/or/not; `count(pattern) >= N`. Example: find controller where area = \"API\" and -[invokes *1..3]-> (area = \"Persistence\") and not <-[invokes]- (kind = test). Replaces search/explore/fetch chains for structural questions."
+    )]
+    async fn query_rpg(
+        &self,
+        Parameters(params): Parameters<QueryRpgParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.staleness_notice().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let result = rpg_nav::query::run(graph, &params.query)?;
+        let output = match params.format.as_deref() {
+            Some("json") => serde_json::to_string_pretty(&result).map_err(|e| e.to_string())?,
+            None | Some("toon") => rpg_nav::toon::format_query_result(&result),
+            Some(other) => {
+                return Err(format!("Unknown format: {}. Use 'toon' or 'json'.", other));
+            }
+        };
+        Ok(format!("{}{}", notice, output))
+    }
+
+    #[tool(
+        description = "Select the minimal set of tests to run for a change. Give changed entity_ids, files, or since_commit (git diff). Follows Tests edges, which link each test (pytest, JUnit, Jest/Vitest/Mocha, Go testing, Rust #[test], RSpec, xUnit/NUnit/MSTest) to the code it uses directly, then the calls from that code, however deep. Returns the tests with the changed entities each covers, the test files to run, and changed entities no test reaches."
+    )]
+    async fn tests_for(
+        &self,
+        Parameters(params): Parameters<TestsForParams>,
+    ) -> Result<String, String> {
+        if params.entity_ids.is_none() && params.files.is_none() && params.since_commit.is_none() {
+            return Err("Provide at least one of entity_ids, files, or since_commit".into());
+        }
+        self.ensure_graph().await?;
+        let notice = self.staleness_notice().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let mut changed = params.entity_ids.unwrap_or_default();
+        let mut files = params.files.unwrap_or_default();
+        if let Some(ref commit) = params.since_commit {
+            let changes =
+                rpg_encoder::evolution::detect_changes(&self.project_root, graph, Some(commit))
+                    .map_err(|e| format!("Failed to detect changes since {}: {}", commit, e))?;
+            for change in &changes {
+                let file_path = match change {
+                    rpg_encoder::evolution::FileChange::Added(p)
+                    | rpg_encoder::evolution::FileChange::Modified(p) => p,
+                    rpg_encoder::evolution::FileChange::Deleted(_) => continue,
+                    rpg_encoder::evolution::FileChange::Renamed { to, .. } => to,
+                };
+                files.push(normalize_path(file_path));
+            }
+        }
+        changed.extend(rpg_nav::test_selection::entities_in_files(graph, &files));
+
+        if changed.is_empty() {
+            return Ok(format!("{}No changed entities found.", notice));
+        }
+
+        let selection = rpg_nav::test_selection::select_tests(graph, &changed);
+        let mut output = rpg_nav::toon::format_test_selection(&selection);
+        if graph.metadata.test_edges == 0 {
+            output.push_str(
+                "\n\nNote: the graph has no Tests edges. Rebuild it (build_rpg) to link tests to code.",
+            );
+        }
+        Ok(format!("{}{}", notice, output))
+    }
+
     #[tool(
         description = "Plan code changes: find relevant entities, compute modification order, assess impact radius. Returns dependency-ordered entity list with blast radius analysis."
     )]
@@ -2510,18 +2664,17 @@ impl RpgServer {
         let graph = guard.as_ref().unwrap();
 
         // Attempt hybrid embedding search
-        #[cfg(feature = "embeddings")]
         let embedding_scores = {
             self.try_init_embeddings(graph).await;
             let mut emb_guard = self.embedding_index.write().await;
             if let Some(ref mut idx) = *emb_guard {
-                idx.score_all(&params.goal).ok().filter(|s| !s.is_empty())
+                idx.score_nearest(&params.goal, rpg_nav::embeddings::SEMANTIC_CANDIDATES)
+                    .ok()
+                    .filter(|s| !s.is_empty())
             } else {
                 None
             }
         };
-        #[cfg(not(feature = "embeddings"))]
-        let embedding_scores: Option<std::collections::HashMap<String, f64>> = None;
 
         let request = rpg_nav::planner::PlanChangeRequest {
             goal: &params.goal,
@@ -2642,6 +2795,8 @@ impl RpgServer {
                 // Now apply the hierarchy
                 let mut guard = self.graph.write().await;
                 let graph = guard.as_mut().ok_or("No RPG loaded")?;
+                let mut recorder = DeltaRecorder::new(graph);
+                recorder.hierarchy(graph);
 
                 // Convert file paths to Module entity IDs for apply_hierarchy
                 let mut entity_assignments: std::collections::HashMap<String, String> =
@@ -2692,6 +2847,7 @@ impl RpgServer {
                 }
 
                 // Clear existing hierarchy and apply new assignments
+                record_assignment_targets(&mut recorder, graph, &entity_assignments);
                 graph.hierarchy.clear();
                 rpg_encoder::hierarchy::apply_hierarchy(graph, &entity_assignments);
                 graph.metadata.semantic_hierarchy = true;
@@ -2704,14 +2860,17 @@ impl RpgServer {
                 graph.refresh_metadata();
 
                 // Save
-                storage::save(&self.project_root, graph)
+                self.save_graph(graph)
                     .map_err(|e| format!("Failed to save RPG: {}", e))?;
+                self.sync_search_index(graph).await;
 
                 let mut result = format!(
-                    "Hierarchy applied (batched workflow, {} file batches).\nfiles_matched: {}\nfiles_unmatched: {}\nhierarchy_type: semantic\n",
+                    "Hierarchy applied (batched workflow, {} file batches).\nfiles_matched: {}\nfiles_unmatched: {}\nhierarchy_type: semantic\nchanges: {}\n",
                     clusters_count,
                     matched,
-                    unmatched.len()
+                    unmatched.len(),
+                    self.journal_submission("submit_hierarchy", recorder.finish(graph), None)
+                        .await
                 );
 
                 if !unmatched.is_empty() {
@@ -2785,6 +2944,8 @@ impl RpgServer {
 
         let mut guard = self.graph.write().await;
         let graph = guard.as_mut().ok_or("No RPG loaded")?;
+        let mut recorder = DeltaRecorder::new(graph);
+        recorder.hierarchy(graph);
 
         // Convert file paths to Module entity IDs for apply_hierarchy
         // Module entities use the file path as their ID in the format "path:filename_stem"
@@ -2838,6 +2999,7 @@ impl RpgServer {
         }
 
         // Clear existing hierarchy and apply new assignments
+        record_assignment_targets(&mut recorder, graph, &entity_assignments);
         graph.hierarchy.clear();
         rpg_encoder::hierarchy::apply_hierarchy(graph, &entity_assignments);
         graph.metadata.semantic_hierarchy = true;
@@ -2850,13 +3012,16 @@ impl RpgServer {
         graph.refresh_metadata();
 
         // Save
-        storage::save(&self.project_root, graph)
+        self.save_graph(graph)
             .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.sync_search_index(graph).await;
 
         let mut result = format!(
-            "Hierarchy applied.\nfiles_matched: {}\nfiles_unmatched: {}\nhierarchy_type: semantic\n",
+            "Hierarchy applied.\nfiles_matched: {}\nfiles_unmatched: {}\nhierarchy_type: semantic\nchanges: {}\n",
             matched,
-            unmatched.len()
+            unmatched.len(),
+            self.journal_submission("submit_hierarchy", recorder.finish(graph), None)
+                .await
         );
 
         if !unmatched.is_empty() {
@@ -2885,7 +3050,203 @@ impl RpgServer {
     }
 
     #[tool(
-        description = "Analyze code health metrics including coupling, instability, centrality, and potential god objects. Returns entities with architectural issues and recommendations for refactoring. Set include_duplication=true to detect code clones via Rabin-Karp fingerprinting (reads source files, slower). Set include_semantic_duplication=true to detect conceptual duplicates via Jaccard similarity on lifted features (in-memory, fast; requires entities to be lifted)."
+        description = "List recent graph submissions (submit_lift_results, submit_hierarchy, submit_routing_decisions, submit_file_syntheses), newest first, with what each changed. The newest one can be reverted with undo_last_submission. The journal is kept in server memory only: it holds the last 20 submissions, is lost when the MCP server restarts, and is cleared by build_rpg, update_rpg, reload_rpg and finalize_lifting."
+    )]
+    async fn list_submissions(&self) -> Result<String, String> {
+        let journal = self.journal.read().await;
+        if journal.last().is_none() {
+            return Ok("No submissions recorded since the graph was last built, updated, reloaded or finalized.".into());
+        }
+
+        let mut result = String::from("submissions (newest first):\n");
+        for submission in journal.iter() {
+            result.push_str(&format!(
+                "  #{} {} at {}: {}\n",
+                submission.id,
+                submission.tool,
+                submission.recorded_at.format("%H:%M:%S"),
+                submission.delta.summary(),
+            ));
+            let features = submission.delta.feature_changes();
+            if !features.is_empty() {
+                let sample: Vec<&str> = features.iter().take(5).map(|c| c.entity_id).collect();
+                result.push_str(&format!(
+                    "    features: {}{}\n",
+                    sample.join(", "),
+                    if features.len() > sample.len() {
+                        ", ..."
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            let moves = submission.delta.hierarchy_moves();
+            if !moves.is_empty() {
+                let sample: Vec<String> = moves
+                    .iter()
+                    .take(5)
+                    .map(|m| format!("{} → {}", m.entity_id, m.to))
+                    .collect();
+                result.push_str(&format!(
+                    "    moves: {}{}\n",
+                    sample.join(", "),
+                    if moves.len() > sample.len() {
+                        ", ..."
+                    } else {
+                        ""
+                    }
+                ));
+            }
+        }
+        Ok(result)
+    }
+
+    #[tool(
+        description = "Revert the most recent submission (see list_submissions): restores the features, hierarchy paths, hierarchy tree and pending routing queue exactly as they were before it, and saves the graph. Call repeatedly to step further back (up to 20 submissions; the journal is in memory only and lost when the MCP server restarts). Refuses if the entities, edges or file index entries it touched have changed since."
+    )]
+    async fn undo_last_submission(&self) -> Result<String, String> {
+        self.ensure_graph().await?;
+
+        let mut guard = self.graph.write().await;
+        let graph = guard.as_mut().ok_or("No RPG loaded")?;
+        let mut journal = self.journal.write().await;
+
+        let Some(last) = journal.last() else {
+            return Err("No submissions to undo. The journal is kept in memory only, so it is empty after a server restart, and is cleared by build_rpg, update_rpg, reload_rpg and finalize_lifting.".into());
+        };
+        let conflicts = last.delta.conflicts_with(graph);
+        if !conflicts.is_empty() {
+            let sample: Vec<&str> = conflicts.iter().take(10).map(|s| s.as_str()).collect();
+            return Err(format!(
+                "Cannot undo submission #{} ({}): the graph changed since (showing up to 10): {}",
+                last.id,
+                last.tool,
+                sample.join(", "),
+            ));
+        }
+        let submission = journal.pop().expect("checked above");
+
+        submission.delta.invert().apply(graph);
+        graph.refresh_metadata();
+        self.save_graph(graph)
+            .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.sync_search_index(graph).await;
+
+        if let Some(pending_before) = submission.pending_before {
+            let mut pending = self.pending_routing.write().await;
+            *pending = pending_before;
+            if pending.is_empty() {
+                clear_pending_routing(&self.project_root);
+            } else {
+                let state = PendingRoutingState {
+                    graph_revision: graph_revision(graph),
+                    entries: pending.clone(),
+                };
+                if let Err(e) = save_pending_routing(&self.project_root, &state) {
+                    eprintln!("rpg: failed to persist pending routing: {e}");
+                }
+            }
+        }
+
+        // Entities may be unlifted again — rebuild batches on the next request
+        *self.lifting_session.write().await = None;
+
+        {
+            let mut emb_guard = self.embedding_index.write().await;
+            if let Some(ref mut idx) = *emb_guard
+                && let Err(e) = idx.sync(graph)
+            {
+                eprintln!("rpg: embedding sync failed: {e}");
+                *emb_guard = None;
+            }
+        }
+
+        let remaining = journal.iter().count();
+        Ok(format!(
+            "Reverted submission #{} ({}).\nreverted: {}\nsubmissions_remaining: {}",
+            submission.id,
+            submission.tool,
+            submission.delta.summary(),
+            remaining,
+        ))
+    }
+
+    #[tool(
+        description = "Check graph integrity: dangling and duplicate edges, edge/reverse-dependency symmetry, file_index consistency, hierarchy membership vs. entity hierarchy_path, containment edges, lookup indexes and metadata counts. Set repair=true to fix every repairable finding and save the graph."
+    )]
+    async fn validate_rpg(
+        &self,
+        Parameters(params): Parameters<ValidateRpgParams>,
+    ) -> Result<String, String> {
+        use rpg_core::validate::{self, Finding, Severity};
+
+        fn list(out: &mut String, label: &str, findings: &[Finding]) {
+            const LIMIT: usize = 50;
+            for finding in findings.iter().take(LIMIT) {
+                let severity = match finding.severity() {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                out.push_str(&format!("  [{}] {}\n", severity, finding));
+            }
+            if findings.len() > LIMIT {
+                out.push_str(&format!(
+                    "  ... and {} more {}\n",
+                    findings.len() - LIMIT,
+                    label
+                ));
+            }
+        }
+
+        self.ensure_graph().await?;
+
+        if !params.repair.unwrap_or(false) {
+            let guard = self.graph.read().await;
+            let graph = guard.as_ref().ok_or("No RPG loaded")?;
+            let report = validate::validate(graph);
+            if report.is_clean() {
+                return Ok("Graph is valid. No integrity issues found.".into());
+            }
+            let mut result = format!(
+                "findings: {} ({} errors, {} warnings, {} repairable)\n",
+                report.findings.len(),
+                report.count(Severity::Error),
+                report.count(Severity::Warning),
+                report.repairable(),
+            );
+            list(&mut result, "findings", &report.findings);
+            if report.repairable() > 0 {
+                result.push_str("Call validate_rpg with repair=true to fix repairable findings.\n");
+            }
+            return Ok(result);
+        }
+
+        let mut guard = self.graph.write().await;
+        let graph = guard.as_mut().ok_or("No RPG loaded")?;
+        let report = validate::repair(graph);
+        if !report.fixed.is_empty() {
+            self.save_graph(graph)
+                .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        }
+
+        let mut result = format!(
+            "fixed: {}\nremaining: {}\n",
+            report.fixed.len(),
+            report.remaining.len()
+        );
+        if !report.fixed.is_empty() {
+            result.push_str("Fixed:\n");
+            list(&mut result, "fixed", &report.fixed);
+        }
+        if !report.remaining.is_empty() {
+            result.push_str("Remaining:\n");
+            list(&mut result, "remaining", &report.remaining);
+        }
+        Ok(result)
+    }
+
+    #[tool(
+        description = "Analyze code health metrics including coupling, instability, centrality, potential god objects, and function complexity (cyclomatic, cognitive, nesting depth). Returns entities with architectural issues and recommendations for refactoring. Set include_duplication=true to detect code clones via Rabin-Karp fingerprinting (reads source files, slower). Set include_semantic_duplication=true to detect conceptual duplicates via Jaccard similarity on lifted features (in-memory, fast; requires entities to be lifted)."
     )]
     async fn analyze_health(
         &self,
@@ -2899,6 +3260,8 @@ impl RpgServer {
         let config = rpg_nav::health::HealthConfig {
             instability_threshold: params.instability_threshold.unwrap_or(0.7),
             god_object_degree_threshold: params.god_object_threshold.unwrap_or(10),
+            cyclomatic_threshold: params.cyclomatic_threshold.unwrap_or(10),
+            cognitive_threshold: params.cognitive_threshold.unwrap_or(15),
             include_duplication: params.include_duplication.unwrap_or(false),
             include_semantic_duplication: params.include_semantic_duplication.unwrap_or(false),
             semantic_duplication_config: rpg_nav::duplication::SemanticDuplicationConfig {
@@ -3020,6 +3383,7 @@ pub fn parse_edge_filter(filter: &str) -> Option<rpg_core::graph::EdgeKind> {
         "writes_state" => Some(rpg_core::graph::EdgeKind::WritesState),
         "dispatches" => Some(rpg_core::graph::EdgeKind::Dispatches),
         "data_flow" => Some(rpg_core::graph::EdgeKind::DataFlow),
+        "tests" => Some(rpg_core::graph::EdgeKind::Tests),
         "contains" => Some(rpg_core::graph::EdgeKind::Contains),
         _ => None,
     }
@@ -3028,7 +3392,7 @@ pub fn parse_edge_filter(filter: &str) -> Option<rpg_core::graph::EdgeKind> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::EdgeKind;
+    use rpg_core::graph::{EdgeKind, Entity, EntityDeps, EntityKind, EntityMetadata};
 
     #[test]
     fn test_parse_edge_filter_data_flow() {
@@ -3049,7 +3413,70 @@ mod tests {
         );
         assert_eq!(parse_edge_filter("dispatches"), Some(EdgeKind::Dispatches));
         assert_eq!(parse_edge_filter("data_flow"), Some(EdgeKind::DataFlow));
+        assert_eq!(parse_edge_filter("tests"), Some(EdgeKind::Tests));
         assert_eq!(parse_edge_filter("contains"), Some(EdgeKind::Contains));
         assert_eq!(parse_edge_filter("unknown"), None);
     }
+
+    fn make_entity(id: &str, name: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            kind: EntityKind::Function,
+            name: name.to_string(),
+            file: std::path::PathBuf::from("src/lib.rs"),
+            line_start: 1,
+            line_end: 5,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_then_undo_restores_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut graph = RPGraph::new("rust");
+        graph.insert_entity(make_entity("src/lib.rs:parse", "parse"));
+        graph.insert_entity(make_entity("src/lib.rs:emit", "emit"));
+        graph.build_file_path_hierarchy();
+        graph.assign_hierarchy_ids();
+        graph.materialize_containment_edges();
+        graph.refresh_metadata();
+        storage::save(root, &graph).unwrap();
+        std::fs::write(
+            storage::rpg_dir(root).join("config.toml"),
+            "[navigation]\nembedding_backend = \"off\"\n",
+        )
+        .unwrap();
+
+        let server = RpgServer::new(root.to_path_buf());
+        let before = server.graph.read().await.clone().unwrap();
+
+        server
+            .submit_lift_results(Parameters(SubmitLiftResultsParams {
+                features: r#"{"src/lib.rs:parse": ["parse source text"]}"#.to_string(),
+            }))
+            .await
+            .unwrap();
+        let lifted = storage::load(root).unwrap();
+        assert_eq!(
+            lifted.entities["src/lib.rs:parse"].semantic_features,
+            ["parse source text"]
+        );
+
+        server.undo_last_submission().await.unwrap();
+        let restored = storage::load(root).unwrap();
+        for graph in [server.graph.read().await.clone().unwrap(), restored] {
+            assert_eq!(graph.entities, before.entities);
+            assert_eq!(graph.hierarchy, before.hierarchy);
+            assert_eq!(graph.file_index, before.file_index);
+        }
+        assert!(server.undo_last_submission().await.is_err());
+    }
 }
@@ -8,6 +8,7 @@ pub(crate) use crate::params::*;
 pub(crate) use rpg_encoder::semantic_lifting::SEMANTIC_ROUTING_PROMPT as ROUTING_PROMPT;
 
 use anyhow::Result;
+use rpg_core::delta::GraphDelta;
 use rpg_core::graph::RPGraph;
 use rpg_core::storage;
 use serde::Deserialize;
@@ -54,6 +55,76 @@ pub(crate) struct HierarchySession {
     pub(crate) batches_completed: usize,
 }
 
+/// Maximum number of submissions kept for `undo_last_submission`. The journal is
+/// never persisted, so it starts empty whenever the server starts.
+pub(crate) const JOURNAL_LIMIT: usize = 20;
+
+/// One graph mutation made by a submit tool.
+pub(crate) struct Submission {
+    pub(crate) id: u64,
+    pub(crate) tool: &'static str,
+    pub(crate) recorded_at: chrono::DateTime<chrono::Utc>,
+    pub(crate) delta: GraphDelta,
+    /// Pending routing queue before the submission, restored on undo
+    /// (`None` for tools that do not touch it).
+    pub(crate) pending_before: Option<Vec<PendingRouting>>,
+}
+
+/// Operation journal of recent submissions, newest last, for undo.
+///
+/// Only the submit tools are journaled. Anything else that replaces or rewrites the
+/// graph (build, update, reload, finalize) clears the journal, since older deltas
+/// would no longer invert cleanly.
+#[derive(Default)]
+pub(crate) struct SubmissionJournal {
+    entries: std::collections::VecDeque<Submission>,
+    next_id: u64,
+}
+
+impl SubmissionJournal {
+    /// Record a submission. No-op deltas (nothing matched) are not recorded.
+    /// Returns the submission ID, if recorded.
+    pub(crate) fn record(
+        &mut self,
+        tool: &'static str,
+        delta: GraphDelta,
+        pending_before: Option<Vec<PendingRouting>>,
+    ) -> Option<u64> {
+        if !delta.has_content_changes() {
+            return None;
+        }
+        self.next_id += 1;
+        self.entries.push_back(Submission {
+            id: self.next_id,
+            tool,
+            recorded_at: chrono::Utc::now(),
+            delta,
+            pending_before,
+        });
+        while self.entries.len() > JOURNAL_LIMIT {
+            self.entries.pop_front();
+        }
+        Some(self.next_id)
+    }
+
+    pub(crate) fn last(&self) -> Option<&Submission> {
+        self.entries.back()
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Submission> {
+        self.entries.pop_back()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Submissions, newest first.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Submission> {
+        self.entries.iter().rev()
+    }
+}
+
 /// Load pending routing state from disk, if it exists.
 pub(crate) fn load_pending_routing(project_root: &std::path::Path) -> Option<PendingRoutingState> {
     let path = storage::pending_routing_file(project_root);
@@ -126,6 +197,48 @@ mod tests {
         assert!(output.contains("submit_lift_results"));
     }
 
+    fn lift_delta(id: &str) -> GraphDelta {
+        let mut before = RPGraph::new("rust");
+        before.insert_entity(rpg_core::graph::Entity {
+            id: id.to_string(),
+            kind: rpg_core::graph::EntityKind::Function,
+            name: id.to_string(),
+            file: std::path::PathBuf::from("a.rs"),
+            line_start: 1,
+            line_end: 2,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            hierarchy_path: String::new(),
+            deps: rpg_core::graph::EntityDeps::default(),
+            signature: None,
+            metadata: rpg_core::graph::EntityMetadata::default(),
+            signals: None,
+        });
+        let mut after = before.clone();
+        after.entities.get_mut(id).unwrap().semantic_features = vec!["do work".to_string()];
+        GraphDelta::between(&before, &after)
+    }
+
+    #[test]
+    fn test_journal_records_newest_last_and_bounds_size() {
+        let mut journal = SubmissionJournal::default();
+        assert_eq!(
+            journal.record("submit_lift_results", GraphDelta::default(), None),
+            None,
+            "no-op submissions are not journaled"
+        );
+        for i in 0..JOURNAL_LIMIT + 3 {
+            journal.record("submit_lift_results", lift_delta(&format!("e{i}")), None);
+        }
+        assert_eq!(journal.iter().count(), JOURNAL_LIMIT);
+        let newest = journal.pop().unwrap();
+        assert_eq!(newest.id, (JOURNAL_LIMIT + 3) as u64);
+        assert_eq!(journal.last().unwrap().id, newest.id - 1);
+        journal.clear();
+        assert!(journal.last().is_none());
+    }
+
     #[test]
     fn test_review_candidates_empty() {
         let output = format_review_candidates(&[]);
@@ -29,6 +29,8 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy:
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -78,11 +80,13 @@ fn make_test_graph() -> RPGraph {
         source: "main.rs:main".to_string(),
         target: "lib.rs:process".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "lib.rs:process".to_string(),
         target: "lib.rs:validate".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
 
     graph.refresh_metadata();
@@ -196,6 +200,7 @@ fn build_nextjs_fixture_graph() -> RPGraph {
             Language::TYPESCRIPT,
             &mut raw_entities,
         );
+        rpg_parser::entities::disambiguate_ids(&mut raw_entities);
 
         for entity in raw_entities {
             graph.insert_entity(entity.into_entity());
@@ -650,6 +655,8 @@ fn test_restore_only_routing_refreshes_hierarchy_ids_and_containment() {
         hierarchy_path: "Auth/login".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     graph.insert_entity(auth_entity);
     graph.insert_into_hierarchy("Auth/login", "src/auth.py:login");
@@ -668,6 +675,8 @@ fn test_restore_only_routing_refreshes_hierarchy_ids_and_containment() {
         hierarchy_path: "Data/loading".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     };
     graph.insert_entity(data_entity);
     graph.insert_into_hierarchy("Data/loading", "src/data.py:load");
@@ -810,6 +819,7 @@ fn test_explore_data_flow_edge_filter() {
         source: "main.rs:caller".to_string(),
         target: "lib.rs:callee".to_string(),
         kind: EdgeKind::DataFlow,
+        confidence: None,
     });
     graph.refresh_metadata();
 
@@ -871,6 +881,7 @@ fn test_rpg_info_data_flow_edges_count() {
         source: "a.rs:f1".to_string(),
         target: "b.rs:f2".to_string(),
         kind: EdgeKind::DataFlow,
+        confidence: None,
     });
     graph.refresh_metadata();
 
@@ -921,6 +932,7 @@ fn test_rpg_info_area_connectivity() {
         source: "auth.rs:validate".to_string(),
         target: "db.rs:query".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.refresh_metadata();
 
@@ -1,6 +1,9 @@
 use criterion::{Criterion, criterion_group, criterion_main};
 use rpg_core::graph::*;
-use rpg_nav::search::{SearchMode, search};
+use rpg_nav::embeddings::{EmbeddingBackend, HashedNgramBackend};
+use rpg_nav::hnsw::{Hnsw, HnswParams};
+use rpg_nav::search::{SearchMode, SearchParams, search, search_with_params};
+use rpg_nav::search_index::SearchIndex;
 use std::hint::black_box;
 use std::path::PathBuf;
 
@@ -18,6 +21,8 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy:
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -180,6 +185,55 @@ fn bench_search_with_scope(c: &mut Criterion) {
     });
 }
 
+fn bench_search_indexed(c: &mut Criterion) {
+    let graph = build_search_graph(2000);
+    let index = SearchIndex::build(&graph);
+
+    c.bench_function("search_indexed_2000_entities", |b| {
+        b.iter(|| {
+            search_with_params(
+                black_box(&graph),
+                &SearchParams {
+                    query: black_box("authentication JWT token"),
+                    mode: SearchMode::Auto,
+                    scope: Some("Security"),
+                    limit: 10,
+                    line_nums: None,
+                    file_pattern: None,
+                    entity_type_filter: None,
+                    metadata_filter: None,
+                    embedding_scores: None,
+                    diff_context: None,
+                    index: Some(&index),
+                },
+            )
+        })
+    });
+}
+
+fn bench_hnsw_search(c: &mut Criterion) {
+    let graph = build_search_graph(2000);
+    let features: Vec<String> = graph
+        .entities
+        .values()
+        .flat_map(|e| e.semantic_features.iter().cloned())
+        .collect();
+    let mut backend = HashedNgramBackend::default();
+    let vectors = backend.embed(features).unwrap();
+    let mut index = Hnsw::new(backend.dimension(), HnswParams::default());
+    for vector in &vectors {
+        index.insert(vector);
+    }
+    let query = backend
+        .embed(vec!["authentication JWT token".to_string()])
+        .unwrap()
+        .remove(0);
+
+    c.bench_function("hnsw_search_2000_entities", |b| {
+        b.iter(|| index.search(black_box(&query), 256))
+    });
+}
+
 criterion_group!(
     benches,
     bench_search_small,
@@ -187,5 +241,7 @@ criterion_group!(
     bench_search_large,
     bench_search_snippets,
     bench_search_with_scope,
+    bench_search_indexed,
+    bench_hnsw_search,
 );
 criterion_main!(benches);
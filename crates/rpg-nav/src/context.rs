@@ -53,8 +53,10 @@ pub fn build_context_pack(
             line_nums: None,
             file_pattern: None,
             entity_type_filter: None,
+            metadata_filter: None,
             embedding_scores,
             diff_context: None,
+            index: None,
         },
     );
 
@@ -163,7 +165,7 @@ fn read_source_truncated(
     max_lines: usize,
 ) -> Option<String> {
     let file_path = project_root.join(&entity.file);
-    let content = std::fs::read_to_string(&file_path).ok()?;
+    let content = rpg_core::notebook::read_source(&file_path)?;
     let lines: Vec<&str> = content.lines().collect();
     let start = entity.line_start.saturating_sub(1);
     let end = entity.line_end.min(lines.len());
@@ -246,7 +248,9 @@ fn estimate_tokens(primary: &[PackedEntity], neighborhood: &[PackedEntity]) -> u
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, RPGraph};
+    use rpg_core::graph::{
+        DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, EntityMetadata, RPGraph,
+    };
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str, features: Vec<&str>) -> Entity {
@@ -263,6 +267,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -286,11 +292,13 @@ mod tests {
                 source: "src/lib.rs:a".to_string(),
                 target: "src/lib.rs:b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "src/lib.rs:b".to_string(),
                 target: "src/lib.rs:c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
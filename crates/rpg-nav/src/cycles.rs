@@ -423,7 +423,7 @@ pub fn detect_cycles(graph: &RPGraph, config: &CycleConfig) -> CycleReport {
             stats
         })
         .collect();
-    area_breakdown.sort_by(|a, b| b.cycle_count.cmp(&a.cycle_count));
+    area_breakdown.sort_by_key(|b| std::cmp::Reverse(b.cycle_count));
 
     let areas_in_cycles = area_breakdown.len();
 
@@ -550,7 +550,7 @@ fn deduplicate_cycles(cycles: Vec<Cycle>) -> Vec<Cycle> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind, EntityMetadata};
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str, kind: EntityKind, file: &str) -> Entity {
@@ -567,6 +567,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -590,11 +592,13 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -621,16 +625,19 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "c".to_string(),
                 target: "a".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -653,11 +660,13 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "a".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -694,27 +703,32 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "a".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             // Cycle 2: C -> D -> E -> C
             DependencyEdge {
                 source: "c".to_string(),
                 target: "d".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "d".to_string(),
                 target: "e".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "e".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -784,6 +798,7 @@ mod tests {
                 source: format!("n{}", i),
                 target: format!("n{}", next),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             });
         }
         graph.refresh_metadata();
@@ -832,6 +847,7 @@ mod tests {
                 source: "parent".to_string(),
                 target: "child".to_string(),
                 kind: EdgeKind::Contains,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -914,21 +930,25 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "a".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "c".to_string(),
                 target: "d".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "d".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -1028,21 +1048,25 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "a".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "c".to_string(),
                 target: "d".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "d".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -104,7 +104,7 @@ fn l1_area(path: &str) -> &str {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind, EntityMetadata};
     use std::path::PathBuf;
 
     fn make_entity(name: &str, hierarchy: &str) -> Entity {
@@ -121,6 +121,8 @@ mod tests {
             hierarchy_path: hierarchy.to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -139,6 +141,7 @@ mod tests {
             source: "src/auth.rs:validate".to_string(),
             target: "src/db.rs:query".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = compute_area_invocations(&graph);
@@ -164,6 +167,7 @@ mod tests {
             source: "src/auth.rs:validate".to_string(),
             target: "src/auth.rs:refresh".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = compute_area_invocations(&graph);
@@ -208,11 +212,13 @@ mod tests {
             source: "src/auth.rs:validate".to_string(),
             target: "src/db.rs:query".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "src/auth.rs:refresh".to_string(),
             target: "src/db.rs:query".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = compute_area_invocations(&graph);
@@ -121,7 +121,9 @@ pub fn apply_proximity_boost(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind};
+    use rpg_core::graph::{
+        DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, EntityMetadata,
+    };
     use std::path::PathBuf;
 
     fn make_test_entity(id: &str, file: &str) -> Entity {
@@ -138,6 +140,8 @@ mod tests {
             hierarchy_path: "Test".to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -164,16 +168,19 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "C".to_string(),
             target: "D".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let mut changed = HashSet::new();
@@ -208,16 +215,19 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "D".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let mut changed = HashSet::new();
@@ -111,12 +111,14 @@ pub fn detect_semantic_duplicates(
     graph: &RPGraph,
     config: &SemanticDuplicationConfig,
 ) -> Vec<SemanticCloneGroup> {
-    // Step 1: Collect eligible entities (exclude Modules, require min_features)
+    // Step 1: Collect eligible entities (exclude Modules and data, require min_features)
     let eligible: Vec<(&String, &str, &[String])> = graph
         .entities
         .iter()
         .filter(|(_, e)| {
-            e.kind != EntityKind::Module && e.semantic_features.len() >= config.min_features
+            e.kind != EntityKind::Module
+                && !e.kind.is_data()
+                && e.semantic_features.len() >= config.min_features
         })
         .map(|(id, e)| {
             let file = e.file.to_str().unwrap_or("");
@@ -461,11 +463,11 @@ pub fn detect_duplication(
 ) -> Vec<CloneGroup> {
     use rayon::prelude::*;
 
-    // Collect entities to analyze (skip Module entities)
+    // Collect entities to analyze (skip Module and data entities)
     let entities: Vec<_> = graph
         .entities
         .iter()
-        .filter(|(_, e)| e.kind != EntityKind::Module)
+        .filter(|(_, e)| e.kind != EntityKind::Module && !e.kind.is_data())
         .collect();
 
     // Phase 1: Cache file contents (read each file once, shared across entities)
@@ -476,7 +478,7 @@ pub fn detect_duplication(
             .collect();
         unique_files
             .into_iter()
-            .filter_map(|p| std::fs::read_to_string(&p).ok().map(|s| (p, s)))
+            .filter_map(|p| rpg_core::notebook::read_source(&p).map(|s| (p, s)))
             .collect()
     };
 
@@ -742,7 +744,7 @@ mod tests {
 
     // --- Semantic duplication tests ---
 
-    use rpg_core::graph::{Entity, EntityDeps};
+    use rpg_core::graph::{Entity, EntityDeps, EntityMetadata};
     use std::path::PathBuf;
 
     fn make_entity_with_features(id: &str, file: &str, features: Vec<&str>) -> Entity {
@@ -759,6 +761,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -881,6 +885,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
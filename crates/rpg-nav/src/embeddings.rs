@@ -1,11 +1,14 @@
-//! Embedding-based semantic search using fastembed.
+//! Embedding-based semantic search behind a pluggable backend.
 //!
 //! Stores per-feature embeddings for each entity, enabling max-cosine similarity
-//! search that preserves multi-role entity semantics. Uses BGE-small-en-v1.5
-//! (384 dimensions) via the fastembed crate.
+//! search that preserves multi-role entity semantics. Vectors come from an
+//! [`EmbeddingBackend`]: BGE-small-en-v1.5 (384 dimensions) via fastembed when the
+//! `embeddings` feature is enabled, or the offline [`HashedNgramBackend`], which
+//! needs no model download and works in air-gapped environments.
 
+use crate::hnsw::{Hnsw, HnswParams};
 use anyhow::{Context, Result, ensure};
-use fastembed::{EmbeddingModel, TextEmbedding};
+use rpg_core::config::EmbeddingBackendKind;
 use rpg_core::graph::RPGraph;
 use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
@@ -14,11 +17,195 @@ use std::path::{Path, PathBuf};
 /// Magic bytes for the binary embedding file format.
 const MAGIC: u32 = 0x5250_4745; // "RPGE"
 const FORMAT_VERSION: u32 = 1;
-const DIMENSION: usize = 384;
+
+/// ANN graph sidecar, stored next to `embeddings.bin`.
+const ANN_FILE: &str = "embeddings.hnsw";
+
+/// Vector count up to which [`EmbeddingIndex::score_nearest`] scores exactly.
+const EXACT_SEARCH_LIMIT: usize = 4096;
+
+/// Semantic candidates to request from [`EmbeddingIndex::score_nearest`]
+/// when blending with lexical scores.
+pub const SEMANTIC_CANDIDATES: usize = 256;
 
 /// Loaded embeddings: entity map + fingerprints from disk.
 type LoadedEmbeddings = (HashMap<String, EntityEmbeddings>, BTreeMap<String, String>);
 
+/// A text embedding model.
+pub trait EmbeddingBackend: Send + Sync {
+    /// Stable model identifier, recorded in the index meta. Vectors from
+    /// different models are never mixed: a mismatch rebuilds the index.
+    fn model_id(&self) -> &str;
+
+    /// Length of every vector this backend returns.
+    fn dimension(&self) -> usize;
+
+    /// Embed a batch of texts, returning one vector per text in order.
+    fn embed(&mut self, texts: Vec<String>) -> Result<Vec<Vec<f32>>>;
+}
+
+/// BGE-small-en-v1.5 via fastembed. The model is downloaded into
+/// `.rpg/models/` on first use and runs offline afterwards.
+#[cfg(feature = "embeddings")]
+pub struct FastEmbedBackend {
+    model: fastembed::TextEmbedding,
+}
+
+#[cfg(feature = "embeddings")]
+impl FastEmbedBackend {
+    const MODEL_ID: &'static str = "BAAI/bge-small-en-v1.5";
+
+    /// Initialize the model with its cache in `rpg_dir/models/`.
+    pub fn new(rpg_dir: &Path) -> Result<Self> {
+        let cache_dir = rpg_dir.join("models");
+        std::fs::create_dir_all(&cache_dir)?;
+
+        let options = fastembed::TextInitOptions::new(fastembed::EmbeddingModel::BGESmallENV15)
+            .with_show_download_progress(true)
+            .with_cache_dir(cache_dir);
+
+        let model = fastembed::TextEmbedding::try_new(options)
+            .context("failed to initialize embedding model (BGE-small-en-v1.5)")?;
+
+        Ok(Self { model })
+    }
+}
+
+#[cfg(feature = "embeddings")]
+impl EmbeddingBackend for FastEmbedBackend {
+    fn model_id(&self) -> &str {
+        Self::MODEL_ID
+    }
+
+    fn dimension(&self) -> usize {
+        384
+    }
+
+    fn embed(&mut self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.model.embed(texts, None).context("fastembed failed")
+    }
+}
+
+/// Offline, model-free backend: signed feature hashing of words and
+/// character trigrams into a fixed-size, L2-normalized vector.
+///
+/// Words capture exact vocabulary overlap; trigrams of each word (with
+/// boundary markers) give partial credit to morphological variants such as
+/// "authenticate" / "authentication". Hashing is FNV-1a, so vectors are
+/// identical across platforms and toolchains.
+#[derive(Debug, Clone)]
+pub struct HashedNgramBackend {
+    dimension: usize,
+}
+
+impl HashedNgramBackend {
+    /// Default vector length.
+    pub const DEFAULT_DIMENSION: usize = 384;
+    const MODEL_ID: &'static str = "rpg/hashed-ngram-v1";
+    /// Weight of a whole word relative to one of its trigrams.
+    const WORD_WEIGHT: f32 = 2.0;
+
+    /// Create a backend producing `dimension`-length vectors.
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension: dimension.max(1),
+        }
+    }
+
+    fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0_f32; self.dimension];
+        let mut add = |key: &[u8], weight: f32| {
+            let hash = fnv1a(key);
+            let slot = (hash % self.dimension as u64) as usize;
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[slot] += sign * weight;
+        };
+        for token in crate::search_index::tokenize(text) {
+            add(format!("w:{token}").as_bytes(), Self::WORD_WEIGHT);
+            let padded: Vec<char> = format!("^{token}$").chars().collect();
+            for gram in padded.windows(3) {
+                add(
+                    format!("g:{}", gram.iter().collect::<String>()).as_bytes(),
+                    1.0,
+                );
+            }
+        }
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in &mut vector {
+                *v /= norm;
+            }
+        }
+        vector
+    }
+}
+
+impl Default for HashedNgramBackend {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DIMENSION)
+    }
+}
+
+impl EmbeddingBackend for HashedNgramBackend {
+    fn model_id(&self) -> &str {
+        Self::MODEL_ID
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed(&mut self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|t| self.embed_text(t)).collect())
+    }
+}
+
+/// 64-bit FNV-1a hash (stable across platforms, unlike `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Create the backend selected by `[navigation] embedding_backend`.
+///
+/// `Auto` prefers fastembed when compiled in and falls back to the offline
+/// backend if the model cannot be loaded (e.g. no network for the download).
+/// Returns `Ok(None)` when embeddings are turned off.
+pub fn init_backend(
+    project_root: &Path,
+    kind: EmbeddingBackendKind,
+) -> Result<Option<Box<dyn EmbeddingBackend>>> {
+    #[cfg(feature = "embeddings")]
+    let rpg_dir = rpg_core::storage::rpg_dir(project_root);
+    #[cfg(not(feature = "embeddings"))]
+    let _ = project_root;
+
+    match kind {
+        EmbeddingBackendKind::Off => Ok(None),
+        EmbeddingBackendKind::Hashed => Ok(Some(Box::new(HashedNgramBackend::default()))),
+        #[cfg(feature = "embeddings")]
+        EmbeddingBackendKind::Fastembed => Ok(Some(Box::new(FastEmbedBackend::new(&rpg_dir)?))),
+        #[cfg(not(feature = "embeddings"))]
+        EmbeddingBackendKind::Fastembed => {
+            anyhow::bail!("embedding_backend = \"fastembed\" requires the `embeddings` feature")
+        }
+        #[cfg(feature = "embeddings")]
+        EmbeddingBackendKind::Auto => match FastEmbedBackend::new(&rpg_dir) {
+            Ok(backend) => Ok(Some(Box::new(backend))),
+            Err(e) => {
+                eprintln!("rpg: {e:#} — using offline hashed embeddings");
+                Ok(Some(Box::new(HashedNgramBackend::default())))
+            }
+        },
+        #[cfg(not(feature = "embeddings"))]
+        EmbeddingBackendKind::Auto => Ok(Some(Box::new(HashedNgramBackend::default()))),
+    }
+}
+
 /// Metadata sidecar for the embedding index.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct EmbeddingMeta {
@@ -39,10 +226,20 @@ struct EntityEmbeddings {
 }
 
 /// In-memory embedding index for semantic search.
+///
+/// Feature vectors live in an HNSW graph ([`Hnsw`]), persisted as
+/// `embeddings.hnsw` next to `embeddings.bin` and updated in place as
+/// entities are re-embedded or pruned.
 pub struct EmbeddingIndex {
-    model: TextEmbedding,
-    /// Map from entity_id → feature-level embeddings.
-    entities: HashMap<String, EntityEmbeddings>,
+    backend: Box<dyn EmbeddingBackend>,
+    /// Map from entity_id → ANN slot of each feature vector, in feature order.
+    entities: HashMap<String, Vec<u32>>,
+    /// Nearest-neighbour graph over all feature vectors (owns the vectors).
+    ann: Hnsw,
+    /// Entity owning each ANN slot (empty for free slots).
+    owners: Vec<String>,
+    /// Indexes with at most this many vectors are scored exactly.
+    exact_search_limit: usize,
     /// Path to the .rpg directory for persistence.
     rpg_dir: PathBuf,
     /// The graph's updated_at timestamp this index corresponds to.
@@ -67,24 +264,35 @@ pub struct SyncStats {
 impl EmbeddingIndex {
     /// Load existing index from disk, or create a new empty one.
     /// Fingerprints are loaded from meta for incremental sync support.
-    pub fn load_or_init(project_root: &Path, graph_updated_at: &str) -> Result<Self> {
+    /// Vectors written by a different backend are discarded.
+    pub fn load_or_init(
+        project_root: &Path,
+        graph_updated_at: &str,
+        backend: Box<dyn EmbeddingBackend>,
+    ) -> Result<Self> {
         let rpg_dir = project_root.join(".rpg");
-        let model = init_model(&rpg_dir)?;
 
         let embeddings_path = rpg_dir.join("embeddings.bin");
         let meta_path = rpg_dir.join("embeddings.meta.json");
+        let ann_path = rpg_dir.join(ANN_FILE);
+
+        let mut index = Self {
+            ann: Hnsw::new(backend.dimension(), HnswParams::default()),
+            backend,
+            entities: HashMap::new(),
+            owners: Vec::new(),
+            exact_search_limit: EXACT_SEARCH_LIMIT,
+            rpg_dir,
+            graph_updated_at: graph_updated_at.to_string(),
+            fingerprints: BTreeMap::new(),
+        };
 
         // Try loading existing index (resilient to corruption)
         if embeddings_path.exists() && meta_path.exists() {
-            match Self::try_load_existing(&meta_path, &embeddings_path) {
+            match Self::try_load_existing(&meta_path, &embeddings_path, index.backend.as_ref()) {
                 Ok(Some((entities, fingerprints))) => {
-                    return Ok(Self {
-                        model,
-                        entities,
-                        rpg_dir,
-                        graph_updated_at: graph_updated_at.to_string(),
-                        fingerprints,
-                    });
+                    index.fingerprints = fingerprints;
+                    index.restore(entities, &ann_path);
                 }
                 Ok(None) => {
                     // Model/dimension mismatch — start fresh
@@ -94,18 +302,12 @@ impl EmbeddingIndex {
                     eprintln!("rpg: corrupt embedding index, rebuilding: {e}");
                     let _ = std::fs::remove_file(&embeddings_path);
                     let _ = std::fs::remove_file(&meta_path);
+                    let _ = std::fs::remove_file(&ann_path);
                 }
             }
         }
 
-        // No valid index — start fresh
-        Ok(Self {
-            model,
-            entities: HashMap::new(),
-            rpg_dir,
-            graph_updated_at: graph_updated_at.to_string(),
-            fingerprints: BTreeMap::new(),
-        })
+        Ok(index)
     }
 
     /// Try to load existing embedding data. Returns Ok(Some((entities, fingerprints)))
@@ -113,6 +315,7 @@ impl EmbeddingIndex {
     fn try_load_existing(
         meta_path: &Path,
         embeddings_path: &Path,
+        backend: &dyn EmbeddingBackend,
     ) -> Result<Option<LoadedEmbeddings>> {
         let meta_json =
             std::fs::read_to_string(meta_path).context("failed to read embeddings meta")?;
@@ -120,14 +323,125 @@ impl EmbeddingIndex {
             serde_json::from_str(&meta_json).context("failed to parse embeddings meta")?;
 
         // Only reject on model/dimension mismatch — fingerprints handle staleness
-        if meta.model != "BAAI/bge-small-en-v1.5" || meta.dimension != DIMENSION as u32 {
+        if meta.model != backend.model_id() || meta.dimension as usize != backend.dimension() {
             return Ok(None);
         }
 
-        let entities = load_binary(embeddings_path)?;
+        let entities = load_binary(embeddings_path, backend.dimension())?;
         Ok(Some((entities, meta.entity_fingerprints)))
     }
 
+    /// Populate the ANN graph from loaded vectors, reusing the persisted
+    /// graph when it matches them and rebuilding it otherwise.
+    fn restore(&mut self, entities: HashMap<String, EntityEmbeddings>, ann_path: &Path) {
+        if ann_path.exists() {
+            match std::fs::read(ann_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| self.read_ann(&bytes, &entities))
+            {
+                Ok(()) => return,
+                Err(e) => eprintln!("rpg: rebuilding embedding ANN graph: {e}"),
+            }
+        }
+        let mut ids: Vec<&String> = entities.keys().collect();
+        ids.sort();
+        for id in ids {
+            self.insert_vectors(id, &entities[id].vectors);
+        }
+    }
+
+    /// Read the `embeddings.hnsw` layout: slot labels followed by the graph.
+    fn read_ann(
+        &mut self,
+        bytes: &[u8],
+        entities: &HashMap<String, EntityEmbeddings>,
+    ) -> Result<()> {
+        let mut cursor = bytes;
+        let slot_count = read_u32(&mut cursor)? as usize;
+        let mut labels: Vec<Option<(String, usize)>> =
+            Vec::with_capacity(slot_count.min(cursor.len() / 2));
+        for _ in 0..slot_count {
+            let id_len = read_u16(&mut cursor)? as usize;
+            if id_len == 0 {
+                labels.push(None);
+                continue;
+            }
+            ensure!(cursor.len() >= id_len, "unexpected end of {ANN_FILE}");
+            let id = std::str::from_utf8(&cursor[..id_len])
+                .context("invalid entity id")?
+                .to_string();
+            cursor = &cursor[id_len..];
+            let feature = read_u16(&mut cursor)? as usize;
+            labels.push(Some((id, feature)));
+        }
+
+        let ann = Hnsw::read(cursor, self.backend.dimension(), |slot| {
+            let (id, feature) = labels.get(slot as usize)?.as_ref()?;
+            entities.get(id)?.vectors.get(*feature).map(Vec::as_slice)
+        })?;
+        ensure!(
+            ann.slot_count() == labels.len(),
+            "{ANN_FILE} labels do not match its slots"
+        );
+        ensure!(
+            ann.len() == entities.values().map(|e| e.vectors.len()).sum::<usize>(),
+            "{ANN_FILE} does not cover every feature vector"
+        );
+
+        let mut slots: HashMap<String, Vec<u32>> = entities
+            .iter()
+            .map(|(id, e)| (id.clone(), vec![u32::MAX; e.vectors.len()]))
+            .collect();
+        let mut owners = vec![String::new(); labels.len()];
+        for (slot, label) in labels.into_iter().enumerate() {
+            let Some((id, feature)) = label else {
+                continue;
+            };
+            ensure!(ann.contains(slot as u32), "{ANN_FILE} labels a free slot");
+            let entry = slots
+                .get_mut(&id)
+                .and_then(|s| s.get_mut(feature))
+                .context("label for unknown vector")?;
+            ensure!(*entry == u32::MAX, "{ANN_FILE} labels a vector twice");
+            *entry = slot as u32;
+            owners[slot] = id;
+        }
+
+        self.ann = ann;
+        self.entities = slots;
+        self.owners = owners;
+        Ok(())
+    }
+
+    /// Add an entity's vectors to the ANN graph, replacing any previous ones.
+    fn insert_vectors(&mut self, entity_id: &str, vectors: &[Vec<f32>]) {
+        self.remove_vectors(entity_id);
+        let slots: Vec<u32> = vectors
+            .iter()
+            .map(|vector| {
+                let slot = self.ann.insert(vector);
+                if self.owners.len() <= slot as usize {
+                    self.owners.resize(slot as usize + 1, String::new());
+                }
+                self.owners[slot as usize] = entity_id.to_string();
+                slot
+            })
+            .collect();
+        self.entities.insert(entity_id.to_string(), slots);
+    }
+
+    /// Remove an entity's vectors from the ANN graph.
+    fn remove_vectors(&mut self, entity_id: &str) -> bool {
+        let Some(slots) = self.entities.remove(entity_id) else {
+            return false;
+        };
+        for slot in slots {
+            self.ann.remove(slot);
+            self.owners[slot as usize].clear();
+        }
+        true
+    }
+
     /// Embed features for a set of entities and add/update them in the index.
     /// `entity_features` maps entity_id → list of semantic feature strings.
     pub fn embed_entities(
@@ -156,17 +470,29 @@ impl EmbeddingIndex {
         }
 
         // Embed all features in one batch
+        let feature_total = all_features.len();
         let embeddings = self
-            .model
-            .embed(all_features, None)
+            .backend
+            .embed(all_features)
             .context("failed to embed features")?;
+        ensure!(
+            embeddings.len() == feature_total,
+            "backend returned {} vectors for {} features",
+            embeddings.len(),
+            feature_total
+        );
+        let dimension = self.backend.dimension();
+        ensure!(
+            embeddings.iter().all(|v| v.len() == dimension),
+            "backend returned vectors that are not {dimension}-dimensional"
+        );
 
-        // Distribute embeddings back to entities
+        // Distribute embeddings back to entities, in a stable order so the
+        // ANN graph is reproducible
+        feature_map.sort();
         let mut count = 0;
         for (entity_id, start, feat_count) in &feature_map {
-            let vectors: Vec<Vec<f32>> = embeddings[*start..*start + *feat_count].to_vec();
-            self.entities
-                .insert(entity_id.clone(), EntityEmbeddings { vectors });
+            self.insert_vectors(entity_id, &embeddings[*start..*start + *feat_count]);
             count += 1;
         }
 
@@ -222,7 +548,7 @@ impl EmbeddingIndex {
             .cloned()
             .collect();
         for id in &to_prune {
-            self.entities.remove(id);
+            self.remove_vectors(id);
             stats.pruned += 1;
         }
 
@@ -255,7 +581,15 @@ impl EmbeddingIndex {
 
     /// Remove entities that no longer exist in the graph.
     pub fn prune(&mut self, valid_entity_ids: &std::collections::HashSet<String>) {
-        self.entities.retain(|id, _| valid_entity_ids.contains(id));
+        let stale: Vec<String> = self
+            .entities
+            .keys()
+            .filter(|id| !valid_entity_ids.contains(*id))
+            .cloned()
+            .collect();
+        for id in &stale {
+            self.remove_vectors(id);
+        }
         self.fingerprints
             .retain(|id, _| valid_entity_ids.contains(id));
     }
@@ -263,19 +597,56 @@ impl EmbeddingIndex {
     /// Score all entities against a query string using max-cosine similarity.
     /// Returns entity_id → score (0.0..1.0).
     pub fn score_all(&mut self, query: &str) -> Result<HashMap<String, f64>> {
-        let query_embeddings = self
-            .model
-            .embed(vec![query.to_string()], None)
+        let query_vec = self.embed_query(query)?;
+        Ok(self.score_exact(&query_vec))
+    }
+
+    /// Score the entities nearest to a query using max-cosine similarity.
+    ///
+    /// Small indexes are scored exactly, like [`score_all`](Self::score_all).
+    /// Larger ones query the ANN graph and return at least the `limit` best
+    /// entities it finds.
+    pub fn score_nearest(&mut self, query: &str, limit: usize) -> Result<HashMap<String, f64>> {
+        let query_vec = self.embed_query(query)?;
+        if self.ann.len() <= self.exact_search_limit {
+            return Ok(self.score_exact(&query_vec));
+        }
+
+        // Entities own several vectors; over-fetch so `limit` distinct ones survive
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for (slot, similarity) in self.ann.search(&query_vec, limit.saturating_mul(2)) {
+            let similarity = f64::from(similarity);
+            if similarity <= 0.0 {
+                continue;
+            }
+            let best = scores
+                .entry(self.owners[slot as usize].clone())
+                .or_insert(similarity);
+            *best = best.max(similarity);
+        }
+        Ok(scores)
+    }
+
+    fn embed_query(&mut self, query: &str) -> Result<Vec<f32>> {
+        let mut query_embeddings = self
+            .backend
+            .embed(vec![query.to_string()])
             .context("failed to embed query")?;
+        ensure!(
+            !query_embeddings.is_empty(),
+            "backend returned no query vector"
+        );
+        Ok(query_embeddings.swap_remove(0))
+    }
 
-        let query_vec = &query_embeddings[0];
+    /// Brute-force max-cosine over every feature vector.
+    fn score_exact(&self, query_vec: &[f32]) -> HashMap<String, f64> {
         let mut scores = HashMap::new();
 
-        for (entity_id, entity_emb) in &self.entities {
-            let max_sim = entity_emb
-                .vectors
+        for (entity_id, slots) in &self.entities {
+            let max_sim = slots
                 .iter()
-                .map(|fv| cosine_similarity(query_vec, fv))
+                .map(|&slot| cosine_similarity(query_vec, self.ann.vector(slot)))
                 .fold(f64::NEG_INFINITY, f64::max);
 
             if max_sim > 0.0 {
@@ -283,7 +654,12 @@ impl EmbeddingIndex {
             }
         }
 
-        Ok(scores)
+        scores
+    }
+
+    /// Identifier of the backend model producing this index's vectors.
+    pub fn model_id(&self) -> &str {
+        self.backend.model_id()
     }
 
     /// Number of entities in the index.
@@ -291,14 +667,29 @@ impl EmbeddingIndex {
         self.entities.len()
     }
 
-    /// Save the index to disk (binary + meta sidecar with fingerprints).
+    /// Number of feature vectors in the index.
+    pub fn vector_count(&self) -> usize {
+        self.ann.len()
+    }
+
+    /// Save the index to disk (binary + ANN graph + meta sidecar with fingerprints).
     pub fn save(&self) -> Result<()> {
         std::fs::create_dir_all(&self.rpg_dir)?;
-        save_binary(&self.rpg_dir.join("embeddings.bin"), &self.entities)?;
+        let dimension = self.backend.dimension();
+        let entities: Vec<(&str, Vec<&[f32]>)> = self
+            .entities
+            .iter()
+            .map(|(id, slots)| {
+                let vectors = slots.iter().map(|&slot| self.ann.vector(slot)).collect();
+                (id.as_str(), vectors)
+            })
+            .collect();
+        save_binary(&self.rpg_dir.join("embeddings.bin"), &entities, dimension)?;
+        std::fs::write(self.rpg_dir.join(ANN_FILE), self.write_ann()?)?;
 
         let meta = EmbeddingMeta {
-            model: "BAAI/bge-small-en-v1.5".to_string(),
-            dimension: DIMENSION as u32,
+            model: self.backend.model_id().to_string(),
+            dimension: dimension as u32,
             version: FORMAT_VERSION,
             graph_updated_at: self.graph_updated_at.clone(),
             entity_fingerprints: self.fingerprints.clone(),
@@ -309,6 +700,28 @@ impl EmbeddingIndex {
         Ok(())
     }
 
+    /// Serialize slot labels (entity id + feature index) followed by the graph.
+    fn write_ann(&self) -> Result<Vec<u8>> {
+        let mut feature_of: HashMap<u32, u16> = HashMap::with_capacity(self.ann.len());
+        for slots in self.entities.values() {
+            for (feature, &slot) in slots.iter().enumerate() {
+                feature_of.insert(slot, feature as u16);
+            }
+        }
+
+        let mut buf: Vec<u8> = Vec::new();
+        buf.write_all(&(self.owners.len() as u32).to_le_bytes())?;
+        for (slot, owner) in self.owners.iter().enumerate() {
+            buf.write_all(&(owner.len() as u16).to_le_bytes())?;
+            if !owner.is_empty() {
+                buf.write_all(owner.as_bytes())?;
+                buf.write_all(&feature_of[&(slot as u32)].to_le_bytes())?;
+            }
+        }
+        buf.extend(self.ann.write()?);
+        Ok(buf)
+    }
+
     /// Update the tracked graph timestamp (call before save after graph changes).
     pub fn set_graph_updated_at(&mut self, ts: &str) {
         self.graph_updated_at = ts.to_string();
@@ -328,21 +741,6 @@ fn compute_fingerprint(features: &[String]) -> String {
     format!("{:016x}", hasher.finish())
 }
 
-/// Initialize the fastembed model with cache in .rpg/models/.
-fn init_model(rpg_dir: &Path) -> Result<TextEmbedding> {
-    let cache_dir = rpg_dir.join("models");
-    std::fs::create_dir_all(&cache_dir)?;
-
-    let options = fastembed::TextInitOptions::new(EmbeddingModel::BGESmallENV15)
-        .with_show_download_progress(true)
-        .with_cache_dir(cache_dir);
-
-    let model = TextEmbedding::try_new(options)
-        .context("failed to initialize embedding model (BGE-small-en-v1.5)")?;
-
-    Ok(model)
-}
-
 /// Cosine similarity between two vectors.
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
     debug_assert_eq!(a.len(), b.len());
@@ -366,17 +764,17 @@ fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
 }
 
 /// Save entity embeddings to binary format.
-fn save_binary(path: &Path, entities: &HashMap<String, EntityEmbeddings>) -> Result<()> {
+fn save_binary(path: &Path, entities: &[(&str, Vec<&[f32]>)], dimension: usize) -> Result<()> {
     let mut buf: Vec<u8> = Vec::new();
 
     // Header (16 bytes)
     buf.write_all(&MAGIC.to_le_bytes())?;
     buf.write_all(&FORMAT_VERSION.to_le_bytes())?;
-    buf.write_all(&(DIMENSION as u32).to_le_bytes())?;
+    buf.write_all(&(dimension as u32).to_le_bytes())?;
     buf.write_all(&(entities.len() as u32).to_le_bytes())?;
 
     // Per entity
-    for (id, emb) in entities {
+    for (id, vectors) in entities {
         let id_bytes = id.as_bytes();
         ensure!(
             u16::try_from(id_bytes.len()).is_ok(),
@@ -384,16 +782,23 @@ fn save_binary(path: &Path, entities: &HashMap<String, EntityEmbeddings>) -> Res
             id_bytes.len()
         );
         ensure!(
-            u16::try_from(emb.vectors.len()).is_ok(),
+            u16::try_from(vectors.len()).is_ok(),
             "too many feature vectors for {}: {}",
             id,
-            emb.vectors.len()
+            vectors.len()
         );
         buf.write_all(&(id_bytes.len() as u16).to_le_bytes())?;
         buf.write_all(id_bytes)?;
-        buf.write_all(&(emb.vectors.len() as u16).to_le_bytes())?;
-        for vec in &emb.vectors {
-            for &val in vec {
+        buf.write_all(&(vectors.len() as u16).to_le_bytes())?;
+        for vec in vectors {
+            ensure!(
+                vec.len() == dimension,
+                "vector for {} has {} dimensions, expected {}",
+                id,
+                vec.len(),
+                dimension
+            );
+            for &val in *vec {
                 buf.write_all(&val.to_le_bytes())?;
             }
         }
@@ -404,7 +809,10 @@ fn save_binary(path: &Path, entities: &HashMap<String, EntityEmbeddings>) -> Res
 }
 
 /// Load entity embeddings from binary format.
-fn load_binary(path: &Path) -> Result<HashMap<String, EntityEmbeddings>> {
+fn load_binary(
+    path: &Path,
+    expected_dimension: usize,
+) -> Result<HashMap<String, EntityEmbeddings>> {
     let data = std::fs::read(path).context("failed to read embeddings.bin")?;
     let mut cursor = &data[..];
 
@@ -414,7 +822,7 @@ fn load_binary(path: &Path) -> Result<HashMap<String, EntityEmbeddings>> {
     let version = read_u32(&mut cursor)?;
     anyhow::ensure!(version == FORMAT_VERSION, "unsupported embeddings version");
     let dimension = read_u32(&mut cursor)? as usize;
-    anyhow::ensure!(dimension == DIMENSION, "dimension mismatch");
+    anyhow::ensure!(dimension == expected_dimension, "dimension mismatch");
     let entity_count = read_u32(&mut cursor)? as usize;
 
     let mut entities = HashMap::with_capacity(entity_count);
@@ -601,15 +1009,23 @@ mod tests {
         entities.insert(
             "test:func".to_string(),
             EntityEmbeddings {
-                vectors: vec![vec![0.1; DIMENSION], vec![0.2; DIMENSION]],
+                vectors: vec![vec![0.1; 384], vec![0.2; 384]],
             },
         );
 
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("test.bin");
 
-        save_binary(&path, &entities).unwrap();
-        let loaded = load_binary(&path).unwrap();
+        let entries: Vec<(&str, Vec<&[f32]>)> = entities
+            .iter()
+            .map(|(id, e)| (id.as_str(), e.vectors.iter().map(Vec::as_slice).collect()))
+            .collect();
+        save_binary(&path, &entries, 384).unwrap();
+        assert!(
+            load_binary(&path, 256).is_err(),
+            "dimension mismatch rejected"
+        );
+        let loaded = load_binary(&path, 384).unwrap();
 
         assert_eq!(loaded.len(), 1);
         assert!(loaded.contains_key("test:func"));
@@ -814,4 +1230,219 @@ mod tests {
         // Empty fingerprints should be omitted from JSON
         assert!(!json.contains("entity_fingerprints"));
     }
+
+    /// Backend returning a fixed vector per known word, to exercise the trait.
+    struct StubBackend;
+
+    impl EmbeddingBackend for StubBackend {
+        fn model_id(&self) -> &'static str {
+            "stub"
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+
+        fn embed(&mut self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    if t.contains("auth") {
+                        vec![1.0, 0.0]
+                    } else {
+                        vec![0.0, 1.0]
+                    }
+                })
+                .collect())
+        }
+    }
+
+    fn lifted_graph() -> RPGraph {
+        use rpg_core::graph::{Entity, EntityDeps, EntityKind, EntityMetadata};
+        let mut graph = RPGraph::new("rust");
+        for (id, features) in [
+            ("a.rs:login", vec!["authenticate user credentials"]),
+            ("b.rs:query", vec!["execute database query"]),
+            ("c.rs:helper", vec![]),
+        ] {
+            graph.insert_entity(Entity {
+                id: id.to_string(),
+                kind: EntityKind::Function,
+                name: id.to_string(),
+                file: PathBuf::from("x.rs"),
+                line_start: 1,
+                line_end: 2,
+                parent_class: None,
+                semantic_features: features.into_iter().map(String::from).collect(),
+                feature_source: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                metadata: EntityMetadata::default(),
+                signals: None,
+            });
+        }
+        graph
+    }
+
+    #[test]
+    fn test_hashed_backend_deterministic_and_normalized() {
+        let mut backend = HashedNgramBackend::default();
+        let a = backend
+            .embed(vec!["validate JWT token".to_string()])
+            .unwrap();
+        let b = backend
+            .embed(vec!["validate JWT token".to_string()])
+            .unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a[0].len(), HashedNgramBackend::DEFAULT_DIMENSION);
+        let norm: f32 = a[0].iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        let empty = backend.embed(vec![String::new()]).unwrap();
+        assert!(empty[0].iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_hashed_backend_ranks_related_text_higher() {
+        let mut backend = HashedNgramBackend::default();
+        let vecs = backend
+            .embed(vec![
+                "handle authentication".to_string(),
+                "authenticate user credentials".to_string(),
+                "execute database query".to_string(),
+            ])
+            .unwrap();
+        let related = cosine_similarity(&vecs[0], &vecs[1]);
+        let unrelated = cosine_similarity(&vecs[0], &vecs[2]);
+        assert!(related > unrelated + 0.1, "{related} vs {unrelated}");
+    }
+
+    #[test]
+    fn test_index_offline_sync_score_and_reload() {
+        let tmp = tempfile::tempdir().unwrap();
+        let graph = lifted_graph();
+        let ts = graph.updated_at.to_rfc3339();
+
+        let mut index =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(HashedNgramBackend::default()))
+                .unwrap();
+        let stats = index.sync(&graph).unwrap();
+        assert_eq!(stats.added, 2, "unlifted entities are skipped");
+        let scores = index.score_all("user authentication").unwrap();
+        assert!(scores["a.rs:login"] > scores.get("b.rs:query").copied().unwrap_or(0.0));
+
+        let mut reloaded =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(HashedNgramBackend::default()))
+                .unwrap();
+        assert_eq!(reloaded.entity_count(), 2);
+        let stats = reloaded.sync(&graph).unwrap();
+        assert_eq!((stats.kept, stats.added), (2, 0));
+    }
+
+    #[test]
+    fn test_index_rebuilds_on_backend_change() {
+        let tmp = tempfile::tempdir().unwrap();
+        let graph = lifted_graph();
+        let ts = graph.updated_at.to_rfc3339();
+        let mut index =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(HashedNgramBackend::default()))
+                .unwrap();
+        index.sync(&graph).unwrap();
+
+        let mut stub =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(StubBackend)).unwrap();
+        assert_eq!(
+            stub.entity_count(),
+            0,
+            "vectors from another model are dropped"
+        );
+        let stats = stub.sync(&graph).unwrap();
+        assert_eq!(stats.added, 2);
+        let scores = stub.score_all("auth").unwrap();
+        assert_eq!(scores.len(), 1);
+        assert!((scores["a.rs:login"] - 1.0).abs() < 1e-6);
+
+        let meta: EmbeddingMeta = serde_json::from_str(
+            &std::fs::read_to_string(tmp.path().join(".rpg/embeddings.meta.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!((meta.model.as_str(), meta.dimension), ("stub", 2));
+    }
+
+    #[test]
+    fn test_score_nearest_uses_ann_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        let graph = lifted_graph();
+        let ts = graph.updated_at.to_rfc3339();
+        let mut index =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(HashedNgramBackend::default()))
+                .unwrap();
+        index.sync(&graph).unwrap();
+        let exact = index.score_nearest("user authentication", 10).unwrap();
+        assert_eq!(exact, index.score_all("user authentication").unwrap());
+
+        index.exact_search_limit = 0;
+        let approx = index.score_nearest("user authentication", 10).unwrap();
+        assert!((approx["a.rs:login"] - exact["a.rs:login"]).abs() < 1e-5);
+        assert!(approx.len() <= exact.len());
+    }
+
+    #[test]
+    fn test_ann_graph_persisted_and_updated_incrementally() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = lifted_graph();
+        let ts = graph.updated_at.to_rfc3339();
+        let mut index =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(StubBackend)).unwrap();
+        index.sync(&graph).unwrap();
+        assert_eq!(index.vector_count(), 2);
+        let ann_path = tmp.path().join(".rpg").join(ANN_FILE);
+        assert!(ann_path.exists());
+
+        // Re-lift one entity and delete the other: slots are replaced in place
+        graph
+            .entities
+            .get_mut("a.rs:login")
+            .unwrap()
+            .semantic_features = vec![
+            "refresh auth token".to_string(),
+            "parse headers".to_string(),
+        ];
+        graph.remove_entity("b.rs:query");
+        let stats = index.sync(&graph).unwrap();
+        assert_eq!((stats.changed, stats.pruned), (1, 1));
+        assert_eq!(index.vector_count(), 2);
+        index.exact_search_limit = 0;
+        let scores = index.score_nearest("auth", 5).unwrap();
+        assert_eq!(scores.keys().collect::<Vec<_>>(), ["a.rs:login"]);
+
+        // Reload reuses the persisted graph
+        let mut reloaded =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(StubBackend)).unwrap();
+        reloaded.exact_search_limit = 0;
+        assert_eq!(reloaded.vector_count(), 2);
+        assert_eq!(reloaded.owners, index.owners);
+        assert_eq!(reloaded.score_nearest("auth", 5).unwrap(), scores);
+
+        // A damaged graph file is rebuilt from embeddings.bin
+        std::fs::write(&ann_path, b"garbage").unwrap();
+        let mut rebuilt =
+            EmbeddingIndex::load_or_init(tmp.path(), &ts, Box::new(StubBackend)).unwrap();
+        assert_eq!(rebuilt.vector_count(), 2);
+        assert_eq!(rebuilt.score_all("auth").unwrap(), scores);
+    }
+
+    #[test]
+    fn test_init_backend_selection() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(
+            init_backend(tmp.path(), EmbeddingBackendKind::Off)
+                .unwrap()
+                .is_none()
+        );
+        let hashed = init_backend(tmp.path(), EmbeddingBackendKind::Hashed)
+            .unwrap()
+            .unwrap();
+        assert_eq!(hashed.model_id(), "rpg/hashed-ngram-v1");
+    }
 }
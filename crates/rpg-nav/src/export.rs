@@ -50,6 +50,10 @@ pub fn export_dot(graph: &RPGraph) -> String {
             rpg_core::graph::EntityKind::Service => "ellipse",
             rpg_core::graph::EntityKind::Middleware => "trapezium",
             rpg_core::graph::EntityKind::Test => "diamond",
+            rpg_core::graph::EntityKind::Field
+            | rpg_core::graph::EntityKind::Constant
+            | rpg_core::graph::EntityKind::EnumVariant
+            | rpg_core::graph::EntityKind::Global => "note",
         };
         let color = if entity.semantic_features.is_empty() {
             "#ffffff"
@@ -78,6 +82,7 @@ pub fn export_dot(graph: &RPGraph) -> String {
             EdgeKind::WritesState => "bold",
             EdgeKind::Dispatches => "solid",
             EdgeKind::DataFlow => "dashed",
+            EdgeKind::Tests => "dashed",
             EdgeKind::Contains => "dotted",
         };
         let label = match edge.kind {
@@ -90,6 +95,7 @@ pub fn export_dot(graph: &RPGraph) -> String {
             EdgeKind::WritesState => "writes_state",
             EdgeKind::Dispatches => "dispatches",
             EdgeKind::DataFlow => "data_flow",
+            EdgeKind::Tests => "tests",
             EdgeKind::Contains => "contains",
         };
         writeln!(
@@ -172,7 +178,7 @@ pub fn export_mermaid(graph: &RPGraph) -> String {
             | EdgeKind::Composes
             | EdgeKind::Renders
             | EdgeKind::Dispatches => "-->",
-            EdgeKind::Imports | EdgeKind::DataFlow => "-.->",
+            EdgeKind::Imports | EdgeKind::DataFlow | EdgeKind::Tests => "-.->",
             EdgeKind::Inherits | EdgeKind::WritesState => "==>",
             EdgeKind::ReadsState => "-.->",
         };
@@ -186,6 +192,7 @@ pub fn export_mermaid(graph: &RPGraph) -> String {
             EdgeKind::WritesState => "writes_state",
             EdgeKind::Dispatches => "dispatches",
             EdgeKind::DataFlow => "data_flow",
+            EdgeKind::Tests => "tests",
             EdgeKind::Contains => "contains",
         };
         writeln!(out, "  {} {}|{}| {}", src, arrow, label, tgt).unwrap();
@@ -2,7 +2,6 @@
 
 use anyhow::Result;
 use rpg_core::graph::{Entity, HierarchyNode, RPGraph};
-use std::fs;
 
 /// Detailed entity information returned by FetchNode.
 #[derive(Debug, Clone)]
@@ -65,7 +64,7 @@ pub fn fetch(
 
 fn read_entity_source(project_root: &std::path::Path, entity: &Entity) -> Option<String> {
     let file_path = project_root.join(&entity.file);
-    let content = fs::read_to_string(&file_path).ok()?;
+    let content = rpg_core::notebook::read_source(&file_path)?;
 
     let lines: Vec<&str> = content.lines().collect();
     let start = entity.line_start.saturating_sub(1);
@@ -6,6 +6,9 @@
 //! - Instability index I = Ce / (Ca + Ce)
 //! - Degree centrality (normalized)
 //! - God Object heuristic (high degree + extreme instability)
+//!
+//! Function-like entities also report the cyclomatic/cognitive complexity and
+//! nesting depth computed from their AST at extraction time.
 
 use crate::duplication::{
     CloneGroup, DuplicationConfig, SemanticCloneGroup, SemanticDuplicationConfig,
@@ -44,6 +47,8 @@ pub enum HealthIssue {
     HighlyStable { instability: f64, in_degree: usize },
     /// Entity has high total degree (hub).
     HubEntity { total_degree: usize },
+    /// Function body exceeds the cyclomatic or cognitive complexity threshold.
+    HighComplexity { cyclomatic: usize, cognitive: usize },
 }
 
 /// Health metrics for a single entity.
@@ -63,6 +68,15 @@ pub struct EntityHealth {
     pub instability: f64,
     /// Degree centrality: total_degree / (n - 1), where n = total entities.
     pub centrality: f64,
+    /// McCabe cyclomatic complexity (function-like entities with AST signals only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cyclomatic_complexity: Option<usize>,
+    /// Nesting-weighted cognitive complexity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cognitive_complexity: Option<usize>,
+    /// Deepest nesting of control-flow structures.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_nesting_depth: Option<usize>,
     /// Detected health issues for this entity.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub issues: Vec<HealthIssue>,
@@ -82,6 +96,9 @@ pub struct HealthSummary {
     pub highly_unstable_count: usize,
     pub highly_stable_count: usize,
     pub hub_count: usize,
+    /// Average cyclomatic complexity over entities with AST signals.
+    pub avg_cyclomatic_complexity: f64,
+    pub high_complexity_count: usize,
 }
 
 /// Complete health analysis report.
@@ -97,6 +114,9 @@ pub struct HealthReport {
     pub top_unstable: Vec<EntityHealth>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub top_god_objects: Vec<EntityHealth>,
+    /// Most complex entities by cognitive complexity, among those flagged.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub top_complex: Vec<EntityHealth>,
 }
 
 /// Configuration for health analysis.
@@ -110,6 +130,10 @@ pub struct HealthConfig {
     pub god_object_degree_threshold: usize,
     /// Instability extreme threshold for god object (must be > this or < 1-this).
     pub god_object_instability_threshold: f64,
+    /// Cyclomatic complexity above which a function is flagged.
+    pub cyclomatic_threshold: usize,
+    /// Cognitive complexity above which a function is flagged.
+    pub cognitive_threshold: usize,
     /// Maximum entities to include in top lists.
     pub top_n: usize,
     /// Include token-based duplication detection (reads source files from disk, slower).
@@ -129,6 +153,8 @@ impl Default for HealthConfig {
             hub_threshold: 8,
             god_object_degree_threshold: 10,
             god_object_instability_threshold: 0.7,
+            cyclomatic_threshold: 10,
+            cognitive_threshold: 15,
             top_n: 10,
             include_duplication: false,
             duplication_config: DuplicationConfig::default(),
@@ -144,11 +170,11 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
     let n = total_entities;
     let normalizer = if n > 1 { (n - 1) as f64 } else { 1.0 };
 
-    // Count dependency edges (exclude Contains)
+    // Count dependency edges (exclude Contains and the derived Tests links)
     let total_dependency_edges = graph
         .edges
         .iter()
-        .filter(|e| e.kind != EdgeKind::Contains)
+        .filter(|e| DEPENDENCY_EDGE_KINDS.contains(&e.kind))
         .count();
 
     // Compute in-degree and out-degree for each entity
@@ -169,6 +195,7 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
     let mut highly_unstable_count = 0usize;
     let mut highly_stable_count = 0usize;
     let mut hub_count = 0usize;
+    let mut high_complexity_count = 0usize;
 
     for (id, entity) in &graph.entities {
         // Skip Module entities (file-level) for analysis
@@ -230,6 +257,18 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
             hub_count += 1;
         }
 
+        // Complexity (function-like entities extracted with a grammar)
+        if let Some(signals) = entity.signals
+            && (signals.cyclomatic_complexity > config.cyclomatic_threshold
+                || signals.cognitive_complexity > config.cognitive_threshold)
+        {
+            issues.push(HealthIssue::HighComplexity {
+                cyclomatic: signals.cyclomatic_complexity,
+                cognitive: signals.cognitive_complexity,
+            });
+            high_complexity_count += 1;
+        }
+
         entities.push(EntityHealth {
             entity_id: id.clone(),
             name: entity.name.clone(),
@@ -239,6 +278,9 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
             out_degree,
             instability: clean_float(instability),
             centrality: clean_float(centrality),
+            cyclomatic_complexity: entity.signals.map(|s| s.cyclomatic_complexity),
+            cognitive_complexity: entity.signals.map(|s| s.cognitive_complexity),
+            max_nesting_depth: entity.signals.map(|s| s.max_nesting_depth),
             issues,
         });
     }
@@ -270,6 +312,15 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
     } else {
         0.0
     };
+    let cyclomatic: Vec<usize> = entities
+        .iter()
+        .filter_map(|e| e.cyclomatic_complexity)
+        .collect();
+    let avg_cyclomatic_complexity = if cyclomatic.is_empty() {
+        0.0
+    } else {
+        cyclomatic.iter().sum::<usize>() as f64 / cyclomatic.len() as f64
+    };
 
     // Sort by instability for top unstable
     let mut sorted_by_instability = entities.clone();
@@ -313,6 +364,24 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
         .take(config.top_n)
         .collect();
 
+    // Most complex functions first
+    let mut top_complex: Vec<EntityHealth> = entities
+        .iter()
+        .filter(|e| {
+            e.issues
+                .iter()
+                .any(|i| matches!(i, HealthIssue::HighComplexity { .. }))
+        })
+        .cloned()
+        .collect();
+    top_complex.sort_by(|a, b| {
+        b.cognitive_complexity
+            .cmp(&a.cognitive_complexity)
+            .then_with(|| b.cyclomatic_complexity.cmp(&a.cyclomatic_complexity))
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
+    });
+    top_complex.truncate(config.top_n);
+
     let summary = HealthSummary {
         total_entities,
         analyzed_entities: analyzed,
@@ -325,6 +394,8 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
         highly_unstable_count,
         highly_stable_count,
         hub_count,
+        avg_cyclomatic_complexity: clean_float(avg_cyclomatic_complexity),
+        high_complexity_count,
     };
 
     // Sort entities by entity_id for deterministic output
@@ -337,6 +408,7 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
         semantic_duplicates: None,
         top_unstable,
         top_god_objects,
+        top_complex,
     }
 }
 
@@ -378,7 +450,7 @@ fn clean_float(v: f64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps};
+    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityMetadata, StructuralSignals};
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str, kind: EntityKind) -> Entity {
@@ -395,6 +467,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -422,16 +496,19 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "a".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -487,6 +564,7 @@ mod tests {
                 source: "god".to_string(),
                 target: dep_id,
                 kind: EdgeKind::Invokes,
+                confidence: None,
             });
         }
         for i in 0..4 {
@@ -499,6 +577,7 @@ mod tests {
                 source: caller_id,
                 target: "god".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             });
         }
 
@@ -524,6 +603,48 @@ mod tests {
         assert_eq!(god.in_degree + god.out_degree, 12);
     }
 
+    #[test]
+    fn test_high_complexity_detection() {
+        let mut graph = make_test_graph();
+        let signals = |cyclomatic, cognitive| StructuralSignals {
+            cyclomatic_complexity: cyclomatic,
+            cognitive_complexity: cognitive,
+            max_nesting_depth: 2,
+            ..StructuralSignals::default()
+        };
+        graph.entities.get_mut("a").unwrap().signals = Some(signals(12, 4));
+        graph.entities.get_mut("b").unwrap().signals = Some(signals(3, 20));
+        graph.entities.get_mut("c").unwrap().signals = Some(signals(3, 1));
+
+        let report = compute_health(&graph, &HealthConfig::default());
+        assert_eq!(report.summary.high_complexity_count, 2);
+        assert!((report.summary.avg_cyclomatic_complexity - 6.0).abs() < 0.001);
+        // Ordered by cognitive complexity
+        let top: Vec<&str> = report
+            .top_complex
+            .iter()
+            .map(|e| e.entity_id.as_str())
+            .collect();
+        assert_eq!(top, ["b", "a"]);
+        let c = report.entities.iter().find(|e| e.entity_id == "c").unwrap();
+        assert_eq!(c.cyclomatic_complexity, Some(3));
+        assert!(
+            !c.issues
+                .iter()
+                .any(|i| matches!(i, HealthIssue::HighComplexity { .. }))
+        );
+
+        // Entities without AST signals report no complexity
+        let report = compute_health(&make_test_graph(), &HealthConfig::default());
+        assert_eq!(report.summary.high_complexity_count, 0);
+        assert!(
+            report
+                .entities
+                .iter()
+                .all(|e| e.cyclomatic_complexity.is_none())
+        );
+    }
+
     #[test]
     fn test_centrality_normalization() {
         let graph = make_test_graph();
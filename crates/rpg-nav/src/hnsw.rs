@@ -0,0 +1,640 @@
+//! Hierarchical Navigable Small World graph for approximate nearest-neighbour search.
+//!
+//! Vectors live in numbered slots compared by cosine distance. Each slot is
+//! linked to its closest neighbours on a random number of layers; a query
+//! descends greedily from the sparse top layer and then runs a best-first
+//! search on layer 0 (Malkov & Yashunin, 2018).
+//!
+//! Removal repairs the graph locally: the removed slot's neighbours are
+//! relinked among themselves, and the slot is reused by the next insert.
+//! Links from other slots to a removed one may remain; searches skip them.
+
+use anyhow::{Result, ensure};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+use std::io::Write;
+
+const MAGIC: u32 = 0x5250_4748; // "RPGH"
+const FORMAT_VERSION: u32 = 1;
+
+/// Links each neighbour of a removed slot gains towards its closest co-neighbours.
+const REPAIR_LINKS: usize = 3;
+
+/// Build and search parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HnswParams {
+    /// Links per slot on upper layers (layer 0 keeps twice as many).
+    pub m: usize,
+    /// Candidate list size while inserting.
+    pub ef_construction: usize,
+    /// Minimum candidate list size while searching.
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 100,
+            ef_search: 64,
+        }
+    }
+}
+
+/// A candidate slot and its distance to the query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    slot: u32,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.slot.cmp(&other.slot))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// HNSW index over fixed-dimension vectors.
+#[derive(Debug, Clone)]
+pub struct Hnsw {
+    params: HnswParams,
+    dimension: usize,
+    /// Flat vector storage, `dimension` floats per slot.
+    vectors: Vec<f32>,
+    norms: Vec<f32>,
+    /// Per slot, per layer: neighbour slots. Empty for a free slot.
+    links: Vec<Vec<Vec<u32>>>,
+    free: Vec<u32>,
+    entry: Option<u32>,
+    /// Layer-assignment RNG state (splitmix64), so builds are reproducible.
+    rng: u64,
+}
+
+impl Hnsw {
+    /// Create an empty index for `dimension`-length vectors.
+    pub fn new(dimension: usize, params: HnswParams) -> Self {
+        Self {
+            params: HnswParams {
+                m: params.m.max(2),
+                ..params
+            },
+            dimension,
+            vectors: Vec::new(),
+            norms: Vec::new(),
+            links: Vec::new(),
+            free: Vec::new(),
+            entry: None,
+            rng: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    /// Vector length.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Number of live vectors.
+    pub fn len(&self) -> usize {
+        self.links.len() - self.free.len()
+    }
+
+    /// Whether the index holds no vectors.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of slots, live or free. Slot numbers are below this.
+    pub fn slot_count(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Whether `slot` currently holds a vector.
+    pub fn contains(&self, slot: u32) -> bool {
+        self.links.get(slot as usize).is_some_and(|l| !l.is_empty())
+    }
+
+    /// The vector stored in a live `slot`.
+    pub fn vector(&self, slot: u32) -> &[f32] {
+        let start = slot as usize * self.dimension;
+        &self.vectors[start..start + self.dimension]
+    }
+
+    /// Cosine similarity between `query` and a live `slot`.
+    pub fn similarity(&self, query: &[f32], slot: u32) -> f32 {
+        1.0 - self.distance(query, norm(query), slot)
+    }
+
+    /// Insert a vector and return its slot.
+    pub fn insert(&mut self, vector: &[f32]) -> u32 {
+        debug_assert_eq!(vector.len(), self.dimension);
+        let level = self.random_level();
+        let slot = self.allocate(vector, level);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(slot);
+            return slot;
+        };
+        let query_norm = self.norms[slot as usize];
+        let top = self.top_level();
+
+        let mut nearest = entry;
+        for layer in (level + 1..=top).rev() {
+            nearest = self.search_layer(vector, query_norm, &[nearest], 1, layer)[0].slot;
+        }
+        let mut entry_points = vec![nearest];
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(
+                vector,
+                query_norm,
+                &entry_points,
+                self.params.ef_construction,
+                layer,
+            );
+            let neighbours: Vec<u32> = found
+                .iter()
+                .filter(|c| c.slot != slot)
+                .take(self.max_links(layer))
+                .map(|c| c.slot)
+                .collect();
+            for &neighbour in &neighbours {
+                self.link(neighbour, slot, layer);
+            }
+            self.links[slot as usize][layer] = neighbours;
+            entry_points = found.iter().map(|c| c.slot).collect();
+        }
+        if level > top {
+            self.entry = Some(slot);
+        }
+        slot
+    }
+
+    /// Remove the vector in `slot`, relinking its neighbours among themselves.
+    pub fn remove(&mut self, slot: u32) {
+        if !self.contains(slot) {
+            return;
+        }
+        let layers = std::mem::take(&mut self.links[slot as usize]);
+        self.free.push(slot);
+        for (layer, neighbours) in layers.iter().enumerate() {
+            for &neighbour in neighbours {
+                let Some(list) = self.layer_links_mut(neighbour, layer) else {
+                    continue;
+                };
+                list.retain(|&s| s != slot);
+                let base = self.vector(neighbour).to_vec();
+                let base_norm = self.norms[neighbour as usize];
+                let mut others: Vec<Candidate> = neighbours
+                    .iter()
+                    .filter(|&&other| other != neighbour && self.contains(other))
+                    .map(|&other| Candidate {
+                        distance: self.distance(&base, base_norm, other),
+                        slot: other,
+                    })
+                    .collect();
+                others.sort();
+                for other in others.into_iter().take(REPAIR_LINKS) {
+                    self.link(neighbour, other.slot, layer);
+                }
+            }
+        }
+        if self.entry == Some(slot) {
+            self.entry = (0..self.links.len() as u32)
+                .filter(|&s| self.contains(s))
+                .max_by_key(|&s| (self.links[s as usize].len(), Reverse(s)));
+        }
+    }
+
+    /// The `k` live slots closest to `query`, most similar first, as
+    /// `(slot, cosine similarity)` pairs.
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if k == 0 || query.len() != self.dimension {
+            return Vec::new();
+        }
+        let query_norm = norm(query);
+        let mut nearest = entry;
+        for layer in (1..=self.top_level()).rev() {
+            nearest = self.search_layer(query, query_norm, &[nearest], 1, layer)[0].slot;
+        }
+        self.search_layer(
+            query,
+            query_norm,
+            &[nearest],
+            self.params.ef_search.max(k),
+            0,
+        )
+        .into_iter()
+        .take(k)
+        .map(|c| (c.slot, 1.0 - c.distance))
+        .collect()
+    }
+
+    /// Best-first search of one layer. Returns up to `ef` candidates, closest first.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        query_norm: f32,
+        entry_points: &[u32],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<u32> = HashSet::new();
+        let mut frontier: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+        let mut found: BinaryHeap<Candidate> = BinaryHeap::new();
+        for &slot in entry_points {
+            if self.contains(slot) && visited.insert(slot) {
+                let candidate = Candidate {
+                    distance: self.distance(query, query_norm, slot),
+                    slot,
+                };
+                frontier.push(Reverse(candidate));
+                found.push(candidate);
+            }
+        }
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(Reverse(current)) = frontier.pop() {
+            if found
+                .peek()
+                .is_some_and(|worst| found.len() >= ef && current.distance > worst.distance)
+            {
+                break;
+            }
+            let Some(neighbours) = self.links[current.slot as usize].get(layer) else {
+                continue;
+            };
+            for &neighbour in neighbours {
+                if !self.contains(neighbour) || !visited.insert(neighbour) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: self.distance(query, query_norm, neighbour),
+                    slot: neighbour,
+                };
+                if found.len() < ef || found.peek().is_some_and(|w| candidate < *w) {
+                    frontier.push(Reverse(candidate));
+                    found.push(candidate);
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+        found.into_sorted_vec()
+    }
+
+    /// Add a `from → to` link on `layer`, keeping only the closest links when
+    /// the list overflows.
+    fn link(&mut self, from: u32, to: u32, layer: usize) {
+        let max = self.max_links(layer);
+        let Some(list) = self.layer_links_mut(from, layer) else {
+            return;
+        };
+        if list.contains(&to) {
+            return;
+        }
+        list.push(to);
+        if list.len() <= max {
+            return;
+        }
+        let base = self.vector(from).to_vec();
+        let base_norm = self.norms[from as usize];
+        let mut ranked: Vec<Candidate> = self.links[from as usize][layer]
+            .iter()
+            .map(|&slot| Candidate {
+                distance: self.distance(&base, base_norm, slot),
+                slot,
+            })
+            .collect();
+        ranked.sort();
+        ranked.truncate(max);
+        self.links[from as usize][layer] = ranked.into_iter().map(|c| c.slot).collect();
+    }
+
+    fn layer_links_mut(&mut self, slot: u32, layer: usize) -> Option<&mut Vec<u32>> {
+        self.links.get_mut(slot as usize)?.get_mut(layer)
+    }
+
+    fn allocate(&mut self, vector: &[f32], level: usize) -> u32 {
+        let layers = vec![Vec::new(); level + 1];
+        if let Some(slot) = self.free.pop() {
+            let start = slot as usize * self.dimension;
+            self.vectors[start..start + self.dimension].copy_from_slice(vector);
+            self.norms[slot as usize] = norm(vector);
+            self.links[slot as usize] = layers;
+            slot
+        } else {
+            self.vectors.extend_from_slice(vector);
+            self.norms.push(norm(vector));
+            self.links.push(layers);
+            (self.links.len() - 1) as u32
+        }
+    }
+
+    fn distance(&self, query: &[f32], query_norm: f32, slot: u32) -> f32 {
+        let denom = query_norm * self.norms[slot as usize];
+        if denom == 0.0 {
+            return 1.0;
+        }
+        let dot: f32 = query
+            .iter()
+            .zip(self.vector(slot))
+            .map(|(a, b)| a * b)
+            .sum();
+        1.0 - dot / denom
+    }
+
+    fn top_level(&self) -> usize {
+        self.entry
+            .map_or(0, |e| self.links[e as usize].len().saturating_sub(1))
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.params.m * 2
+        } else {
+            self.params.m
+        }
+    }
+
+    /// Draw a layer with P(level ≥ l) = m^-l.
+    fn random_level(&mut self) -> usize {
+        self.rng = self.rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        let uniform = ((z >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        let scale = 1.0 / (self.params.m as f64).ln();
+        #[allow(clippy::cast_sign_loss)] // uniform is in (0, 1], so -ln(uniform) >= 0
+        let level = (-uniform.ln() * scale).floor().min(16.0) as usize;
+        level
+    }
+
+    /// Serialize the link structure (not the vectors, which the caller
+    /// persists and passes back to [`Hnsw::read`]).
+    pub fn write(&self) -> Result<Vec<u8>> {
+        let mut buf: Vec<u8> = Vec::new();
+        for value in [
+            MAGIC,
+            FORMAT_VERSION,
+            self.dimension as u32,
+            self.params.m as u32,
+            self.params.ef_construction as u32,
+            self.params.ef_search as u32,
+            self.entry.unwrap_or(u32::MAX),
+            self.links.len() as u32,
+        ] {
+            buf.write_all(&value.to_le_bytes())?;
+        }
+        buf.write_all(&self.rng.to_le_bytes())?;
+        for layers in &self.links {
+            buf.write_all(&[layers.len() as u8])?;
+            for neighbours in layers {
+                buf.write_all(&(neighbours.len() as u32).to_le_bytes())?;
+                for slot in neighbours {
+                    buf.write_all(&slot.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(buf)
+    }
+
+    /// Restore an index of `dimension`-length vectors written by [`Hnsw::write`].
+    /// `vector(slot)` supplies the vector of every slot that was live when it
+    /// was written.
+    pub fn read<'a>(
+        bytes: &[u8],
+        dimension: usize,
+        vector: impl Fn(u32) -> Option<&'a [f32]>,
+    ) -> Result<Self> {
+        let mut cursor = bytes;
+        ensure!(read_u32(&mut cursor)? == MAGIC, "invalid hnsw magic bytes");
+        ensure!(
+            read_u32(&mut cursor)? == FORMAT_VERSION,
+            "unsupported hnsw version"
+        );
+        ensure!(
+            read_u32(&mut cursor)? as usize == dimension,
+            "hnsw dimension mismatch"
+        );
+        let params = HnswParams {
+            m: read_u32(&mut cursor)? as usize,
+            ef_construction: read_u32(&mut cursor)? as usize,
+            ef_search: read_u32(&mut cursor)? as usize,
+        };
+        let entry = read_u32(&mut cursor)?;
+        let slots = read_u32(&mut cursor)? as usize;
+        ensure!(cursor.len() >= 8, "unexpected end of hnsw file");
+        let rng = u64::from_le_bytes(cursor[..8].try_into()?);
+        cursor = &cursor[8..];
+
+        let mut index = Self::new(dimension, params);
+        index.rng = rng;
+        for slot in 0..slots as u32 {
+            ensure!(!cursor.is_empty(), "unexpected end of hnsw file");
+            let layer_count = cursor[0] as usize;
+            cursor = &cursor[1..];
+            let mut layers = Vec::new();
+            for _ in 0..layer_count {
+                let count = read_u32(&mut cursor)? as usize;
+                let mut neighbours = Vec::with_capacity(count.min(cursor.len() / 4));
+                for _ in 0..count {
+                    let neighbour = read_u32(&mut cursor)?;
+                    ensure!((neighbour as usize) < slots, "hnsw link out of range");
+                    neighbours.push(neighbour);
+                }
+                layers.push(neighbours);
+            }
+            if layers.is_empty() {
+                index.vectors.extend(std::iter::repeat_n(0.0, dimension));
+                index.norms.push(0.0);
+                index.free.push(slot);
+            } else {
+                let Some(values) = vector(slot) else {
+                    anyhow::bail!("no vector for hnsw slot {slot}");
+                };
+                ensure!(values.len() == dimension, "hnsw vector dimension mismatch");
+                index.vectors.extend_from_slice(values);
+                index.norms.push(norm(values));
+            }
+            index.links.push(layers);
+        }
+        ensure!(cursor.is_empty(), "trailing bytes in hnsw file");
+        index.entry = (entry != u32::MAX).then_some(entry);
+        ensure!(
+            index.entry.is_none_or(|e| index.contains(e)),
+            "hnsw entry point is not a live slot"
+        );
+        Ok(index)
+    }
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    vector.iter().map(|v| v * v).sum::<f32>().sqrt()
+}
+
+fn read_u32(cursor: &mut &[u8]) -> Result<u32> {
+    ensure!(cursor.len() >= 4, "unexpected end of hnsw file");
+    let bytes: [u8; 4] = cursor[..4].try_into()?;
+    *cursor = &cursor[4..];
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random unit vectors.
+    fn vectors(count: usize, dimension: usize) -> Vec<Vec<f32>> {
+        let mut state: u32 = 7;
+        (0..count)
+            .map(|_| {
+                let v: Vec<f32> = (0..dimension)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 17;
+                        state ^= state << 5;
+                        (state % 2000) as f32 / 1000.0 - 1.0
+                    })
+                    .collect();
+                let n = norm(&v);
+                v.into_iter().map(|x| x / n).collect()
+            })
+            .collect()
+    }
+
+    fn exact(data: &[Vec<f32>], live: &[u32], query: &[f32], k: usize) -> Vec<u32> {
+        let mut scored: Vec<(u32, f32)> = live
+            .iter()
+            .map(|&s| {
+                let dot: f32 = data[s as usize].iter().zip(query).map(|(a, b)| a * b).sum();
+                (s, dot)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(s, _)| s).collect()
+    }
+
+    fn recall(index: &Hnsw, data: &[Vec<f32>], live: &[u32], queries: &[Vec<f32>]) -> f64 {
+        let mut hits = 0;
+        for query in queries {
+            let truth: HashSet<u32> = exact(data, live, query, 10).into_iter().collect();
+            hits += index
+                .search(query, 10)
+                .iter()
+                .filter(|(s, _)| truth.contains(s))
+                .count();
+        }
+        hits as f64 / (queries.len() * 10) as f64
+    }
+
+    #[test]
+    fn test_search_finds_exact_match() {
+        let data = vectors(200, 16);
+        let mut index = Hnsw::new(16, HnswParams::default());
+        for v in &data {
+            index.insert(v);
+        }
+        assert_eq!(index.len(), 200);
+        let results = index.search(&data[42], 3);
+        assert_eq!(results[0].0, 42);
+        assert!((results[0].1 - 1.0).abs() < 1e-5);
+        assert!(results.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn test_recall_against_brute_force() {
+        let data = vectors(1000, 16);
+        let mut index = Hnsw::new(16, HnswParams::default());
+        for v in &data {
+            index.insert(v);
+        }
+        let live: Vec<u32> = (0..1000).collect();
+        let queries = vectors(1040, 16).split_off(1000);
+        let r = recall(&index, &data, &live, &queries);
+        assert!(r >= 0.9, "recall {r}");
+    }
+
+    #[test]
+    fn test_remove_and_reuse_slots() {
+        let data = vectors(600, 16);
+        let mut index = Hnsw::new(16, HnswParams::default());
+        for v in &data {
+            index.insert(v);
+        }
+        for slot in (0..600).step_by(3) {
+            index.remove(slot);
+        }
+        index.remove(0); // already removed: no-op
+        let live: Vec<u32> = (0..600).filter(|s| s % 3 != 0).collect();
+        assert_eq!(index.len(), live.len());
+        assert!(!index.contains(3));
+
+        let queries = vectors(630, 16).split_off(600);
+        for query in &queries {
+            assert!(index.search(query, 10).iter().all(|(s, _)| s % 3 != 0));
+        }
+        assert!(recall(&index, &data, &live, &queries) >= 0.85);
+
+        let slot = index.insert(&data[3]);
+        assert_eq!(slot % 3, 0, "freed slots are reused");
+        assert_eq!(index.search(&data[3], 1)[0].0, slot);
+    }
+
+    #[test]
+    fn test_remove_everything() {
+        let data = vectors(20, 8);
+        let mut index = Hnsw::new(8, HnswParams::default());
+        for v in &data {
+            index.insert(v);
+        }
+        for slot in 0..20 {
+            index.remove(slot);
+        }
+        assert!(index.is_empty());
+        assert!(index.search(&data[0], 5).is_empty());
+        index.insert(&data[0]);
+        assert_eq!(index.search(&data[0], 5).len(), 1);
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let data = vectors(300, 12);
+        let mut index = Hnsw::new(12, HnswParams::default());
+        for v in &data {
+            index.insert(v);
+        }
+        index.remove(7);
+        let bytes = index.write().unwrap();
+        let restored = Hnsw::read(&bytes, 12, |slot| Some(data[slot as usize].as_slice())).unwrap();
+        assert_eq!(restored.len(), 299);
+        assert!(!restored.contains(7));
+        for query in data.iter().take(20) {
+            assert_eq!(restored.search(query, 5), index.search(query, 5));
+        }
+
+        assert!(Hnsw::read(&bytes, 12, |_| None).is_err(), "missing vectors");
+        assert!(Hnsw::read(&bytes, 8, |s| Some(data[s as usize].as_slice())).is_err());
+        assert!(
+            Hnsw::read(&bytes[..bytes.len() - 1], 12, |s| Some(
+                data[s as usize].as_slice()
+            ))
+            .is_err()
+        );
+    }
+}
@@ -141,7 +141,10 @@ pub fn compute_impact_radius(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use rpg_core::graph::{
+        DependencyEdge, Entity, EntityDeps, EntityKind, EntityMetadata, ExternalNode,
+        PackageEcosystem,
+    };
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str) -> Entity {
@@ -158,6 +161,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -178,17 +183,51 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "c".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
         graph
     }
 
+    #[test]
+    fn test_impact_radius_from_external_package() {
+        let mut graph = make_test_graph();
+        graph.externals.insert(
+            "ext:lodash".to_string(),
+            ExternalNode {
+                id: "ext:lodash".to_string(),
+                name: "lodash".to_string(),
+                ecosystem: PackageEcosystem::Npm,
+                version: None,
+                manifests: vec![PathBuf::from("package.json")],
+            },
+        );
+        graph.edges.push(DependencyEdge {
+            source: "c".to_string(),
+            target: "ext:lodash".to_string(),
+            kind: EdgeKind::Imports,
+            confidence: None,
+        });
+        graph.refresh_metadata();
+
+        let result =
+            compute_impact_radius(&graph, "ext:lodash", Direction::Upstream, 5, None, None)
+                .unwrap();
+        let ids: Vec<&str> = result
+            .reachable
+            .iter()
+            .map(|e| e.entity_id.as_str())
+            .collect();
+        assert_eq!(ids, ["c", "b", "a"], "everything depending on the package");
+    }
+
     #[test]
     fn test_impact_radius_downstream_chain() {
         let graph = make_test_graph();
@@ -264,11 +303,13 @@ mod tests {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
             DependencyEdge {
                 source: "b".to_string(),
                 target: "a".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             },
         ];
         graph.refresh_metadata();
@@ -294,6 +335,7 @@ mod tests {
             source: "mod".to_string(),
             target: "fn1".to_string(),
             kind: EdgeKind::Contains,
+            confidence: None,
         }];
         graph.refresh_metadata();
 
@@ -318,6 +360,7 @@ mod tests {
             source: "mod".to_string(),
             target: "fn1".to_string(),
             kind: EdgeKind::Contains,
+            confidence: None,
         }];
         graph.refresh_metadata();
 
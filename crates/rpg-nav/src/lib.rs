@@ -1,6 +1,7 @@
 //! Navigation tools for querying the Repository Planning Graph.
 //!
-//! Provides SearchNode (intent-based discovery), FetchNode (entity details),
+//! Provides SearchNode (intent-based discovery, backed by a persistent inverted
+//! index), FetchNode (entity details),
 //! ExploreRPG (dependency traversal), Health analysis, Duplication detection,
 //! and TOON serialization for LLM-optimized output.
 
@@ -9,15 +10,18 @@ pub mod cycles;
 pub mod dataflow;
 pub mod diff;
 pub mod duplication;
-#[cfg(feature = "embeddings")]
 pub mod embeddings;
 pub mod explore;
 pub mod export;
 pub mod fetch;
 pub mod health;
+pub mod hnsw;
 pub mod impact;
 pub mod paths;
 pub mod planner;
+pub mod query;
 pub mod search;
+pub mod search_index;
 pub mod slice;
+pub mod test_selection;
 pub mod toon;
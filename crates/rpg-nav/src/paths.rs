@@ -308,7 +308,7 @@ fn find_shortest_path_excluding(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind, EntityMetadata};
     use std::path::PathBuf;
 
     fn make_test_entity(id: &str) -> Entity {
@@ -325,6 +325,8 @@ mod tests {
             hierarchy_path: "Test".to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -339,11 +341,13 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let paths = find_paths(&graph, "A", "C", None, 3, None);
@@ -366,21 +370,25 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "A".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "D".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "C".to_string(),
             target: "D".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let paths = find_paths(&graph, "A", "D", None, 3, None);
@@ -400,6 +408,7 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let paths = find_paths(&graph, "A", "C", None, 3, None);
@@ -419,16 +428,19 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "C".to_string(),
             target: "D".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         // Can't reach D with max_hops=2
@@ -457,11 +469,13 @@ mod tests {
                 source: "A".to_string(),
                 target: b.clone(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             });
             graph.edges.push(DependencyEdge {
                 source: b,
                 target: "C".to_string(),
                 kind: EdgeKind::Invokes,
+                confidence: None,
             });
         }
 
@@ -482,11 +496,13 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Imports,
+            confidence: None,
         });
 
         // Filter by Invokes only - can't reach C
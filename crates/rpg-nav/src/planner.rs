@@ -6,6 +6,7 @@
 use crate::explore::{Direction, get_neighbors};
 use crate::impact::compute_impact_radius;
 use crate::search::{SearchMode, SearchParams, search_with_params};
+use crate::test_selection::tests_covering;
 use rpg_core::graph::RPGraph;
 use std::collections::{HashMap, HashSet};
 
@@ -42,7 +43,7 @@ pub struct ChangePlan {
     /// Entity IDs in dependency-safe modification order (leaf dependencies first).
     pub modification_order: Vec<String>,
     pub impact_summary: Vec<ImpactSummary>,
-    /// Entity IDs of test entities that reference target entities.
+    /// Entity IDs of tests that exercise target entities (via `Tests` edges).
     pub test_coverage: Vec<String>,
     /// Lifting coverage as a percentage (for partial-lift warning).
     pub coverage_pct: f64,
@@ -72,8 +73,10 @@ pub fn plan_change(
             line_nums: None,
             file_pattern: None,
             entity_type_filter: None,
+            metadata_filter: None,
             embedding_scores,
             diff_context: None,
+            index: None,
         },
     );
 
@@ -126,8 +129,8 @@ pub fn plan_change(
     // Step 3: Topological sort of target entities for modification order
     let modification_order = topological_sort(graph, &target_ids);
 
-    // Step 4: Find test entities that reference targets
-    let test_coverage = find_test_coverage(graph, &target_ids);
+    // Step 4: Find tests that exercise targets
+    let test_coverage = tests_covering(graph, &target_ids);
 
     ChangePlan {
         goal: request.goal.to_string(),
@@ -210,36 +213,6 @@ fn topological_sort(graph: &RPGraph, target_ids: &HashSet<String>) -> Vec<String
     order
 }
 
-/// Find test entities that have edges to any target entity.
-fn find_test_coverage(graph: &RPGraph, target_ids: &HashSet<String>) -> Vec<String> {
-    let mut test_ids = Vec::new();
-
-    // Look for entities whose name or file path suggests they're tests
-    for (id, entity) in &graph.entities {
-        if target_ids.contains(id) {
-            continue;
-        }
-        let is_test = entity.kind == rpg_core::graph::EntityKind::Test
-            || entity.name.starts_with("test_")
-            || entity.name.starts_with("Test")
-            || entity.file.to_string_lossy().contains("test");
-
-        if !is_test {
-            continue;
-        }
-
-        // Check if this test entity has edges to any target
-        let neighbors = get_neighbors(graph, id, Direction::Downstream, None);
-        let touches_target = neighbors.iter().any(|(nid, _, _)| target_ids.contains(nid));
-        if touches_target {
-            test_ids.push(id.clone());
-        }
-    }
-
-    test_ids.sort();
-    test_ids
-}
-
 /// Format a change plan as a human-readable markdown string.
 pub fn format_change_plan(plan: &ChangePlan) -> String {
     let mut out = format!("## Change Plan for: \"{}\"\n\n", plan.goal);
@@ -311,7 +284,9 @@ pub fn format_change_plan(plan: &ChangePlan) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, RPGraph};
+    use rpg_core::graph::{
+        DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, EntityMetadata, RPGraph,
+    };
     use std::path::PathBuf;
 
     fn make_entity(
@@ -334,6 +309,8 @@ mod tests {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -374,6 +351,7 @@ mod tests {
             source: "src/server.rs:Server::start".to_string(),
             target: "src/config.rs:Config".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         // Add a test entity
         graph.entities.insert(
@@ -390,6 +368,13 @@ mod tests {
             source: "tests/server_test.rs:test_start".to_string(),
             target: "src/server.rs:Server::start".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
+        });
+        graph.edges.push(DependencyEdge {
+            source: "tests/server_test.rs:test_start".to_string(),
+            target: "src/server.rs:Server::start".to_string(),
+            kind: EdgeKind::Tests,
+            confidence: None,
         });
         graph.refresh_metadata();
         graph
@@ -431,11 +416,18 @@ mod tests {
         let graph = build_test_graph();
         let mut targets = HashSet::new();
         targets.insert("src/server.rs:Server::start".to_string());
-        let tests = find_test_coverage(&graph, &targets);
+        let tests = tests_covering(&graph, &targets);
         assert!(
             tests.contains(&"tests/server_test.rs:test_start".to_string()),
             "should find test_start as related test"
         );
+
+        // Config is only reached through Server::start
+        let targets = HashSet::from(["src/config.rs:Config".to_string()]);
+        assert_eq!(
+            tests_covering(&graph, &targets),
+            ["tests/server_test.rs:test_start"]
+        );
     }
 
     #[test]
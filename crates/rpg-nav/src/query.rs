@@ -0,0 +1,1525 @@
+//! Declarative structured queries over the graph.
+//!
+//! A query selects nodes by kind, filters them with field predicates and edge
+//! patterns, and optionally aggregates the matches:
+//!
+//! ```text
+//! find controller where area = "API"
+//!     and -[invokes *1..3]-> (area = "Persistence")
+//!     and not <-[invokes]- (kind = test)
+//! ```
+//!
+//! Grammar (keywords are case-insensitive):
+//!
+//! ```text
+//! query     := "find" kinds ["where" expr] ["count" | "group by" field] ["limit" N]
+//! kinds     := "*" | kind ("," kind)*          entity kinds, plus "external"
+//! expr      := term ("or" term)*
+//! term      := factor ("and" factor)*
+//! factor    := "not" factor | "(" expr ")" | field op value
+//!            | pattern | "count" "(" pattern ")" op N
+//! pattern   := "-[" edges "]->" "(" [expr] ")"     outgoing
+//!            | "<-[" edges "]-" "(" [expr] ")"     incoming
+//!            | "-[" edges "]-" "(" [expr] ")"      either direction
+//! edges     := [edge ("|" edge)*] ["*" [N] [".." [N]]]
+//! op        := "=" | "!=" | "~" | "!~" | "<" | "<=" | ">" | ">="
+//! ```
+//!
+//! Text fields are `id`, `name`, `kind`, `file`, `area` (first hierarchy
+//! segment), `path` (hierarchy path, matching descendants too), `feature`,
+//! `decorator`, `visibility` and `parent`; `lifted` is boolean; `lines`,
+//! `fan_in` and `fan_out` are numeric. `=` compares case-insensitively and
+//! treats `*`/`?` as glob wildcards; `~` tests for a substring. A pattern holds
+//! when a path of the listed edge kinds (any direct dependency kind when none
+//! are listed) leads to a node satisfying the inner expression; `*m..n` bounds the
+//! path length, which defaults to exactly one edge. `count(pattern)` counts
+//! the distinct nodes such paths reach.
+
+use globset::{GlobBuilder, GlobMatcher};
+use rpg_core::graph::{EdgeKind, Entity, EntityKind, ExternalNode, RPGraph};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Rows returned when a query has no `limit` clause.
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// Most conditions, operators and groups a `where` clause may hold. Parsing
+/// and evaluation recurse through the expression, so this bounds their depth.
+pub const MAX_EXPR_NODES: usize = 256;
+
+/// A parsed query.
+#[derive(Debug, Clone)]
+pub struct Query {
+    /// Node kinds to select; `None` selects every entity.
+    kinds: Option<Vec<NodeKind>>,
+    filter: Option<Expr>,
+    aggregate: Aggregate,
+    limit: Option<usize>,
+    /// Edge patterns, referenced by index from [`Expr`] so their node sets can
+    /// be cached during evaluation.
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Entity(EntityKind),
+    External,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Predicate(Predicate),
+    Pattern(usize),
+    Count {
+        pattern: usize,
+        op: CmpOp,
+        value: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Id,
+    Name,
+    Kind,
+    File,
+    Area,
+    Path,
+    Feature,
+    Decorator,
+    Visibility,
+    Parent,
+    Lifted,
+    Lines,
+    FanIn,
+    FanOut,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "id" => Self::Id,
+            "name" => Self::Name,
+            "kind" | "type" => Self::Kind,
+            "file" => Self::File,
+            "area" => Self::Area,
+            "path" | "hierarchy" => Self::Path,
+            "feature" | "features" => Self::Feature,
+            "decorator" | "decorators" => Self::Decorator,
+            "visibility" => Self::Visibility,
+            "parent" | "parent_class" => Self::Parent,
+            "lifted" => Self::Lifted,
+            "lines" => Self::Lines,
+            "fan_in" => Self::FanIn,
+            "fan_out" => Self::FanOut,
+            _ => return None,
+        })
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Self::Lines | Self::FanIn | Self::FanOut)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    fn holds(self, left: f64, right: f64) -> bool {
+        match self {
+            Self::Eq => (left - right).abs() < f64::EPSILON,
+            Self::Ne => (left - right).abs() >= f64::EPSILON,
+            Self::Lt => left < right,
+            Self::Le => left <= right,
+            Self::Gt => left > right,
+            Self::Ge => left >= right,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Predicate {
+    field: Field,
+    test: Test,
+    negate: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Test {
+    Exact(String),
+    Glob(GlobMatcher),
+    Contains(String),
+    Kind(NodeKind),
+    Bool(bool),
+    Number(CmpOp, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Outgoing,
+    Incoming,
+    Either,
+}
+
+#[derive(Debug, Clone)]
+struct Pattern {
+    direction: Direction,
+    /// Edge kinds to follow; empty follows every [`is_default_edge`] kind.
+    edges: Vec<EdgeKind>,
+    min_hops: usize,
+    max_hops: Option<usize>,
+    target: Option<Expr>,
+}
+
+impl Pattern {
+    fn follows(&self, kind: EdgeKind) -> bool {
+        if self.edges.is_empty() {
+            is_default_edge(kind)
+        } else {
+            self.edges.contains(&kind)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    None,
+    Count,
+    GroupBy(Field),
+}
+
+/// One node selected by a query.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryMatch {
+    pub entity_id: String,
+    pub name: String,
+    pub kind: String,
+    pub file: String,
+    pub line: usize,
+    pub area: String,
+}
+
+/// Matches sharing one value of the `group by` field.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryGroup {
+    pub key: String,
+    pub count: usize,
+}
+
+/// The outcome of [`execute`].
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryResult {
+    /// Number of matching nodes, before `limit`.
+    pub total: usize,
+    /// Matching nodes, sorted by ID (empty for aggregate queries).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<QueryMatch>,
+    /// Per-value counts for `group by` queries, largest first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<QueryGroup>>,
+    /// Whether `limit` cut off rows or groups.
+    pub truncated: bool,
+}
+
+/// Parse and run a query against the graph.
+pub fn run(graph: &RPGraph, query: &str) -> Result<QueryResult, String> {
+    Ok(execute(graph, &parse(query)?))
+}
+
+// ---------------------------------------------------------------------------
+// Lexer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Number(f64),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+    Pipe,
+    Star,
+    DotDot,
+    /// `-[`
+    DashBracket,
+    /// `<-[`
+    ArrowBracket,
+    /// `]->`
+    BracketArrow,
+    /// `]-`
+    BracketDash,
+}
+
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        None => "end of query".to_string(),
+        Some(Token::Word(w)) => format!("'{w}'"),
+        Some(Token::Str(s)) => format!("\"{s}\""),
+        Some(Token::Number(n)) => format!("{n}"),
+        Some(Token::Op(op)) => format!("'{op}'"),
+        Some(Token::LParen) => "'('".to_string(),
+        Some(Token::RParen) => "')'".to_string(),
+        Some(Token::Comma) => "','".to_string(),
+        Some(Token::Pipe) => "'|'".to_string(),
+        Some(Token::Star) => "'*'".to_string(),
+        Some(Token::DotDot) => "'..'".to_string(),
+        Some(Token::DashBracket) => "'-['".to_string(),
+        Some(Token::ArrowBracket) => "'<-['".to_string(),
+        Some(Token::BracketArrow) => "']->'".to_string(),
+        Some(Token::BracketDash) => "']-'".to_string(),
+    }
+}
+
+/// Split a query into tokens with their `(start, end)` byte offsets.
+fn tokenize(input: &str) -> Result<Vec<(Token, usize, usize)>, String> {
+    const SYMBOLS: &[(&str, Option<Token>)] = &[
+        ("<-[", Some(Token::ArrowBracket)),
+        ("]->", Some(Token::BracketArrow)),
+        ("-[", Some(Token::DashBracket)),
+        ("]-", Some(Token::BracketDash)),
+        ("..", Some(Token::DotDot)),
+        ("!=", None),
+        ("!~", None),
+        ("<=", None),
+        (">=", None),
+        ("=", None),
+        ("~", None),
+        ("<", None),
+        (">", None),
+        ("(", Some(Token::LParen)),
+        (")", Some(Token::RParen)),
+        (",", Some(Token::Comma)),
+        ("|", Some(Token::Pipe)),
+        ("*", Some(Token::Star)),
+    ];
+    const OPS: &[&str] = &["!=", "!~", "<=", ">=", "=", "~", "<", ">"];
+
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    'outer: while pos < input.len() {
+        let rest = &input[pos..];
+        let c = rest.chars().next().unwrap_or(' ');
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            let Some(len) = rest[1..].find(c) else {
+                return Err(format!("at offset {pos}: unterminated string"));
+            };
+            tokens.push((Token::Str(rest[1..=len].to_string()), pos, pos + len + 2));
+            pos += len + 2;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let len = rest
+                .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+                .unwrap_or(rest.len());
+            // Keep `1..3` as number, range, number
+            let len = rest[..len].find("..").unwrap_or(len);
+            let text = &rest[..len];
+            let value = text
+                .parse::<f64>()
+                .map_err(|_| format!("at offset {pos}: invalid number '{text}'"))?;
+            tokens.push((Token::Number(value), pos, pos + len));
+            pos += len;
+            continue;
+        }
+        for (symbol, token) in SYMBOLS {
+            if rest.starts_with(symbol) {
+                let token = match token {
+                    Some(t) => t.clone(),
+                    None => Token::Op(OPS.iter().find(|op| *op == symbol).copied().unwrap_or("=")),
+                };
+                tokens.push((token, pos, pos + symbol.len()));
+                pos += symbol.len();
+                continue 'outer;
+            }
+        }
+        if is_word_char(c) {
+            // Hyphens join words (`my-app`) unless they open an edge pattern
+            let len = rest
+                .char_indices()
+                .find(|&(i, ch)| !(is_word_char(ch) || ch == '-' && !rest[i..].starts_with("-[")))
+                .map_or(rest.len(), |(i, _)| i);
+            tokens.push((Token::Word(rest[..len].to_string()), pos, pos + len));
+            pos += len;
+            continue;
+        }
+        return Err(format!("at offset {pos}: unexpected character '{c}'"));
+    }
+    Ok(tokens)
+}
+
+/// Characters allowed in bare words: identifiers, paths and glob patterns.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '/' | ':' | '.' | '?' | '@' | '$' | '#')
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/// Parse a query string.
+pub fn parse(input: &str) -> Result<Query, String> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        input,
+        tokens,
+        pos: 0,
+        patterns: Vec::new(),
+        nodes: 0,
+    };
+    parser.query()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<(Token, usize, usize)>,
+    pos: usize,
+    patterns: Vec<Pattern>,
+    /// Expression nodes parsed so far, see [`MAX_EXPR_NODES`].
+    nodes: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _, _)| t)
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + ahead).map(|(t, _, _)| t)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn error(&self, message: &str) -> String {
+        let offset = self
+            .tokens
+            .get(self.pos)
+            .map_or(self.input.len(), |(_, start, _)| *start);
+        format!(
+            "at offset {offset}: {message}, found {}",
+            describe(self.peek())
+        )
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &Token, what: &str) -> Result<(), String> {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {what}")))
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, String> {
+        if !self.eat_keyword("find") {
+            return Err(self.error("expected 'find'"));
+        }
+        let kinds = self.kinds()?;
+        let filter = if self.eat_keyword("where") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+        let aggregate = if self.eat_keyword("count") {
+            Aggregate::Count
+        } else if self.eat_keyword("group") {
+            if !self.eat_keyword("by") {
+                return Err(self.error("expected 'by'"));
+            }
+            let field = self.field()?;
+            if field.is_numeric() {
+                return Err(format!("cannot group by numeric field '{field:?}'"));
+            }
+            Aggregate::GroupBy(field)
+        } else {
+            Aggregate::None
+        };
+        let limit = if self.eat_keyword("limit") {
+            Some(self.count_value()?)
+        } else {
+            None
+        };
+        if self.peek().is_some() {
+            return Err(self.error("unexpected input"));
+        }
+        Ok(Query {
+            kinds,
+            filter,
+            aggregate,
+            limit,
+            patterns: std::mem::take(&mut self.patterns),
+        })
+    }
+
+    fn kinds(&mut self) -> Result<Option<Vec<NodeKind>>, String> {
+        if self.peek() == Some(&Token::Star) {
+            self.pos += 1;
+            return Ok(None);
+        }
+        let mut kinds = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Word(w)) => {
+                    let Some(kind) = parse_node_kind(w) else {
+                        return Err(self.error("expected an entity kind"));
+                    };
+                    kinds.push(kind);
+                    self.pos += 1;
+                }
+                _ => return Err(self.error("expected an entity kind or '*'")),
+            }
+            if self.peek() != Some(&Token::Comma) {
+                return Ok(Some(kinds));
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Count an expression node, failing once the query holds too many.
+    fn node(&mut self) -> Result<(), String> {
+        self.nodes += 1;
+        if self.nodes > MAX_EXPR_NODES {
+            return Err(self.error(&format!(
+                "query too complex (more than {MAX_EXPR_NODES} conditions and operators)"
+            )));
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        while self.eat_keyword("or") {
+            self.node()?;
+            let right = self.term()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.factor()?;
+        while self.eat_keyword("and") {
+            self.node()?;
+            let right = self.factor()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expr, String> {
+        self.node()?;
+        if self.eat_keyword("not") {
+            return Ok(Expr::Not(Box::new(self.factor()?)));
+        }
+        match self.peek() {
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                self.expect(&Token::RParen, "')'")?;
+                Ok(inner)
+            }
+            Some(Token::DashBracket | Token::ArrowBracket) => Ok(Expr::Pattern(self.pattern()?)),
+            Some(Token::Word(w))
+                if w.eq_ignore_ascii_case("count") && self.peek_at(1) == Some(&Token::LParen) =>
+            {
+                self.pos += 2;
+                let pattern = self.pattern()?;
+                self.expect(&Token::RParen, "')'")?;
+                let op = match self.next() {
+                    Some(Token::Op(op)) => cmp_op(op)
+                        .ok_or_else(|| self.error_before("count() needs a numeric comparison"))?,
+                    _ => return Err(self.error_before("expected a comparison after count()")),
+                };
+                let value = self.number()?;
+                Ok(Expr::Count { pattern, op, value })
+            }
+            _ => Ok(Expr::Predicate(self.predicate()?)),
+        }
+    }
+
+    /// Error pointing at the token just consumed.
+    fn error_before(&mut self, message: &str) -> String {
+        self.pos -= 1;
+        self.error(message)
+    }
+
+    fn field(&mut self) -> Result<Field, String> {
+        match self.peek() {
+            Some(Token::Word(w)) => {
+                let Some(field) = Field::parse(&w.to_lowercase()) else {
+                    return Err(self.error("unknown field"));
+                };
+                self.pos += 1;
+                Ok(field)
+            }
+            _ => Err(self.error("expected a field name")),
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, String> {
+        let field = self.field()?;
+        let Some(Token::Op(op)) = self.next() else {
+            return Err(self.error_before("expected an operator"));
+        };
+        if field.is_numeric() {
+            let Some(cmp) = cmp_op(op) else {
+                return Err(self.error_before("numeric fields take =, !=, <, <=, > or >="));
+            };
+            let value = self.number()?;
+            return Ok(Predicate {
+                field,
+                test: Test::Number(cmp, value),
+                negate: false,
+            });
+        }
+
+        let negate = matches!(op, "!=" | "!~");
+        let value = self.text()?;
+        let test = match (field, op) {
+            (Field::Kind, "=" | "!=") => match parse_node_kind(&value) {
+                Some(kind) => Test::Kind(kind),
+                None => return Err(self.error_before("unknown entity kind")),
+            },
+            (Field::Lifted, "=" | "!=") => match value.to_lowercase().as_str() {
+                "true" | "yes" => Test::Bool(true),
+                "false" | "no" => Test::Bool(false),
+                _ => return Err(self.error_before("'lifted' takes true or false")),
+            },
+            (Field::Lifted, _) => {
+                return Err(self.error_before("'lifted' takes = or !="));
+            }
+            (_, "=" | "!=") if value.contains(['*', '?']) => {
+                let glob = GlobBuilder::new(&value)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| self.error_before(&format!("invalid pattern: {e}")))?;
+                Test::Glob(glob.compile_matcher())
+            }
+            (_, "=" | "!=") => Test::Exact(value.to_lowercase()),
+            (_, "~" | "!~") => Test::Contains(value.to_lowercase()),
+            _ => {
+                return Err(self.error_before("text fields take =, !=, ~ or !~"));
+            }
+        };
+        Ok(Predicate {
+            field,
+            test,
+            negate,
+        })
+    }
+
+    /// A quoted string, or bare tokens written without spaces between them
+    /// (so unquoted globs such as `src/api/*.rs` read as one value).
+    fn text(&mut self) -> Result<String, String> {
+        if let Some(Token::Str(s)) = self.peek() {
+            let s = s.clone();
+            self.pos += 1;
+            return Ok(s);
+        }
+        let first = self.pos;
+        while let Some((Token::Word(_) | Token::Number(_) | Token::Star, start, _)) =
+            self.tokens.get(self.pos)
+        {
+            if self.pos > first && self.tokens[self.pos - 1].2 != *start {
+                break;
+            }
+            self.pos += 1;
+        }
+        if self.pos == first {
+            return Err(self.error("expected a value"));
+        }
+        Ok(self.input[self.tokens[first].1..self.tokens[self.pos - 1].2].to_string())
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => Err(self.error("expected a number")),
+        }
+    }
+
+    fn count_value(&mut self) -> Result<usize, String> {
+        let n = self.number()?;
+        if n < 0.0 || n.fract() != 0.0 {
+            return Err(self.error_before("expected a whole number"));
+        }
+        #[allow(clippy::cast_sign_loss)] // checked non-negative above
+        let count = n as usize;
+        Ok(count)
+    }
+
+    fn pattern(&mut self) -> Result<usize, String> {
+        let incoming = match self.next() {
+            Some(Token::ArrowBracket) => true,
+            Some(Token::DashBracket) => false,
+            _ => return Err(self.error_before("expected '-[' or '<-['")),
+        };
+
+        let mut edges = Vec::new();
+        while let Some(Token::Word(w)) = self.peek() {
+            let Some(kind) = parse_edge_kind(w) else {
+                return Err(self.error("unknown edge kind"));
+            };
+            edges.push(kind);
+            self.pos += 1;
+            if self.peek() != Some(&Token::Pipe) {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        let (mut min_hops, mut max_hops) = (1, Some(1));
+        if self.peek() == Some(&Token::Star) {
+            self.pos += 1;
+            (min_hops, max_hops) = (1, None);
+            if matches!(self.peek(), Some(Token::Number(_))) {
+                min_hops = self.count_value()?;
+                max_hops = Some(min_hops);
+            }
+            if self.peek() == Some(&Token::DotDot) {
+                self.pos += 1;
+                max_hops = if matches!(self.peek(), Some(Token::Number(_))) {
+                    Some(self.count_value()?)
+                } else {
+                    None
+                };
+            }
+            if max_hops.is_some_and(|max| max < min_hops) {
+                return Err(self.error_before("path length range is empty"));
+            }
+        }
+
+        let direction = match (incoming, self.next()) {
+            (false, Some(Token::BracketArrow)) => Direction::Outgoing,
+            (false, Some(Token::BracketDash)) => Direction::Either,
+            (true, Some(Token::BracketDash)) => Direction::Incoming,
+            (true, _) => return Err(self.error_before("expected ']-'")),
+            (false, _) => return Err(self.error_before("expected ']->' or ']-'")),
+        };
+
+        self.expect(&Token::LParen, "'(' after edge pattern")?;
+        let target = if self.peek() == Some(&Token::RParen) {
+            None
+        } else {
+            Some(self.expr()?)
+        };
+        self.expect(&Token::RParen, "')'")?;
+
+        self.patterns.push(Pattern {
+            direction,
+            edges,
+            min_hops,
+            max_hops,
+            target,
+        });
+        Ok(self.patterns.len() - 1)
+    }
+}
+
+fn cmp_op(op: &str) -> Option<CmpOp> {
+    Some(match op {
+        "=" => CmpOp::Eq,
+        "!=" => CmpOp::Ne,
+        "<" => CmpOp::Lt,
+        "<=" => CmpOp::Le,
+        ">" => CmpOp::Gt,
+        ">=" => CmpOp::Ge,
+        _ => return None,
+    })
+}
+
+/// Parse a kind name, accepting plurals (`controllers`).
+fn parse_node_kind(name: &str) -> Option<NodeKind> {
+    let name = name.to_lowercase();
+    let singular = name
+        .strip_suffix("es")
+        .filter(|s| s.ends_with("ss"))
+        .or_else(|| name.strip_suffix('s'))
+        .unwrap_or(&name);
+    [name.as_str(), singular].into_iter().find_map(|n| {
+        Some(NodeKind::Entity(match n {
+            "function" => EntityKind::Function,
+            "class" => EntityKind::Class,
+            "method" => EntityKind::Method,
+            "page" => EntityKind::Page,
+            "layout" => EntityKind::Layout,
+            "component" => EntityKind::Component,
+            "hook" => EntityKind::Hook,
+            "store" => EntityKind::Store,
+            "module" | "file" => EntityKind::Module,
+            "controller" => EntityKind::Controller,
+            "model" => EntityKind::Model,
+            "service" => EntityKind::Service,
+            "middleware" => EntityKind::Middleware,
+            "route" => EntityKind::Route,
+            "test" => EntityKind::Test,
+            "field" => EntityKind::Field,
+            "constant" => EntityKind::Constant,
+            "enum_variant" => EntityKind::EnumVariant,
+            "global" => EntityKind::Global,
+            "external" | "package" => return Some(NodeKind::External),
+            _ => return None,
+        }))
+    })
+}
+
+/// Edge kinds followed and counted when none are named: direct dependencies,
+/// not containment or the transitive `Tests` links.
+fn is_default_edge(kind: EdgeKind) -> bool {
+    !matches!(kind, EdgeKind::Contains | EdgeKind::Tests)
+}
+
+fn parse_edge_kind(name: &str) -> Option<EdgeKind> {
+    Some(match name.to_lowercase().as_str() {
+        "imports" => EdgeKind::Imports,
+        "invokes" => EdgeKind::Invokes,
+        "inherits" => EdgeKind::Inherits,
+        "composes" => EdgeKind::Composes,
+        "renders" => EdgeKind::Renders,
+        "reads_state" => EdgeKind::ReadsState,
+        "writes_state" => EdgeKind::WritesState,
+        "dispatches" => EdgeKind::Dispatches,
+        "data_flow" => EdgeKind::DataFlow,
+        "tests" => EdgeKind::Tests,
+        "contains" => EdgeKind::Contains,
+        _ => return None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+/// A graph node a query can reach: an entity, an external package, or
+/// (through `contains` edges) a hierarchy node.
+struct Node<'g> {
+    id: &'g str,
+    entity: Option<&'g Entity>,
+    external: Option<&'g ExternalNode>,
+}
+
+struct Evaluator<'g, 'q> {
+    query: &'q Query,
+    nodes: Vec<Node<'g>>,
+    outgoing: Vec<Vec<(usize, EdgeKind)>>,
+    incoming: Vec<Vec<(usize, EdgeKind)>>,
+    /// Per pattern: nodes the pattern holds for.
+    sources: Vec<Option<Vec<bool>>>,
+    /// Per pattern: nodes satisfying the pattern's inner expression.
+    targets: Vec<Option<Vec<bool>>>,
+}
+
+impl<'g, 'q> Evaluator<'g, 'q> {
+    fn new(graph: &'g RPGraph, query: &'q Query) -> Self {
+        let mut nodes: Vec<Node<'g>> = Vec::new();
+        let mut index: HashMap<&'g str, usize> = HashMap::new();
+        for entity in graph.entities.values() {
+            index.insert(&entity.id, nodes.len());
+            nodes.push(Node {
+                id: &entity.id,
+                entity: Some(entity),
+                external: None,
+            });
+        }
+        for external in graph.externals.values() {
+            index.insert(&external.id, nodes.len());
+            nodes.push(Node {
+                id: &external.id,
+                entity: None,
+                external: Some(external),
+            });
+        }
+
+        let mut outgoing: Vec<Vec<(usize, EdgeKind)>> = vec![Vec::new(); nodes.len()];
+        let mut incoming: Vec<Vec<(usize, EdgeKind)>> = vec![Vec::new(); nodes.len()];
+        for edge in &graph.edges {
+            let mut slot = |id: &'g str| {
+                *index.entry(id).or_insert_with(|| {
+                    nodes.push(Node {
+                        id,
+                        entity: None,
+                        external: None,
+                    });
+                    outgoing.push(Vec::new());
+                    incoming.push(Vec::new());
+                    nodes.len() - 1
+                })
+            };
+            let (source, target) = (slot(&edge.source), slot(&edge.target));
+            outgoing[source].push((target, edge.kind));
+            incoming[target].push((source, edge.kind));
+        }
+
+        Self {
+            query,
+            nodes,
+            outgoing,
+            incoming,
+            sources: vec![None; query.patterns.len()],
+            targets: vec![None; query.patterns.len()],
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr, node: usize) -> bool {
+        match expr {
+            Expr::And(a, b) => self.eval(a, node) && self.eval(b, node),
+            Expr::Or(a, b) => self.eval(a, node) || self.eval(b, node),
+            Expr::Not(inner) => !self.eval(inner, node),
+            Expr::Predicate(predicate) => self.test(predicate, node),
+            Expr::Pattern(pattern) => {
+                if self.sources[*pattern].is_none() {
+                    let sources = self.pattern_sources(*pattern);
+                    self.sources[*pattern] = Some(sources);
+                }
+                self.sources[*pattern].as_ref().is_some_and(|s| s[node])
+            }
+            Expr::Count { pattern, op, value } => {
+                let count = self.reached_targets(*pattern, node);
+                op.holds(count as f64, *value)
+            }
+        }
+    }
+
+    fn target_set(&mut self, pattern: usize) -> Vec<bool> {
+        if let Some(targets) = &self.targets[pattern] {
+            return targets.clone();
+        }
+        let query = self.query;
+        let targets: Vec<bool> = match &query.patterns[pattern].target {
+            None => vec![true; self.nodes.len()],
+            Some(expr) => (0..self.nodes.len()).map(|n| self.eval(expr, n)).collect(),
+        };
+        self.targets[pattern] = Some(targets.clone());
+        targets
+    }
+
+    /// Every node from which a matching path starts, found by walking back
+    /// from the pattern's targets.
+    fn pattern_sources(&mut self, pattern: usize) -> Vec<bool> {
+        let targets = self.target_set(pattern);
+        let starts: Vec<usize> = (0..self.nodes.len()).filter(|&n| targets[n]).collect();
+        let reached = self.walk(pattern, &starts, false);
+        let mut sources = vec![false; self.nodes.len()];
+        for node in reached {
+            sources[node] = true;
+        }
+        sources
+    }
+
+    /// Distinct targets reachable from `node` along the pattern.
+    fn reached_targets(&mut self, pattern: usize, node: usize) -> usize {
+        let targets = self.target_set(pattern);
+        self.walk(pattern, &[node], true)
+            .into_iter()
+            .filter(|&n| targets[n])
+            .count()
+    }
+
+    /// Breadth-first walk along the pattern's edges (forward from sources, or
+    /// backward from targets), returning the nodes reached at a length within
+    /// the pattern's bounds.
+    ///
+    /// States are `(node, min(depth, min_hops))`: once a walk is long enough,
+    /// its exact length only matters against `max_hops`, and BFS reaches each
+    /// state first at its shortest length.
+    fn walk(&self, pattern: usize, starts: &[usize], forward: bool) -> Vec<usize> {
+        let pattern = &self.query.patterns[pattern];
+        let (min, max) = (pattern.min_hops, pattern.max_hops);
+        let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        let mut reached: Vec<usize> = Vec::new();
+        let mut reached_set: HashSet<usize> = HashSet::new();
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+        for &start in starts {
+            if seen.insert((start, 0)) {
+                queue.push_back((start, 0));
+            }
+        }
+        while let Some((node, depth)) = queue.pop_front() {
+            if depth >= min && reached_set.insert(node) {
+                reached.push(node);
+            }
+            if max.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            let along = matches!(
+                (pattern.direction, forward),
+                (Direction::Outgoing, true) | (Direction::Incoming, false)
+            );
+            let lists: &[&Vec<(usize, EdgeKind)>] = match pattern.direction {
+                Direction::Either => &[&self.outgoing[node], &self.incoming[node]],
+                _ if along => &[&self.outgoing[node]],
+                _ => &[&self.incoming[node]],
+            };
+            for list in lists {
+                for &(next, kind) in *list {
+                    if pattern.follows(kind) && seen.insert((next, (depth + 1).min(min))) {
+                        queue.push_back((next, depth + 1));
+                    }
+                }
+            }
+        }
+        reached
+    }
+
+    fn test(&self, predicate: &Predicate, node: usize) -> bool {
+        let result = match &predicate.test {
+            Test::Kind(kind) => self.node_kind(node) == Some(*kind),
+            Test::Bool(expected) => {
+                self.nodes[node]
+                    .entity
+                    .is_some_and(|e| !e.semantic_features.is_empty())
+                    == *expected
+            }
+            Test::Number(op, value) => self
+                .number(predicate.field, node)
+                .is_some_and(|n| op.holds(n, *value)),
+            Test::Exact(value) => self
+                .texts(predicate.field, node)
+                .iter()
+                .any(|t| match predicate.field {
+                    Field::Path => {
+                        let t = t.to_lowercase();
+                        t == *value
+                            || t.strip_prefix(value.as_str())
+                                .is_some_and(|rest| rest.starts_with('/'))
+                    }
+                    _ => t.eq_ignore_ascii_case(value),
+                }),
+            Test::Glob(glob) => self
+                .texts(predicate.field, node)
+                .iter()
+                .any(|t| glob.is_match(t)),
+            Test::Contains(value) => self
+                .texts(predicate.field, node)
+                .iter()
+                .any(|t| t.to_lowercase().contains(value.as_str())),
+        };
+        result != predicate.negate
+    }
+
+    fn node_kind(&self, node: usize) -> Option<NodeKind> {
+        let node = &self.nodes[node];
+        match (node.entity, node.external) {
+            (Some(entity), _) => Some(NodeKind::Entity(entity.kind)),
+            (None, Some(_)) => Some(NodeKind::External),
+            (None, None) => None,
+        }
+    }
+
+    fn texts(&self, field: Field, node: usize) -> Vec<String> {
+        let n = &self.nodes[node];
+        if let Some(external) = n.external {
+            return match field {
+                Field::Id => vec![external.id.clone()],
+                Field::Name => vec![external.name.clone()],
+                Field::Kind => vec!["external".to_string()],
+                _ => Vec::new(),
+            };
+        }
+        let Some(entity) = n.entity else {
+            return match field {
+                Field::Id => vec![n.id.to_string()],
+                _ => Vec::new(),
+            };
+        };
+        match field {
+            Field::Id => vec![entity.id.clone()],
+            Field::Name => vec![entity.name.clone()],
+            Field::Kind => vec![kind_name(entity.kind)],
+            Field::File => vec![rpg_core::graph::normalize_path(&entity.file)],
+            Field::Area => area_of(entity).into_iter().collect(),
+            Field::Path => (!entity.hierarchy_path.is_empty())
+                .then(|| entity.hierarchy_path.clone())
+                .into_iter()
+                .collect(),
+            Field::Feature => entity.semantic_features.clone(),
+            Field::Decorator => entity.metadata.decorators.clone(),
+            Field::Visibility => entity
+                .metadata
+                .visibility
+                .map(|v| v.as_str().to_string())
+                .into_iter()
+                .collect(),
+            Field::Parent => entity.parent_class.clone().into_iter().collect(),
+            Field::Lifted => vec![(!entity.semantic_features.is_empty()).to_string()],
+            Field::Lines | Field::FanIn | Field::FanOut => self
+                .number(field, node)
+                .map(|n| n.to_string())
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn number(&self, field: Field, node: usize) -> Option<f64> {
+        let distinct = |edges: &[(usize, EdgeKind)]| {
+            edges
+                .iter()
+                .filter(|(_, kind)| is_default_edge(*kind))
+                .map(|(n, _)| *n)
+                .collect::<HashSet<_>>()
+                .len() as f64
+        };
+        match field {
+            Field::Lines => self.nodes[node]
+                .entity
+                .map(|e| (e.line_end.saturating_sub(e.line_start) + 1) as f64),
+            Field::FanIn => Some(distinct(&self.incoming[node])),
+            Field::FanOut => Some(distinct(&self.outgoing[node])),
+            _ => None,
+        }
+    }
+
+    fn is_candidate(&self, node: usize) -> bool {
+        let Some(kind) = self.node_kind(node) else {
+            return false;
+        };
+        match &self.query.kinds {
+            None => matches!(kind, NodeKind::Entity(_)),
+            Some(kinds) => kinds.contains(&kind),
+        }
+    }
+
+    fn row(&self, node: usize) -> QueryMatch {
+        let n = &self.nodes[node];
+        match n.entity {
+            Some(entity) => QueryMatch {
+                entity_id: entity.id.clone(),
+                name: entity.name.clone(),
+                kind: kind_name(entity.kind),
+                file: rpg_core::graph::normalize_path(&entity.file),
+                line: entity.line_start,
+                area: area_of(entity).unwrap_or_default(),
+            },
+            None => QueryMatch {
+                entity_id: n.id.to_string(),
+                name: n.external.map_or_else(String::new, |e| e.name.clone()),
+                kind: "external".to_string(),
+                file: String::new(),
+                line: 0,
+                area: String::new(),
+            },
+        }
+    }
+}
+
+fn kind_name(kind: EntityKind) -> String {
+    match kind {
+        EntityKind::EnumVariant => "enum_variant".to_string(),
+        other => format!("{other:?}").to_lowercase(),
+    }
+}
+
+fn area_of(entity: &Entity) -> Option<String> {
+    entity
+        .hierarchy_path
+        .split('/')
+        .next()
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+}
+
+/// Run a parsed query against the graph.
+pub fn execute(graph: &RPGraph, query: &Query) -> QueryResult {
+    let mut evaluator = Evaluator::new(graph, query);
+    let mut matched: Vec<usize> = Vec::new();
+    for node in 0..evaluator.nodes.len() {
+        if !evaluator.is_candidate(node) {
+            continue;
+        }
+        if query
+            .filter
+            .as_ref()
+            .is_none_or(|filter| evaluator.eval(filter, node))
+        {
+            matched.push(node);
+        }
+    }
+    matched.sort_by(|a, b| evaluator.nodes[*a].id.cmp(evaluator.nodes[*b].id));
+
+    let total = matched.len();
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    match query.aggregate {
+        Aggregate::Count => QueryResult {
+            total,
+            matches: Vec::new(),
+            groups: None,
+            truncated: false,
+        },
+        Aggregate::GroupBy(field) => {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for &node in &matched {
+                let mut keys = evaluator.texts(field, node);
+                keys.sort();
+                keys.dedup();
+                if keys.is_empty() {
+                    keys.push("(none)".to_string());
+                }
+                for key in keys {
+                    *counts.entry(key).or_default() += 1;
+                }
+            }
+            let mut groups: Vec<QueryGroup> = counts
+                .into_iter()
+                .map(|(key, count)| QueryGroup { key, count })
+                .collect();
+            groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+            let truncated = groups.len() > limit;
+            groups.truncate(limit);
+            QueryResult {
+                total,
+                matches: Vec::new(),
+                groups: Some(groups),
+                truncated,
+            }
+        }
+        Aggregate::None => QueryResult {
+            total,
+            matches: matched
+                .iter()
+                .take(limit)
+                .map(|&node| evaluator.row(node))
+                .collect(),
+            groups: None,
+            truncated: total > limit,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpg_core::graph::{DependencyEdge, EntityDeps, EntityMetadata};
+    use std::path::PathBuf;
+
+    fn make_entity(id: &str, kind: EntityKind, file: &str, hierarchy: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            kind,
+            name: id.rsplit(':').next().unwrap_or(id).to_string(),
+            file: PathBuf::from(file),
+            line_start: 1,
+            line_end: 10,
+            parent_class: None,
+            semantic_features: if hierarchy.is_empty() {
+                Vec::new()
+            } else {
+                vec![format!("handle {id}")]
+            },
+            feature_source: None,
+            hierarchy_path: hierarchy.to_string(),
+            deps: EntityDeps::default(),
+            signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
+        }
+    }
+
+    /// Two API controllers, a service, a repository and a test:
+    ///
+    /// ```text
+    /// users_ctl -> user_service -> user_repo
+    /// orders_ctl -> format_order
+    /// test_users -> users_ctl
+    /// ```
+    fn make_test_graph() -> RPGraph {
+        let mut graph = RPGraph::new("python");
+        for entity in [
+            make_entity(
+                "api/users.py:users_ctl",
+                EntityKind::Controller,
+                "api/users.py",
+                "API/users/handle",
+            ),
+            make_entity(
+                "api/orders.py:orders_ctl",
+                EntityKind::Controller,
+                "api/orders.py",
+                "API/orders/handle",
+            ),
+            make_entity(
+                "core/users.py:user_service",
+                EntityKind::Service,
+                "core/users.py",
+                "Domain/users/manage",
+            ),
+            make_entity(
+                "db/users.py:user_repo",
+                EntityKind::Class,
+                "db/users.py",
+                "Persistence/users/store",
+            ),
+            make_entity(
+                "api/orders.py:format_order",
+                EntityKind::Function,
+                "api/orders.py",
+                "",
+            ),
+            make_entity(
+                "tests/test_users.py:test_users",
+                EntityKind::Test,
+                "tests/test_users.py",
+                "Testing/users/verify",
+            ),
+        ] {
+            graph.insert_entity(entity);
+        }
+        for (source, target) in [
+            ("api/users.py:users_ctl", "core/users.py:user_service"),
+            ("core/users.py:user_service", "db/users.py:user_repo"),
+            ("api/orders.py:orders_ctl", "api/orders.py:format_order"),
+            ("tests/test_users.py:test_users", "api/users.py:users_ctl"),
+        ] {
+            graph.edges.push(DependencyEdge {
+                source: source.to_string(),
+                target: target.to_string(),
+                kind: EdgeKind::Invokes,
+                confidence: None,
+            });
+        }
+        graph.refresh_metadata();
+        graph
+    }
+
+    fn ids(graph: &RPGraph, query: &str) -> Vec<String> {
+        run(graph, query)
+            .unwrap()
+            .matches
+            .into_iter()
+            .map(|m| m.entity_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_kind_and_field_filters() {
+        let graph = make_test_graph();
+        assert_eq!(
+            ids(&graph, "find controllers"),
+            ["api/orders.py:orders_ctl", "api/users.py:users_ctl"]
+        );
+        assert_eq!(
+            ids(&graph, "find * where area = api and name ~ USER"),
+            ["api/users.py:users_ctl"]
+        );
+        assert_eq!(
+            ids(
+                &graph,
+                "find * where file = api/*.py and not kind = controller"
+            ),
+            ["api/orders.py:format_order"]
+        );
+        assert_eq!(
+            ids(&graph, "FIND service, class WHERE path = \"Domain/users\""),
+            ["core/users.py:user_service"]
+        );
+        assert_eq!(
+            ids(&graph, "find * where lifted = false"),
+            ["api/orders.py:format_order"]
+        );
+        assert_eq!(
+            ids(&graph, "find * where fan_in >= 1 and fan_out = 0"),
+            ["api/orders.py:format_order", "db/users.py:user_repo"]
+        );
+    }
+
+    #[test]
+    fn test_edge_patterns_with_path_bounds() {
+        let graph = make_test_graph();
+        // One hop: controllers reach the service, not the repository
+        assert!(
+            ids(
+                &graph,
+                "find controller where -[invokes]-> (area = Persistence)"
+            )
+            .is_empty()
+        );
+        // Up to three hops, and no test invoking them
+        assert_eq!(
+            ids(
+                &graph,
+                "find controller where area = \"API\" \
+                 and -[invokes *1..3]-> (area = \"Persistence\") \
+                 and not <-[invokes]- (kind = test)"
+            ),
+            Vec::<String>::new(),
+            "users_ctl is tested"
+        );
+        assert_eq!(
+            ids(
+                &graph,
+                "find controller where -[invokes *..3]-> (area = Persistence)"
+            ),
+            ["api/users.py:users_ctl"]
+        );
+        // Exact length: the repository is two hops away from the controller
+        assert_eq!(
+            ids(&graph, "find * where -[*2]-> (kind = class)"),
+            ["api/users.py:users_ctl"]
+        );
+        assert_eq!(
+            ids(&graph, "find * where -[*3..]-> (kind = class)"),
+            ["tests/test_users.py:test_users"]
+        );
+        // Untested controllers
+        assert_eq!(
+            ids(
+                &graph,
+                "find controller where not <-[invokes *]- (kind = test)"
+            ),
+            ["api/orders.py:orders_ctl"]
+        );
+        // Either direction, empty target
+        assert_eq!(
+            ids(&graph, "find function where -[invokes]- ()"),
+            ["api/orders.py:format_order"]
+        );
+        // Nested patterns
+        assert_eq!(
+            ids(
+                &graph,
+                "find test where -[invokes]-> (-[invokes]-> (kind = service))"
+            ),
+            ["tests/test_users.py:test_users"]
+        );
+    }
+
+    #[test]
+    fn test_count_and_aggregation() {
+        let graph = make_test_graph();
+        assert_eq!(
+            ids(&graph, "find * where count(-[invokes *]-> ()) >= 2"),
+            ["api/users.py:users_ctl", "tests/test_users.py:test_users"]
+        );
+
+        let result = run(&graph, "find * where kind != test count").unwrap();
+        assert_eq!(result.total, 5);
+        assert!(result.matches.is_empty());
+
+        let result = run(&graph, "find * group by area").unwrap();
+        let groups: Vec<(String, usize)> = result
+            .groups
+            .unwrap()
+            .into_iter()
+            .map(|g| (g.key, g.count))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("API".to_string(), 2),
+                ("(none)".to_string(), 1),
+                ("Domain".to_string(), 1),
+                ("Persistence".to_string(), 1),
+                ("Testing".to_string(), 1),
+            ]
+        );
+
+        let result = run(&graph, "find * limit 2").unwrap();
+        assert_eq!((result.total, result.matches.len()), (6, 2));
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn test_external_packages() {
+        let mut graph = make_test_graph();
+        let id = ExternalNode::id_for("sqlalchemy");
+        graph.externals.insert(
+            id.clone(),
+            ExternalNode {
+                id: id.clone(),
+                name: "sqlalchemy".to_string(),
+                ecosystem: rpg_core::graph::PackageEcosystem::PyPI,
+                version: None,
+                manifests: Vec::new(),
+            },
+        );
+        graph.edges.push(DependencyEdge {
+            source: "db/users.py:user_repo".to_string(),
+            target: id.clone(),
+            kind: EdgeKind::Imports,
+            confidence: None,
+        });
+        assert_eq!(ids(&graph, "find external"), [id.as_str()]);
+        assert_eq!(
+            ids(&graph, "find * where -[imports]-> (name = sqlalchemy)"),
+            ["db/users.py:user_repo"]
+        );
+        assert_eq!(ids(&graph, "find package where fan_in = 1"), [id]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (query, expected) in [
+            ("controllers", "expected 'find'"),
+            ("find widget", "expected an entity kind"),
+            ("find * where colour = red", "unknown field"),
+            ("find * where kind = widget", "unknown entity kind"),
+            ("find * where lines ~ 3", "numeric fields take"),
+            ("find * where -[calls]-> ()", "unknown edge kind"),
+            ("find * where -[invokes *3..1]-> ()", "range is empty"),
+            ("find * where <-[invokes]-> ()", "expected ']-'"),
+            ("find * where name = \"open", "unterminated string"),
+            (
+                "find * where name =",
+                "expected a value, found end of query",
+            ),
+            ("find * group by lines", "cannot group by"),
+            ("find * limit 2 extra", "unexpected input"),
+        ] {
+            let err = parse(query).unwrap_err();
+            assert!(err.contains(expected), "{query}: {err}");
+        }
+        let err = parse("find * where name = x and").unwrap_err();
+        assert!(err.starts_with("at offset 25:"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_deep_or_long_expressions() {
+        let nested = format!(
+            "find * where {}name = a{}",
+            "(".repeat(50_000),
+            ")".repeat(50_000)
+        );
+        let negated = format!("find * where {}name = a", "not ".repeat(50_000));
+        let chained = format!("find * where name = a{}", " or name = a".repeat(50_000));
+        for query in [nested, negated, chained] {
+            let err = parse(&query).unwrap_err();
+            assert!(err.contains("query too complex"), "{err}");
+        }
+        let within = format!("find * where name = a{}", " or name = a".repeat(100));
+        assert!(parse(&within).is_ok());
+    }
+}
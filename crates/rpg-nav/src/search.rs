@@ -1,6 +1,7 @@
 //! SearchNode: intent-based code entity discovery.
 
-use rpg_core::graph::{Entity, EntityKind, RPGraph};
+use crate::search_index::{FEATURE_FIELDS, Field, SNIPPET_FIELDS, SearchIndex, tokenize};
+use rpg_core::graph::{Entity, EntityKind, EntityMetadata, RPGraph, Visibility};
 use std::collections::{HashMap, HashSet};
 
 /// Search mode (matching the paper's SearchNode tool).
@@ -26,6 +27,65 @@ pub struct SearchResult {
     pub lifted: bool,
 }
 
+/// Filter on extracted entity metadata. `None` fields are unconstrained.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataFilter {
+    /// Keep entities whose visibility is one of these.
+    pub visibility: Option<Vec<Visibility>>,
+    pub is_async: Option<bool>,
+    pub is_static: Option<bool>,
+    pub is_abstract: Option<bool>,
+    pub is_deprecated: Option<bool>,
+    /// Case-insensitive substring that one of the entity's decorators must contain.
+    pub decorator: Option<String>,
+    pub has_docstring: Option<bool>,
+}
+
+impl MetadataFilter {
+    /// Whether the filter constrains nothing.
+    pub fn is_empty(&self) -> bool {
+        self.visibility.is_none()
+            && self.is_async.is_none()
+            && self.is_static.is_none()
+            && self.is_abstract.is_none()
+            && self.is_deprecated.is_none()
+            && self.decorator.is_none()
+            && self.has_docstring.is_none()
+    }
+
+    /// Whether `metadata` satisfies every constraint in this filter.
+    pub fn matches(&self, metadata: &EntityMetadata) -> bool {
+        if let Some(ref visibilities) = self.visibility
+            && !metadata
+                .visibility
+                .is_some_and(|v| visibilities.contains(&v))
+        {
+            return false;
+        }
+        let flags = [
+            (self.is_async, metadata.is_async),
+            (self.is_static, metadata.is_static),
+            (self.is_abstract, metadata.is_abstract),
+            (self.is_deprecated, metadata.is_deprecated),
+            (self.has_docstring, metadata.docstring.is_some()),
+        ];
+        if flags
+            .iter()
+            .any(|(wanted, actual)| wanted.is_some_and(|w| w != *actual))
+        {
+            return false;
+        }
+        if let Some(ref needle) = self.decorator {
+            let needle = needle.to_lowercase();
+            return metadata
+                .decorators
+                .iter()
+                .any(|d| d.to_lowercase().contains(&needle));
+        }
+        true
+    }
+}
+
 /// Full search parameters matching the paper's SearchNode spec.
 pub struct SearchParams<'a> {
     pub query: &'a str,
@@ -38,11 +98,16 @@ pub struct SearchParams<'a> {
     pub file_pattern: Option<&'a str>,
     /// Filter results to specific entity kinds (function, class, method).
     pub entity_type_filter: Option<Vec<EntityKind>>,
+    /// Filter results by visibility, modifiers, decorators, or docstring presence.
+    pub metadata_filter: Option<&'a MetadataFilter>,
     /// Pre-computed embedding scores (entity_id → cosine score) for hybrid blending.
     /// When provided, features-mode search uses rank-based hybrid scoring.
     pub embedding_scores: Option<&'a std::collections::HashMap<String, f64>>,
     /// Diff-aware search context for proximity-based ranking boost.
     pub diff_context: Option<&'a crate::diff::DiffContext>,
+    /// Inverted index to score against instead of scanning every entity.
+    /// Must be synced with `graph` (see [`SearchIndex::sync`]).
+    pub index: Option<&'a SearchIndex>,
 }
 
 /// Search the RPG for entities matching a query with a configurable result limit.
@@ -63,8 +128,10 @@ pub fn search(
             line_nums: None,
             file_pattern: None,
             entity_type_filter: None,
+            metadata_filter: None,
             embedding_scores: None,
             diff_context: None,
+            index: None,
         },
     )
 }
@@ -102,6 +169,9 @@ fn apply_diff_boost(
 }
 
 /// Search with full parameters (paper-complete SearchNode).
+///
+/// When `params.index` is set, candidates come from the inverted index
+/// (BM25F scoring, cached scope sets); otherwise every entity is scanned.
 pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchResult> {
     let query_lower = params.query.to_lowercase();
     let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
@@ -114,6 +184,10 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
         .file_pattern
         .and_then(|pat| globset::Glob::new(pat).ok().map(|g| g.compile_matcher()));
 
+    if let Some(index) = params.index {
+        return search_indexed(graph, index, params, file_matcher.as_ref());
+    }
+
     let entities: Box<dyn Iterator<Item = (&String, &Entity)>> = if let Some(scope) = params.scope {
         let scoped_ids: HashSet<String> =
             collect_scoped_entities(graph, scope).into_iter().collect();
@@ -129,48 +203,22 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
 
     // Apply file_pattern, line_nums, and entity_type filters
     let entities: Vec<(&String, &Entity)> = entities
-        .filter(|(_, entity)| {
-            // File pattern filter
-            if let Some(ref matcher) = file_matcher
-                && !matcher.is_match(entity.file.as_path())
-            {
-                return false;
-            }
-            // Line range filter
-            if let Some((start, end)) = params.line_nums
-                && (entity.line_end < start || entity.line_start > end)
-            {
-                return false;
-            }
-            // Entity type filter
-            if let Some(ref kinds) = params.entity_type_filter
-                && !kinds.contains(&entity.kind)
-            {
-                return false;
-            }
-            true
-        })
+        .filter(|(_, entity)| passes_filters(entity, params, file_matcher.as_ref()))
         .collect();
 
-    // Collect IDs of entities that passed all user filters (scope/file/line/type).
+    // Collect IDs of entities that passed all user filters (scope/file/line/type/metadata).
     // This ensures semantic-only results from embeddings respect the same filters.
-    let candidate_ids: HashSet<&String> = entities.iter().map(|(id, _)| *id).collect();
+    let candidate_ids: HashSet<&str> = entities.iter().map(|(id, _)| id.as_str()).collect();
+    let is_candidate = |id: &str| candidate_ids.contains(id);
 
-    // When diff-aware boosting is requested, expand search limit to ensure changed
-    // entities outside the normal top-N have a chance to be boosted into results.
-    // Apply boost before final truncation.
-    let search_limit = if params.diff_context.is_some() {
-        params.limit * 10
-    } else {
-        params.limit
-    };
+    let search_limit = expanded_limit(params);
 
-    let mut results = match params.mode {
+    let results = match params.mode {
         SearchMode::Features => {
             let lexical = search_features(&entities, &query_terms, search_limit);
             maybe_hybrid_rerank(
                 graph,
-                &candidate_ids,
+                &is_candidate,
                 lexical,
                 params.embedding_scores,
                 search_limit,
@@ -178,44 +226,180 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
         }
         SearchMode::Snippets => search_snippets(&entities, &query_terms, search_limit),
         SearchMode::Auto => {
-            // Merge features + snippets.
             let feat_results = search_features(&entities, &query_terms, search_limit * 2);
             let snip_results = search_snippets(&entities, &query_terms, search_limit * 2);
+            let merged = merge_auto(feat_results, snip_results, search_limit);
+
+            // Apply hybrid reranking if embeddings available
+            maybe_hybrid_rerank(
+                graph,
+                &is_candidate,
+                merged,
+                params.embedding_scores,
+                search_limit,
+            )
+        }
+    };
 
-            let mut score_map: std::collections::HashMap<String, SearchResult> =
-                std::collections::HashMap::new();
+    finish(results, params)
+}
 
-            for r in feat_results {
-                score_map
-                    .entry(r.entity_id.clone())
-                    .and_modify(|existing| {
-                        existing.score += r.score;
-                        existing.matched_features.extend(r.matched_features.clone());
-                    })
-                    .or_insert(r);
-            }
-            for r in snip_results {
-                score_map
-                    .entry(r.entity_id.clone())
-                    .and_modify(|existing| existing.score += r.score * 0.5)
-                    .or_insert_with(|| SearchResult {
-                        score: r.score * 0.5,
-                        ..r
-                    });
-            }
+/// When diff-aware boosting is requested, expand the search limit so changed
+/// entities outside the normal top-N have a chance to be boosted into results.
+fn expanded_limit(params: &SearchParams) -> usize {
+    if params.diff_context.is_some() {
+        params.limit * 10
+    } else {
+        params.limit
+    }
+}
 
-            let mut merged: Vec<SearchResult> = score_map.into_values().collect();
-            merged.sort_by(|a, b| {
-                b.score
-                    .partial_cmp(&a.score)
-                    .unwrap_or(std::cmp::Ordering::Equal)
+/// Apply diff-aware proximity boost if provided, then truncate to the requested limit.
+fn finish(results: Vec<SearchResult>, params: &SearchParams) -> Vec<SearchResult> {
+    let mut results = apply_diff_boost(results, params.diff_context);
+    results.truncate(params.limit);
+    results
+}
+
+/// Whether an entity passes the file_pattern, line_nums, entity type and metadata filters.
+fn passes_filters(
+    entity: &Entity,
+    params: &SearchParams,
+    file_matcher: Option<&globset::GlobMatcher>,
+) -> bool {
+    // File pattern filter
+    if let Some(matcher) = file_matcher
+        && !matcher.is_match(entity.file.as_path())
+    {
+        return false;
+    }
+    // Line range filter
+    if let Some((start, end)) = params.line_nums
+        && (entity.line_end < start || entity.line_start > end)
+    {
+        return false;
+    }
+    // Entity type filter
+    if let Some(ref kinds) = params.entity_type_filter
+        && !kinds.contains(&entity.kind)
+    {
+        return false;
+    }
+    // Metadata filter
+    if let Some(filter) = params.metadata_filter
+        && !filter.matches(&entity.metadata)
+    {
+        return false;
+    }
+    true
+}
+
+/// Merge features and snippet results for auto mode (snippet scores count half).
+fn merge_auto(
+    feat_results: Vec<SearchResult>,
+    snip_results: Vec<SearchResult>,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut score_map: HashMap<String, SearchResult> = HashMap::new();
+
+    for r in feat_results {
+        score_map
+            .entry(r.entity_id.clone())
+            .and_modify(|existing| {
+                existing.score += r.score;
+                existing.matched_features.extend(r.matched_features.clone());
+            })
+            .or_insert(r);
+    }
+    for r in snip_results {
+        score_map
+            .entry(r.entity_id.clone())
+            .and_modify(|existing| existing.score += r.score * 0.5)
+            .or_insert_with(|| SearchResult {
+                score: r.score * 0.5,
+                ..r
             });
-            merged.truncate(search_limit);
+    }
 
-            // Apply hybrid reranking if embeddings available
+    let mut merged: Vec<SearchResult> = score_map.into_values().collect();
+    sort_by_score(&mut merged);
+    merged.truncate(limit);
+    merged
+}
+
+fn sort_by_score(results: &mut [SearchResult]) {
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
+    });
+}
+
+/// Index-backed search: score postings instead of scanning every entity.
+fn search_indexed(
+    graph: &RPGraph,
+    index: &SearchIndex,
+    params: &SearchParams,
+    file_matcher: Option<&globset::GlobMatcher>,
+) -> Vec<SearchResult> {
+    let scoped = params.scope.map(|scope| index.scope_entities(scope));
+    let is_candidate = |id: &str| {
+        scoped.as_ref().is_none_or(|ids| ids.contains(id))
+            && graph
+                .entities
+                .get(id)
+                .is_some_and(|entity| passes_filters(entity, params, file_matcher))
+    };
+    let terms = index.query_terms(params.query);
+    let search_limit = expanded_limit(params);
+
+    let ranked = |fields: &[(Field, f64)], with_features: bool, limit: usize| {
+        let mut results: Vec<SearchResult> = index
+            .score(&terms, fields)
+            .into_iter()
+            .filter(|(id, _)| is_candidate(id))
+            .filter_map(|(id, score)| {
+                let entity = graph.entities.get(&id)?;
+                Some(SearchResult {
+                    entity_id: id,
+                    entity_name: entity.name.clone(),
+                    file: entity.file.display().to_string(),
+                    line_start: entity.line_start,
+                    score,
+                    matched_features: Vec::new(),
+                    lifted: !entity.semantic_features.is_empty(),
+                })
+            })
+            .collect();
+        sort_by_score(&mut results);
+        results.truncate(limit);
+        if with_features {
+            for result in &mut results {
+                result.matched_features = matched_features(graph, &result.entity_id, &terms);
+            }
+        }
+        results
+    };
+
+    let results = match params.mode {
+        SearchMode::Features => maybe_hybrid_rerank(
+            graph,
+            &is_candidate,
+            ranked(FEATURE_FIELDS, true, search_limit),
+            params.embedding_scores,
+            search_limit,
+        ),
+        SearchMode::Snippets => ranked(SNIPPET_FIELDS, false, search_limit),
+        SearchMode::Auto => {
+            let merged = merge_auto(
+                ranked(FEATURE_FIELDS, true, search_limit * 2),
+                ranked(SNIPPET_FIELDS, false, search_limit * 2),
+                search_limit,
+            );
             maybe_hybrid_rerank(
                 graph,
-                &candidate_ids,
+                &is_candidate,
                 merged,
                 params.embedding_scores,
                 search_limit,
@@ -223,8 +407,70 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
         }
     };
 
-    // Apply diff-aware proximity boost if provided, then truncate to requested limit
-    results = apply_diff_boost(results, params.diff_context);
+    finish(results, params)
+}
+
+/// Features of an entity containing at least one of the query terms.
+fn matched_features(graph: &RPGraph, id: &str, terms: &[(String, f64)]) -> Vec<String> {
+    let Some(entity) = graph.entities.get(id) else {
+        return Vec::new();
+    };
+    entity
+        .semantic_features
+        .iter()
+        .filter(|feature| {
+            tokenize(feature)
+                .iter()
+                .any(|token| terms.iter().any(|(term, _)| term == token))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Add the external packages (see [`RPGraph::externals`]) whose name matches
+/// the query to entity `results`, keeping the best `params.limit`. Packages
+/// have no file, lines or metadata, so nothing is added when the search is
+/// narrowed by any of those filters.
+pub fn with_external_packages(
+    graph: &RPGraph,
+    params: &SearchParams,
+    mut results: Vec<SearchResult>,
+) -> Vec<SearchResult> {
+    let narrowed = params.scope.is_some()
+        || params.line_nums.is_some()
+        || params.file_pattern.is_some()
+        || params.entity_type_filter.is_some()
+        || params.metadata_filter.is_some_and(|f| !f.is_empty());
+    if narrowed || graph.externals.is_empty() {
+        return results;
+    }
+    let query_lower = params.query.to_lowercase();
+    let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
+    let empty_idf = HashMap::new();
+    for ext in graph.externals.values() {
+        // Weighted like entity names in snippet search
+        let score = multi_signal_score(&ext.name, &query_lower, &query_terms, &empty_idf) * 2.0;
+        if score > 0.05 {
+            results.push(SearchResult {
+                entity_id: ext.id.clone(),
+                entity_name: ext.name.clone(),
+                file: ext
+                    .manifests
+                    .first()
+                    .map(|m| m.display().to_string())
+                    .unwrap_or_default(),
+                line_start: 0,
+                score,
+                matched_features: vec![format!("external {} package", ext.ecosystem.as_str())],
+                lifted: true,
+            });
+        }
+    }
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
     results.truncate(params.limit);
     results
 }
@@ -463,10 +709,10 @@ fn rank_normalize(scores: &HashMap<String, f64>) -> HashMap<String, f64> {
 ///
 /// Entities that only appear in semantic scores (not in lexical results) are included
 /// as stub results — enabling true semantic discovery of entities the keyword search missed.
-/// Only entities in `candidate_ids` are considered (preserves user filters like scope/file/line).
+/// Only entities passing `is_candidate` are considered (preserves user filters like scope/file/line).
 fn maybe_hybrid_rerank(
     graph: &RPGraph,
-    candidate_ids: &HashSet<&String>,
+    is_candidate: &dyn Fn(&str) -> bool,
     lexical_results: Vec<SearchResult>,
     embedding_scores: Option<&HashMap<String, f64>>,
     limit: usize,
@@ -481,7 +727,7 @@ fn maybe_hybrid_rerank(
     // Filter semantic scores to only include entities that passed user filters
     let filtered_sem: HashMap<String, f64> = sem_scores
         .iter()
-        .filter(|(id, _)| candidate_ids.contains(id))
+        .filter(|(id, _)| is_candidate(id))
         .map(|(id, &score)| (id.clone(), score))
         .collect();
 
@@ -0,0 +1,648 @@
+//! Persistent inverted index for lexical search.
+//!
+//! Maps tokens from entity features, names, file paths and hierarchy paths to
+//! per-field term frequencies, scored with BM25F. Identifiers are split into
+//! their camelCase / snake_case words so `parseConfig` matches "parse config".
+//! The index also caches the entity set of every hierarchy scope.
+//!
+//! Stored as `.rpg/search_index.json` and synced incrementally: per-entity
+//! fingerprints decide which entities are re-tokenized after a graph change.
+
+use anyhow::{Context, Result};
+use rpg_core::graph::{HierarchyNode, RPGraph};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+const INDEX_FILE: &str = "search_index.json";
+const FORMAT_VERSION: u32 = 1;
+const FIELD_COUNT: usize = 4;
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 length normalization.
+const B: f64 = 0.75;
+/// Minimum normalized Levenshtein similarity for a fuzzy term expansion.
+const FUZZY_THRESHOLD: f64 = 0.8;
+/// Maximum vocabulary terms a misspelled query token expands to.
+const FUZZY_EXPANSIONS: usize = 3;
+
+/// An indexed text field of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Lifted semantic features.
+    Features,
+    /// Entity name.
+    Name,
+    /// Source file path.
+    Path,
+    /// Semantic hierarchy path.
+    Hierarchy,
+}
+
+impl Field {
+    fn slot(self) -> usize {
+        match self {
+            Self::Features => 0,
+            Self::Name => 1,
+            Self::Path => 2,
+            Self::Hierarchy => 3,
+        }
+    }
+}
+
+/// Field weights for features search.
+pub const FEATURE_FIELDS: &[(Field, f64)] = &[(Field::Features, 1.0)];
+
+/// Field weights for snippet search (name, then file path, then hierarchy path).
+pub const SNIPPET_FIELDS: &[(Field, f64)] = &[
+    (Field::Name, 2.0),
+    (Field::Path, 1.0),
+    (Field::Hierarchy, 0.5),
+];
+
+/// Per-entity bookkeeping for incremental sync.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct IndexedEntity {
+    fingerprint: String,
+    /// Token count per field.
+    lengths: [u32; FIELD_COUNT],
+    /// Distinct tokens, so the entity's postings can be removed.
+    terms: Vec<String>,
+}
+
+/// Inverted index over the graph's entities.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    /// The graph's updated_at timestamp this index corresponds to.
+    graph_updated_at: String,
+    entities: BTreeMap<String, IndexedEntity>,
+    /// Token → entity_id → term frequency per field.
+    postings: BTreeMap<String, BTreeMap<String, [u32; FIELD_COUNT]>>,
+    /// Summed field lengths across all entities (for average lengths).
+    total_lengths: [u64; FIELD_COUNT],
+    /// Hierarchy scope path ("Area/Category/Sub") → entity IDs in its subtree.
+    scopes: BTreeMap<String, Vec<String>>,
+}
+
+/// Statistics from an incremental index sync.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    /// Entities whose indexed text is unchanged.
+    pub kept: usize,
+    /// Entities re-tokenized because their text changed.
+    pub changed: usize,
+    /// Entities indexed for the first time.
+    pub added: usize,
+    /// Entities removed because they left the graph.
+    pub pruned: usize,
+}
+
+impl SyncStats {
+    /// Whether the sync changed any postings.
+    pub fn is_noop(&self) -> bool {
+        self.changed == 0 && self.added == 0 && self.pruned == 0
+    }
+}
+
+impl SearchIndex {
+    /// Build a fresh index for `graph` (not persisted).
+    pub fn build(graph: &RPGraph) -> Self {
+        let mut index = Self::default();
+        index.sync(graph);
+        index
+    }
+
+    /// Load the index from `.rpg/`. Returns `Ok(None)` when no index exists
+    /// or it was written by an incompatible format version.
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = rpg_core::storage::rpg_dir(project_root).join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path).context("failed to read search index")?;
+        let index: Self = serde_json::from_str(&json).context("failed to parse search index")?;
+        Ok((index.version == FORMAT_VERSION).then_some(index))
+    }
+
+    /// Load the on-disk index and bring it in sync with `graph`. The file is
+    /// rewritten only when it is missing, corrupt or its postings were stale,
+    /// so read-only commands like `search` leave it alone.
+    pub fn open(project_root: &Path, graph: &RPGraph) -> Result<(Self, SyncStats)> {
+        let (mut index, loaded) = match Self::load(project_root) {
+            Ok(Some(index)) => (index, true),
+            Ok(None) => (Self::default(), false),
+            Err(e) => {
+                eprintln!("rpg: corrupt search index, rebuilding: {e}");
+                (Self::default(), false)
+            }
+        };
+        let stats = index.sync(graph);
+        if !loaded || !stats.is_noop() {
+            index.save(project_root)?;
+        }
+        Ok((index, stats))
+    }
+
+    /// Save the index to `.rpg/search_index.json` atomically, holding the
+    /// graph lock so concurrent writers don't interleave.
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let dir = rpg_core::storage::rpg_dir(project_root);
+        std::fs::create_dir_all(&dir)?;
+        let json = serde_json::to_string(self)?;
+        let _lock = rpg_core::storage::acquire_lock(project_root, true)?;
+        rpg_core::storage::write_atomic(&dir.join(INDEX_FILE), json.as_bytes())
+    }
+
+    /// Whether the index was last synced against this revision of `graph`.
+    pub fn is_current(&self, graph: &RPGraph) -> bool {
+        self.version == FORMAT_VERSION && self.graph_updated_at == graph.updated_at.to_rfc3339()
+    }
+
+    /// Incrementally sync the index with the graph.
+    ///
+    /// Compares per-entity fingerprints of the indexed text:
+    /// - Unchanged fingerprints → keep existing postings
+    /// - Changed fingerprints → re-tokenize
+    /// - New entities → tokenize fresh
+    /// - Deleted entities → prune postings
+    ///
+    /// The scope cache is always rebuilt, since hierarchy moves do not
+    /// necessarily change any entity's indexed text.
+    pub fn sync(&mut self, graph: &RPGraph) -> SyncStats {
+        let mut stats = SyncStats::default();
+        self.version = FORMAT_VERSION;
+
+        let stale: Vec<String> = self
+            .entities
+            .keys()
+            .filter(|id| !graph.entities.contains_key(*id))
+            .cloned()
+            .collect();
+        for id in stale {
+            self.remove(&id);
+            stats.pruned += 1;
+        }
+
+        for (id, entity) in &graph.entities {
+            let fields = [
+                entity.semantic_features.join("\n"),
+                entity.name.clone(),
+                entity.file.display().to_string(),
+                entity.hierarchy_path.clone(),
+            ];
+            let fingerprint = compute_fingerprint(&fields);
+            match self.entities.get(id) {
+                Some(existing) if existing.fingerprint == fingerprint => {
+                    stats.kept += 1;
+                    continue;
+                }
+                Some(_) => {
+                    self.remove(id);
+                    stats.changed += 1;
+                }
+                None => stats.added += 1,
+            }
+            self.insert(id, fingerprint, &fields);
+        }
+
+        self.scopes.clear();
+        for (name, area) in &graph.hierarchy {
+            collect_scopes(name, area, &mut self.scopes);
+        }
+        self.graph_updated_at = graph.updated_at.to_rfc3339();
+        stats
+    }
+
+    fn insert(&mut self, id: &str, fingerprint: String, fields: &[String; FIELD_COUNT]) {
+        let mut lengths = [0u32; FIELD_COUNT];
+        let mut frequencies: BTreeMap<String, [u32; FIELD_COUNT]> = BTreeMap::new();
+        for (slot, text) in fields.iter().enumerate() {
+            for token in tokenize(text) {
+                lengths[slot] += 1;
+                frequencies.entry(token).or_default()[slot] += 1;
+            }
+        }
+        for (slot, len) in lengths.iter().enumerate() {
+            self.total_lengths[slot] += u64::from(*len);
+        }
+        let terms: Vec<String> = frequencies.keys().cloned().collect();
+        for (token, tf) in frequencies {
+            self.postings
+                .entry(token)
+                .or_default()
+                .insert(id.to_string(), tf);
+        }
+        self.entities.insert(
+            id.to_string(),
+            IndexedEntity {
+                fingerprint,
+                lengths,
+                terms,
+            },
+        );
+    }
+
+    fn remove(&mut self, id: &str) {
+        let Some(indexed) = self.entities.remove(id) else {
+            return;
+        };
+        for (slot, len) in indexed.lengths.iter().enumerate() {
+            self.total_lengths[slot] = self.total_lengths[slot].saturating_sub(u64::from(*len));
+        }
+        for term in &indexed.terms {
+            if let Some(postings) = self.postings.get_mut(term) {
+                postings.remove(id);
+                if postings.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    /// Number of indexed entities.
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Number of distinct indexed terms.
+    pub fn term_count(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Tokenize a query into weighted index terms.
+    ///
+    /// Tokens present in the vocabulary weigh 1.0. A token with no postings
+    /// (e.g. a typo) expands to its closest vocabulary terms, weighted by
+    /// their normalized edit-distance similarity.
+    pub fn query_terms(&self, query: &str) -> Vec<(String, f64)> {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut terms = Vec::new();
+        for token in tokenize(query) {
+            if !seen.insert(token.clone()) {
+                continue;
+            }
+            if self.postings.contains_key(&token) {
+                terms.push((token, 1.0));
+                continue;
+            }
+            if token.chars().count() < 4 {
+                continue;
+            }
+            let mut near: Vec<(&String, f64)> = self
+                .postings
+                .keys()
+                .map(|term| (term, strsim::normalized_levenshtein(&token, term)))
+                .filter(|(_, sim)| *sim >= FUZZY_THRESHOLD)
+                .collect();
+            near.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            for (term, sim) in near.into_iter().take(FUZZY_EXPANSIONS) {
+                if seen.insert(term.clone()) {
+                    terms.push((term.clone(), sim));
+                }
+            }
+        }
+        terms
+    }
+
+    /// Score every entity matching `terms` with BM25F over the weighted `fields`.
+    /// Returns entity_id → score (unbounded, higher is better).
+    pub fn score(&self, terms: &[(String, f64)], fields: &[(Field, f64)]) -> HashMap<String, f64> {
+        let n = self.entities.len() as f64;
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        if n == 0.0 {
+            return scores;
+        }
+        let avg_lengths: Vec<f64> = fields
+            .iter()
+            .map(|(field, _)| (self.total_lengths[field.slot()] as f64 / n).max(1.0))
+            .collect();
+
+        for (term, term_weight) in terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let matching: Vec<(&String, f64)> = postings
+                .iter()
+                .filter_map(|(id, tf)| {
+                    let lengths = &self.entities.get(id)?.lengths;
+                    let weighted: f64 = fields
+                        .iter()
+                        .zip(&avg_lengths)
+                        .map(|((field, weight), avg)| {
+                            let slot = field.slot();
+                            let norm = 1.0 - B + B * f64::from(lengths[slot]) / avg;
+                            weight * f64::from(tf[slot]) / norm
+                        })
+                        .sum();
+                    (weighted > 0.0).then_some((id, weighted))
+                })
+                .collect();
+            if matching.is_empty() {
+                continue;
+            }
+            let df = matching.len() as f64;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            for (id, tf) in matching {
+                *scores.entry(id.clone()).or_insert(0.0) +=
+                    term_weight * idf * tf * (K1 + 1.0) / (tf + K1);
+            }
+        }
+        scores
+    }
+
+    /// Entity IDs under a comma-separated list of hierarchy scopes, from the
+    /// cached scope sets. Unknown scopes contribute nothing.
+    pub fn scope_entities(&self, scope: &str) -> HashSet<&str> {
+        scope
+            .split(',')
+            .filter_map(|s| self.scopes.get(s.trim()))
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Record the entity set of `node` and of every node below it.
+fn collect_scopes(path: &str, node: &HierarchyNode, scopes: &mut BTreeMap<String, Vec<String>>) {
+    for (name, child) in &node.children {
+        collect_scopes(&format!("{path}/{name}"), child, scopes);
+    }
+    scopes.insert(path.to_string(), node.all_entity_ids());
+}
+
+/// Split text into lowercase search tokens.
+///
+/// Words are separated by any non-alphanumeric character. A word with
+/// camelCase humps yields both its parts and the whole word, so
+/// `parseConfig` indexes as `parse`, `config` and `parseconfig`.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        let parts = split_camel_case(word);
+        if parts.len() > 1 {
+            tokens.extend(parts);
+        }
+        tokens.push(word.to_lowercase());
+    }
+    tokens
+}
+
+/// Split a camelCase / PascalCase word at its humps (`HTTPServer` → `http`, `server`).
+fn split_camel_case(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let hump = chars[i].is_uppercase()
+            && (chars[i - 1].is_lowercase()
+                || chars[i - 1].is_ascii_digit()
+                || (chars[i - 1].is_uppercase()
+                    && chars.get(i + 1).is_some_and(|c| c.is_lowercase())));
+        if hump {
+            parts.push(chars[start..i].iter().collect::<String>().to_lowercase());
+            start = i;
+        }
+    }
+    parts.push(chars[start..].iter().collect::<String>().to_lowercase());
+    parts
+}
+
+/// Compute a deterministic fingerprint for an entity's indexed text.
+fn compute_fingerprint(fields: &[String; FIELD_COUNT]) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    fields.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpg_core::graph::{Entity, EntityDeps, EntityKind, EntityMetadata};
+    use std::path::PathBuf;
+
+    fn entity(id: &str, name: &str, file: &str, features: &[&str]) -> Entity {
+        Entity {
+            id: id.to_string(),
+            kind: EntityKind::Function,
+            name: name.to_string(),
+            file: PathBuf::from(file),
+            line_start: 1,
+            line_end: 10,
+            parent_class: None,
+            semantic_features: features.iter().map(|s| s.to_string()).collect(),
+            feature_source: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
+        }
+    }
+
+    fn graph() -> RPGraph {
+        let mut graph = RPGraph::new("rust");
+        for e in [
+            entity(
+                "src/auth.rs:validateToken",
+                "validateToken",
+                "src/auth.rs",
+                &["validate jwt token", "check token expiry"],
+            ),
+            entity(
+                "src/db.rs:open_pool",
+                "open_pool",
+                "src/db.rs",
+                &["open database connection pool"],
+            ),
+            entity(
+                "src/db.rs:run_query",
+                "run_query",
+                "src/db.rs",
+                &["execute sql query", "return database rows"],
+            ),
+        ] {
+            graph.insert_entity(e);
+        }
+        graph
+    }
+
+    fn ranked(index: &SearchIndex, query: &str, fields: &[(Field, f64)]) -> Vec<String> {
+        let mut scores: Vec<(String, f64)> = index
+            .score(&index.query_terms(query), fields)
+            .into_iter()
+            .collect();
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        scores.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn test_tokenize_splits_identifiers() {
+        assert_eq!(
+            tokenize("parseConfig"),
+            vec!["parse", "config", "parseconfig"]
+        );
+        assert_eq!(tokenize("open_pool"), vec!["open", "pool"]);
+        assert_eq!(tokenize("HTTPServer"), vec!["http", "server", "httpserver"]);
+        assert_eq!(
+            tokenize("src/db.rs: Run  query"),
+            vec!["src", "db", "rs", "run", "query"]
+        );
+    }
+
+    #[test]
+    fn test_features_ranking_prefers_rare_terms() {
+        let index = SearchIndex::build(&graph());
+        let results = ranked(&index, "database query", FEATURE_FIELDS);
+        assert_eq!(results[0], "src/db.rs:run_query");
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_snippet_fields_match_identifier_words() {
+        let index = SearchIndex::build(&graph());
+        assert_eq!(
+            ranked(&index, "validate token", SNIPPET_FIELDS),
+            vec!["src/auth.rs:validateToken"]
+        );
+        assert_eq!(
+            ranked(&index, "db", SNIPPET_FIELDS).len(),
+            2,
+            "file path tokens are indexed"
+        );
+    }
+
+    #[test]
+    fn test_query_terms_expand_typos() {
+        let index = SearchIndex::build(&graph());
+        let terms = index.query_terms("databse");
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].0, "database");
+        assert!(terms[0].1 < 1.0);
+        assert!(index.query_terms("zzzzzzzz").is_empty());
+    }
+
+    #[test]
+    fn test_sync_keeps_changes_adds_and_prunes() {
+        let mut graph = graph();
+        let mut index = SearchIndex::build(&graph);
+        assert_eq!(index.entity_count(), 3);
+
+        graph
+            .entities
+            .get_mut("src/db.rs:open_pool")
+            .unwrap()
+            .semantic_features = vec!["create connection pool".to_string()];
+        graph.remove_entity("src/db.rs:run_query");
+        graph.insert_entity(entity("src/db.rs:close", "close", "src/db.rs", &[]));
+
+        let stats = index.sync(&graph);
+        assert_eq!(
+            stats,
+            SyncStats {
+                kept: 1,
+                changed: 1,
+                added: 1,
+                pruned: 1,
+            }
+        );
+        assert!(index.query_terms("sql").is_empty(), "pruned terms leave");
+        assert!(ranked(&index, "database", FEATURE_FIELDS).is_empty());
+        assert_eq!(
+            ranked(&index, "create", FEATURE_FIELDS),
+            vec!["src/db.rs:open_pool"]
+        );
+        assert!(index.sync(&graph).is_noop());
+    }
+
+    #[test]
+    fn test_incremental_sync_matches_fresh_build() {
+        let mut graph = graph();
+        let mut index = SearchIndex::build(&graph);
+        graph.remove_entity("src/auth.rs:validateToken");
+        index.sync(&graph);
+        let fresh = SearchIndex::build(&graph);
+        assert_eq!(index.postings, fresh.postings);
+        assert_eq!(index.total_lengths, fresh.total_lengths);
+    }
+
+    #[test]
+    fn test_scope_cache() {
+        let mut graph = graph();
+        let mut area = HierarchyNode::new("Data");
+        let mut sub = HierarchyNode::new("Pool");
+        sub.entities.push("src/db.rs:open_pool".to_string());
+        area.children.insert("Pool".to_string(), sub);
+        area.entities.push("src/db.rs:run_query".to_string());
+        graph.hierarchy.insert("Data".to_string(), area);
+
+        let index = SearchIndex::build(&graph);
+        assert_eq!(index.scope_entities("Data").len(), 2);
+        assert_eq!(
+            index.scope_entities("Data/Pool"),
+            HashSet::from(["src/db.rs:open_pool"])
+        );
+        assert_eq!(index.scope_entities("Data/Pool, Missing").len(), 1);
+        assert!(index.scope_entities("Missing").is_empty());
+    }
+
+    #[test]
+    fn test_open_persists_and_reloads() {
+        let tmp = tempfile::tempdir().unwrap();
+        let graph = graph();
+        let (index, stats) = SearchIndex::open(tmp.path(), &graph).unwrap();
+        assert_eq!(stats.added, 3);
+        assert!(tmp.path().join(".rpg/search_index.json").exists());
+
+        let loaded = SearchIndex::load(tmp.path()).unwrap().unwrap();
+        assert!(loaded.is_current(&graph));
+        assert_eq!(loaded.postings, index.postings);
+
+        let (_, stats) = SearchIndex::open(tmp.path(), &graph).unwrap();
+        assert!(stats.is_noop());
+        assert_eq!(stats.kept, 3);
+    }
+
+    #[test]
+    fn test_open_skips_rewrite_when_postings_are_fresh() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = graph();
+        SearchIndex::open(tmp.path(), &graph).unwrap();
+        let path = tmp.path().join(".rpg/search_index.json");
+        let stamp = std::time::SystemTime::UNIX_EPOCH;
+        let mtime = || std::fs::metadata(&path).unwrap().modified().unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(stamp)
+            .unwrap();
+
+        // A new graph timestamp alone (e.g. sharded storage reading shard
+        // mtimes) changes no postings, so the file is left alone
+        graph.refresh_metadata();
+        let (_, stats) = SearchIndex::open(tmp.path(), &graph).unwrap();
+        assert!(stats.is_noop());
+        assert_eq!(mtime(), stamp);
+
+        graph.entities.get_mut("src/db.rs:open_pool").unwrap().name = "connect".into();
+        let (_, stats) = SearchIndex::open(tmp.path(), &graph).unwrap();
+        assert_eq!(stats.changed, 1);
+        assert_ne!(mtime(), stamp);
+    }
+
+    #[test]
+    fn test_open_rebuilds_corrupt_index() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(tmp.path().join(".rpg/search_index.json"), "not json").unwrap();
+        let (index, stats) = SearchIndex::open(tmp.path(), &graph()).unwrap();
+        assert_eq!(stats.added, 3);
+        assert_eq!(index.entity_count(), 3);
+        assert!(SearchIndex::load(tmp.path()).unwrap().is_some());
+    }
+}
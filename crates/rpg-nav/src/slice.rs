@@ -105,6 +105,7 @@ pub fn slice_between(
             source,
             target,
             kind,
+            confidence: None,
         })
         .collect();
 
@@ -206,7 +207,7 @@ fn find_shortest_path_with_edges(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::{Entity, EntityDeps, EntityKind};
+    use rpg_core::graph::{Entity, EntityDeps, EntityKind, EntityMetadata};
     use std::path::PathBuf;
 
     fn make_test_entity(id: &str, name: &str) -> Entity {
@@ -223,6 +224,8 @@ mod tests {
             hierarchy_path: "Test".to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
         }
     }
 
@@ -236,6 +239,7 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = slice_between(&graph, &["A".to_string(), "B".to_string()], 5, false);
@@ -260,16 +264,19 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "A".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = slice_between(&graph, &["A".to_string(), "C".to_string()], 5, false);
@@ -295,11 +302,13 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "C".to_string(),
             target: "D".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = slice_between(&graph, &["A".to_string(), "C".to_string()], 5, false);
@@ -326,16 +335,19 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "C".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
         graph.edges.push(DependencyEdge {
             source: "B".to_string(),
             target: "D".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = slice_between(
@@ -361,6 +373,7 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = slice_between(&graph, &["A".to_string(), "B".to_string()], 5, true);
@@ -383,6 +396,7 @@ mod tests {
             source: "A".to_string(),
             target: "B".to_string(),
             kind: EdgeKind::Invokes,
+            confidence: None,
         });
 
         let result = slice_between(&graph, &["A".to_string(), "B".to_string()], 5, false);
@@ -0,0 +1,491 @@
+//! Test selection: the minimal set of tests to run for a change, from `Tests` edges.
+//!
+//! `Tests` edges link a test only to the code it uses directly. A test also
+//! exercises everything those callees reach through calls, renders and
+//! dispatches, and the state any of them reads or writes; that reach is
+//! followed backward from the changed code here, however many hops it takes.
+
+use rpg_core::graph::{EdgeKind, Entity, EntityKind, RPGraph};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+/// A test selected for a change.
+#[derive(Debug, Clone)]
+pub struct SelectedTest {
+    pub entity_id: String,
+    pub name: String,
+    pub file: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    /// Changed entities this test exercises (itself, if the test was changed).
+    pub covers: Vec<String>,
+}
+
+/// The result of selecting tests for a set of changed entities.
+#[derive(Debug, Clone, Default)]
+pub struct TestSelection {
+    /// Tests to run, ordered by file and line.
+    pub tests: Vec<SelectedTest>,
+    /// Distinct files containing the selected tests.
+    pub files: Vec<String>,
+    /// Changed non-test entities that no test exercises.
+    pub uncovered: Vec<String>,
+    /// Requested IDs that are not entities in the graph.
+    pub unknown: Vec<String>,
+}
+
+/// Entity IDs of every entity defined in the given files (paths relative to the
+/// project root, as stored in the graph).
+pub fn entities_in_files(graph: &RPGraph, files: &[String]) -> Vec<String> {
+    files
+        .iter()
+        .filter_map(|f| graph.file_index.get(Path::new(f)))
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+/// Test entity IDs reaching any of the targets, sorted.
+pub fn tests_covering(graph: &RPGraph, target_ids: &HashSet<String>) -> Vec<String> {
+    let reach = Reach::new(graph);
+    let tests: BTreeSet<&str> = target_ids
+        .iter()
+        .flat_map(|target| reach.tests_reaching(target))
+        .collect();
+    tests.into_iter().map(str::to_string).collect()
+}
+
+/// Select the tests to run after changing `changed` entities.
+///
+/// Changed tests are selected themselves. Any other changed entity selects the
+/// tests reaching it (see the module docs); a changed class also selects the
+/// tests of its members. A test nested inside another selected test (an `it` inside a
+/// selected `describe`, a method of a selected test class) is dropped, since
+/// running the enclosing one runs it too.
+pub fn select_tests(graph: &RPGraph, changed: &[String]) -> TestSelection {
+    let mut selection = TestSelection::default();
+
+    // Map each changed entity (and the members of changed classes) back to the
+    // requested ID it stands for
+    let mut targets: BTreeMap<&str, &str> = BTreeMap::new();
+    for id in changed {
+        let Some(entity) = graph.entities.get(id) else {
+            if !selection.unknown.contains(id) {
+                selection.unknown.push(id.clone());
+            }
+            continue;
+        };
+        targets.insert(entity.id.as_str(), id.as_str());
+        if entity.kind == EntityKind::Class {
+            for member in members(graph, entity) {
+                targets.entry(member.id.as_str()).or_insert(id.as_str());
+            }
+        }
+    }
+
+    let mut covers: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (&target, &requested) in &targets {
+        if graph
+            .entities
+            .get(target)
+            .is_some_and(|e| e.kind == EntityKind::Test)
+        {
+            covers.entry(target).or_default().insert(requested);
+        }
+    }
+    let reach = Reach::new(graph);
+    for (&target, &requested) in &targets {
+        for test in reach.tests_reaching(target) {
+            covers.entry(test).or_default().insert(requested);
+        }
+    }
+
+    let mut selected: Vec<&Entity> = covers
+        .keys()
+        .filter_map(|id| graph.entities.get(*id))
+        .collect();
+    selected.sort_by(|a, b| {
+        (&a.file, a.line_start, a.line_end).cmp(&(&b.file, b.line_start, b.line_end))
+    });
+    let enclosed = |inner: &Entity| {
+        selected.iter().any(|outer| {
+            outer.id != inner.id
+                && outer.file == inner.file
+                && outer.line_start <= inner.line_start
+                && inner.line_end <= outer.line_end
+                && (outer.line_start, outer.line_end) != (inner.line_start, inner.line_end)
+        })
+    };
+
+    let mut covered: HashSet<&str> = HashSet::new();
+    for test in &selected {
+        let test_covers = &covers[test.id.as_str()];
+        covered.extend(test_covers.iter().copied());
+        if enclosed(test) {
+            continue;
+        }
+        selection.tests.push(SelectedTest {
+            entity_id: test.id.clone(),
+            name: test.name.clone(),
+            file: test.file.display().to_string(),
+            line_start: test.line_start,
+            line_end: test.line_end,
+            covers: test_covers.iter().map(|s| (*s).to_string()).collect(),
+        });
+    }
+
+    let files: BTreeSet<&String> = selection.tests.iter().map(|t| &t.file).collect();
+    selection.files = files.into_iter().cloned().collect();
+
+    let mut uncovered: BTreeSet<String> = BTreeSet::new();
+    for id in changed {
+        if graph.entities.contains_key(id) && !covered.contains(id.as_str()) {
+            uncovered.insert(id.clone());
+        }
+    }
+    selection.uncovered = uncovered.into_iter().collect();
+
+    selection
+}
+
+/// Reverse links from code to the tests and code that use it.
+struct Reach<'a> {
+    graph: &'a RPGraph,
+    /// Tests with a `Tests` edge to each entity.
+    tested_by: HashMap<&'a str, Vec<&'a str>>,
+    /// Entities calling, rendering or dispatching to each entity.
+    callers: HashMap<&'a str, Vec<&'a str>>,
+    /// Entities reading or writing each piece of state.
+    accessors: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> Reach<'a> {
+    fn new(graph: &'a RPGraph) -> Self {
+        let mut reach = Self {
+            graph,
+            tested_by: HashMap::new(),
+            callers: HashMap::new(),
+            accessors: HashMap::new(),
+        };
+        for edge in &graph.edges {
+            let map = match edge.kind {
+                EdgeKind::Tests => &mut reach.tested_by,
+                EdgeKind::Invokes | EdgeKind::Renders | EdgeKind::Dispatches => &mut reach.callers,
+                EdgeKind::ReadsState | EdgeKind::WritesState => &mut reach.accessors,
+                _ => continue,
+            };
+            map.entry(edge.target.as_str())
+                .or_default()
+                .push(edge.source.as_str());
+        }
+        reach
+    }
+
+    /// Tests reaching `target`: those linked to it, or to code that reaches it
+    /// through calls. State is reached only as the last hop, and paths through
+    /// other tests do not count.
+    fn tests_reaching(&self, target: &'a str) -> BTreeSet<&'a str> {
+        let mut tests = BTreeSet::new();
+        if self.is_test(target) {
+            return tests;
+        }
+        let mut seen: HashSet<&str> = HashSet::from([target]);
+        let mut queue = VecDeque::from([target]);
+        for &accessor in self.accessors.get(target).into_iter().flatten() {
+            if self.is_code(accessor) && seen.insert(accessor) {
+                queue.push_back(accessor);
+            }
+        }
+        while let Some(current) = queue.pop_front() {
+            tests.extend(self.tested_by.get(current).into_iter().flatten().copied());
+            for &caller in self.callers.get(current).into_iter().flatten() {
+                if self.is_code(caller) && seen.insert(caller) {
+                    queue.push_back(caller);
+                }
+            }
+        }
+        tests
+    }
+
+    fn is_test(&self, id: &str) -> bool {
+        self.graph
+            .entities
+            .get(id)
+            .is_some_and(|e| e.kind == EntityKind::Test)
+    }
+
+    /// Whether `id` is an entity other than a test, which the reach may pass.
+    fn is_code(&self, id: &str) -> bool {
+        self.graph
+            .entities
+            .get(id)
+            .is_some_and(|e| e.kind != EntityKind::Test)
+    }
+}
+
+/// Methods and other members declared inside `class`.
+fn members<'a>(graph: &'a RPGraph, class: &'a Entity) -> impl Iterator<Item = &'a Entity> {
+    graph
+        .file_index
+        .get(&class.file)
+        .into_iter()
+        .flatten()
+        .filter_map(|id| graph.entities.get(id))
+        .filter(|e| e.parent_class.as_deref() == Some(class.name.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpg_core::graph::{DependencyEdge, EntityDeps, EntityMetadata};
+    use std::path::PathBuf;
+
+    fn entity(id: &str, kind: EntityKind, lines: (usize, usize), parent: Option<&str>) -> Entity {
+        let (file, name) = id.split_once(':').unwrap();
+        Entity {
+            id: id.to_string(),
+            name: name.rsplit("::").next().unwrap().to_string(),
+            kind,
+            file: PathBuf::from(file),
+            line_start: lines.0,
+            line_end: lines.1,
+            parent_class: parent.map(str::to_string),
+            semantic_features: Vec::new(),
+            feature_source: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
+        }
+    }
+
+    fn tests_edge(test: &str, target: &str) -> DependencyEdge {
+        DependencyEdge {
+            source: test.to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Tests,
+            confidence: None,
+        }
+    }
+
+    /// A `Session` class, a suite whose setup and nested tests reach it, and a
+    /// test of an unrelated helper.
+    fn build_graph() -> RPGraph {
+        let mut graph = RPGraph::new("typescript");
+        for e in [
+            entity("src/session.ts:Session", EntityKind::Class, (1, 30), None),
+            entity(
+                "src/session.ts:Session::refresh",
+                EntityKind::Method,
+                (5, 12),
+                Some("Session"),
+            ),
+            entity(
+                "src/session.ts:expire",
+                EntityKind::Function,
+                (32, 40),
+                None,
+            ),
+            entity("src/util.ts:slugify", EntityKind::Function, (1, 5), None),
+            entity("src/util.ts:unused", EntityKind::Function, (7, 9), None),
+            entity(
+                "test/session.test.ts:Session",
+                EntityKind::Test,
+                (3, 40),
+                None,
+            ),
+            entity(
+                "test/session.test.ts:refreshes",
+                EntityKind::Test,
+                (8, 14),
+                None,
+            ),
+            entity(
+                "test/session.test.ts:expires",
+                EntityKind::Test,
+                (16, 22),
+                None,
+            ),
+            entity(
+                "test/util.test.ts:slugifies",
+                EntityKind::Test,
+                (1, 4),
+                None,
+            ),
+        ] {
+            graph.insert_entity(e);
+        }
+        graph.edges = vec![
+            tests_edge(
+                "test/session.test.ts:refreshes",
+                "src/session.ts:Session::refresh",
+            ),
+            tests_edge("test/session.test.ts:expires", "src/session.ts:expire"),
+            tests_edge("test/session.test.ts:Session", "src/session.ts:expire"),
+            tests_edge("test/util.test.ts:slugifies", "src/util.ts:slugify"),
+        ];
+        graph.refresh_metadata();
+        graph
+    }
+
+    fn edge(source: &str, target: &str, kind: EdgeKind) -> DependencyEdge {
+        DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind,
+            confidence: None,
+        }
+    }
+
+    /// A test calling into a chain of `depth` functions, the last of which
+    /// writes a global.
+    fn chain_graph(depth: usize) -> RPGraph {
+        let mut graph = RPGraph::new("rust");
+        graph.insert_entity(entity(
+            "tests/chain.rs:deep",
+            EntityKind::Test,
+            (1, 5),
+            None,
+        ));
+        graph.insert_entity(entity(
+            "src/chain.rs:STATE",
+            EntityKind::Global,
+            (1, 1),
+            None,
+        ));
+        let ids: Vec<String> = (0..depth).map(|i| format!("src/chain.rs:f{i}")).collect();
+        for (i, id) in ids.iter().enumerate() {
+            graph.insert_entity(entity(id, EntityKind::Function, (i + 2, i + 2), None));
+        }
+        graph.edges.push(tests_edge("tests/chain.rs:deep", &ids[0]));
+        graph
+            .edges
+            .push(edge("tests/chain.rs:deep", &ids[0], EdgeKind::Invokes));
+        for w in ids.windows(2) {
+            graph.edges.push(edge(&w[0], &w[1], EdgeKind::Invokes));
+        }
+        graph.edges.push(edge(
+            &ids[depth - 1],
+            "src/chain.rs:STATE",
+            EdgeKind::WritesState,
+        ));
+        graph
+    }
+
+    fn selected_ids(selection: &TestSelection) -> Vec<&str> {
+        selection
+            .tests
+            .iter()
+            .map(|t| t.entity_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_select_follows_tests_edges() {
+        let graph = build_graph();
+        let selection = select_tests(&graph, &["src/util.ts:slugify".to_string()]);
+        assert_eq!(selected_ids(&selection), ["test/util.test.ts:slugifies"]);
+        assert_eq!(selection.files, ["test/util.test.ts"]);
+        assert_eq!(selection.tests[0].covers, ["src/util.ts:slugify"]);
+        assert!(selection.uncovered.is_empty());
+    }
+
+    #[test]
+    fn test_select_follows_calls_and_state_transitively() {
+        let graph = chain_graph(3);
+        for target in ["src/chain.rs:f2", "src/chain.rs:STATE"] {
+            let selection = select_tests(&graph, &[target.to_string()]);
+            assert_eq!(
+                selected_ids(&selection),
+                ["tests/chain.rs:deep"],
+                "{target}"
+            );
+        }
+        let targets = HashSet::from(["src/chain.rs:f1".to_string()]);
+        assert_eq!(tests_covering(&graph, &targets), ["tests/chain.rs:deep"]);
+    }
+
+    #[test]
+    fn test_select_follows_long_call_chains() {
+        let mut graph = chain_graph(40);
+        // A cycle in the chain must not stall the walk
+        graph.edges.push(edge(
+            "src/chain.rs:f39",
+            "src/chain.rs:f10",
+            EdgeKind::Invokes,
+        ));
+        for target in ["src/chain.rs:f39", "src/chain.rs:STATE"] {
+            let selection = select_tests(&graph, &[target.to_string()]);
+            assert_eq!(
+                selected_ids(&selection),
+                ["tests/chain.rs:deep"],
+                "{target}"
+            );
+            assert!(selection.uncovered.is_empty(), "{target}");
+        }
+    }
+
+    #[test]
+    fn test_select_does_not_reach_through_other_tests() {
+        let mut graph = chain_graph(1);
+        graph.insert_entity(entity(
+            "tests/chain.rs:outer",
+            EntityKind::Test,
+            (7, 9),
+            None,
+        ));
+        graph.edges.push(edge(
+            "tests/chain.rs:outer",
+            "tests/chain.rs:deep",
+            EdgeKind::Invokes,
+        ));
+        let selection = select_tests(&graph, &["src/chain.rs:f0".to_string()]);
+        assert_eq!(selected_ids(&selection), ["tests/chain.rs:deep"]);
+    }
+
+    #[test]
+    fn test_select_expands_class_members() {
+        let graph = build_graph();
+        let selection = select_tests(&graph, &["src/session.ts:Session".to_string()]);
+        assert_eq!(selected_ids(&selection), ["test/session.test.ts:refreshes"]);
+        assert_eq!(selection.tests[0].covers, ["src/session.ts:Session"]);
+        assert!(selection.uncovered.is_empty());
+    }
+
+    #[test]
+    fn test_select_drops_tests_inside_selected_suite() {
+        let graph = build_graph();
+        let selection = select_tests(
+            &graph,
+            &[
+                "src/session.ts:expire".to_string(),
+                "src/session.ts:Session::refresh".to_string(),
+            ],
+        );
+        // The suite's setup reaches `expire`, so running it covers both nested tests
+        assert_eq!(selected_ids(&selection), ["test/session.test.ts:Session"]);
+    }
+
+    #[test]
+    fn test_select_changed_tests_and_uncovered() {
+        let graph = build_graph();
+        let changed = [
+            "test/util.test.ts:slugifies".to_string(),
+            "src/util.ts:unused".to_string(),
+            "src/gone.ts:missing".to_string(),
+        ];
+        let selection = select_tests(&graph, &changed);
+        assert_eq!(selected_ids(&selection), ["test/util.test.ts:slugifies"]);
+        assert_eq!(selection.uncovered, ["src/util.ts:unused"]);
+        assert_eq!(selection.unknown, ["src/gone.ts:missing"]);
+    }
+
+    #[test]
+    fn test_entities_in_files() {
+        let graph = build_graph();
+        let mut ids = entities_in_files(&graph, &["src/util.ts".to_string()]);
+        ids.sort();
+        assert_eq!(ids, ["src/util.ts:slugify", "src/util.ts:unused"]);
+    }
+}
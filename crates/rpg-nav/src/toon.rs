@@ -8,7 +8,9 @@
 use crate::context::ContextPackResult;
 use crate::fetch::{FetchOutput, FetchResult, HierarchyFetchResult};
 use crate::impact::ImpactResult;
+use crate::query::QueryResult;
 use crate::search::SearchResult;
+use crate::test_selection::TestSelection;
 use rpg_core::graph::RPGraph;
 use serde::Serialize;
 use toon_format::{EncodeOptions, encode};
@@ -86,6 +88,8 @@ struct FetchEntityOutput {
     kind: String,
     file: String,
     lines: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cell: Option<String>,
     #[serde(skip_serializing_if = "String::is_empty")]
     hierarchy: String,
     lifted: bool,
@@ -125,6 +129,14 @@ struct FetchEntityOutput {
     data_flows_from: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modifiers: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    decorators: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docstring: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    complexity: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     siblings: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -215,6 +227,7 @@ pub fn format_fetch_result_projected(result: &FetchResult, projection: &FetchPro
         kind: format!("{:?}", entity.kind).to_lowercase(),
         file: entity.file.display().to_string(),
         lines: format!("{}-{}", entity.line_start, entity.line_end),
+        cell: entity.metadata.cell.map(format_cell),
         hierarchy: if include_hierarchy {
             entity.hierarchy_path.clone()
         } else {
@@ -307,6 +320,10 @@ pub fn format_fetch_result_projected(result: &FetchResult, projection: &FetchPro
             Vec::new()
         },
         signature: entity.signature.as_ref().map(format_signature),
+        modifiers: format_modifiers(&entity.metadata),
+        decorators: entity.metadata.decorators.clone(),
+        docstring: entity.metadata.docstring.clone(),
+        complexity: entity.signals.map(|s| format_complexity(&s)),
         siblings: if include_hierarchy {
             result.hierarchy_context.clone()
         } else {
@@ -335,6 +352,7 @@ pub fn format_fetch_result(result: &FetchResult) -> String {
         kind: format!("{:?}", entity.kind).to_lowercase(),
         file: entity.file.display().to_string(),
         lines: format!("{}-{}", entity.line_start, entity.line_end),
+        cell: entity.metadata.cell.map(format_cell),
         hierarchy: entity.hierarchy_path.clone(),
         lifted: !entity.semantic_features.is_empty(),
         features: entity.semantic_features.clone(),
@@ -355,6 +373,10 @@ pub fn format_fetch_result(result: &FetchResult) -> String {
         data_flows_to: entity.deps.data_flows_to.clone(),
         data_flows_from: entity.deps.data_flows_from.clone(),
         signature: entity.signature.as_ref().map(format_signature),
+        modifiers: format_modifiers(&entity.metadata),
+        decorators: entity.metadata.decorators.clone(),
+        docstring: entity.metadata.docstring.clone(),
+        complexity: entity.signals.map(|s| format_complexity(&s)),
         siblings: result.hierarchy_context.clone(),
         source: result.source_code.clone(),
     };
@@ -387,6 +409,28 @@ fn format_signature(sig: &rpg_core::graph::Signature) -> String {
     }
 }
 
+/// Space-separated modifier keywords like `public static async`, or `None` if there are none.
+fn format_modifiers(metadata: &rpg_core::graph::EntityMetadata) -> Option<String> {
+    let modifiers = metadata.modifiers();
+    (!modifiers.is_empty()).then(|| modifiers.join(" "))
+}
+
+/// Notebook position like `3 (line 2)`: cell 3, second line of the cell.
+fn format_cell(cell: rpg_core::graph::NotebookCell) -> String {
+    format!("{} (line {})", cell.index, cell.line)
+}
+
+/// Compact complexity summary like `cyclomatic=4 cognitive=6 nesting=2 params=3`.
+fn format_complexity(signals: &rpg_core::graph::StructuralSignals) -> String {
+    format!(
+        "cyclomatic={} cognitive={} nesting={} params={}",
+        signals.cyclomatic_complexity,
+        signals.cognitive_complexity,
+        signals.max_nesting_depth,
+        signals.parameter_count
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Hierarchy node fetch result
 // ---------------------------------------------------------------------------
@@ -477,6 +521,7 @@ struct RpgInfoOutput {
     areas: usize,
     edges: usize,
     data_flow_edges: usize,
+    test_edges: usize,
     hierarchy_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     summary: Option<String>,
@@ -538,6 +583,7 @@ pub fn format_rpg_info(graph: &RPGraph) -> String {
         areas: graph.metadata.functional_areas,
         edges: graph.metadata.total_edges,
         data_flow_edges: graph.metadata.data_flow_edges,
+        test_edges: graph.metadata.test_edges,
         hierarchy_type: if graph.metadata.semantic_hierarchy {
             "semantic".to_string()
         } else {
@@ -688,6 +734,56 @@ pub fn format_context_pack(result: &ContextPackResult) -> String {
     encode(&output, &encode_opts()).unwrap_or_else(|_| "encoding error".to_string())
 }
 
+// ---------------------------------------------------------------------------
+// Structured query output
+// ---------------------------------------------------------------------------
+
+/// Format a structured query result as TOON.
+pub fn format_query_result(result: &QueryResult) -> String {
+    encode(result, &encode_opts()).unwrap_or_else(|_| format!("{:?}", result))
+}
+
+// ---------------------------------------------------------------------------
+// Test selection output
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct SelectedTestRow {
+    entity_id: String,
+    lines: String,
+    covers: String,
+}
+
+#[derive(Serialize)]
+struct TestSelectionOutput {
+    tests: Vec<SelectedTestRow>,
+    files: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    uncovered: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unknown: Vec<String>,
+}
+
+/// Format a test selection as TOON.
+pub fn format_test_selection(selection: &TestSelection) -> String {
+    let output = TestSelectionOutput {
+        tests: selection
+            .tests
+            .iter()
+            .map(|t| SelectedTestRow {
+                entity_id: t.entity_id.clone(),
+                lines: format!("{}-{}", t.line_start, t.line_end),
+                covers: t.covers.join(", "),
+            })
+            .collect(),
+        files: selection.files.clone(),
+        uncovered: selection.uncovered.clone(),
+        unknown: selection.unknown.clone(),
+    };
+
+    encode(&output, &encode_opts()).unwrap_or_else(|_| format!("{:?}", selection))
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -745,6 +841,14 @@ pub fn format_health_report(report: &HealthReport) -> String {
         report.summary.highly_stable_count
     ));
     output.push_str(&format!("hubs: {}\n", report.summary.hub_count));
+    output.push_str(&format!(
+        "avg_cyclomatic: {:.2}\n",
+        report.summary.avg_cyclomatic_complexity
+    ));
+    output.push_str(&format!(
+        "high_complexity: {}\n",
+        report.summary.high_complexity_count
+    ));
 
     // Top unstable entities
     if !report.top_unstable.is_empty() {
@@ -775,6 +879,21 @@ pub fn format_health_report(report: &HealthReport) -> String {
         }
     }
 
+    // Most complex functions
+    if !report.top_complex.is_empty() {
+        output.push_str("\n## Most Complex Functions\n\n");
+        for entity in &report.top_complex {
+            output.push_str(&format!(
+                "- {} ({}) | cyclomatic={} | cognitive={} | nesting={}\n",
+                entity.entity_id,
+                entity.kind,
+                entity.cyclomatic_complexity.unwrap_or(0),
+                entity.cognitive_complexity.unwrap_or(0),
+                entity.max_nesting_depth.unwrap_or(0)
+            ));
+        }
+    }
+
     // Duplication info if present
     if let Some(ref dupes) = report.duplicates {
         output.push_str("\n## Duplication Hotspots\n\n");
@@ -833,11 +952,17 @@ pub fn format_health_report(report: &HealthReport) -> String {
             report.summary.hub_count
         ));
     }
+    if report.summary.high_complexity_count > 0 {
+        output.push_str(&format!(
+            "4. **Simplify complex functions**: {} functions exceed the complexity thresholds. Consider extracting branches or flattening nesting.\n",
+            report.summary.high_complexity_count
+        ));
+    }
     if let Some(ref sem_dupes) = report.semantic_duplicates
         && !sem_dupes.is_empty()
     {
         output.push_str(&format!(
-            "4. **Extract shared abstractions**: {} entity pairs share similar intent. \
+            "5. **Extract shared abstractions**: {} entity pairs share similar intent. \
              Consider introducing a shared interface or helper.\n",
             sem_dupes.len()
         ));
@@ -845,6 +970,7 @@ pub fn format_health_report(report: &HealthReport) -> String {
     if report.summary.god_object_count == 0
         && report.summary.highly_unstable_count == 0
         && report.summary.hub_count == 0
+        && report.summary.high_complexity_count == 0
     {
         output.push_str(
             "✅ No major architectural issues detected. The codebase shows good modularity.\n",
@@ -1072,7 +1198,7 @@ mod tests {
     #[test]
     fn test_format_fetch_result_projected_features_only() {
         use crate::fetch::FetchResult;
-        use rpg_core::graph::{Entity, EntityDeps, EntityKind};
+        use rpg_core::graph::{Entity, EntityDeps, EntityKind, EntityMetadata};
         use std::path::PathBuf;
 
         let result = FetchResult {
@@ -1092,6 +1218,8 @@ mod tests {
                     ..Default::default()
                 },
                 signature: None,
+                metadata: EntityMetadata::default(),
+                signals: None,
             },
             source_code: Some("fn foo() { bar() }".to_string()),
             hierarchy_context: vec!["sibling".to_string()],
@@ -1115,7 +1243,7 @@ mod tests {
     #[test]
     fn test_format_fetch_result_projected_source_max_lines() {
         use crate::fetch::FetchResult;
-        use rpg_core::graph::{Entity, EntityDeps, EntityKind};
+        use rpg_core::graph::{Entity, EntityDeps, EntityKind, EntityMetadata};
         use std::path::PathBuf;
 
         let long_source = (1..=20)
@@ -1136,6 +1264,8 @@ mod tests {
                 hierarchy_path: String::new(),
                 deps: EntityDeps::default(),
                 signature: None,
+                metadata: EntityMetadata::default(),
+                signals: None,
             },
             source_code: Some(long_source),
             hierarchy_context: vec![],
@@ -1210,4 +1340,40 @@ mod tests {
         assert!(output.contains("upstream"));
         assert!(output.contains("entry point"));
     }
+
+    #[test]
+    fn test_format_query_result() {
+        use crate::query::{QueryGroup, QueryMatch};
+
+        let result = QueryResult {
+            total: 3,
+            matches: vec![QueryMatch {
+                entity_id: "api/users.py:list_users".to_string(),
+                name: "list_users".to_string(),
+                kind: "controller".to_string(),
+                file: "api/users.py".to_string(),
+                line: 12,
+                area: "API".to_string(),
+            }],
+            groups: None,
+            truncated: true,
+        };
+        let output = format_query_result(&result);
+        assert!(output.contains("total: 3"));
+        assert!(output.contains("list_users\"|list_users|controller|api/users.py|12|API"));
+        assert!(output.contains("truncated: true"));
+
+        let grouped = QueryResult {
+            total: 3,
+            matches: Vec::new(),
+            groups: Some(vec![QueryGroup {
+                key: "API".to_string(),
+                count: 3,
+            }]),
+            truncated: false,
+        };
+        let output = format_query_result(&grouped);
+        assert!(output.contains("API|3"));
+        assert!(!output.contains("matches"));
+    }
 }
@@ -16,6 +16,8 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -32,18 +34,21 @@ fn make_graph() -> RPGraph {
         source: "a.rs:a".to_string(),
         target: "b.rs:b".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     // b -> c (invokes)
     graph.edges.push(DependencyEdge {
         source: "b.rs:b".to_string(),
         target: "c.rs:c".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     // d -> a (imports)
     graph.edges.push(DependencyEdge {
         source: "d.rs:d".to_string(),
         target: "a.rs:a".to_string(),
         kind: EdgeKind::Imports,
+        confidence: None,
     });
 
     graph
@@ -141,11 +146,13 @@ fn test_explore_no_cycles() {
         source: "a.rs:a".to_string(),
         target: "b.rs:b".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "b.rs:b".to_string(),
         target: "a.rs:a".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
 
     let result = explore(&graph, "a.rs:a", Direction::Downstream, 10, None);
@@ -184,6 +191,7 @@ fn test_explore_composes_edge() {
         source: "mod.rs:mod_a".to_string(),
         target: "impl.rs:impl_a".to_string(),
         kind: EdgeKind::Composes,
+        confidence: None,
     });
     graph.rebuild_edge_index();
 
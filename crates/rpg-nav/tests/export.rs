@@ -16,6 +16,8 @@ fn make_entity(id: &str, name: &str, file: &str, kind: EntityKind) -> Entity {
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -27,21 +29,25 @@ fn make_graph() -> RPGraph {
         source: "a.rs:a".to_string(),
         target: "b.rs:b".to_string(),
         kind: EdgeKind::Invokes,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "a.rs:a".to_string(),
         target: "b.rs:b".to_string(),
         kind: EdgeKind::Imports,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "b.rs:b".to_string(),
         target: "a.rs:a".to_string(),
         kind: EdgeKind::Inherits,
+        confidence: None,
     });
     graph.edges.push(DependencyEdge {
         source: "a.rs:a".to_string(),
         target: "b.rs:b".to_string(),
         kind: EdgeKind::Composes,
+        confidence: None,
     });
     graph
 }
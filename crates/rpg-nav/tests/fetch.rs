@@ -17,6 +17,8 @@ fn make_entity(id: &str, name: &str, file: &str, hierarchy: &str) -> Entity {
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
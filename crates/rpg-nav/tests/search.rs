@@ -1,5 +1,8 @@
 use rpg_core::graph::*;
-use rpg_nav::search::{SearchMode, SearchParams, search, search_with_params};
+use rpg_nav::search::{
+    MetadataFilter, SearchMode, SearchParams, search, search_with_params, with_external_packages,
+};
+use rpg_nav::search_index::SearchIndex;
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy: &str) -> Entity {
@@ -16,6 +19,8 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy:
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        metadata: EntityMetadata::default(),
+        signals: None,
     }
 }
 
@@ -247,8 +252,10 @@ fn test_search_with_file_pattern() {
             line_nums: None,
             file_pattern: Some("auth*"),
             entity_type_filter: None,
+            metadata_filter: None,
             embedding_scores: None,
             diff_context: None,
+            index: None,
         },
     );
     // Only auth.rs matches the pattern "auth*"
@@ -295,8 +302,10 @@ fn test_search_with_line_nums() {
             line_nums: Some((40, 70)),
             file_pattern: None,
             entity_type_filter: None,
+            metadata_filter: None,
             embedding_scores: None,
             diff_context: None,
+            index: None,
         },
     );
     // Only "late" should match (lines 50-60 overlaps 40-70)
@@ -336,8 +345,10 @@ fn test_search_params_combined() {
             line_nums: Some((1, 20)),
             file_pattern: Some("auth*"),
             entity_type_filter: None,
+            metadata_filter: None,
             embedding_scores: None,
             diff_context: None,
+            index: None,
         },
     );
     assert_eq!(results.len(), 1);
@@ -380,3 +391,184 @@ fn test_multi_scope_dedup_overlapping() {
         "results should contain no duplicate entity IDs"
     );
 }
+
+#[test]
+fn test_search_params_metadata_filter() {
+    let mut graph = make_graph();
+    for (id, visibility, is_async) in [
+        ("auth.rs:validate_token", Visibility::Public, true),
+        ("api.rs:handle_login", Visibility::Public, false),
+        ("db.rs:query_users", Visibility::Private, true),
+    ] {
+        let metadata = &mut graph.entities.get_mut(id).unwrap().metadata;
+        metadata.visibility = Some(visibility);
+        metadata.is_async = is_async;
+    }
+    graph
+        .entities
+        .get_mut("api.rs:handle_login")
+        .unwrap()
+        .metadata
+        .decorators = vec!["Post(\"/login\")".to_string()];
+
+    let run = |filter: &MetadataFilter| {
+        let mut names: Vec<String> = search_with_params(
+            &graph,
+            &SearchParams {
+                query: "authentication database query",
+                mode: SearchMode::Features,
+                scope: None,
+                limit: 10,
+                line_nums: None,
+                file_pattern: None,
+                entity_type_filter: None,
+                metadata_filter: Some(filter),
+                embedding_scores: None,
+                diff_context: None,
+                index: None,
+            },
+        )
+        .into_iter()
+        .map(|r| r.entity_name)
+        .collect();
+        names.sort();
+        names
+    };
+
+    let public_async = MetadataFilter {
+        visibility: Some(vec![Visibility::Public]),
+        is_async: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(run(&public_async), ["validate_token"]);
+
+    let non_async = MetadataFilter {
+        is_async: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(run(&non_async), ["handle_login"]);
+
+    let decorated = MetadataFilter {
+        decorator: Some("post".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(run(&decorated), ["handle_login"]);
+
+    assert_eq!(run(&MetadataFilter::default()).len(), 3);
+}
+
+#[test]
+fn test_search_matches_external_packages() {
+    let mut graph = make_graph();
+    graph.externals.insert(
+        "ext:jsonwebtoken".to_string(),
+        ExternalNode {
+            id: "ext:jsonwebtoken".to_string(),
+            name: "jsonwebtoken".to_string(),
+            ecosystem: PackageEcosystem::Cargo,
+            version: Some("9".to_string()),
+            manifests: vec![PathBuf::from("Cargo.toml")],
+        },
+    );
+    let mut params = SearchParams {
+        query: "jsonwebtoken",
+        mode: SearchMode::Auto,
+        scope: None,
+        limit: 10,
+        line_nums: None,
+        file_pattern: None,
+        entity_type_filter: None,
+        metadata_filter: None,
+        embedding_scores: None,
+        diff_context: None,
+        index: None,
+    };
+
+    let results = with_external_packages(&graph, &params, search_with_params(&graph, &params));
+    assert_eq!(results[0].entity_id, "ext:jsonwebtoken");
+    assert_eq!(results[0].file, "Cargo.toml");
+    assert_eq!(results[0].matched_features, ["external cargo package"]);
+
+    // Packages have no file to match a pattern against
+    params.file_pattern = Some("*.rs");
+    let results = with_external_packages(&graph, &params, search_with_params(&graph, &params));
+    assert!(results.iter().all(|r| r.entity_id != "ext:jsonwebtoken"));
+}
+
+fn indexed_params<'a>(
+    query: &'a str,
+    mode: SearchMode,
+    index: &'a SearchIndex,
+) -> SearchParams<'a> {
+    SearchParams {
+        query,
+        mode,
+        scope: None,
+        limit: 10,
+        line_nums: None,
+        file_pattern: None,
+        entity_type_filter: None,
+        metadata_filter: None,
+        embedding_scores: None,
+        diff_context: None,
+        index: Some(index),
+    }
+}
+
+#[test]
+fn test_indexed_search_modes() {
+    let graph = make_graph();
+    let index = SearchIndex::build(&graph);
+
+    let results = search_with_params(
+        &graph,
+        &indexed_params("authentication", SearchMode::Features, &index),
+    );
+    let names: Vec<&str> = results.iter().map(|r| r.entity_name.as_str()).collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"validate_token") && names.contains(&"handle_login"));
+    assert!(results.iter().all(|r| {
+        r.matched_features
+            .iter()
+            .all(|f| f.contains("authentication"))
+    }));
+
+    let results = search_with_params(
+        &graph,
+        &indexed_params("parse config", SearchMode::Snippets, &index),
+    );
+    assert_eq!(results[0].entity_name, "parse_config");
+    assert!(results[0].matched_features.is_empty());
+
+    let results = search_with_params(&graph, &indexed_params("JWT", SearchMode::Auto, &index));
+    assert_eq!(results[0].entity_name, "validate_token");
+}
+
+#[test]
+fn test_indexed_search_applies_scope_and_filters() {
+    let graph = make_graph();
+    let index = SearchIndex::build(&graph);
+
+    let mut params = indexed_params("authentication", SearchMode::Features, &index);
+    params.scope = Some("Security/auth/login");
+    let results = search_with_params(&graph, &params);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].entity_name, "handle_login");
+
+    params.scope = None;
+    params.file_pattern = Some("auth.rs");
+    let results = search_with_params(&graph, &params);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].entity_name, "validate_token");
+}
+
+#[test]
+fn test_indexed_search_tolerates_typos() {
+    let graph = make_graph();
+    let index = SearchIndex::build(&graph);
+    let results = search_with_params(
+        &graph,
+        &indexed_params("configuraton", SearchMode::Features, &index),
+    );
+    assert_eq!(results[0].entity_name, "parse_config");
+}
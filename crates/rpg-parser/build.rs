@@ -1,5 +1,8 @@
 use serde::Deserialize;
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 fn main() {
     let out_dir = std::env::var("OUT_DIR").unwrap();
@@ -60,6 +63,20 @@ struct LangToml {
     glob: String,
     grammar: GrammarSection,
     builtin: Option<BuiltinSection>,
+    /// Whether files of the language count toward project language detection;
+    /// when false the language is indexed only if requested by name.
+    #[serde(default = "default_detect")]
+    detect: bool,
+    /// Tree-sitter query captures; validated and compiled at runtime by
+    /// `languages::queries`, so the build only needs to know it exists.
+    queries: Option<toml::Value>,
+    /// Absolute path of the TOML file, for `include_str!`.
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+fn default_detect() -> bool {
+    true
 }
 
 #[derive(Deserialize)]
@@ -107,13 +124,14 @@ fn scan_language_defs(dir: &Path) -> Vec<LangToml> {
                     e
                 )
             });
-            let def: LangToml = toml::from_str(&content).unwrap_or_else(|e| {
+            let mut def: LangToml = toml::from_str(&content).unwrap_or_else(|e| {
                 panic!(
                     "failed to parse language def {}: {}",
                     entry.path().display(),
                     e
                 )
             });
+            def.path = entry.path().canonicalize().unwrap_or_else(|_| entry.path());
             defs.push(def);
         }
     }
@@ -212,10 +230,16 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          \x20   }\n\n",
     );
 
-    // from_extension()
+    // from_extension() / from_builtin_extension()
     code.push_str(
-        "    /// Detect language from file extension.\n\
+        "    /// Detect language from file extension, including extensions added by\n\
+         \x20   /// installed runtime language definitions.\n\
          \x20   pub fn from_extension(ext: &str) -> Option<Self> {\n\
+         \x20       Self::from_builtin_extension(ext)\n\
+         \x20           .or_else(|| crate::languages::user::extension_language(ext))\n\
+         \x20   }\n\n\
+         \x20   /// Detect language from the extensions declared in `languages/defs`.\n\
+         \x20   fn from_builtin_extension(ext: &str) -> Option<Self> {\n\
          \x20       match ext {\n",
     );
     for (i, def) in defs.iter().enumerate() {
@@ -271,6 +295,27 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          \x20   }\n\n",
     );
 
+    // is_detected()
+    let undetected: Vec<String> = defs
+        .iter()
+        .enumerate()
+        .filter(|(_, def)| !def.detect)
+        .map(|(i, _)| i.to_string())
+        .collect();
+    code.push_str(
+        "    /// Whether files of this language count toward [`Self::detect_all`];\n\
+         \x20   /// documents that only embed code are indexed when requested by name.\n\
+         \x20   pub fn is_detected(self) -> bool {\n",
+    );
+    if undetected.is_empty() {
+        code.push_str("        true\n    }\n\n");
+    } else {
+        code.push_str(&format!(
+            "        !matches!(self.0, {})\n    }}\n\n",
+            undetected.join(" | ")
+        ));
+    }
+
     // detect_primary() / detect_all() / count_files()
     code.push_str(
         "    /// Detect the primary language of a project by counting file extensions.\n\
@@ -291,7 +336,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          \x20           .filter(|(_, count)| **count > 0)\n\
          \x20           .map(|(idx, count)| (Self::from_index(idx), *count))\n\
          \x20           .collect();\n\
-         \x20       langs.sort_by(|a, b| b.1.cmp(&a.1));\n\
+         \x20       langs.sort_by_key(|b| std::cmp::Reverse(b.1));\n\
          \x20       langs.into_iter().map(|(lang, _)| lang).collect()\n\
          \x20   }\n\n\
          \x20   /// Count files per language in the project.\n\
@@ -305,6 +350,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          \x20       for entry in walker.flatten() {\n\
          \x20           if let Some(ext) = entry.path().extension().and_then(|e| e.to_str())\n\
          \x20               && let Some(lang) = Self::from_extension(ext)\n\
+         \x20               && lang.is_detected()\n\
          \x20           {\n\
          \x20               counts[lang.index()] += 1;\n\
          \x20           }\n\
@@ -373,7 +419,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
     );
     for def in defs {
         if !def.grammar.aliases.is_empty() {
-            code.push_str(&format!("        \"{}\" => match file_ext {{\n", def.name,));
+            code.push_str(&format!("        \"{}\" => match file_ext {{\n", def.name));
             for alias in &def.grammar.aliases {
                 let ext_patterns: Vec<String> = alias
                     .for_extensions
@@ -407,9 +453,12 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          ) -> Option<fn(&std::path::Path, &str) -> Vec<crate::entities::RawEntity>> {\n\
          \x20   match name {\n",
     );
+    // Several languages may share one extractor; emit each name once
+    let mut seen_extractors = std::collections::HashSet::new();
     for def in defs {
         if let Some(ref builtin) = def.builtin
             && let Some(ref extractor) = builtin.entity_extractor
+            && seen_extractors.insert(extractor.as_str())
         {
             code.push_str(&format!(
                 "        \"{}\" => Some(crate::entities::{}),\n",
@@ -429,7 +478,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
         if let Some(ref builtin) = def.builtin
             && let Some(ref extractor) = builtin.dep_extractor
         {
-            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor,));
+            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor));
         }
     }
     code.push_str("        _ => None,\n    }\n}\n\n");
@@ -444,7 +493,32 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
         if let Some(ref builtin) = def.builtin
             && let Some(ref extractor) = builtin.entity_extractor
         {
-            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor,));
+            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor));
+        }
+    }
+    code.push_str("        _ => None,\n    }\n}\n\n");
+
+    // --- query_def_source() — embedded TOML for languages with a [queries] section ---
+    code.push_str("/// Embedded TOML source of a language def that declares `[queries]`.\n");
+    if !defs.iter().any(|def| def.queries.is_some()) {
+        // A match with only the wildcard arm would trip clippy
+        code.push_str(
+            "pub fn query_def_source(_lang: LangId) -> Option<&'static str> {\n    None\n}\n",
+        );
+        return code;
+    }
+    code.push_str(
+        "pub fn query_def_source(lang: LangId) -> Option<&'static str> {\n\
+         \x20   match lang.0 {\n",
+    );
+    for (i, def) in defs.iter().enumerate() {
+        if def.queries.is_some() {
+            // Forward slashes even on Windows so include_str! doesn't see backslashes
+            let path_str = def.path.display().to_string().replace('\\', "/");
+            code.push_str(&format!(
+                "        {} => Some(include_str!(\"{}\")),\n",
+                i, path_str
+            ));
         }
     }
     code.push_str("        _ => None,\n    }\n}\n");
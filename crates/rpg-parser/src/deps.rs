@@ -1,5 +1,9 @@
 //! Extract dependencies (imports, calls, inheritance) from AST.
 
+use crate::entities::{
+    ELIXIR_DEF_KEYWORDS, dart_member_name, dart_signature_node, elixir_call_target,
+    elixir_function_head, elixir_keyword_value, lua_assignments, lua_function_name, zig_container,
+};
 use crate::languages::Language;
 use std::path::Path;
 
@@ -214,6 +218,23 @@ pub fn find_enclosing_scope(scopes: &[FunctionScope], row: usize) -> Option<Stri
 
 /// Extract callee name from a Rust call expression's function node.
 fn extract_rust_callee(node: &tree_sitter::Node, source: &str) -> String {
+    match node.kind() {
+        // obj.method(): the method name is the field of the callee expression
+        "field_expression" => {
+            return node
+                .child_by_field_name("field")
+                .map(|f| source[f.byte_range()].to_string())
+                .unwrap_or_default();
+        }
+        // foo::<T>() and obj.method::<T>()
+        "generic_function" => {
+            return node
+                .child_by_field_name("function")
+                .map(|f| extract_rust_callee(&f, source))
+                .unwrap_or_default();
+        }
+        _ => {}
+    }
     let text = &source[node.byte_range()];
     // For paths like foo::bar::baz(), extract "baz"
     // For simple identifiers, return as-is
@@ -360,22 +381,19 @@ fn collect_rust_calls(
                     }
                 }
             }
-            "method_call_expression" | "field_expression" => {
-                // obj.method() — tree-sitter-rust uses "method_call_expression" for x.foo()
-                // but some versions nest it differently
-                if child.kind() == "method_call_expression" {
-                    // The method name is in the "name" field
-                    if let Some(method_node) = child.child_by_field_name("name") {
-                        let callee = source[method_node.byte_range()].to_string();
-                        if !callee.is_empty() {
-                            let call_row = child.start_position().row;
-                            let caller = find_enclosing_scope(scopes, call_row)
-                                .unwrap_or_else(|| "<module>".to_string());
-                            calls.push(CallDep {
-                                caller_entity: caller,
-                                callee,
-                            });
-                        }
+            "method_call_expression" => {
+                // obj.method() — tree-sitter-rust uses "method_call_expression" for x.foo();
+                // the method name is in the "name" field
+                if let Some(method_node) = child.child_by_field_name("name") {
+                    let callee = source[method_node.byte_range()].to_string();
+                    if !callee.is_empty() {
+                        let call_row = child.start_position().row;
+                        let caller = find_enclosing_scope(scopes, call_row)
+                            .unwrap_or_else(|| "<module>".to_string());
+                        calls.push(CallDep {
+                            caller_entity: caller,
+                            callee,
+                        });
                     }
                 }
             }
@@ -435,7 +453,12 @@ fn parse_rust_use(text: &str) -> ImportDep {
 }
 
 /// Generic dependency extraction dispatching to the correct language extractor.
+/// Runtime `[queries]` installed for the language take precedence; languages
+/// without a builtin extractor use the `[queries]` section of their TOML.
 pub fn extract_deps(path: &Path, source: &str, language: Language) -> RawDeps {
+    if let Some(queries) = crate::languages::user::user_queries(language) {
+        return crate::languages::queries::extract_deps(&queries, path, source, language);
+    }
     if let Some(name) = crate::languages::builtin_dep_extractor_name(language) {
         match name {
             "extract_python_deps" => return extract_python_deps(path, source),
@@ -451,6 +474,12 @@ pub fn extract_deps(path: &Path, source: &str, language: Language) -> RawDeps {
             "extract_swift_deps" => return extract_swift_deps(path, source),
             "extract_scala_deps" => return extract_scala_deps(path, source),
             "extract_bash_deps" => return extract_bash_deps(path, source),
+            "extract_lua_deps" => return extract_lua_deps(path, source),
+            "extract_elixir_deps" => return extract_elixir_deps(path, source),
+            "extract_dart_deps" => return extract_dart_deps(path, source),
+            "extract_zig_deps" => return extract_zig_deps(path, source),
+            "extract_embedded_deps" => return extract_embedded_deps(path, source),
+            "extract_notebook_deps" => return extract_notebook_deps(path, source),
             other => {
                 eprintln!(
                     "warning: unrecognized dep extractor '{}' for {:?}",
@@ -459,6 +488,9 @@ pub fn extract_deps(path: &Path, source: &str, language: Language) -> RawDeps {
             }
         }
     }
+    if let Some(queries) = crate::languages::queries::builtin_queries(language) {
+        return crate::languages::queries::extract_deps(queries, path, source, language);
+    }
     RawDeps::default()
 }
 
@@ -587,12 +619,54 @@ pub fn collect_js_scopes(
                     continue;
                 }
             }
+            // Test blocks: describe("...", () => {}), it("...", () => {})
+            "call_expression" => {
+                if let Some(name) = js_test_block_name(&child, source) {
+                    scopes.push(FunctionScope {
+                        name,
+                        start_row: child.start_position().row,
+                        end_row: child.end_position().row,
+                    });
+                }
+            }
             _ => {}
         }
         collect_js_scopes(&child, source, scopes, parent_class);
     }
 }
 
+/// Jest, Vitest, Mocha and Jasmine functions that declare a test or suite.
+const JS_TEST_BLOCKS: &[&str] = &["describe", "context", "suite", "it", "test", "specify"];
+
+/// The description of a `describe("...", () => {})`-style test block (also
+/// `it.only(...)`, `test.skip(...)`), which names the block's scope. It matches
+/// the name of the Test entity the `jest` paradigm extracts for the block.
+fn js_test_block_name(call: &tree_sitter::Node, source: &str) -> Option<String> {
+    let function = call.child_by_field_name("function")?;
+    let callee = match function.kind() {
+        "identifier" => function,
+        "member_expression" => function.child_by_field_name("object")?,
+        _ => return None,
+    };
+    if !JS_TEST_BLOCKS.contains(&&source[callee.byte_range()]) {
+        return None;
+    }
+    let arguments = call.child_by_field_name("arguments")?;
+    let callback = arguments.named_child(1)?;
+    if !matches!(
+        callback.kind(),
+        "arrow_function" | "function" | "function_expression"
+    ) {
+        return None;
+    }
+    let description = arguments.named_child(0)?;
+    if description.kind() != "string" {
+        return None;
+    }
+    let fragment = description.named_child(0)?;
+    (fragment.kind() == "string_fragment").then(|| source[fragment.byte_range()].to_string())
+}
+
 fn collect_js_imports(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
@@ -1625,12 +1699,47 @@ fn collect_ruby_scopes(
                     continue;
                 }
             }
+            // RSpec blocks: describe User do ... end, it "works" do ... end
+            "call" => {
+                if let Some(name) = rspec_block_name(&child, source) {
+                    scopes.push(FunctionScope {
+                        name,
+                        start_row: child.start_position().row,
+                        end_row: child.end_position().row,
+                    });
+                }
+            }
             _ => {}
         }
         collect_ruby_scopes(&child, source, scopes, parent_class);
     }
 }
 
+/// RSpec methods that declare an example or example group.
+const RSPEC_BLOCKS: &[&str] = &[
+    "describe", "context", "feature", "it", "specify", "example", "scenario",
+];
+
+/// The description of an RSpec example or group (`it "works" do ... end`,
+/// `RSpec.describe User do ... end`), which names the block's scope. It
+/// matches the name of the Test entity the `ruby` paradigm extracts for it.
+fn rspec_block_name(call: &tree_sitter::Node, source: &str) -> Option<String> {
+    let method = call.child_by_field_name("method")?;
+    if !RSPEC_BLOCKS.contains(&&source[method.byte_range()]) {
+        return None;
+    }
+    call.child_by_field_name("block")?;
+    let description = call.child_by_field_name("arguments")?.named_child(0)?;
+    let name = match description.kind() {
+        "string" => description
+            .named_child(0)
+            .filter(|c| c.kind() == "string_content")?,
+        "constant" | "scope_resolution" => description,
+        _ => return None,
+    };
+    Some(source[name.byte_range()].to_string())
+}
+
 fn collect_ruby_imports_and_inheritance(
     node: &tree_sitter::Node,
     source: &str,
@@ -2372,3 +2481,793 @@ fn collect_bash_deps_recursive(
         collect_bash_deps_recursive(&child, source, scopes, deps);
     }
 }
+
+// ---------------------------------------------------------------------------
+// Lua
+// ---------------------------------------------------------------------------
+
+/// Extract deps from Lua source.
+///
+/// `require` calls are imports, and top-level `setmetatable(Derived, {__index = Base})`
+/// (or `local Derived = setmetatable({}, Base)`) is reported as inheritance.
+pub fn extract_lua_deps(_path: &Path, source: &str) -> RawDeps {
+    let lang = Language::LUA.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return RawDeps::default();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return RawDeps::default();
+    };
+
+    let mut deps = RawDeps::default();
+    let root = tree.root_node();
+
+    let mut scopes: Vec<FunctionScope> = Vec::new();
+    collect_lua_scopes(&root, source, &mut scopes);
+
+    collect_lua_inheritance(&root, source, &mut deps);
+    collect_lua_calls(&root, source, &scopes, &mut deps);
+
+    deps
+}
+
+fn collect_lua_scopes(node: &tree_sitter::Node, source: &str, scopes: &mut Vec<FunctionScope>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "function_declaration" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    scopes.push(FunctionScope {
+                        name: lua_scope_name(&name_node, source),
+                        start_row: child.start_position().row,
+                        end_row: child.end_position().row,
+                    });
+                }
+            }
+            "variable_declaration" | "assignment_statement" => {
+                for (target, value) in lua_assignments(&child) {
+                    if value.kind() == "function_definition" {
+                        scopes.push(FunctionScope {
+                            name: lua_scope_name(&target, source),
+                            start_row: child.start_position().row,
+                            end_row: child.end_position().row,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        collect_lua_scopes(&child, source, scopes);
+    }
+}
+
+fn lua_scope_name(name_node: &tree_sitter::Node, source: &str) -> String {
+    match lua_function_name(name_node, source) {
+        (name, Some(table)) => format!("{}.{}", table, name),
+        (name, None) => name,
+    }
+}
+
+fn collect_lua_inheritance(root: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        match child.kind() {
+            // setmetatable(Derived, {__index = Base})
+            "function_call" => {
+                if let Some((Some(derived), base)) = lua_setmetatable(&child, source) {
+                    deps.inherits.push(InheritDep {
+                        child_class: derived,
+                        parent_class: base,
+                    });
+                }
+            }
+            // local Derived = setmetatable({}, {__index = Base})
+            "variable_declaration" | "assignment_statement" => {
+                for (target, value) in lua_assignments(&child) {
+                    if value.kind() == "function_call"
+                        && let Some((_, base)) = lua_setmetatable(&value, source)
+                    {
+                        deps.inherits.push(InheritDep {
+                            child_class: source[target.byte_range()].to_string(),
+                            parent_class: base,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parse `setmetatable(target, meta)` into the target name (when it is an
+/// identifier) and the base table: `meta` itself, or its `__index` field.
+fn lua_setmetatable(call: &tree_sitter::Node, source: &str) -> Option<(Option<String>, String)> {
+    let name = call.child_by_field_name("name")?;
+    if &source[name.byte_range()] != "setmetatable" {
+        return None;
+    }
+    let args = call.child_by_field_name("arguments")?;
+    let mut cursor = args.walk();
+    let mut arg_nodes = args.named_children(&mut cursor);
+    let target = arg_nodes.next()?;
+    let meta = arg_nodes.next()?;
+    let base = match meta.kind() {
+        "identifier" => source[meta.byte_range()].to_string(),
+        "table_constructor" => {
+            let mut fc = meta.walk();
+            let index = meta.named_children(&mut fc).find(|f| {
+                f.child_by_field_name("name")
+                    .is_some_and(|n| &source[n.byte_range()] == "__index")
+            })?;
+            let value = index.child_by_field_name("value")?;
+            if value.kind() != "identifier" {
+                return None;
+            }
+            source[value.byte_range()].to_string()
+        }
+        _ => return None,
+    };
+    let target = (target.kind() == "identifier").then(|| source[target.byte_range()].to_string());
+    Some((target, base))
+}
+
+fn collect_lua_calls(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    deps: &mut RawDeps,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "function_call"
+            && let Some(name_node) = child.child_by_field_name("name")
+        {
+            let callee = match name_node.kind() {
+                "dot_index_expression" | "method_index_expression" => {
+                    lua_function_name(&name_node, source).0
+                }
+                _ => source[name_node.byte_range()].to_string(),
+            };
+            if callee == "require" {
+                // require("mod") / require "mod"
+                if let Some(module) = child
+                    .child_by_field_name("arguments")
+                    .and_then(|args| find_descendant_kind(&args, "string_content"))
+                {
+                    deps.imports.push(ImportDep {
+                        module: source[module.byte_range()].to_string(),
+                        symbols: Vec::new(),
+                    });
+                }
+            } else if !callee.is_empty() && callee != "setmetatable" {
+                let caller = find_enclosing_scope(scopes, child.start_position().row)
+                    .unwrap_or_else(|| "<module>".to_string());
+                deps.calls.push(CallDep {
+                    caller_entity: caller,
+                    callee,
+                });
+            }
+        }
+        collect_lua_calls(&child, source, scopes, deps);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Elixir
+// ---------------------------------------------------------------------------
+
+/// Elixir special forms and directives that look like calls but are not.
+const ELIXIR_NON_CALLS: &[&str] = &[
+    "defmodule",
+    "defprotocol",
+    "defimpl",
+    "defstruct",
+    "defexception",
+    "import",
+    "alias",
+    "require",
+    "use",
+    "if",
+    "unless",
+    "case",
+    "cond",
+    "with",
+    "for",
+    "try",
+    "receive",
+    "quote",
+    "unquote",
+];
+
+/// Extract deps from Elixir source.
+///
+/// `import`, `alias`, `require` and `use` are imports. `use`, `@behaviour`
+/// and `defimpl` are reported as inheritance of the enclosing module, the
+/// same way Ruby mixins are.
+pub fn extract_elixir_deps(_path: &Path, source: &str) -> RawDeps {
+    let lang = Language::ELIXIR.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return RawDeps::default();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return RawDeps::default();
+    };
+
+    let mut deps = RawDeps::default();
+    let root = tree.root_node();
+
+    let mut scopes: Vec<FunctionScope> = Vec::new();
+    collect_elixir_scopes(&root, source, &mut scopes, None);
+
+    collect_elixir_imports_and_inheritance(&root, source, &mut deps, None);
+    collect_elixir_calls(&root, source, &scopes, &mut deps);
+
+    deps
+}
+
+fn collect_elixir_scopes(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &mut Vec<FunctionScope>,
+    module: Option<&str>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match elixir_call_target(&child, source) {
+            Some("defmodule" | "defprotocol" | "defimpl") => {
+                let name = elixir_first_argument(&child, source);
+                collect_elixir_scopes(&child, source, scopes, name.as_deref());
+                continue;
+            }
+            Some(kw) if ELIXIR_DEF_KEYWORDS.contains(&kw) => {
+                if let Some(name) = elixir_function_head(&child).and_then(|head| {
+                    elixir_call_target(&head, source).or_else(|| {
+                        (head.kind() == "identifier").then(|| &source[head.byte_range()])
+                    })
+                }) {
+                    scopes.push(FunctionScope {
+                        name: match module {
+                            Some(m) => format!("{}.{}", m, name),
+                            None => name.to_string(),
+                        },
+                        start_row: child.start_position().row,
+                        end_row: child.end_position().row,
+                    });
+                }
+                continue;
+            }
+            _ => {}
+        }
+        collect_elixir_scopes(&child, source, scopes, module);
+    }
+}
+
+fn elixir_first_argument(call: &tree_sitter::Node, source: &str) -> Option<String> {
+    let mut cursor = call.walk();
+    let args = call
+        .children(&mut cursor)
+        .find(|c| c.kind() == "arguments")?;
+    let mut ac = args.walk();
+    let first = args.named_children(&mut ac).next()?;
+    Some(source[first.byte_range()].to_string())
+}
+
+fn collect_elixir_imports_and_inheritance(
+    node: &tree_sitter::Node,
+    source: &str,
+    deps: &mut RawDeps,
+    module: Option<&str>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "unary_operator" {
+            // @behaviour Mod
+            if let Some(operand) = child.child_by_field_name("operand")
+                && elixir_call_target(&operand, source) == Some("behaviour")
+                && let (Some(module), Some(behaviour)) =
+                    (module, elixir_first_argument(&operand, source))
+            {
+                deps.inherits.push(InheritDep {
+                    child_class: module.to_string(),
+                    parent_class: behaviour,
+                });
+            }
+            continue;
+        }
+        match elixir_call_target(&child, source) {
+            Some(kw @ ("defmodule" | "defprotocol" | "defimpl")) => {
+                let Some(name) = elixir_first_argument(&child, source) else {
+                    continue;
+                };
+                let mut name_for_children = name.clone();
+                if kw == "defimpl" {
+                    let target = {
+                        let mut c = child.walk();
+                        child
+                            .children(&mut c)
+                            .find(|n| n.kind() == "arguments")
+                            .and_then(|args| {
+                                let mut ac = args.walk();
+                                args.children(&mut ac).find(|n| n.kind() == "keywords")
+                            })
+                            .and_then(|kw| elixir_keyword_value(&kw, "for", source))
+                            .or_else(|| module.map(String::from))
+                    };
+                    if let Some(target) = target {
+                        name_for_children = format!("{}.{}", name, target);
+                    }
+                    deps.inherits.push(InheritDep {
+                        child_class: name_for_children.clone(),
+                        parent_class: name,
+                    });
+                }
+                collect_elixir_imports_and_inheritance(
+                    &child,
+                    source,
+                    deps,
+                    Some(&name_for_children),
+                );
+            }
+            Some(kw @ ("import" | "alias" | "require" | "use")) => {
+                deps.imports.extend(parse_elixir_import(&child, source));
+                if kw == "use"
+                    && let (Some(module), Some(used)) =
+                        (module, elixir_first_argument(&child, source))
+                {
+                    deps.inherits.push(InheritDep {
+                        child_class: module.to_string(),
+                        parent_class: used,
+                    });
+                }
+            }
+            _ => collect_elixir_imports_and_inheritance(&child, source, deps, module),
+        }
+    }
+}
+
+/// Parse `import Mod, only: [f: 1]`, `alias Mod.{A, B}` and friends.
+fn parse_elixir_import(call: &tree_sitter::Node, source: &str) -> Option<ImportDep> {
+    let mut cursor = call.walk();
+    let args = call
+        .children(&mut cursor)
+        .find(|c| c.kind() == "arguments")?;
+    let mut ac = args.walk();
+    let mut arg_nodes = args.named_children(&mut ac);
+    let first = arg_nodes.next()?;
+    let mut symbols = Vec::new();
+    let module = if first.kind() == "dot"
+        && let (Some(left), Some(right)) = (
+            first.child_by_field_name("left"),
+            first.child_by_field_name("right"),
+        )
+        && right.kind() == "tuple"
+    {
+        // alias MyApp.{Repo, Post}
+        let mut tc = right.walk();
+        symbols.extend(
+            right
+                .named_children(&mut tc)
+                .map(|n| source[n.byte_range()].to_string()),
+        );
+        source[left.byte_range()].to_string()
+    } else {
+        source[first.byte_range()].to_string()
+    };
+    // import Mod, only: [f: 1, g: 2]
+    if let Some(only) = arg_nodes
+        .find(|n| n.kind() == "keywords")
+        .and_then(|kw| elixir_keyword_value(&kw, "only", source))
+    {
+        symbols.extend(
+            only.trim_matches(|c| c == '[' || c == ']')
+                .split(',')
+                .filter_map(|s| s.split(':').next())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+        );
+    }
+    Some(ImportDep { module, symbols })
+}
+
+fn collect_elixir_calls(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    deps: &mut RawDeps,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        // Module attributes (`@doc`, `@spec`) are not calls
+        if child.kind() == "unary_operator" && source[child.byte_range()].starts_with('@') {
+            continue;
+        }
+        if child.kind() == "call" {
+            let callee = match child.child_by_field_name("target") {
+                Some(target) if target.kind() == "identifier" => {
+                    let name = &source[target.byte_range()];
+                    if ELIXIR_DEF_KEYWORDS.contains(&name) {
+                        // Skip the function head; walk `do:` keywords and the do-block
+                        let mut bc = child.walk();
+                        for part in child.named_children(&mut bc) {
+                            if part.kind() == "arguments" {
+                                let mut ac = part.walk();
+                                for arg in part.named_children(&mut ac).skip(1) {
+                                    collect_elixir_calls(&arg, source, scopes, deps);
+                                }
+                            } else {
+                                collect_elixir_calls(&part, source, scopes, deps);
+                            }
+                        }
+                        continue;
+                    }
+                    (!ELIXIR_NON_CALLS.contains(&name)).then(|| name.to_string())
+                }
+                // Remote call: Mod.fun(...)
+                Some(target) if target.kind() == "dot" => target
+                    .child_by_field_name("right")
+                    .filter(|r| r.kind() == "identifier")
+                    .map(|r| source[r.byte_range()].to_string()),
+                _ => None,
+            };
+            if let Some(callee) = callee {
+                let caller = find_enclosing_scope(scopes, child.start_position().row)
+                    .unwrap_or_else(|| "<module>".to_string());
+                deps.calls.push(CallDep {
+                    caller_entity: caller,
+                    callee,
+                });
+            }
+        }
+        collect_elixir_calls(&child, source, scopes, deps);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dart
+// ---------------------------------------------------------------------------
+
+/// Extract deps from Dart source.
+pub fn extract_dart_deps(_path: &Path, source: &str) -> RawDeps {
+    let lang = Language::DART.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return RawDeps::default();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return RawDeps::default();
+    };
+
+    let mut deps = RawDeps::default();
+    let root = tree.root_node();
+
+    let mut scopes: Vec<FunctionScope> = Vec::new();
+    collect_dart_scopes(&root, source, &mut scopes, None);
+
+    collect_dart_imports_and_inheritance(&root, source, &mut deps);
+    collect_dart_calls(&root, source, &scopes, &mut deps);
+
+    deps
+}
+
+fn collect_dart_scopes(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &mut Vec<FunctionScope>,
+    parent_class: Option<&str>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "class_declaration"
+            | "mixin_declaration"
+            | "extension_declaration"
+            | "enum_declaration" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    let cls = source[name_node.byte_range()].to_string();
+                    collect_dart_scopes(&child, source, scopes, Some(&cls));
+                    continue;
+                }
+            }
+            "function_declaration" | "method_declaration" | "declaration" => {
+                if let Some(name) =
+                    dart_signature_node(&child).and_then(|sig| dart_member_name(&sig, source))
+                {
+                    scopes.push(FunctionScope {
+                        name: match parent_class {
+                            Some(cls) => format!("{}.{}", cls, name),
+                            None => name,
+                        },
+                        start_row: child.start_position().row,
+                        end_row: child.end_position().row,
+                    });
+                }
+            }
+            _ => {}
+        }
+        collect_dart_scopes(&child, source, scopes, parent_class);
+    }
+}
+
+fn collect_dart_imports_and_inheritance(
+    node: &tree_sitter::Node,
+    source: &str,
+    deps: &mut RawDeps,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "import_specification" => {
+                if let Some(uri) = child.child_by_field_name("uri") {
+                    let module = source[uri.byte_range()]
+                        .trim_matches(|c| c == '\'' || c == '"')
+                        .to_string();
+                    // import 'x.dart' show A, B;
+                    let mut symbols = Vec::new();
+                    let mut cc = child.walk();
+                    for combinator in child.children(&mut cc) {
+                        if combinator.kind() == "combinator"
+                            && source[combinator.byte_range()].starts_with("show")
+                        {
+                            let mut ic = combinator.walk();
+                            symbols.extend(
+                                combinator
+                                    .named_children(&mut ic)
+                                    .filter(|n| n.kind() == "identifier")
+                                    .map(|n| source[n.byte_range()].to_string()),
+                            );
+                        }
+                    }
+                    deps.imports.push(ImportDep { module, symbols });
+                }
+            }
+            "class_declaration" | "mixin_declaration" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    let class_name = source[name_node.byte_range()].to_string();
+                    // extends + with (superclass), implements (interfaces),
+                    // and a mixin's `on` constraint (a bare type child)
+                    let mut parents = Vec::new();
+                    for field in ["superclass", "interfaces"] {
+                        if let Some(clause) = child.child_by_field_name(field) {
+                            collect_dart_type_names(&clause, source, &mut parents);
+                        }
+                    }
+                    let mut tc = child.walk();
+                    for type_node in child.children(&mut tc).filter(|n| n.kind() == "type") {
+                        collect_dart_type_names(&type_node, source, &mut parents);
+                    }
+                    for parent in parents {
+                        deps.inherits.push(InheritDep {
+                            child_class: class_name.clone(),
+                            parent_class: parent,
+                        });
+                    }
+                }
+                collect_dart_imports_and_inheritance(&child, source, deps);
+            }
+            _ => {
+                collect_dart_imports_and_inheritance(&child, source, deps);
+            }
+        }
+    }
+}
+
+/// Collect the outermost type names under a supertype clause, without type arguments.
+fn collect_dart_type_names(node: &tree_sitter::Node, source: &str, names: &mut Vec<String>) {
+    if node.kind() == "type" {
+        // Type arguments of `extends Base<T>` surface as a `type` wrapping a `type`
+        let is_type_arguments = node.named_child(0).is_some_and(|c| c.kind() == "type");
+        if !is_type_arguments && let Some(name) = find_descendant_kind(node, "type_identifier") {
+            names.push(source[name.byte_range()].to_string());
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_dart_type_names(&child, source, names);
+    }
+}
+
+fn collect_dart_calls(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    deps: &mut RawDeps,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let callee = match child.kind() {
+            "call_expression" => {
+                child
+                    .child_by_field_name("function")
+                    .and_then(|f| match f.kind() {
+                        "identifier" => Some(f),
+                        "member_expression" | "null_aware_member_expression" => {
+                            f.child_by_field_name("property")
+                        }
+                        _ => None,
+                    })
+            }
+            // new Foo() / const Foo()
+            "new_expression" | "const_object_expression" => child
+                .child_by_field_name("type")
+                .and_then(|t| find_descendant_kind(&t, "type_identifier")),
+            _ => None,
+        };
+        if let Some(callee) = callee {
+            let caller = find_enclosing_scope(scopes, child.start_position().row)
+                .unwrap_or_else(|| "<module>".to_string());
+            deps.calls.push(CallDep {
+                caller_entity: caller,
+                callee: source[callee.byte_range()].to_string(),
+            });
+        }
+        collect_dart_calls(&child, source, scopes, deps);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Zig
+// ---------------------------------------------------------------------------
+
+/// Extract deps from Zig source. `@import("mod")` is the only import form;
+/// Zig has no inheritance.
+pub fn extract_zig_deps(_path: &Path, source: &str) -> RawDeps {
+    let lang = Language::ZIG.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return RawDeps::default();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return RawDeps::default();
+    };
+
+    let mut deps = RawDeps::default();
+    let root = tree.root_node();
+
+    let mut scopes: Vec<FunctionScope> = Vec::new();
+    collect_zig_scopes(&root, source, &mut scopes, None);
+
+    collect_zig_deps_recursive(&root, source, &scopes, &mut deps);
+
+    deps
+}
+
+fn collect_zig_scopes(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &mut Vec<FunctionScope>,
+    parent_class: Option<&str>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "function_declaration" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    let name = source[name_node.byte_range()].to_string();
+                    scopes.push(FunctionScope {
+                        name: match parent_class {
+                            Some(cls) => format!("{}.{}", cls, name),
+                            None => name,
+                        },
+                        start_row: child.start_position().row,
+                        end_row: child.end_position().row,
+                    });
+                }
+            }
+            "variable_declaration" => {
+                if let Some(container) = zig_container(&child) {
+                    let mut ic = child.walk();
+                    if let Some(name_node) =
+                        child.children(&mut ic).find(|n| n.kind() == "identifier")
+                    {
+                        let cls = source[name_node.byte_range()].to_string();
+                        collect_zig_scopes(&container, source, scopes, Some(&cls));
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+        collect_zig_scopes(&child, source, scopes, parent_class);
+    }
+}
+
+fn collect_zig_deps_recursive(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    deps: &mut RawDeps,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "builtin_function" => {
+                let mut bc = child.walk();
+                let is_import = child.children(&mut bc).any(|n| {
+                    n.kind() == "builtin_identifier" && &source[n.byte_range()] == "@import"
+                });
+                if is_import && let Some(module) = find_descendant_kind(&child, "string_content") {
+                    deps.imports.push(ImportDep {
+                        module: source[module.byte_range()].to_string(),
+                        symbols: Vec::new(),
+                    });
+                }
+            }
+            "call_expression" => {
+                let callee = child
+                    .child_by_field_name("function")
+                    .and_then(|f| match f.kind() {
+                        "identifier" => Some(f),
+                        "field_expression" => f.child_by_field_name("member"),
+                        _ => None,
+                    });
+                if let Some(callee) = callee {
+                    let caller = find_enclosing_scope(scopes, child.start_position().row)
+                        .unwrap_or_else(|| "<module>".to_string());
+                    deps.calls.push(CallDep {
+                        caller_entity: caller,
+                        callee: source[callee.byte_range()].to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+        collect_zig_deps_recursive(&child, source, scopes, deps);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Embedded regions (Vue, Svelte, Astro, HTML, Markdown)
+// ---------------------------------------------------------------------------
+
+/// Extract deps from the embedded regions of a host file, merged in source order.
+pub fn extract_embedded_deps(path: &Path, source: &str) -> RawDeps {
+    let mut deps = RawDeps::default();
+    for region in crate::embedded::split_regions(path, source) {
+        let r = extract_deps(path, &region.source, region.language);
+        deps.imports.extend(r.imports);
+        deps.calls.extend(r.calls);
+        deps.inherits.extend(r.inherits);
+        deps.composes.extend(r.composes);
+        deps.renders.extend(r.renders);
+        deps.reads_state.extend(r.reads_state);
+        deps.writes_state.extend(r.writes_state);
+        deps.dispatches.extend(r.dispatches);
+    }
+    deps
+}
+
+/// Extract deps from the Python code cells of a Jupyter notebook. Calls made
+/// by top-level cell code are attributed to the notebook's Module entity.
+pub fn extract_notebook_deps(path: &Path, source: &str) -> RawDeps {
+    let Some(script) = rpg_core::notebook::Script::parse(source) else {
+        return RawDeps::default();
+    };
+    let mut deps = extract_deps(path, &script.source, Language::PYTHON);
+    let module = crate::entities::notebook_module_name(path);
+    for call in &mut deps.calls {
+        if call.caller_entity == "<module>" {
+            call.caller_entity.clone_from(&module);
+        }
+    }
+    deps
+}
+
+/// Find the first descendant of a specific kind (depth-first).
+fn find_descendant_kind<'a>(
+    node: &tree_sitter::Node<'a>,
+    kind: &str,
+) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == kind {
+            return Some(child);
+        }
+        if let Some(found) = find_descendant_kind(&child, kind) {
+            return Some(found);
+        }
+    }
+    None
+}
@@ -0,0 +1,206 @@
+//! Host files that embed code in other languages: single-file components
+//! (Vue, Svelte, Astro), HTML pages and Markdown documents.
+//!
+//! A host file is split into regions — `<script>` blocks, Astro frontmatter,
+//! Markdown code fences — and each region is parsed with its own grammar.
+//! Region sources are padded with the line breaks that precede them, so line
+//! numbers of the extracted entities refer to the host file.
+
+use crate::languages::Language;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// A block of embedded code.
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub language: Language,
+    /// The block's code, preceded by one `\n` per host line above it.
+    pub source: String,
+}
+
+/// Whether files of `language` are hosts split into [`Region`]s.
+pub fn is_host(language: Language) -> bool {
+    language == Language::VUE
+        || language == Language::SVELTE
+        || language == Language::ASTRO
+        || language == Language::HTML
+        || language == Language::MARKDOWN
+}
+
+/// Split a host file into its embedded regions, in source order.
+/// Returns nothing for files that are not hosts.
+pub fn split_regions(path: &Path, source: &str) -> Vec<Region> {
+    let Some(host) = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Language::from_extension)
+        .filter(|l| is_host(*l))
+    else {
+        return Vec::new();
+    };
+    let Some(tree) = parse(source, host) else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+
+    let mut regions = Vec::new();
+    if host == Language::MARKDOWN {
+        collect_fences(&root, source, &mut regions);
+        return regions;
+    }
+    // Astro components run their frontmatter and scripts as TypeScript
+    let default_script = if host == Language::ASTRO {
+        if let Some(frontmatter) = astro_frontmatter(source) {
+            regions.push(frontmatter);
+        }
+        Language::TYPESCRIPT
+    } else {
+        Language::JAVASCRIPT
+    };
+    collect_scripts(&root, source, default_script, &mut regions);
+    regions
+}
+
+fn parse(source: &str, language: Language) -> Option<tree_sitter::Tree> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language.ts_language()).ok()?;
+    parser.parse(source.as_bytes(), None)
+}
+
+/// The region for `source[start..end]`, padded to its host line.
+fn region(language: Language, source: &str, start: usize, end: usize) -> Region {
+    let line = source[..start].matches('\n').count();
+    Region {
+        language,
+        source: format!("{}{}", "\n".repeat(line), &source[start..end]),
+    }
+}
+
+/// `---` fenced frontmatter at the very top of an Astro component.
+fn astro_frontmatter(source: &str) -> Option<Region> {
+    let body_start = source
+        .strip_prefix("---\n")
+        .or_else(|| source.strip_prefix("---\r\n"))
+        .map(|rest| source.len() - rest.len())?;
+    let mut offset = body_start;
+    for line in source[body_start..].split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some(region(Language::TYPESCRIPT, source, body_start, offset));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Inline `<script>` elements whose language can be parsed.
+fn collect_scripts(node: &Node, source: &str, default: Language, out: &mut Vec<Region>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() != "script_element" {
+            collect_scripts(&child, source, default, out);
+            continue;
+        }
+        let mut sc = child.walk();
+        let start_tag = child
+            .named_children(&mut sc)
+            .find(|c| c.kind() == "start_tag");
+        let mut sc = child.walk();
+        let raw_text = child
+            .named_children(&mut sc)
+            .find(|c| c.kind() == "raw_text");
+        if let (Some(tag), Some(text)) = (start_tag, raw_text)
+            && let Some(language) = script_language(&tag, source, default)
+        {
+            out.push(region(language, source, text.start_byte(), text.end_byte()));
+        }
+    }
+}
+
+/// Language of a `<script>` from its `lang` and `type` attributes; `None` for
+/// external scripts, data blocks, templates and languages without a grammar.
+fn script_language(start_tag: &Node, source: &str, default: Language) -> Option<Language> {
+    let mut language = default;
+    let mut cursor = start_tag.walk();
+    for attr in start_tag.named_children(&mut cursor) {
+        if attr.kind() != "attribute" {
+            continue;
+        }
+        let mut ac = attr.walk();
+        let mut parts = attr.named_children(&mut ac);
+        let Some(name) = parts.next() else {
+            continue;
+        };
+        let value = parts
+            .next()
+            .map(|v| source[v.byte_range()].trim_matches(|c| c == '"' || c == '\''))
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match source[name.byte_range()].to_ascii_lowercase().as_str() {
+            "lang" => {
+                language = match value.as_str() {
+                    "ts" | "typescript" | "tsx" => Language::TYPESCRIPT,
+                    "js" | "javascript" | "jsx" => Language::JAVASCRIPT,
+                    _ => return None,
+                }
+            }
+            "type" => match value.as_str() {
+                "" | "module" | "text/javascript" | "application/javascript" => {}
+                "text/typescript" | "application/typescript" => language = Language::TYPESCRIPT,
+                _ => return None,
+            },
+            // External scripts: browsers ignore any inline content
+            "src" => return None,
+            _ => {}
+        }
+    }
+    Some(language)
+}
+
+/// Markdown fenced code blocks tagged with a known language.
+fn collect_fences(node: &Node, source: &str, out: &mut Vec<Region>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() != "fenced_code_block" {
+            collect_fences(&child, source, out);
+            continue;
+        }
+        let mut fc = child.walk();
+        let mut info = None;
+        let mut content = None;
+        for part in child.named_children(&mut fc) {
+            match part.kind() {
+                "info_string" => info = Some(part),
+                "code_fence_content" => content = Some(part),
+                _ => {}
+            }
+        }
+        let language = info
+            .and_then(|i| source[i.byte_range()].split_whitespace().next())
+            .and_then(fence_language);
+        if let (Some(language), Some(content)) = (language, content) {
+            out.push(region(
+                language,
+                source,
+                content.start_byte(),
+                content.end_byte(),
+            ));
+        }
+    }
+}
+
+/// Map a fence info string (`python`, `rs`, `{.ts}`, `c++`, ...) to a language.
+fn fence_language(info: &str) -> Option<Language> {
+    let tag = info
+        .trim_matches(|c| c == '{' || c == '}')
+        .trim_start_matches('.')
+        .to_ascii_lowercase();
+    let tag = match tag.as_str() {
+        "c++" => "cpp",
+        "c#" => "csharp",
+        "golang" => "go",
+        "shell" | "zsh" => "bash",
+        "node" => "javascript",
+        other => other,
+    };
+    Language::from_name(tag).or_else(|| Language::from_extension(tag))
+}
@@ -1,7 +1,12 @@
 //! Extract code entities (functions, classes, methods) from AST.
 
 use crate::languages::Language;
-use rpg_core::graph::{Entity, EntityDeps, EntityKind, Param, Signature, normalize_path};
+use crate::metadata::extract_metadata;
+use crate::signals::analyze_node;
+use rpg_core::graph::{
+    Entity, EntityDeps, EntityKind, EntityMetadata, Param, Signature, StructuralSignals,
+    normalize_path,
+};
 use std::path::Path;
 
 /// A raw parameter extracted from AST.
@@ -29,17 +34,60 @@ pub struct RawEntity {
     pub parent_class: Option<String>,
     pub source_text: String,
     pub signature: Option<RawSignature>,
+    /// Visibility, modifiers, docstring and decorators.
+    pub metadata: EntityMetadata,
+    /// AST-derived complexity signals; `None` for containers and for entities
+    /// synthesized without a syntax node.
+    pub signals: Option<StructuralSignals>,
+    /// Overload discriminator appended to the ID (`#<arity>` or `#<arity>.<n>`)
+    /// when an earlier entity in the same file has the same name and parent.
+    /// Assigned by [`disambiguate_ids`].
+    pub discriminator: Option<String>,
 }
 
 impl RawEntity {
     /// Generate a unique entity ID.
+    ///
+    /// Format is `file:name` or `file:Class::name`, followed by `#discriminator`
+    /// for overloads, property accessors, and other same-named definitions.
     pub fn id(&self) -> String {
+        let base = self.base_id();
+        match &self.discriminator {
+            Some(disc) => format!("{}#{}", base, disc),
+            None => base,
+        }
+    }
+
+    /// The ID without the overload discriminator (shared by all overloads).
+    pub fn base_id(&self) -> String {
         match &self.parent_class {
             Some(class) => format!("{}:{}::{}", normalize_path(&self.file), class, self.name),
             None => format!("{}:{}", normalize_path(&self.file), self.name),
         }
     }
 
+    /// Structural signals for auto-lift gating: the AST signals when the
+    /// entity was extracted with a grammar, otherwise the regex fallback.
+    pub fn structural_signals(&self) -> StructuralSignals {
+        self.signals
+            .unwrap_or_else(|| crate::signals::analyze(&self.source_text))
+    }
+
+    /// Number of parameters in the signature (0 when no signature was extracted).
+    fn arity(&self) -> usize {
+        self.signature.as_ref().map_or(0, |s| s.parameters.len())
+    }
+
+    /// Parameter type annotations in order (empty where a parameter is untyped).
+    fn param_types(&self) -> Vec<&str> {
+        self.signature.as_ref().map_or_else(Vec::new, |s| {
+            s.parameters
+                .iter()
+                .map(|p| p.type_annotation.as_deref().unwrap_or(""))
+                .collect()
+        })
+    }
+
     /// Convert to a full Entity (with empty semantic features and deps).
     pub fn into_entity(self) -> Entity {
         let id = self.id();
@@ -67,6 +115,8 @@ impl RawEntity {
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature,
+            metadata: self.metadata,
+            signals: self.signals,
         }
     }
 }
@@ -114,6 +164,9 @@ fn extract_python_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_python_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::PYTHON),
+                        signals: analyze_node(&child, source, Language::PYTHON),
+                        discriminator: None,
                     });
                 }
             }
@@ -134,6 +187,9 @@ fn extract_python_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::PYTHON),
+                        signals: analyze_node(&child, source, Language::PYTHON),
+                        discriminator: None,
                     });
                     // Recurse into class body for methods
                     if let Some(body) = child.child_by_field_name("body") {
@@ -193,6 +249,9 @@ fn extract_rust_node(
                         parent_class: parent_struct.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_rust_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::RUST),
+                        signals: analyze_node(&child, source, Language::RUST),
+                        discriminator: None,
                     });
                 }
             }
@@ -208,6 +267,9 @@ fn extract_rust_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::RUST),
+                        signals: analyze_node(&child, source, Language::RUST),
+                        discriminator: None,
                     });
                 }
             }
@@ -232,6 +294,9 @@ fn extract_rust_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::RUST),
+                        signals: analyze_node(&child, source, Language::RUST),
+                        discriminator: None,
                     });
                     // Recurse into trait body for default method implementations
                     if let Some(body) = child.child_by_field_name("body") {
@@ -250,15 +315,111 @@ fn extract_rust_node(
 
 /// Generic entity extraction dispatching to the correct language extractor.
 ///
-/// Tries the builtin extractor registered in the language TOML first.
-/// Falls back to an empty result for languages without a builtin extractor.
+/// Runtime `[queries]` installed for the language win; otherwise the builtin
+/// extractor registered in the language TOML is used. Languages without one
+/// fall back to the `[queries]` section of their TOML, and to an empty result
+/// when they have neither.
+/// Fields, constants, enum variants and globals from
+/// [`crate::variables::extract_variables`] are appended, except those already
+/// reported as code (e.g. a function bound to a `const`).
+/// The result is passed through [`disambiguate_ids`], so every ID is unique.
 pub fn extract_entities(path: &Path, source: &str, language: Language) -> Vec<RawEntity> {
-    if let Some(extractor_name) = crate::languages::builtin_entity_extractor_name(language)
+    let mut entities = if let Some(queries) = crate::languages::user::user_queries(language) {
+        crate::languages::queries::extract_entities(&queries, path, source, language)
+    } else if let Some(extractor_name) = crate::languages::builtin_entity_extractor_name(language)
         && let Some(extractor) = crate::languages::builtin_entity_extractor(extractor_name)
     {
-        return extractor(path, source);
+        extractor(path, source)
+    } else if let Some(queries) = crate::languages::queries::builtin_queries(language) {
+        crate::languages::queries::extract_entities(queries, path, source, language)
+    } else {
+        return Vec::new();
+    };
+
+    let variables = crate::variables::extract_variables(path, source, language);
+    let code_count = entities.len();
+    for variable in variables {
+        let is_code = entities[..code_count].iter().any(|e| {
+            e.name == variable.name
+                && e.parent_class == variable.parent_class
+                && e.line_start <= variable.line_start
+                && variable.line_start <= e.line_end
+        });
+        if !is_code {
+            entities.push(variable);
+        }
+    }
+    disambiguate_ids(&mut entities);
+    entities
+}
+
+/// Make entity IDs unique within a single file's entity list.
+///
+/// Exact duplicates (the same definition reported twice, e.g. by a base extractor
+/// and a paradigm feature) collapse into one, keeping the later report. Remaining
+/// entities that share a base ID — Java/C#/C++/Kotlin overloads, Python property
+/// getter/setter pairs, same-named nested functions — are ordered by their
+/// signature, code before data (a field named like its getter), then arity, then
+/// parameter types, with position only breaking the remaining ties. The first keeps
+/// the plain ID, and each later one gets `#<arity>`, or `#<arity>.<n>` when that
+/// arity is already taken in the group. Moving overloads around in the file
+/// leaves their IDs alone, but adding or removing one can renumber the others:
+/// with `put(String)` and `put(String, int)`, adding `put(Integer)` sorts first,
+/// takes the plain ID and moves `put(String)` to `#1`.
+///
+/// Idempotent: previously assigned discriminators are recomputed from scratch,
+/// so this can be re-run after paradigm passes append entities.
+pub fn disambiguate_ids(entities: &mut Vec<RawEntity>) {
+    use std::collections::HashMap;
+    use std::collections::hash_map::Entry;
+
+    let mut seen: HashMap<(String, usize), usize> = HashMap::new();
+    let mut deduped: Vec<RawEntity> = Vec::with_capacity(entities.len());
+    for mut entity in entities.drain(..) {
+        entity.discriminator = None;
+        match seen.entry((entity.base_id(), entity.line_start)) {
+            Entry::Occupied(slot) => deduped[*slot.get()] = entity,
+            Entry::Vacant(slot) => {
+                slot.insert(deduped.len());
+                deduped.push(entity);
+            }
+        }
+    }
+
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, entity) in deduped.iter().enumerate() {
+        groups.entry(entity.base_id()).or_default().push(idx);
+    }
+
+    for mut members in groups.into_values() {
+        if members.len() < 2 {
+            continue;
+        }
+        members.sort_by_cached_key(|&idx| {
+            let entity = &deduped[idx];
+            (
+                entity.kind.is_data(),
+                entity.arity(),
+                entity.param_types(),
+                entity.line_start,
+                idx,
+            )
+        });
+        let mut taken: Vec<String> = Vec::new();
+        for &idx in &members[1..] {
+            let arity = deduped[idx].arity();
+            let mut disc = arity.to_string();
+            let mut n = 2;
+            while taken.contains(&disc) {
+                disc = format!("{}.{}", arity, n);
+                n += 1;
+            }
+            taken.push(disc.clone());
+            deduped[idx].discriminator = Some(disc);
+        }
     }
-    Vec::new()
+
+    *entities = deduped;
 }
 
 // ---------------------------------------------------------------------------
@@ -286,7 +447,7 @@ fn extract_js_like_entities(path: &Path, source: &str, lang: Language) -> Vec<Ra
     };
 
     let mut entities = Vec::new();
-    extract_js_node(&tree.root_node(), path, source, None, &mut entities);
+    extract_js_node(&tree.root_node(), path, source, None, &mut entities, lang);
     entities
 }
 
@@ -296,6 +457,7 @@ fn extract_js_node(
     source: &str,
     parent_class: Option<&str>,
     entities: &mut Vec<RawEntity>,
+    lang: Language,
 ) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
@@ -323,6 +485,9 @@ fn extract_js_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_js_signature(&child, source),
+                        metadata: extract_metadata(&child, source, lang),
+                        signals: analyze_node(&child, source, lang),
+                        discriminator: None,
                     });
                 }
             }
@@ -344,9 +509,12 @@ fn extract_js_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, lang),
+                        signals: analyze_node(&child, source, lang),
+                        discriminator: None,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
-                        extract_js_node(&body, path, source, Some(class_name), entities);
+                        extract_js_node(&body, path, source, Some(class_name), entities, lang);
                     }
                 }
             }
@@ -363,6 +531,9 @@ fn extract_js_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, lang),
+                        signals: analyze_node(&child, source, lang),
+                        discriminator: None,
                     });
                 }
             }
@@ -378,6 +549,9 @@ fn extract_js_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_js_signature(&child, source),
+                        metadata: extract_metadata(&child, source, lang),
+                        signals: analyze_node(&child, source, lang),
+                        discriminator: None,
                     });
                 }
             }
@@ -416,6 +590,9 @@ fn extract_js_node(
                                 parent_class: parent_class.map(String::from),
                                 source_text: source[child.byte_range()].to_string(),
                                 signature: None,
+                                metadata: extract_metadata(&decl, source, lang),
+                                signals: analyze_node(&decl, source, lang),
+                                discriminator: None,
                             });
                         } else if let Some(name_node) = decl.child_by_field_name("name") {
                             let name_kind = name_node.kind();
@@ -443,6 +620,9 @@ fn extract_js_node(
                                     parent_class: parent_class.map(String::from),
                                     source_text: source[child.byte_range()].to_string(),
                                     signature: None,
+                                    metadata: extract_metadata(&decl, source, lang),
+                                    signals: analyze_node(&decl, source, lang),
+                                    discriminator: None,
                                 });
                             } else {
                                 let name = &source[name_node.byte_range()];
@@ -456,6 +636,9 @@ fn extract_js_node(
                                         parent_class: parent_class.map(String::from),
                                         source_text: decl_source.to_string(),
                                         signature: None,
+                                        metadata: extract_metadata(&decl, source, lang),
+                                        signals: analyze_node(&decl, source, lang),
+                                        discriminator: None,
                                     });
                                     // Extract createSlice reducer keys as child entities
                                     if decl_source.contains("createSlice(") {
@@ -471,11 +654,11 @@ fn extract_js_node(
             }
             "export_statement" => {
                 // Recurse into export statements to find declarations
-                extract_js_node(&child, path, source, parent_class, entities);
+                extract_js_node(&child, path, source, parent_class, entities, lang);
             }
             _ => {
                 if parent_class.is_none() {
-                    extract_js_node(&child, path, source, None, entities);
+                    extract_js_node(&child, path, source, None, entities, lang);
                 }
             }
         }
@@ -643,6 +826,9 @@ fn extract_create_slice_reducers(
                     parent_class: Some(slice_name.to_string()),
                     source_text: source[child.byte_range()].to_string(),
                     signature: None,
+                    metadata: EntityMetadata::default(),
+                    signals: None,
+                    discriminator: None,
                 });
             }
         }
@@ -700,6 +886,9 @@ fn extract_destructured_hooks(
                 parent_class: parent_class.map(String::from),
                 source_text: source[outer_decl.byte_range()].to_string(),
                 signature: None,
+                metadata: EntityMetadata::default(),
+                signals: None,
+                discriminator: None,
             });
         }
     }
@@ -736,6 +925,9 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_go_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::GO),
+                        signals: analyze_node(&child, source, Language::GO),
+                        discriminator: None,
                     });
                 }
             }
@@ -766,6 +958,9 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
                         parent_class: receiver,
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_go_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::GO),
+                        signals: analyze_node(&child, source, Language::GO),
+                        discriminator: None,
                     });
                 }
             }
@@ -786,6 +981,9 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
                             parent_class: None,
                             source_text: source[spec.byte_range()].to_string(),
                             signature: None,
+                            metadata: extract_metadata(&spec, source, Language::GO),
+                            signals: analyze_node(&spec, source, Language::GO),
+                            discriminator: None,
                         });
                     }
                 }
@@ -841,6 +1039,9 @@ fn extract_java_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::JAVA),
+                        signals: analyze_node(&child, source, Language::JAVA),
+                        discriminator: None,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_java_node(&body, path, source, Some(class_name), entities);
@@ -859,6 +1060,9 @@ fn extract_java_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_java_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::JAVA),
+                        signals: analyze_node(&child, source, Language::JAVA),
+                        discriminator: None,
                     });
                 }
             }
@@ -927,6 +1131,9 @@ fn extract_c_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_c_signature(&child, source),
+                        metadata: extract_metadata(&child, source, lang),
+                        signals: analyze_node(&child, source, lang),
+                        discriminator: None,
                     });
                 }
             }
@@ -942,6 +1149,9 @@ fn extract_c_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, lang),
+                        signals: analyze_node(&child, source, lang),
+                        discriminator: None,
                     });
                     // C++: recurse into class/struct body for methods
                     if lang == Language::CPP
@@ -1030,6 +1240,9 @@ fn extract_csharp_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::CSHARP),
+                        signals: analyze_node(&child, source, Language::CSHARP),
+                        discriminator: None,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_csharp_node(&body, path, source, Some(class_name), entities);
@@ -1048,6 +1261,9 @@ fn extract_csharp_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_csharp_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::CSHARP),
+                        signals: analyze_node(&child, source, Language::CSHARP),
+                        discriminator: None,
                     });
                 }
             }
@@ -1107,6 +1323,9 @@ fn extract_php_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::PHP),
+                        signals: analyze_node(&child, source, Language::PHP),
+                        discriminator: None,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_php_node(&body, path, source, Some(class_name), entities);
@@ -1125,6 +1344,9 @@ fn extract_php_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::PHP),
+                        signals: analyze_node(&child, source, Language::PHP),
+                        discriminator: None,
                     });
                 }
             }
@@ -1140,6 +1362,9 @@ fn extract_php_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::PHP),
+                        signals: analyze_node(&child, source, Language::PHP),
+                        discriminator: None,
                     });
                 }
             }
@@ -1191,6 +1416,9 @@ fn extract_ruby_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::RUBY),
+                        signals: analyze_node(&child, source, Language::RUBY),
+                        discriminator: None,
                     });
                     // Recurse into class/module body for methods
                     if let Some(body) = child.child_by_field_name("body") {
@@ -1215,6 +1443,9 @@ fn extract_ruby_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::RUBY),
+                        signals: analyze_node(&child, source, Language::RUBY),
+                        discriminator: None,
                     });
                 }
             }
@@ -1230,6 +1461,9 @@ fn extract_ruby_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::RUBY),
+                        signals: analyze_node(&child, source, Language::RUBY),
+                        discriminator: None,
                     });
                 }
             }
@@ -1281,6 +1515,9 @@ fn extract_kotlin_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::KOTLIN),
+                        signals: analyze_node(&child, source, Language::KOTLIN),
+                        discriminator: None,
                     });
                     // kotlin-ng uses "class_body" / "enum_class_body" child nodes (not a "body" field)
                     let body = child.child_by_field_name("body").or_else(|| {
@@ -1311,6 +1548,9 @@ fn extract_kotlin_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::KOTLIN),
+                        signals: analyze_node(&child, source, Language::KOTLIN),
+                        discriminator: None,
                     });
                 }
             }
@@ -1365,6 +1605,9 @@ fn extract_swift_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::SWIFT),
+                        signals: analyze_node(&child, source, Language::SWIFT),
+                        discriminator: None,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_swift_node(&body, path, source, Some(class_name), entities);
@@ -1388,6 +1631,9 @@ fn extract_swift_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::SWIFT),
+                        signals: analyze_node(&child, source, Language::SWIFT),
+                        discriminator: None,
                     });
                 }
             }
@@ -1402,6 +1648,9 @@ fn extract_swift_node(
                     parent_class: parent_class.map(String::from),
                     source_text: source[child.byte_range()].to_string(),
                     signature: None,
+                    metadata: extract_metadata(&child, source, Language::SWIFT),
+                    signals: analyze_node(&child, source, Language::SWIFT),
+                    discriminator: None,
                 });
             }
             "extension_declaration" => {
@@ -1462,6 +1711,9 @@ fn extract_scala_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::SCALA),
+                        signals: analyze_node(&child, source, Language::SCALA),
+                        discriminator: None,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_scala_node(&body, path, source, Some(class_name), entities);
@@ -1485,6 +1737,9 @@ fn extract_scala_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        metadata: extract_metadata(&child, source, Language::SCALA),
+                        signals: analyze_node(&child, source, Language::SCALA),
+                        discriminator: None,
                     });
                 }
             }
@@ -1541,6 +1796,9 @@ fn extract_bash_node(
                     parent_class: None,
                     source_text: source[child.byte_range()].to_string(),
                     signature: None,
+                    metadata: extract_metadata(&child, source, Language::BASH),
+                    signals: analyze_node(&child, source, Language::BASH),
+                    discriminator: None,
                 });
             }
         } else {
@@ -1549,6 +1807,673 @@ fn extract_bash_node(
     }
 }
 
+// ---------------------------------------------------------------------------
+// Lua
+// ---------------------------------------------------------------------------
+
+/// Extract entities from a Lua source file.
+///
+/// `function M.f()` and `function M:f()` become methods of `M`, and the module
+/// table they are attached to (`local M = {}`) is reported as a class.
+pub fn extract_lua_entities(path: &Path, source: &str) -> Vec<RawEntity> {
+    let lang = Language::LUA.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return Vec::new();
+    };
+    let mut entities = Vec::new();
+    let root = tree.root_node();
+    extract_lua_node(&root, path, source, &mut entities);
+    extract_lua_tables(&root, path, source, &mut entities);
+    entities.sort_by_key(|e| e.line_start);
+    entities
+}
+
+fn extract_lua_node(
+    node: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    entities: &mut Vec<RawEntity>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "function_declaration" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    let (name, table) = lua_function_name(&name_node, source);
+                    entities.push(RawEntity {
+                        name,
+                        kind: if table.is_some() {
+                            EntityKind::Method
+                        } else {
+                            EntityKind::Function
+                        },
+                        file: path.to_path_buf(),
+                        line_start: child.start_position().row + 1,
+                        line_end: child.end_position().row + 1,
+                        parent_class: table,
+                        source_text: source[child.byte_range()].to_string(),
+                        signature: extract_lua_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::LUA),
+                        signals: analyze_node(&child, source, Language::LUA),
+                        discriminator: None,
+                    });
+                }
+            }
+            "variable_declaration" | "assignment_statement" => {
+                // `local f = function() end` / `M.f = function() end`
+                for (target, value) in lua_assignments(&child) {
+                    if value.kind() != "function_definition" {
+                        continue;
+                    }
+                    let (name, table) = lua_function_name(&target, source);
+                    entities.push(RawEntity {
+                        name,
+                        kind: if table.is_some() {
+                            EntityKind::Method
+                        } else {
+                            EntityKind::Function
+                        },
+                        file: path.to_path_buf(),
+                        line_start: child.start_position().row + 1,
+                        line_end: child.end_position().row + 1,
+                        parent_class: table,
+                        source_text: source[child.byte_range()].to_string(),
+                        signature: extract_lua_signature(&value, source),
+                        metadata: extract_metadata(&child, source, Language::LUA),
+                        signals: analyze_node(&value, source, Language::LUA),
+                        discriminator: None,
+                    });
+                }
+            }
+            _ => {
+                extract_lua_node(&child, path, source, entities);
+            }
+        }
+    }
+}
+
+/// Report top-level tables that received methods as classes.
+fn extract_lua_tables(
+    root: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    entities: &mut Vec<RawEntity>,
+) {
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if !matches!(
+            child.kind(),
+            "variable_declaration" | "assignment_statement"
+        ) {
+            continue;
+        }
+        for (target, value) in lua_assignments(&child) {
+            let is_table = match value.kind() {
+                "table_constructor" => true,
+                "function_call" => value
+                    .child_by_field_name("name")
+                    .is_some_and(|n| &source[n.byte_range()] == "setmetatable"),
+                _ => false,
+            };
+            let name = &source[target.byte_range()];
+            if !is_table
+                || target.kind() != "identifier"
+                || !entities
+                    .iter()
+                    .any(|e| e.parent_class.as_deref() == Some(name))
+                || entities
+                    .iter()
+                    .any(|e| e.kind == EntityKind::Class && e.name == name)
+            {
+                continue;
+            }
+            entities.push(RawEntity {
+                name: name.to_string(),
+                kind: EntityKind::Class,
+                file: path.to_path_buf(),
+                line_start: child.start_position().row + 1,
+                line_end: child.end_position().row + 1,
+                parent_class: None,
+                source_text: source[child.byte_range()].to_string(),
+                signature: None,
+                metadata: extract_metadata(&child, source, Language::LUA),
+                signals: None,
+                discriminator: None,
+            });
+        }
+    }
+}
+
+/// Pair the targets and values of a Lua assignment (`a, b = x, y`).
+pub(crate) fn lua_assignments<'a>(
+    node: &tree_sitter::Node<'a>,
+) -> Vec<(tree_sitter::Node<'a>, tree_sitter::Node<'a>)> {
+    let statement = if node.kind() == "assignment_statement" {
+        *node
+    } else {
+        match find_child_kind(node, "assignment_statement") {
+            Some(s) => s,
+            None => return Vec::new(),
+        }
+    };
+    let (Some(targets), Some(values)) = (
+        find_child_kind(&statement, "variable_list"),
+        find_child_kind(&statement, "expression_list"),
+    ) else {
+        return Vec::new();
+    };
+    let mut tc = targets.walk();
+    let mut vc = values.walk();
+    targets
+        .children_by_field_name("name", &mut tc)
+        .zip(values.children_by_field_name("value", &mut vc))
+        .collect()
+}
+
+/// Split a Lua function name into `(name, table)`: `M.f` and `M:f` yield
+/// `("f", Some("M"))`, a bare identifier yields `(name, None)`.
+pub(crate) fn lua_function_name(
+    name_node: &tree_sitter::Node,
+    source: &str,
+) -> (String, Option<String>) {
+    let field = match name_node.kind() {
+        "dot_index_expression" => "field",
+        "method_index_expression" => "method",
+        _ => return (source[name_node.byte_range()].to_string(), None),
+    };
+    match (
+        name_node.child_by_field_name(field),
+        name_node.child_by_field_name("table"),
+    ) {
+        (Some(name), Some(table)) => (
+            source[name.byte_range()].to_string(),
+            Some(source[table.byte_range()].to_string()),
+        ),
+        _ => (source[name_node.byte_range()].to_string(), None),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Elixir
+// ---------------------------------------------------------------------------
+
+/// Extract entities from an Elixir source file.
+///
+/// Everything in Elixir is a macro call, so modules (`defmodule`,
+/// `defprotocol`, `defimpl`) and functions (`def`, `defp`, `defmacro`, ...)
+/// are recognized by the call target. Consecutive clauses of the same
+/// function are merged into one entity.
+pub fn extract_elixir_entities(path: &Path, source: &str) -> Vec<RawEntity> {
+    let lang = Language::ELIXIR.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return Vec::new();
+    };
+    let mut entities = Vec::new();
+    extract_elixir_node(&tree.root_node(), path, source, None, &mut entities);
+    entities
+}
+
+/// Elixir keywords that define a named function or macro.
+pub(crate) const ELIXIR_DEF_KEYWORDS: &[&str] = &[
+    "def",
+    "defp",
+    "defmacro",
+    "defmacrop",
+    "defguard",
+    "defguardp",
+    "defdelegate",
+];
+
+fn extract_elixir_node(
+    node: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    parent_class: Option<&str>,
+    entities: &mut Vec<RawEntity>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let keyword = if child.kind() == "call" {
+            elixir_call_target(&child, source)
+        } else {
+            None
+        };
+        match keyword {
+            Some(kw @ ("defmodule" | "defprotocol" | "defimpl")) => {
+                let Some(class_name) = elixir_module_name(&child, kw, source, parent_class) else {
+                    continue;
+                };
+                entities.push(RawEntity {
+                    name: class_name.clone(),
+                    kind: EntityKind::Class,
+                    file: path.to_path_buf(),
+                    line_start: child.start_position().row + 1,
+                    line_end: child.end_position().row + 1,
+                    parent_class: parent_class.map(String::from),
+                    source_text: source[child.byte_range()].to_string(),
+                    signature: None,
+                    metadata: extract_metadata(&child, source, Language::ELIXIR),
+                    signals: None,
+                    discriminator: None,
+                });
+                if let Some(body) = find_direct_child_kind(&child, "do_block") {
+                    extract_elixir_node(&body, path, source, Some(&class_name), entities);
+                }
+            }
+            Some(kw) if ELIXIR_DEF_KEYWORDS.contains(&kw) => {
+                let Some(head) = elixir_function_head(&child) else {
+                    continue;
+                };
+                let Some(name) = elixir_call_target(&head, source)
+                    .or_else(|| (head.kind() == "identifier").then(|| &source[head.byte_range()]))
+                else {
+                    continue;
+                };
+                let signature = extract_elixir_signature(&head, source);
+                let text = &source[child.byte_range()];
+                // Merge a further clause of the function defined just before.
+                if let Some(prev) = entities.last_mut()
+                    && prev.name == name
+                    && prev.parent_class.as_deref() == parent_class
+                    && prev.kind != EntityKind::Class
+                    && prev.arity() == signature.as_ref().map_or(0, |s| s.parameters.len())
+                {
+                    prev.line_end = child.end_position().row + 1;
+                    prev.source_text.push('\n');
+                    prev.source_text.push_str(text);
+                    continue;
+                }
+                entities.push(RawEntity {
+                    name: name.to_string(),
+                    kind: if parent_class.is_some() {
+                        EntityKind::Method
+                    } else {
+                        EntityKind::Function
+                    },
+                    file: path.to_path_buf(),
+                    line_start: child.start_position().row + 1,
+                    line_end: child.end_position().row + 1,
+                    parent_class: parent_class.map(String::from),
+                    source_text: text.to_string(),
+                    signature,
+                    metadata: extract_metadata(&child, source, Language::ELIXIR),
+                    signals: analyze_node(&child, source, Language::ELIXIR),
+                    discriminator: None,
+                });
+            }
+            _ => {
+                extract_elixir_node(&child, path, source, parent_class, entities);
+            }
+        }
+    }
+}
+
+/// The identifier a call is made on (`def` in `def foo`), if it is a plain identifier.
+pub(crate) fn elixir_call_target<'s>(node: &tree_sitter::Node, source: &'s str) -> Option<&'s str> {
+    if node.kind() != "call" {
+        return None;
+    }
+    let target = node.child_by_field_name("target")?;
+    (target.kind() == "identifier").then(|| &source[target.byte_range()])
+}
+
+/// The function head of a `def` call: `foo(a, b)` in `def foo(a, b) when a > 0`.
+pub(crate) fn elixir_function_head<'a>(
+    def: &tree_sitter::Node<'a>,
+) -> Option<tree_sitter::Node<'a>> {
+    let args = find_direct_child_kind(def, "arguments")?;
+    let mut cursor = args.walk();
+    let mut head = args.named_children(&mut cursor).next()?;
+    // Guards: `foo(x) when is_integer(x)`
+    while head.kind() == "binary_operator" {
+        head = head.child_by_field_name("left")?;
+    }
+    Some(head)
+}
+
+/// Module name for `defmodule`/`defprotocol`/`defimpl`. Implementations are
+/// named after the protocol and the implementing type (`Size.Map`).
+fn elixir_module_name(
+    node: &tree_sitter::Node,
+    keyword: &str,
+    source: &str,
+    parent_class: Option<&str>,
+) -> Option<String> {
+    let args = find_direct_child_kind(node, "arguments")?;
+    let mut cursor = args.walk();
+    let first = args.named_children(&mut cursor).next()?;
+    let name = source[first.byte_range()].to_string();
+    if keyword != "defimpl" {
+        return Some(name);
+    }
+    let target = find_direct_child_kind(&args, "keywords")
+        .and_then(|kw| elixir_keyword_value(&kw, "for", source))
+        .or_else(|| parent_class.map(String::from));
+    Some(match target {
+        Some(target) => format!("{}.{}", name, target),
+        None => name,
+    })
+}
+
+/// The value of `key:` in an Elixir keyword list node.
+pub(crate) fn elixir_keyword_value(
+    keywords: &tree_sitter::Node,
+    key: &str,
+    source: &str,
+) -> Option<String> {
+    let mut cursor = keywords.walk();
+    keywords
+        .named_children(&mut cursor)
+        .filter(|pair| pair.kind() == "pair")
+        .find(|pair| {
+            pair.child_by_field_name("key")
+                .is_some_and(|k| source[k.byte_range()].trim().trim_end_matches(':') == key)
+        })
+        .and_then(|pair| pair.child_by_field_name("value"))
+        .map(|v| source[v.byte_range()].to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Dart
+// ---------------------------------------------------------------------------
+
+/// Extract entities from a Dart source file.
+pub fn extract_dart_entities(path: &Path, source: &str) -> Vec<RawEntity> {
+    let lang = Language::DART.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return Vec::new();
+    };
+    let mut entities = Vec::new();
+    extract_dart_node(&tree.root_node(), path, source, None, &mut entities);
+    entities
+}
+
+fn extract_dart_node(
+    node: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    parent_class: Option<&str>,
+    entities: &mut Vec<RawEntity>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "class_declaration"
+            | "mixin_declaration"
+            | "extension_declaration"
+            | "enum_declaration" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    let class_name = &source[name_node.byte_range()];
+                    entities.push(RawEntity {
+                        name: class_name.to_string(),
+                        kind: EntityKind::Class,
+                        file: path.to_path_buf(),
+                        line_start: child.start_position().row + 1,
+                        line_end: child.end_position().row + 1,
+                        parent_class: parent_class.map(String::from),
+                        source_text: source[child.byte_range()].to_string(),
+                        signature: None,
+                        metadata: extract_metadata(&child, source, Language::DART),
+                        signals: None,
+                        discriminator: None,
+                    });
+                    if let Some(body) = child.child_by_field_name("body") {
+                        extract_dart_node(&body, path, source, Some(class_name), entities);
+                    }
+                }
+            }
+            "function_declaration" | "method_declaration" | "declaration" => {
+                // `declaration` covers constructors and abstract members; fields have
+                // no signature and are skipped.
+                let Some(signature_node) = dart_signature_node(&child) else {
+                    continue;
+                };
+                let Some(name) = dart_member_name(&signature_node, source) else {
+                    continue;
+                };
+                entities.push(RawEntity {
+                    name,
+                    kind: if parent_class.is_some() {
+                        EntityKind::Method
+                    } else {
+                        EntityKind::Function
+                    },
+                    file: path.to_path_buf(),
+                    line_start: child.start_position().row + 1,
+                    line_end: child.end_position().row + 1,
+                    parent_class: parent_class.map(String::from),
+                    source_text: source[child.byte_range()].to_string(),
+                    signature: extract_dart_signature(&signature_node, source),
+                    metadata: extract_metadata(&child, source, Language::DART),
+                    signals: analyze_node(&child, source, Language::DART),
+                    discriminator: None,
+                });
+            }
+            _ => {
+                extract_dart_node(&child, path, source, parent_class, entities);
+            }
+        }
+    }
+}
+
+/// The innermost `*_signature` node of a Dart function, method or constructor.
+pub(crate) fn dart_signature_node<'a>(
+    node: &tree_sitter::Node<'a>,
+) -> Option<tree_sitter::Node<'a>> {
+    let mut current = match node.child_by_field_name("signature") {
+        Some(signature) => signature,
+        None => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .find(|c| c.kind().ends_with("_signature"))?
+        }
+    };
+    // method_signature wraps the function/getter/setter/constructor signature
+    while current.kind() == "method_signature" {
+        let mut cursor = current.walk();
+        current = current
+            .named_children(&mut cursor)
+            .find(|c| c.kind().ends_with("_signature"))?;
+    }
+    Some(current)
+}
+
+/// Name of a Dart signature; named constructors join their parts (`Point.origin`).
+pub(crate) fn dart_member_name(signature: &tree_sitter::Node, source: &str) -> Option<String> {
+    let mut cursor = signature.walk();
+    let parts: Vec<&str> = signature
+        .children_by_field_name("name", &mut cursor)
+        .filter(|n| n.is_named())
+        .map(|n| &source[n.byte_range()])
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("."))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Zig
+// ---------------------------------------------------------------------------
+
+/// Extract entities from a Zig source file.
+///
+/// `const Name = struct { ... }` (and enum/union/opaque containers) become
+/// classes, with the functions declared inside them as methods.
+pub fn extract_zig_entities(path: &Path, source: &str) -> Vec<RawEntity> {
+    let lang = Language::ZIG.ts_language();
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&lang).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return Vec::new();
+    };
+    let mut entities = Vec::new();
+    extract_zig_node(&tree.root_node(), path, source, None, &mut entities);
+    entities
+}
+
+fn extract_zig_node(
+    node: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    parent_class: Option<&str>,
+    entities: &mut Vec<RawEntity>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "function_declaration" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    let name = &source[name_node.byte_range()];
+                    entities.push(RawEntity {
+                        name: name.to_string(),
+                        kind: if parent_class.is_some() {
+                            EntityKind::Method
+                        } else {
+                            EntityKind::Function
+                        },
+                        file: path.to_path_buf(),
+                        line_start: child.start_position().row + 1,
+                        line_end: child.end_position().row + 1,
+                        parent_class: parent_class.map(String::from),
+                        source_text: source[child.byte_range()].to_string(),
+                        signature: extract_zig_signature(&child, source),
+                        metadata: extract_metadata(&child, source, Language::ZIG),
+                        signals: analyze_node(&child, source, Language::ZIG),
+                        discriminator: None,
+                    });
+                }
+            }
+            "variable_declaration" => {
+                if let Some(container) = zig_container(&child)
+                    && let Some(name_node) = find_direct_child_kind(&child, "identifier")
+                {
+                    let class_name = &source[name_node.byte_range()];
+                    entities.push(RawEntity {
+                        name: class_name.to_string(),
+                        kind: EntityKind::Class,
+                        file: path.to_path_buf(),
+                        line_start: child.start_position().row + 1,
+                        line_end: child.end_position().row + 1,
+                        parent_class: parent_class.map(String::from),
+                        source_text: source[child.byte_range()].to_string(),
+                        signature: None,
+                        metadata: extract_metadata(&child, source, Language::ZIG),
+                        signals: None,
+                        discriminator: None,
+                    });
+                    extract_zig_node(&container, path, source, Some(class_name), entities);
+                } else {
+                    extract_zig_node(&child, path, source, parent_class, entities);
+                }
+            }
+            _ => {
+                extract_zig_node(&child, path, source, parent_class, entities);
+            }
+        }
+    }
+}
+
+/// The container type (`struct`, `enum`, `union`, `opaque`) a Zig declaration binds, if any.
+pub(crate) fn zig_container<'a>(
+    declaration: &tree_sitter::Node<'a>,
+) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = declaration.walk();
+    declaration.named_children(&mut cursor).find(|c| {
+        matches!(
+            c.kind(),
+            "struct_declaration" | "enum_declaration" | "union_declaration" | "opaque_declaration"
+        )
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Embedded regions (Vue, Svelte, Astro, HTML, Markdown)
+// ---------------------------------------------------------------------------
+
+/// Extract entities from the embedded regions of a host file.
+///
+/// Each region is extracted with its own language; entities keep the host
+/// path, and their lines refer to the host file.
+pub fn extract_embedded_entities(path: &Path, source: &str) -> Vec<RawEntity> {
+    crate::embedded::split_regions(path, source)
+        .into_iter()
+        .flat_map(|region| extract_entities(path, &region.source, region.language))
+        .collect()
+}
+
+/// Extract entities from the Python code cells of a Jupyter notebook.
+///
+/// Lines refer to the notebook's script (see [`rpg_core::notebook`]) and each
+/// entity records its cell. Code outside any function or class becomes a
+/// Module entity named after the notebook.
+pub fn extract_notebook_entities(path: &Path, source: &str) -> Vec<RawEntity> {
+    let Some(script) = rpg_core::notebook::Script::parse(source) else {
+        return Vec::new();
+    };
+    if script.is_empty() {
+        return Vec::new();
+    }
+    let mut entities = extract_entities(path, &script.source, Language::PYTHON);
+    for entity in &mut entities {
+        entity.metadata.cell = script.cell_at(entity.line_start);
+    }
+
+    let top_level: Vec<&str> = script
+        .source
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| {
+            !entities
+                .iter()
+                .any(|e| !e.kind.is_data() && (e.line_start..=e.line_end).contains(&(i + 1)))
+        })
+        .map(|(_, line)| line)
+        .collect();
+    entities.insert(
+        0,
+        RawEntity {
+            name: notebook_module_name(path),
+            kind: EntityKind::Module,
+            file: path.to_path_buf(),
+            line_start: 1,
+            line_end: script.source.lines().count(),
+            parent_class: None,
+            source_text: top_level.join("\n").trim().to_string(),
+            signature: None,
+            metadata: EntityMetadata::default(),
+            signals: None,
+            discriminator: None,
+        },
+    );
+    disambiguate_ids(&mut entities);
+    entities
+}
+
+/// Name of a notebook's Module entity: the file stem, as for the Module
+/// entities the graph creates for other files.
+pub fn notebook_module_name(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("module")
+        .to_string()
+}
+
 // ---------------------------------------------------------------------------
 // Signature extraction helpers
 // ---------------------------------------------------------------------------
@@ -1932,6 +2857,147 @@ pub fn extract_csharp_signature(node: &tree_sitter::Node, source: &str) -> Optio
     })
 }
 
+/// Extract signature from a Lua function_declaration or function_definition.
+pub fn extract_lua_signature(node: &tree_sitter::Node, source: &str) -> Option<RawSignature> {
+    let params_node = node.child_by_field_name("parameters")?;
+    let mut params = Vec::new();
+    let mut cursor = params_node.walk();
+    for child in params_node.named_children(&mut cursor) {
+        match child.kind() {
+            "identifier" => params.push(RawParam {
+                name: source[child.byte_range()].to_string(),
+                type_annotation: None,
+            }),
+            "vararg_expression" => params.push(RawParam {
+                name: "...".to_string(),
+                type_annotation: None,
+            }),
+            _ => {}
+        }
+    }
+    Some(RawSignature {
+        parameters: params,
+        return_type: None,
+    })
+}
+
+/// Extract signature from an Elixir function head (`foo(a, b \\ 1)`).
+///
+/// Pattern-matched parameters keep their pattern text as the name; default
+/// values (`\\`) are dropped.
+pub fn extract_elixir_signature(head: &tree_sitter::Node, source: &str) -> Option<RawSignature> {
+    let mut params = Vec::new();
+    if let Some(args) = find_direct_child_kind(head, "arguments") {
+        let mut cursor = args.walk();
+        for child in args.named_children(&mut cursor) {
+            let param = if child.kind() == "binary_operator"
+                && let Some(left) = child.child_by_field_name("left")
+                && source[left.end_byte()..child.end_byte()]
+                    .trim_start()
+                    .starts_with("\\\\")
+            {
+                left
+            } else {
+                child
+            };
+            params.push(RawParam {
+                name: source[param.byte_range()].to_string(),
+                type_annotation: None,
+            });
+        }
+    }
+    Some(RawSignature {
+        parameters: params,
+        return_type: None,
+    })
+}
+
+/// Extract signature from a Dart `*_signature` node (see [`dart_signature_node`]).
+pub fn extract_dart_signature(node: &tree_sitter::Node, source: &str) -> Option<RawSignature> {
+    let mut params = Vec::new();
+    if let Some(list) = node.child_by_field_name("parameters") {
+        collect_dart_params(&list, source, &mut params);
+    }
+    let return_type = node
+        .child_by_field_name("return_type")
+        .map(|t| source[t.byte_range()].to_string());
+    Some(RawSignature {
+        parameters: params,
+        return_type,
+    })
+}
+
+fn collect_dart_params(list: &tree_sitter::Node, source: &str, params: &mut Vec<RawParam>) {
+    let mut cursor = list.walk();
+    for child in list.named_children(&mut cursor) {
+        match child.kind() {
+            // `[optional]` and `{named}` groups
+            "optional_formal_parameters" => collect_dart_params(&child, source, params),
+            "formal_parameter" => {
+                // `this.count` / `super.key` constructor parameters carry the
+                // name on an inner identifier
+                let name = child.child_by_field_name("name").or_else(|| {
+                    let mut c = child.walk();
+                    child
+                        .named_children(&mut c)
+                        .find(|n| {
+                            matches!(n.kind(), "constructor_param" | "super_formal_parameter")
+                        })
+                        .and_then(|p| {
+                            let mut pc = p.walk();
+                            p.named_children(&mut pc)
+                                .filter(|n| n.kind() == "identifier")
+                                .last()
+                        })
+                });
+                if let Some(name) = name {
+                    params.push(RawParam {
+                        name: source[name.byte_range()].to_string(),
+                        type_annotation: find_child_kind(&child, "type")
+                            .map(|t| source[t.byte_range()].to_string()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Extract signature from a Zig function_declaration. An explicit `self`
+/// receiver is skipped, as for Rust and Python.
+pub fn extract_zig_signature(node: &tree_sitter::Node, source: &str) -> Option<RawSignature> {
+    let params_node = find_direct_child_kind(node, "parameters")?;
+    let mut params = Vec::new();
+    let mut cursor = params_node.walk();
+    for child in params_node.named_children(&mut cursor) {
+        if child.kind() != "parameter" {
+            continue;
+        }
+        let Some(name) = child
+            .child_by_field_name("name")
+            .map(|n| source[n.byte_range()].to_string())
+        else {
+            continue;
+        };
+        if name == "self" {
+            continue;
+        }
+        params.push(RawParam {
+            name,
+            type_annotation: child
+                .child_by_field_name("type")
+                .map(|t| source[t.byte_range()].to_string()),
+        });
+    }
+    let return_type = node
+        .child_by_field_name("type")
+        .map(|t| source[t.byte_range()].to_string());
+    Some(RawSignature {
+        parameters: params,
+        return_type,
+    })
+}
+
 /// Find the first child of a specific kind (helper for C signature extraction).
 fn find_child_kind<'a>(node: &tree_sitter::Node<'a>, kind: &str) -> Option<tree_sitter::Node<'a>> {
     let mut cursor = node.walk();
@@ -1945,3 +3011,12 @@ fn find_child_kind<'a>(node: &tree_sitter::Node<'a>, kind: &str) -> Option<tree_
     }
     None
 }
+
+/// Find the first direct (non-recursive) child of a specific kind.
+fn find_direct_child_kind<'a>(
+    node: &tree_sitter::Node<'a>,
+    kind: &str,
+) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find(|c| c.kind() == kind)
+}
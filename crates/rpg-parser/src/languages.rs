@@ -3,10 +3,16 @@
 //! All types and methods are auto-generated from `languages/defs/*.toml`
 //! by build.rs. Adding a new language requires only a TOML file +
 //! the tree-sitter grammar dependency — zero Rust source changes.
+//! Languages without a builtin extractor describe their entities and
+//! dependencies with tree-sitter queries (see [`queries`]); projects and
+//! users can extend registered languages at runtime (see [`user`]).
+
+pub mod queries;
+pub mod user;
 
 // Generated: LangId struct, Language alias, from_extension, from_name,
 // name, glob_pattern, ts_language, detect_primary, detect_all,
 // grammar_for, expand_lang_aliases, effective_grammar_name,
 // builtin_entity_extractor, builtin_dep_extractor_name,
-// builtin_entity_extractor_name
+// builtin_entity_extractor_name, query_def_source
 include!(concat!(env!("OUT_DIR"), "/lang_registry.rs"));
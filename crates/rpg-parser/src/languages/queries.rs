@@ -0,0 +1,491 @@
+//! Query-driven entity and dependency extraction.
+//!
+//! A language TOML may declare a `[queries]` section of tree-sitter queries
+//! instead of naming `[builtin]` extractors. When both are present the builtin
+//! extractor wins, so the queries only take effect for languages without one.
+//!
+//! Capture conventions:
+//!
+//! - `[[queries.entities]]` (`kind` + `query`): `@name` (required),
+//!   `@definition` (the declaration node; defaults to the parent of `@name`),
+//!   `@parent` (explicit parent name; otherwise the innermost enclosing class),
+//!   `@params` (repeatable) and `@return_type`. Functions nested in a class
+//!   become methods.
+//! - `parameters`: run inside each `@params` node; `@name` plus optional `@type`.
+//! - `imports`: `@module` plus any number of `@symbol`.
+//! - `calls`: `@callee`; the caller is the enclosing function or method.
+//! - `inherits`: `@parent`, plus optional `@child` (defaults to the innermost
+//!   enclosing class).
+
+use crate::deps::{CallDep, FunctionScope, ImportDep, InheritDep, RawDeps, find_enclosing_scope};
+use crate::entities::{RawEntity, RawParam, RawSignature};
+use crate::languages::Language;
+use crate::metadata::extract_metadata;
+use crate::paradigms::defs::parse_entity_kind;
+use crate::signals::analyze_node;
+use rpg_core::graph::EntityKind;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::OnceLock;
+use tree_sitter::{Node, Query, QueryCursor, StreamingIterator};
+
+/// The `[queries]` section of a language TOML.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LanguageQueries {
+    #[serde(default)]
+    pub entities: Vec<EntityPattern>,
+    #[serde(default)]
+    pub parameters: Option<String>,
+    #[serde(default)]
+    pub imports: Vec<String>,
+    #[serde(default)]
+    pub calls: Vec<String>,
+    #[serde(default)]
+    pub inherits: Vec<String>,
+}
+
+/// One `[[queries.entities]]` entry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntityPattern {
+    /// Entity kind name, as accepted by paradigm defs (`function`, `class`, ...).
+    pub kind: String,
+    pub query: String,
+}
+
+#[derive(Deserialize)]
+struct QueriesFile {
+    queries: Option<LanguageQueries>,
+}
+
+impl LanguageQueries {
+    /// Read the `[queries]` section from a language TOML, if it has one.
+    pub fn from_toml(content: &str) -> Result<Option<Self>, String> {
+        toml::from_str::<QueriesFile>(content)
+            .map(|f| f.queries)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Compile every query against `grammar`, checking the required captures.
+    pub fn compile(&self, grammar: &tree_sitter::Language) -> Result<CompiledQueries, String> {
+        let compile = |section: &str, source: &str, required: &str| {
+            let query = Query::new(grammar, source).map_err(|e| format!("{section}: {e}"))?;
+            if query.capture_index_for_name(required).is_none() {
+                return Err(format!("{section}: query has no @{required} capture"));
+            }
+            Ok(query)
+        };
+        let compile_all = |section: &str, sources: &[String], required: &str| {
+            sources
+                .iter()
+                .map(|s| compile(section, s, required))
+                .collect::<Result<Vec<_>, String>>()
+        };
+
+        let entities = self
+            .entities
+            .iter()
+            .map(|p| {
+                let kind = parse_entity_kind(&p.kind)
+                    .ok_or_else(|| format!("entities: unknown kind '{}'", p.kind))?;
+                Ok((kind, compile("entities", &p.query, "name")?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let parameters = self
+            .parameters
+            .as_deref()
+            .map(|s| compile("parameters", s, "name"))
+            .transpose()?;
+
+        Ok(CompiledQueries {
+            entities,
+            parameters,
+            imports: compile_all("imports", &self.imports, "module")?,
+            calls: compile_all("calls", &self.calls, "callee")?,
+            inherits: compile_all("inherits", &self.inherits, "parent")?,
+        })
+    }
+}
+
+/// A language's `[queries]` section compiled against its grammar.
+pub struct CompiledQueries {
+    entities: Vec<(EntityKind, Query)>,
+    parameters: Option<Query>,
+    imports: Vec<Query>,
+    calls: Vec<Query>,
+    inherits: Vec<Query>,
+}
+
+/// Compiled queries of a built-in language def, or `None` when the def has no
+/// `[queries]` section. Invalid sections are reported once and then ignored.
+pub fn builtin_queries(language: Language) -> Option<&'static CompiledQueries> {
+    static COMPILED: OnceLock<Vec<Option<CompiledQueries>>> = OnceLock::new();
+    COMPILED
+        .get_or_init(|| {
+            (0..Language::count())
+                .map(|idx| {
+                    let lang = Language::from_index(idx);
+                    let source = super::query_def_source(lang)?;
+                    let compiled = LanguageQueries::from_toml(source)
+                        .and_then(|q| q.unwrap_or_default().compile(&lang.ts_language()));
+                    compiled
+                        .map_err(|e| {
+                            eprintln!(
+                                "warning: invalid [queries] in language def '{}': {}",
+                                lang.name(),
+                                e
+                            );
+                        })
+                        .ok()
+                })
+                .collect()
+        })
+        .get(language.index())?
+        .as_ref()
+}
+
+/// A definition matched by an entity query, with its parent resolved.
+struct Definition<'t> {
+    name: String,
+    kind: EntityKind,
+    node: Node<'t>,
+    parent: Option<String>,
+    params: Vec<Node<'t>>,
+    return_type: Option<String>,
+    has_signature: bool,
+}
+
+fn parse(source: &str, language: Language) -> Option<tree_sitter::Tree> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language.ts_language()).ok()?;
+    parser.parse(source.as_bytes(), None)
+}
+
+fn capture_node<'t>(
+    query: &Query,
+    m: &tree_sitter::QueryMatch<'_, 't>,
+    name: &str,
+) -> Option<Node<'t>> {
+    let idx = query.capture_index_for_name(name)?;
+    m.captures.iter().find(|c| c.index == idx).map(|c| c.node)
+}
+
+fn capture_nodes<'t>(
+    query: &Query,
+    m: &tree_sitter::QueryMatch<'_, 't>,
+    name: &str,
+) -> Vec<Node<'t>> {
+    query
+        .capture_index_for_name(name)
+        .map_or_else(Vec::new, |idx| {
+            m.captures
+                .iter()
+                .filter(|c| c.index == idx)
+                .map(|c| c.node)
+                .collect()
+        })
+}
+
+fn text(node: Node, source: &str) -> String {
+    source[node.byte_range()].to_string()
+}
+
+/// Run the entity queries and resolve nesting, in source order.
+fn find_definitions<'t>(
+    queries: &CompiledQueries,
+    root: Node<'t>,
+    source: &str,
+) -> Vec<Definition<'t>> {
+    let mut seen = HashSet::new();
+    let mut defs = Vec::new();
+    for (kind, query) in &queries.entities {
+        let has_signature = query.capture_index_for_name("params").is_some()
+            || query.capture_index_for_name("return_type").is_some();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, root, source.as_bytes());
+        while let Some(m) = matches.next() {
+            let Some(name_node) = capture_node(query, m, "name") else {
+                continue;
+            };
+            // The same name node matched by two patterns is one definition; first wins
+            if !seen.insert(name_node.id()) {
+                continue;
+            }
+            defs.push(Definition {
+                name: text(name_node, source),
+                kind: *kind,
+                node: capture_node(query, m, "definition")
+                    .or_else(|| name_node.parent())
+                    .unwrap_or(name_node),
+                parent: capture_node(query, m, "parent").map(|n| text(n, source)),
+                params: capture_nodes(query, m, "params"),
+                return_type: capture_node(query, m, "return_type").map(|n| text(n, source)),
+                has_signature,
+            });
+        }
+    }
+
+    defs.sort_by_key(|d| (d.node.start_byte(), std::cmp::Reverse(d.node.end_byte())));
+    let mut classes: Vec<(String, usize)> = Vec::new();
+    for def in &mut defs {
+        while classes
+            .last()
+            .is_some_and(|(_, end)| *end <= def.node.start_byte())
+        {
+            classes.pop();
+        }
+        if def.parent.is_none() {
+            def.parent = classes.last().map(|(name, _)| name.clone());
+        }
+        if def.kind == EntityKind::Function && def.parent.is_some() {
+            def.kind = EntityKind::Method;
+        }
+        if def.kind == EntityKind::Class {
+            classes.push((def.name.clone(), def.node.end_byte()));
+        }
+    }
+    defs
+}
+
+fn signature(queries: &CompiledQueries, def: &Definition, source: &str) -> Option<RawSignature> {
+    if !def.has_signature {
+        return None;
+    }
+    let mut parameters = Vec::new();
+    for params in &def.params {
+        let Some(query) = &queries.parameters else {
+            parameters.push(RawParam {
+                name: text(*params, source),
+                type_annotation: None,
+            });
+            continue;
+        };
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, *params, source.as_bytes());
+        while let Some(m) = matches.next() {
+            if let Some(name) = capture_node(query, m, "name") {
+                parameters.push(RawParam {
+                    name: text(name, source),
+                    type_annotation: capture_node(query, m, "type").map(|n| text(n, source)),
+                });
+            }
+        }
+    }
+    Some(RawSignature {
+        parameters,
+        return_type: def.return_type.clone(),
+    })
+}
+
+/// Extract entities with a language's entity queries.
+pub fn extract_entities(
+    queries: &CompiledQueries,
+    path: &Path,
+    source: &str,
+    language: Language,
+) -> Vec<RawEntity> {
+    let Some(tree) = parse(source, language) else {
+        return Vec::new();
+    };
+    find_definitions(queries, tree.root_node(), source)
+        .iter()
+        .map(|def| RawEntity {
+            name: def.name.clone(),
+            kind: def.kind,
+            file: path.to_path_buf(),
+            line_start: def.node.start_position().row + 1,
+            line_end: def.node.end_position().row + 1,
+            parent_class: def.parent.clone(),
+            source_text: text(def.node, source),
+            signature: signature(queries, def, source),
+            metadata: extract_metadata(&def.node, source, language),
+            signals: if def.kind == EntityKind::Class {
+                None
+            } else {
+                analyze_node(&def.node, source, language)
+            },
+            discriminator: None,
+        })
+        .collect()
+}
+
+/// Extract imports, calls and inheritance with a language's dependency queries.
+pub fn extract_deps(
+    queries: &CompiledQueries,
+    _path: &Path,
+    source: &str,
+    language: Language,
+) -> RawDeps {
+    let Some(tree) = parse(source, language) else {
+        return RawDeps::default();
+    };
+    let root = tree.root_node();
+    let defs = find_definitions(queries, root, source);
+    let scopes: Vec<FunctionScope> = defs
+        .iter()
+        .filter(|d| d.kind != EntityKind::Class)
+        .map(|d| FunctionScope {
+            name: match &d.parent {
+                Some(parent) => format!("{}.{}", parent, d.name),
+                None => d.name.clone(),
+            },
+            start_row: d.node.start_position().row,
+            end_row: d.node.end_position().row,
+        })
+        .collect();
+
+    let mut deps = RawDeps::default();
+
+    // Imports, merging matches that share a module node (one match per symbol)
+    let mut import_nodes = Vec::new();
+    for query in &queries.imports {
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, root, source.as_bytes());
+        while let Some(m) = matches.next() {
+            let Some(module) = capture_node(query, m, "module") else {
+                continue;
+            };
+            let symbols = capture_nodes(query, m, "symbol")
+                .into_iter()
+                .map(|n| text(n, source));
+            if let Some(i) = import_nodes.iter().position(|id| *id == module.id()) {
+                deps.imports[i].symbols.extend(symbols);
+            } else {
+                import_nodes.push(module.id());
+                deps.imports.push(ImportDep {
+                    module: source[module.byte_range()]
+                        .trim_matches(|c| matches!(c, '"' | '\'' | '<' | '>'))
+                        .to_string(),
+                    symbols: symbols.collect(),
+                });
+            }
+        }
+    }
+
+    for query in &queries.calls {
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, root, source.as_bytes());
+        while let Some(m) = matches.next() {
+            if let Some(callee) = capture_node(query, m, "callee") {
+                let row = callee.start_position().row;
+                deps.calls.push(CallDep {
+                    caller_entity: find_enclosing_scope(&scopes, row)
+                        .unwrap_or_else(|| "<module>".to_string()),
+                    callee: text(callee, source),
+                });
+            }
+        }
+    }
+
+    for query in &queries.inherits {
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, root, source.as_bytes());
+        while let Some(m) = matches.next() {
+            let Some(parent) = capture_node(query, m, "parent") else {
+                continue;
+            };
+            // Innermost class containing the clause
+            let child = capture_node(query, m, "child")
+                .map(|n| text(n, source))
+                .or_else(|| {
+                    defs.iter()
+                        .rev()
+                        .find(|d| {
+                            d.kind == EntityKind::Class
+                                && d.node.start_byte() <= parent.start_byte()
+                                && parent.end_byte() <= d.node.end_byte()
+                        })
+                        .map(|d| d.name.clone())
+                });
+            if let Some(child_class) = child {
+                deps.inherits.push(InheritDep {
+                    child_class,
+                    parent_class: text(parent, source),
+                });
+            }
+        }
+    }
+
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_language_queries_compile() {
+        for idx in 0..Language::count() {
+            let lang = Language::from_index(idx);
+            if let Some(source) = super::super::query_def_source(lang) {
+                let queries = LanguageQueries::from_toml(source)
+                    .unwrap_or_else(|e| panic!("{}: {}", lang.name(), e))
+                    .expect("query_def_source implies a [queries] section");
+                if let Err(e) = queries.compile(&lang.ts_language()) {
+                    panic!("{}: {}", lang.name(), e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries_on_existing_grammar() {
+        let toml = r#"
+            [queries]
+            parameters = "(parameter_declaration name: (identifier) @name type: (_) @type)"
+            imports = ["(import_spec path: (_) @module)"]
+            calls = ["(call_expression function: (identifier) @callee)"]
+
+            [[queries.entities]]
+            kind = "class"
+            query = "(type_declaration (type_spec name: (type_identifier) @name)) @definition"
+
+            [[queries.entities]]
+            kind = "function"
+            query = """
+            (function_declaration
+              name: (identifier) @name
+              parameters: (parameter_list) @params
+              result: (_)? @return_type) @definition
+            """
+        "#;
+        let queries = LanguageQueries::from_toml(toml)
+            .unwrap()
+            .unwrap()
+            .compile(&Language::GO.ts_language())
+            .unwrap();
+        let source = "package main\n\nimport \"fmt\"\n\ntype Server struct{}\n\n\
+                      func add(a int, b int) int {\n\treturn helper(a) + b\n}\n";
+        let path = Path::new("main.go");
+
+        let entities = extract_entities(&queries, path, source, Language::GO);
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].name, "Server");
+        assert_eq!(entities[0].kind, EntityKind::Class);
+        let add = &entities[1];
+        assert_eq!(add.kind, EntityKind::Function);
+        assert_eq!((add.line_start, add.line_end), (7, 9));
+        let sig = add.signature.as_ref().unwrap();
+        assert_eq!(sig.parameters.len(), 2);
+        assert_eq!(sig.parameters[1].name, "b");
+        assert_eq!(sig.parameters[1].type_annotation.as_deref(), Some("int"));
+        assert_eq!(sig.return_type.as_deref(), Some("int"));
+
+        let deps = extract_deps(&queries, path, source, Language::GO);
+        assert_eq!(deps.imports.len(), 1);
+        assert_eq!(deps.imports[0].module, "fmt");
+        assert_eq!(deps.calls.len(), 1);
+        assert_eq!(deps.calls[0].caller_entity, "add");
+        assert_eq!(deps.calls[0].callee, "helper");
+    }
+
+    #[test]
+    fn test_missing_required_capture_is_rejected() {
+        let queries = LanguageQueries {
+            calls: vec!["(call_expression function: (identifier) @fn)".to_string()],
+            ..LanguageQueries::default()
+        };
+        let err = queries.compile(&Language::GO.ts_language()).err().unwrap();
+        assert!(err.contains("@callee"), "{err}");
+    }
+}
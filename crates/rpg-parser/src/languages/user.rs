@@ -0,0 +1,127 @@
+//! Runtime language definitions from `languages/*.toml` in the user config
+//! dir and the project's `.rpg/` (see [`crate::user_defs`]).
+//!
+//! Grammars are compiled in, so a runtime def extends a registered language:
+//!
+//! ```toml
+//! schema_version = 1
+//! name = "python"       # a registered language
+//! extensions = ["pyw"]  # additional file extensions (optional)
+//!
+//! [queries]             # same schema as the built-in defs (optional)
+//! calls = ["(call function: (identifier) @callee)"]
+//! ```
+//!
+//! Runtime `[queries]` take precedence over both the builtin extractor and the
+//! compiled-in `[queries]`. Extensions of built-in languages are never remapped.
+//! Definitions take effect once [`install_user_languages`] has run.
+
+use super::Language;
+use super::queries::{CompiledQueries, LanguageQueries};
+use crate::user_defs::{DefFile, LANGUAGES_DIR, def_files};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+
+#[derive(Deserialize)]
+struct UserLanguageToml {
+    schema_version: u32,
+    name: String,
+    #[serde(default)]
+    extensions: Vec<String>,
+    queries: Option<LanguageQueries>,
+}
+
+/// A validated runtime language definition.
+pub struct UserLanguage {
+    pub language: Language,
+    /// Extra extensions (without the dot) detected as this language.
+    pub extensions: Vec<String>,
+    pub queries: Option<Arc<CompiledQueries>>,
+    pub file: DefFile,
+}
+
+static INSTALLED: RwLock<Vec<UserLanguage>> = RwLock::new(Vec::new());
+
+/// Parse and validate the runtime language defs of a project without
+/// installing them. A later def for the same language replaces an earlier one.
+pub fn load_user_languages(project_root: &Path) -> (Vec<UserLanguage>, Vec<String>) {
+    let mut loaded: Vec<UserLanguage> = Vec::new();
+    let mut errors = Vec::new();
+    for file in def_files(project_root, LANGUAGES_DIR) {
+        match parse_user_language(&file) {
+            Ok(lang) => {
+                loaded.retain(|l| l.language != lang.language);
+                loaded.push(lang);
+            }
+            Err(e) => errors.push(format!("{}: {}", file.path.display(), e)),
+        }
+    }
+    (loaded, errors)
+}
+
+fn parse_user_language(file: &DefFile) -> Result<UserLanguage, String> {
+    let def: UserLanguageToml = toml::from_str(&file.read()?).map_err(|e| e.to_string())?;
+    if def.schema_version != 1 {
+        return Err(format!(
+            "unsupported schema_version {}; expected 1",
+            def.schema_version
+        ));
+    }
+    let language = Language::from_name(&def.name).ok_or_else(|| {
+        format!(
+            "unknown language '{}'; runtime definitions extend a compiled-in language",
+            def.name
+        )
+    })?;
+    let mut extensions = Vec::new();
+    for ext in &def.extensions {
+        let ext = ext.trim_start_matches('.');
+        if let Some(owner) = Language::from_builtin_extension(ext) {
+            return Err(format!(
+                "extension '{}' already belongs to {}",
+                ext,
+                owner.name()
+            ));
+        }
+        extensions.push(ext.to_string());
+    }
+    let queries = def
+        .queries
+        .map(|q| q.compile(&language.ts_language()).map(Arc::new))
+        .transpose()?;
+    Ok(UserLanguage {
+        language,
+        extensions,
+        queries,
+        file: file.clone(),
+    })
+}
+
+/// Load and install the runtime language defs of a project, replacing any
+/// installed before. Invalid defs are skipped; their errors are returned.
+pub fn install_user_languages(project_root: &Path) -> Vec<String> {
+    let (loaded, errors) = load_user_languages(project_root);
+    *INSTALLED.write().unwrap_or_else(PoisonError::into_inner) = loaded;
+    errors
+}
+
+/// The installed runtime language claiming a file extension.
+pub fn extension_language(ext: &str) -> Option<Language> {
+    INSTALLED
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|l| l.extensions.iter().any(|e| e == ext))
+        .map(|l| l.language)
+}
+
+/// Installed runtime queries for a language, if any.
+pub fn user_queries(language: Language) -> Option<Arc<CompiledQueries>> {
+    INSTALLED
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|l| l.language == language)
+        .and_then(|l| l.queries.clone())
+}
@@ -1,15 +1,22 @@
 //! Tree-sitter based code parsing for RPG entity and dependency extraction.
 //!
 //! Supports Python, Rust, TypeScript, JavaScript, Go, Java, C, and C++.
-//! Extracts functions, classes, methods, traits, import statements,
-//! function calls, and inheritance relationships.
+//! Extracts functions, classes, methods, traits, fields, constants, globals,
+//! import statements, function calls, state accesses, and inheritance
+//! relationships.
 
 pub mod deps;
+pub mod embedded;
 pub mod entities;
 pub mod languages;
+pub mod metadata;
+pub mod packages;
 pub mod paradigms;
+pub mod receivers;
 pub mod signals;
 pub mod treesitter;
+pub mod user_defs;
+pub mod variables;
 
 use entities::RawEntity;
 use languages::Language;
@@ -74,6 +81,7 @@ pub fn parse_files_with_paradigms(
                 language,
                 &mut raw,
             );
+            entities::disambiguate_ids(&mut raw);
             raw
         })
         .collect()
@@ -0,0 +1,632 @@
+//! Extract structural entity metadata from declaration nodes: visibility,
+//! modifiers (async/static/abstract), docstrings, and decorators/attributes.
+//!
+//! The scan is mostly grammar-agnostic — modifier keywords, `*modifier*` nodes,
+//! decorator-like nodes and preceding comments look alike across tree-sitter
+//! grammars. Language rules only fill in implicit defaults (Go capitalization,
+//! Python underscores, Java package-private, Rust `self`-less methods, ...).
+
+use crate::languages::Language;
+use rpg_core::graph::{EntityMetadata, Visibility};
+use tree_sitter::Node;
+
+/// Docstrings longer than this are truncated.
+const MAX_DOCSTRING_CHARS: usize = 2000;
+
+/// Extract metadata for a declaration node produced by an entity extractor.
+///
+/// `node` is the node the entity was created from (e.g. `function_item`,
+/// `method_definition`, a JS `variable_declarator`). Wrappers such as
+/// `export_statement` or Python's `decorated_definition` are found by
+/// walking up from it.
+pub fn extract_metadata(node: &Node, source: &str, language: Language) -> EntityMetadata {
+    let outer = outer_declaration(node);
+    let mut meta = EntityMetadata::default();
+    let mut visibility = None;
+
+    scan_modifiers(node, source, language, &mut meta, &mut visibility);
+    // `const f = async () => {}`: the modifier lives on the value
+    if node.kind() == "variable_declarator"
+        && let Some(value) = node.child_by_field_name("value")
+    {
+        scan_modifiers(&value, source, language, &mut meta, &mut visibility);
+    }
+
+    meta.decorators = collect_decorators(node, &outer, source);
+    meta.docstring = extract_docstring(node, &outer, source, language);
+    meta.visibility = visibility.or_else(|| default_visibility(node, &outer, source, language));
+    apply_language_rules(node, source, language, &mut meta);
+
+    meta.is_deprecated = meta.decorators.iter().any(|d| {
+        let d = d.to_ascii_lowercase();
+        d.contains("deprecated") || d.contains("obsolete")
+    }) || meta
+        .docstring
+        .as_deref()
+        .is_some_and(docstring_marks_deprecated);
+    meta
+}
+
+/// Climb through wrapper nodes that carry the entity's comments and decorators.
+fn outer_declaration<'a>(node: &Node<'a>) -> Node<'a> {
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        match parent.kind() {
+            "export_statement"
+            | "decorated_definition"
+            | "lexical_declaration"
+            | "variable_declaration"
+            | "type_declaration"
+            | "class_member" => current = parent,
+            _ => break,
+        }
+    }
+    current
+}
+
+fn is_modifier_kind(kind: &str) -> bool {
+    kind.ends_with("modifier")
+        || kind.ends_with("modifiers")
+        || kind == "modifier"
+        || kind == "storage_class_specifier"
+}
+
+fn is_decorator_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        "decorator"
+            | "annotation"
+            | "marker_annotation"
+            | "attribute_item"
+            | "attribute"
+            | "attribute_list"
+    )
+}
+
+fn is_comment_kind(kind: &str) -> bool {
+    kind.contains("comment")
+}
+
+/// Record modifier keywords found directly on `node` or inside its modifier nodes.
+fn scan_modifiers(
+    node: &Node,
+    source: &str,
+    language: Language,
+    meta: &mut EntityMetadata,
+    visibility: &mut Option<Visibility>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if !child.is_named() {
+            apply_keyword(&source[child.byte_range()], language, meta, visibility);
+        } else if language == Language::RUST && child.kind() == "visibility_modifier" {
+            let text = &source[child.byte_range()];
+            visibility.get_or_insert(match text {
+                "pub" => Visibility::Public,
+                "pub(self)" => Visibility::Private,
+                _ => Visibility::Internal,
+            });
+        } else if is_modifier_kind(child.kind()) {
+            scan_modifier_leaves(&child, source, language, meta, visibility);
+        }
+    }
+}
+
+fn scan_modifier_leaves(
+    node: &Node,
+    source: &str,
+    language: Language,
+    meta: &mut EntityMetadata,
+    visibility: &mut Option<Visibility>,
+) {
+    if is_decorator_kind(node.kind()) {
+        return;
+    }
+    if node.child_count() == 0 {
+        apply_keyword(&source[node.byte_range()], language, meta, visibility);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        scan_modifier_leaves(&child, source, language, meta, visibility);
+    }
+}
+
+fn apply_keyword(
+    word: &str,
+    language: Language,
+    meta: &mut EntityMetadata,
+    visibility: &mut Option<Visibility>,
+) {
+    match word {
+        "public" => {
+            visibility.get_or_insert(Visibility::Public);
+        }
+        // Kotlin `open` means non-final; in Swift it is the widest visibility
+        "open" if language == Language::SWIFT => {
+            visibility.get_or_insert(Visibility::Public);
+        }
+        "private" | "fileprivate" => {
+            visibility.get_or_insert(Visibility::Private);
+        }
+        "protected" => {
+            visibility.get_or_insert(Visibility::Protected);
+        }
+        "internal" => {
+            visibility.get_or_insert(Visibility::Internal);
+        }
+        "async" | "suspend" => meta.is_async = true,
+        "static" => meta.is_static = true,
+        "abstract" => meta.is_abstract = true,
+        _ => {}
+    }
+}
+
+/// Decorators, attributes and annotations, in source order and without sigils.
+fn collect_decorators(node: &Node, outer: &Node, source: &str) -> Vec<String> {
+    let mut found = Vec::new();
+
+    // Preceding siblings: Rust `#[attr]`, TS member decorators
+    let mut preceding = Vec::new();
+    let mut sibling = outer.prev_sibling();
+    while let Some(s) = sibling {
+        if is_decorator_kind(s.kind()) {
+            preceding.push(s);
+        } else if !is_comment_kind(s.kind()) {
+            break;
+        }
+        sibling = s.prev_sibling();
+    }
+    found.extend(preceding.into_iter().rev());
+
+    // Children of the wrapper, the node, and its modifier lists
+    let mut holders = vec![*outer];
+    if outer.id() != node.id() {
+        holders.push(*node);
+    }
+    for holder in holders {
+        let mut cursor = holder.walk();
+        for child in holder.children(&mut cursor) {
+            if is_decorator_kind(child.kind()) {
+                found.push(child);
+            } else if is_modifier_kind(child.kind()) {
+                let mut inner = child.walk();
+                found.extend(
+                    child
+                        .children(&mut inner)
+                        .filter(|c| is_decorator_kind(c.kind())),
+                );
+            }
+        }
+    }
+
+    let mut decorators: Vec<String> = Vec::new();
+    for d in found {
+        for text in decorator_texts(&d, source) {
+            if !decorators.contains(&text) {
+                decorators.push(text);
+            }
+        }
+    }
+    decorators
+}
+
+fn decorator_texts(node: &Node, source: &str) -> Vec<String> {
+    // C# `[A, B]` and PHP `#[A, B]` wrap one or more `attribute` nodes
+    if node.kind() == "attribute_list" {
+        let mut out = Vec::new();
+        collect_descendants(node, "attribute", &mut out);
+        return out
+            .iter()
+            .map(|a| normalize_decorator(&source[a.byte_range()]))
+            .collect();
+    }
+    vec![normalize_decorator(&source[node.byte_range()])]
+}
+
+fn collect_descendants<'a>(node: &Node<'a>, kind: &str, out: &mut Vec<Node<'a>>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == kind {
+            out.push(child);
+        } else {
+            collect_descendants(&child, kind, out);
+        }
+    }
+}
+
+fn normalize_decorator(text: &str) -> String {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("#[")
+        .and_then(|t| t.strip_suffix(']'))
+        .unwrap_or(text);
+    let text = text.trim_start_matches('@');
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn extract_docstring(
+    node: &Node,
+    outer: &Node,
+    source: &str,
+    language: Language,
+) -> Option<String> {
+    if language == Language::PYTHON {
+        return python_docstring(node, source);
+    }
+    if language == Language::ELIXIR {
+        return elixir_docstring(node, source);
+    }
+
+    // Contiguous comments directly above the declaration (attributes may be interleaved)
+    let mut blocks = Vec::new();
+    let mut next_row = outer.start_position().row;
+    let mut sibling = outer.prev_sibling();
+    while let Some(s) = sibling {
+        if s.end_position().row + 1 < next_row {
+            break;
+        }
+        if is_comment_kind(s.kind()) {
+            let text = &source[s.byte_range()];
+            if !is_doc_comment(text, language) {
+                break;
+            }
+            blocks.push(text);
+        } else if !is_decorator_kind(s.kind()) {
+            break;
+        }
+        next_row = s.start_position().row;
+        sibling = s.prev_sibling();
+    }
+    blocks.reverse();
+
+    let lines: Vec<String> = blocks
+        .iter()
+        .flat_map(|b| strip_comment_markers(b))
+        .collect();
+    finish_docstring(&lines)
+}
+
+fn is_doc_comment(text: &str, language: Language) -> bool {
+    if text.starts_with("#!") {
+        return false;
+    }
+    match language {
+        // Languages whose doc convention is plain comments
+        Language::GO
+        | Language::RUBY
+        | Language::BASH
+        | Language::C
+        | Language::CPP
+        | Language::LUA => true,
+        // `(** ... *)` is the ML-family doc comment
+        _ => {
+            (text.starts_with("///") && !text.starts_with("////"))
+                || text.starts_with("/**")
+                || text.starts_with("(**")
+        }
+    }
+}
+
+fn strip_comment_markers(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            let mut l = line.trim();
+            for prefix in [
+                "/**", "/*!", "/*", "///", "//!", "//", "(**", "(*", "---", "--", "#",
+            ] {
+                if let Some(rest) = l.strip_prefix(prefix) {
+                    l = rest;
+                    break;
+                }
+            }
+            l = l
+                .strip_suffix("*/")
+                .or_else(|| l.strip_suffix("*)"))
+                .unwrap_or(l)
+                .trim();
+            l.strip_prefix('*').unwrap_or(l).trim().to_string()
+        })
+        .collect()
+}
+
+/// Elixir documents functions with a preceding `@doc` attribute and modules
+/// with `@moduledoc` inside their body. `@doc false` hides the entity.
+fn elixir_docstring(node: &Node, source: &str) -> Option<String> {
+    let doc = if elixir_call_name(node, source) == Some("defmodule") {
+        let mut cursor = node.walk();
+        let body = node
+            .children(&mut cursor)
+            .find(|c| c.kind() == "do_block")?;
+        let mut bc = body.walk();
+        body.named_children(&mut bc)
+            .filter_map(|c| elixir_attribute(&c, source))
+            .find(|(name, _)| *name == "moduledoc")?
+            .1
+    } else {
+        elixir_preceding_attributes(node, source)
+            .into_iter()
+            .find(|(name, _)| *name == "doc")?
+            .1
+    };
+    let text = source[doc.byte_range()].trim();
+    let inner = ["\"\"\"", "\""]
+        .iter()
+        .find_map(|q| text.strip_prefix(q).and_then(|t| t.strip_suffix(q)))?;
+    let lines: Vec<String> = inner.lines().map(|l| l.trim().to_string()).collect();
+    finish_docstring(&lines)
+}
+
+/// `@name value` as `(name, value node)`.
+fn elixir_attribute<'a>(node: &Node<'a>, source: &'a str) -> Option<(&'a str, Node<'a>)> {
+    if node.kind() != "unary_operator" || !source[node.byte_range()].starts_with('@') {
+        return None;
+    }
+    let operand = node.child_by_field_name("operand")?;
+    let name = elixir_call_name(&operand, source)?;
+    let mut cursor = operand.walk();
+    let args = operand
+        .children(&mut cursor)
+        .find(|c| c.kind() == "arguments")?;
+    Some((name, args.named_child(0)?))
+}
+
+/// Module attributes directly above a definition, nearest first.
+fn elixir_preceding_attributes<'a>(node: &Node<'a>, source: &'a str) -> Vec<(&'a str, Node<'a>)> {
+    let mut attributes = Vec::new();
+    let mut sibling = node.prev_named_sibling();
+    while let Some(s) = sibling {
+        if let Some(attribute) = elixir_attribute(&s, source) {
+            attributes.push(attribute);
+        } else if !is_comment_kind(s.kind()) {
+            break;
+        }
+        sibling = s.prev_named_sibling();
+    }
+    attributes
+}
+
+fn elixir_call_name<'a>(node: &Node, source: &'a str) -> Option<&'a str> {
+    let target = node.child_by_field_name("target")?;
+    (target.kind() == "identifier").then(|| &source[target.byte_range()])
+}
+
+fn python_docstring(node: &Node, source: &str) -> Option<String> {
+    let body = node.child_by_field_name("body")?;
+    let first = body.named_child(0)?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+    let string = first.named_child(0).filter(|s| s.kind() == "string")?;
+    let text = source[string.byte_range()].trim_start_matches(['r', 'R', 'u', 'U', 'b', 'B']);
+    let inner = ["\"\"\"", "'''", "\"", "'"]
+        .iter()
+        .find_map(|q| text.strip_prefix(q).and_then(|t| t.strip_suffix(q)))?;
+    let lines: Vec<String> = inner.lines().map(|l| l.trim().to_string()).collect();
+    finish_docstring(&lines)
+}
+
+/// Trim surrounding blank lines, join, and cap the length.
+fn finish_docstring(lines: &[String]) -> Option<String> {
+    let start = lines.iter().position(|l| !l.is_empty())?;
+    let end = lines.iter().rposition(|l| !l.is_empty())?;
+    let doc = lines[start..=end].join("\n");
+    Some(match doc.char_indices().nth(MAX_DOCSTRING_CHARS) {
+        Some((cut, _)) => doc[..cut].to_string(),
+        None => doc,
+    })
+}
+
+fn docstring_marks_deprecated(doc: &str) -> bool {
+    let lower = doc.to_ascii_lowercase();
+    lower.contains("@deprecated")
+        || lower.contains(".. deprecated::")
+        || lower
+            .lines()
+            .any(|l| l.trim_start().starts_with("deprecated:"))
+}
+
+/// Visibility implied when no modifier says otherwise.
+fn default_visibility(
+    node: &Node,
+    outer: &Node,
+    source: &str,
+    language: Language,
+) -> Option<Visibility> {
+    let name_node = node.child_by_field_name("name");
+    let name = name_node.map_or("", |n| &source[n.byte_range()]);
+    let parent_kind = node.parent().map(|p| p.kind());
+    let grandparent_kind = node.parent().and_then(|p| p.parent()).map(|g| g.kind());
+
+    Some(match language {
+        Language::PYTHON => {
+            if name.starts_with("__") && name.ends_with("__") || !name.starts_with('_') {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        Language::GO => {
+            if name.starts_with(|c: char| c.is_uppercase()) {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        Language::RUST => {
+            // Trait items and trait impl members share the trait's visibility
+            let grandparent = node.parent().and_then(|p| p.parent());
+            if grandparent.is_some_and(|g| {
+                g.kind() == "trait_item"
+                    || (g.kind() == "impl_item" && g.child_by_field_name("trait").is_some())
+            }) {
+                return None;
+            }
+            Visibility::Private
+        }
+        Language::TYPESCRIPT | Language::JAVASCRIPT => {
+            if name_node.is_some_and(|n| n.kind() == "private_property_identifier") {
+                Visibility::Private
+            } else if parent_kind == Some("class_body") || outer.kind() == "export_statement" {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        Language::JAVA => {
+            if parent_kind == Some("interface_body") {
+                Visibility::Public
+            } else {
+                Visibility::Internal
+            }
+        }
+        Language::CSHARP => match (parent_kind, grandparent_kind) {
+            (Some("declaration_list"), Some("interface_declaration")) => Visibility::Public,
+            (Some("declaration_list"), Some(g)) if !g.contains("namespace") => Visibility::Private,
+            _ => Visibility::Internal,
+        },
+        Language::SWIFT => Visibility::Internal,
+        Language::CPP if parent_kind == Some("field_declaration_list") => cpp_member_access(
+            node, source,
+        )
+        .unwrap_or(if grandparent_kind == Some("class_specifier") {
+            Visibility::Private
+        } else {
+            Visibility::Public
+        }),
+        Language::RUBY => ruby_access_section(node, source).unwrap_or(Visibility::Public),
+        Language::BASH => return None,
+        Language::LUA if source[node.byte_range()].starts_with("local") => Visibility::Private,
+        Language::ELIXIR => match elixir_call_name(node, source) {
+            Some("defp" | "defmacrop" | "defguardp") => Visibility::Private,
+            _ => Visibility::Public,
+        },
+        Language::DART => {
+            let name = if name.is_empty() {
+                crate::entities::dart_signature_node(node)
+                    .and_then(|sig| crate::entities::dart_member_name(&sig, source))
+                    .unwrap_or_default()
+            } else {
+                name.to_string()
+            };
+            // `Point._internal` is a private named constructor
+            if name.rsplit('.').next().is_some_and(|n| n.starts_with('_')) {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        Language::ZIG => {
+            let text = &source[node.byte_range()];
+            if text.starts_with("pub ") || text.starts_with("export ") {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        _ => Visibility::Public,
+    })
+}
+
+/// The nearest preceding `public:` / `private:` / `protected:` label.
+fn cpp_member_access(node: &Node, source: &str) -> Option<Visibility> {
+    let mut sibling = node.prev_sibling();
+    while let Some(s) = sibling {
+        if s.kind() == "access_specifier" {
+            return Visibility::from_name(&source[s.byte_range()]);
+        }
+        sibling = s.prev_sibling();
+    }
+    None
+}
+
+/// The nearest preceding bare `private` / `protected` / `public` call in a Ruby body.
+fn ruby_access_section(node: &Node, source: &str) -> Option<Visibility> {
+    let mut sibling = node.prev_sibling();
+    while let Some(s) = sibling {
+        if s.kind() == "identifier"
+            && let Some(v @ (Visibility::Public | Visibility::Private | Visibility::Protected)) =
+                Visibility::from_name(&source[s.byte_range()])
+        {
+            return Some(v);
+        }
+        sibling = s.prev_sibling();
+    }
+    None
+}
+
+fn apply_language_rules(node: &Node, source: &str, language: Language, meta: &mut EntityMetadata) {
+    match language {
+        Language::PYTHON => {
+            for d in &meta.decorators {
+                let base = d.rsplit('.').next().unwrap_or(d);
+                match base {
+                    "staticmethod" | "classmethod" => meta.is_static = true,
+                    "abstractmethod" => meta.is_abstract = true,
+                    _ => {}
+                }
+            }
+        }
+        Language::RUST => {
+            // Associated functions without a `self` receiver
+            let in_impl = node
+                .parent()
+                .and_then(|p| p.parent())
+                .is_some_and(|g| g.kind() == "impl_item" || g.kind() == "trait_item");
+            if in_impl && node.kind() == "function_item" {
+                let has_self = node.child_by_field_name("parameters").is_some_and(|p| {
+                    let mut cursor = p.walk();
+                    p.children(&mut cursor)
+                        .any(|c| c.kind() == "self_parameter")
+                });
+                meta.is_static = !has_self;
+            }
+        }
+        Language::C | Language::CPP => {
+            // File-scope `static` is internal linkage, not a static member
+            let is_member = node
+                .parent()
+                .is_some_and(|p| p.kind() == "field_declaration_list");
+            if meta.is_static && !is_member {
+                meta.is_static = false;
+                meta.visibility = Some(Visibility::Private);
+            }
+        }
+        Language::RUBY if node.kind() == "singleton_method" => meta.is_static = true,
+        Language::ELIXIR => {
+            // `@impl true`, `@deprecated "..."` and friends act as annotations
+            let mut attributes: Vec<String> = elixir_preceding_attributes(node, source)
+                .into_iter()
+                .filter(|(name, _)| !matches!(*name, "doc" | "moduledoc" | "spec"))
+                .map(|(name, value)| format!("{} {}", name, &source[value.byte_range()]))
+                .collect();
+            attributes.reverse();
+            meta.decorators.extend(attributes);
+        }
+        Language::DART => {
+            // `static` sits in the method signature, `async` on the body
+            let has_token = |holder: Option<Node>, token: &str| {
+                holder.is_some_and(|h| {
+                    let mut cursor = h.walk();
+                    h.children(&mut cursor)
+                        .any(|c| !c.is_named() && &source[c.byte_range()] == token)
+                })
+            };
+            let signature = node.child_by_field_name("signature");
+            if has_token(Some(*node), "static") || has_token(signature, "static") {
+                meta.is_static = true;
+            }
+            if has_token(node.child_by_field_name("body"), "async") {
+                meta.is_async = true;
+            }
+            // Bodiless members of a class are abstract
+            if node.kind() == "declaration"
+                && crate::entities::dart_signature_node(node)
+                    .is_some_and(|sig| !sig.kind().contains("constructor"))
+            {
+                meta.is_abstract = true;
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,547 @@
+//! Third-party packages declared in project manifests.
+//!
+//! Reads the dependency sections of the manifests also sniffed by
+//! [`read_manifest`](crate::paradigms::helpers::read_manifest), for the
+//! ecosystems whose imports can be traced back to a package: Cargo
+//! (`Cargo.toml`), npm (`package.json`), PyPI (`requirements.txt`,
+//! `pyproject.toml`) and Go modules (`go.mod`). Local dependencies — path,
+//! workspace and `file:` packages — are skipped, since their code is indexed
+//! as part of the project.
+
+use crate::languages::Language;
+use rpg_core::graph::{ExternalNode, PackageEcosystem};
+use std::path::Path;
+
+/// Manifests read by [`declared_packages`], with their ecosystem.
+const MANIFESTS: &[(&str, PackageEcosystem)] = &[
+    ("Cargo.toml", PackageEcosystem::Cargo),
+    ("package.json", PackageEcosystem::Npm),
+    ("requirements.txt", PackageEcosystem::PyPI),
+    ("pyproject.toml", PackageEcosystem::PyPI),
+    ("go.mod", PackageEcosystem::Go),
+];
+
+/// Dependency tables of a `Cargo.toml` and of its `[target.*]` tables.
+const CARGO_SECTIONS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Crates shipped with the Rust toolchain, which no manifest declares.
+const RUST_STD_CRATES: [&str; 5] = ["std", "core", "alloc", "proc_macro", "test"];
+
+/// Top-level packages of the Go standard library.
+const GO_STD_PACKAGES: &[&str] = &[
+    "archive",
+    "bufio",
+    "builtin",
+    "bytes",
+    "cmp",
+    "compress",
+    "container",
+    "context",
+    "crypto",
+    "database",
+    "debug",
+    "embed",
+    "encoding",
+    "errors",
+    "expvar",
+    "flag",
+    "fmt",
+    "go",
+    "hash",
+    "html",
+    "image",
+    "index",
+    "io",
+    "iter",
+    "log",
+    "maps",
+    "math",
+    "mime",
+    "net",
+    "os",
+    "path",
+    "plugin",
+    "reflect",
+    "regexp",
+    "runtime",
+    "slices",
+    "sort",
+    "strconv",
+    "strings",
+    "sync",
+    "syscall",
+    "testing",
+    "text",
+    "time",
+    "unicode",
+    "unique",
+    "unsafe",
+];
+
+/// Top-level modules of the Python standard library commonly imported.
+const PYTHON_STD_MODULES: &[&str] = &[
+    "abc",
+    "argparse",
+    "array",
+    "ast",
+    "asyncio",
+    "base64",
+    "binascii",
+    "bisect",
+    "builtins",
+    "bz2",
+    "calendar",
+    "cmath",
+    "codecs",
+    "collections",
+    "concurrent",
+    "configparser",
+    "contextlib",
+    "contextvars",
+    "copy",
+    "csv",
+    "ctypes",
+    "dataclasses",
+    "datetime",
+    "decimal",
+    "difflib",
+    "dis",
+    "email",
+    "enum",
+    "errno",
+    "fnmatch",
+    "fractions",
+    "functools",
+    "gc",
+    "getpass",
+    "gettext",
+    "glob",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "hmac",
+    "html",
+    "http",
+    "importlib",
+    "inspect",
+    "io",
+    "ipaddress",
+    "itertools",
+    "json",
+    "logging",
+    "lzma",
+    "math",
+    "mimetypes",
+    "multiprocessing",
+    "operator",
+    "os",
+    "pathlib",
+    "pickle",
+    "platform",
+    "pprint",
+    "queue",
+    "random",
+    "re",
+    "secrets",
+    "select",
+    "shlex",
+    "shutil",
+    "signal",
+    "socket",
+    "sqlite3",
+    "ssl",
+    "stat",
+    "statistics",
+    "string",
+    "struct",
+    "subprocess",
+    "sys",
+    "sysconfig",
+    "tarfile",
+    "tempfile",
+    "textwrap",
+    "threading",
+    "time",
+    "timeit",
+    "tomllib",
+    "traceback",
+    "types",
+    "typing",
+    "unicodedata",
+    "unittest",
+    "urllib",
+    "uuid",
+    "warnings",
+    "weakref",
+    "xml",
+    "zipfile",
+    "zlib",
+    "zoneinfo",
+];
+
+/// Node.js built-in modules, importable without the `node:` prefix.
+const NODE_BUILTIN_MODULES: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "crypto",
+    "dgram",
+    "dns",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "querystring",
+    "readline",
+    "stream",
+    "string_decoder",
+    "timers",
+    "tls",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "worker_threads",
+    "zlib",
+];
+
+/// PyPI distributions whose top-level module is not their normalized name.
+const PYPI_MODULES: &[(&str, &str)] = &[
+    ("attrs", "attr"),
+    ("beautifulsoup4", "bs4"),
+    ("msgpack_python", "msgpack"),
+    ("opencv_contrib_python", "cv2"),
+    ("opencv_python", "cv2"),
+    ("opencv_python_headless", "cv2"),
+    ("pillow", "pil"),
+    ("psycopg2_binary", "psycopg2"),
+    ("pyjwt", "jwt"),
+    ("pymupdf", "fitz"),
+    ("python_dateutil", "dateutil"),
+    ("python_dotenv", "dotenv"),
+    ("pyyaml", "yaml"),
+    ("scikit_image", "skimage"),
+    ("scikit_learn", "sklearn"),
+];
+
+/// Packages declared by the manifests in `dir`, in declaration order. Each
+/// node's manifest path is relative to `root`.
+pub fn declared_packages(root: &Path, dir: &Path) -> Vec<ExternalNode> {
+    let mut nodes: Vec<ExternalNode> = Vec::new();
+    for (file, ecosystem) in MANIFESTS {
+        let path = dir.join(file);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let declared = match (*file, ecosystem) {
+            (_, PackageEcosystem::Cargo) => cargo_packages(&content),
+            (_, PackageEcosystem::Npm) => npm_packages(&content),
+            ("pyproject.toml", _) => pyproject_packages(&content),
+            (_, PackageEcosystem::PyPI) => requirements_packages(&content),
+            (_, PackageEcosystem::Go) => go_packages(&content),
+        };
+        let manifest = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        for (name, version) in declared {
+            if nodes.iter().any(|n| n.name == name) {
+                continue;
+            }
+            nodes.push(ExternalNode {
+                id: ExternalNode::id_for(&name),
+                name,
+                ecosystem: *ecosystem,
+                version,
+                manifests: vec![manifest.clone()],
+            });
+        }
+    }
+    nodes
+}
+
+/// The ecosystem whose packages files of `language` import.
+pub fn ecosystem(language: Language) -> Option<PackageEcosystem> {
+    if language == Language::RUST {
+        Some(PackageEcosystem::Cargo)
+    } else if language == Language::PYTHON || language == Language::JUPYTER {
+        Some(PackageEcosystem::PyPI)
+    } else if language == Language::GO {
+        Some(PackageEcosystem::Go)
+    } else if language == Language::JAVASCRIPT
+        || language == Language::TYPESCRIPT
+        || crate::embedded::is_host(language)
+    {
+        Some(PackageEcosystem::Npm)
+    } else {
+        None
+    }
+}
+
+/// Whether importing `module` (as written in the import statement) loads
+/// code from `package`: `serde::de` from `serde`, `lodash/fp` from `lodash`,
+/// `yaml` from `PyYAML`, `github.com/spf13/cobra/doc` from `github.com/spf13/cobra`.
+pub fn provides_module(package: &ExternalNode, module: &str) -> bool {
+    let module = module.trim_matches(|c| c == '"' || c == '\'' || c == '`');
+    match package.ecosystem {
+        PackageEcosystem::Cargo => {
+            let root = module.trim_start_matches("::").split("::").next();
+            root == Some(package.name.replace('-', "_").as_str())
+        }
+        PackageEcosystem::Npm => {
+            let mut parts = module.split('/');
+            let root = match parts.next() {
+                Some(scope) if scope.starts_with('@') => {
+                    parts.next().map(|name| format!("{}/{}", scope, name))
+                }
+                first => first.map(str::to_string),
+            };
+            root.as_deref() == Some(package.name.as_str())
+        }
+        PackageEcosystem::PyPI => {
+            if module.starts_with('.') {
+                return false;
+            }
+            let root = module.split('.').next().unwrap_or_default().to_lowercase();
+            let name = normalize_pypi(&package.name);
+            let alias = PYPI_MODULES
+                .iter()
+                .find(|(dist, _)| *dist == name)
+                .map(|(_, module)| *module);
+            root == name || Some(root.as_str()) == alias
+        }
+        PackageEcosystem::Go => {
+            module == package.name
+                || module
+                    .strip_prefix(package.name.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }
+    }
+}
+
+/// Whether `module`, as written in an import or at the start of a path,
+/// belongs to the standard library of `ecosystem` rather than to the project
+/// or a declared package: `std::fs` in Cargo, `os.path` in PyPI, `fs` or
+/// `node:fs` in npm, `net/http` in Go.
+pub fn is_standard_library(ecosystem: PackageEcosystem, module: &str) -> bool {
+    let module = module.trim_matches(|c| c == '"' || c == '\'' || c == '`');
+    match ecosystem {
+        PackageEcosystem::Cargo => {
+            let root = module.trim_start_matches("::").split("::").next();
+            root.is_some_and(|root| RUST_STD_CRATES.contains(&root))
+        }
+        PackageEcosystem::PyPI => {
+            let root = module.split('.').next().unwrap_or_default();
+            PYTHON_STD_MODULES.contains(&root)
+        }
+        PackageEcosystem::Npm => {
+            let root = module.split('/').next().unwrap_or_default();
+            module.starts_with("node:") || NODE_BUILTIN_MODULES.contains(&root)
+        }
+        PackageEcosystem::Go => {
+            let root = module.split('/').next().unwrap_or_default();
+            GO_STD_PACKAGES.contains(&root)
+        }
+    }
+}
+
+/// Lowercase a PyPI name with `-` and `.` as `_`, the form modules use.
+fn normalize_pypi(name: &str) -> String {
+    name.to_lowercase().replace(['-', '.'], "_")
+}
+
+/// `[dependencies]`, `[dev-dependencies]`, `[build-dependencies]`, their
+/// `[target.*]` variants and `[workspace.dependencies]`.
+fn cargo_packages(content: &str) -> Vec<(String, Option<String>)> {
+    let Ok(doc) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut tables: Vec<&toml::Table> = CARGO_SECTIONS
+        .iter()
+        .filter_map(|s| doc.get(*s)?.as_table())
+        .collect();
+    if let Some(targets) = doc.get("target").and_then(toml::Value::as_table) {
+        for target in targets.values().filter_map(toml::Value::as_table) {
+            tables.extend(
+                CARGO_SECTIONS
+                    .iter()
+                    .filter_map(|s| target.get(*s)?.as_table()),
+            );
+        }
+    }
+    if let Some(deps) = doc
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    {
+        tables.push(deps);
+    }
+
+    let mut out = Vec::new();
+    for (name, spec) in tables.into_iter().flatten() {
+        match spec {
+            toml::Value::String(version) => out.push((name.clone(), Some(version.clone()))),
+            toml::Value::Table(spec)
+                if !spec.contains_key("path") && !spec.contains_key("workspace") =>
+            {
+                let version = spec.get("version").and_then(toml::Value::as_str);
+                out.push((name.clone(), version.map(str::to_string)));
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// `dependencies`, `devDependencies`, `peerDependencies` and
+/// `optionalDependencies`.
+fn npm_packages(content: &str) -> Vec<(String, Option<String>)> {
+    let Ok(doc) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for section in [
+        "dependencies",
+        "devDependencies",
+        "peerDependencies",
+        "optionalDependencies",
+    ] {
+        let Some(deps) = doc.get(section).and_then(|d| d.as_object()) else {
+            continue;
+        };
+        for (name, version) in deps {
+            let version = version.as_str().unwrap_or_default();
+            if ["file:", "link:", "workspace:", "portal:"]
+                .iter()
+                .any(|p| version.starts_with(p))
+            {
+                continue;
+            }
+            out.push((name.clone(), Some(version.to_string())));
+        }
+    }
+    out
+}
+
+/// One requirement specifier per line; options (`-r`, `-e`) and
+/// paths or URLs without a package name are skipped.
+fn requirements_packages(content: &str) -> Vec<(String, Option<String>)> {
+    content
+        .lines()
+        .map(|line| line.split(" #").next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '-']))
+        .filter_map(pep508)
+        .collect()
+}
+
+/// `[project]` dependencies and optional dependencies, and Poetry's
+/// dependency tables.
+fn pyproject_packages(content: &str) -> Vec<(String, Option<String>)> {
+    let Ok(doc) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    if let Some(project) = doc.get("project") {
+        let optional = project
+            .get("optional-dependencies")
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flat_map(|t| t.values());
+        for list in project.get("dependencies").into_iter().chain(optional) {
+            let specs = list.as_array().into_iter().flatten();
+            out.extend(specs.filter_map(toml::Value::as_str).filter_map(pep508));
+        }
+    }
+    if let Some(poetry) = doc.get("tool").and_then(|t| t.get("poetry")) {
+        let groups = poetry
+            .get("group")
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flat_map(|t| t.values())
+            .filter_map(|g| g.get("dependencies"));
+        let tables = ["dependencies", "dev-dependencies"]
+            .iter()
+            .filter_map(|s| poetry.get(*s))
+            .chain(groups)
+            .filter_map(toml::Value::as_table);
+        for (name, spec) in tables.flatten() {
+            if name == "python" {
+                continue;
+            }
+            let version = match spec {
+                toml::Value::String(version) => Some(version.clone()),
+                toml::Value::Table(spec) if !spec.contains_key("path") => spec
+                    .get("version")
+                    .and_then(toml::Value::as_str)
+                    .map(str::to_string),
+                _ => continue,
+            };
+            out.push((name.clone(), version));
+        }
+    }
+    out
+}
+
+/// Name and version specifier of a PEP 508 requirement (`requests>=2.31`,
+/// `uvicorn[standard]==0.29 ; python_version>"3.8"`, `pkg @ https://...`).
+fn pep508(spec: &str) -> Option<(String, Option<String>)> {
+    let end = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(spec.len());
+    let (name, rest) = spec.split_at(end);
+    if name.is_empty()
+        || !rest.is_empty() && !rest.starts_with([' ', '[', '<', '>', '=', '!', '~', ';', '@'])
+    {
+        return None;
+    }
+    let rest = match rest.trim_start().strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map_or("", |(_, r)| r),
+        None => rest,
+    };
+    let version = rest.split(';').next().unwrap_or_default().trim();
+    let version = (!version.is_empty() && !version.starts_with('@')).then(|| version.to_string());
+    Some((name.to_string(), version))
+}
+
+/// `require` directives, single-line or in a block; `// indirect`
+/// requirements are skipped as the project does not import them.
+fn go_packages(content: &str) -> Vec<(String, Option<String>)> {
+    let mut out = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.trim();
+        let spec = if in_block {
+            if line.starts_with(')') {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim_start();
+            if rest.starts_with('(') {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        if spec.contains("// indirect") {
+            continue;
+        }
+        let mut parts = spec.split_whitespace();
+        if let Some(path) = parts.next().filter(|p| !p.starts_with("//")) {
+            out.push((path.to_string(), parts.next().map(str::to_string)));
+        }
+    }
+    out
+}
@@ -13,14 +13,22 @@ use std::path::Path;
 ///
 /// Paradigm defs are iterated in priority order (lowest priority number first = highest priority).
 /// Once an entity is frozen (by `Reclassify` or `Skip`), no lower-priority paradigm can touch it.
+/// Methods are only considered by rules that explicitly match `kind = "method"`
+/// (e.g. JUnit `@Test` methods).
 pub fn classify_entities(active_defs: &[&ParadigmDef], file: &Path, entities: &mut [RawEntity]) {
     for entity in entities.iter_mut() {
-        // Only reclassify base kinds — Method entities are never reclassified
-        if !matches!(entity.kind, EntityKind::Function | EntityKind::Class) {
+        // Only reclassify base kinds
+        if !matches!(
+            entity.kind,
+            EntityKind::Function | EntityKind::Class | EntityKind::Method
+        ) {
             continue;
         }
         'paradigms: for def in active_defs {
             for rule in &def.classify {
+                if entity.kind == EntityKind::Method && !targets_methods(&rule.match_rule) {
+                    continue;
+                }
                 if matches_entity(&rule.match_rule, entity, file) {
                     match &rule.action {
                         ClassifyAction::Skip => {
@@ -44,6 +52,14 @@ pub fn classify_entities(active_defs: &[&ParadigmDef], file: &Path, entities: &m
     }
 }
 
+/// Whether a rule names methods explicitly in its `kind` filter.
+fn targets_methods(m: &EntityMatch) -> bool {
+    m.kind
+        .as_deref()
+        .and_then(parse_entity_kind)
+        .is_some_and(|k| k == EntityKind::Method)
+}
+
 /// Check if an entity matches all specified fields of an `EntityMatch` (AND logic).
 pub fn matches_entity(m: &EntityMatch, entity: &RawEntity, file: &Path) -> bool {
     // kind filter
@@ -81,7 +97,7 @@ pub fn matches_entity(m: &EntityMatch, entity: &RawEntity, file: &Path) -> bool
 
     // Structural signal filters (max_branches, max_loops, max_calls)
     if m.max_branches.is_some() || m.max_loops.is_some() || m.max_calls.is_some() {
-        let signals = crate::signals::analyze(&entity.source_text);
+        let signals = entity.structural_signals();
         if let Some(max_br) = m.max_branches
             && signals.branch_count > max_br
         {
@@ -120,6 +136,16 @@ pub fn matches_entity(m: &EntityMatch, entity: &RawEntity, file: &Path) -> bool
         return false;
     }
 
+    // decorator_any filter (OR within the list, arguments ignored)
+    if let Some(ref names) = m.decorator_any
+        && !entity.metadata.decorators.iter().any(|d| {
+            let head = d.split('(').next().unwrap_or(d).trim();
+            names.iter().any(|n| n == head)
+        })
+    {
+        return false;
+    }
+
     // file_name_stem filter
     if let Some(ref stem) = m.file_name_stem {
         let file_stem = file
@@ -132,6 +158,16 @@ pub fn matches_entity(m: &EntityMatch, entity: &RawEntity, file: &Path) -> bool
         }
     }
 
+    // file_name_regex filter
+    if let Some(ref regex_str) = m.file_name_regex {
+        let file_name = file.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if let Ok(re) = Regex::new(regex_str)
+            && !re.is_match(file_name)
+        {
+            return false;
+        }
+    }
+
     // file_path_contains filter
     if let Some(ref substr) = m.file_path_contains {
         let path_str = file.to_string_lossy();
@@ -163,6 +199,9 @@ mod tests {
             parent_class: None,
             source_text: source.to_string(),
             signature: None,
+            metadata: rpg_core::graph::EntityMetadata::default(),
+            signals: None,
+            discriminator: None,
         }
     }
 
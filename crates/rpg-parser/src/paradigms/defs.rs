@@ -2,12 +2,17 @@
 //!
 //! Each TOML file in `paradigms/defs/` defines a paradigm (React, Next.js, Redux, Django, etc.).
 //! Adding a new paradigm = drop a TOML file + `cargo build`. No Rust edits needed.
+//! Projects and users can add more at runtime in `paradigms/` directories
+//! (see [`crate::user_defs`]).
 
+use super::query_engine::QueryCache;
+use crate::user_defs::{DefFile, PARADIGMS_DIR, def_files};
 use regex::Regex;
 use rpg_core::graph::{EdgeKind, EntityKind};
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::Path;
 
 // ---------------------------------------------------------------------------
 // Core types
@@ -87,7 +92,11 @@ pub struct EntityMatch {
     pub max_loops: Option<usize>,
     pub max_calls: Option<usize>,
     pub source_contains_any: Option<Vec<String>>,
+    /// Decorators, attributes or annotations, matched against the name before
+    /// any arguments (`Test`, `tokio::test`, `pytest.mark.parametrize`).
+    pub decorator_any: Option<Vec<String>>,
     pub file_name_stem: Option<String>,
+    pub file_name_regex: Option<String>,
     pub file_path_contains: Option<String>,
 }
 
@@ -99,6 +108,8 @@ pub struct EntityQuery {
     pub entity_kind: String,
     pub entity_name: String,
     pub parent: Option<String>,
+    /// Capture spanning the whole entity (defaults to the name capture's parent).
+    pub span: Option<String>,
     pub query: String,
     #[serde(default)]
     pub query_by_language: HashMap<String, String>,
@@ -182,6 +193,10 @@ pub fn parse_entity_kind(s: &str) -> Option<EntityKind> {
         "middleware" => Some(EntityKind::Middleware),
         "route" => Some(EntityKind::Route),
         "test" => Some(EntityKind::Test),
+        "field" => Some(EntityKind::Field),
+        "constant" => Some(EntityKind::Constant),
+        "enum_variant" => Some(EntityKind::EnumVariant),
+        "global" => Some(EntityKind::Global),
         _ => None,
     }
 }
@@ -202,12 +217,17 @@ pub fn parse_edge_kind(s: &str) -> Option<EdgeKind> {
     }
 }
 
+/// The regex-valued fields of a match rule.
+fn match_regexes(m: &EntityMatch) -> impl Iterator<Item = &String> {
+    m.name_regex.iter().chain(&m.file_name_regex)
+}
+
 /// Validate all TOML definitions at load time.
 ///
 /// Checks:
 /// - `schema_version` is 1
 /// - All `entity_kind`/`edge_kind` values are valid variants
-/// - All `name_regex` fields compile as `Regex`
+/// - All `name_regex`/`file_name_regex` fields compile as `Regex`
 /// - No duplicate rule IDs across all loaded definitions
 pub fn validate_defs(defs: &[ParadigmDef]) -> Result<(), Vec<ValidationError>> {
     let mut errors = Vec::new();
@@ -248,15 +268,15 @@ pub fn validate_defs(defs: &[ParadigmDef]) -> Result<(), Vec<ValidationError>> {
                 });
             }
 
-            // Validate regex
-            if let Some(ref regex_str) = rule.match_rule.name_regex
-                && Regex::new(regex_str).is_err()
-            {
-                errors.push(ValidationError {
-                    paradigm: def.name.clone(),
-                    rule_id: Some(rule.id.clone()),
-                    message: format!("invalid regex '{}'", regex_str),
-                });
+            // Validate regexes
+            for regex_str in match_regexes(&rule.match_rule) {
+                if Regex::new(regex_str).is_err() {
+                    errors.push(ValidationError {
+                        paradigm: def.name.clone(),
+                        rule_id: Some(rule.id.clone()),
+                        message: format!("invalid regex '{}'", regex_str),
+                    });
+                }
             }
         }
 
@@ -316,15 +336,15 @@ pub fn validate_defs(defs: &[ParadigmDef]) -> Result<(), Vec<ValidationError>> {
                 });
             }
 
-            // Validate regex in match rule
-            if let Some(ref regex_str) = rule.match_rule.name_regex
-                && Regex::new(regex_str).is_err()
-            {
-                errors.push(ValidationError {
-                    paradigm: def.name.clone(),
-                    rule_id: Some(rule.id.clone()),
-                    message: format!("invalid regex '{}'", regex_str),
-                });
+            // Validate regexes in match rule
+            for regex_str in match_regexes(&rule.match_rule) {
+                if Regex::new(regex_str).is_err() {
+                    errors.push(ValidationError {
+                        paradigm: def.name.clone(),
+                        rule_id: Some(rule.id.clone()),
+                        message: format!("invalid regex '{}'", regex_str),
+                    });
+                }
             }
 
             // Validate template variables
@@ -423,6 +443,92 @@ pub fn load_builtin_defs() -> Result<Vec<ParadigmDef>, Vec<ValidationError>> {
     Ok(defs)
 }
 
+/// Load built-in paradigm definitions merged with the runtime ones from the
+/// user config dir and `.rpg/paradigms/` (see [`crate::user_defs`]).
+///
+/// A runtime def replaces any earlier def with the same name. Fails if a
+/// builtin is invalid or any runtime def was rejected.
+pub fn load_defs(project_root: &Path) -> Result<Vec<ParadigmDef>, Vec<ValidationError>> {
+    let mut defs = load_builtin_defs()?;
+    let (user_defs, errors) = load_user_paradigms(project_root);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    for (def, _) in user_defs {
+        merge_def(&mut defs, def);
+    }
+    Ok(defs)
+}
+
+/// Like [`load_defs`], but falls back to the builtins (with a warning on
+/// stderr) when runtime definitions are invalid.
+pub fn load_defs_or_builtin(project_root: &Path) -> Vec<ParadigmDef> {
+    load_defs(project_root).unwrap_or_else(|errs| {
+        for e in &errs {
+            eprintln!("warning: ignoring paradigm definitions: {}", e);
+        }
+        load_builtin_defs().unwrap_or_default()
+    })
+}
+
+/// Parse and validate the runtime paradigm defs of a project, in load order.
+///
+/// Each def is validated against the builtins plus the defs accepted before
+/// it, and its queries must compile. Rejected files and defs are returned as
+/// errors; the rest are returned with the file they came from.
+pub fn load_user_paradigms(
+    project_root: &Path,
+) -> (Vec<(ParadigmDef, DefFile)>, Vec<ValidationError>) {
+    let mut merged = load_builtin_defs().unwrap_or_default();
+    let mut accepted = Vec::new();
+    let mut errors = Vec::new();
+    for file in def_files(project_root, PARADIGMS_DIR) {
+        let parsed = file
+            .read()
+            .and_then(|content| toml::from_str::<ParadigmDef>(&content).map_err(|e| e.to_string()));
+        let def = match parsed {
+            Ok(def) => def,
+            Err(message) => {
+                errors.push(ValidationError {
+                    paradigm: file.path.display().to_string(),
+                    rule_id: None,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let mut candidate = merged.clone();
+        merge_def(&mut candidate, def.clone());
+        if let Err(errs) = validate_defs(&candidate) {
+            errors.extend(errs);
+            continue;
+        }
+        if let Err(errs) = QueryCache::compile_all(std::slice::from_ref(&def)) {
+            errors.extend(errs.into_iter().map(|message| ValidationError {
+                paradigm: def.name.clone(),
+                rule_id: None,
+                message,
+            }));
+            continue;
+        }
+        merged = candidate;
+        accepted.push((def, file));
+    }
+    (accepted, errors)
+}
+
+/// Insert `def`, replacing any def with the same name, keeping priority order.
+fn merge_def(defs: &mut Vec<ParadigmDef>, def: ParadigmDef) {
+    defs.retain(|d| d.name != def.name);
+    defs.push(def);
+    defs.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -434,7 +540,7 @@ mod tests {
     #[test]
     fn test_load_builtin_defs() {
         let defs = load_builtin_defs().expect("built-in defs should load and validate");
-        assert!(defs.len() >= 31, "expected at least 31 paradigm defs");
+        assert!(defs.len() >= 32, "expected at least 32 paradigm defs");
 
         // Verify priority ordering (ascending priority, then alphabetical name)
         let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
@@ -446,6 +552,7 @@ mod tests {
                 "compose", // 10
                 "django",  // 10
                 "gin",     // 10
+                "jest",    // 10
                 "laravel", // 10
                 "nestjs",  // 10
                 "nextjs",  // 10
@@ -496,6 +603,11 @@ mod tests {
         );
         assert_eq!(parse_entity_kind("route"), Some(EntityKind::Route));
         assert_eq!(parse_entity_kind("test"), Some(EntityKind::Test));
+        assert_eq!(parse_entity_kind("field"), Some(EntityKind::Field));
+        assert_eq!(
+            parse_entity_kind("enum_variant"),
+            Some(EntityKind::EnumVariant)
+        );
         assert_eq!(parse_entity_kind("bogus"), None);
     }
 
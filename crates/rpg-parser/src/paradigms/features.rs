@@ -7,7 +7,7 @@ use super::defs::ParadigmDef;
 use crate::deps::{self, CallDep, FunctionScope, RawDeps, find_enclosing_scope};
 use crate::entities::RawEntity;
 use crate::languages::Language;
-use rpg_core::graph::EntityKind;
+use rpg_core::graph::{EntityKind, EntityMetadata};
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -508,6 +508,9 @@ fn extract_reducer_keys(
                 parent_class: Some(slice_name.to_string()),
                 source_text: source[child.byte_range()].to_string(),
                 signature: None,
+                metadata: EntityMetadata::default(),
+                signals: None,
+                discriminator: None,
             });
         }
     }
@@ -559,6 +562,9 @@ fn extract_destructured_hooks(
                                 parent_class: None,
                                 source_text: source[child.byte_range()].to_string(),
                                 signature: None,
+                                metadata: EntityMetadata::default(),
+                                signals: None,
+                                discriminator: None,
                             });
                         }
                     }
@@ -7,7 +7,7 @@ use super::defs::{ParadigmDef, parse_edge_kind, parse_entity_kind};
 use crate::deps::{CallDep, FunctionScope, RawDeps, find_enclosing_scope};
 use crate::entities::RawEntity;
 use crate::languages::Language;
-use rpg_core::graph::EdgeKind;
+use rpg_core::graph::{EdgeKind, EntityMetadata};
 use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::StreamingIterator;
@@ -187,16 +187,26 @@ pub fn execute_entity_queries(
                 }
             });
 
+            // Capture spanning the whole entity, if the query names one
+            let span_idx = eq
+                .span
+                .as_deref()
+                .and_then(|s| query.capture_index_for_name(s.trim_start_matches('@')));
+
             let mut cursor = tree_sitter::QueryCursor::new();
             let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
             while let Some(m) = matches.next() {
                 let name_node = m.captures.iter().find(|c| c.index == name_idx);
                 if let Some(cap) = name_node {
                     let name = source[cap.node.byte_range()].to_string();
-                    let src_range = cap.node.parent().map_or_else(
+                    let span_node = span_idx
+                        .and_then(|idx| m.captures.iter().find(|c| c.index == idx))
+                        .map(|c| c.node);
+                    let src_range = span_node.or_else(|| cap.node.parent()).map_or_else(
                         || cap.node.byte_range(),
                         |p: tree_sitter::Node<'_>| p.byte_range(),
                     );
+                    let lines = span_node.unwrap_or(cap.node);
 
                     // Resolve parent: from capture, literal string, or None
                     let parent_class = if let Some(pidx) = parent_capture_idx {
@@ -212,11 +222,14 @@ pub fn execute_entity_queries(
                         name,
                         kind: entity_kind,
                         file: file.to_path_buf(),
-                        line_start: cap.node.start_position().row + 1,
-                        line_end: cap.node.end_position().row + 1,
+                        line_start: lines.start_position().row + 1,
+                        line_end: lines.end_position().row + 1,
                         parent_class,
                         source_text: source[src_range].to_string(),
                         signature: None,
+                        metadata: EntityMetadata::default(),
+                        signals: None,
+                        discriminator: None,
                     });
                 }
             }
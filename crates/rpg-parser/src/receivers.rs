@@ -0,0 +1,1024 @@
+//! Static receiver types of method calls in statically typed languages.
+//!
+//! Java, C#, Go, Rust, Kotlin, TypeScript and Swift declare the types of
+//! parameters, fields and most local variables. For every call site this
+//! module infers the type of the receiver of `recv.method()` from those
+//! declarations: a parameter or local variable in scope, a field of the
+//! enclosing type (`this.repo`, `self.repo`, or a bare `repo` where members
+//! are implicit), a constructor expression (`new Repo()`, `Repo()`,
+//! `&Repo{}`, `Repo::new()`) or a type name for static calls (`Repo.open()`,
+//! `Repo::open()`). Grounding uses it to resolve the call to `Repo`'s method
+//! instead of any method with that name.
+//!
+//! Inference is flow-insensitive within a function and does not follow the
+//! return types of calls, so `var r = repos.get(id)` leaves `r` untyped.
+
+use crate::languages::Language;
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// A call site and what it is called on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiverCall {
+    /// 1-indexed line of the call.
+    pub line: usize,
+    /// Called method or function name, as recorded in `invokes`.
+    pub method: String,
+    pub receiver: Receiver,
+    /// Path qualifying a call with receiver [`Receiver::None`] (`std::fs` in
+    /// `std::fs::metadata()`, `fmt` in `fmt.Println()`); `None` for bare calls.
+    pub module: Option<String>,
+    /// Number of arguments passed, counting a trailing lambda; `None` when a
+    /// spread or splat argument hides it.
+    pub args: Option<usize>,
+}
+
+/// The receiver of a call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Receiver {
+    /// A bare call (`save()`), or one qualified by what may be a module,
+    /// package or namespace (`fmt.Println()`, `utils::parse()`).
+    None,
+    /// A value whose type could not be inferred (`lookup().save()`).
+    Unknown,
+    /// A value or type named as by [`type_name`].
+    Typed(String),
+}
+
+/// Whether receiver types are inferred for `language`.
+pub fn supports(language: Language) -> bool {
+    [
+        Language::JAVA,
+        Language::CSHARP,
+        Language::GO,
+        Language::RUST,
+        Language::KOTLIN,
+        Language::TYPESCRIPT,
+        Language::SWIFT,
+    ]
+    .contains(&language)
+}
+
+/// Whether [`receiver_calls`] reports the call sites of `language`: those
+/// covered by [`supports`], and Python, JavaScript and C++, whose calls carry
+/// the name path they are made on (`os.remove()`) and their argument count
+/// but no receiver type.
+pub fn records_calls(language: Language) -> bool {
+    supports(language)
+        || [Language::PYTHON, Language::JAVASCRIPT, Language::CPP].contains(&language)
+}
+
+/// Every call site in `source`, with the receiver type where it is known.
+/// Returns nothing for languages not covered by [`records_calls`]; in those
+/// not covered by [`supports`], a call on anything but a name path is
+/// reported as a bare call.
+pub fn receiver_calls(source: &str, language: Language) -> Vec<ReceiverCall> {
+    if !records_calls(language) {
+        return Vec::new();
+    }
+    let mut parser = tree_sitter::Parser::new();
+    if parser.set_language(&language.ts_language()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+        return Vec::new();
+    };
+    if !supports(language) {
+        let mut calls = Vec::new();
+        untyped_calls(tree.root_node(), source, &mut calls);
+        return calls;
+    }
+
+    let mut inference = Inference {
+        source,
+        language,
+        fields: HashMap::new(),
+        scopes: Vec::new(),
+        generics: Vec::new(),
+        calls: Vec::new(),
+    };
+    let root = tree.root_node();
+    inference.collect_fields(root, None, false);
+    inference.visit(root, None);
+    inference.calls
+}
+
+/// The name of a type as written in a declaration, without references,
+/// pointers, nullability, generic arguments or qualifying path:
+/// `&mut repo::Repo<T>` and `Repo?` are both `Repo`. `Box`, `Rc` and `Arc`
+/// are seen through. Returns `None` for arrays, slices, maps, tuples and
+/// function types, and for unions other than with `null`/`undefined`.
+pub fn type_name(text: &str) -> Option<String> {
+    let mut ty = text.trim().trim_start_matches(':').trim();
+    if ty.contains('|') {
+        let mut parts = ty
+            .split('|')
+            .map(str::trim)
+            .filter(|p| !matches!(*p, "null" | "undefined"));
+        ty = parts.next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+    }
+    loop {
+        let trimmed = ty.trim_start_matches(['&', '*']).trim_start();
+        let trimmed = if trimmed.starts_with('\'') {
+            // Rust lifetime of a reference (`&'a Repo`)
+            trimmed
+                .split_once(' ')
+                .map_or("", |(_, rest)| rest.trim_start())
+        } else {
+            trimmed
+        };
+        let trimmed = ["mut ", "dyn ", "impl ", "const ", "readonly "]
+            .iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix))
+            .unwrap_or(trimmed);
+        if trimmed == ty {
+            break;
+        }
+        ty = trimmed;
+    }
+    let ty = ty.trim_end_matches(['?', '!']);
+    if ty.starts_with(['[', '(']) || ty.contains('[') || ty.starts_with("func") {
+        return None;
+    }
+    let (head, args) = match ty.split_once('<') {
+        Some((head, args)) => (head.trim(), Some(args.strip_suffix('>').unwrap_or(args))),
+        None => (ty, None),
+    };
+    let name = head.rsplit(['.', ':']).next().unwrap_or(head);
+    if matches!(name, "Box" | "Rc" | "Arc")
+        && let Some(inner) = args
+    {
+        return type_name(inner);
+    }
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (valid && name != "fn").then(|| name.to_string())
+}
+
+/// Call sites of Python, JavaScript and C++, where no receiver type is known:
+/// a call on a name path (`os.path.join()`, `utils::parse()`) records the
+/// path as its module, any other call is a bare call.
+fn untyped_calls(node: Node, source: &str, calls: &mut Vec<ReceiverCall>) {
+    let text = |n: Node| source[n.byte_range()].to_string();
+    if matches!(node.kind(), "call" | "call_expression")
+        && let Some(callee) = node.child_by_field_name("function")
+    {
+        let target = match callee.kind() {
+            "identifier" => Some((text(callee), None)),
+            "attribute" | "member_expression" => {
+                let member = callee
+                    .child_by_field_name("attribute")
+                    .or_else(|| callee.child_by_field_name("property"));
+                let object = callee.child_by_field_name("object");
+                member.map(|m| (text(m), object.filter(|o| is_name_path(*o)).map(text)))
+            }
+            "field_expression" => callee.child_by_field_name("field").map(|m| {
+                let object = callee.child_by_field_name("argument");
+                (text(m), object.filter(|o| is_name_path(*o)).map(text))
+            }),
+            "qualified_identifier" => callee.child_by_field_name("name").and_then(|m| {
+                let scope = callee.child_by_field_name("scope")?;
+                (m.kind() == "identifier").then(|| (text(m), Some(text(scope))))
+            }),
+            _ => None,
+        };
+        if let Some((method, module)) = target {
+            calls.push(ReceiverCall {
+                line: node.start_position().row + 1,
+                method,
+                receiver: Receiver::None,
+                module,
+                args: argument_count(node),
+            });
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        untyped_calls(child, source, calls);
+    }
+}
+
+/// Number of arguments passed by `call`: the entries of its argument list,
+/// or for Kotlin and Swift its value arguments plus a trailing lambda. `None`
+/// if an argument is spread (`...xs`, `*args`).
+fn argument_count(call: Node) -> Option<usize> {
+    let mut parts = Vec::new();
+    if let Some(list) = call.child_by_field_name("arguments") {
+        parts.push(list);
+    } else {
+        let mut cursor = call.walk();
+        for child in call.named_children(&mut cursor).skip(1) {
+            if child.kind() == "call_suffix" {
+                let mut cursor = child.walk();
+                parts.extend(child.named_children(&mut cursor));
+            } else {
+                parts.push(child);
+            }
+        }
+        // Kotlin wraps `f(x) { ... }` in a call whose callee is `f(x)`
+        if let Some(parent) = call.parent()
+            && parent.kind() == "call_expression"
+            && parent.named_child(0) == Some(call)
+        {
+            let mut cursor = parent.walk();
+            parts.extend(
+                parent
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() == "annotated_lambda"),
+            );
+        }
+    }
+
+    let mut count = 0;
+    for part in parts {
+        match part.kind() {
+            "annotated_lambda" | "lambda_literal" => count += 1,
+            "argument_list" | "arguments" | "value_arguments" => {
+                let mut cursor = part.walk();
+                for arg in part.named_children(&mut cursor) {
+                    let spread = |n: Node| {
+                        matches!(
+                            n.kind(),
+                            "spread_element"
+                                | "list_splat"
+                                | "dictionary_splat"
+                                | "variadic_argument"
+                        )
+                    };
+                    if spread(arg) || arg.named_child(0).is_some_and(spread) {
+                        return None;
+                    }
+                    if arg.kind() != "comment" {
+                        count += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(count)
+}
+
+/// Whether `node` is a dotted path of plain names (`os`, `os.path`).
+fn is_name_path(node: Node) -> bool {
+    match node.kind() {
+        "identifier" => true,
+        "attribute" | "member_expression" => {
+            node.child_by_field_name("object").is_some_and(is_name_path)
+        }
+        _ => false,
+    }
+}
+
+/// Node kinds that open a function scope.
+fn is_function(language: Language, kind: &str) -> bool {
+    let kinds: &[&str] = if language == Language::JAVA {
+        &[
+            "method_declaration",
+            "constructor_declaration",
+            "compact_constructor_declaration",
+            "lambda_expression",
+        ]
+    } else if language == Language::CSHARP {
+        &[
+            "method_declaration",
+            "constructor_declaration",
+            "local_function_statement",
+            "lambda_expression",
+            "accessor_declaration",
+            "operator_declaration",
+        ]
+    } else if language == Language::GO {
+        &["function_declaration", "method_declaration", "func_literal"]
+    } else if language == Language::RUST {
+        &["function_item", "closure_expression"]
+    } else if language == Language::KOTLIN {
+        &[
+            "function_declaration",
+            "secondary_constructor",
+            "anonymous_function",
+            "lambda_literal",
+            "anonymous_initializer",
+            "getter",
+            "setter",
+        ]
+    } else if language == Language::TYPESCRIPT {
+        &[
+            "function_declaration",
+            "generator_function_declaration",
+            "method_definition",
+            "arrow_function",
+            "function_expression",
+        ]
+    } else {
+        &[
+            "function_declaration",
+            "init_declaration",
+            "deinit_declaration",
+            "lambda_literal",
+        ]
+    };
+    kinds.contains(&kind)
+}
+
+/// Languages where a bare identifier can name a member of the enclosing type.
+fn has_implicit_members(language: Language) -> bool {
+    [
+        Language::JAVA,
+        Language::CSHARP,
+        Language::KOTLIN,
+        Language::SWIFT,
+    ]
+    .contains(&language)
+}
+
+struct Inference<'a> {
+    source: &'a str,
+    language: Language,
+    /// Field types by the name of the declaring type.
+    fields: HashMap<String, HashMap<String, String>>,
+    /// Variables of the enclosing functions, innermost last; `None` for a
+    /// variable whose type is unknown (it still shadows fields).
+    scopes: Vec<HashMap<String, Option<String>>>,
+    /// Type parameters in scope, which name no concrete type.
+    generics: Vec<String>,
+    calls: Vec<ReceiverCall>,
+}
+
+impl<'a> Inference<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        &self.source[node.byte_range()]
+    }
+
+    /// First pass: types of the fields of every type declared in the file,
+    /// so members used before their declaration are known.
+    fn collect_fields(&mut self, node: Node, class: Option<&str>, in_function: bool) {
+        let own_class = self.class_name(node);
+        let class = own_class.as_deref().or(class);
+        let in_function = in_function || is_function(self.language, node.kind());
+
+        if let Some(class) = class
+            && (!in_function || self.is_parameter_property(node))
+        {
+            for (name, ty) in self.declared(node) {
+                if let Some(ty) = ty {
+                    self.fields
+                        .entry(class.to_string())
+                        .or_default()
+                        .insert(name, ty);
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect_fields(child, class, in_function);
+        }
+    }
+
+    /// Second pass: record calls, tracking variables in scope.
+    fn visit(&mut self, node: Node, class: Option<&str>) {
+        let own_class = self.class_name(node);
+        let class = own_class.as_deref().or(class);
+        let function = is_function(self.language, node.kind());
+        let generics = self.generics.len();
+        if function || own_class.is_some() {
+            self.collect_generics(node);
+        }
+        if function {
+            let parameters = self.lambda_parameters(node);
+            self.scopes
+                .push(parameters.into_iter().map(|p| (p, None)).collect());
+        }
+
+        if let Some((method, receiver)) = self.call_target(node) {
+            let (receiver, module) = match receiver {
+                Some(node) => {
+                    let receiver = self.receiver(node, class);
+                    let module = (receiver == Receiver::None).then(|| self.text(node).to_string());
+                    (receiver, module)
+                }
+                None => (Receiver::None, None),
+            };
+            self.calls.push(ReceiverCall {
+                line: node.start_position().row + 1,
+                method,
+                receiver,
+                module,
+                args: argument_count(node),
+            });
+        }
+
+        // Declared before visiting the children, which hold the uses of
+        // loop variables; initializers were typed without the new variable
+        if !function && !self.scopes.is_empty() {
+            for (name, ty) in self.declared(node) {
+                let ty = ty.filter(|t| !self.generics.contains(t));
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.insert(name, ty);
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit(child, class);
+        }
+
+        if function {
+            self.scopes.pop();
+        }
+        self.generics.truncate(generics);
+    }
+
+    /// Type parameters declared by a type or function node.
+    fn collect_generics(&mut self, node: Node) {
+        let mut cursor = node.walk();
+        let lists: Vec<Node> = node
+            .named_children(&mut cursor)
+            .filter(|c| matches!(c.kind(), "type_parameters" | "type_parameter_list"))
+            .collect();
+        for list in lists {
+            let mut cursor = list.walk();
+            for param in list.named_children(&mut cursor) {
+                if let Some(name) = param
+                    .child_by_field_name("name")
+                    .or_else(|| param.named_child(0))
+                {
+                    self.generics.push(self.text(name).to_string());
+                }
+            }
+        }
+    }
+
+    /// Name of the type whose members `node` declares, for type declarations
+    /// and Rust `impl` blocks.
+    fn class_name(&self, node: Node) -> Option<String> {
+        let kind = node.kind();
+        let lang = self.language;
+        let field = if lang == Language::RUST {
+            match kind {
+                "impl_item" => "type",
+                "struct_item" | "trait_item" | "enum_item" | "union_item" => "name",
+                _ => return None,
+            }
+        } else if lang == Language::GO {
+            if kind != "type_spec" {
+                return None;
+            }
+            "name"
+        } else {
+            let kinds: &[&str] = if lang == Language::JAVA {
+                &[
+                    "class_declaration",
+                    "interface_declaration",
+                    "enum_declaration",
+                    "record_declaration",
+                ]
+            } else if lang == Language::CSHARP {
+                &[
+                    "class_declaration",
+                    "struct_declaration",
+                    "interface_declaration",
+                    "record_declaration",
+                    "record_struct_declaration",
+                ]
+            } else if lang == Language::KOTLIN {
+                &["class_declaration", "object_declaration"]
+            } else if lang == Language::TYPESCRIPT {
+                &[
+                    "class_declaration",
+                    "abstract_class_declaration",
+                    "class",
+                    "interface_declaration",
+                ]
+            } else {
+                &["class_declaration", "protocol_declaration"]
+            };
+            if !kinds.contains(&kind) {
+                return None;
+            }
+            "name"
+        };
+        type_name(self.text(node.child_by_field_name(field)?))
+    }
+
+    /// TypeScript constructor parameters declared with an accessibility
+    /// modifier, which are also fields (`constructor(private repo: Repo)`).
+    fn is_parameter_property(&self, node: Node) -> bool {
+        self.language == Language::TYPESCRIPT
+            && matches!(node.kind(), "required_parameter" | "optional_parameter")
+            && {
+                let mut cursor = node.walk();
+                node.named_children(&mut cursor)
+                    .any(|c| c.kind() == "accessibility_modifier")
+            }
+    }
+
+    /// Variables declared by `node`, with their type where it is written or
+    /// follows from the initializer.
+    fn declared(&self, node: Node) -> Vec<(String, Option<String>)> {
+        let lang = self.language;
+        let kind = node.kind();
+        let mut out = Vec::new();
+        if lang == Language::JAVA {
+            match kind {
+                "local_variable_declaration" | "field_declaration" | "constant_declaration" => {
+                    let ty = node.child_by_field_name("type");
+                    let mut cursor = node.walk();
+                    for declarator in node.children_by_field_name("declarator", &mut cursor) {
+                        let Some(name) = declarator.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let value = declarator.child_by_field_name("value");
+                        out.push((self.text(name).to_string(), self.declared_type(ty, value)));
+                    }
+                }
+                "formal_parameter" | "enhanced_for_statement" | "resource" => {
+                    self.push_typed(&mut out, node, "name", "type", "value");
+                }
+                _ => {}
+            }
+        } else if lang == Language::CSHARP {
+            match kind {
+                "variable_declaration" => {
+                    let ty = node.child_by_field_name("type");
+                    let mut cursor = node.walk();
+                    for declarator in node.named_children(&mut cursor) {
+                        if declarator.kind() != "variable_declarator" {
+                            continue;
+                        }
+                        let Some(name) = declarator
+                            .child_by_field_name("name")
+                            .or_else(|| declarator.named_child(0))
+                        else {
+                            continue;
+                        };
+                        let mut inner = declarator.walk();
+                        let value = declarator
+                            .named_children(&mut inner)
+                            .find(|c| c.id() != name.id() && c.kind() != "bracketed_argument_list")
+                            .map(|c| {
+                                if c.kind() == "equals_value_clause" {
+                                    c.named_child(0).unwrap_or(c)
+                                } else {
+                                    c
+                                }
+                            });
+                        out.push((self.text(name).to_string(), self.declared_type(ty, value)));
+                    }
+                }
+                "parameter" | "property_declaration" => {
+                    self.push_typed(&mut out, node, "name", "type", "value");
+                }
+                "foreach_statement" => self.push_typed(&mut out, node, "left", "type", ""),
+                _ => {}
+            }
+        } else if lang == Language::GO {
+            match kind {
+                "parameter_declaration" | "var_spec" | "const_spec" | "field_declaration" => {
+                    let ty = node.child_by_field_name("type");
+                    let values: Vec<Node> = node
+                        .child_by_field_name("value")
+                        .map(|v| {
+                            let mut cursor = v.walk();
+                            v.named_children(&mut cursor).collect()
+                        })
+                        .unwrap_or_default();
+                    let mut cursor = node.walk();
+                    for (i, name) in node.children_by_field_name("name", &mut cursor).enumerate() {
+                        let value = values.get(i).copied();
+                        out.push((self.text(name).to_string(), self.declared_type(ty, value)));
+                    }
+                }
+                "short_var_declaration" => {
+                    let (Some(left), Some(right)) = (
+                        node.child_by_field_name("left"),
+                        node.child_by_field_name("right"),
+                    ) else {
+                        return out;
+                    };
+                    let mut cursor = right.walk();
+                    let values: Vec<Node> = right.named_children(&mut cursor).collect();
+                    let mut cursor = left.walk();
+                    for (i, name) in left.named_children(&mut cursor).enumerate() {
+                        let ty = values.get(i).and_then(|v| self.expr_type(*v, None));
+                        out.push((self.text(name).to_string(), ty));
+                    }
+                }
+                "range_clause" => {
+                    let Some(left) = node.child_by_field_name("left") else {
+                        return out;
+                    };
+                    let mut cursor = left.walk();
+                    for name in left.named_children(&mut cursor) {
+                        out.push((self.text(name).to_string(), None));
+                    }
+                }
+                _ => {}
+            }
+        } else if lang == Language::RUST {
+            match kind {
+                "parameter" | "let_declaration" => {
+                    let Some(pattern) = node.child_by_field_name("pattern") else {
+                        return out;
+                    };
+                    let name = match pattern.kind() {
+                        "identifier" => pattern,
+                        "mut_pattern" => match pattern.named_child(0) {
+                            Some(inner) if inner.kind() == "identifier" => inner,
+                            _ => return out,
+                        },
+                        _ => return out,
+                    };
+                    let ty = self.declared_type(
+                        node.child_by_field_name("type"),
+                        node.child_by_field_name("value"),
+                    );
+                    out.push((self.text(name).to_string(), ty));
+                }
+                "field_declaration" => self.push_typed(&mut out, node, "name", "type", ""),
+                "for_expression" => self.push_typed(&mut out, node, "pattern", "", ""),
+                _ => {}
+            }
+        } else if lang == Language::KOTLIN {
+            match kind {
+                "parameter" | "class_parameter" => {
+                    let mut cursor = node.walk();
+                    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+                    let name = children.iter().find(|c| c.kind() == "identifier");
+                    let ty = children.iter().find(|c| c.kind().ends_with("_type"));
+                    if let Some(name) = name {
+                        let ty = ty.and_then(|t| type_name(self.text(*t)));
+                        out.push((self.text(*name).to_string(), ty));
+                    }
+                }
+                "for_statement" => {
+                    let mut cursor = node.walk();
+                    let variable = node
+                        .named_children(&mut cursor)
+                        .find(|c| c.kind() == "variable_declaration");
+                    if let Some(name) = variable.and_then(|v| v.named_child(0)) {
+                        let ty = variable.and_then(|v| v.named_child(1));
+                        let ty = ty.and_then(|t| type_name(self.text(t)));
+                        out.push((self.text(name).to_string(), ty));
+                    }
+                }
+                "property_declaration" => {
+                    let mut cursor = node.walk();
+                    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+                    let Some(pos) = children
+                        .iter()
+                        .position(|c| c.kind() == "variable_declaration")
+                    else {
+                        return out;
+                    };
+                    let variable = children[pos];
+                    let Some(name) = variable.named_child(0) else {
+                        return out;
+                    };
+                    let ty = variable.named_child(1);
+                    let value = children.get(pos + 1).copied().filter(|c| {
+                        !matches!(
+                            c.kind(),
+                            "getter" | "setter" | "property_delegate" | "type_constraints"
+                        )
+                    });
+                    out.push((self.text(name).to_string(), self.declared_type(ty, value)));
+                }
+                _ => {}
+            }
+        } else if lang == Language::TYPESCRIPT {
+            match kind {
+                "required_parameter" | "optional_parameter" => {
+                    self.push_typed(&mut out, node, "pattern", "type", "value");
+                }
+                "variable_declarator" | "public_field_definition" => {
+                    self.push_typed(&mut out, node, "name", "type", "value");
+                }
+                "for_in_statement" => self.push_typed(&mut out, node, "left", "", ""),
+                _ => {}
+            }
+        } else if lang == Language::SWIFT {
+            match kind {
+                "parameter" => {
+                    let mut cursor = node.walk();
+                    let mut name = None;
+                    let mut ty = None;
+                    for child in node.children_by_field_name("name", &mut cursor) {
+                        if child.kind() == "simple_identifier" {
+                            name = Some(child);
+                        } else {
+                            ty = Some(child);
+                        }
+                    }
+                    if let Some(name) = name {
+                        out.push((
+                            self.text(name).to_string(),
+                            ty.and_then(|t| type_name(self.text(t))),
+                        ));
+                    }
+                }
+                "property_declaration" => {
+                    let Some(name) = node
+                        .child_by_field_name("name")
+                        .and_then(|p| p.child_by_field_name("bound_identifier"))
+                    else {
+                        return out;
+                    };
+                    let mut cursor = node.walk();
+                    let ty = node
+                        .named_children(&mut cursor)
+                        .find(|c| c.kind() == "type_annotation")
+                        .and_then(|a| a.child_by_field_name("name"));
+                    let value = node.child_by_field_name("value");
+                    out.push((self.text(name).to_string(), self.declared_type(ty, value)));
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+
+    /// Push the variable named by `name_field` of `node`, typed by its
+    /// `type_field` or else by its `value_field` initializer.
+    fn push_typed(
+        &self,
+        out: &mut Vec<(String, Option<String>)>,
+        node: Node,
+        name_field: &str,
+        type_field: &str,
+        value_field: &str,
+    ) {
+        let Some(name) = node.child_by_field_name(name_field) else {
+            return;
+        };
+        if !matches!(
+            name.kind(),
+            "identifier" | "field_identifier" | "property_identifier" | "simple_identifier"
+        ) {
+            return;
+        }
+        let ty = self.declared_type(
+            node.child_by_field_name(type_field),
+            node.child_by_field_name(value_field),
+        );
+        out.push((self.text(name).to_string(), ty));
+    }
+
+    /// The written type, unless it asks for inference (`var`, `implicit_type`),
+    /// else the type of the initializer.
+    fn declared_type(&self, ty: Option<Node>, value: Option<Node>) -> Option<String> {
+        if let Some(ty) = ty
+            && ty.kind() != "implicit_type"
+        {
+            let ty = self.text(ty);
+            if ty != "var" {
+                return type_name(ty);
+            }
+        }
+        self.expr_type(value?, None)
+    }
+
+    /// Called method name and receiver expression of a call node.
+    fn call_target<'t>(&self, node: Node<'t>) -> Option<(String, Option<Node<'t>>)> {
+        let lang = self.language;
+        let name = |n: Node| self.text(n).to_string();
+        if lang == Language::JAVA {
+            if node.kind() != "method_invocation" {
+                return None;
+            }
+            let method = node.child_by_field_name("name")?;
+            return Some((name(method), node.child_by_field_name("object")));
+        }
+        let callee = match node.kind() {
+            "call_expression" if lang == Language::KOTLIN || lang == Language::SWIFT => {
+                node.named_child(0)?
+            }
+            "call_expression" => node.child_by_field_name("function")?,
+            "invocation_expression" if lang == Language::CSHARP => {
+                node.child_by_field_name("function")?
+            }
+            _ => return None,
+        };
+        let callee = if callee.kind() == "generic_function" {
+            callee.child_by_field_name("function")?
+        } else {
+            callee
+        };
+        match callee.kind() {
+            "identifier" | "simple_identifier" => Some((name(callee), None)),
+            "generic_name" => Some((name(callee.named_child(0)?), None)),
+            "member_access_expression" => Some((
+                name(callee.child_by_field_name("name")?),
+                callee.child_by_field_name("expression"),
+            )),
+            "selector_expression" => Some((
+                name(callee.child_by_field_name("field")?),
+                callee.child_by_field_name("operand"),
+            )),
+            "field_expression" => Some((
+                name(callee.child_by_field_name("field")?),
+                callee.child_by_field_name("value"),
+            )),
+            "scoped_identifier" => Some((
+                name(callee.child_by_field_name("name")?),
+                callee.child_by_field_name("path"),
+            )),
+            "member_expression" => Some((
+                name(callee.child_by_field_name("property")?),
+                callee.child_by_field_name("object"),
+            )),
+            "navigation_expression" if lang == Language::KOTLIN => {
+                let mut cursor = callee.walk();
+                let method = callee.named_children(&mut cursor).last()?;
+                Some((name(method), callee.named_child(0)))
+            }
+            "navigation_expression" => Some((
+                name(
+                    callee
+                        .child_by_field_name("suffix")?
+                        .child_by_field_name("suffix")?,
+                ),
+                callee.child_by_field_name("target"),
+            )),
+            _ => None,
+        }
+    }
+
+    /// What a call's receiver expression denotes: a value, or a type for
+    /// static calls.
+    fn receiver(&self, node: Node, class: Option<&str>) -> Receiver {
+        match self.expr_type(node, class) {
+            Some(ty) if !self.generics.contains(&ty) => Receiver::Typed(ty),
+            Some(_) => Receiver::Unknown,
+            None if self.is_module_path(node) => Receiver::None,
+            None => Receiver::Unknown,
+        }
+    }
+
+    /// Whether an untyped receiver may name a module, package or namespace
+    /// rather than a value: an undeclared identifier, or a path of them.
+    fn is_module_path(&self, node: Node) -> bool {
+        match node.kind() {
+            "identifier" | "simple_identifier" | "type_identifier" | "package_identifier" => {
+                let name = self.text(node);
+                !self.scopes.iter().any(|s| s.contains_key(name))
+            }
+            "scoped_identifier" | "scoped_type_identifier" => true,
+            _ => self
+                .member_parts(node)
+                .is_some_and(|(object, _)| self.is_module_path(object)),
+        }
+    }
+
+    /// Untyped parameters of lambdas and closures, which have no declaration
+    /// node of their own (`x -> x.save()`, `|x| x.save()`).
+    fn lambda_parameters(&self, node: Node) -> Vec<String> {
+        let parameters = match node.kind() {
+            "lambda_expression" | "closure_expression" => node.child_by_field_name("parameters"),
+            "arrow_function" => node.child_by_field_name("parameter"),
+            "lambda_literal" => {
+                let mut cursor = node.walk();
+                node.named_children(&mut cursor)
+                    .find(|c| c.kind() == "lambda_parameters")
+            }
+            _ => None,
+        };
+        let Some(parameters) = parameters else {
+            return Vec::new();
+        };
+        if matches!(parameters.kind(), "identifier" | "simple_identifier") {
+            return vec![self.text(parameters).to_string()];
+        }
+        let mut cursor = parameters.walk();
+        parameters
+            .named_children(&mut cursor)
+            .filter_map(|p| match p.kind() {
+                "identifier" | "simple_identifier" => Some(p),
+                "variable_declaration" => p.named_child(0),
+                _ => None,
+            })
+            .map(|p| self.text(p).to_string())
+            .collect()
+    }
+
+    /// Static type of an expression, where it follows from declarations.
+    fn expr_type(&self, node: Node, class: Option<&str>) -> Option<String> {
+        let lang = self.language;
+        match node.kind() {
+            "this" | "self" | "this_expression" | "self_expression" => class.map(str::to_string),
+            "identifier" | "simple_identifier" | "type_identifier" => {
+                self.identifier_type(self.text(node), class)
+            }
+            "parenthesized_expression" => self.expr_type(node.named_child(0)?, class),
+            // Rust paths of associated functions (`repo::Repo::open`, `Repo::<T>::new`)
+            "scoped_identifier" | "scoped_type_identifier" | "generic_type" => {
+                type_name(self.text(node)).filter(|t| t.starts_with(char::is_uppercase))
+            }
+            "object_creation_expression" => type_name(self.text(node.child_by_field_name("type")?)),
+            "new_expression" => type_name(self.text(node.child_by_field_name("constructor")?)),
+            "composite_literal" => type_name(self.text(node.child_by_field_name("type")?)),
+            "struct_expression" => type_name(self.text(node.child_by_field_name("name")?)),
+            "unary_expression" if lang == Language::GO => {
+                self.expr_type(node.child_by_field_name("operand")?, class)
+            }
+            "reference_expression" => self.expr_type(node.child_by_field_name("value")?, class),
+            "call_expression" | "invocation_expression" => self.constructed_type(node, class),
+            "field_access"
+            | "member_access_expression"
+            | "selector_expression"
+            | "field_expression"
+            | "member_expression"
+            | "navigation_expression" => {
+                let (object, field) = self.member_parts(node)?;
+                let field = self.text(field);
+                match self.expr_type(object, class) {
+                    Some(owner) => self.fields.get(&owner)?.get(field).cloned(),
+                    // A qualified type name (`com.acme.Repo.open()`)
+                    None if lang != Language::GO && field.starts_with(char::is_uppercase) => {
+                        type_name(field)
+                    }
+                    None => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Object and member of a member access expression.
+    fn member_parts<'t>(&self, node: Node<'t>) -> Option<(Node<'t>, Node<'t>)> {
+        let field = |name: &str| node.child_by_field_name(name);
+        match node.kind() {
+            "field_access" => Some((field("object")?, field("field")?)),
+            "member_access_expression" => Some((field("expression")?, field("name")?)),
+            "selector_expression" => Some((field("operand")?, field("field")?)),
+            "field_expression" => Some((field("value")?, field("field")?)),
+            "member_expression" => Some((field("object")?, field("property")?)),
+            "navigation_expression" if self.language == Language::KOTLIN => {
+                let mut cursor = node.walk();
+                let member = node.named_children(&mut cursor).last()?;
+                Some((node.named_child(0)?, member))
+            }
+            "navigation_expression" => Some((
+                field("target")?,
+                field("suffix")?.child_by_field_name("suffix")?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Type constructed by a call: `Repo()` in Kotlin and Swift, `new(Repo)`
+    /// in Go, `Repo::new()` and similar constructors in Rust.
+    fn constructed_type(&self, node: Node, class: Option<&str>) -> Option<String> {
+        let lang = self.language;
+        if lang == Language::KOTLIN || lang == Language::SWIFT {
+            let callee = node.named_child(0)?;
+            let name = self.text(callee);
+            return (matches!(callee.kind(), "identifier" | "simple_identifier")
+                && name.starts_with(char::is_uppercase))
+            .then(|| name.to_string());
+        }
+        let callee = node.child_by_field_name("function")?;
+        if lang == Language::GO && self.text(callee) == "new" {
+            let arguments = node.child_by_field_name("arguments")?;
+            return type_name(self.text(arguments.named_child(0)?));
+        }
+        if lang == Language::RUST && callee.kind() == "scoped_identifier" {
+            let method = self.text(callee.child_by_field_name("name")?);
+            let constructs = matches!(method, "new" | "default" | "from")
+                || method.starts_with("new_")
+                || method.starts_with("with_");
+            if constructs {
+                return self.expr_type(callee.child_by_field_name("path")?, class);
+            }
+        }
+        None
+    }
+
+    /// Type of a variable or, failing that, of a field of the enclosing type;
+    /// capitalized names of neither are taken as type names (static calls).
+    fn identifier_type(&self, name: &str, class: Option<&str>) -> Option<String> {
+        if name == "Self" {
+            return class.map(str::to_string);
+        }
+        if let Some(ty) = self.scopes.iter().rev().find_map(|s| s.get(name)) {
+            return ty.clone();
+        }
+        if has_implicit_members(self.language)
+            && let Some(ty) = class
+                .and_then(|c| self.fields.get(c))
+                .and_then(|f| f.get(name))
+        {
+            return Some(ty.clone());
+        }
+        (self.language != Language::GO && name.starts_with(char::is_uppercase))
+            .then(|| name.to_string())
+    }
+}
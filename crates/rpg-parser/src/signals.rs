@@ -1,25 +1,17 @@
-//! Structural signal extraction from source text.
+//! Structural signal extraction from entity source code.
 //!
-//! Language-agnostic regex heuristics for counting branches, loops, early returns,
-//! and function calls in entity source code. Used for confidence-gated auto-lift.
+//! [`analyze_node`] walks the tree-sitter AST of a function-like entity and
+//! computes branch/loop/return/call counts plus cyclomatic and cognitive
+//! complexity, nesting depth and parameter count. [`analyze`] is the
+//! language-agnostic regex fallback for entities without a syntax node.
+//! Both feed confidence-gated auto-lift; the AST signals are also stored on
+//! the entity for health analysis.
 
+use crate::languages::Language;
 use regex::Regex;
+pub use rpg_core::graph::StructuralSignals;
 use std::sync::OnceLock;
-
-/// Structural complexity signals extracted from source text.
-#[derive(Debug, Default, PartialEq, Eq)]
-pub struct StructuralSignals {
-    /// Total line count.
-    pub line_count: usize,
-    /// Branch points: if, else, match, switch, case, when, elif, elsif.
-    pub branch_count: usize,
-    /// Loop constructs: for, while, loop, each, do { }.
-    pub loop_count: usize,
-    /// Return statements not on the last line.
-    pub early_return_count: usize,
-    /// Function/method calls: identifier followed by `(`.
-    pub call_count: usize,
-}
+use tree_sitter::Node;
 
 /// Compute structural signals from raw source text.
 ///
@@ -58,7 +50,544 @@ pub fn analyze(source: &str) -> StructuralSignals {
         loop_count,
         early_return_count,
         call_count,
+        ..StructuralSignals::default()
+    }
+}
+
+/// Declarations and anonymous functions that own a body of control flow.
+const FUNCTION_KINDS: &[&str] = &[
+    "function_definition",
+    "function_declaration",
+    "function_item",
+    "generator_function_declaration",
+    "method_declaration",
+    "method_definition",
+    "method",
+    "singleton_method",
+    "constructor_declaration",
+    "secondary_constructor",
+    "init_declaration",
+    "local_function_statement",
+    "arrow_function",
+    "function_expression",
+    "generator_function",
+    "function",
+    "lambda",
+    "lambda_expression",
+    "lambda_literal",
+    "closure_expression",
+    "func_literal",
+    "anonymous_function",
+];
+
+const IF_KINDS: &[&str] = &[
+    "if_statement",
+    "if_expression",
+    "if",
+    "unless",
+    "if_modifier",
+    "unless_modifier",
+    "guard_statement",
+];
+
+/// Dedicated else-if nodes; grammars without one nest an `if` under the else.
+const ELSE_IF_KINDS: &[&str] = &["elif_clause", "elsif", "else_if_clause"];
+
+const ELSE_KINDS: &[&str] = &["else_clause", "else"];
+
+const SWITCH_KINDS: &[&str] = &[
+    "switch_statement",
+    "switch_expression",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "select_statement",
+    "match_expression",
+    "match_statement",
+    "when_expression",
+    "case",
+    "case_match",
+];
+
+const ARM_KINDS: &[&str] = &[
+    "switch_case",
+    "switch_default",
+    "switch_label",
+    "switch_section",
+    "switch_expression_arm",
+    "switch_entry",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "default_case",
+    "case_statement",
+    "default_statement",
+    "case_clause",
+    "case_item",
+    "match_arm",
+    "match_conditional_expression",
+    "match_default_expression",
+    "when_entry",
+    "when",
+    "in_clause",
+];
+
+const TERNARY_KINDS: &[&str] = &[
+    "conditional_expression",
+    "ternary_expression",
+    "conditional",
+];
+
+const LOOP_KINDS: &[&str] = &[
+    "for_statement",
+    "for_expression",
+    "for_in_statement",
+    "for_range_loop",
+    "enhanced_for_statement",
+    "foreach_statement",
+    "c_style_for_statement",
+    "while_statement",
+    "while_expression",
+    "loop_expression",
+    "do_statement",
+    "do_while_statement",
+    "repeat_while_statement",
+    "for",
+    "while",
+    "until",
+    "while_modifier",
+    "until_modifier",
+];
+
+const CATCH_KINDS: &[&str] = &[
+    "catch_clause",
+    "catch_block",
+    "except_clause",
+    "rescue",
+    "rescue_modifier",
+];
+
+const CALL_KINDS: &[&str] = &[
+    "call_expression",
+    "call",
+    "method_invocation",
+    "invocation_expression",
+    "function_call_expression",
+    "member_call_expression",
+    "nullsafe_member_call_expression",
+    "scoped_call_expression",
+    "object_creation_expression",
+    "new_expression",
+];
+
+const RETURN_KINDS: &[&str] = &["return_statement", "return_expression", "return"];
+
+/// Binary node kinds that may carry a short-circuit boolean operator.
+const BOOLEAN_KINDS: &[&str] = &[
+    "binary_expression",
+    "boolean_operator",
+    "binary",
+    "conjunction_expression",
+    "disjunction_expression",
+    "infix_expression",
+    "list",
+];
+
+const BOOLEAN_OPERATORS: &[&str] = &["&&", "||", "and", "or"];
+
+/// Statement containers skipped when locating a function's final statement.
+const BLOCK_KINDS: &[&str] = &[
+    "block",
+    "statement_block",
+    "compound_statement",
+    "statement_list",
+    "statements",
+    "function_body",
+    "body_statement",
+];
+
+/// Control-flow role of a syntax node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Construct {
+    If,
+    ElseIf,
+    Else,
+    Switch,
+    Arm { default: bool },
+    Ternary,
+    Loop,
+    Catch,
+    Function,
+    Return,
+    Call,
+    Other,
+}
+
+/// Compute structural signals for a function-like entity from its AST.
+///
+/// `node` is the declaration node the entity was extracted from; variable
+/// declarators holding an arrow function or lambda are accepted too.
+/// Returns `None` for classes, types and other containers, whose gating
+/// falls back to [`analyze`].
+pub fn analyze_node(node: &Node, source: &str, language: Language) -> Option<StructuralSignals> {
+    let function = function_node(node)?;
+    let mut walker = Walker {
+        source,
+        language,
+        tail_start: final_statement(&function).map(|n| n.start_byte()),
+        signals: StructuralSignals {
+            line_count: node.end_position().row - node.start_position().row + 1,
+            cyclomatic_complexity: 1,
+            parameter_count: count_parameters(&function, source),
+            ..StructuralSignals::default()
+        },
+    };
+    let mut cursor = function.walk();
+    for child in function.children(&mut cursor) {
+        walker.visit(child, 0, false);
+    }
+    Some(walker.signals)
+}
+
+/// The function node behind an entity declaration, if it has one.
+fn function_node<'t>(node: &Node<'t>) -> Option<Node<'t>> {
+    if FUNCTION_KINDS.contains(&node.kind()) {
+        return Some(*node);
+    }
+    node.child_by_field_name("value")
+        .filter(|v| FUNCTION_KINDS.contains(&v.kind()))
+}
+
+struct Walker<'s> {
+    source: &'s str,
+    language: Language,
+    /// Start of the function's final statement; returns before it are early.
+    tail_start: Option<usize>,
+    signals: StructuralSignals,
+}
+
+impl Walker<'_> {
+    fn visit(&mut self, node: Node, nesting: usize, in_closure: bool) {
+        let mut inner = nesting;
+        let mut in_closure = in_closure;
+        let construct = classify(&node, self.source, self.language);
+        let s = &mut self.signals;
+        match construct {
+            Construct::If => {
+                s.branch_count += 1;
+                s.cyclomatic_complexity += 1;
+                s.cognitive_complexity += 1 + nesting;
+                inner = nesting + 1;
+                if has_plain_else(&node) {
+                    s.branch_count += 1;
+                    s.cognitive_complexity += 1;
+                }
+            }
+            Construct::ElseIf => {
+                s.branch_count += 1;
+                s.cyclomatic_complexity += 1;
+                s.cognitive_complexity += 1;
+                if has_plain_else(&node) {
+                    s.branch_count += 1;
+                    s.cognitive_complexity += 1;
+                }
+            }
+            Construct::Else => {
+                s.branch_count += 1;
+                s.cognitive_complexity += 1;
+            }
+            Construct::Switch => {
+                s.branch_count += 1;
+                s.cognitive_complexity += 1 + nesting;
+                inner = nesting + 1;
+            }
+            Construct::Arm { default } => {
+                s.branch_count += 1;
+                if !default {
+                    s.cyclomatic_complexity += 1;
+                }
+            }
+            Construct::Ternary => {
+                s.cyclomatic_complexity += 1;
+                s.cognitive_complexity += 1 + nesting;
+                inner = nesting + 1;
+            }
+            Construct::Loop => {
+                s.loop_count += 1;
+                s.cyclomatic_complexity += 1;
+                s.cognitive_complexity += 1 + nesting;
+                inner = nesting + 1;
+            }
+            Construct::Catch => {
+                s.cyclomatic_complexity += 1;
+                s.cognitive_complexity += 1 + nesting;
+                inner = nesting + 1;
+            }
+            Construct::Function => {
+                // Nested closures deepen nesting for cognitive complexity but
+                // their returns leave the closure, not the entity.
+                in_closure = true;
+                inner = nesting + 1;
+            }
+            Construct::Return => {
+                if !in_closure && self.tail_start.is_some_and(|tail| node.end_byte() <= tail) {
+                    s.early_return_count += 1;
+                }
+            }
+            Construct::Call => s.call_count += 1,
+            Construct::Other => {
+                if let Some(op) = boolean_operator(&node, self.source) {
+                    s.cyclomatic_complexity += 1;
+                    // One increment per run of the same operator (`a && b && c`)
+                    let continues_run = node
+                        .parent()
+                        .and_then(|p| boolean_operator(&p, self.source))
+                        .is_some_and(|parent_op| parent_op == op);
+                    if !continues_run {
+                        s.cognitive_complexity += 1;
+                    }
+                }
+            }
+        }
+        if inner > nesting && construct != Construct::Function {
+            s.max_nesting_depth = s.max_nesting_depth.max(inner);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.visit(child, inner, in_closure);
+        }
+    }
+}
+
+/// Determine the control-flow role of a node for the given language.
+fn classify(node: &Node, source: &str, language: Language) -> Construct {
+    if !node.is_named() {
+        return Construct::Other;
+    }
+    let kind = node.kind();
+    // Kinds whose meaning differs between grammars
+    match (kind, language) {
+        ("case_statement", Language::BASH) => return Construct::Switch,
+        ("do_statement", Language::SWIFT) => return Construct::Other,
+        ("command", Language::BASH) => return Construct::Call,
+        // Ruby blocks are closures; elsewhere `block` is a plain statement block
+        ("block" | "do_block", Language::RUBY) => return Construct::Function,
+        ("control_transfer_statement" | "jump_expression", _) => {
+            return if source[node.byte_range()].starts_with("return") {
+                Construct::Return
+            } else {
+                Construct::Other
+            };
+        }
+        _ => {}
+    }
+    if IF_KINDS.contains(&kind) {
+        if is_else_if(node) {
+            Construct::ElseIf
+        } else {
+            Construct::If
+        }
+    } else if ELSE_IF_KINDS.contains(&kind) {
+        Construct::ElseIf
+    } else if ELSE_KINDS.contains(&kind) {
+        // `else` wrapping an `if` is an else-if chain, counted at the `if`;
+        // a childless `else` is just the keyword (Swift), counted at its `if`.
+        match first_named_child(node) {
+            Some(child) if !IF_KINDS.contains(&child.kind()) => Construct::Else,
+            _ => Construct::Other,
+        }
+    } else if SWITCH_KINDS.contains(&kind) {
+        Construct::Switch
+    } else if ARM_KINDS.contains(&kind) {
+        Construct::Arm {
+            default: is_default_arm(node, source),
+        }
+    } else if TERNARY_KINDS.contains(&kind) {
+        Construct::Ternary
+    } else if LOOP_KINDS.contains(&kind) {
+        Construct::Loop
+    } else if CATCH_KINDS.contains(&kind) {
+        Construct::Catch
+    } else if FUNCTION_KINDS.contains(&kind) {
+        Construct::Function
+    } else if RETURN_KINDS.contains(&kind) {
+        Construct::Return
+    } else if CALL_KINDS.contains(&kind) {
+        Construct::Call
+    } else {
+        Construct::Other
+    }
+}
+
+/// An `if` that continues an else-if chain: directly under an `else`, or the
+/// non-consequence child of another `if` (Java, Go, C#, Kotlin, Scala, Swift).
+fn is_else_if(node: &Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    if ELSE_KINDS.contains(&parent.kind()) {
+        return true;
+    }
+    IF_KINDS.contains(&parent.kind())
+        && (parent.child_by_field_name("alternative") == Some(*node)
+            || node
+                .prev_sibling()
+                .is_some_and(|prev| prev.kind() == "else"))
+}
+
+/// Whether an `if` has a final `else` branch that is not a wrapper node of
+/// its own (those are counted when visited) and not another `if`. A Swift
+/// `guard` always has one, so it stays a single decision.
+fn has_plain_else(node: &Node) -> bool {
+    if node.kind() == "guard_statement" {
+        return false;
+    }
+    let alternative = node.child_by_field_name("alternative").or_else(|| {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        children
+            .iter()
+            .position(|c| c.kind() == "else")
+            .and_then(|i| children[i + 1..].iter().find(|c| c.is_named()).copied())
+    });
+    alternative.is_some_and(|alt| {
+        let kind = alt.kind();
+        !IF_KINDS.contains(&kind) && !ELSE_IF_KINDS.contains(&kind) && !ELSE_KINDS.contains(&kind)
+    })
+}
+
+/// Default/wildcard arms (`default:`, `else ->`, `_ =>`, `case _:`, `*)`)
+/// do not add a decision point.
+fn is_default_arm(node: &Node, source: &str) -> bool {
+    if first_named_child(node).is_some_and(|c| c.kind() == "default_keyword") {
+        return true;
     }
+    let text = source[node.byte_range()].trim_start();
+    let text = text.strip_prefix("case ").unwrap_or(text).trim_start();
+    text.starts_with("default")
+        || text.starts_with("else")
+        || text.starts_with("*)")
+        || (text.starts_with('_')
+            && !text[1..].starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
+
+/// The short-circuit operator of a boolean node, if it is one.
+fn boolean_operator<'s>(node: &Node, source: &'s str) -> Option<&'s str> {
+    if !BOOLEAN_KINDS.contains(&node.kind()) {
+        return None;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find_map(|child| {
+        let text = &source[child.byte_range()];
+        let is_operator = !child.is_named() || child.kind() == "operator_identifier";
+        (is_operator && BOOLEAN_OPERATORS.contains(&text)).then_some(text)
+    })
+}
+
+/// The last statement of the function body, descending through blocks.
+fn final_statement<'t>(function: &Node<'t>) -> Option<Node<'t>> {
+    let mut current = function.child_by_field_name("body").or_else(|| {
+        let mut cursor = function.walk();
+        function
+            .children(&mut cursor)
+            .find(|c| c.kind() == "function_body")
+    })?;
+    while BLOCK_KINDS.contains(&current.kind()) {
+        let mut cursor = current.walk();
+        let last = current
+            .named_children(&mut cursor)
+            .filter(|c| !c.kind().contains("comment"))
+            .last()?;
+        current = last;
+    }
+    Some(current)
+}
+
+/// Declared parameters, excluding receivers such as `self`, `&self` or `cls`.
+fn count_parameters(function: &Node, source: &str) -> usize {
+    // Single unparenthesized arrow-function parameter
+    if function.child_by_field_name("parameter").is_some() {
+        return 1;
+    }
+    let Some(list) = parameter_list(function) else {
+        // Swift lists parameters directly on the declaration
+        let mut cursor = function.walk();
+        return function
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() == "parameter")
+            .count();
+    };
+    let mut cursor = list.walk();
+    list.named_children(&mut cursor)
+        .map(|param| {
+            let kind = param.kind();
+            let text = source[param.byte_range()].trim();
+            if kind.contains("comment")
+                || matches!(
+                    kind,
+                    "self_parameter"
+                        | "receiver_parameter"
+                        | "parameter_modifiers"
+                        | "keyword_separator"
+                        | "positional_separator"
+                )
+                || matches!(text, "self" | "cls" | "void")
+                || param
+                    .child_by_field_name("name")
+                    .is_some_and(|n| &source[n.byte_range()] == "self")
+            {
+                return 0;
+            }
+            // Dart wraps `[optional]` and `{named}` parameters in a group
+            if kind == "optional_formal_parameters" {
+                let mut group = param.walk();
+                return param
+                    .named_children(&mut group)
+                    .filter(|c| c.kind() == "formal_parameter")
+                    .count();
+            }
+            // Go groups names sharing a type: `a, b int`
+            let mut names = param.walk();
+            param
+                .children_by_field_name("name", &mut names)
+                .count()
+                .max(1)
+        })
+        .sum()
+}
+
+fn parameter_list<'t>(function: &Node<'t>) -> Option<Node<'t>> {
+    if let Some(list) = function.child_by_field_name("parameters") {
+        return Some(list);
+    }
+    // C/C++: parameters hang off the (possibly pointer-wrapped) declarator
+    let mut declarator = function.child_by_field_name("declarator");
+    while let Some(d) = declarator {
+        if let Some(list) = d.child_by_field_name("parameters") {
+            return Some(list);
+        }
+        declarator = d.child_by_field_name("declarator");
+    }
+    // Dart: parameters live in the (method) signature
+    if let Some(signature) = function.child_by_field_name("signature") {
+        let mut node = Some(signature);
+        while let Some(n) = node {
+            if let Some(list) = n.child_by_field_name("parameters") {
+                return Some(list);
+            }
+            node = first_named_child(&n);
+        }
+    }
+    let mut cursor = function.walk();
+    function
+        .named_children(&mut cursor)
+        .find(|c| matches!(c.kind(), "function_value_parameters" | "parameters"))
+}
+
+fn first_named_child<'t>(node: &Node<'t>) -> Option<Node<'t>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|c| !c.kind().contains("comment"))
 }
 
 #[cfg(test)]
@@ -151,6 +680,10 @@ fn handle(req: Request) -> Response {
                 loop_count: 0,
                 early_return_count: 0,
                 call_count: 0,
+                cyclomatic_complexity: 0,
+                cognitive_complexity: 0,
+                max_nesting_depth: 0,
+                parameter_count: 0,
             }
         );
     }
@@ -193,4 +726,164 @@ fn handle(req: Request) -> Response {
         let signals = analyze(source);
         assert_eq!(signals.early_return_count, 0);
     }
+
+    fn ast(file: &str, source: &str, language: Language, name: &str) -> StructuralSignals {
+        crate::entities::extract_entities(std::path::Path::new(file), source, language)
+            .into_iter()
+            .find(|e| e.name == name)
+            .and_then(|e| e.signals)
+            .unwrap_or_else(|| panic!("no signals for {name}"))
+    }
+
+    #[test]
+    fn test_ast_ignores_comments_and_strings() {
+        let source = r#"
+fn describe(x: i32) -> String {
+    // if this were a loop we would return early
+    let label = "while for if else match";
+    format!("{label}: {x}")
+}"#;
+        let regex = analyze(source);
+        assert!(regex.branch_count > 0 && regex.loop_count > 0);
+
+        let signals = ast("src/lib.rs", source, Language::RUST, "describe");
+        assert_eq!(signals.branch_count, 0);
+        assert_eq!(signals.loop_count, 0);
+        assert_eq!(signals.early_return_count, 0);
+        assert_eq!(signals.cyclomatic_complexity, 1);
+        assert_eq!(signals.cognitive_complexity, 0);
+        assert_eq!(signals.parameter_count, 1);
+    }
+
+    #[test]
+    fn test_ast_nesting_and_cognitive_complexity() {
+        let source = r"
+impl Router {
+    fn route(&self, req: &Request, depth: usize) -> Response {
+        if req.is_valid() {
+            for header in req.headers() {
+                if header.is_auth() && header.is_fresh() {
+                    return self.auth(header);
+                }
+            }
+        } else if depth > 3 {
+            return Response::bad_request();
+        } else {
+            log();
+        }
+        Response::ok()
+    }
+}";
+        let signals = ast("src/lib.rs", source, Language::RUST, "route");
+        // if, nested if, else-if, else
+        assert_eq!(signals.branch_count, 4);
+        assert_eq!(signals.loop_count, 1);
+        assert_eq!(signals.early_return_count, 2);
+        assert_eq!(signals.max_nesting_depth, 3);
+        // 1 + if + for + if + && + else-if
+        assert_eq!(signals.cyclomatic_complexity, 6);
+        // if(1) + for(2) + if(3) + &&(1) + else-if(1) + else(1)
+        assert_eq!(signals.cognitive_complexity, 9);
+        assert_eq!(signals.parameter_count, 2);
+        assert_eq!(signals.call_count, 8);
+    }
+
+    #[test]
+    fn test_ast_switch_arms_and_defaults() {
+        let source = r"
+function kind(x) {
+  switch (x) {
+    case 1: return 'one';
+    case 2: return 'two';
+    default: return x > 9 ? 'many' : 'few';
+  }
+}";
+        let signals = ast("a.js", source, Language::JAVASCRIPT, "kind");
+        assert_eq!(signals.branch_count, 4);
+        // 1 + two non-default cases + ternary
+        assert_eq!(signals.cyclomatic_complexity, 4);
+        // switch(1) + ternary nested in switch(2)
+        assert_eq!(signals.cognitive_complexity, 3);
+        // The switch is the final statement
+        assert_eq!(signals.early_return_count, 0);
+    }
+
+    #[test]
+    fn test_ast_closures_nest_but_do_not_return_early() {
+        let source = r"
+export const load = async (ids, cache) => {
+  const found = ids.filter((id) => {
+    if (cache.has(id)) {
+      return false;
+    }
+    return true;
+  });
+  return fetchAll(found);
+};";
+        let signals = ast("a.ts", source, Language::TYPESCRIPT, "load");
+        assert_eq!(signals.parameter_count, 2);
+        assert_eq!(signals.early_return_count, 0);
+        assert_eq!(signals.branch_count, 1);
+        // Nested one level inside the closure
+        assert_eq!(signals.cognitive_complexity, 2);
+        assert_eq!(signals.call_count, 3);
+    }
+
+    #[test]
+    fn test_ast_python_and_go() {
+        let python = "
+class Store:
+    def get(self, key, default=None, *rest):
+        try:
+            return self.items[key]
+        except KeyError:
+            pass
+        while key and not rest:
+            key = key.parent
+        return default
+";
+        let signals = ast("store.py", python, Language::PYTHON, "get");
+        assert_eq!(signals.parameter_count, 3);
+        assert_eq!(signals.loop_count, 1);
+        assert_eq!(signals.early_return_count, 1);
+        // 1 + except + while + and
+        assert_eq!(signals.cyclomatic_complexity, 4);
+
+        let go = "package p\n\nfunc Split(a, b int, sep string) int {\n\tif a > b {\n\t\treturn 0\n\t}\n\treturn a\n}\n";
+        let signals = ast("p.go", go, Language::GO, "Split");
+        assert_eq!(signals.parameter_count, 3);
+        assert_eq!(signals.early_return_count, 1);
+        assert_eq!(signals.cyclomatic_complexity, 2);
+    }
+
+    #[test]
+    fn test_ast_lua_dart_zig_parameters() {
+        let lua = "function M:scale(x, y, ...)\n  if x then return x end\n  return y\nend\n";
+        let signals = ast("m.lua", lua, Language::LUA, "scale");
+        assert_eq!(signals.parameter_count, 3);
+        assert_eq!(signals.early_return_count, 1);
+
+        let dart = "class A {\n  int f(int a, [int b = 0, int c = 1]) {\n    for (var i in xs) { g(i); }\n    return a;\n  }\n}\n";
+        let signals = ast("a.dart", dart, Language::DART, "f");
+        assert_eq!(signals.parameter_count, 3);
+        assert_eq!(signals.loop_count, 1);
+
+        let zig = "const P = struct {\n    fn len(self: P, scale: i32) i32 {\n        while (true) {}\n        return scale;\n    }\n};\n";
+        let signals = ast("p.zig", zig, Language::ZIG, "len");
+        assert_eq!(signals.parameter_count, 1);
+        assert_eq!(signals.loop_count, 1);
+    }
+
+    #[test]
+    fn test_ast_containers_have_no_signals() {
+        let entities = crate::entities::extract_entities(
+            std::path::Path::new("a.java"),
+            "class A { void f() {} }",
+            Language::JAVA,
+        );
+        let class = entities.iter().find(|e| e.name == "A").unwrap();
+        assert!(class.signals.is_none());
+        let method = entities.iter().find(|e| e.name == "f").unwrap();
+        assert_eq!(method.signals.as_ref().unwrap().cyclomatic_complexity, 1);
+    }
 }
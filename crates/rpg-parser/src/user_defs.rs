@@ -0,0 +1,153 @@
+//! Runtime-loaded paradigm and language definitions.
+//!
+//! Besides the builtins compiled in by build.rs, definitions are read at
+//! runtime from two directories, in this order:
+//!
+//! 1. the user config dir — `$RPG_CONFIG_DIR`, else `$XDG_CONFIG_HOME/rpg`,
+//!    else `~/.config/rpg` (`%APPDATA%\rpg` on Windows)
+//! 2. the project's `.rpg/` directory
+//!
+//! Each may hold `paradigms/*.toml` and `languages/*.toml`. A later definition
+//! replaces an earlier one with the same name, so project defs win over user
+//! defs, which win over builtins.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Subdirectory holding paradigm definitions.
+pub const PARADIGMS_DIR: &str = "paradigms";
+/// Subdirectory holding language definitions.
+pub const LANGUAGES_DIR: &str = "languages";
+
+/// Where a runtime definition file was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefScope {
+    /// The user config dir, shared by all projects.
+    User,
+    /// The project's `.rpg/` directory.
+    Project,
+}
+
+impl fmt::Display for DefScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::User => "user",
+            Self::Project => "project",
+        })
+    }
+}
+
+/// A runtime definition file.
+#[derive(Debug, Clone)]
+pub struct DefFile {
+    pub scope: DefScope,
+    pub path: PathBuf,
+}
+
+impl DefFile {
+    /// Read the file, prefixing errors with its path.
+    pub fn read(&self) -> Result<String, String> {
+        std::fs::read_to_string(&self.path).map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+}
+
+/// The user-level config directory for rpg-encoder, if one can be determined.
+pub fn user_config_dir() -> Option<PathBuf> {
+    let var = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(dir) = var("RPG_CONFIG_DIR") {
+        return Some(dir);
+    }
+    if let Some(base) = var("XDG_CONFIG_HOME") {
+        return Some(base.join("rpg"));
+    }
+    if cfg!(windows)
+        && let Some(base) = var("APPDATA")
+    {
+        return Some(base.join("rpg"));
+    }
+    var("HOME").map(|home| home.join(".config").join("rpg"))
+}
+
+/// `*.toml` files in `subdir` of the user config dir, then of the project's
+/// `.rpg/` directory, each sorted by file name.
+pub fn def_files(project_root: &Path, subdir: &str) -> Vec<DefFile> {
+    let dirs = [
+        user_config_dir().map(|d| (DefScope::User, d)),
+        Some((DefScope::Project, rpg_core::storage::rpg_dir(project_root))),
+    ];
+    let mut files = Vec::new();
+    for (scope, dir) in dirs.into_iter().flatten() {
+        let Ok(entries) = std::fs::read_dir(dir.join(subdir)) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        paths.sort();
+        files.extend(paths.into_iter().map(|path| DefFile { scope, path }));
+    }
+    files
+}
+
+/// Runtime definitions in effect for a project, for `rpg_info`.
+#[derive(Debug, Default)]
+pub struct UserDefsReport {
+    /// `(paradigm name, file)` of every loaded paradigm def.
+    pub paradigms: Vec<(String, DefFile)>,
+    /// `(language name, file)` of every loaded language def.
+    pub languages: Vec<(String, DefFile)>,
+    /// Files or definitions that were rejected, with the reason.
+    pub errors: Vec<String>,
+}
+
+impl UserDefsReport {
+    /// Load (without installing) the runtime definitions of a project.
+    pub fn collect(project_root: &Path) -> Self {
+        let (paradigms, paradigm_errors) =
+            crate::paradigms::defs::load_user_paradigms(project_root);
+        let (languages, mut errors) = crate::languages::user::load_user_languages(project_root);
+        errors.extend(paradigm_errors.iter().map(ToString::to_string));
+        Self {
+            paradigms: paradigms
+                .into_iter()
+                .map(|(def, file)| (def.name, file))
+                .collect(),
+            languages: languages
+                .into_iter()
+                .map(|lang| (lang.language.name().to_string(), lang.file))
+                .collect(),
+            errors,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paradigms.is_empty() && self.languages.is_empty() && self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for UserDefsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, defs) in [("paradigm", &self.paradigms), ("language", &self.languages)] {
+            for (name, file) in defs {
+                writeln!(
+                    f,
+                    "  {} {} ({}: {})",
+                    kind,
+                    name,
+                    file.scope,
+                    file.path.display()
+                )?;
+            }
+        }
+        for error in &self.errors {
+            writeln!(f, "  rejected: {}", error)?;
+        }
+        Ok(())
+    }
+}
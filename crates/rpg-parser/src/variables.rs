@@ -0,0 +1,1582 @@
+//! Fields, constants, enum variants and module-level globals, and the
+//! functions that read and write them.
+//!
+//! [`extract_variables`] reports the data declarations of a file as entities:
+//! fields of classes and structs (including Python and Ruby attributes
+//! assigned in methods and TypeScript parameter properties), constants
+//! (`const`, `static final`, `#define`, ALL_CAPS assignments in Python),
+//! enum variants, and variables declared at module level.
+//!
+//! [`state_accesses`] reports every name read or assigned inside a function
+//! body: bare names that are not local to the function, members accessed
+//! through a value (`self.count`, `cfg.timeout`), and qualified names
+//! (`Config.TIMEOUT`, `Color::Red`). Grounding keeps those that name a data
+//! entity and records them as `reads_state` / `writes_state` dependencies.
+//!
+//! Locals are found flow-insensitively per function, so a name assigned
+//! anywhere in a Python function hides the global of that name throughout it
+//! unless declared `global`, as in Python itself.
+//!
+//! Only the languages listed in [`supports`] are covered. Scala, Bash, Lua,
+//! Elixir, Dart and Zig files yield no data entities and no state
+//! accesses; their functions are still extracted and linked by calls.
+
+use crate::entities::RawEntity;
+use crate::languages::Language;
+use crate::metadata::extract_metadata;
+use rpg_core::graph::EntityKind;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// A read or write of a named variable, field or constant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateAccess {
+    /// 1-indexed line of the access.
+    pub line: usize,
+    /// Accessed name, without sigils (`count` for `@count`, `$count`).
+    pub name: String,
+    /// Whether the access assigns or updates the value (`x = 1`, `x += 1`,
+    /// `x++`), or a member or element of it (`x.y = 1`, `x[k] = v`).
+    pub write: bool,
+}
+
+/// Whether data declarations and accesses are extracted for `language`.
+pub fn supports(language: Language) -> bool {
+    [
+        Language::PYTHON,
+        Language::RUST,
+        Language::JAVASCRIPT,
+        Language::TYPESCRIPT,
+        Language::GO,
+        Language::JAVA,
+        Language::CSHARP,
+        Language::KOTLIN,
+        Language::SWIFT,
+        Language::C,
+        Language::CPP,
+        Language::PHP,
+        Language::RUBY,
+    ]
+    .contains(&language)
+}
+
+/// Field, constant, enum variant and global entities declared in `source`.
+/// Each name is reported once per enclosing type, at its first declaration.
+/// Returns nothing for languages not covered by [`supports`].
+pub fn extract_variables(path: &Path, source: &str, language: Language) -> Vec<RawEntity> {
+    let Some(tree) = parse(source, language) else {
+        return Vec::new();
+    };
+    let mut declarations = Declarations {
+        path,
+        source,
+        language,
+        entities: Vec::new(),
+    };
+    declarations.visit(tree.root_node(), None);
+
+    let mut seen = HashSet::new();
+    let mut entities = declarations.entities;
+    entities.retain(|e| seen.insert((e.parent_class.clone(), e.name.clone())));
+    entities
+}
+
+/// Every read and write of a non-local name inside the function bodies of
+/// `source`, in source order. An update (`x += 1`, `x++`) is both.
+/// Returns nothing for languages not covered by [`supports`].
+pub fn state_accesses(source: &str, language: Language) -> Vec<StateAccess> {
+    let Some(tree) = parse(source, language) else {
+        return Vec::new();
+    };
+    let mut accesses = Accesses {
+        source,
+        language,
+        scopes: Vec::new(),
+        found: Vec::new(),
+    };
+    accesses.visit(tree.root_node(), Mode::Read);
+    accesses.found
+}
+
+fn parse(source: &str, language: Language) -> Option<tree_sitter::Tree> {
+    if !supports(language) {
+        return None;
+    }
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language.ts_language()).ok()?;
+    parser.parse(source.as_bytes(), None)
+}
+
+/// Node kinds whose body is a function scope.
+fn is_function(language: Language, kind: &str) -> bool {
+    let kinds: &[&str] = if language == Language::PYTHON {
+        &["function_definition", "lambda"]
+    } else if language == Language::RUST {
+        &["function_item", "closure_expression"]
+    } else if language == Language::JAVASCRIPT || language == Language::TYPESCRIPT {
+        &[
+            "function_declaration",
+            "generator_function_declaration",
+            "method_definition",
+            "arrow_function",
+            "function_expression",
+            "function",
+            "generator_function",
+        ]
+    } else if language == Language::GO {
+        &["function_declaration", "method_declaration", "func_literal"]
+    } else if language == Language::JAVA {
+        &[
+            "method_declaration",
+            "constructor_declaration",
+            "compact_constructor_declaration",
+            "lambda_expression",
+        ]
+    } else if language == Language::CSHARP {
+        &[
+            "method_declaration",
+            "constructor_declaration",
+            "destructor_declaration",
+            "local_function_statement",
+            "lambda_expression",
+            "accessor_declaration",
+            "operator_declaration",
+        ]
+    } else if language == Language::KOTLIN {
+        &[
+            "function_declaration",
+            "secondary_constructor",
+            "anonymous_function",
+            "lambda_literal",
+            "anonymous_initializer",
+            "getter",
+            "setter",
+        ]
+    } else if language == Language::SWIFT {
+        &[
+            "function_declaration",
+            "init_declaration",
+            "deinit_declaration",
+            "lambda_literal",
+        ]
+    } else if language == Language::C || language == Language::CPP {
+        &["function_definition", "lambda_expression"]
+    } else if language == Language::PHP {
+        &[
+            "function_definition",
+            "method_declaration",
+            "anonymous_function",
+            "anonymous_function_creation_expression",
+            "arrow_function",
+        ]
+    } else {
+        &["method", "singleton_method"]
+    };
+    kinds.contains(&kind)
+}
+
+/// Names in all caps (`MAX_SIZE`, `A1`), the convention for constants in
+/// languages without a `const` declaration.
+fn is_all_caps(name: &str) -> bool {
+    name.chars().any(|c| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Whether `node` has one of the keywords `words` among its own tokens or
+/// its modifier nodes.
+fn has_keyword(node: Node, source: &str, words: &[&str]) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|child| {
+        if child.child_count() == 0 {
+            words.contains(&&source[child.byte_range()])
+        } else {
+            let holds_modifiers = child.kind().contains("modifier")
+                || matches!(
+                    child.kind(),
+                    "modifiers"
+                        | "value_binding_pattern"
+                        | "binding_pattern_kind"
+                        | "storage_class_specifier"
+                );
+            holds_modifiers && has_keyword(child, source, words)
+        }
+    })
+}
+
+/// The type whose members are being declared.
+struct Container {
+    name: String,
+    is_enum: bool,
+}
+
+struct Declarations<'a> {
+    path: &'a Path,
+    source: &'a str,
+    language: Language,
+    entities: Vec<RawEntity>,
+}
+
+impl<'a> Declarations<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        &self.source[node.byte_range()]
+    }
+
+    fn visit(&mut self, node: Node, class: Option<&Container>) {
+        if is_function(self.language, node.kind()) {
+            if let Some(class) = class {
+                self.instance_fields(node, class);
+            }
+            return;
+        }
+        if let Some(container) = self.container(node) {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                self.visit(child, Some(&container));
+            }
+            return;
+        }
+        if self.declare(node, class) {
+            return;
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit(child, class);
+        }
+    }
+
+    /// The type declared by `node`, named as its entity is.
+    fn container(&self, node: Node) -> Option<Container> {
+        let lang = self.language;
+        let kind = node.kind();
+        let named = |field: &str| {
+            let name = node.child_by_field_name(field)?;
+            Some(self.text(name).to_string())
+        };
+        let (name, is_enum) = if lang == Language::PYTHON {
+            if kind != "class_definition" {
+                return None;
+            }
+            let is_enum = node
+                .child_by_field_name("superclasses")
+                .is_some_and(|bases| {
+                    let mut cursor = bases.walk();
+                    bases.named_children(&mut cursor).any(|base| {
+                        let base = self.text(base).rsplit('.').next().unwrap_or_default();
+                        base.ends_with("Enum") || base.ends_with("Flag")
+                    })
+                });
+            (named("name")?, is_enum)
+        } else if lang == Language::RUST {
+            match kind {
+                "struct_item" | "union_item" | "trait_item" => (named("name")?, false),
+                "enum_item" => (named("name")?, true),
+                // Methods of `impl` blocks are parented to the type as written
+                "impl_item" => (named("type")?, false),
+                _ => return None,
+            }
+        } else if lang == Language::JAVASCRIPT || lang == Language::TYPESCRIPT {
+            match kind {
+                "class_declaration" | "abstract_class_declaration" | "class" => {
+                    (named("name")?, false)
+                }
+                "enum_declaration" => (named("name")?, true),
+                _ => return None,
+            }
+        } else if lang == Language::GO {
+            let is_struct = node
+                .child_by_field_name("type")
+                .is_some_and(|t| t.kind() == "struct_type");
+            if kind != "type_spec" || !is_struct {
+                return None;
+            }
+            (named("name")?, false)
+        } else if lang == Language::JAVA || lang == Language::CSHARP || lang == Language::PHP {
+            match kind {
+                "class_declaration"
+                | "interface_declaration"
+                | "record_declaration"
+                | "struct_declaration"
+                | "record_struct_declaration"
+                | "annotation_type_declaration"
+                | "trait_declaration" => (named("name")?, false),
+                "enum_declaration" => (named("name")?, true),
+                _ => return None,
+            }
+        } else if lang == Language::KOTLIN {
+            match kind {
+                "class_declaration" => {
+                    let mut cursor = node.walk();
+                    let is_enum = node
+                        .named_children(&mut cursor)
+                        .any(|c| c.kind() == "enum_class_body");
+                    (named("name")?, is_enum)
+                }
+                "object_declaration" => (named("name")?, false),
+                _ => return None,
+            }
+        } else if lang == Language::SWIFT {
+            match kind {
+                "class_declaration" => {
+                    let is_enum = node
+                        .child_by_field_name("declaration_kind")
+                        .is_some_and(|k| self.text(k) == "enum");
+                    (named("name")?, is_enum)
+                }
+                "protocol_declaration" => (named("name")?, false),
+                _ => return None,
+            }
+        } else if lang == Language::C || lang == Language::CPP {
+            let is_enum = kind == "enum_specifier";
+            if !matches!(
+                kind,
+                "struct_specifier" | "union_specifier" | "class_specifier"
+            ) && !is_enum
+                || node.child_by_field_name("body").is_none()
+            {
+                return None;
+            }
+            // `typedef struct { ... } Name;`
+            let name = named("name").or_else(|| {
+                let parent = node.parent().filter(|p| p.kind() == "type_definition")?;
+                Some(
+                    self.text(parent.child_by_field_name("declarator")?)
+                        .to_string(),
+                )
+            });
+            match name {
+                Some(name) => (name, is_enum),
+                // Anonymous enums declare plain constants
+                None if is_enum => (String::new(), true),
+                None => return None,
+            }
+        } else {
+            if !matches!(kind, "class" | "module") {
+                return None;
+            }
+            (named("name")?, false)
+        };
+        Some(Container { name, is_enum })
+    }
+
+    /// Record the declarations made by `node`; `false` when it declares none.
+    fn declare(&mut self, node: Node, class: Option<&Container>) -> bool {
+        let lang = self.language;
+        if lang == Language::PYTHON {
+            self.declare_python(node, class)
+        } else if lang == Language::RUST {
+            self.declare_rust(node, class)
+        } else if lang == Language::JAVASCRIPT || lang == Language::TYPESCRIPT {
+            self.declare_js(node, class)
+        } else if lang == Language::GO {
+            self.declare_go(node, class)
+        } else if lang == Language::JAVA || lang == Language::CSHARP {
+            self.declare_java_like(node, class)
+        } else if lang == Language::KOTLIN {
+            self.declare_kotlin(node, class)
+        } else if lang == Language::SWIFT {
+            self.declare_swift(node, class)
+        } else if lang == Language::C || lang == Language::CPP {
+            self.declare_c(node, class)
+        } else if lang == Language::PHP {
+            self.declare_php(node, class)
+        } else {
+            self.declare_ruby(node, class)
+        }
+    }
+
+    fn declare_python(&mut self, node: Node, class: Option<&Container>) -> bool {
+        let Some(assignment) = node
+            .named_child(0)
+            .filter(|c| node.kind() == "expression_statement" && c.kind() == "assignment")
+        else {
+            return false;
+        };
+        let Some(left) = assignment.child_by_field_name("left") else {
+            return true;
+        };
+        let mut names = Vec::new();
+        if left.kind() == "identifier" {
+            names.push(left);
+        } else if matches!(left.kind(), "pattern_list" | "tuple_pattern") {
+            let mut cursor = left.walk();
+            names.extend(
+                left.named_children(&mut cursor)
+                    .filter(|c| c.kind() == "identifier"),
+            );
+        }
+        let annotated_only = assignment.child_by_field_name("right").is_none();
+        for name in names {
+            let name = self.text(name);
+            if name.starts_with("__") && name.ends_with("__") {
+                continue;
+            }
+            let kind = match class {
+                Some(class) if class.is_enum && !annotated_only && !name.starts_with('_') => {
+                    EntityKind::EnumVariant
+                }
+                _ if is_all_caps(name) => EntityKind::Constant,
+                Some(_) => EntityKind::Field,
+                None => EntityKind::Global,
+            };
+            self.push(name, kind, class, node, node);
+        }
+        true
+    }
+
+    fn declare_rust(&mut self, node: Node, class: Option<&Container>) -> bool {
+        let kind = match node.kind() {
+            "const_item" => EntityKind::Constant,
+            "static_item" => {
+                let mut cursor = node.walk();
+                let mutable = node
+                    .children(&mut cursor)
+                    .any(|c| c.kind() == "mutable_specifier");
+                if mutable {
+                    EntityKind::Global
+                } else {
+                    EntityKind::Constant
+                }
+            }
+            "field_declaration" if class.is_some() => EntityKind::Field,
+            "enum_variant" => EntityKind::EnumVariant,
+            _ => return false,
+        };
+        if let Some(name) = node.child_by_field_name("name") {
+            self.push(self.text(name), kind, class, node, node);
+        }
+        true
+    }
+
+    fn declare_js(&mut self, node: Node, class: Option<&Container>) -> bool {
+        match node.kind() {
+            "lexical_declaration" | "variable_declaration" if class.is_none() => {
+                let kind = if node.child(0).is_some_and(|k| self.text(k) == "const") {
+                    EntityKind::Constant
+                } else {
+                    EntityKind::Global
+                };
+                let mut cursor = node.walk();
+                for declarator in node.named_children(&mut cursor) {
+                    let Some(name) = declarator
+                        .child_by_field_name("name")
+                        .filter(|n| n.kind() == "identifier")
+                    else {
+                        continue;
+                    };
+                    // Functions and classes bound to a name are entities of their own
+                    let is_code = declarator.child_by_field_name("value").is_some_and(|v| {
+                        matches!(
+                            v.kind(),
+                            "arrow_function"
+                                | "function_expression"
+                                | "function"
+                                | "generator_function"
+                                | "class"
+                        )
+                    });
+                    if !is_code {
+                        self.push(self.text(name), kind, None, node, declarator);
+                    }
+                }
+                true
+            }
+            "public_field_definition" | "field_definition" => {
+                let Some(name) = node
+                    .child_by_field_name("name")
+                    .or_else(|| node.child_by_field_name("property"))
+                    .filter(|n| n.kind() != "computed_property_name")
+                else {
+                    return true;
+                };
+                let is_method = node
+                    .child_by_field_name("value")
+                    .is_some_and(|v| matches!(v.kind(), "arrow_function" | "function_expression"));
+                if !is_method {
+                    let kind = if has_keyword(node, self.source, &["static"])
+                        && has_keyword(node, self.source, &["readonly"])
+                    {
+                        EntityKind::Constant
+                    } else {
+                        EntityKind::Field
+                    };
+                    self.push(self.text(name), kind, class, node, node);
+                }
+                true
+            }
+            "property_identifier" if class.is_some_and(|c| c.is_enum) => {
+                self.push(self.text(node), EntityKind::EnumVariant, class, node, node);
+                true
+            }
+            "enum_assignment" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.push(self.text(name), EntityKind::EnumVariant, class, node, node);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn declare_go(&mut self, node: Node, class: Option<&Container>) -> bool {
+        let kind = match node.kind() {
+            "const_spec" => EntityKind::Constant,
+            "var_spec" => EntityKind::Global,
+            "field_declaration" if class.is_some() => EntityKind::Field,
+            _ => return false,
+        };
+        let mut cursor = node.walk();
+        let names: Vec<Node> = node.children_by_field_name("name", &mut cursor).collect();
+        for name in names {
+            self.push(self.text(name), kind, class, node, node);
+        }
+        true
+    }
+
+    fn declare_java_like(&mut self, node: Node, class: Option<&Container>) -> bool {
+        let source = self.source;
+        match node.kind() {
+            "field_declaration" | "constant_declaration" => {
+                let constant = node.kind() == "constant_declaration"
+                    || has_keyword(node, source, &["const"])
+                    || has_keyword(node, source, &["static"])
+                        && has_keyword(node, source, &["final", "readonly"]);
+                let kind = if constant {
+                    EntityKind::Constant
+                } else {
+                    EntityKind::Field
+                };
+                // Java: `declarator` fields; C#: a `variable_declaration` child
+                let holder = (0..node.named_child_count())
+                    .filter_map(|i| node.named_child(i as u32))
+                    .find(|c| c.kind() == "variable_declaration")
+                    .unwrap_or(node);
+                let mut cursor = holder.walk();
+                let declarators: Vec<Node> = holder
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() == "variable_declarator")
+                    .collect();
+                for declarator in declarators {
+                    if let Some(name) = declarator
+                        .child_by_field_name("name")
+                        .or_else(|| declarator.named_child(0))
+                    {
+                        self.push(self.text(name), kind, class, node, node);
+                    }
+                }
+                true
+            }
+            "property_declaration" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.push(self.text(name), EntityKind::Field, class, node, node);
+                }
+                true
+            }
+            "enum_constant" | "enum_member_declaration" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.push(self.text(name), EntityKind::EnumVariant, class, node, node);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn declare_kotlin(&mut self, node: Node, class: Option<&Container>) -> bool {
+        let source = self.source;
+        match node.kind() {
+            "property_declaration" => {
+                let kind = if has_keyword(node, source, &["const"]) {
+                    EntityKind::Constant
+                } else if class.is_some() {
+                    EntityKind::Field
+                } else if has_keyword(node, source, &["var"]) {
+                    EntityKind::Global
+                } else {
+                    EntityKind::Constant
+                };
+                let mut cursor = node.walk();
+                let declarations: Vec<Node> = node
+                    .named_children(&mut cursor)
+                    .flat_map(|c| match c.kind() {
+                        "variable_declaration" => vec![c],
+                        "multi_variable_declaration" => {
+                            let mut inner = c.walk();
+                            c.named_children(&mut inner).collect()
+                        }
+                        _ => Vec::new(),
+                    })
+                    .collect();
+                for declaration in declarations {
+                    if let Some(name) = identifier(declaration) {
+                        self.push(self.text(name), kind, class, node, node);
+                    }
+                }
+                true
+            }
+            "class_parameter" => {
+                if has_keyword(node, source, &["val", "var"])
+                    && let Some(name) = identifier(node)
+                {
+                    self.push(self.text(name), EntityKind::Field, class, node, node);
+                }
+                true
+            }
+            "enum_entry" => {
+                if let Some(name) = identifier(node) {
+                    self.push(self.text(name), EntityKind::EnumVariant, class, node, node);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn declare_swift(&mut self, node: Node, class: Option<&Container>) -> bool {
+        match node.kind() {
+            "property_declaration" => {
+                let constant = has_keyword(node, self.source, &["let"]);
+                let kind = match class {
+                    Some(_) if constant && has_keyword(node, self.source, &["static"]) => {
+                        EntityKind::Constant
+                    }
+                    Some(_) => EntityKind::Field,
+                    None if constant => EntityKind::Constant,
+                    None => EntityKind::Global,
+                };
+                let mut cursor = node.walk();
+                let patterns: Vec<Node> =
+                    node.children_by_field_name("name", &mut cursor).collect();
+                for pattern in patterns {
+                    let name = pattern
+                        .child_by_field_name("bound_identifier")
+                        .unwrap_or(pattern);
+                    self.push(self.text(name), kind, class, node, node);
+                }
+                true
+            }
+            "enum_entry" => {
+                let mut cursor = node.walk();
+                let names: Vec<Node> = node.children_by_field_name("name", &mut cursor).collect();
+                for name in names {
+                    self.push(self.text(name), EntityKind::EnumVariant, class, node, node);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn declare_c(&mut self, node: Node, class: Option<&Container>) -> bool {
+        let source = self.source;
+        match node.kind() {
+            "preproc_def" if class.is_none() => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.push(self.text(name), EntityKind::Constant, None, node, node);
+                }
+                true
+            }
+            "declaration" | "field_declaration" => {
+                // Members of a struct declared along with a variable or field
+                if let Some(ty) = node.child_by_field_name("type") {
+                    self.visit(ty, class);
+                }
+                let in_type = node.kind() == "field_declaration";
+                if in_type != class.is_some() || has_keyword(node, source, &["extern", "typedef"]) {
+                    return true;
+                }
+                let is_const = {
+                    let mut cursor = node.walk();
+                    node.named_children(&mut cursor).any(|c| {
+                        c.kind() == "type_qualifier"
+                            && matches!(self.text(c), "const" | "constexpr")
+                    })
+                };
+                let kind = match class {
+                    Some(_) if is_const && has_keyword(node, source, &["static"]) => {
+                        EntityKind::Constant
+                    }
+                    Some(_) => EntityKind::Field,
+                    None if is_const => EntityKind::Constant,
+                    None => EntityKind::Global,
+                };
+                let mut cursor = node.walk();
+                let declarators: Vec<Node> = node
+                    .children_by_field_name("declarator", &mut cursor)
+                    .collect();
+                for declarator in declarators {
+                    if let Some(name) = c_declarator_name(declarator) {
+                        self.push(self.text(name), kind, class, node, node);
+                    }
+                }
+                true
+            }
+            "enumerator" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    let kind = match class {
+                        Some(class) if !class.name.is_empty() => EntityKind::EnumVariant,
+                        _ => EntityKind::Constant,
+                    };
+                    let class = class.filter(|c| !c.name.is_empty());
+                    self.push(self.text(name), kind, class, node, node);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn declare_php(&mut self, node: Node, class: Option<&Container>) -> bool {
+        match node.kind() {
+            "const_declaration" => {
+                let mut cursor = node.walk();
+                let elements: Vec<Node> = node
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() == "const_element")
+                    .collect();
+                for element in elements {
+                    if let Some(name) = element.named_child(0).filter(|n| n.kind() == "name") {
+                        self.push(self.text(name), EntityKind::Constant, class, node, node);
+                    }
+                }
+                true
+            }
+            "property_declaration" => {
+                let mut cursor = node.walk();
+                let elements: Vec<Node> = node
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() == "property_element")
+                    .collect();
+                for element in elements {
+                    if let Some(name) = element
+                        .child_by_field_name("name")
+                        .and_then(|v| v.named_child(0))
+                    {
+                        self.push(self.text(name), EntityKind::Field, class, node, node);
+                    }
+                }
+                true
+            }
+            "enum_case" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.push(self.text(name), EntityKind::EnumVariant, class, node, node);
+                }
+                true
+            }
+            "expression_statement" if class.is_none() => {
+                let Some(left) = node
+                    .named_child(0)
+                    .filter(|a| a.kind() == "assignment_expression")
+                    .and_then(|a| a.child_by_field_name("left"))
+                    .filter(|l| l.kind() == "variable_name")
+                else {
+                    return false;
+                };
+                if let Some(name) = left.named_child(0) {
+                    self.push(self.text(name), EntityKind::Global, None, node, node);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn declare_ruby(&mut self, node: Node, class: Option<&Container>) -> bool {
+        match node.kind() {
+            "assignment" => {
+                let Some(left) = node.child_by_field_name("left") else {
+                    return true;
+                };
+                let kind = match left.kind() {
+                    "constant" => EntityKind::Constant,
+                    "global_variable" => EntityKind::Global,
+                    "class_variable" if class.is_some() => EntityKind::Field,
+                    _ => return true,
+                };
+                let name = self.text(left).trim_start_matches(['$', '@']);
+                let class = class.filter(|_| kind != EntityKind::Global);
+                self.push(name, kind, class, node, node);
+                true
+            }
+            "call" if class.is_some() => {
+                let is_accessor = node.child_by_field_name("receiver").is_none()
+                    && node.child_by_field_name("method").is_some_and(|m| {
+                        matches!(
+                            self.text(m),
+                            "attr_accessor" | "attr_reader" | "attr_writer"
+                        )
+                    });
+                if !is_accessor {
+                    return false;
+                }
+                if let Some(arguments) = node.child_by_field_name("arguments") {
+                    let mut cursor = arguments.walk();
+                    let symbols: Vec<Node> = arguments
+                        .named_children(&mut cursor)
+                        .filter(|c| c.kind() == "simple_symbol")
+                        .collect();
+                    for symbol in symbols {
+                        let name = self.text(symbol).trim_start_matches(':');
+                        self.push(name, EntityKind::Field, class, node, node);
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Fields declared by a method rather than the type body: attributes
+    /// assigned through `self` (Python) or `@` (Ruby) in any method, and
+    /// through `this` or as parameter properties in a JS/TS constructor.
+    fn instance_fields(&mut self, function: Node, class: &Container) {
+        let lang = self.language;
+        if lang == Language::JAVASCRIPT || lang == Language::TYPESCRIPT {
+            let is_constructor = function.kind() == "method_definition"
+                && function
+                    .child_by_field_name("name")
+                    .is_some_and(|n| self.text(n) == "constructor");
+            if !is_constructor {
+                return;
+            }
+            if let Some(parameters) = function.child_by_field_name("parameters") {
+                let mut cursor = parameters.walk();
+                let properties: Vec<Node> = parameters
+                    .named_children(&mut cursor)
+                    .filter(|p| {
+                        let mut inner = p.walk();
+                        p.named_children(&mut inner)
+                            .any(|c| c.kind() == "accessibility_modifier")
+                            || has_keyword(*p, self.source, &["readonly"])
+                    })
+                    .collect();
+                for property in properties {
+                    if let Some(name) = property.child_by_field_name("pattern") {
+                        self.push(
+                            self.text(name),
+                            EntityKind::Field,
+                            Some(class),
+                            property,
+                            property,
+                        );
+                    }
+                }
+            }
+        } else if lang != Language::PYTHON && lang != Language::RUBY {
+            return;
+        }
+        if let Some(body) = function.child_by_field_name("body") {
+            self.assigned_attributes(body, class);
+        }
+    }
+
+    fn assigned_attributes(&mut self, node: Node, class: &Container) {
+        let kind = node.kind();
+        if is_function(self.language, kind) || self.container(node).is_some() {
+            return;
+        }
+        if matches!(
+            kind,
+            "assignment" | "assignment_expression" | "operator_assignment"
+        ) && let Some(left) = node.child_by_field_name("left")
+        {
+            let name = match left.kind() {
+                "instance_variable" => Some(self.text(left).trim_start_matches('@')),
+                "attribute" | "member_expression" => {
+                    let object = left.child_by_field_name("object");
+                    let is_self = object.is_some_and(|o| matches!(self.text(o), "self" | "this"));
+                    left.child_by_field_name("attribute")
+                        .or_else(|| left.child_by_field_name("property"))
+                        .filter(|_| is_self)
+                        .map(|n| self.text(n))
+                }
+                _ => None,
+            };
+            if let Some(name) = name {
+                // The statement holding the assignment carries the comments
+                let statement = node
+                    .parent()
+                    .filter(|p| p.kind() == "expression_statement")
+                    .unwrap_or(node);
+                self.push(name, EntityKind::Field, Some(class), statement, statement);
+            }
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.assigned_attributes(child, class);
+        }
+    }
+
+    /// Record a declaration spanning `node`, with metadata read from `meta`.
+    fn push(
+        &mut self,
+        name: &str,
+        kind: EntityKind,
+        class: Option<&Container>,
+        node: Node,
+        meta: Node,
+    ) {
+        if name.is_empty() {
+            return;
+        }
+        self.entities.push(RawEntity {
+            name: name.to_string(),
+            kind,
+            file: self.path.to_path_buf(),
+            line_start: node.start_position().row + 1,
+            line_end: line_end(node),
+            parent_class: class.map(|c| c.name.clone()),
+            source_text: self.text(node).to_string(),
+            signature: None,
+            metadata: extract_metadata(&meta, self.source, self.language),
+            signals: None,
+            discriminator: None,
+        });
+    }
+}
+
+/// 1-indexed last line of `node`, excluding a trailing newline (`#define`).
+fn line_end(node: Node) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row
+    } else {
+        end.row + 1
+    }
+}
+
+/// The first identifier child of a Kotlin declaration.
+fn identifier(node: Node) -> Option<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|c| matches!(c.kind(), "identifier" | "simple_identifier"))
+}
+
+/// The identifier declared by a C/C++ declarator, unless it declares a function.
+fn c_declarator_name(declarator: Node) -> Option<Node> {
+    match declarator.kind() {
+        "identifier" | "field_identifier" => Some(declarator),
+        "init_declarator" | "pointer_declarator" | "array_declarator" | "reference_declarator" => {
+            let inner = declarator.child_by_field_name("declarator").or_else(|| {
+                declarator.named_child(declarator.named_child_count().saturating_sub(1) as u32)
+            })?;
+            c_declarator_name(inner)
+        }
+        _ => None,
+    }
+}
+
+/// How an expression is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Read,
+    /// Assigned (`x = 1`) or, for the object of a member or element,
+    /// modified through it (`x.y = 1`).
+    Write,
+    /// Read and assigned (`x += 1`, `x++`).
+    Update,
+}
+
+/// Names bound in a function, and those it declares as globals.
+#[derive(Default)]
+struct Scope {
+    locals: HashSet<String>,
+    globals: HashSet<String>,
+}
+
+struct Accesses<'a> {
+    source: &'a str,
+    language: Language,
+    /// Enclosing functions, innermost last.
+    scopes: Vec<Scope>,
+    found: Vec<StateAccess>,
+}
+
+impl<'a> Accesses<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        &self.source[node.byte_range()]
+    }
+
+    fn visit(&mut self, node: Node, mode: Mode) {
+        if is_function(self.language, node.kind()) {
+            let mut scope = Scope::default();
+            self.collect_bindings(node, &mut scope);
+            scope.locals.retain(|name| !scope.globals.contains(name));
+            self.scopes.push(scope);
+            self.visit_children(node, Mode::Read);
+            self.scopes.pop();
+            return;
+        }
+        if self.scopes.is_empty() {
+            self.visit_children(node, Mode::Read);
+            return;
+        }
+
+        if let Some((target, compound)) = self.assignment(node) {
+            let target_mode = if compound { Mode::Update } else { Mode::Write };
+            self.visit_except(node, target, Mode::Read);
+            self.visit(target, target_mode);
+        } else if let Some(target) = self.update_target(node) {
+            self.visit(target, Mode::Update);
+        } else if let Some(callee) = self.callee(node) {
+            self.visit_except(node, callee, Mode::Read);
+            if let Some((object, _)) = self.member(callee) {
+                if let Some(object) = object {
+                    self.visit(object, Mode::Read);
+                }
+            } else if self.reference(callee).is_none() {
+                self.visit(callee, Mode::Read);
+            }
+        } else if let Some((object, name)) = self.member(node) {
+            if let Some(name) = name {
+                self.record(name, mode);
+            }
+            if let Some(object) = object {
+                self.visit(object, object_mode(mode));
+            }
+        } else if let Some(object) = self.subscript_object(node) {
+            self.visit_except(node, object, Mode::Read);
+            self.visit(object, object_mode(mode));
+        } else if let Some(name) = self.reference(node) {
+            if !self.is_local(node, &name) {
+                self.push(node, name, mode);
+            }
+        } else {
+            self.visit_children(node, mode);
+        }
+    }
+
+    fn visit_children(&mut self, node: Node, mode: Mode) {
+        let mut cursor = node.walk();
+        for (i, child) in node.children(&mut cursor).enumerate() {
+            if !child.is_named() || self.skipped(node, child, i) {
+                continue;
+            }
+            self.visit(child, mode);
+        }
+    }
+
+    /// Visit the children of `node` other than `except`.
+    fn visit_except(&mut self, node: Node, except: Node, mode: Mode) {
+        let mut cursor = node.walk();
+        for (i, child) in node.children(&mut cursor).enumerate() {
+            if !child.is_named() || child.id() == except.id() || self.skipped(node, child, i) {
+                continue;
+            }
+            self.visit(child, mode);
+        }
+    }
+
+    /// Children that never hold accesses: types, imports, annotations and
+    /// argument labels.
+    fn skipped(&self, parent: Node, child: Node, index: usize) -> bool {
+        let field = u32::try_from(index)
+            .ok()
+            .and_then(|i| parent.field_name_for_child(i));
+        if matches!(
+            field,
+            Some("type" | "return_type" | "returns" | "type_parameters" | "type_arguments")
+        ) {
+            return true;
+        }
+        if field == Some("name")
+            && (parent.kind() == "keyword_argument" || is_function(self.language, parent.kind()))
+        {
+            return true;
+        }
+        // C/C++ function names are in the declarator, along with the parameters
+        if field == Some("declarator") && parent.kind() == "function_definition" {
+            return true;
+        }
+        matches!(
+            child.kind(),
+            "type"
+                | "type_annotation"
+                | "type_arguments"
+                | "type_argument_list"
+                | "type_parameters"
+                | "type_descriptor"
+                | "user_type"
+                | "named_type"
+                | "decorator"
+                | "annotation"
+                | "marker_annotation"
+                | "attribute_item"
+                | "attribute_list"
+                | "value_argument_label"
+                | "label"
+                | "global_statement"
+                | "nonlocal_statement"
+                | "global_declaration"
+                | "import_statement"
+                | "import_from_statement"
+                | "import_declaration"
+                | "use_declaration"
+        )
+    }
+
+    fn record(&mut self, node: Node, mode: Mode) {
+        let name = self.text(node).trim_start_matches(['$', '@']).to_string();
+        self.push(node, name, mode);
+    }
+
+    fn push(&mut self, node: Node, name: String, mode: Mode) {
+        let line = node.start_position().row + 1;
+        if mode != Mode::Write {
+            self.found.push(StateAccess {
+                line,
+                name: name.clone(),
+                write: false,
+            });
+        }
+        if mode != Mode::Read {
+            self.found.push(StateAccess {
+                line,
+                name,
+                write: true,
+            });
+        }
+    }
+
+    /// Whether the reference `node` to `name` is bound in an enclosing function.
+    fn is_local(&self, node: Node, name: &str) -> bool {
+        match node.kind() {
+            // Sigils and PHP constants never name locals
+            "instance_variable" | "class_variable" | "global_variable" | "constant" | "name" => {
+                false
+            }
+            // PHP functions see only the globals they import
+            "variable_name" => !self.scopes.iter().any(|s| s.globals.contains(name)),
+            _ => self.scopes.iter().any(|s| s.locals.contains(name)),
+        }
+    }
+
+    /// The name `node` refers to when it is a bare variable reference.
+    fn reference(&self, node: Node) -> Option<String> {
+        let lang = self.language;
+        let kind = node.kind();
+        let name = if lang == Language::SWIFT {
+            (kind == "simple_identifier").then(|| self.text(node))
+        } else if lang == Language::KOTLIN {
+            matches!(kind, "identifier" | "simple_identifier").then(|| self.text(node))
+        } else if lang == Language::JAVASCRIPT || lang == Language::TYPESCRIPT {
+            matches!(kind, "identifier" | "shorthand_property_identifier").then(|| self.text(node))
+        } else if lang == Language::PHP {
+            match kind {
+                "variable_name" => node.named_child(0).map(|n| self.text(n)),
+                "name" => {
+                    let parent = node.parent().map(|p| p.kind()).unwrap_or_default();
+                    let is_constant = !matches!(
+                        parent,
+                        "qualified_name" | "namespace_name" | "object_creation_expression"
+                    );
+                    is_constant.then(|| self.text(node))
+                }
+                _ => None,
+            }
+        } else if lang == Language::RUBY {
+            matches!(
+                kind,
+                "identifier"
+                    | "constant"
+                    | "instance_variable"
+                    | "global_variable"
+                    | "class_variable"
+            )
+            .then(|| self.text(node).trim_start_matches(['$', '@']))
+        } else {
+            (kind == "identifier").then(|| self.text(node))
+        };
+        name.map(str::to_string)
+    }
+
+    /// The object and member name of a member access (`obj.name`,
+    /// `Type::NAME`). The object is `None` for `::` paths, whose scope names
+    /// a type or module rather than a value.
+    fn member<'t>(&self, node: Node<'t>) -> Option<(Option<Node<'t>>, Option<Node<'t>>)> {
+        let lang = self.language;
+        let kind = node.kind();
+        let fields = |object: &str, name: &str| {
+            Some((
+                node.child_by_field_name(object),
+                node.child_by_field_name(name),
+            ))
+        };
+        let first_last = || {
+            let count = node.named_child_count();
+            let last = node.named_child(u32::try_from(count.checked_sub(1)?).ok()?)?;
+            let last = if last.kind() == "navigation_suffix" {
+                last.child_by_field_name("suffix")
+                    .or_else(|| last.named_child(0))?
+            } else {
+                last
+            };
+            Some((node.named_child(0), Some(last)))
+        };
+        if lang == Language::PYTHON {
+            (kind == "attribute").then(|| fields("object", "attribute"))?
+        } else if lang == Language::RUST {
+            match kind {
+                "field_expression" => fields("value", "field"),
+                "scoped_identifier" => Some((None, node.child_by_field_name("name"))),
+                _ => None,
+            }
+        } else if lang == Language::JAVASCRIPT || lang == Language::TYPESCRIPT {
+            (kind == "member_expression").then(|| fields("object", "property"))?
+        } else if lang == Language::GO {
+            (kind == "selector_expression").then(|| fields("operand", "field"))?
+        } else if lang == Language::JAVA {
+            (kind == "field_access").then(|| fields("object", "field"))?
+        } else if lang == Language::CSHARP {
+            (kind == "member_access_expression").then(|| fields("expression", "name"))?
+        } else if lang == Language::KOTLIN {
+            (kind == "navigation_expression").then(first_last)?
+        } else if lang == Language::SWIFT {
+            if kind != "navigation_expression" {
+                return None;
+            }
+            let suffix = node.child_by_field_name("suffix")?;
+            Some((
+                node.child_by_field_name("target"),
+                suffix.child_by_field_name("suffix"),
+            ))
+        } else if lang == Language::C || lang == Language::CPP {
+            match kind {
+                "field_expression" => fields("argument", "field"),
+                "qualified_identifier" => Some((None, node.child_by_field_name("name"))),
+                _ => None,
+            }
+        } else if lang == Language::PHP {
+            match kind {
+                "member_access_expression" | "nullsafe_member_access_expression" => {
+                    fields("object", "name")
+                }
+                "scoped_property_access_expression" => {
+                    Some((None, node.child_by_field_name("name")))
+                }
+                "class_constant_access_expression" => first_last().map(|(_, name)| (None, name)),
+                _ => None,
+            }
+        } else {
+            match kind {
+                "scope_resolution" => Some((None, node.child_by_field_name("name"))),
+                // `obj.attr`: a call without arguments reads an attribute
+                "call"
+                    if node.child_by_field_name("arguments").is_none()
+                        && node.child_by_field_name("block").is_none() =>
+                {
+                    node.child_by_field_name("receiver")?;
+                    fields("receiver", "method")
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// The indexed object of an element access (`items[k]`).
+    fn subscript_object<'t>(&self, node: Node<'t>) -> Option<Node<'t>> {
+        let field = match node.kind() {
+            "subscript" => Some("value"),
+            "subscript_expression"
+                if self.language == Language::C || self.language == Language::CPP =>
+            {
+                Some("argument")
+            }
+            "subscript_expression" | "element_reference" => Some("object"),
+            "index_expression" => Some("operand"),
+            "array_access" => Some("array"),
+            "element_access_expression" => Some("expression"),
+            // Kotlin: the indexed object is the first child
+            "indexing_expression" => None,
+            _ => return None,
+        };
+        field
+            .and_then(|f| node.child_by_field_name(f))
+            .or_else(|| node.named_child(0))
+    }
+
+    /// The node naming the function called by a call expression.
+    fn callee<'t>(&self, node: Node<'t>) -> Option<Node<'t>> {
+        let lang = self.language;
+        match node.kind() {
+            "call" if lang == Language::PYTHON => node.child_by_field_name("function"),
+            // Ruby calls without arguments are attribute reads (see `member`)
+            "call" => node
+                .child_by_field_name("method")
+                .filter(|_| self.member(node).is_none()),
+            "call_expression" if lang == Language::KOTLIN || lang == Language::SWIFT => {
+                node.named_child(0)
+            }
+            "call_expression" | "invocation_expression" | "function_call_expression" => {
+                node.child_by_field_name("function")
+            }
+            "macro_invocation" => node.child_by_field_name("macro"),
+            "new_expression" => node.child_by_field_name("constructor"),
+            "method_invocation"
+            | "member_call_expression"
+            | "nullsafe_member_call_expression"
+            | "scoped_call_expression" => node.child_by_field_name("name"),
+            _ => None,
+        }
+    }
+
+    /// The assigned target of an assignment, and whether it is compound (`+=`).
+    fn assignment<'t>(&self, node: Node<'t>) -> Option<(Node<'t>, bool)> {
+        let kind = node.kind();
+        let compound = matches!(
+            kind,
+            "augmented_assignment"
+                | "compound_assignment_expr"
+                | "augmented_assignment_expression"
+                | "operator_assignment"
+        );
+        if !compound
+            && !matches!(
+                kind,
+                "assignment"
+                    | "assignment_expression"
+                    | "assignment_statement"
+                    | "reference_assignment_expression"
+            )
+        {
+            return None;
+        }
+        let target = node
+            .child_by_field_name("left")
+            .or_else(|| node.child_by_field_name("target"))?;
+        let operator = node
+            .child_by_field_name("operator")
+            .is_some_and(|op| self.text(op) != "=");
+        Some((target, compound || operator))
+    }
+
+    /// The variable incremented or decremented by `x++`, `--x`.
+    fn update_target<'t>(&self, node: Node<'t>) -> Option<Node<'t>> {
+        if !matches!(
+            node.kind(),
+            "update_expression"
+                | "inc_statement"
+                | "dec_statement"
+                | "postfix_unary_expression"
+                | "prefix_unary_expression"
+                | "unary_expression"
+                | "postfix_expression"
+                | "prefix_expression"
+        ) {
+            return None;
+        }
+        let text = self.text(node);
+        let is_update = ["++", "--"]
+            .iter()
+            .any(|op| text.starts_with(op) || text.ends_with(op));
+        if !is_update {
+            return None;
+        }
+        node.child_by_field_name("argument")
+            .or_else(|| node.child_by_field_name("operand"))
+            .or_else(|| node.named_child(0))
+    }
+
+    /// Collect the names bound in `function`, excluding nested functions
+    /// other than their own name.
+    fn collect_bindings(&self, function: Node, scope: &mut Scope) {
+        let mut cursor = function.walk();
+        for child in function.named_children(&mut cursor) {
+            self.collect_node_bindings(child, scope);
+        }
+    }
+
+    fn collect_node_bindings(&self, node: Node, scope: &mut Scope) {
+        let kind = node.kind();
+        if is_function(self.language, kind) {
+            if let Some(name) = node.child_by_field_name("name") {
+                self.bind(name, scope);
+            }
+            return;
+        }
+        if matches!(
+            kind,
+            "global_statement" | "nonlocal_statement" | "global_declaration"
+        ) {
+            let mut cursor = node.walk();
+            for name in node.named_children(&mut cursor) {
+                if let Some(name) = self.reference(name) {
+                    scope.globals.insert(name);
+                }
+            }
+            return;
+        }
+        for pattern in self.bound_patterns(node) {
+            self.bind(pattern, scope);
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect_node_bindings(child, scope);
+        }
+    }
+
+    /// The patterns holding the names that `node` binds locally.
+    fn bound_patterns<'t>(&self, node: Node<'t>) -> Vec<Node<'t>> {
+        let lang = self.language;
+        let kind = node.kind();
+        let field = |name: &str| {
+            node.child_by_field_name(name)
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        let fields = |name: &str| {
+            let mut cursor = node.walk();
+            node.children_by_field_name(name, &mut cursor)
+                .collect::<Vec<_>>()
+        };
+        if lang == Language::PYTHON {
+            match kind {
+                "parameters" | "lambda_parameters" => vec![node],
+                "assignment" | "augmented_assignment" | "for_statement" | "for_in_clause" => {
+                    field("left")
+                }
+                "as_pattern" => field("alias"),
+                "named_expression" | "class_definition" => field("name"),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::RUST {
+            match kind {
+                "parameter" | "let_declaration" | "for_expression" | "let_condition" => {
+                    field("pattern")
+                }
+                "closure_parameters" => vec![node],
+                "match_arm" => field("pattern"),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::JAVASCRIPT || lang == Language::TYPESCRIPT {
+            match kind {
+                "formal_parameters" => vec![node],
+                "arrow_function" => field("parameter"),
+                "variable_declarator" | "class_declaration" => field("name"),
+                "catch_clause" => field("parameter"),
+                "for_in_statement" if node.child_by_field_name("kind").is_some() => field("left"),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::GO {
+            match kind {
+                "parameter_declaration"
+                | "variadic_parameter_declaration"
+                | "var_spec"
+                | "const_spec" => fields("name"),
+                "short_var_declaration" | "range_clause" => field("left"),
+                "type_switch_statement" => field("alias"),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::JAVA {
+            match kind {
+                "formal_parameter"
+                | "spread_parameter"
+                | "variable_declarator"
+                | "enhanced_for_statement"
+                | "catch_formal_parameter"
+                | "resource" => field("name"),
+                "inferred_parameters" => vec![node],
+                "lambda_expression" => field("parameters")
+                    .into_iter()
+                    .filter(|p| p.kind() == "identifier")
+                    .collect(),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::CSHARP {
+            match kind {
+                "parameter" | "catch_declaration" | "declaration_expression" => field("name"),
+                "variable_declarator" => field("name")
+                    .into_iter()
+                    .chain(node.named_child(0).filter(|c| c.kind() == "identifier"))
+                    .take(1)
+                    .collect(),
+                "foreach_statement" => field("left"),
+                "lambda_expression" => field("parameters")
+                    .into_iter()
+                    .filter(|p| p.kind() == "identifier")
+                    .collect(),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::KOTLIN {
+            match kind {
+                "variable_declaration" => vec![node],
+                "parameter" => node.named_child(0).into_iter().collect(),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::SWIFT {
+            match kind {
+                "parameter" | "lambda_parameter" => fields("name"),
+                "pattern" => field("bound_identifier"),
+                _ => Vec::new(),
+            }
+        } else if lang == Language::C || lang == Language::CPP {
+            match kind {
+                "parameter_declaration" | "for_range_loop" => field("declarator"),
+                "declaration" => {
+                    let mut cursor = node.walk();
+                    node.children_by_field_name("declarator", &mut cursor)
+                        .filter_map(c_declarator_name)
+                        .collect()
+                }
+                _ => Vec::new(),
+            }
+        } else if lang == Language::RUBY {
+            match kind {
+                "method_parameters" | "block_parameters" | "lambda_parameters" => vec![node],
+                "assignment" | "operator_assignment" => field("left")
+                    .into_iter()
+                    .filter(|l| matches!(l.kind(), "identifier" | "left_assignment_list"))
+                    .collect(),
+                "for" => field("pattern"),
+                _ => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Add the names in a binding pattern, skipping defaults, types and
+    /// members (`self.x = 1` binds nothing).
+    fn bind(&self, pattern: Node, scope: &mut Scope) {
+        if let Some(name) = self.reference(pattern) {
+            scope.locals.insert(name);
+            return;
+        }
+        if self.member(pattern).is_some() || self.subscript_object(pattern).is_some() {
+            return;
+        }
+        if self.language == Language::C || self.language == Language::CPP {
+            if let Some(name) = c_declarator_name(pattern).and_then(|n| self.reference(n)) {
+                scope.locals.insert(name);
+            }
+            return;
+        }
+        let mut cursor = pattern.walk();
+        for (i, child) in pattern.children(&mut cursor).enumerate() {
+            let field = u32::try_from(i)
+                .ok()
+                .and_then(|i| pattern.field_name_for_child(i));
+            if !child.is_named()
+                || matches!(
+                    field,
+                    Some("value" | "default_value" | "default" | "right" | "type")
+                )
+                || self.skipped(pattern, child, i)
+            {
+                continue;
+            }
+            self.bind(child, scope);
+        }
+    }
+}
+
+/// The mode of the object of a member or element access: assigning
+/// `x.y` or `x[k]` modifies `x`.
+fn object_mode(mode: Mode) -> Mode {
+    match mode {
+        Mode::Read => Mode::Read,
+        Mode::Write | Mode::Update => Mode::Write,
+    }
+}
@@ -57,9 +57,15 @@ fn test_signature_void_return() {
 fn test_extract_c_struct() {
     let source = "struct Point { int x; int y; };\n";
     let entities = extract_entities(Path::new("test.c"), source, Language::C);
-    assert_eq!(entities.len(), 1);
+    assert_eq!(entities.len(), 3);
     assert_eq!(entities[0].name, "Point");
     assert_eq!(entities[0].kind, EntityKind::Class);
+    let fields: Vec<&str> = entities[1..]
+        .iter()
+        .filter(|e| e.kind == EntityKind::Field && e.parent_class.as_deref() == Some("Point"))
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(fields, ["x", "y"]);
 }
 
 #[test]
@@ -31,9 +31,10 @@ fn test_extract_cpp_class_with_method() {
 fn test_extract_cpp_struct() {
     let source = "struct Point { int x; int y; };\n";
     let entities = extract_entities(Path::new("test.cpp"), source, Language::CPP);
-    assert_eq!(entities.len(), 1);
+    assert_eq!(entities.len(), 3);
     assert_eq!(entities[0].name, "Point");
     assert_eq!(entities[0].kind, EntityKind::Class);
+    assert!(entities[1..].iter().all(|e| e.kind == EntityKind::Field));
 }
 
 #[test]
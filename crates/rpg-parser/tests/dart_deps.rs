@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use rpg_parser::deps::extract_deps;
+use rpg_parser::languages::Language;
+
+#[test]
+fn dart_imports() {
+    let source =
+        "import 'package:flutter/material.dart';\nimport 'dart:async' show Future, Stream;";
+    let deps = extract_deps(Path::new("main.dart"), source, Language::DART);
+    assert_eq!(deps.imports.len(), 2);
+    assert_eq!(deps.imports[0].module, "package:flutter/material.dart");
+    assert!(deps.imports[0].symbols.is_empty());
+    assert_eq!(deps.imports[1].module, "dart:async");
+    assert_eq!(deps.imports[1].symbols, vec!["Future", "Stream"]);
+}
+
+#[test]
+fn dart_class_inheritance() {
+    let source =
+        "class Home extends StatefulWidget<Home> with Logging implements Routable, Disposable {}";
+    let deps = extract_deps(Path::new("home.dart"), source, Language::DART);
+    let parents: Vec<&str> = deps
+        .inherits
+        .iter()
+        .map(|i| i.parent_class.as_str())
+        .collect();
+    assert_eq!(
+        parents,
+        vec!["StatefulWidget", "Logging", "Routable", "Disposable"]
+    );
+    assert!(deps.inherits.iter().all(|i| i.child_class == "Home"));
+}
+
+#[test]
+fn dart_calls_with_caller() {
+    let source = r"class Repo {
+  Future<void> save(Item item) async {
+    validate(item);
+    await db.insert(item);
+    final log = Logger();
+  }
+}";
+    let deps = extract_deps(Path::new("repo.dart"), source, Language::DART);
+    let callees: Vec<&str> = deps.calls.iter().map(|c| c.callee.as_str()).collect();
+    assert!(callees.contains(&"validate"));
+    assert!(callees.contains(&"insert"));
+    assert!(callees.contains(&"Logger"));
+    assert!(deps.calls.iter().all(|c| c.caller_entity == "Repo.save"));
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+
+#[test]
+fn dart_detected_from_extension() {
+    assert_eq!(Language::from_extension("dart"), Some(Language::DART));
+}
+
+#[test]
+fn dart_extract_class_with_members() {
+    let source = r"class Counter {
+  int count;
+  Counter(this.count);
+  Counter.zero() : count = 0;
+  void increment(int by, [int times = 1]) {}
+  int get doubled => count * 2;
+}";
+    let entities = extract_entities(Path::new("counter.dart"), source, Language::DART);
+    let class = entities.iter().find(|e| e.name == "Counter").unwrap();
+    assert_eq!(class.kind, EntityKind::Class);
+
+    let ctor = entities
+        .iter()
+        .find(|e| e.name == "Counter" && e.kind == EntityKind::Method)
+        .unwrap();
+    assert_eq!(ctor.parent_class.as_deref(), Some("Counter"));
+    assert_eq!(ctor.signature.as_ref().unwrap().parameters[0].name, "count");
+    assert!(entities.iter().any(|e| e.name == "Counter.zero"));
+
+    let increment = entities.iter().find(|e| e.name == "increment").unwrap();
+    assert_eq!(increment.kind, EntityKind::Method);
+    let sig = increment.signature.as_ref().unwrap();
+    assert_eq!(sig.parameters.len(), 2);
+    assert_eq!(sig.parameters[0].type_annotation.as_deref(), Some("int"));
+    assert_eq!(sig.return_type.as_deref(), Some("void"));
+
+    let getter = entities.iter().find(|e| e.name == "doubled").unwrap();
+    assert_eq!(getter.kind, EntityKind::Method);
+}
+
+#[test]
+fn dart_extract_top_level_function() {
+    let source = "String greet(String name) {\n  return 'hi $name';\n}";
+    let entities = extract_entities(Path::new("greet.dart"), source, Language::DART);
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].name, "greet");
+    assert_eq!(entities[0].kind, EntityKind::Function);
+    let sig = entities[0].signature.as_ref().unwrap();
+    assert_eq!(sig.return_type.as_deref(), Some("String"));
+    assert_eq!(sig.parameters[0].name, "name");
+}
+
+#[test]
+fn dart_extract_mixin_extension_enum() {
+    let source = r"mixin Logging {
+  void log(String m) {}
+}
+extension StringX on String {
+  bool get isBlank => trim().isEmpty;
+}
+enum Color { red, green }";
+    let entities = extract_entities(Path::new("misc.dart"), source, Language::DART);
+    for name in ["Logging", "StringX", "Color"] {
+        let e = entities.iter().find(|e| e.name == name).unwrap();
+        assert_eq!(e.kind, EntityKind::Class);
+    }
+    let log = entities.iter().find(|e| e.name == "log").unwrap();
+    assert_eq!(log.parent_class.as_deref(), Some("Logging"));
+    let blank = entities.iter().find(|e| e.name == "isBlank").unwrap();
+    assert_eq!(blank.parent_class.as_deref(), Some("StringX"));
+}
+
+#[test]
+fn dart_abstract_method() {
+    let source = "abstract class Shape {\n  double area();\n}";
+    let entities = extract_entities(Path::new("shape.dart"), source, Language::DART);
+    let area = entities.iter().find(|e| e.name == "area").unwrap();
+    assert_eq!(area.kind, EntityKind::Method);
+    assert_eq!(area.parent_class.as_deref(), Some("Shape"));
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use rpg_parser::deps::extract_deps;
+use rpg_parser::languages::Language;
+
+#[test]
+fn elixir_import_alias_require() {
+    let source = r"defmodule MyApp.Web do
+  import Ecto.Query, only: [from: 2]
+  alias MyApp.{Repo, Post}
+  require Logger
+end";
+    let deps = extract_deps(Path::new("web.ex"), source, Language::ELIXIR);
+    assert_eq!(deps.imports.len(), 3);
+    assert_eq!(deps.imports[0].module, "Ecto.Query");
+    assert_eq!(deps.imports[0].symbols, vec!["from"]);
+    assert_eq!(deps.imports[1].module, "MyApp");
+    assert_eq!(deps.imports[1].symbols, vec!["Repo", "Post"]);
+    assert_eq!(deps.imports[2].module, "Logger");
+}
+
+#[test]
+fn elixir_use_and_behaviour_inheritance() {
+    let source = r"defmodule MyApp.Worker do
+  use GenServer
+  @behaviour MyApp.Job
+end";
+    let deps = extract_deps(Path::new("worker.ex"), source, Language::ELIXIR);
+    assert!(deps.imports.iter().any(|i| i.module == "GenServer"));
+    assert_eq!(deps.inherits.len(), 2);
+    assert!(
+        deps.inherits
+            .iter()
+            .all(|i| i.child_class == "MyApp.Worker")
+    );
+    assert!(deps.inherits.iter().any(|i| i.parent_class == "GenServer"));
+    assert!(deps.inherits.iter().any(|i| i.parent_class == "MyApp.Job"));
+}
+
+#[test]
+fn elixir_defimpl_inherits_protocol() {
+    let source = "defimpl Size, for: Map do\n  def size(map), do: map_size(map)\nend";
+    let deps = extract_deps(Path::new("size.ex"), source, Language::ELIXIR);
+    assert_eq!(deps.inherits.len(), 1);
+    assert_eq!(deps.inherits[0].child_class, "Size.Map");
+    assert_eq!(deps.inherits[0].parent_class, "Size");
+}
+
+#[test]
+fn elixir_local_and_remote_calls() {
+    let source = r#"defmodule Blog do
+  @doc "Publishes."
+  def publish(post) do
+    if valid?(post) do
+      post |> Repo.insert() |> notify()
+    end
+  end
+end"#;
+    let deps = extract_deps(Path::new("blog.ex"), source, Language::ELIXIR);
+    let callees: Vec<&str> = deps.calls.iter().map(|c| c.callee.as_str()).collect();
+    assert!(callees.contains(&"valid?"));
+    assert!(callees.contains(&"insert"));
+    assert!(callees.contains(&"notify"));
+    // Neither the function head, `if`, nor `@doc` count as calls
+    assert!(!callees.contains(&"publish"));
+    assert!(!callees.contains(&"if"));
+    assert!(!callees.contains(&"doc"));
+    assert!(deps.calls.iter().all(|c| c.caller_entity == "Blog.publish"));
+}
+
+#[test]
+fn elixir_one_line_body_calls() {
+    let source = "defmodule A do\n  def f(x), do: g(x)\nend";
+    let deps = extract_deps(Path::new("a.ex"), source, Language::ELIXIR);
+    assert_eq!(deps.calls.len(), 1);
+    assert_eq!(deps.calls[0].caller_entity, "A.f");
+    assert_eq!(deps.calls[0].callee, "g");
+}
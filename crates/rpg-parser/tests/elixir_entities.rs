@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+
+#[test]
+fn elixir_detected_from_extension() {
+    assert_eq!(Language::from_extension("ex"), Some(Language::ELIXIR));
+    assert_eq!(Language::from_extension("exs"), Some(Language::ELIXIR));
+}
+
+#[test]
+fn elixir_extract_module_with_functions() {
+    let source = r"defmodule MyApp.Accounts do
+  def create(attrs, opts \\ []) do
+    attrs
+  end
+
+  defp normalize(x), do: x
+end";
+    let entities = extract_entities(Path::new("accounts.ex"), source, Language::ELIXIR);
+    assert_eq!(entities.len(), 3);
+
+    let module = entities
+        .iter()
+        .find(|e| e.name == "MyApp.Accounts")
+        .unwrap();
+    assert_eq!(module.kind, EntityKind::Class);
+
+    let create = entities.iter().find(|e| e.name == "create").unwrap();
+    assert_eq!(create.kind, EntityKind::Method);
+    assert_eq!(create.parent_class.as_deref(), Some("MyApp.Accounts"));
+    let params: Vec<&str> = create
+        .signature
+        .as_ref()
+        .unwrap()
+        .parameters
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(params, vec!["attrs", "opts"]);
+
+    let normalize = entities.iter().find(|e| e.name == "normalize").unwrap();
+    assert_eq!(normalize.parent_class.as_deref(), Some("MyApp.Accounts"));
+}
+
+#[test]
+fn elixir_merges_function_clauses() {
+    let source = r"defmodule Math do
+  def fact(0), do: 1
+  def fact(n) when n > 0, do: n * fact(n - 1)
+  def fact(a, b), do: a * b
+end";
+    let entities = extract_entities(Path::new("math.ex"), source, Language::ELIXIR);
+    let facts: Vec<_> = entities.iter().filter(|e| e.name == "fact").collect();
+    // fact/1 clauses merge; fact/2 is a separate function
+    assert_eq!(facts.len(), 2);
+    assert_eq!(facts[0].line_start, 2);
+    assert_eq!(facts[0].line_end, 3);
+    assert_eq!(facts[1].signature.as_ref().unwrap().parameters.len(), 2);
+}
+
+#[test]
+fn elixir_extract_protocol_and_impl() {
+    let source = r"defprotocol Size do
+  def size(data)
+end
+
+defimpl Size, for: Map do
+  def size(map), do: map_size(map)
+end";
+    let entities = extract_entities(Path::new("size.ex"), source, Language::ELIXIR);
+    let protocol = entities.iter().find(|e| e.name == "Size").unwrap();
+    assert_eq!(protocol.kind, EntityKind::Class);
+    let implementation = entities.iter().find(|e| e.name == "Size.Map").unwrap();
+    assert_eq!(implementation.kind, EntityKind::Class);
+    let impl_size = entities
+        .iter()
+        .find(|e| e.name == "size" && e.parent_class.as_deref() == Some("Size.Map"))
+        .unwrap();
+    assert_eq!(impl_size.kind, EntityKind::Method);
+}
+
+#[test]
+fn elixir_extract_macro_and_zero_arity() {
+    let source = "defmodule M do\n  defmacro debug(expr), do: expr\n  def version, do: 1\nend";
+    let entities = extract_entities(Path::new("m.ex"), source, Language::ELIXIR);
+    assert!(entities.iter().any(|e| e.name == "debug"));
+    let version = entities.iter().find(|e| e.name == "version").unwrap();
+    assert!(version.signature.as_ref().unwrap().parameters.is_empty());
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use rpg_parser::deps::extract_deps;
+use rpg_parser::languages::Language;
+
+#[test]
+fn vue_imports_and_calls() {
+    let source = r#"<template><Child /></template>
+<script setup lang="ts">
+import Child from './Child.vue'
+import { ref } from 'vue'
+function load() {
+  fetchItems()
+}
+</script>
+"#;
+    let deps = extract_deps(Path::new("List.vue"), source, Language::VUE);
+    let modules: Vec<&str> = deps.imports.iter().map(|i| i.module.as_str()).collect();
+    assert_eq!(modules, vec!["./Child.vue", "vue"]);
+    assert_eq!(deps.imports[1].symbols, vec!["ref"]);
+    assert_eq!(deps.calls.len(), 1);
+    assert_eq!(deps.calls[0].caller_entity, "load");
+    assert_eq!(deps.calls[0].callee, "fetchItems");
+}
+
+#[test]
+fn deps_merged_across_regions() {
+    let source = r"---
+import Layout from '../layouts/Layout.astro';
+---
+<Layout />
+<script>
+  import { track } from '../analytics';
+  class Banner extends HTMLElement {}
+</script>
+";
+    let deps = extract_deps(Path::new("index.astro"), source, Language::ASTRO);
+    let modules: Vec<&str> = deps.imports.iter().map(|i| i.module.as_str()).collect();
+    assert_eq!(modules, vec!["../layouts/Layout.astro", "../analytics"]);
+    assert_eq!(deps.inherits.len(), 1);
+    assert_eq!(deps.inherits[0].child_class, "Banner");
+    assert_eq!(deps.inherits[0].parent_class, "HTMLElement");
+}
+
+#[test]
+fn markdown_fence_deps() {
+    let source = "Usage:\n\n```py\nimport os\n\ndef main():\n    os.listdir('.')\n```\n";
+    let deps = extract_deps(Path::new("docs/usage.md"), source, Language::MARKDOWN);
+    assert_eq!(deps.imports.len(), 1);
+    assert_eq!(deps.imports[0].module, "os");
+    assert_eq!(deps.calls[0].caller_entity, "main");
+    assert_eq!(deps.calls[0].callee, "listdir");
+}
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::embedded::split_regions;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+
+#[test]
+fn host_languages_detected_from_extension() {
+    assert_eq!(Language::from_extension("vue"), Some(Language::VUE));
+    assert_eq!(Language::from_extension("svelte"), Some(Language::SVELTE));
+    assert_eq!(Language::from_extension("astro"), Some(Language::ASTRO));
+    assert_eq!(Language::from_extension("htm"), Some(Language::HTML));
+    assert_eq!(Language::from_extension("md"), Some(Language::MARKDOWN));
+}
+
+#[test]
+fn docs_and_pages_do_not_count_toward_detection() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("docs")).unwrap();
+    std::fs::write(root.join("src/app.py"), "def main():\n    pass\n").unwrap();
+    for doc in ["d1", "d2", "d3"] {
+        std::fs::write(
+            root.join(format!("docs/{doc}.md")),
+            "```python\ndef example():\n    load()\n```\n",
+        )
+        .unwrap();
+    }
+    std::fs::write(root.join("docs/index.html"), "<script>run()</script>\n").unwrap();
+
+    assert_eq!(Language::detect_primary(root), Some(Language::PYTHON));
+    assert_eq!(Language::detect_all(root), vec![Language::PYTHON]);
+    assert!(!Language::MARKDOWN.is_detected());
+    assert!(Language::VUE.is_detected());
+}
+
+#[test]
+fn vue_script_blocks() {
+    let source = r#"<template>
+  <button @click="increment">{{ count }}</button>
+</template>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+const count = ref(0)
+function increment(): void {
+  count.value++
+}
+</script>
+
+<style scoped>
+button { color: red; }
+</style>
+"#;
+    let regions = split_regions(Path::new("Counter.vue"), source);
+    assert_eq!(regions.len(), 1);
+    assert_eq!(regions[0].language, Language::TYPESCRIPT);
+
+    let entities = extract_entities(Path::new("Counter.vue"), source, Language::VUE);
+    let increment = entities.iter().find(|e| e.name == "increment").unwrap();
+    assert_eq!(increment.kind, EntityKind::Function);
+    assert_eq!(increment.file, Path::new("Counter.vue"));
+    assert_eq!((increment.line_start, increment.line_end), (8, 10));
+    assert_eq!(
+        increment.signature.as_ref().unwrap().return_type.as_deref(),
+        Some("void")
+    );
+    assert_eq!(increment.id(), "Counter.vue:increment");
+}
+
+#[test]
+fn svelte_module_and_instance_scripts() {
+    let source = r#"<script context="module">
+  export function preload() {}
+</script>
+
+<script>
+  function handleClick() {}
+</script>
+
+<button on:click={handleClick}>Go</button>
+"#;
+    let entities = extract_entities(Path::new("Page.svelte"), source, Language::SVELTE);
+    let lines: Vec<(&str, usize)> = entities
+        .iter()
+        .map(|e| (e.name.as_str(), e.line_start))
+        .collect();
+    assert_eq!(lines, vec![("preload", 2), ("handleClick", 6)]);
+}
+
+#[test]
+fn astro_frontmatter_and_script() {
+    let source = r"---
+import Card from './Card.astro';
+function title(name: string): string {
+  return name.toUpperCase();
+}
+---
+<Card title={title('x')} />
+<script>
+  class Toggle {
+    flip() {}
+  }
+</script>
+";
+    let regions = split_regions(Path::new("index.astro"), source);
+    assert_eq!(regions.len(), 2);
+    assert!(regions.iter().all(|r| r.language == Language::TYPESCRIPT));
+
+    let entities = extract_entities(Path::new("index.astro"), source, Language::ASTRO);
+    let title = entities.iter().find(|e| e.name == "title").unwrap();
+    assert_eq!(title.line_start, 3);
+    let flip = entities.iter().find(|e| e.name == "flip").unwrap();
+    assert_eq!(flip.kind, EntityKind::Method);
+    assert_eq!(flip.parent_class.as_deref(), Some("Toggle"));
+    assert_eq!(flip.line_start, 10);
+}
+
+#[test]
+fn html_skips_data_and_external_scripts() {
+    let source = r#"<!doctype html>
+<html>
+<head>
+  <script src="app.js"></script>
+  <script type="application/ld+json">{"@type": "Thing"}</script>
+  <script type="text/x-template"><div></div></script>
+  <script type="module">
+    function boot() {}
+  </script>
+</head>
+</html>
+"#;
+    let regions = split_regions(Path::new("index.html"), source);
+    assert_eq!(regions.len(), 1);
+    assert_eq!(regions[0].language, Language::JAVASCRIPT);
+
+    let entities = extract_entities(Path::new("index.html"), source, Language::HTML);
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].name, "boot");
+    assert_eq!(entities[0].line_start, 8);
+}
+
+#[test]
+fn markdown_code_fences() {
+    let source = r"# Guide
+
+```python
+def greet(name):
+    return f'hi {name}'
+```
+
+~~~rs
+fn main() {}
+~~~
+
+```text
+def not_code(): pass
+```
+
+```
+def untagged(): pass
+```
+";
+    let regions = split_regions(Path::new("README.md"), source);
+    let languages: Vec<Language> = regions.iter().map(|r| r.language).collect();
+    assert_eq!(languages, vec![Language::PYTHON, Language::RUST]);
+
+    let entities = extract_entities(Path::new("README.md"), source, Language::MARKDOWN);
+    let lines: Vec<(&str, usize)> = entities
+        .iter()
+        .map(|e| (e.name.as_str(), e.line_start))
+        .collect();
+    assert_eq!(lines, vec![("greet", 4), ("main", 9)]);
+}
+
+#[test]
+fn non_host_files_have_no_regions() {
+    assert!(split_regions(Path::new("app.ts"), "function f() {}").is_empty());
+}
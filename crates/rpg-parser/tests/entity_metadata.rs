@@ -0,0 +1,342 @@
+use rpg_core::graph::{EntityMetadata, Visibility};
+use rpg_parser::entities::{RawEntity, extract_entities};
+use rpg_parser::languages::Language;
+use std::path::Path;
+
+fn extract(file: &str, source: &str, language: Language) -> Vec<RawEntity> {
+    extract_entities(Path::new(file), source, language)
+}
+
+fn meta<'a>(entities: &'a [RawEntity], name: &str) -> &'a EntityMetadata {
+    &entities
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("entity {name} not found"))
+        .metadata
+}
+
+#[test]
+fn test_rust_metadata() {
+    let source = r#"
+/// Parses the input.
+///
+/// Returns the AST.
+#[inline]
+#[deprecated(note = "use parse2")]
+pub async fn parse() {}
+
+// not a doc comment
+fn helper() {}
+
+pub(crate) struct Parser;
+
+impl Parser {
+    pub fn new() -> Self { Parser }
+    fn step(&self) {}
+}
+"#;
+    let entities = extract("src/lib.rs", source, Language::RUST);
+
+    let parse = meta(&entities, "parse");
+    assert_eq!(parse.visibility, Some(Visibility::Public));
+    assert!(parse.is_async);
+    assert!(parse.is_deprecated);
+    assert_eq!(
+        parse.docstring.as_deref(),
+        Some("Parses the input.\n\nReturns the AST.")
+    );
+    assert_eq!(
+        parse.decorators,
+        ["inline", "deprecated(note = \"use parse2\")"]
+    );
+
+    let helper = meta(&entities, "helper");
+    assert_eq!(helper.visibility, Some(Visibility::Private));
+    assert!(helper.docstring.is_none());
+
+    assert_eq!(
+        meta(&entities, "Parser").visibility,
+        Some(Visibility::Internal)
+    );
+    assert!(meta(&entities, "new").is_static);
+    assert!(!meta(&entities, "step").is_static);
+}
+
+#[test]
+fn test_python_metadata() {
+    let source = r#"
+@dataclass
+class Config:
+    """Runtime configuration."""
+
+    @staticmethod
+    async def load(path):
+        """Load config from disk.
+
+        .. deprecated:: 2.0
+        """
+
+    def _validate(self):
+        pass
+
+    def __repr__(self):
+        pass
+"#;
+    let entities = extract("config.py", source, Language::PYTHON);
+
+    let class = meta(&entities, "Config");
+    assert_eq!(class.decorators, ["dataclass"]);
+    assert_eq!(class.docstring.as_deref(), Some("Runtime configuration."));
+
+    let load = meta(&entities, "load");
+    assert!(load.is_async);
+    assert!(load.is_static);
+    assert!(load.is_deprecated);
+    assert_eq!(load.visibility, Some(Visibility::Public));
+    assert!(
+        load.docstring
+            .as_deref()
+            .is_some_and(|d| d.starts_with("Load config from disk."))
+    );
+
+    assert_eq!(
+        meta(&entities, "_validate").visibility,
+        Some(Visibility::Private)
+    );
+    assert_eq!(
+        meta(&entities, "__repr__").visibility,
+        Some(Visibility::Public)
+    );
+}
+
+#[test]
+fn test_typescript_metadata() {
+    let source = r"
+/** Fetches a user. */
+export async function fetchUser(id: string) {}
+
+function local() {}
+
+export const handler = async () => {};
+
+class Service {
+  @Get()
+  static async list() {}
+  private reset() {}
+  #secret() {}
+}
+";
+    let entities = extract("api.ts", source, Language::TYPESCRIPT);
+
+    let fetch = meta(&entities, "fetchUser");
+    assert_eq!(fetch.visibility, Some(Visibility::Public));
+    assert!(fetch.is_async);
+    assert_eq!(fetch.docstring.as_deref(), Some("Fetches a user."));
+
+    assert_eq!(
+        meta(&entities, "local").visibility,
+        Some(Visibility::Private)
+    );
+
+    let handler = meta(&entities, "handler");
+    assert_eq!(handler.visibility, Some(Visibility::Public));
+    assert!(handler.is_async);
+
+    let list = meta(&entities, "list");
+    assert!(list.is_static && list.is_async);
+    assert_eq!(list.visibility, Some(Visibility::Public));
+    assert_eq!(list.decorators, ["Get()"]);
+
+    assert_eq!(
+        meta(&entities, "reset").visibility,
+        Some(Visibility::Private)
+    );
+    assert_eq!(
+        meta(&entities, "#secret").visibility,
+        Some(Visibility::Private)
+    );
+}
+
+#[test]
+fn test_java_and_csharp_metadata() {
+    let java = r"
+/** A repository. */
+@Deprecated
+public abstract class Repo {
+    protected static void open() {}
+    void save() {}
+}
+";
+    let entities = extract("Repo.java", java, Language::JAVA);
+    let repo = meta(&entities, "Repo");
+    assert_eq!(repo.visibility, Some(Visibility::Public));
+    assert!(repo.is_abstract && repo.is_deprecated);
+    assert_eq!(repo.docstring.as_deref(), Some("A repository."));
+    let open = meta(&entities, "open");
+    assert_eq!(open.visibility, Some(Visibility::Protected));
+    assert!(open.is_static);
+    assert_eq!(
+        meta(&entities, "save").visibility,
+        Some(Visibility::Internal)
+    );
+
+    let csharp = r"
+namespace App {
+    public class Service {
+        /// <summary>Runs.</summary>
+        [Obsolete]
+        public async Task Run() {}
+        void Stop() {}
+    }
+}
+";
+    let entities = extract("Service.cs", csharp, Language::CSHARP);
+    let run = meta(&entities, "Run");
+    assert_eq!(run.visibility, Some(Visibility::Public));
+    assert!(run.is_async && run.is_deprecated);
+    assert_eq!(run.decorators, ["Obsolete"]);
+    assert_eq!(run.docstring.as_deref(), Some("<summary>Runs.</summary>"));
+    assert_eq!(
+        meta(&entities, "Stop").visibility,
+        Some(Visibility::Private)
+    );
+}
+
+#[test]
+fn test_go_and_c_family_metadata() {
+    let go = "\
+package api
+
+// Serve starts the server.
+// Deprecated: use ServeContext.
+func Serve() {}
+
+func helper() {}
+";
+    let entities = extract("api.go", go, Language::GO);
+    let serve = meta(&entities, "Serve");
+    assert_eq!(serve.visibility, Some(Visibility::Public));
+    assert!(serve.is_deprecated);
+    assert_eq!(
+        serve.docstring.as_deref(),
+        Some("Serve starts the server.\nDeprecated: use ServeContext.")
+    );
+    assert_eq!(
+        meta(&entities, "helper").visibility,
+        Some(Visibility::Private)
+    );
+
+    let c = "/* Internal helper. */\nstatic int step(void) { return 0; }\nint run(void) { return step(); }\n";
+    let entities = extract("main.c", c, Language::C);
+    let step = meta(&entities, "step");
+    assert_eq!(step.visibility, Some(Visibility::Private));
+    assert!(!step.is_static);
+    assert_eq!(step.docstring.as_deref(), Some("Internal helper."));
+    assert_eq!(meta(&entities, "run").visibility, Some(Visibility::Public));
+
+    let cpp = "class A {\n  void hidden() {}\npublic:\n  static void make() {}\n};\n";
+    let entities = extract("a.cpp", cpp, Language::CPP);
+    assert_eq!(
+        meta(&entities, "hidden").visibility,
+        Some(Visibility::Private)
+    );
+    let make = meta(&entities, "make");
+    assert_eq!(make.visibility, Some(Visibility::Public));
+    assert!(make.is_static);
+}
+
+#[test]
+fn test_kotlin_php_ruby_metadata() {
+    let kotlin = "class Repo {\n    private suspend fun load() {}\n    fun save() {}\n}\n";
+    let entities = extract("Repo.kt", kotlin, Language::KOTLIN);
+    let load = meta(&entities, "load");
+    assert_eq!(load.visibility, Some(Visibility::Private));
+    assert!(load.is_async);
+    assert_eq!(meta(&entities, "save").visibility, Some(Visibility::Public));
+
+    let php = "<?php\n#[Deprecated]\nabstract class A {\n    public static function m() {}\n    private function n() {}\n}\n";
+    let entities = extract("a.php", php, Language::PHP);
+    let class = meta(&entities, "A");
+    assert!(class.is_abstract && class.is_deprecated);
+    let m = meta(&entities, "m");
+    assert_eq!(m.visibility, Some(Visibility::Public));
+    assert!(m.is_static);
+    assert_eq!(meta(&entities, "n").visibility, Some(Visibility::Private));
+
+    let ruby = "# A widget.\nclass Widget\n  def draw; end\n  def self.build; end\n  private\n  def cache; end\nend\n";
+    let entities = extract("widget.rb", ruby, Language::RUBY);
+    assert_eq!(
+        meta(&entities, "Widget").docstring.as_deref(),
+        Some("A widget.")
+    );
+    assert_eq!(meta(&entities, "draw").visibility, Some(Visibility::Public));
+    assert!(meta(&entities, "build").is_static);
+    assert_eq!(
+        meta(&entities, "cache").visibility,
+        Some(Visibility::Private)
+    );
+}
+
+#[test]
+fn test_lua_elixir_dart_zig_metadata() {
+    let lua =
+        "-- Adds two numbers.\nfunction add(a, b) return a + b end\nlocal function helper() end\n";
+    let entities = extract("m.lua", lua, Language::LUA);
+    let add = meta(&entities, "add");
+    assert_eq!(add.visibility, Some(Visibility::Public));
+    assert_eq!(add.docstring.as_deref(), Some("Adds two numbers."));
+    assert_eq!(
+        meta(&entities, "helper").visibility,
+        Some(Visibility::Private)
+    );
+
+    let elixir = r#"defmodule Shop do
+  @moduledoc "The shop."
+
+  @doc """
+  Places an order.
+  """
+  @deprecated "Use submit/1"
+  def order(cart), do: cart
+
+  defp total(cart), do: cart
+end
+"#;
+    let entities = extract("shop.ex", elixir, Language::ELIXIR);
+    assert_eq!(
+        meta(&entities, "Shop").docstring.as_deref(),
+        Some("The shop.")
+    );
+    let order = meta(&entities, "order");
+    assert_eq!(order.visibility, Some(Visibility::Public));
+    assert_eq!(order.docstring.as_deref(), Some("Places an order."));
+    assert!(order.is_deprecated);
+    assert_eq!(
+        meta(&entities, "total").visibility,
+        Some(Visibility::Private)
+    );
+
+    let dart = "/// A cache.\nabstract class Cache {\n  @override\n  static Future<void> load() async {}\n  void _evict() {}\n  int size();\n}\n";
+    let entities = extract("cache.dart", dart, Language::DART);
+    let cache = meta(&entities, "Cache");
+    assert_eq!(cache.docstring.as_deref(), Some("A cache."));
+    assert!(cache.is_abstract);
+    let load = meta(&entities, "load");
+    assert!(load.is_static && load.is_async);
+    assert_eq!(load.decorators, vec!["override"]);
+    assert_eq!(
+        meta(&entities, "_evict").visibility,
+        Some(Visibility::Private)
+    );
+    assert!(meta(&entities, "size").is_abstract);
+
+    let zig = "/// Entry point.\npub fn main() void {}\nfn helper() void {}\n";
+    let entities = extract("main.zig", zig, Language::ZIG);
+    let main = meta(&entities, "main");
+    assert_eq!(main.visibility, Some(Visibility::Public));
+    assert_eq!(main.docstring.as_deref(), Some("Entry point."));
+    assert_eq!(
+        meta(&entities, "helper").visibility,
+        Some(Visibility::Private)
+    );
+}
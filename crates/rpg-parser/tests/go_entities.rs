@@ -111,10 +111,13 @@ type Config struct {
 }
 ";
     let entities = extract_entities(Path::new("test.go"), source, Language::GO);
-    assert_eq!(entities.len(), 1);
+    assert_eq!(entities.len(), 2);
     assert_eq!(entities[0].name, "Config");
     assert_eq!(entities[0].kind, EntityKind::Class);
     assert!(entities[0].parent_class.is_none());
+    assert_eq!(entities[1].name, "Port");
+    assert_eq!(entities[1].kind, EntityKind::Field);
+    assert_eq!(entities[1].parent_class.as_deref(), Some("Config"));
 }
 
 #[test]
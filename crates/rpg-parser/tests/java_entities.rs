@@ -94,3 +94,53 @@ fn java_signature_void_return() {
     assert_eq!(sig.parameters[0].name, "name");
     assert_eq!(sig.return_type.as_deref(), Some("void"));
 }
+
+#[test]
+fn java_overloads_get_distinct_ids() {
+    let source = "\
+public class Foo {
+    public Foo() {}
+    public Foo(int size) {}
+    public Foo(String name) {}
+    public void put(String key) {}
+    public void put(String key, int value) {}
+}";
+    let entities = extract_entities(Path::new("src/Foo.java"), source, Language::JAVA);
+    let ids: Vec<String> = entities.iter().map(|e| e.id()).collect();
+    assert!(ids.contains(&"src/Foo.java:Foo::Foo".to_string()));
+    assert!(ids.contains(&"src/Foo.java:Foo::Foo#1".to_string()));
+    assert!(ids.contains(&"src/Foo.java:Foo::Foo#1.2".to_string()));
+    assert!(ids.contains(&"src/Foo.java:Foo::put".to_string()));
+    assert!(ids.contains(&"src/Foo.java:Foo::put#2".to_string()));
+
+    let unique: std::collections::HashSet<&String> = ids.iter().collect();
+    assert_eq!(unique.len(), ids.len(), "entity IDs must not collide");
+}
+
+#[test]
+fn java_overload_ids_survive_inserting_an_overload_above() {
+    let id_of = |source: &str, line: &str| {
+        let entities = extract_entities(Path::new("src/Foo.java"), source, Language::JAVA);
+        let row = source.lines().position(|l| l.contains(line)).unwrap() + 1;
+        entities
+            .iter()
+            .find(|e| e.line_start == row)
+            .map(rpg_parser::entities::RawEntity::id)
+            .unwrap()
+    };
+    let before = "\
+public class Foo {
+    public void put(String key) {}
+    public void put(String key, int value) {}
+}";
+    let after = "\
+public class Foo {
+    public void put(int index, String key, int value) {}
+    public void put(long key) {}
+    public void put(String key) {}
+    public void put(String key, int value) {}
+}";
+    for line in ["put(String key)", "put(String key, int value)"] {
+        assert_eq!(id_of(before, line), id_of(after, line), "{line}");
+    }
+}
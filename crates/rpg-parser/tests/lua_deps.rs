@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use rpg_parser::deps::extract_deps;
+use rpg_parser::languages::Language;
+
+#[test]
+fn lua_require_import() {
+    let source = "local json = require(\"cjson\")\nrequire \"socket.http\"";
+    let deps = extract_deps(Path::new("net.lua"), source, Language::LUA);
+    assert_eq!(deps.imports.len(), 2);
+    assert_eq!(deps.imports[0].module, "cjson");
+    assert_eq!(deps.imports[1].module, "socket.http");
+}
+
+#[test]
+fn lua_setmetatable_inheritance() {
+    let source = r"local Dog = setmetatable({}, {__index = Animal})
+setmetatable(Puppy, {__index = Dog})";
+    let deps = extract_deps(Path::new("dog.lua"), source, Language::LUA);
+    assert_eq!(deps.inherits.len(), 2);
+    assert_eq!(deps.inherits[0].child_class, "Dog");
+    assert_eq!(deps.inherits[0].parent_class, "Animal");
+    assert_eq!(deps.inherits[1].child_class, "Puppy");
+    assert_eq!(deps.inherits[1].parent_class, "Dog");
+}
+
+#[test]
+fn lua_calls_with_caller() {
+    let source = r"local M = {}
+function M.run(x)
+  validate(x)
+  self:log(x)
+  string.format('%d', x)
+end";
+    let deps = extract_deps(Path::new("m.lua"), source, Language::LUA);
+    let callees: Vec<&str> = deps.calls.iter().map(|c| c.callee.as_str()).collect();
+    assert!(callees.contains(&"validate"));
+    assert!(callees.contains(&"log"));
+    assert!(callees.contains(&"format"));
+    assert!(deps.calls.iter().all(|c| c.caller_entity == "M.run"));
+}
+
+#[test]
+fn lua_require_is_not_a_call() {
+    let source = "local x = require('x')";
+    let deps = extract_deps(Path::new("a.lua"), source, Language::LUA);
+    assert!(deps.calls.is_empty());
+}
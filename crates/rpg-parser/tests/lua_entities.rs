@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+
+#[test]
+fn lua_detected_from_extension() {
+    assert_eq!(Language::from_extension("lua"), Some(Language::LUA));
+}
+
+#[test]
+fn lua_extract_global_and_local_functions() {
+    let source = "function greet(name)\nend\nlocal function helper(x, ...)\nend";
+    let entities = extract_entities(Path::new("util.lua"), source, Language::LUA);
+    assert_eq!(entities.len(), 2);
+
+    let greet = entities.iter().find(|e| e.name == "greet").unwrap();
+    assert_eq!(greet.kind, EntityKind::Function);
+    assert!(greet.parent_class.is_none());
+
+    let helper = entities.iter().find(|e| e.name == "helper").unwrap();
+    let params: Vec<&str> = helper
+        .signature
+        .as_ref()
+        .unwrap()
+        .parameters
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(params, vec!["x", "..."]);
+}
+
+#[test]
+fn lua_extract_module_table_methods() {
+    let source = r"local M = {}
+
+function M.add(a, b)
+  return a + b
+end
+
+function M:reset()
+  self.total = 0
+end
+
+return M";
+    let entities = extract_entities(Path::new("calc.lua"), source, Language::LUA);
+    assert_eq!(entities.len(), 3);
+
+    let module = entities.iter().find(|e| e.name == "M").unwrap();
+    assert_eq!(module.kind, EntityKind::Class);
+
+    let add = entities.iter().find(|e| e.name == "add").unwrap();
+    assert_eq!(add.kind, EntityKind::Method);
+    assert_eq!(add.parent_class.as_deref(), Some("M"));
+
+    let reset = entities.iter().find(|e| e.name == "reset").unwrap();
+    assert_eq!(reset.parent_class.as_deref(), Some("M"));
+    // The implicit `self` of `M:reset` is not a declared parameter
+    assert!(reset.signature.as_ref().unwrap().parameters.is_empty());
+}
+
+#[test]
+fn lua_extract_function_assignment() {
+    let source = "local Account = {}\nAccount.new = function(balance)\n  return balance\nend";
+    let entities = extract_entities(Path::new("account.lua"), source, Language::LUA);
+    let ctor = entities.iter().find(|e| e.name == "new").unwrap();
+    assert_eq!(ctor.kind, EntityKind::Method);
+    assert_eq!(ctor.parent_class.as_deref(), Some("Account"));
+    assert_eq!(ctor.signature.as_ref().unwrap().parameters.len(), 1);
+}
+
+#[test]
+fn lua_plain_table_is_not_a_class() {
+    let source = "local config = { debug = true }";
+    let entities = extract_entities(Path::new("config.lua"), source, Language::LUA);
+    assert!(entities.is_empty());
+}
@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use rpg_parser::deps::extract_deps;
+use rpg_parser::languages::Language;
+
+#[test]
+fn notebook_imports_and_calls() {
+    let source = r#"{"cells": [
+        {"cell_type": "code", "source": ["import numpy as np\n", "from model import train\n"]},
+        {"cell_type": "markdown", "source": "Fit the model"},
+        {"cell_type": "code", "source": ["def fit(x):\n", "    return train(np.asarray(x))\n"]},
+        {"cell_type": "code", "source": "result = fit([1, 2])\n!pip list\nshow(result)"}
+    ], "metadata": {"language_info": {"name": "python"}}}"#;
+    let deps = extract_deps(Path::new("exp/train.ipynb"), source, Language::JUPYTER);
+    let modules: Vec<&str> = deps.imports.iter().map(|i| i.module.as_str()).collect();
+    assert_eq!(modules, vec!["numpy", "model"]);
+
+    let calls: Vec<(&str, &str)> = deps
+        .calls
+        .iter()
+        .map(|c| (c.caller_entity.as_str(), c.callee.as_str()))
+        .collect();
+    assert!(calls.contains(&("fit", "train")));
+    // Top-level cell code calls from the notebook's Module entity
+    assert!(calls.contains(&("train", "fit")));
+    assert!(calls.contains(&("train", "show")));
+    assert!(!calls.iter().any(|(caller, _)| *caller == "<module>"));
+}
@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use rpg_core::graph::{EntityKind, NotebookCell};
+use rpg_parser::entities::{RawEntity, extract_entities};
+use rpg_parser::languages::Language;
+
+const NOTEBOOK: &str = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": ["# Sales analysis\n", "Load and clean the data."]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 1,
+   "metadata": {},
+   "outputs": [],
+   "source": [
+    "%matplotlib inline\n",
+    "import pandas as pd\n",
+    "df = pd.read_csv('sales.csv')"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 2,
+   "metadata": {},
+   "outputs": [],
+   "source": [
+    "def clean(frame):\n",
+    "    \"\"\"Drop incomplete rows.\"\"\"\n",
+    "    return frame.dropna()\n",
+    "\n",
+    "class Report:\n",
+    "    def render(self):\n",
+    "        return 'ok'\n"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 3,
+   "metadata": {},
+   "outputs": [],
+   "source": "summary = clean(df).describe()\nprint(summary)"
+  }
+ ],
+ "metadata": {
+  "kernelspec": {"display_name": "Python 3", "language": "python", "name": "python3"}
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+
+fn find<'a>(entities: &'a [RawEntity], name: &str) -> &'a RawEntity {
+    entities
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("missing entity {}", name))
+}
+
+#[test]
+fn notebook_functions_and_classes() {
+    let entities = extract_entities(Path::new("sales.ipynb"), NOTEBOOK, Language::JUPYTER);
+    let clean = find(&entities, "clean");
+    assert_eq!(clean.kind, EntityKind::Function);
+    assert_eq!(
+        clean.metadata.docstring.as_deref(),
+        Some("Drop incomplete rows.")
+    );
+    let render = find(&entities, "render");
+    assert_eq!(render.kind, EntityKind::Method);
+    assert_eq!(render.parent_class.as_deref(), Some("Report"));
+    assert_eq!(render.id(), "sales.ipynb:Report::render");
+}
+
+#[test]
+fn notebook_entities_record_their_cell() {
+    let entities = extract_entities(Path::new("sales.ipynb"), NOTEBOOK, Language::JUPYTER);
+    assert_eq!(
+        find(&entities, "clean").metadata.cell,
+        Some(NotebookCell { index: 3, line: 1 })
+    );
+    assert_eq!(
+        find(&entities, "Report").metadata.cell,
+        Some(NotebookCell { index: 3, line: 5 })
+    );
+    assert_eq!(
+        find(&entities, "render").metadata.cell,
+        Some(NotebookCell { index: 3, line: 6 })
+    );
+}
+
+#[test]
+fn notebook_lines_refer_to_script() {
+    let entities = extract_entities(Path::new("sales.ipynb"), NOTEBOOK, Language::JUPYTER);
+    let script = rpg_core::notebook::Script::parse(NOTEBOOK).unwrap();
+    let lines: Vec<&str> = script.source.lines().collect();
+    let clean = find(&entities, "clean");
+    assert_eq!(lines[clean.line_start - 1], "def clean(frame):");
+    assert_eq!(lines[clean.line_end - 1], "    return frame.dropna()");
+    // Magics are commented out so the cell still parses as Python
+    assert!(lines.contains(&"# %matplotlib inline"));
+}
+
+#[test]
+fn top_level_code_is_module_entity() {
+    let entities = extract_entities(Path::new("nb/sales.ipynb"), NOTEBOOK, Language::JUPYTER);
+    let module = find(&entities, "sales");
+    assert_eq!(module.kind, EntityKind::Module);
+    assert_eq!(module.id(), "nb/sales.ipynb:sales");
+    assert!(module.source_text.contains("df = pd.read_csv('sales.csv')"));
+    assert!(module.source_text.contains("print(summary)"));
+    assert!(!module.source_text.contains("dropna"));
+    assert_eq!(module.metadata.cell, None);
+}
+
+#[test]
+fn non_python_cells_and_kernels_skipped() {
+    let bash_cell = r#"{"cells": [
+        {"cell_type": "code", "source": "%%bash\nls -la\n"},
+        {"cell_type": "code", "source": "%%time\ndef slow():\n    pass\n"}
+    ], "metadata": {}}"#;
+    let entities = extract_entities(Path::new("a.ipynb"), bash_cell, Language::JUPYTER);
+    let names: Vec<&str> = entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["a", "slow"]);
+
+    let r_kernel = r#"{"cells": [{"cell_type": "code", "source": "f <- function(x) x"}],
+        "metadata": {"kernelspec": {"language": "R", "name": "ir"}}}"#;
+    assert!(extract_entities(Path::new("r.ipynb"), r_kernel, Language::JUPYTER).is_empty());
+    assert!(extract_entities(Path::new("bad.ipynb"), "{not json", Language::JUPYTER).is_empty());
+}
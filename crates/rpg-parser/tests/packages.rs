@@ -0,0 +1,214 @@
+use std::path::{Path, PathBuf};
+
+use rpg_core::graph::{ExternalNode, PackageEcosystem};
+use rpg_parser::languages::Language;
+use rpg_parser::packages::{declared_packages, ecosystem, is_standard_library, provides_module};
+
+fn write(root: &Path, file: &str, content: &str) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn packages(root: &Path, dir: &str) -> Vec<(String, Option<String>)> {
+    declared_packages(root, &root.join(dir))
+        .into_iter()
+        .map(|n| (n.name, n.version))
+        .collect()
+}
+
+fn pkg(name: &str, version: Option<&str>) -> (String, Option<String>) {
+    (name.to_string(), version.map(str::to_string))
+}
+
+fn node(name: &str, ecosystem: PackageEcosystem) -> ExternalNode {
+    ExternalNode {
+        id: ExternalNode::id_for(name),
+        name: name.to_string(),
+        ecosystem,
+        version: None,
+        manifests: Vec::new(),
+    }
+}
+
+#[test]
+fn test_cargo_packages_skip_local_crates() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "crates/app/Cargo.toml",
+        r#"
+[package]
+name = "app"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+anyhow.workspace = true
+core = { path = "../core" }
+
+[dev-dependencies]
+tempfile = "3"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#,
+    );
+
+    let declared = declared_packages(dir.path(), &dir.path().join("crates/app"));
+    assert_eq!(
+        declared.iter().map(|n| n.name.as_str()).collect::<Vec<_>>(),
+        vec!["serde", "tempfile", "libc"]
+    );
+    assert_eq!(declared[0].id, "ext:serde");
+    assert_eq!(declared[0].version.as_deref(), Some("1.0"));
+    assert_eq!(declared[0].ecosystem, PackageEcosystem::Cargo);
+    assert_eq!(
+        declared[0].manifests,
+        vec![PathBuf::from("crates/app/Cargo.toml")]
+    );
+}
+
+#[test]
+fn test_npm_packages_skip_local_packages() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "package.json",
+        r#"{
+  "name": "web",
+  "dependencies": { "react": "^18.2.0", "@acme/ui": "workspace:*" },
+  "devDependencies": { "@types/node": "20.1.0", "shared": "file:../shared" },
+  "peerDependencies": { "lodash": "4.x" }
+}"#,
+    );
+
+    assert_eq!(
+        packages(dir.path(), ""),
+        vec![
+            pkg("react", Some("^18.2.0")),
+            pkg("@types/node", Some("20.1.0")),
+            pkg("lodash", Some("4.x")),
+        ]
+    );
+}
+
+#[test]
+fn test_python_requirements_and_pyproject() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "requirements.txt",
+        "# pinned\nrequests==2.31.0  # http\nuvicorn[standard]>=0.29 ; python_version > \"3.8\"\n\
+         -r dev.txt\n-e .\ngit+https://example.com/pkg.git\nmypkg @ https://example.com/mypkg.whl\n",
+    );
+    write(
+        dir.path(),
+        "pyproject.toml",
+        r#"
+[project]
+dependencies = ["numpy>=1.26", "requests"]
+
+[project.optional-dependencies]
+docs = ["mkdocs"]
+
+[tool.poetry.dependencies]
+python = "^3.11"
+PyYAML = "^6.0"
+shared = { path = "../shared" }
+
+[tool.poetry.group.test.dependencies]
+pytest = { version = "^8" }
+"#,
+    );
+
+    assert_eq!(
+        packages(dir.path(), ""),
+        vec![
+            pkg("requests", Some("==2.31.0")),
+            pkg("uvicorn", Some(">=0.29")),
+            pkg("mypkg", None),
+            pkg("numpy", Some(">=1.26")),
+            pkg("mkdocs", None),
+            pkg("PyYAML", Some("^6.0")),
+            pkg("pytest", Some("^8")),
+        ]
+    );
+}
+
+#[test]
+fn test_go_mod_requirements() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "go.mod",
+        "module example.com/app\n\ngo 1.22\n\nrequire github.com/spf13/cobra v1.8.0\n\n\
+         require (\n\tgolang.org/x/sync v0.7.0\n\tgithub.com/inconshreveable/mousetrap v1.1.0 // indirect\n)\n",
+    );
+
+    assert_eq!(
+        packages(dir.path(), ""),
+        vec![
+            pkg("github.com/spf13/cobra", Some("v1.8.0")),
+            pkg("golang.org/x/sync", Some("v0.7.0")),
+        ]
+    );
+}
+
+#[test]
+fn test_provides_module() {
+    let serde_json = node("serde-json", PackageEcosystem::Cargo);
+    assert!(provides_module(&serde_json, "serde_json::Value"));
+    assert!(provides_module(&serde_json, "::serde_json"));
+    assert!(!provides_module(&serde_json, "serde"));
+
+    let types = node("@types/node", PackageEcosystem::Npm);
+    assert!(provides_module(&types, "'@types/node/fs'"));
+    assert!(!provides_module(&types, "@types/react"));
+    let lodash = node("lodash", PackageEcosystem::Npm);
+    assert!(provides_module(&lodash, "lodash/fp"));
+    assert!(!provides_module(&lodash, "./lodash"));
+
+    let yaml = node("PyYAML", PackageEcosystem::PyPI);
+    assert!(provides_module(&yaml, "yaml"));
+    let pillow = node("Pillow", PackageEcosystem::PyPI);
+    assert!(provides_module(&pillow, "PIL.Image"));
+    let dateutil = node("python-dateutil", PackageEcosystem::PyPI);
+    assert!(provides_module(&dateutil, "dateutil.parser"));
+    let requests = node("requests", PackageEcosystem::PyPI);
+    assert!(provides_module(&requests, "requests.adapters"));
+    assert!(!provides_module(&requests, ".requests"));
+
+    let cobra = node("github.com/spf13/cobra", PackageEcosystem::Go);
+    assert!(provides_module(&cobra, "\"github.com/spf13/cobra/doc\""));
+    assert!(!provides_module(&cobra, "github.com/spf13/cobra-cli"));
+}
+
+#[test]
+fn test_is_standard_library() {
+    assert!(is_standard_library(PackageEcosystem::Cargo, "std::fs"));
+    assert!(!is_standard_library(PackageEcosystem::Cargo, "crate::fs"));
+    assert!(is_standard_library(PackageEcosystem::PyPI, "os.path"));
+    assert!(!is_standard_library(PackageEcosystem::PyPI, "app.os"));
+    assert!(is_standard_library(
+        PackageEcosystem::Npm,
+        "node:fs/promises"
+    ));
+    assert!(is_standard_library(
+        PackageEcosystem::Npm,
+        "'child_process'"
+    ));
+    assert!(!is_standard_library(PackageEcosystem::Npm, "./fs"));
+    assert!(is_standard_library(PackageEcosystem::Go, "\"net/http\""));
+    assert!(!is_standard_library(
+        PackageEcosystem::Go,
+        "example.com/app/strings"
+    ));
+}
+
+#[test]
+fn test_ecosystem_by_language() {
+    assert_eq!(ecosystem(Language::RUST), Some(PackageEcosystem::Cargo));
+    assert_eq!(ecosystem(Language::JUPYTER), Some(PackageEcosystem::PyPI));
+    assert_eq!(ecosystem(Language::VUE), Some(PackageEcosystem::Npm));
+    assert_eq!(ecosystem(Language::JAVA), None);
+}
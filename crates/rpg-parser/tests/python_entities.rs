@@ -225,3 +225,31 @@ fn test_source_text_captured() {
     assert!(entities[0].source_text.contains("def hello()"));
     assert!(entities[0].source_text.contains("return 42"));
 }
+
+#[test]
+fn test_property_accessors_get_distinct_ids() {
+    let source = "\
+class Foo:
+    @property
+    def size(self):
+        return self._size
+
+    @size.setter
+    def size(self, value):
+        self._size = value
+";
+    let entities = rpg_parser::entities::extract_entities(
+        Path::new("src/module.py"),
+        source,
+        rpg_parser::languages::Language::PYTHON,
+    );
+    let ids: Vec<String> = entities
+        .iter()
+        .filter(|e| e.name == "size")
+        .map(|e| e.id())
+        .collect();
+    assert_eq!(
+        ids,
+        vec!["src/module.py:Foo::size", "src/module.py:Foo::size#1"]
+    );
+}
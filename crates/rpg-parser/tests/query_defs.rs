@@ -0,0 +1,114 @@
+//! The query-driven language definition in `tests/fixtures/query_language`,
+//! installed as a runtime def over the builtin Python extractor.
+
+use std::path::Path;
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::deps::extract_deps;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use rpg_parser::languages::user::install_user_languages;
+
+const SOURCE: &str = r"import os
+from collections import OrderedDict, deque
+
+class Stack(Base):
+    def push(self, item: int, limit=10):
+        self.items.append(check(item))
+
+def helper(x: str) -> str:
+    return os.path.join(x)
+";
+
+fn install_fixture() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/query_language");
+    assert!(install_user_languages(&root).is_empty());
+}
+
+#[test]
+fn query_language_entities() {
+    install_fixture();
+    let entities = extract_entities(Path::new("stack.py"), SOURCE, Language::PYTHON);
+    let names: Vec<(&str, EntityKind, Option<&str>)> = entities
+        .iter()
+        .filter(|e| !e.kind.is_data())
+        .map(|e| (e.name.as_str(), e.kind, e.parent_class.as_deref()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("Stack", EntityKind::Class, None),
+            ("push", EntityKind::Method, Some("Stack")),
+            ("helper", EntityKind::Function, None),
+        ]
+    );
+
+    let push = entities.iter().find(|e| e.name == "push").unwrap();
+    assert_eq!((push.line_start, push.line_end), (5, 6));
+    let params: Vec<(&str, Option<&str>)> = push
+        .signature
+        .as_ref()
+        .unwrap()
+        .parameters
+        .iter()
+        .map(|p| (p.name.as_str(), p.type_annotation.as_deref()))
+        .collect();
+    assert_eq!(
+        params,
+        vec![("self", None), ("item", Some("int")), ("limit", None)]
+    );
+
+    let helper = entities.iter().find(|e| e.name == "helper").unwrap();
+    let sig = helper.signature.as_ref().unwrap();
+    assert_eq!(sig.return_type.as_deref(), Some("str"));
+}
+
+#[test]
+fn query_language_deps() {
+    install_fixture();
+    let deps = extract_deps(Path::new("stack.py"), SOURCE, Language::PYTHON);
+
+    let imports: Vec<(&str, Vec<&str>)> = deps
+        .imports
+        .iter()
+        .map(|i| {
+            (
+                i.module.as_str(),
+                i.symbols.iter().map(String::as_str).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        imports,
+        vec![
+            ("os", vec![]),
+            ("collections", vec!["OrderedDict", "deque"])
+        ]
+    );
+
+    let calls: Vec<(&str, &str)> = deps
+        .calls
+        .iter()
+        .map(|c| (c.caller_entity.as_str(), c.callee.as_str()))
+        .collect();
+    assert_eq!(
+        calls,
+        vec![
+            ("Stack.push", "check"),
+            ("Stack.push", "append"),
+            ("helper", "join"),
+        ]
+    );
+
+    let inherits: Vec<(&str, &str)> = deps
+        .inherits
+        .iter()
+        .map(|i| (i.child_class.as_str(), i.parent_class.as_str()))
+        .collect();
+    assert_eq!(inherits, vec![("Stack", "Base")]);
+}
@@ -0,0 +1,361 @@
+use rpg_parser::languages::Language;
+use rpg_parser::receivers::{Receiver, receiver_calls, type_name};
+
+/// `(method, receiver)` of every call, in source order.
+fn typed(source: &str, language: Language) -> Vec<(String, Receiver)> {
+    receiver_calls(source, language)
+        .into_iter()
+        .map(|c| (c.method, c.receiver))
+        .collect()
+}
+
+fn call(method: &str, receiver: Option<&str>) -> (String, Receiver) {
+    let receiver = receiver.map_or(Receiver::None, |r| Receiver::Typed(r.to_string()));
+    (method.to_string(), receiver)
+}
+
+fn unknown(method: &str) -> (String, Receiver) {
+    (method.to_string(), Receiver::Unknown)
+}
+
+#[test]
+fn test_java_receivers_from_params_locals_and_fields() {
+    let source = r"class Service extends Base {
+    private Repo repo;
+
+    void run(Cache cache, List<User> users) {
+        var dao = new Dao();
+        cache.get();
+        this.repo.save();
+        repo.save();
+        dao.load();
+        for (User u : users) {
+            u.greet();
+        }
+        Strings.join();
+        helper();
+        lookup().call();
+    }
+}
+";
+    assert_eq!(
+        typed(source, Language::JAVA),
+        vec![
+            call("get", Some("Cache")),
+            call("save", Some("Repo")),
+            call("save", Some("Repo")),
+            call("load", Some("Dao")),
+            call("greet", Some("User")),
+            call("join", Some("Strings")),
+            call("helper", None),
+            unknown("call"),
+            call("lookup", None),
+        ]
+    );
+}
+
+#[test]
+fn test_java_locals_shadow_fields_and_generics_are_untyped() {
+    let source = r"class Box<T> {
+    private Repo repo;
+
+    void run(T item) {
+        Object repo = make();
+        repo.save();
+        item.save();
+    }
+}
+";
+    assert_eq!(
+        typed(source, Language::JAVA),
+        vec![
+            call("make", None),
+            call("save", Some("Object")),
+            unknown("save"),
+        ]
+    );
+}
+
+#[test]
+fn test_csharp_receivers() {
+    let source = r"class Service {
+    private Repo repo;
+    public Cache Cache { get; set; }
+
+    void Run(Store store) {
+        var dao = new Dao();
+        store.Save();
+        this.repo.Find();
+        Cache.Get();
+        dao.Load();
+    }
+}
+";
+    assert_eq!(
+        typed(source, Language::CSHARP),
+        vec![
+            call("Save", Some("Store")),
+            call("Find", Some("Repo")),
+            call("Get", Some("Cache")),
+            call("Load", Some("Dao")),
+        ]
+    );
+}
+
+#[test]
+fn test_go_receivers() {
+    let source = r"package main
+
+type Service struct {
+	repo *store.Repo
+}
+
+func (s *Service) Run(cache Cache) {
+	var c Cache
+	d := &Dao{}
+	e := new(Dao)
+	cache.Get()
+	s.repo.Save()
+	c.Get()
+	d.Load()
+	e.Load()
+	fmt.Println()
+}
+";
+    assert_eq!(
+        typed(source, Language::GO),
+        vec![
+            call("new", None),
+            call("Get", Some("Cache")),
+            call("Save", Some("Repo")),
+            call("Get", Some("Cache")),
+            call("Load", Some("Dao")),
+            call("Load", Some("Dao")),
+            call("Println", None),
+        ]
+    );
+}
+
+#[test]
+fn test_rust_receivers() {
+    let source = r"struct Service {
+    repo: Box<Repo>,
+}
+
+impl<T> Runner for Service<T> {
+    fn run(&self, cache: &mut Cache, item: T) {
+        let dao = Dao::new();
+        let other: Dao = make();
+        cache.get();
+        self.repo.save();
+        dao.load();
+        other.load();
+        item.load();
+        Self::helper();
+        utils::helper();
+    }
+}
+";
+    assert_eq!(
+        typed(source, Language::RUST),
+        vec![
+            call("new", Some("Dao")),
+            call("make", None),
+            call("get", Some("Cache")),
+            call("save", Some("Repo")),
+            call("load", Some("Dao")),
+            call("load", Some("Dao")),
+            unknown("load"),
+            call("helper", Some("Service")),
+            call("helper", None),
+        ]
+    );
+}
+
+#[test]
+fn test_kotlin_receivers() {
+    let source = r"class Service(private val repo: Repo) {
+    private val cache = Cache()
+
+    fun run(store: Store?) {
+        val dao = Dao()
+        store.save()
+        this.repo.find()
+        cache.get()
+        dao.load()
+    }
+}
+";
+    assert_eq!(
+        typed(source, Language::KOTLIN),
+        vec![
+            call("Cache", None),
+            call("Dao", None),
+            call("save", Some("Store")),
+            call("find", Some("Repo")),
+            call("get", Some("Cache")),
+            call("load", Some("Dao")),
+        ]
+    );
+}
+
+#[test]
+fn test_typescript_receivers() {
+    let source = r"class Service {
+  private cache: Cache | null;
+
+  constructor(private repo: Repo) {}
+
+  run(store: Store): void {
+    const dao = new Dao();
+    store.save();
+    this.repo.find();
+    this.cache.get();
+    dao.load();
+  }
+}
+";
+    assert_eq!(
+        typed(source, Language::TYPESCRIPT),
+        vec![
+            call("save", Some("Store")),
+            call("find", Some("Repo")),
+            call("get", Some("Cache")),
+            call("load", Some("Dao")),
+        ]
+    );
+}
+
+#[test]
+fn test_swift_receivers() {
+    let source = r"class Service {
+    var repo: Repo
+    let cache = Cache()
+
+    func run(store: Store) {
+        let dao = Dao()
+        store.save()
+        self.repo.find()
+        cache.get()
+        dao.load()
+    }
+}
+";
+    assert_eq!(
+        typed(source, Language::SWIFT),
+        vec![
+            call("Cache", None),
+            call("Dao", None),
+            call("save", Some("Store")),
+            call("find", Some("Repo")),
+            call("get", Some("Cache")),
+            call("load", Some("Dao")),
+        ]
+    );
+}
+
+#[test]
+fn test_untyped_languages_record_name_paths() {
+    let modules = |source: &str, language: Language| -> Vec<(String, Option<String>)> {
+        receiver_calls(source, language)
+            .into_iter()
+            .inspect(|c| assert_eq!(c.receiver, Receiver::None))
+            .map(|c| (c.method, c.module))
+            .collect()
+    };
+    let path =
+        |method: &str, module: Option<&str>| (method.to_string(), module.map(str::to_string));
+    assert_eq!(
+        modules(
+            "def f(x):\n    x.save()\n    os.path.join()\n    load().save()\n    run()\n",
+            Language::PYTHON
+        ),
+        vec![
+            path("save", Some("x")),
+            path("join", Some("os.path")),
+            path("save", None),
+            path("load", None),
+            path("run", None),
+        ]
+    );
+    assert_eq!(
+        modules(
+            "fs.promises.readFile(p);\nthis.save();\n",
+            Language::JAVASCRIPT
+        ),
+        vec![path("readFile", Some("fs.promises")), path("save", None)]
+    );
+    assert!(receiver_calls("def f(x)\n  x.save\nend\n", Language::RUBY).is_empty());
+}
+
+#[test]
+fn test_type_name() {
+    assert_eq!(type_name("&'a mut repo::Repo<T>").as_deref(), Some("Repo"));
+    assert_eq!(type_name("Arc<dyn Store>").as_deref(), Some("Store"));
+    assert_eq!(type_name("*pkg.Repo").as_deref(), Some("Repo"));
+    assert_eq!(type_name("Repo?").as_deref(), Some("Repo"));
+    assert_eq!(type_name(": Repo | undefined").as_deref(), Some("Repo"));
+    assert_eq!(type_name("List<User>").as_deref(), Some("List"));
+    assert_eq!(type_name("User[]"), None);
+    assert_eq!(type_name("[]User"), None);
+    assert_eq!(type_name("map[string]User"), None);
+    assert_eq!(type_name("Repo | Cache"), None);
+    assert_eq!(type_name("(i32, i32)"), None);
+}
+
+#[test]
+fn test_call_argument_counts() {
+    let counts = |source: &str, language: Language| -> Vec<(String, Option<usize>)> {
+        receiver_calls(source, language)
+            .into_iter()
+            .map(|c| (c.method, c.args))
+            .collect()
+    };
+    let arg = |method: &str, args: Option<usize>| (method.to_string(), args);
+    assert_eq!(
+        counts(
+            "class A { void f(Store s) { s.put(\"a\"); s.put(\"a\", 1); s.clear(); } }",
+            Language::JAVA
+        ),
+        vec![
+            arg("put", Some(1)),
+            arg("put", Some(2)),
+            arg("clear", Some(0))
+        ]
+    );
+    assert_eq!(
+        counts(
+            "class A { void F(Store s) { s.Put(\"a\", 1); } }",
+            Language::CSHARP
+        ),
+        vec![arg("Put", Some(2))]
+    );
+    assert_eq!(
+        counts(
+            "fun f(s: Store) {\n    s.put(\"a\", 1)\n    s.each(1) { it }\n}\n",
+            Language::KOTLIN
+        ),
+        vec![arg("put", Some(2)), arg("each", Some(2))]
+    );
+    assert_eq!(
+        counts(
+            "func f(s: Store) {\n    s.put(\"a\", at: 1)\n    s.each { $0 }\n}\n",
+            Language::SWIFT
+        ),
+        vec![arg("put", Some(2)), arg("each", Some(1))]
+    );
+    assert_eq!(
+        counts(
+            "function f(s: Store) { s.put(...xs); s.put(1); }",
+            Language::TYPESCRIPT
+        ),
+        vec![arg("put", None), arg("put", Some(1))]
+    );
+    assert_eq!(
+        counts(
+            "def f():\n    run(1, key=2)\n    run(*xs)\n",
+            Language::PYTHON
+        ),
+        vec![arg("run", Some(2)), arg("run", None)]
+    );
+}
@@ -78,6 +78,21 @@ fn main() {
     assert!(deps.calls.iter().any(|c| c.callee == "read_to_string"));
 }
 
+#[test]
+fn test_method_call_callee_is_method_name() {
+    let source = "\
+fn main() {
+    self.repo
+        .save();
+    items.iter().collect::<Vec<_>>();
+    parse::<u32>();
+}
+";
+    let deps = extract_rust_deps(Path::new("test.rs"), source);
+    let callees: Vec<&str> = deps.calls.iter().map(|c| c.callee.as_str()).collect();
+    assert_eq!(callees, vec!["save", "collect", "iter", "parse"]);
+}
+
 #[test]
 fn test_empty_file() {
     let source = "";
@@ -0,0 +1,227 @@
+use rpg_core::graph::EntityKind;
+use rpg_parser::deps::extract_deps;
+use rpg_parser::entities::RawEntity;
+use rpg_parser::languages::Language;
+use rpg_parser::paradigms::defs::load_builtin_defs;
+use rpg_parser::paradigms::query_engine::QueryCache;
+use std::path::{Path, PathBuf};
+
+/// Parse one file with every built-in paradigm active.
+fn parse(file: &str, source: &str) -> Vec<RawEntity> {
+    let defs = load_builtin_defs().unwrap();
+    let qcache = QueryCache::compile_all(&defs).unwrap();
+    let active: Vec<&_> = defs.iter().collect();
+    rpg_parser::parse_files_with_paradigms(
+        vec![(PathBuf::from(file), source.to_string())],
+        &active,
+        &qcache,
+    )
+}
+
+fn kind_of(entities: &[RawEntity], name: &str) -> EntityKind {
+    entities
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no entity named {name}: {entities:?}"))
+        .kind
+}
+
+/// (caller, callee) pairs of the calls extracted from `source`.
+fn calls(file: &str, source: &str, language: Language) -> Vec<(String, String)> {
+    extract_deps(Path::new(file), source, language)
+        .calls
+        .into_iter()
+        .map(|c| (c.caller_entity, c.callee))
+        .collect()
+}
+
+#[test]
+fn test_rust_test_attributes() {
+    let source = r#"
+fn parse(input: &str) -> usize { input.len() }
+
+#[cfg(test)]
+mod tests {
+    fn fixture() -> &'static str { "x" }
+
+    #[test]
+    fn parses_input() { assert_eq!(super::parse(fixture()), 1); }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn parses_async() {}
+}
+"#;
+    let entities = parse("src/parser.rs", source);
+    assert_eq!(kind_of(&entities, "parses_input"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "parses_async"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "fixture"), EntityKind::Function);
+    assert_eq!(kind_of(&entities, "parse"), EntityKind::Function);
+}
+
+#[test]
+fn test_pytest_and_unittest() {
+    let source = r"
+def make_user():
+    return User()
+
+def test_login():
+    assert login(make_user())
+
+class TestAuth:
+    def test_logout(self):
+        assert logout(make_user())
+
+    def helper(self):
+        pass
+";
+    let entities = parse("tests/test_auth.py", source);
+    assert_eq!(kind_of(&entities, "test_login"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "TestAuth"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "test_logout"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "helper"), EntityKind::Method);
+    assert_eq!(kind_of(&entities, "make_user"), EntityKind::Function);
+
+    // Outside a test module, test_ names are ordinary code
+    let entities = parse("app/db.py", "def test_connection():\n    pass\n");
+    assert_eq!(kind_of(&entities, "test_connection"), EntityKind::Function);
+}
+
+#[test]
+fn test_go_testing() {
+    let source = r#"
+package parser
+
+import "testing"
+
+func TestParse(t *testing.T) { Parse("x") }
+func BenchmarkParse(b *testing.B) {}
+func Testify() {}
+func newFixture() string { return "x" }
+"#;
+    let entities = parse("parser_test.go", source);
+    assert_eq!(kind_of(&entities, "TestParse"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "BenchmarkParse"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "Testify"), EntityKind::Function);
+    assert_eq!(kind_of(&entities, "newFixture"), EntityKind::Function);
+
+    let entities = parse(
+        "parser.go",
+        "package parser\n\nfunc TestMode() bool { return true }\n",
+    );
+    assert_eq!(kind_of(&entities, "TestMode"), EntityKind::Function);
+}
+
+#[test]
+fn test_junit_methods() {
+    let source = r"
+class UserServiceTest {
+    @BeforeEach
+    void setUp() {}
+
+    @Test
+    void createsUser() { service.create(); }
+
+    @ParameterizedTest
+    void validatesEmail(String email) {}
+}
+";
+    let entities = parse("src/test/java/UserServiceTest.java", source);
+    assert_eq!(kind_of(&entities, "UserServiceTest"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "createsUser"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "validatesEmail"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "setUp"), EntityKind::Method);
+}
+
+#[test]
+fn test_xunit_and_nunit() {
+    let source = r"
+public class CalculatorTests {
+    [Fact]
+    public void Adds() { new Calculator().Add(1, 2); }
+
+    [Theory, InlineData(1)]
+    public void Negates(int x) {}
+
+    private Calculator Make() { return new Calculator(); }
+}
+
+[TestFixture]
+public class ParserFixture {
+    [Test]
+    public void Parses() {}
+}
+";
+    let entities = parse("CalculatorTests.cs", source);
+    assert_eq!(kind_of(&entities, "CalculatorTests"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "Adds"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "Negates"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "Make"), EntityKind::Method);
+    assert_eq!(kind_of(&entities, "ParserFixture"), EntityKind::Test);
+    assert_eq!(kind_of(&entities, "Parses"), EntityKind::Test);
+}
+
+#[test]
+fn test_jest_blocks() {
+    let source = r#"import { verify } from "./token";
+
+describe("verify", () => {
+  beforeEach(() => {
+    resetClock();
+  });
+
+  it("rejects expired tokens", () => {
+    expect(verify(expired)).toBe(false);
+  });
+
+  test.skip("accepts fresh tokens", function () {
+    verify(fresh);
+  });
+});
+"#;
+    let entities = parse("src/token.test.ts", source);
+    let suite = entities.iter().find(|e| e.name == "verify").unwrap();
+    assert_eq!(suite.kind, EntityKind::Test);
+    assert_eq!((suite.line_start, suite.line_end), (3, 15));
+    let case = entities
+        .iter()
+        .find(|e| e.name == "rejects expired tokens")
+        .unwrap();
+    assert_eq!(case.kind, EntityKind::Test);
+    assert_eq!((case.line_start, case.line_end), (8, 10));
+    assert_eq!(kind_of(&entities, "accepts fresh tokens"), EntityKind::Test);
+
+    // Calls inside a block belong to the innermost block
+    let calls = calls("src/token.test.ts", source, Language::TYPESCRIPT);
+    assert!(calls.contains(&("rejects expired tokens".into(), "verify".into())));
+    assert!(calls.contains(&("accepts fresh tokens".into(), "verify".into())));
+    assert!(calls.contains(&("verify".into(), "resetClock".into())));
+}
+
+#[test]
+fn test_rspec_blocks() {
+    let source = r#"RSpec.describe User do
+  before do
+    stub_mailer(:welcome)
+  end
+
+  context "when active" do
+    it "sends a welcome email" do
+      User.new.activate!
+    end
+  end
+end
+"#;
+    let entities = parse("spec/user_spec.rb", source);
+    let group = entities.iter().find(|e| e.name == "User").unwrap();
+    assert_eq!(group.kind, EntityKind::Test);
+    assert_eq!((group.line_start, group.line_end), (1, 11));
+    assert_eq!(kind_of(&entities, "when active"), EntityKind::Test);
+    assert_eq!(
+        kind_of(&entities, "sends a welcome email"),
+        EntityKind::Test
+    );
+
+    let calls = calls("spec/user_spec.rb", source, Language::RUBY);
+    assert!(calls.contains(&("sends a welcome email".into(), "activate!".into())));
+    assert!(calls.contains(&("User".into(), "stub_mailer".into())));
+}
@@ -26,6 +26,7 @@ fn extract_entities_with_paradigms(
         language,
         &mut entities,
     );
+    rpg_parser::entities::disambiguate_ids(&mut entities);
     entities
 }
 
@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use rpg_parser::languages::user::{install_user_languages, load_user_languages};
+use rpg_parser::paradigms::defs::{load_builtin_defs, load_defs, load_defs_or_builtin};
+use rpg_parser::user_defs::{DefScope, UserDefsReport};
+
+const ACME_RPC: &str = r#"
+schema_version = 1
+name = "acme-rpc"
+priority = 5
+languages = ["go"]
+
+[detect]
+deps = ["acme.dev/rpc"]
+
+[[classify]]
+id = "acme.handler"
+action = { reclassify = "controller" }
+[classify.match]
+kind = "function"
+source_contains_any = ["rpc.Request"]
+"#;
+
+fn write_def(root: &Path, subdir: &str, file: &str, content: &str) {
+    let dir = root.join(".rpg").join(subdir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(file), content).unwrap();
+}
+
+#[test]
+fn project_paradigm_merged_by_priority() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_def(tmp.path(), "paradigms", "acme.toml", ACME_RPC);
+
+    let builtin = load_builtin_defs().unwrap();
+    let defs = load_defs(tmp.path()).unwrap();
+    assert_eq!(defs.len(), builtin.len() + 1);
+    let pos = defs.iter().position(|d| d.name == "acme-rpc").unwrap();
+    assert!(defs[..pos].iter().all(|d| d.priority <= 5));
+    assert!(defs[pos + 1..].iter().all(|d| d.priority >= 5));
+
+    let report = UserDefsReport::collect(tmp.path());
+    assert_eq!(report.paradigms.len(), 1);
+    assert_eq!(report.paradigms[0].0, "acme-rpc");
+    assert_eq!(report.paradigms[0].1.scope, DefScope::Project);
+    assert!(report.errors.is_empty());
+    assert!(
+        report
+            .to_string()
+            .starts_with("  paradigm acme-rpc (project: ")
+    );
+}
+
+#[test]
+fn project_paradigm_replaces_builtin_with_same_name() {
+    let tmp = tempfile::tempdir().unwrap();
+    let gin = ACME_RPC
+        .replace("acme-rpc", "gin")
+        .replace("acme.handler", "gin.rpc_handler");
+    write_def(tmp.path(), "paradigms", "gin.toml", &gin);
+
+    let defs = load_defs(tmp.path()).unwrap();
+    assert_eq!(defs.len(), load_builtin_defs().unwrap().len());
+    let gin = defs.iter().find(|d| d.name == "gin").unwrap();
+    assert_eq!(gin.priority, 5);
+    assert_eq!(gin.classify.len(), 1);
+}
+
+#[test]
+fn invalid_paradigm_is_rejected() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_def(
+        tmp.path(),
+        "paradigms",
+        "bad.toml",
+        &ACME_RPC.replace("controller", "widget"),
+    );
+    write_def(tmp.path(), "paradigms", "broken.toml", "name = ");
+
+    let errs = load_defs(tmp.path()).unwrap_err();
+    assert_eq!(errs.len(), 2);
+    assert!(errs[0].to_string().contains("unknown entity_kind 'widget'"));
+    assert!(errs[1].to_string().contains("broken.toml"));
+
+    // Lenient loading keeps the builtins
+    let defs = load_defs_or_builtin(tmp.path());
+    assert_eq!(defs.len(), load_builtin_defs().unwrap().len());
+    assert_eq!(UserDefsReport::collect(tmp.path()).errors.len(), 2);
+}
+
+#[test]
+fn runtime_language_definitions() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_def(
+        tmp.path(),
+        "languages",
+        "go.toml",
+        r#"
+schema_version = 1
+name = "go"
+extensions = [".gox"]
+
+[[queries.entities]]
+kind = "function"
+query = "(const_spec name: (identifier) @name) @definition"
+"#,
+    );
+    write_def(
+        tmp.path(),
+        "languages",
+        "klingon.toml",
+        "schema_version = 1\nname = \"klingon\"\n",
+    );
+    write_def(
+        tmp.path(),
+        "languages",
+        "python.toml",
+        "schema_version = 1\nname = \"python\"\nextensions = [\"rs\"]\n",
+    );
+
+    let (loaded, errors) = load_user_languages(tmp.path());
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].contains("unknown language 'klingon'"));
+    assert!(errors[1].contains("extension 'rs' already belongs to rust"));
+
+    assert_eq!(Language::from_extension("gox"), None);
+    assert_eq!(install_user_languages(tmp.path()).len(), 2);
+    assert_eq!(Language::from_extension("gox"), Some(Language::GO));
+
+    // The runtime queries replace the builtin extractor: constants are functions now
+    let source = "package m\n\nconst pi = 3.14\n\nfunc add(x, y int) int { return x + y }\n";
+    let entities = extract_entities(Path::new("m.gox"), source, Language::GO);
+    let names: Vec<&str> = entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["pi"]);
+    assert!(entities.iter().all(|e| e.kind == EntityKind::Function));
+
+    // Reinstalling from a project without definitions removes them
+    let empty = tempfile::tempdir().unwrap();
+    assert!(install_user_languages(empty.path()).is_empty());
+    assert_eq!(Language::from_extension("gox"), None);
+}
@@ -0,0 +1,494 @@
+use rpg_core::graph::EntityKind;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use rpg_parser::variables::{extract_variables, state_accesses};
+use std::path::Path;
+
+/// `(kind, parent, name)` of every declaration, in source order.
+fn declared(
+    path: &str,
+    source: &str,
+    language: Language,
+) -> Vec<(EntityKind, Option<String>, String)> {
+    extract_variables(Path::new(path), source, language)
+        .into_iter()
+        .map(|e| (e.kind, e.parent_class, e.name))
+        .collect()
+}
+
+fn decl(
+    kind: EntityKind,
+    parent: Option<&str>,
+    name: &str,
+) -> (EntityKind, Option<String>, String) {
+    (kind, parent.map(str::to_string), name.to_string())
+}
+
+/// `(line, "R" | "W", name)` of every access, in source order.
+fn accessed(source: &str, language: Language) -> Vec<(usize, &'static str, String)> {
+    state_accesses(source, language)
+        .into_iter()
+        .map(|a| (a.line, if a.write { "W" } else { "R" }, a.name))
+        .collect()
+}
+
+fn read(line: usize, name: &str) -> (usize, &'static str, String) {
+    (line, "R", name.to_string())
+}
+
+fn write(line: usize, name: &str) -> (usize, &'static str, String) {
+    (line, "W", name.to_string())
+}
+
+#[test]
+fn test_python_declarations() {
+    let source = r"MAX_SIZE = 10
+counter: int = 0
+__all__ = ['Service']
+
+class Service:
+    limit = 5
+    name: str
+
+    def __init__(self, repo):
+        self.repo = repo
+        self._cache = {}
+
+class Color(enum.Enum):
+    RED = 1
+";
+    assert_eq!(
+        declared("a.py", source, Language::PYTHON),
+        vec![
+            decl(EntityKind::Constant, None, "MAX_SIZE"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::Field, Some("Service"), "limit"),
+            decl(EntityKind::Field, Some("Service"), "name"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+            decl(EntityKind::Field, Some("Service"), "_cache"),
+            decl(EntityKind::EnumVariant, Some("Color"), "RED"),
+        ]
+    );
+}
+
+#[test]
+fn test_python_accesses_respect_locals_and_global_statements() {
+    let source = r"def run(self, x):
+    global counter
+    counter += 1
+    self.count = self.count + MAX_SIZE
+    limit = Service.limit
+    total = 0
+    for item in items:
+        total += item
+    return [z for z in limit]
+";
+    assert_eq!(
+        accessed(source, Language::PYTHON),
+        vec![
+            read(3, "counter"),
+            write(3, "counter"),
+            read(4, "count"),
+            read(4, "MAX_SIZE"),
+            write(4, "count"),
+            read(5, "limit"),
+            read(5, "Service"),
+            read(7, "items"),
+        ]
+    );
+}
+
+#[test]
+fn test_rust_declarations_and_accesses() {
+    let source = r"const MAX: usize = 10;
+static mut COUNTER: u32 = 0;
+struct Service { repo: Repo, count: u32 }
+enum Color { Red, Green(u8) }
+impl Service {
+    const LIMIT: u32 = 5;
+    fn run(&mut self) {
+        self.count += 1;
+        let y = MAX + Self::LIMIT;
+        unsafe { COUNTER = y; }
+        self.repo.save();
+    }
+}
+";
+    assert_eq!(
+        declared("a.rs", source, Language::RUST),
+        vec![
+            decl(EntityKind::Constant, None, "MAX"),
+            decl(EntityKind::Global, None, "COUNTER"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+            decl(EntityKind::Field, Some("Service"), "count"),
+            decl(EntityKind::EnumVariant, Some("Color"), "Red"),
+            decl(EntityKind::EnumVariant, Some("Color"), "Green"),
+            decl(EntityKind::Constant, Some("Service"), "LIMIT"),
+        ]
+    );
+    assert_eq!(
+        accessed(source, Language::RUST),
+        vec![
+            read(8, "count"),
+            write(8, "count"),
+            read(9, "MAX"),
+            read(9, "LIMIT"),
+            write(10, "COUNTER"),
+            read(11, "repo"),
+        ]
+    );
+}
+
+#[test]
+fn test_typescript_skips_bound_functions_and_keeps_parameter_properties() {
+    let source = r"export const MAX = 10;
+let counter = 0;
+const handler = () => 1;
+enum Color { Red, Green = 2 }
+class Service {
+  static readonly LIMIT = 5;
+  count = 0;
+  onClick = () => this.count;
+  constructor(private store: Store, name: string) {
+    this.label = name;
+  }
+}
+";
+    assert_eq!(
+        declared("a.ts", source, Language::TYPESCRIPT),
+        vec![
+            decl(EntityKind::Constant, None, "MAX"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::EnumVariant, Some("Color"), "Red"),
+            decl(EntityKind::EnumVariant, Some("Color"), "Green"),
+            decl(EntityKind::Constant, Some("Service"), "LIMIT"),
+            decl(EntityKind::Field, Some("Service"), "count"),
+            decl(EntityKind::Field, Some("Service"), "store"),
+            decl(EntityKind::Field, Some("Service"), "label"),
+        ]
+    );
+}
+
+#[test]
+fn test_javascript_accesses() {
+    let source = r"function run(x) {
+  counter++;
+  this.count += MAX;
+  const y = Service.LIMIT;
+  legacy = y;
+  cache[x] = y;
+}
+";
+    assert_eq!(
+        accessed(source, Language::JAVASCRIPT),
+        vec![
+            read(2, "counter"),
+            write(2, "counter"),
+            read(3, "MAX"),
+            read(3, "count"),
+            write(3, "count"),
+            read(4, "LIMIT"),
+            read(4, "Service"),
+            write(5, "legacy"),
+            write(6, "cache"),
+        ]
+    );
+}
+
+#[test]
+fn test_go_declarations_and_accesses() {
+    let source = r"package main
+
+const (
+	A = iota
+	B
+)
+var counter int
+
+type Service struct {
+	repo  *Repo
+	Count int
+}
+
+func (s *Service) Run(n int) {
+	counter++
+	s.Count += A
+	s.repo.Save()
+}
+";
+    assert_eq!(
+        declared("a.go", source, Language::GO),
+        vec![
+            decl(EntityKind::Constant, None, "A"),
+            decl(EntityKind::Constant, None, "B"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+            decl(EntityKind::Field, Some("Service"), "Count"),
+        ]
+    );
+    assert_eq!(
+        accessed(source, Language::GO),
+        vec![
+            read(15, "counter"),
+            write(15, "counter"),
+            read(16, "A"),
+            read(16, "Count"),
+            write(16, "Count"),
+            read(17, "repo"),
+        ]
+    );
+}
+
+#[test]
+fn test_java_and_csharp_constants_fields_and_enum_members() {
+    let java = r"class Service {
+    static final int MAX = 10;
+    private Repo repo;
+    int a, b = 2;
+}
+enum Color { RED, GREEN(2); int v; }
+interface Limits { int LIMIT = 5; }
+";
+    assert_eq!(
+        declared("A.java", java, Language::JAVA),
+        vec![
+            decl(EntityKind::Constant, Some("Service"), "MAX"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+            decl(EntityKind::Field, Some("Service"), "a"),
+            decl(EntityKind::Field, Some("Service"), "b"),
+            decl(EntityKind::EnumVariant, Some("Color"), "RED"),
+            decl(EntityKind::EnumVariant, Some("Color"), "GREEN"),
+            decl(EntityKind::Field, Some("Color"), "v"),
+            decl(EntityKind::Constant, Some("Limits"), "LIMIT"),
+        ]
+    );
+
+    let csharp = r"class Service {
+    const int Max = 10;
+    static readonly int Limit = 5;
+    private Repo repo;
+    public int Count { get; set; }
+    void Run() {
+        this.repo.Save();
+        Count = Max;
+    }
+}
+enum Color { Red, Green = 2 }
+";
+    assert_eq!(
+        declared("a.cs", csharp, Language::CSHARP),
+        vec![
+            decl(EntityKind::Constant, Some("Service"), "Max"),
+            decl(EntityKind::Constant, Some("Service"), "Limit"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+            decl(EntityKind::Field, Some("Service"), "Count"),
+            decl(EntityKind::EnumVariant, Some("Color"), "Red"),
+            decl(EntityKind::EnumVariant, Some("Color"), "Green"),
+        ]
+    );
+    assert_eq!(
+        accessed(csharp, Language::CSHARP),
+        vec![read(7, "repo"), read(8, "Max"), write(8, "Count")]
+    );
+}
+
+#[test]
+fn test_kotlin_and_swift_properties() {
+    let kotlin = r"const val MAX = 10
+var counter = 0
+class Service(private val repo: Repo, name: String) {
+    val limit = 5
+    fun run() {
+        counter++
+        repo.save()
+    }
+}
+enum class Color { RED, GREEN }
+";
+    assert_eq!(
+        declared("a.kt", kotlin, Language::KOTLIN),
+        vec![
+            decl(EntityKind::Constant, None, "MAX"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+            decl(EntityKind::Field, Some("Service"), "limit"),
+            decl(EntityKind::EnumVariant, Some("Color"), "RED"),
+            decl(EntityKind::EnumVariant, Some("Color"), "GREEN"),
+        ]
+    );
+    assert_eq!(
+        accessed(kotlin, Language::KOTLIN),
+        vec![read(6, "counter"), write(6, "counter"), read(7, "repo")]
+    );
+
+    let swift = r"let maxSize = 10
+var counter = 0
+class Service {
+    static let limit = 5
+    var count = 0
+    func run() {
+        counter += 1
+        self.count = maxSize
+    }
+}
+enum Color { case red, green }
+";
+    assert_eq!(
+        declared("a.swift", swift, Language::SWIFT),
+        vec![
+            decl(EntityKind::Constant, None, "maxSize"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::Constant, Some("Service"), "limit"),
+            decl(EntityKind::Field, Some("Service"), "count"),
+            decl(EntityKind::EnumVariant, Some("Color"), "red"),
+            decl(EntityKind::EnumVariant, Some("Color"), "green"),
+        ]
+    );
+    assert_eq!(
+        accessed(swift, Language::SWIFT),
+        vec![
+            read(7, "counter"),
+            write(7, "counter"),
+            read(8, "maxSize"),
+            write(8, "count"),
+        ]
+    );
+}
+
+#[test]
+fn test_c_skips_extern_and_function_declarations() {
+    let source = r"#define MAX_SIZE 10
+static const int limit = 5;
+int counter = 0;
+extern int shared;
+int helper(int);
+typedef struct { int x; } point;
+enum color { RED, GREEN };
+void run(point *p) {
+    counter++;
+    p->x = MAX_SIZE + limit;
+}
+";
+    assert_eq!(
+        declared("a.c", source, Language::C),
+        vec![
+            decl(EntityKind::Constant, None, "MAX_SIZE"),
+            decl(EntityKind::Constant, None, "limit"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::Field, Some("point"), "x"),
+            decl(EntityKind::EnumVariant, Some("color"), "RED"),
+            decl(EntityKind::EnumVariant, Some("color"), "GREEN"),
+        ]
+    );
+    assert_eq!(
+        accessed(source, Language::C),
+        vec![
+            read(9, "counter"),
+            write(9, "counter"),
+            read(10, "MAX_SIZE"),
+            read(10, "limit"),
+            write(10, "x"),
+        ]
+    );
+}
+
+#[test]
+fn test_php_functions_see_only_declared_globals() {
+    let source = r"<?php
+const MAX = 10;
+$counter = 0;
+class Service {
+    const LIMIT = 5;
+    private $repo;
+    function run($x) {
+        global $counter;
+        $counter++;
+        $x = $this->repo;
+        return self::LIMIT + MAX;
+    }
+}
+";
+    assert_eq!(
+        declared("a.php", source, Language::PHP),
+        vec![
+            decl(EntityKind::Constant, None, "MAX"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::Constant, Some("Service"), "LIMIT"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+        ]
+    );
+    assert_eq!(
+        accessed(source, Language::PHP),
+        vec![
+            read(9, "counter"),
+            write(9, "counter"),
+            read(10, "repo"),
+            read(11, "LIMIT"),
+            read(11, "MAX"),
+        ]
+    );
+}
+
+#[test]
+fn test_ruby_instance_variables_and_accessors() {
+    let source = r"MAX = 10
+$counter = 0
+class Service
+  attr_reader :repo
+  def initialize(repo)
+    @repo = repo
+    @count = 0
+  end
+  def run
+    $counter += 1
+    @count += MAX
+  end
+end
+";
+    assert_eq!(
+        declared("a.rb", source, Language::RUBY),
+        vec![
+            decl(EntityKind::Constant, None, "MAX"),
+            decl(EntityKind::Global, None, "counter"),
+            decl(EntityKind::Field, Some("Service"), "repo"),
+            decl(EntityKind::Field, Some("Service"), "count"),
+        ]
+    );
+    assert_eq!(
+        accessed(source, Language::RUBY),
+        vec![
+            write(6, "repo"),
+            write(7, "count"),
+            read(10, "counter"),
+            write(10, "counter"),
+            read(11, "MAX"),
+            read(11, "count"),
+            write(11, "count"),
+        ]
+    );
+}
+
+#[test]
+fn test_extract_entities_includes_data_after_code() {
+    let source = r"class Service {
+    private String name;
+    String name() { return name; }
+}
+";
+    let entities = extract_entities(Path::new("Service.java"), source, Language::JAVA);
+    let ids: Vec<(String, EntityKind)> = entities.iter().map(|e| (e.id(), e.kind)).collect();
+    assert!(ids.contains(&("Service.java:Service".to_string(), EntityKind::Class)));
+    // The method keeps the plain ID although the field is declared first
+    assert!(ids.contains(&("Service.java:Service::name".to_string(), EntityKind::Method)));
+    assert!(ids.contains(&(
+        "Service.java:Service::name#0".to_string(),
+        EntityKind::Field
+    )));
+}
+
+#[test]
+fn test_unsupported_language_yields_nothing() {
+    let source = "local MAX = 10\n";
+    assert!(extract_variables(Path::new("a.lua"), source, Language::LUA).is_empty());
+    assert!(state_accesses(source, Language::LUA).is_empty());
+}
@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use rpg_parser::deps::extract_deps;
+use rpg_parser::languages::Language;
+
+#[test]
+fn zig_import() {
+    let source = "const std = @import(\"std\");\nconst util = @import(\"util.zig\");";
+    let deps = extract_deps(Path::new("main.zig"), source, Language::ZIG);
+    assert_eq!(deps.imports.len(), 2);
+    assert_eq!(deps.imports[0].module, "std");
+    assert_eq!(deps.imports[1].module, "util.zig");
+    assert!(deps.calls.is_empty());
+}
+
+#[test]
+fn zig_calls_with_caller() {
+    let source = r#"const Parser = struct {
+    fn parse(self: *Parser) void {
+        self.advance();
+        std.debug.print("x", .{});
+        helper(1);
+    }
+};"#;
+    let deps = extract_deps(Path::new("parser.zig"), source, Language::ZIG);
+    let callees: Vec<&str> = deps.calls.iter().map(|c| c.callee.as_str()).collect();
+    assert_eq!(callees, vec!["advance", "print", "helper"]);
+    assert!(deps.calls.iter().all(|c| c.caller_entity == "Parser.parse"));
+}
+
+#[test]
+fn zig_no_inheritance() {
+    let source = "const A = struct {};\nconst B = struct {};";
+    let deps = extract_deps(Path::new("ab.zig"), source, Language::ZIG);
+    assert!(deps.inherits.is_empty());
+}
@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+
+#[test]
+fn zig_detected_from_extension() {
+    assert_eq!(Language::from_extension("zig"), Some(Language::ZIG));
+}
+
+#[test]
+fn zig_extract_function() {
+    let source = "pub fn add(a: i32, b: i32) i32 {\n    return a + b;\n}";
+    let entities = extract_entities(Path::new("math.zig"), source, Language::ZIG);
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].name, "add");
+    assert_eq!(entities[0].kind, EntityKind::Function);
+    let sig = entities[0].signature.as_ref().unwrap();
+    assert_eq!(sig.parameters.len(), 2);
+    assert_eq!(sig.parameters[0].type_annotation.as_deref(), Some("i32"));
+    assert_eq!(sig.return_type.as_deref(), Some("i32"));
+}
+
+#[test]
+fn zig_extract_struct_with_methods() {
+    let source = r"const Point = struct {
+    x: i32,
+    y: i32,
+
+    pub fn init(x: i32, y: i32) Point {
+        return .{ .x = x, .y = y };
+    }
+
+    fn norm(self: Point) i32 {
+        return self.x + self.y;
+    }
+};";
+    let entities = extract_entities(Path::new("point.zig"), source, Language::ZIG);
+    assert_eq!(entities.len(), 3);
+
+    let point = entities.iter().find(|e| e.name == "Point").unwrap();
+    assert_eq!(point.kind, EntityKind::Class);
+
+    let init = entities.iter().find(|e| e.name == "init").unwrap();
+    assert_eq!(init.kind, EntityKind::Method);
+    assert_eq!(init.parent_class.as_deref(), Some("Point"));
+
+    // The explicit `self` receiver is not part of the signature
+    let norm = entities.iter().find(|e| e.name == "norm").unwrap();
+    assert!(norm.signature.as_ref().unwrap().parameters.is_empty());
+}
+
+#[test]
+fn zig_extract_enum_and_union() {
+    let source = "const Color = enum { red, green };\nconst Value = union(enum) { int: i64, float: f64 };\nconst max = 10;";
+    let entities = extract_entities(Path::new("types.zig"), source, Language::ZIG);
+    assert_eq!(entities.len(), 2);
+    assert!(entities.iter().all(|e| e.kind == EntityKind::Class));
+}